@@ -0,0 +1,99 @@
+use actix_web::body::MessageBody;
+use actix_web::dev::{ServiceRequest, ServiceResponse};
+use actix_web::http::Method;
+use actix_web::middleware::Next;
+use actix_web::{web, Error, HttpMessage};
+use std::sync::Arc;
+use std::time::Instant;
+
+use crate::api::auth::Principal;
+use crate::domain::AuditRecorder;
+use crate::models::NewAuditEvent;
+use crate::state::AppState;
+
+const API_PREFIX: &str = "/api/v1/";
+
+fn is_mutating(method: &Method) -> bool {
+    matches!(*method, Method::POST | Method::PUT | Method::PATCH | Method::DELETE)
+}
+
+/// First path segment under /api/v1, e.g. "servers" for /api/v1/servers/12/power/on
+fn resource_of(path: &str) -> Option<String> {
+    path.strip_prefix(API_PREFIX)
+        .and_then(|rest| rest.split('/').next())
+        .filter(|segment| !segment.is_empty())
+        .map(str::to_string)
+}
+
+/// Middleware recording an audit event for every mutating v1 request made by an
+/// authenticated principal. Must be wrapped inside `require_bearer_token`.
+pub async fn record_audit_event(
+    req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<impl MessageBody>, Error> {
+    let principal = req.extensions().get::<Principal>().cloned();
+    let principal = match principal {
+        Some(principal) if is_mutating(req.method()) => principal,
+        _ => return next.call(req).await,
+    };
+
+    let app_state = req.app_data::<web::Data<AppState>>().cloned();
+    let http_method = req.method().to_string();
+    let request_path = req.path().to_string();
+    let client_ip = req.connection_info().realip_remote_addr().map(str::to_string);
+
+    let recorder = Arc::new(AuditRecorder::default());
+    let started = Instant::now();
+    let result = recorder.clone().scope(next.call(req)).await;
+    let duration_ms = started.elapsed().as_millis().min(i32::MAX as u128) as i32;
+
+    let (status_code, route, entity_id) = match &result {
+        Ok(res) => {
+            let request = res.request();
+            let entity_id = request
+                .match_info()
+                .iter()
+                .find(|(name, _)| *name == "id" || name.ends_with("_id"))
+                .map(|(_, value)| value.to_string());
+            (
+                res.status().as_u16(),
+                request.match_pattern().unwrap_or_else(|| request_path.clone()),
+                entity_id,
+            )
+        }
+        Err(e) => (e.as_response_error().status_code().as_u16(), request_path.clone(), None),
+    };
+
+    let changes = recorder.take_changes();
+    let actions = recorder.take_actions();
+
+    let event = NewAuditEvent {
+        token_id: principal.token_id,
+        principal_name: principal.name,
+        principal_scope: principal.scope.as_str().to_string(),
+        principal_role: principal.role.map(|role| role.as_str().to_string()),
+        client_ip,
+        http_method,
+        resource: resource_of(&request_path),
+        route,
+        request_path,
+        entity_id,
+        status_code: status_code as i32,
+        success: (200..400).contains(&status_code),
+        duration_ms,
+        changes: (!changes.is_empty()).then(|| serde_json::json!(changes)),
+        actions: (!actions.is_empty()).then(|| serde_json::json!(actions)),
+    };
+
+    // The request has already been handled; a failed audit write is logged rather than surfaced
+    match app_state {
+        Some(app_state) => {
+            if let Err(e) = app_state.audit_repo().record_event(event).await {
+                log::error!("Failed to record audit event: {}", e);
+            }
+        }
+        None => log::error!("Failed to record audit event: application state not configured"),
+    }
+
+    result
+}
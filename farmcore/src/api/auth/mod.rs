@@ -0,0 +1,231 @@
+use actix_web::body::MessageBody;
+use actix_web::dev::{Payload, ServiceRequest, ServiceResponse};
+use actix_web::http::{header, StatusCode};
+use actix_web::middleware::Next;
+use actix_web::{web, Error, FromRequest, HttpMessage, HttpRequest, HttpResponse, ResponseError};
+use serde::Serialize;
+use std::future::{ready, Ready};
+use std::sync::OnceLock;
+
+mod permissions;
+
+use permissions::required_role;
+
+use crate::api::responses::ApiResponse;
+use crate::models::{Role, TokenScope};
+use crate::repositories::TokenRepository;
+use crate::state::AppState;
+
+/// Routes reachable without a token (API index and health probes)
+const PUBLIC_ROUTES: &[(&str, &str)] = &[
+    ("GET", "/api/v1"),
+    ("GET", "/api/v1/health"),
+    ("GET", "/api/v1/openapi.json"),
+];
+
+/// Inventory ingest routes; these require an AGENT-scoped token and nothing else accepts one
+const AGENT_ROUTES: &[(&str, &str)] = &[
+    ("POST", "/api/v1/servers/inventory"),
+    ("POST", "/api/v1/vms/inventory"),
+    ("POST", "/api/v1/k8s/inventory"),
+];
+
+/// Environment variable holding a bootstrap token with API scope and the ADMIN role.
+/// It is never stored in the database, so it works before migrations have run.
+const BOOTSTRAP_TOKEN_ENV: &str = "FARM_BOOTSTRAP_TOKEN";
+
+/// The authenticated caller of a request, inserted into request extensions by the middleware
+#[derive(Debug, Clone, Serialize)]
+pub struct Principal {
+    pub token_id: Option<i32>, // None for the bootstrap token
+    pub name: String,
+    pub scope: TokenScope,
+    pub role: Option<Role>, // None for AGENT tokens
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum AuthError {
+    #[error("Missing bearer token in Authorization header")]
+    MissingToken,
+
+    #[error("Invalid, expired, or revoked API token")]
+    InvalidToken,
+
+    #[error("Token scope {0} is not permitted on this route")]
+    ScopeNotPermitted(&'static str),
+
+    #[error("Role {actual} is not permitted on this route; {required} or higher is required")]
+    InsufficientRole {
+        actual: &'static str,
+        required: &'static str,
+    },
+
+    #[error("Authentication backend error: {0}")]
+    Backend(String),
+}
+
+impl ResponseError for AuthError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            AuthError::MissingToken | AuthError::InvalidToken => StatusCode::UNAUTHORIZED,
+            AuthError::ScopeNotPermitted(_) | AuthError::InsufficientRole { .. } => StatusCode::FORBIDDEN,
+            AuthError::Backend(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        let code = match self {
+            AuthError::MissingToken | AuthError::InvalidToken => "UNAUTHORIZED",
+            AuthError::ScopeNotPermitted(_) | AuthError::InsufficientRole { .. } => "FORBIDDEN",
+            AuthError::Backend(_) => "AUTH_ERROR",
+        };
+        let response = ApiResponse::<()>::error(code, &self.to_string());
+
+        let mut builder = HttpResponse::build(self.status_code());
+        if self.status_code() == StatusCode::UNAUTHORIZED {
+            builder.insert_header((header::WWW_AUTHENTICATE, "Bearer"));
+        }
+        builder.json(response)
+    }
+}
+
+impl FromRequest for Principal {
+    type Error = AuthError;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        ready(req.extensions().get::<Principal>().cloned().ok_or(AuthError::MissingToken))
+    }
+}
+
+fn route_listed(routes: &[(&str, &str)], method: &str, path: &str) -> bool {
+    routes.iter().any(|(m, p)| *m == method && *p == path)
+}
+
+/// Whether a route can be called without a token
+pub fn is_public_route(method: &str, path: &str) -> bool {
+    route_listed(PUBLIC_ROUTES, method, path)
+}
+
+/// Scope required for a route, or None if the route is public
+fn required_scope(method: &str, path: &str) -> Option<TokenScope> {
+    if is_public_route(method, path) {
+        None
+    } else if route_listed(AGENT_ROUTES, method, path) {
+        Some(TokenScope::Agent)
+    } else {
+        Some(TokenScope::Api)
+    }
+}
+
+fn bearer_token(req: &ServiceRequest) -> Option<String> {
+    let value = req.headers().get(header::AUTHORIZATION)?.to_str().ok()?;
+    let (scheme, token) = value.split_once(' ')?;
+    if !scheme.eq_ignore_ascii_case("bearer") || token.trim().is_empty() {
+        return None;
+    }
+    Some(token.trim().to_string())
+}
+
+fn bootstrap_token_hash() -> Option<&'static str> {
+    static HASH: OnceLock<Option<String>> = OnceLock::new();
+    HASH.get_or_init(|| {
+        std::env::var(BOOTSTRAP_TOKEN_ENV)
+            .ok()
+            .filter(|t| !t.is_empty())
+            .map(|t| TokenRepository::hash_token(&t))
+    })
+    .as_deref()
+}
+
+async fn authenticate(app_state: &AppState, token: &str) -> Result<Principal, AuthError> {
+    if bootstrap_token_hash() == Some(TokenRepository::hash_token(token).as_str()) {
+        return Ok(Principal {
+            token_id: None,
+            name: "bootstrap".to_string(),
+            scope: TokenScope::Api,
+            role: Some(Role::Admin),
+        });
+    }
+
+    let repo = app_state.token_repo();
+    let api_token = repo
+        .find_active_token(token)
+        .await
+        .map_err(|e| AuthError::Backend(e.to_string()))?
+        .ok_or(AuthError::InvalidToken)?;
+
+    let scope = TokenScope::parse(&api_token.scope).ok_or(AuthError::InvalidToken)?;
+    let role = match scope {
+        TokenScope::Api => Some(api_token.role.as_deref().and_then(Role::parse).ok_or(AuthError::InvalidToken)?),
+        TokenScope::Agent => None,
+    };
+
+    // last_used_at is informational, don't hold the request up for it
+    let token_id = api_token.token_id;
+    tokio::spawn(async move {
+        if let Err(e) = repo.touch_token(token_id).await {
+            log::warn!("Failed to update last_used_at for token {}: {}", token_id, e);
+        }
+    });
+
+    Ok(Principal {
+        token_id: Some(api_token.token_id),
+        name: api_token.token_name,
+        scope,
+        role,
+    })
+}
+
+/// Middleware enforcing bearer-token authentication and role checks on every v1 route except the public ones
+pub async fn require_bearer_token(
+    req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<impl MessageBody>, Error> {
+    let path = req.path().trim_end_matches('/').to_string();
+    let required = match required_scope(req.method().as_str(), &path) {
+        Some(scope) => scope,
+        None => return next.call(req).await,
+    };
+
+    let token = bearer_token(&req).ok_or(AuthError::MissingToken)?;
+    let app_state = req
+        .app_data::<web::Data<AppState>>()
+        .cloned()
+        .ok_or_else(|| AuthError::Backend("application state not configured".to_string()))?;
+
+    let principal = authenticate(&app_state, &token).await?;
+    if principal.scope != required {
+        log::warn!(
+            "Token '{}' ({}) rejected on {} {}",
+            principal.name,
+            principal.scope.as_str(),
+            req.method(),
+            path
+        );
+        return Err(AuthError::ScopeNotPermitted(principal.scope.as_str()).into());
+    }
+
+    // Roles only apply to API tokens; agent tokens are already pinned to the ingest routes
+    if let Some(role) = principal.role {
+        let minimum_role = required_role(req.method().as_str(), &path);
+        if role < minimum_role {
+            log::warn!(
+                "Token '{}' ({}) lacks {} for {} {}",
+                principal.name,
+                role.as_str(),
+                minimum_role.as_str(),
+                req.method(),
+                path
+            );
+            return Err(AuthError::InsufficientRole {
+                actual: role.as_str(),
+                required: minimum_role.as_str(),
+            }
+            .into());
+        }
+    }
+
+    req.extensions_mut().insert(principal);
+    next.call(req).await
+}
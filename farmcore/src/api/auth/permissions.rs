@@ -0,0 +1,75 @@
+use crate::models::Role;
+
+/// Per-route permission map for API-scoped tokens. Patterns use `{name}` for a
+/// single path segment and `*` as the method wildcard. The first match wins;
+/// routes that are not listed fall back to `default_role`.
+const ROUTE_PERMISSIONS: &[(&str, &str, Role)] = &[
+    // Database administration
+    ("*", "/api/v1/migrations/run", Role::Admin),
+    ("*", "/api/v1/migrations/reset", Role::Admin),
+    ("*", "/api/v1/migrations/seed", Role::Admin),
+
+    // Token management
+    ("GET", "/api/v1/tokens", Role::Viewer),
+    ("*", "/api/v1/tokens/list", Role::Admin),
+    ("*", "/api/v1/tokens/{id}", Role::Admin),
+    ("*", "/api/v1/tokens", Role::Admin),
+
+    // Audit log
+    ("GET", "/api/v1/audit", Role::Admin),
+    ("GET", "/api/v1/audit/{id}", Role::Admin),
+
+    // Webhooks: the scope root serves both the index and creation
+    ("GET", "/api/v1/webhooks", Role::Viewer),
+    ("*", "/api/v1/webhooks", Role::Admin),
+    ("*", "/api/v1/webhooks/list", Role::Admin),
+    ("*", "/api/v1/webhooks/deliveries", Role::Admin),
+    ("*", "/api/v1/webhooks/deliveries/{id}", Role::Admin),
+    ("*", "/api/v1/webhooks/{id}", Role::Admin),
+    ("*", "/api/v1/webhooks/{id}/ping", Role::Admin),
+
+    // Merging deletes the merged server records
+    ("POST", "/api/v1/servers/{id}/merge", Role::Admin),
+
+    // Applying a topology deletes whatever the document leaves out; planning stays with operators
+    ("POST", "/api/v1/topology/apply", Role::Admin),
+
+    // Redfish power control
+    ("GET", "/api/v1/servers/{id}/power/status", Role::Viewer),
+    ("POST", "/api/v1/servers/{id}/power/on", Role::Operator),
+    ("POST", "/api/v1/servers/{id}/power/off", Role::Operator),
+    ("POST", "/api/v1/servers/{id}/power/restart", Role::Operator),
+    ("POST", "/api/v1/servers/{id}/power/force-off", Role::Operator),
+    ("POST", "/api/v1/servers/{id}/power/force-restart", Role::Operator),
+];
+
+/// Reads are open to every role; anything that changes state needs an operator
+fn default_role(method: &str) -> Role {
+    match method {
+        "GET" | "HEAD" | "OPTIONS" => Role::Viewer,
+        _ => Role::Operator,
+    }
+}
+
+fn path_matches(pattern: &str, path: &str) -> bool {
+    let mut pattern_segments = pattern.split('/');
+    let mut path_segments = path.split('/');
+
+    loop {
+        match (pattern_segments.next(), path_segments.next()) {
+            (None, None) => return true,
+            (Some(p), Some(s)) if p.starts_with('{') && p.ends_with('}') && !s.is_empty() => {}
+            (Some(p), Some(s)) if p == s => {}
+            _ => return false,
+        }
+    }
+}
+
+/// Minimum role an API token needs to call `method path`
+pub fn required_role(method: &str, path: &str) -> Role {
+    ROUTE_PERMISSIONS
+        .iter()
+        .find(|(m, p, _)| (*m == "*" || *m == method) && path_matches(p, path))
+        .map(|(_, _, role)| *role)
+        .unwrap_or_else(|| default_role(method))
+}
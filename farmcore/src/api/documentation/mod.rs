@@ -1,5 +1,10 @@
+use schemars::{JsonSchema, Schema, SchemaGenerator};
 use serde::Serialize;
 
+use crate::api::responses::ApiResponse;
+
+pub mod openapi;
+
 #[derive(Serialize, Debug, Clone)]
 pub struct ApiDocumentation {
     pub service: String,
@@ -32,6 +37,29 @@ pub struct EndpointDoc {
     pub examples: Vec<ExampleDoc>,
     #[serde(skip_serializing_if = "Vec::is_empty")]
     pub response_codes: Vec<ResponseCodeDoc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_schema: Option<SchemaDoc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response_schema: Option<SchemaDoc>,
+}
+
+/// JSON Schema of a request or response body, derived from a Rust type.
+/// Only the name is shown in the human-readable docs; the OpenAPI generator
+/// calls `generate` to pull the full schema into `components.schemas`.
+#[derive(Serialize, Debug, Clone)]
+pub struct SchemaDoc {
+    pub name: String,
+    #[serde(skip)]
+    pub generate: fn(&mut SchemaGenerator) -> Schema,
+}
+
+impl SchemaDoc {
+    pub fn of<T: JsonSchema>() -> Self {
+        Self {
+            name: T::schema_name().into_owned(),
+            generate: |generator| generator.subschema_for::<T>(),
+        }
+    }
 }
 
 #[derive(Serialize, Debug, Clone)]
@@ -175,6 +203,8 @@ impl EndpointDoc {
             request_body: None,
             examples: Vec::new(),
             response_codes: Vec::new(),
+            request_schema: None,
+            response_schema: None,
         }
     }
 
@@ -207,6 +237,18 @@ impl EndpointDoc {
         self.response_codes.push(response);
         self
     }
+
+    /// Document the JSON request body as type `T`
+    pub fn with_request_schema<T: JsonSchema>(mut self) -> Self {
+        self.request_schema = Some(SchemaDoc::of::<T>());
+        self
+    }
+
+    /// Document the success response as `ApiResponse<T>`
+    pub fn with_response_schema<T: JsonSchema>(mut self) -> Self {
+        self.response_schema = Some(SchemaDoc::of::<ApiResponse<T>>());
+        self
+    }
 }
 
 impl ParameterDoc {
@@ -313,6 +355,14 @@ pub fn standard_response_format() -> ResponseFormatDoc {
     }
 }
 
+pub fn bearer_auth_doc() -> AuthDoc {
+    AuthDoc {
+        type_name: "Bearer".to_string(),
+        description: "API token sent as 'Authorization: Bearer <token>'. AGENT-scoped tokens may only post inventory. API-scoped tokens carry a role: VIEWER (reads), OPERATOR (writes and BMC power actions), or ADMIN (token management and migrations).".to_string(),
+        header_name: Some("Authorization".to_string()),
+    }
+}
+
 pub fn pagination_parameters() -> Vec<ParameterDoc> {
     vec![
         ParameterDoc::new("page", ParameterType::Integer, "Page number", false)
@@ -0,0 +1,255 @@
+use schemars::generate::SchemaSettings;
+use serde_json::{json, Map, Value};
+
+use super::{ApiDocumentation, EndpointDoc, HttpMethod, ParameterDoc, ParameterType, SchemaDoc};
+use crate::api::auth::is_public_route;
+use crate::api::responses::ApiResponse;
+
+// ===================================================================
+// OPENAPI 3.1 GENERATION
+// ===================================================================
+//
+// Converts the per-module `ApiDocumentation` into a single OpenAPI 3.1
+// document. Body schemas come from the `SchemaDoc`s attached to endpoints,
+// so they stay in sync with the model structs.
+
+const OPENAPI_VERSION: &str = "3.1.0";
+
+fn method_key(method: &HttpMethod) -> &'static str {
+    match method {
+        HttpMethod::Get => "get",
+        HttpMethod::Post => "post",
+        HttpMethod::Put => "put",
+        HttpMethod::Patch => "patch",
+        HttpMethod::Delete => "delete",
+        HttpMethod::Head => "head",
+        HttpMethod::Options => "options",
+    }
+}
+
+fn parameter_schema(param_type: &ParameterType) -> Value {
+    match param_type {
+        ParameterType::String => json!({ "type": "string" }),
+        ParameterType::Integer => json!({ "type": "integer", "format": "int64" }),
+        ParameterType::Boolean => json!({ "type": "boolean" }),
+        ParameterType::Array => json!({ "type": "array", "items": {} }),
+        ParameterType::Object => json!({ "type": "object" }),
+        ParameterType::Number => json!({ "type": "number" }),
+    }
+}
+
+fn parameter(param: &ParameterDoc, location: &str) -> Value {
+    let mut schema = parameter_schema(&param.parameter_type);
+    if let Some(default) = &param.default_value {
+        schema["default"] = json!(default);
+    }
+    if !param.enum_values.is_empty() {
+        schema["enum"] = json!(param.enum_values);
+    }
+
+    let mut value = json!({
+        "name": param.name,
+        "in": location,
+        "description": param.description,
+        // Path parameters are always required in OpenAPI
+        "required": param.required || location == "path",
+        "schema": schema,
+    });
+    if let Some(example) = &param.example {
+        value["example"] = json!(example);
+    }
+    value
+}
+
+/// Names of `{placeholders}` in a route pattern
+fn path_placeholders(path: &str) -> Vec<&str> {
+    path.split('/')
+        .filter_map(|segment| segment.strip_prefix('{').and_then(|s| s.strip_suffix('}')))
+        .collect()
+}
+
+fn operation_id(method: &HttpMethod, path: &str) -> String {
+    let mut id = method_key(method).to_string();
+    for segment in path.trim_start_matches("/api/v1").split('/').filter(|s| !s.is_empty()) {
+        id.push('_');
+        id.extend(segment.chars().map(|c| if c.is_ascii_alphanumeric() { c } else { '_' }));
+    }
+    id.replace("__", "_").trim_end_matches('_').to_string()
+}
+
+fn json_content(schema: Value) -> Value {
+    json!({ "application/json": { "schema": schema } })
+}
+
+struct Builder {
+    generator: schemars::SchemaGenerator,
+    paths: Map<String, Value>,
+    tags: Vec<Value>,
+    error_schema: Value,
+    generic_schema: Value,
+}
+
+impl Builder {
+    fn new() -> Self {
+        let mut generator = SchemaSettings::draft2020_12()
+            .with(|settings| {
+                settings.definitions_path = "/components/schemas".into();
+                settings.meta_schema = None;
+            })
+            .for_serialize()
+            .into_generator();
+
+        let error_schema = generator.subschema_for::<ApiResponse<()>>().to_value();
+        let generic_schema = generator.subschema_for::<ApiResponse<Value>>().to_value();
+
+        Self {
+            generator,
+            paths: Map::new(),
+            tags: Vec::new(),
+            error_schema,
+            generic_schema,
+        }
+    }
+
+    fn schema(&mut self, schema: &SchemaDoc) -> Value {
+        (schema.generate)(&mut self.generator).to_value()
+    }
+
+    fn operation(&mut self, endpoint: &EndpointDoc, tag: &str) -> Value {
+        let mut parameters: Vec<Value> = endpoint
+            .path_parameters
+            .iter()
+            .map(|p| parameter(p, "path"))
+            .collect();
+
+        // Every placeholder must be declared, even if the docs forgot to list it
+        for name in path_placeholders(&endpoint.path) {
+            if !endpoint.path_parameters.iter().any(|p| p.name == name) {
+                let param_type = if name == "id" || name.ends_with("_id") {
+                    ParameterType::Integer
+                } else {
+                    ParameterType::String
+                };
+                parameters.push(parameter(&ParameterDoc::new(name, param_type, name, true), "path"));
+            }
+        }
+        parameters.extend(endpoint.query_parameters.iter().map(|p| parameter(p, "query")));
+
+        let mut operation = json!({
+            "operationId": operation_id(&endpoint.method, &endpoint.path),
+            "summary": endpoint.description,
+            "tags": [tag],
+        });
+        if !parameters.is_empty() {
+            operation["parameters"] = json!(parameters);
+        }
+
+        if let Some(schema) = &endpoint.request_schema {
+            let schema = self.schema(schema);
+            operation["requestBody"] = json!({ "required": true, "content": json_content(schema) });
+        } else if let Some(body) = &endpoint.request_body {
+            let mut content = Map::new();
+            content.insert(body.content_type.clone(), json!({ "schema": body.schema, "example": body.example }));
+            operation["requestBody"] = json!({
+                "required": true,
+                "description": body.description,
+                "content": content,
+            });
+        } else if let Some(example) = endpoint.examples.iter().find_map(|e| e.request_body.as_ref()) {
+            operation["requestBody"] = json!({
+                "required": true,
+                "content": { "application/json": { "schema": { "type": "object" }, "example": example } },
+            });
+        }
+
+        let success_schema = match &endpoint.response_schema {
+            Some(schema) => self.schema(schema),
+            None => self.generic_schema.clone(),
+        };
+
+        let mut responses = Map::new();
+        for response in &endpoint.response_codes {
+            let schema = if response.code < 400 { success_schema.clone() } else { self.error_schema.clone() };
+            let mut value = json!({ "description": response.description, "content": json_content(schema) });
+            if let Some(example) = &response.example {
+                value["content"]["application/json"]["example"] = example.clone();
+            }
+            responses.insert(response.code.to_string(), value);
+        }
+        if !responses.keys().any(|code| code.starts_with('2')) {
+            responses.insert(
+                "200".to_string(),
+                json!({ "description": "Success", "content": json_content(success_schema) }),
+            );
+        }
+        operation["responses"] = Value::Object(responses);
+
+        if is_public_route(&method_key(&endpoint.method).to_uppercase(), &endpoint.path) {
+            operation["security"] = json!([]);
+        }
+
+        operation
+    }
+
+    fn add_module(&mut self, index_path: Option<&str>, doc: &ApiDocumentation) {
+        let tag = doc.service.clone();
+        self.tags.push(json!({ "name": tag, "description": doc.description }));
+
+        // The module's documentation index is a route too
+        if let Some(index_path) = index_path {
+            let index = EndpointDoc::new(index_path, HttpMethod::Get, &format!("{} documentation", doc.service));
+            self.add_endpoint(&index, &tag);
+        }
+
+        for endpoint in &doc.endpoints {
+            self.add_endpoint(endpoint, &tag);
+        }
+    }
+
+    fn add_endpoint(&mut self, endpoint: &EndpointDoc, tag: &str) {
+        let path = match endpoint.path.trim_end_matches('/') {
+            "" => "/",
+            trimmed => trimmed,
+        };
+        let method = method_key(&endpoint.method);
+
+        // First description of a route wins (root also links to module indexes)
+        if self.paths.get(path).and_then(|item| item.get(method)).is_some() {
+            return;
+        }
+
+        let operation = self.operation(endpoint, tag);
+        let item = self.paths.entry(path.to_string()).or_insert_with(|| json!({}));
+        item[method] = operation;
+    }
+}
+
+/// Build an OpenAPI 3.1 document from each module's documentation.
+/// `modules` pairs the path a module's documentation index is served at (if any) with its documentation.
+pub fn openapi_document(title: &str, version: &str, modules: &[(Option<&str>, ApiDocumentation)]) -> Value {
+    let mut builder = Builder::new();
+    for (index_path, doc) in modules {
+        builder.add_module(*index_path, doc);
+    }
+
+    json!({
+        "openapi": OPENAPI_VERSION,
+        "info": {
+            "title": title,
+            "version": version,
+        },
+        "tags": builder.tags,
+        "paths": builder.paths,
+        "components": {
+            "schemas": builder.generator.take_definitions(true),
+            "securitySchemes": {
+                "bearerAuth": {
+                    "type": "http",
+                    "scheme": "bearer",
+                    "description": super::bearer_auth_doc().description,
+                },
+            },
+        },
+        "security": [{ "bearerAuth": [] }],
+    })
+}
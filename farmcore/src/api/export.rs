@@ -0,0 +1,147 @@
+use actix_web::http::header;
+use actix_web::web::Bytes;
+use actix_web::HttpResponse;
+use futures_util::stream::{self, StreamExt};
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::api::query_parser::{CommonPaginationQuery, QueryError};
+use crate::api::responses::ApiResponse;
+use crate::database::RowStream;
+use crate::models::TableSchema;
+
+/// File formats list endpoints can stream with `format=`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ExportFormat {
+    Csv,
+    Ndjson,
+}
+
+impl ExportFormat {
+    pub const NAMES: &'static [&'static str] = &["csv", "ndjson"];
+
+    /// Parse `format=`; `json` (or no format) is the normal paginated response
+    pub fn parse(query: &CommonPaginationQuery) -> Result<Option<Self>, QueryError> {
+        match query.format.as_deref().map(str::to_ascii_lowercase).as_deref() {
+            None | Some("") | Some("json") => Ok(None),
+            Some("csv") => Ok(Some(ExportFormat::Csv)),
+            Some("ndjson") => Ok(Some(ExportFormat::Ndjson)),
+            Some(other) => Err(QueryError::new("format", format!("Unknown export format '{}'", other))
+                .with_allowed(Self::NAMES)),
+        }
+    }
+
+    /// The requested export, for handlers whose query has already been validated
+    pub fn from_query(query: &CommonPaginationQuery) -> Option<Self> {
+        Self::parse(query).ok().flatten()
+    }
+
+    fn content_type(self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "text/csv; charset=utf-8",
+            ExportFormat::Ndjson => "application/x-ndjson",
+        }
+    }
+
+    fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Ndjson => "ndjson",
+        }
+    }
+}
+
+/// Stream the rows of a list query as a CSV or NDJSON download. CSV has one column per
+/// requested column (all of the table's columns by default); NDJSON lines are the same
+/// objects the JSON API returns, trimmed to `columns=` when given.
+pub fn export_response<T>(
+    format: ExportFormat,
+    schema: &TableSchema,
+    query: &CommonPaginationQuery,
+    rows: Result<RowStream<T>, sqlx::Error>,
+) -> HttpResponse
+where
+    T: Serialize + 'static,
+{
+    let rows = match rows {
+        Ok(rows) => rows,
+        Err(e) => {
+            log::error!("Database error exporting {}: {}", schema.table, e);
+            return HttpResponse::InternalServerError()
+                .json(ApiResponse::<()>::error("DATABASE_ERROR", &format!("Failed to export {}", schema.table)));
+        }
+    };
+
+    let requested: Option<Vec<String>> = query.columns.as_deref()
+        .filter(|columns| !columns.is_empty())
+        .map(|columns| columns.split(',').map(|column| column.trim().to_string()).collect());
+    let columns = requested.clone()
+        .unwrap_or_else(|| schema.column_names().into_iter().map(str::to_string).collect());
+
+    let header = match format {
+        ExportFormat::Csv => Some(csv_line(columns.iter().map(|column| Value::String(column.clone())))),
+        ExportFormat::Ndjson => None,
+    };
+
+    let table = schema.table;
+    let body = rows.map(move |row| {
+        let row = row.map_err(|e| {
+            // Headers are already sent; cutting the stream short is the only way left to signal failure
+            log::error!("Database error while exporting {}: {}", table, e);
+            actix_web::error::ErrorInternalServerError("export failed")
+        })?;
+        let value = serde_json::to_value(&row).map_err(actix_web::error::ErrorInternalServerError)?;
+        let line = match format {
+            ExportFormat::Csv => csv_line(columns.iter().map(|column| value.get(column).cloned().unwrap_or(Value::Null))),
+            ExportFormat::Ndjson => {
+                let object = match &requested {
+                    Some(_) => Value::Object(columns.iter()
+                        .map(|column| (column.clone(), value.get(column).cloned().unwrap_or(Value::Null)))
+                        .collect()),
+                    None => value,
+                };
+                let mut line = object.to_string();
+                line.push('\n');
+                line
+            }
+        };
+        Ok::<_, actix_web::Error>(Bytes::from(line))
+    });
+    let body = stream::iter(header.map(|line| Ok(Bytes::from(line)))).chain(body);
+
+    HttpResponse::Ok()
+        .content_type(format.content_type())
+        .insert_header((
+            header::CONTENT_DISPOSITION,
+            format!("attachment; filename=\"{}.{}\"", table, format.extension()),
+        ))
+        .streaming(body)
+}
+
+/// One RFC 4180 record. Nested JSON (labels, annotations) is written as JSON text.
+fn csv_line(values: impl Iterator<Item = Value>) -> String {
+    let fields: Vec<String> = values.map(|value| match value {
+        Value::Null => String::new(),
+        Value::String(s) => csv_field(&s),
+        Value::Bool(b) => b.to_string(),
+        Value::Number(n) => n.to_string(),
+        nested => csv_field(&nested.to_string()),
+    }).collect();
+    let mut line = fields.join(",");
+    line.push_str("\r\n");
+    line
+}
+
+fn csv_field(text: &str) -> String {
+    // Spreadsheets evaluate cells starting with these as formulas
+    let text = if text.starts_with(['=', '+', '-', '@']) {
+        format!("'{}", text)
+    } else {
+        text.to_string()
+    };
+    if text.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text
+    }
+}
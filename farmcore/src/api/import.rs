@@ -0,0 +1,326 @@
+use actix_web::http::header;
+use actix_web::HttpRequest;
+use serde::Deserialize;
+use serde_json::{Map, Value};
+
+use crate::api::query_parser::QueryError;
+use crate::models::{ImportKind, ImportRow, ImportRowError};
+
+/// Query parameters of the bulk import endpoint
+#[derive(Debug, Clone, Deserialize)]
+pub struct ImportQuery {
+    /// csv or yaml; taken from the Content-Type when omitted
+    pub format: Option<String>,
+    /// Record type of every row: required for CSV files without a `kind` column and for YAML
+    /// documents that are a bare list of rows
+    pub kind: Option<String>,
+    /// Validate every row inside a transaction that is always rolled back
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+/// Document formats the bulk import endpoint accepts
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ImportFormat {
+    Csv,
+    Yaml,
+}
+
+impl ImportFormat {
+    pub const NAMES: &'static [&'static str] = &["csv", "yaml"];
+
+    /// `format=` wins over the Content-Type header
+    pub fn detect(query: &ImportQuery, req: &HttpRequest) -> Result<Self, QueryError> {
+        if let Some(format) = query.format.as_deref() {
+            return match format.to_ascii_lowercase().as_str() {
+                "csv" => Ok(ImportFormat::Csv),
+                "yaml" | "yml" => Ok(ImportFormat::Yaml),
+                other => Err(QueryError::new("format", format!("Unknown import format '{}'", other))
+                    .with_allowed(Self::NAMES)),
+            };
+        }
+
+        let content_type = req.headers().get(header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or_default();
+        let mime = content_type.split(';').next().unwrap_or_default().trim().to_ascii_lowercase();
+        match mime.as_str() {
+            "text/csv" => Ok(ImportFormat::Csv),
+            "application/yaml" | "application/x-yaml" | "text/yaml" | "text/x-yaml" => Ok(ImportFormat::Yaml),
+            _ => Err(QueryError::new("format", "Send text/csv or application/yaml, or pass format=")
+                .with_allowed(Self::NAMES)),
+        }
+    }
+}
+
+/// Split an import document into typed rows. Problems with the document as a whole (bad
+/// YAML, ragged CSV) are a `QueryError`; problems with single fields come back as row
+/// errors alongside the rows that parsed, so one request reports everything at once.
+pub fn parse_document(
+    format: ImportFormat,
+    query: &ImportQuery,
+    body: &[u8],
+) -> Result<(Vec<ImportRow>, Vec<ImportRowError>), QueryError> {
+    let kind = match query.kind.as_deref() {
+        Some(name) => Some(ImportKind::parse(name).ok_or_else(|| {
+            QueryError::new("kind", format!("Unknown import kind '{}'", name)).with_allowed(ImportKind::NAMES)
+        })?),
+        None => None,
+    };
+    let text = std::str::from_utf8(body)
+        .map_err(|_| QueryError::new("body", "Import documents must be UTF-8"))?;
+
+    let records = match format {
+        ImportFormat::Csv => csv_rows(text, kind)?,
+        ImportFormat::Yaml => yaml_rows(text, kind)?,
+    };
+
+    let mut rows = Vec::new();
+    let mut errors = Vec::new();
+    for (kind, row, fields) in records {
+        match typed_row(kind, row, fields) {
+            Ok(row) => rows.push(row),
+            Err(row_errors) => errors.extend(row_errors),
+        }
+    }
+    Ok((rows, errors))
+}
+
+type RawRow = (ImportKind, usize, Map<String, Value>);
+
+/// CSV rows keep their line number. Every cell is text at this point; `typed_row` converts it.
+fn csv_rows(text: &str, kind: Option<ImportKind>) -> Result<Vec<RawRow>, QueryError> {
+    let mut records = csv_records(text)?.into_iter();
+    let (_, header) = records.next()
+        .ok_or_else(|| QueryError::new("body", "CSV import is empty"))?;
+    let header: Vec<String> = header.iter().map(|name| name.trim().to_string()).collect();
+    let kind_index = header.iter().position(|name| name == "kind");
+    if kind.is_none() && kind_index.is_none() {
+        return Err(QueryError::new("kind", "CSV imports need a kind= parameter or a kind column")
+            .with_allowed(ImportKind::NAMES));
+    }
+
+    let mut rows = Vec::new();
+    for (line, record) in records {
+        if record.len() != header.len() {
+            return Err(QueryError::new("body", format!(
+                "Line {} has {} fields but the header has {}", line, record.len(), header.len()
+            )));
+        }
+
+        let row_kind = match kind_index.map(|index| record[index].as_str()) {
+            Some(name) if !name.trim().is_empty() => ImportKind::parse(name).ok_or_else(|| {
+                QueryError::new("body", format!("Line {}: unknown kind '{}'", line, name))
+                    .with_allowed(ImportKind::NAMES)
+            })?,
+            _ => kind.ok_or_else(|| QueryError::new("body", format!("Line {}: kind is empty", line)))?,
+        };
+
+        let fields = header.iter().zip(record)
+            .enumerate()
+            .filter(|(index, _)| Some(*index) != kind_index)
+            .map(|(_, (name, cell))| {
+                let value = if cell.is_empty() { Value::Null } else { Value::String(unescape_formula(cell)) };
+                (name.clone(), value)
+            })
+            .collect();
+        rows.push((row_kind, line, fields));
+    }
+    Ok(rows)
+}
+
+/// RFC 4180 records paired with the line each one starts on; blank lines are skipped
+fn csv_records(text: &str) -> Result<Vec<(usize, Vec<String>)>, QueryError> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut line = 1;
+    let mut start = 1;
+
+    let mut chars = text.trim_start_matches('\u{feff}').chars().peekable();
+    while let Some(c) = chars.next() {
+        if quoted {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    field.push('"');
+                }
+                '"' => quoted = false,
+                '\n' => {
+                    line += 1;
+                    field.push(c);
+                }
+                _ => field.push(c),
+            }
+            continue;
+        }
+
+        match c {
+            '"' if field.is_empty() => quoted = true,
+            ',' => record.push(std::mem::take(&mut field)),
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\n' => {
+                record.push(std::mem::take(&mut field));
+                records.push((start, std::mem::take(&mut record)));
+                line += 1;
+                start = line;
+            }
+            _ => field.push(c),
+        }
+    }
+
+    if quoted {
+        return Err(QueryError::new("body", format!("Unterminated quoted field starting on line {}", start)));
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push((start, record));
+    }
+    records.retain(|(_, record)| !(record.len() == 1 && record[0].trim().is_empty()));
+    Ok(records)
+}
+
+/// Undo the `'` the CSV export puts in front of cells spreadsheets would evaluate,
+/// so an exported file imports unchanged
+fn unescape_formula(cell: String) -> String {
+    match cell.strip_prefix('\'') {
+        Some(rest) if rest.starts_with(['=', '+', '-', '@']) => rest.to_string(),
+        _ => cell,
+    }
+}
+
+/// YAML is either a mapping of sections (`datacenters:`, `racks:`, ...) or, with `kind=`,
+/// a plain list of rows. Rows are numbered from 1 within their section.
+fn yaml_rows(text: &str, kind: Option<ImportKind>) -> Result<Vec<RawRow>, QueryError> {
+    let document: Value = serde_yaml::from_str(text)
+        .map_err(|e| QueryError::new("body", format!("Invalid YAML: {}", e)))?;
+
+    let sections = match (document, kind) {
+        (Value::Null, _) => Vec::new(),
+        (Value::Array(rows), Some(kind)) => vec![(kind, Value::Array(rows))],
+        (Value::Object(sections), _) => sections.into_iter()
+            .map(|(name, rows)| match ImportKind::parse(&name) {
+                Some(kind) => Ok((kind, rows)),
+                None => Err(QueryError::new("body", format!("Unknown import section '{}'", name))
+                    .with_allowed(ImportKind::NAMES)),
+            })
+            .collect::<Result<Vec<_>, _>>()?,
+        _ => return Err(QueryError::new(
+            "body",
+            "Expected a mapping of datacenters, racks, positions and switches, or a list of rows with kind=",
+        )),
+    };
+
+    let mut rows = Vec::new();
+    for (kind, section) in sections {
+        let section = match section {
+            Value::Array(section) => section,
+            Value::Null => continue,
+            _ => return Err(QueryError::new("body", format!("Section '{}' must be a list of rows", kind.name()))),
+        };
+        for (index, row) in section.into_iter().enumerate() {
+            match row {
+                Value::Object(fields) => rows.push((kind, index + 1, fields)),
+                _ => return Err(QueryError::new("body", format!(
+                    "Row {} of '{}' must be a mapping of column names to values", index + 1, kind.name()
+                ))),
+            }
+        }
+    }
+    Ok(rows)
+}
+
+/// Maintained by the database on every table
+const ASSIGNED_COLUMNS: &[&str] = &["created_at", "updated_at"];
+
+/// Check every field against the kind's columns and convert it to the column type. Keys and
+/// timestamps are assigned by the database and dropped, so exported files can be re-imported.
+fn typed_row(kind: ImportKind, row: usize, raw: Map<String, Value>) -> Result<ImportRow, Vec<ImportRowError>> {
+    let schema = kind.schema();
+    let mut typed = ImportRow { kind, row, fields: Map::new() };
+    let mut errors = Vec::new();
+
+    for (name, value) in raw {
+        // Blank cells are how a CSV mixing kinds leaves out other kinds' columns
+        if value.is_null() {
+            continue;
+        }
+        let column = match kind.references().iter().find(|column| column.name == name) {
+            Some(column) => column,
+            None => match schema.column(&name) {
+                Some(column) if column.name == schema.key || ASSIGNED_COLUMNS.contains(&column.name) => continue,
+                Some(column) => column,
+                None => {
+                    errors.push(ImportRowError::new(&typed, Some(&name), format!(
+                        "Unknown column '{}' for {}", name, schema.table
+                    )));
+                    continue;
+                }
+            },
+        };
+
+        match column.json_value(value) {
+            Ok(value) => {
+                typed.fields.insert(name, value);
+            }
+            Err(message) => errors.push(ImportRowError::new(&typed, Some(&name), message)),
+        }
+    }
+
+    if errors.is_empty() { Ok(typed) } else { Err(errors) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn records(text: &str) -> Vec<(usize, Vec<String>)> {
+        csv_records(text).expect("CSV should parse")
+    }
+
+    fn row(line: usize, cells: &[&str]) -> (usize, Vec<String>) {
+        (line, cells.iter().map(|cell| cell.to_string()).collect())
+    }
+
+    #[test]
+    fn quoted_fields_keep_commas_and_newlines() {
+        let text = "name,notes\n\"dc-1, west\",\"first line\nsecond line\"\ndc-2,plain\n";
+        assert_eq!(records(text), vec![
+            row(1, &["name", "notes"]),
+            row(2, &["dc-1, west", "first line\nsecond line"]),
+            // The quoted newline moves the next record down a line
+            row(4, &["dc-2", "plain"]),
+        ]);
+    }
+
+    #[test]
+    fn doubled_quotes_are_unescaped() {
+        let text = "name,notes\ndc-1,\"the \"\"old\"\" hall\"\n";
+        assert_eq!(records(text)[1], row(2, &["dc-1", "the \"old\" hall"]));
+    }
+
+    #[test]
+    fn crlf_line_endings_and_trailing_blank_lines_are_dropped() {
+        let text = "\u{feff}name,code\r\ndc-1,AMS\r\ndc-2,\r\n\r\n";
+        assert_eq!(records(text), vec![
+            row(1, &["name", "code"]),
+            row(2, &["dc-1", "AMS"]),
+            row(3, &["dc-2", ""]),
+        ]);
+    }
+
+    #[test]
+    fn unterminated_quote_is_rejected() {
+        let error = csv_records("name\n\"dc-1\n").unwrap_err();
+        assert_eq!(error.message, "Unterminated quoted field starting on line 2");
+    }
+
+    #[test]
+    fn ragged_row_names_its_line() {
+        let text = "name,code\ndc-1,AMS\ndc-2\n";
+        let error = csv_rows(text, Some(ImportKind::Datacenters)).unwrap_err();
+        assert_eq!(error.parameter, "body");
+        assert_eq!(error.message, "Line 3 has 1 fields but the header has 2");
+    }
+}
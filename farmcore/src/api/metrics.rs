@@ -0,0 +1,93 @@
+use actix_web::body::MessageBody;
+use actix_web::dev::{ServiceRequest, ServiceResponse};
+use actix_web::middleware::{from_fn, Next};
+use actix_web::{get, web, Error, HttpResponse, Responder};
+use serde::Deserialize;
+use std::time::{Duration, Instant};
+
+use crate::api::auth::require_bearer_token;
+use crate::metrics;
+use crate::state::AppState;
+
+/// Prometheus text format content type
+const TEXT_FORMAT: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// Agents of ACTIVE servers silent for longer than this count as stale, unless the scrape
+/// passes stale_after
+const DEFAULT_STALE_AFTER_SECS: u64 = 3600;
+
+/// Upper bound on stale_after, so the threshold stays a representable time
+const MAX_STALE_AFTER_SECS: u64 = 365 * 24 * 3600;
+
+/// Route label for requests that matched no route, so scans of random paths do not
+/// create a series each
+const UNMATCHED_ROUTE: &str = "unmatched";
+
+/// Middleware counting and timing every request by method, route pattern and status.
+/// Streaming bodies (exports, the event feed) are timed until their first byte.
+pub async fn record_request_metrics(
+    req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<impl MessageBody>, Error> {
+    let started = Instant::now();
+    let method = req.method().to_string();
+    let route = req.match_pattern().unwrap_or_else(|| UNMATCHED_ROUTE.to_string());
+
+    let result = next.call(req).await;
+    let status = match &result {
+        Ok(res) => res.status(),
+        Err(e) => e.as_response_error().status_code(),
+    };
+    metrics::observe_http_request(&method, &route, status.as_u16(), started.elapsed());
+    result
+}
+
+/// Prometheus scrape endpoint, served outside /api. It needs an API token like the API
+/// itself (any role); give the scrape job one through its `authorization` settings.
+#[get("")]
+pub async fn metrics_endpoint(app_state: web::Data<AppState>) -> impl Responder {
+    HttpResponse::Ok()
+        .content_type(TEXT_FORMAT)
+        .body(metrics::render(app_state.storage()))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FleetMetricsQuery {
+    /// Seconds without an inventory report after which an agent counts as stale
+    pub stale_after: Option<u64>,
+}
+
+/// Fleet inventory gauges, computed from the database on every scrape; scrape it less
+/// often than /metrics (e.g. every minute or more). Set `stale_after` through the scrape
+/// job's `params` to change the stale agent threshold.
+#[get("/fleet")]
+pub async fn fleet_metrics_endpoint(
+    app_state: web::Data<AppState>,
+    query: web::Query<FleetMetricsQuery>,
+) -> impl Responder {
+    let stale_after = Duration::from_secs(query.stale_after.unwrap_or(DEFAULT_STALE_AFTER_SECS).min(MAX_STALE_AFTER_SECS));
+    let stale_before = chrono::Utc::now() - stale_after;
+
+    match app_state.fleet_repo().get_fleet_stats(stale_before).await {
+        Ok(stats) => HttpResponse::Ok()
+            .content_type(TEXT_FORMAT)
+            .body(metrics::render_fleet(&stats, stale_after)),
+        Err(e) => {
+            log::error!("Database error collecting fleet metrics: {}", e);
+            HttpResponse::InternalServerError()
+                .content_type(TEXT_FORMAT)
+                .body("# fleet metrics unavailable: database error\n")
+        }
+    }
+}
+
+/// The metrics carry BMC addresses and fleet totals, so they sit behind the same bearer
+/// token check as /api
+pub fn configure_metrics_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/metrics")
+            .wrap(from_fn(require_bearer_token))
+            .service(metrics_endpoint)
+            .service(fleet_metrics_endpoint),
+    );
+}
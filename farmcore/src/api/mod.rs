@@ -2,6 +2,11 @@ pub mod v1;
 pub mod responses;
 pub mod documentation;
 pub mod query_parser;
+pub mod auth;
+pub mod audit;
+pub mod export;
+pub mod import;
+pub mod metrics;
 
 use actix_web::web;
 
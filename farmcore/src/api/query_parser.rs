@@ -1,8 +1,15 @@
-use serde::Deserialize;
+use actix_web::http::StatusCode;
+use actix_web::{HttpResponse, ResponseError};
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::fmt;
 
+use crate::api::export::ExportFormat;
+use crate::api::responses::{ApiResponse, PaginationMeta};
 use crate::search::SearchQueryBuilder;
-use crate::models::{QueryOptions, QueryValue, WhereCondition};
+use crate::models::{Predicate, QueryOptions, QueryValue, TableSchema, WhereCondition};
 
 #[derive(Deserialize, Clone, Debug)]
 pub struct CommonPaginationQuery {
@@ -10,16 +17,132 @@ pub struct CommonPaginationQuery {
     pub per_page: Option<i64>,
     pub columns: Option<String>,
     pub search: Option<String>,
+    /// Comma-separated sort keys, `-` for descending: `sort=-last_inventory_at,server_name`
+    pub sort: Option<String>,
+    /// Keyset pagination: `next_cursor` from the previous page, or empty for the first page
+    pub after: Option<String>,
+    /// `count=false` skips the total count query
+    pub count: Option<bool>,
+    /// `csv` or `ndjson` streams every matching row as a download instead of a JSON page
+    pub format: Option<String>,
     #[serde(flatten)]
     pub filters: HashMap<String, String>,
 }
 
+impl CommonPaginationQuery {
+    pub fn include_total(&self) -> bool {
+        self.count.unwrap_or(true)
+    }
+}
+
+/// Largest page for offset pagination
+const MAX_PER_PAGE: i64 = 100;
+/// Largest page for cursor pagination, which has no OFFSET to scan past
+const MAX_CURSOR_PER_PAGE: i64 = 1000;
+
+/// Position in a keyset-paginated listing: rows continue after `key` in the table's key order.
+/// Handed to clients as an opaque base64 token.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Cursor {
+    table: String,
+    key: i64,
+    descending: bool,
+}
+
+impl Cursor {
+    pub fn encode(&self) -> String {
+        URL_SAFE_NO_PAD.encode(serde_json::to_vec(self).unwrap_or_default())
+    }
+
+    fn decode(token: &str, schema: &TableSchema) -> Result<Self, QueryError> {
+        let invalid = || QueryError::new("after", "Invalid or expired cursor");
+        let bytes = URL_SAFE_NO_PAD.decode(token).map_err(|_| invalid())?;
+        let cursor: Cursor = serde_json::from_slice(&bytes).map_err(|_| invalid())?;
+        if cursor.table != schema.table {
+            return Err(QueryError::new("after", format!("Cursor does not belong to {}", schema.table)));
+        }
+        Ok(cursor)
+    }
+}
+
+/// Keyset pagination requested with `after=`
+#[derive(Debug, Clone, Copy)]
+pub struct KeysetPage {
+    /// Key of the last row already seen; `None` on the first page
+    pub after: Option<i64>,
+    pub descending: bool,
+}
+
+/// A rejected list query, reported to the client as a 400 with the offending parameter
+#[derive(Debug, Clone, Serialize)]
+pub struct QueryError {
+    /// Query parameter at fault, e.g. "columns", "search[1].term" or a filter key
+    pub parameter: String,
+    pub message: String,
+    /// Accepted values for the parameter, when there is a closed set
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allowed: Option<Vec<String>>,
+}
+
+impl QueryError {
+    pub fn new(parameter: impl Into<String>, message: impl Into<String>) -> Self {
+        Self { parameter: parameter.into(), message: message.into(), allowed: None }
+    }
+
+    pub fn with_allowed<S: ToString>(mut self, allowed: &[S]) -> Self {
+        self.allowed = Some(allowed.iter().map(|s| s.to_string()).collect());
+        self
+    }
+
+    pub fn unknown_column(parameter: impl Into<String>, column: &str, schema: &TableSchema) -> Self {
+        Self::new(parameter, format!("Unknown column '{}' for {}", column, schema.table))
+            .with_allowed(&schema.column_names())
+    }
+
+    /// Unknown filter or search column; lists relation columns (`gpu.model`) as well
+    pub fn unknown_filter(parameter: impl Into<String>, column: &str, schema: &TableSchema) -> Self {
+        Self::new(parameter, format!("Unknown column '{}' for {}", column, schema.table))
+            .with_allowed(&schema.filter_names())
+    }
+}
+
+impl fmt::Display for QueryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.parameter, self.message)
+    }
+}
+
+impl ResponseError for QueryError {
+    fn status_code(&self) -> StatusCode {
+        StatusCode::BAD_REQUEST
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        let details = serde_json::to_value(self).unwrap_or_default();
+        let response = ApiResponse::<()>::error_with_details("INVALID_PARAMS", &self.message, details);
+        HttpResponse::BadRequest().json(response)
+    }
+}
+
+/// A list query parsed against a table by `QueryParser::parse_all`
+#[derive(Debug, Clone)]
+pub struct ParsedQuery {
+    pub page: i64,
+    pub per_page: i64,
+    pub offset: i64,
+    /// Search and filter conditions only, without the keyset bound; totals are counted over these
+    pub where_conditions: Vec<WhereCondition>,
+    /// Options for the page itself, keyset bound included
+    pub options: QueryOptions,
+}
+
 pub struct QueryParser;
 
 impl QueryParser {
     pub fn parse_pagination(query: &CommonPaginationQuery) -> Result<(i64, i64, i64), String> {
         let page = query.page.unwrap_or(1);
-        let per_page = query.per_page.unwrap_or(10).min(100);
+        let max_per_page = if query.after.is_some() { MAX_CURSOR_PER_PAGE } else { MAX_PER_PAGE };
+        let per_page = query.per_page.unwrap_or(10).min(max_per_page);
 
         if page < 1 || per_page < 1 {
             return Err("Invalid pagination parameters".to_string());
@@ -29,47 +152,181 @@ impl QueryParser {
         Ok((page, per_page, offset))
     }
 
-    pub fn parse_columns(columns: &Option<String>) -> Option<Vec<String>> {
+    /// Parse `after=` into keyset pagination on the table's key. Cursor pages are ordered by the
+    /// key alone, ascending unless `sort=-<key>`; the direction then travels inside the cursor.
+    pub fn parse_cursor(query: &CommonPaginationQuery, schema: &TableSchema) -> Result<Option<KeysetPage>, QueryError> {
+        let Some(token) = query.after.as_deref().map(str::trim) else {
+            return Ok(None);
+        };
+        if query.page.is_some_and(|page| page != 1) {
+            return Err(QueryError::new("page", "page cannot be combined with after"));
+        }
+
+        let descending = match query.sort.as_deref().map(str::trim) {
+            None | Some("") => None,
+            Some(sort) if sort == schema.key || sort.strip_prefix('+') == Some(schema.key) => Some(false),
+            Some(sort) if sort.strip_prefix('-') == Some(schema.key) => Some(true),
+            Some(_) => {
+                return Err(QueryError::new("sort", format!("Cursor pagination can only sort on {}", schema.key))
+                    .with_allowed(&[schema.key.to_string(), format!("-{}", schema.key)]));
+            }
+        };
+
+        if token.is_empty() {
+            return Ok(Some(KeysetPage { after: None, descending: descending.unwrap_or(false) }));
+        }
+        let cursor = Cursor::decode(token, schema)?;
+        if descending.is_some_and(|descending| descending != cursor.descending) {
+            return Err(QueryError::new("sort", "sort does not match the cursor's direction"));
+        }
+        Ok(Some(KeysetPage { after: Some(cursor.key), descending: cursor.descending }))
+    }
+
+    /// Pagination metadata for a list response. `total_count` is `None` when the caller opted out
+    /// with `count=false`; `last_key` is the key of the last returned row, used for `next_cursor`.
+    pub fn pagination_meta(
+        query: &CommonPaginationQuery,
+        schema: &TableSchema,
+        returned: usize,
+        total_count: Option<i64>,
+        last_key: Option<i64>,
+    ) -> PaginationMeta {
+        // The query was validated before the repository ran, so fall back quietly
+        let (page, per_page, _) = Self::parse_pagination(query).unwrap_or((1, MAX_PER_PAGE, 0));
+        let full_page = returned as i64 >= per_page;
+        let total_pages = total_count.map(|total| (total + per_page - 1) / per_page);
+
+        match Self::parse_cursor(query, schema).ok().flatten() {
+            Some(keyset) => {
+                let next_cursor = last_key.filter(|_| full_page).map(|key| {
+                    Cursor { table: schema.table.to_string(), key, descending: keyset.descending }.encode()
+                });
+                PaginationMeta {
+                    current_page: page,
+                    per_page,
+                    total_count,
+                    total_pages,
+                    has_next: next_cursor.is_some(),
+                    has_prev: keyset.after.is_some(),
+                    next_cursor,
+                }
+            }
+            None => PaginationMeta {
+                current_page: page,
+                per_page,
+                total_count,
+                total_pages,
+                // Without a count, a full page is the best hint that more rows follow
+                has_next: total_pages.map_or(full_page, |pages| page < pages),
+                has_prev: page > 1,
+                next_cursor: None,
+            },
+        }
+    }
+
+    pub fn parse_columns(columns: &Option<String>, schema: &TableSchema) -> Result<Option<Vec<String>>, QueryError> {
         match columns {
             Some(cols) if !cols.is_empty() => {
-                Some(cols.split(',').map(|s| s.trim().to_string()).collect())
+                let mut parsed = Vec::new();
+                for col in cols.split(',').map(str::trim) {
+                    if schema.column(col).is_none() {
+                        return Err(QueryError::unknown_column("columns", col, schema));
+                    }
+                    parsed.push(col.to_string());
+                }
+                Ok(Some(parsed))
             }
-            _ => None,
+            _ => Ok(None),
         }
     }
 
     pub fn parse_search_conditions(
         search: Option<&str>,
         filters: &HashMap<String, String>,
-    ) -> Result<Vec<WhereCondition>, String> {
+        schema: &TableSchema,
+    ) -> Result<Vec<WhereCondition>, QueryError> {
         let mut conditions = match search {
-            Some(json) => SearchQueryBuilder::build_conditions(json)?,
+            Some(json) => SearchQueryBuilder::build_conditions(json, schema)?,
             None => Vec::new(),
         };
+        // Parenthesize the search so an OR inside it cannot swallow the filters ANDed on below
+        if conditions.len() > 1 {
+            conditions = vec![WhereCondition { predicate: Predicate::Group(conditions), logical_operator: None }];
+        }
 
         // Turn remaining query params (e.g. sub_cluster_id=1&cluster_id=2) into WHERE conditions.
-        // Known pagination/control keys are excluded.
-        let skip = ["page", "per_page", "columns", "search"];
-        for (key, value) in filters {
-            if skip.contains(&key.as_str()) {
-                continue;
+        // Known pagination/control keys are excluded; anything else must be a column of the table
+        // or of one of its relations (gpu.model=A100).
+        // A comma-separated value on a non-text column (status=ACTIVE,RMA) means IN.
+        let skip = ["page", "per_page", "columns", "search", "sort", "after", "count", "format"];
+        let mut keys: Vec<&String> = filters.keys().filter(|key| !skip.contains(&key.as_str())).collect();
+        keys.sort(); // deterministic SQL and error reporting regardless of HashMap order
+        for key in keys {
+            let target = schema.filter(key)
+                .ok_or_else(|| QueryError::unknown_filter(key.as_str(), key, schema))?;
+            let column = target.column();
+            if !column.allows("=") {
+                return Err(QueryError::new(key.as_str(), format!("Column '{}' cannot be filtered on", key)));
             }
-            let qv = if let Ok(i) = value.parse::<i64>() {
-                QueryValue::Integer(i)
+            let raw = &filters[key];
+            let parse = |term: &str| column.parse_value(term, "=").map_err(|e| QueryError::new(key.as_str(), e));
+            let (operator, value) = if SearchQueryBuilder::filter_is_list(column.column_type, raw) {
+                let values = SearchQueryBuilder::split_list(raw).iter()
+                    .map(|term| parse(term))
+                    .collect::<Result<Vec<_>, _>>()?;
+                ("IN", QueryValue::List(values))
             } else {
-                QueryValue::String(value.clone())
+                ("=", parse(raw)?)
             };
-            conditions.push(WhereCondition {
-                column: key.clone(),
-                operator: "=".to_string(),
-                value: qv,
-                logical_operator: Some("AND".to_string()),
-            });
+            conditions.push(WhereCondition::compare(key, operator, value, Some("AND".to_string())));
         }
 
         Ok(conditions)
     }
 
+    /// Parse `sort` into an ORDER BY clause. The repository's default order is appended as a
+    /// tiebreaker so pages stay stable when sort keys have duplicate values.
+    pub fn parse_sort(
+        sort: &Option<String>,
+        schema: &TableSchema,
+        default_order_by: Option<String>,
+    ) -> Result<Option<String>, QueryError> {
+        let sort = match sort {
+            Some(sort) if !sort.trim().is_empty() => sort,
+            _ => return Ok(default_order_by),
+        };
+
+        let mut keys: Vec<&str> = Vec::new();
+        let mut clauses = Vec::new();
+        for key in sort.split(',').map(str::trim) {
+            let (name, direction) = match key.strip_prefix('-') {
+                Some(name) => (name, "DESC"),
+                None => (key.strip_prefix('+').unwrap_or(key), "ASC"),
+            };
+            if name.is_empty() {
+                return Err(QueryError::new("sort", "Empty sort key"));
+            }
+            let column = schema.column(name)
+                .filter(|column| column.sortable())
+                .ok_or_else(|| QueryError::new("sort", format!("Cannot sort {} by '{}'", schema.table, name))
+                    .with_allowed(&schema.sortable_column_names()))?;
+            if keys.contains(&column.name) {
+                return Err(QueryError::new("sort", format!("Column '{}' is sorted on more than once", name)));
+            }
+            keys.push(column.name);
+            clauses.push(format!("`{}` {}", column.name, direction));
+        }
+        clauses.extend(default_order_by);
+
+        Ok(Some(clauses.join(", ")))
+    }
+
+    /// Options for streaming every matching row: the page window is dropped and the
+    /// full row is read, since exports project `columns` themselves
+    pub fn export_options(options: QueryOptions) -> QueryOptions {
+        QueryOptions { columns: None, limit: None, offset: None, ..options }
+    }
+
     pub fn create_query_options(
         columns: Option<Vec<String>>,
         where_conditions: Vec<WhereCondition>,
@@ -86,26 +343,219 @@ impl QueryParser {
         }
     }
 
-    /// Complete parsing workflow - returns (page, per_page, offset, columns, conditions)
+    /// Complete parsing workflow - pagination, conditions and the page query options
     pub fn parse_all(
         query: &CommonPaginationQuery,
+        schema: &TableSchema,
         default_order_by: Option<String>
-    ) -> Result<(i64, i64, i64, Option<Vec<String>>, Vec<WhereCondition>, QueryOptions), String> {
-        let (page, per_page, offset) = Self::parse_pagination(query)?;
-        let columns = Self::parse_columns(&query.columns);
+    ) -> Result<ParsedQuery, QueryError> {
+        let (page, per_page, offset) = Self::parse_pagination(query)
+            .map_err(|e| QueryError::new("page", e))?;
+        let columns = Self::parse_columns(&query.columns, schema)?;
         let where_conditions = Self::parse_search_conditions(
-            query.search.as_deref(), 
-            &query.filters
+            query.search.as_deref(),
+            &query.filters,
+            schema,
         )?;
-        
-        let query_options = Self::create_query_options(
-            columns.clone(),
-            where_conditions.clone(),
-            per_page,
-            offset,
-            default_order_by,
+
+        let query_options = match Self::parse_cursor(query, schema)? {
+            // Keyset page: bound by the last key seen instead of skipping rows with OFFSET
+            Some(keyset) => {
+                let direction = if keyset.descending { "DESC" } else { "ASC" };
+                let mut conditions = where_conditions.clone();
+                if let Some(after) = keyset.after {
+                    let operator = if keyset.descending { "<" } else { ">" };
+                    conditions.push(WhereCondition::compare(
+                        schema.key, operator, QueryValue::Integer(after), Some("AND".to_string()),
+                    ));
+                }
+                QueryOptions {
+                    columns,
+                    where_conditions: conditions,
+                    limit: Some(per_page),
+                    offset: None,
+                    order_by: Some(format!("`{}` {}", schema.key, direction)),
+                }
+            }
+            None => {
+                let order_by = Self::parse_sort(&query.sort, schema, default_order_by)?;
+                Self::create_query_options(columns, where_conditions.clone(), per_page, offset, order_by)
+            }
+        };
+
+        Ok(ParsedQuery { page, per_page, offset, where_conditions, options: query_options })
+    }
+
+    /// Validate a list query against a table before handing it to a repository, so handlers
+    /// can answer 400 for bad input. Returns (page, per_page, offset).
+    pub fn validate(query: &CommonPaginationQuery, schema: &TableSchema) -> Result<(i64, i64, i64), QueryError> {
+        let ParsedQuery { page, per_page, offset, .. } = Self::parse_all(query, schema, None)?;
+        ExportFormat::parse(query)?;
+        Ok((page, per_page, offset))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Server;
+    use actix_web::web;
+
+    fn query(query_string: &str) -> CommonPaginationQuery {
+        web::Query::<CommonPaginationQuery>::from_query(query_string).unwrap().into_inner()
+    }
+
+    fn parse(query_string: &str) -> Result<ParsedQuery, QueryError> {
+        QueryParser::parse_all(&query(query_string), &Server::SCHEMA, Some("`server_id` ASC".to_string()))
+    }
+
+    fn compare(condition: &WhereCondition) -> (&str, &str, &QueryValue) {
+        match &condition.predicate {
+            Predicate::Compare { column, operator, value } => (column.as_str(), operator.as_str(), value),
+            Predicate::Group(_) => panic!("expected a comparison, got a group"),
+        }
+    }
+
+    #[test]
+    fn known_columns_sort_keys_and_filters_are_accepted() {
+        let parsed = parse("columns=server_id,server_name&sort=-last_inventory_at,server_name&status=ACTIVE,RMA&cluster_id=3&page=2&per_page=20").unwrap();
+        assert_eq!((parsed.page, parsed.per_page, parsed.offset), (2, 20, 20));
+        assert_eq!(parsed.options.columns, Some(vec!["server_id".to_string(), "server_name".to_string()]));
+        assert_eq!(
+            parsed.options.order_by.as_deref(),
+            Some("`last_inventory_at` DESC, `server_name` ASC, `server_id` ASC")
         );
 
-        Ok((page, per_page, offset, columns, where_conditions, query_options))
+        // Filters come out in key order, whatever order the query string had
+        assert_eq!(parsed.where_conditions.len(), 2);
+        assert_eq!(compare(&parsed.where_conditions[0]), ("cluster_id", "=", &QueryValue::Integer(3)));
+        let statuses = QueryValue::List(vec![QueryValue::String("ACTIVE".to_string()), QueryValue::String("RMA".to_string())]);
+        assert_eq!(compare(&parsed.where_conditions[1]), ("status", "IN", &statuses));
+    }
+
+    #[test]
+    fn an_unknown_column_is_rejected() {
+        let error = parse("columns=server_id,password").unwrap_err();
+        assert_eq!(error.parameter, "columns");
+        assert_eq!(error.message, "Unknown column 'password' for servers");
+        assert!(error.allowed.unwrap().contains(&"server_name".to_string()));
+
+        let error = parse("columns=server_id,%60server_name%60").unwrap_err();
+        assert_eq!(error.parameter, "columns");
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn an_unknown_sort_key_is_rejected() {
+        let error = parse("sort=server_name;DROP TABLE servers").unwrap_err();
+        assert_eq!(error.parameter, "sort");
+        assert_eq!(error.message, "Cannot sort servers by 'server_name;DROP TABLE servers'");
+        assert!(error.allowed.unwrap().contains(&"last_inventory_at".to_string()));
+
+        assert_eq!(parse("sort=-").unwrap_err().message, "Empty sort key");
+        assert_eq!(parse("sort=server_name,-server_name").unwrap_err().message, "Column 'server_name' is sorted on more than once");
+    }
+
+    #[test]
+    fn an_unknown_filter_is_rejected() {
+        let error = parse("hostname=gpu-node-01").unwrap_err();
+        assert_eq!(error.parameter, "hostname");
+        assert_eq!(error.message, "Unknown column 'hostname' for servers");
+        // Relation columns are offered alongside the table's own
+        let allowed = error.allowed.unwrap();
+        assert!(allowed.contains(&"status".to_string()));
+        assert!(allowed.contains(&"gpu.model".to_string()));
+
+        let error = parse("cluster_id=three").unwrap_err();
+        assert_eq!(error.parameter, "cluster_id");
+        assert_eq!(error.message, "'three' is not an integer");
+    }
+
+    #[test]
+    fn relation_columns_are_filters() {
+        let parsed = parse("gpu.model=A100&memory.count=16&nic.is_primary=true").unwrap();
+        let conditions: Vec<_> = parsed.where_conditions.iter().map(compare).collect();
+        assert_eq!(conditions, [
+            ("gpu.model", "=", &QueryValue::String("A100".to_string())),
+            ("memory.count", "=", &QueryValue::Integer(16)),
+            ("nic.is_primary", "=", &QueryValue::Boolean(true)),
+        ]);
+
+        for key in ["gpu.serial", "psu.model", "gpu."] {
+            let error = parse(&format!("{}=x", key)).unwrap_err();
+            assert_eq!(error.parameter, key);
+            assert_eq!(error.message, format!("Unknown column '{}' for servers", key));
+            assert!(error.allowed.unwrap().contains(&"gpu.count".to_string()));
+        }
+        assert_eq!(parse("memory.count=many").unwrap_err().message, "'many' is not an integer");
+    }
+
+    fn cursor(table: &str, key: i64, descending: bool) -> String {
+        Cursor { table: table.to_string(), key, descending }.encode()
+    }
+
+    #[test]
+    fn a_cursor_round_trips_through_the_pagination_meta() {
+        // The first page of a cursor listing: no bound, and the next cursor is the last key seen
+        let first = query("after=&per_page=2&sort=-server_id");
+        let keyset = QueryParser::parse_cursor(&first, &Server::SCHEMA).unwrap().unwrap();
+        assert_eq!((keyset.after, keyset.descending), (None, true));
+        let meta = QueryParser::pagination_meta(&first, &Server::SCHEMA, 2, None, Some(41));
+        assert!(meta.has_next && !meta.has_prev);
+        let next_cursor = meta.next_cursor.unwrap();
+
+        // The direction travels in the cursor, so the next page need not repeat the sort
+        let next = query(&format!("after={}&per_page=2", next_cursor));
+        let parsed = QueryParser::parse_all(&next, &Server::SCHEMA, None).unwrap();
+        assert_eq!(parsed.options.order_by.as_deref(), Some("`server_id` DESC"));
+        assert_eq!(parsed.options.offset, None);
+        let bound = parsed.options.where_conditions.last().unwrap();
+        assert_eq!(compare(bound), ("server_id", "<", &QueryValue::Integer(41)));
+        // Totals are counted without the bound
+        assert!(parsed.where_conditions.is_empty());
+
+        // A short page is the last one
+        let meta = QueryParser::pagination_meta(&next, &Server::SCHEMA, 1, None, Some(40));
+        assert!(!meta.has_next && meta.has_prev);
+        assert_eq!(meta.next_cursor, None);
+
+        // Without an OFFSET to scan past, cursor pages may be larger
+        let (_, per_page, _) = QueryParser::parse_pagination(&query("after=&per_page=5000")).unwrap();
+        assert_eq!(per_page, MAX_CURSOR_PER_PAGE);
+    }
+
+    #[test]
+    fn a_malformed_or_tampered_cursor_is_rejected() {
+        let valid = cursor("servers", 41, false);
+        let tampered = URL_SAFE_NO_PAD.encode(r#"{"table":"servers","key":"41 OR 1=1","descending":false}"#);
+        for token in ["not a cursor", &valid[..valid.len() - 3], tampered.as_str()] {
+            let error = QueryParser::parse_cursor(&query(&format!("after={}", token)), &Server::SCHEMA).unwrap_err();
+            assert_eq!(error.parameter, "after", "{}", token);
+            assert_eq!(error.message, "Invalid or expired cursor", "{}", token);
+        }
+
+        // A cursor handed out for another listing
+        let error = QueryParser::parse_cursor(&query(&format!("after={}", cursor("virtual_machines", 41, false))), &Server::SCHEMA).unwrap_err();
+        assert_eq!(error.message, "Cursor does not belong to servers");
+    }
+
+    #[test]
+    fn a_cursor_only_sorts_on_the_key() {
+        let error = QueryParser::parse_cursor(&query("after=&sort=server_name"), &Server::SCHEMA).unwrap_err();
+        assert_eq!(error.parameter, "sort");
+        assert_eq!(error.allowed, Some(vec!["server_id".to_string(), "-server_id".to_string()]));
+
+        let descending = cursor("servers", 41, true);
+        let error = QueryParser::parse_cursor(&query(&format!("after={}&sort=server_id", descending)), &Server::SCHEMA).unwrap_err();
+        assert_eq!(error.message, "sort does not match the cursor's direction");
+
+        let error = QueryParser::parse_cursor(&query(&format!("after={}&page=2", descending)), &Server::SCHEMA).unwrap_err();
+        assert_eq!(error.parameter, "page");
+    }
+
+    #[test]
+    fn control_parameters_are_not_filters() {
+        let parsed = parse("page=1&per_page=500&count=false&format=csv").unwrap();
+        assert!(parsed.where_conditions.is_empty());
+        assert_eq!(parsed.per_page, MAX_PER_PAGE);
+    }
+}
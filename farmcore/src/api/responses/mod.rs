@@ -1,6 +1,8 @@
+use schemars::JsonSchema;
 use serde::Serialize;
 
-#[derive(Serialize)]
+#[derive(Serialize, JsonSchema)]
+#[schemars(rename = "ApiResponse_{T}")]
 pub struct ApiResponse<T> {
     pub success: bool,
     pub data: Option<T>,
@@ -10,7 +12,7 @@ pub struct ApiResponse<T> {
     pub meta: Option<ApiMeta>,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, JsonSchema)]
 pub struct ApiError {
     pub code: String,
     pub message: String,
@@ -18,7 +20,7 @@ pub struct ApiError {
     pub details: Option<serde_json::Value>,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, JsonSchema)]
 pub struct ApiMeta {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub pagination: Option<PaginationMeta>,
@@ -29,14 +31,20 @@ pub struct ApiMeta {
     pub timestamp: chrono::DateTime<chrono::Utc>,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, JsonSchema)]
 pub struct PaginationMeta {
     pub current_page: i64,
     pub per_page: i64,
-    pub total_count: i64,
-    pub total_pages: i64,
+    /// Omitted when the request asked for `count=false`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total_count: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total_pages: Option<i64>,
     pub has_next: bool,
     pub has_prev: bool,
+    /// Pass as `after=` to fetch the next page of a cursor-paginated listing
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
 }
 
 // Update helper methods to only include meta when needed
@@ -0,0 +1,117 @@
+use actix_web::{get, web, HttpResponse, Responder, ResponseError};
+
+use crate::api::documentation::*;
+use crate::api::export::{export_response, ExportFormat};
+use crate::api::query_parser::{CommonPaginationQuery, QueryParser};
+use crate::api::responses::{ApiMeta, ApiResponse};
+use crate::models::AuditEvent;
+use crate::state::AppState;
+
+/// Documentation for the audit log endpoints; also feeds /api/v1/openapi.json.
+/// The scope root serves the event list itself, so there is no index handler.
+pub fn documentation() -> ApiDocumentation {
+    ApiDocumentation::new(
+        "Farm Audit Log API",
+        "v1",
+        "Who did what: every mutating API call and BMC power action (ADMIN role required)",
+        "/api/v1/audit",
+    )
+    .with_response_format(standard_response_format())
+    .with_authentication(bearer_auth_doc())
+    .add_endpoint(
+        EndpointDoc::new("/api/v1/audit", HttpMethod::Get, "Paginated audit events, newest first")
+            .add_query_parameter(ParameterDoc::new("page", ParameterType::Integer, "Page number", false).with_default("1"))
+            .add_query_parameter(ParameterDoc::new("per_page", ParameterType::Integer, "Items per page (max 100)", false).with_default("10"))
+            .add_query_parameter(ParameterDoc::new("columns", ParameterType::String, "Comma-separated column list", false))
+            .add_query_parameter(ParameterDoc::new("sort", ParameterType::String, "Comma-separated sort columns, prefixed with - for descending", false).with_example("-created_at"))
+            .add_query_parameter(ParameterDoc::new("format", ParameterType::String, "csv or ndjson streams every matching row as a download (no page cap); columns, search and filters apply", false))
+            .add_query_parameter(ParameterDoc::new("after", ParameterType::String, "Cursor from meta.pagination.next_cursor; empty for the first page. Pages by primary key, up to 1000 per page", false))
+            .add_query_parameter(ParameterDoc::new("count", ParameterType::Boolean, "Set to false to skip the total count", false).with_default("true"))
+            .add_query_parameter(ParameterDoc::new("search", ParameterType::String, "JSON search criteria", false))
+            .add_example(ExampleDoc::new(
+                "Who power-cycled server 42?",
+                "/api/v1/audit?resource=servers&entity_id=42&http_method=POST",
+            ))
+            .add_response_code(ResponseCodeDoc::new(200, "Success"))
+            .add_response_code(ResponseCodeDoc::new(400, "Invalid parameters"))
+            .add_response_code(ResponseCodeDoc::new(403, "Requires the ADMIN role"))
+            .with_response_schema::<Vec<AuditEvent>>(),
+    )
+    .add_endpoint(
+        EndpointDoc::new("/api/v1/audit/{id}", HttpMethod::Get, "Get a single audit event")
+            .add_path_parameter(ParameterDoc::new("id", ParameterType::Integer, "Audit event ID", true))
+            .add_response_code(ResponseCodeDoc::new(200, "Success"))
+            .add_response_code(ResponseCodeDoc::new(404, "Audit event not found"))
+            .with_response_schema::<AuditEvent>(),
+    )
+}
+
+// ===================================================================
+// AUDIT LOG ENDPOINTS
+// ===================================================================
+
+#[get("")]
+pub async fn get_audit_events(
+    app_state: web::Data<AppState>,
+    query: web::Query<CommonPaginationQuery>,
+) -> impl Responder {
+    if let Err(e) = QueryParser::validate(&query, &AuditEvent::SCHEMA) {
+        return e.error_response();
+    }
+
+    if let Some(format) = ExportFormat::from_query(&query) {
+        let rows = app_state.audit_repo().stream_events(query.clone().into_inner());
+        return export_response(format, &AuditEvent::SCHEMA, &query, rows);
+    }
+
+    let query = query.into_inner();
+    match app_state.audit_repo().get_all_events(query.clone()).await {
+        Ok((events, total_count)) => {
+            let last_key = events.last().map(|event| event.audit_id);
+            let meta = ApiMeta {
+                pagination: Some(QueryParser::pagination_meta(&query, &AuditEvent::SCHEMA, events.len(), total_count, last_key)),
+                filters_applied: None,
+                request_id: None,
+                timestamp: chrono::Utc::now(),
+            };
+            HttpResponse::Ok().json(ApiResponse::success_with_meta(events, meta))
+        }
+        Err(e) => {
+            log::error!("Database error fetching audit events: {}", e);
+            HttpResponse::InternalServerError()
+                .json(ApiResponse::<()>::error("DATABASE_ERROR", "Failed to fetch audit events"))
+        }
+    }
+}
+
+#[get("/{id}")]
+pub async fn get_audit_event_by_id(
+    app_state: web::Data<AppState>,
+    id: web::Path<i64>,
+) -> impl Responder {
+    let audit_id = id.into_inner();
+    match app_state.audit_repo().get_event_by_id(audit_id).await {
+        Ok(Some(event)) => HttpResponse::Ok().json(ApiResponse::success(event)),
+        Ok(None) => HttpResponse::NotFound().json(ApiResponse::<()>::error(
+            "NOT_FOUND",
+            &format!("Audit event with ID {} not found", audit_id),
+        )),
+        Err(e) => {
+            log::error!("Database error fetching audit event {}: {}", audit_id, e);
+            HttpResponse::InternalServerError()
+                .json(ApiResponse::<()>::error("DATABASE_ERROR", "Failed to fetch audit event"))
+        }
+    }
+}
+
+// ===================================================================
+// ROUTE CONFIGURATION
+// ===================================================================
+
+pub fn configure_audit_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/audit")
+            .service(get_audit_events)
+            .service(get_audit_event_by_id),
+    );
+}
@@ -1,15 +1,16 @@
-use actix_web::{delete, get, post, put, web, HttpResponse, Responder};
+use actix_web::{delete, get, post, put, web, HttpResponse, Responder, ResponseError};
 use std::collections::HashMap;
 
-use crate::api::responses::ApiResponse;
+use crate::api::responses::{ApiResponse, ApiMeta};
 use crate::api::documentation::*;
-use crate::api::query_parser::CommonPaginationQuery;
+use crate::api::export::{export_response, ExportFormat};
+use crate::api::query_parser::{CommonPaginationQuery, QueryParser};
 use crate::state::AppState;
-use crate::models::{ServerCluster, ServerSubCluster};
+use crate::models::{ClusterWithServers, ClusterWithSubClusters, ServerCluster, ServerSubCluster};
 
-#[get("")]
-pub async fn index() -> impl Responder {
-    let documentation = ApiDocumentation::new(
+/// Documentation for the server cluster endpoints; also feeds /api/v1/openapi.json
+pub fn documentation() -> ApiDocumentation {
+    ApiDocumentation::new(
         "Farm Cluster API",
         "v1",
         "Cluster management endpoints",
@@ -17,31 +18,37 @@ pub async fn index() -> impl Responder {
     )
     .with_response_format(standard_response_format())
     .add_endpoint(
-        EndpointDoc::new("/api/v1/clusters", HttpMethod::Get, 
+        EndpointDoc::new("/api/v1/clusters/list", HttpMethod::Get, 
             "List all clusters with pagination and filtering")
             .add_query_parameter(ParameterDoc::new("page", ParameterType::Integer, "Page number", false).with_default("1"))
             .add_query_parameter(ParameterDoc::new("per_page", ParameterType::Integer, "Items per page", false).with_default("10"))
             .add_query_parameter(ParameterDoc::new("columns", ParameterType::String, "Comma-separated column list", false))
+            .add_query_parameter(ParameterDoc::new("sort", ParameterType::String, "Comma-separated sort columns, prefixed with - for descending", false).with_example("cluster_name"))
+            .add_query_parameter(ParameterDoc::new("format", ParameterType::String, "csv or ndjson streams every matching row as a download (no page cap); columns, search and filters apply", false))
             .add_response_code(ResponseCodeDoc::new(200, "Success"))
             .add_response_code(ResponseCodeDoc::new(400, "Invalid parameters"))
+            .with_response_schema::<Vec<ServerCluster>>()
     )
     .add_endpoint(
         EndpointDoc::new("/api/v1/clusters/{id}", HttpMethod::Get, "Get specific cluster by ID")
             .add_path_parameter(ParameterDoc::new("id", ParameterType::Integer, "Cluster ID", true))
             .add_response_code(ResponseCodeDoc::new(200, "Success"))
             .add_response_code(ResponseCodeDoc::new(404, "Cluster not found"))
+            .with_response_schema::<ServerCluster>()
     )
     .add_endpoint(
         EndpointDoc::new("/api/v1/clusters/{id}/with-sub-clusters", HttpMethod::Get, "Get cluster with all its sub-clusters")
             .add_path_parameter(ParameterDoc::new("id", ParameterType::Integer, "Cluster ID", true))
             .add_response_code(ResponseCodeDoc::new(200, "Success"))
             .add_response_code(ResponseCodeDoc::new(404, "Cluster not found"))
+            .with_response_schema::<ClusterWithSubClusters>()
     )
     .add_endpoint(
         EndpointDoc::new("/api/v1/clusters/{id}/with-servers", HttpMethod::Get, "Get cluster with all its servers")
             .add_path_parameter(ParameterDoc::new("id", ParameterType::Integer, "Cluster ID", true))
             .add_response_code(ResponseCodeDoc::new(200, "Success"))
             .add_response_code(ResponseCodeDoc::new(404, "Cluster not found"))
+            .with_response_schema::<ClusterWithServers>()
     )
     .add_endpoint(
         EndpointDoc::new("/api/v1/clusters/stats", HttpMethod::Get, "Get statistics for all clusters")
@@ -57,12 +64,14 @@ pub async fn index() -> impl Responder {
         EndpointDoc::new("/api/v1/clusters", HttpMethod::Post, "Create a new cluster")
             .add_response_code(ResponseCodeDoc::new(201, "Cluster created"))
             .add_response_code(ResponseCodeDoc::new(400, "Invalid data"))
+            .with_request_schema::<ServerCluster>()
     )
     .add_endpoint(
         EndpointDoc::new("/api/v1/clusters/{id}", HttpMethod::Put, "Update cluster fields")
             .add_path_parameter(ParameterDoc::new("id", ParameterType::Integer, "Cluster ID", true))
             .add_response_code(ResponseCodeDoc::new(200, "Success"))
             .add_response_code(ResponseCodeDoc::new(404, "Cluster not found"))
+            .with_request_schema::<HashMap<String, serde_json::Value>>()
     )
     .add_endpoint(
         EndpointDoc::new("/api/v1/clusters/{id}", HttpMethod::Delete, "Delete a cluster")
@@ -74,12 +83,14 @@ pub async fn index() -> impl Responder {
         EndpointDoc::new("/api/v1/clusters/{id}/sub-clusters", HttpMethod::Get, "Get all sub-clusters for a cluster")
             .add_path_parameter(ParameterDoc::new("id", ParameterType::Integer, "Cluster ID", true))
             .add_response_code(ResponseCodeDoc::new(200, "Success"))
+            .with_response_schema::<Vec<ServerSubCluster>>()
     )
     .add_endpoint(
         EndpointDoc::new("/api/v1/clusters/sub-clusters/{sub_cluster_id}", HttpMethod::Get, "Get specific sub-cluster by ID")
             .add_path_parameter(ParameterDoc::new("sub_cluster_id", ParameterType::Integer, "Sub-cluster ID", true))
             .add_response_code(ResponseCodeDoc::new(200, "Success"))
             .add_response_code(ResponseCodeDoc::new(404, "Sub-cluster not found"))
+            .with_response_schema::<ServerSubCluster>()
     )
     .add_endpoint(
         EndpointDoc::new("/api/v1/clusters/sub-clusters/{sub_cluster_id}/stats", HttpMethod::Get, "Get sub-cluster statistics")
@@ -92,21 +103,26 @@ pub async fn index() -> impl Responder {
             .add_path_parameter(ParameterDoc::new("id", ParameterType::Integer, "Cluster ID", true))
             .add_response_code(ResponseCodeDoc::new(201, "Sub-cluster created"))
             .add_response_code(ResponseCodeDoc::new(400, "Invalid data"))
+            .with_request_schema::<ServerSubCluster>()
     )
     .add_endpoint(
         EndpointDoc::new("/api/v1/clusters/sub-clusters/{sub_cluster_id}", HttpMethod::Put, "Update sub-cluster fields")
             .add_path_parameter(ParameterDoc::new("sub_cluster_id", ParameterType::Integer, "Sub-cluster ID", true))
             .add_response_code(ResponseCodeDoc::new(200, "Success"))
             .add_response_code(ResponseCodeDoc::new(404, "Sub-cluster not found"))
+            .with_request_schema::<HashMap<String, serde_json::Value>>()
     )
     .add_endpoint(
         EndpointDoc::new("/api/v1/clusters/sub-clusters/{sub_cluster_id}", HttpMethod::Delete, "Delete a sub-cluster")
             .add_path_parameter(ParameterDoc::new("sub_cluster_id", ParameterType::Integer, "Sub-cluster ID", true))
             .add_response_code(ResponseCodeDoc::new(200, "Success"))
             .add_response_code(ResponseCodeDoc::new(404, "Sub-cluster not found"))
-    );
+    )
+}
 
-    let response = ApiResponse::success(documentation);
+#[get("")]
+pub async fn index() -> impl Responder {
+    let response = ApiResponse::success(documentation());
     HttpResponse::Ok().json(response)
 }
 
@@ -117,9 +133,26 @@ pub async fn get_all_clusters(
     app_state: web::Data<AppState>, 
     query: web::Query<CommonPaginationQuery>
 ) -> impl Responder {
-    match app_state.cluster_repo().get_all_clusters(query.into_inner()).await {
-        Ok(clusters) => {
-            let response = ApiResponse::success(clusters);
+    if let Err(e) = QueryParser::validate(&query, &ServerCluster::SCHEMA) {
+        return e.error_response();
+    }
+
+    if let Some(format) = ExportFormat::from_query(&query) {
+        let rows = app_state.cluster_repo().stream_clusters(query.clone().into_inner());
+        return export_response(format, &ServerCluster::SCHEMA, &query, rows);
+    }
+
+    let query = query.into_inner();
+    match app_state.cluster_repo().get_all_clusters(query.clone()).await {
+        Ok((clusters, total_count)) => {
+            let last_key = clusters.last().map(|cluster| cluster.cluster_id as i64);
+            let meta = ApiMeta {
+                pagination: Some(QueryParser::pagination_meta(&query, &ServerCluster::SCHEMA, clusters.len(), total_count, last_key)),
+                filters_applied: None,
+                request_id: None,
+                timestamp: chrono::Utc::now(),
+            };
+            let response = ApiResponse::success_with_meta(clusters, meta);
             HttpResponse::Ok().json(response)
         },
         Err(e) => {
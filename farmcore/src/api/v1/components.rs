@@ -1,13 +1,17 @@
-use actix_web::{get, web, HttpResponse, Responder};
-use crate::api::responses::ApiResponse;
+use actix_web::{get, web, HttpResponse, Responder, ResponseError};
+use crate::api::responses::{ApiResponse, ApiMeta};
 use crate::api::documentation::*;
-use crate::api::query_parser::CommonPaginationQuery;
-use crate::repositories::ComponentRepo;
+use crate::api::export::{export_response, ExportFormat};
+use crate::api::query_parser::{CommonPaginationQuery, QueryParser};
+use crate::models::{
+    ComponentBmcType, ComponentCatalog, ComponentCatalogStats, ComponentCpuType, ComponentDiskType,
+    ComponentGpuType, ComponentMemoryType, ComponentMotherboardType, ComponentNetworkType,
+};
 use crate::state::AppState;
 
-#[get("")]
-pub async fn index() -> impl Responder {
-    let documentation = ApiDocumentation::new(
+/// Documentation for the component catalog endpoints; also feeds /api/v1/openapi.json
+pub fn documentation() -> ApiDocumentation {
+    ApiDocumentation::new(
         "Farm Component Catalog API",
         "v1",
         "Component reference data management endpoints",
@@ -20,6 +24,7 @@ pub async fn index() -> impl Responder {
             .add_example(ExampleDoc::new("Get full catalog", "/api/v1/components/catalog"))
             .add_response_code(ResponseCodeDoc::new(200, "Success - Returns all component types"))
             .add_response_code(ResponseCodeDoc::new(500, "Internal server error"))
+            .with_response_schema::<ComponentCatalog>()
     )
     .add_endpoint(
         EndpointDoc::new("/api/v1/components/stats", HttpMethod::Get, 
@@ -27,72 +32,90 @@ pub async fn index() -> impl Responder {
             .add_example(ExampleDoc::new("Get catalog stats", "/api/v1/components/stats"))
             .add_response_code(ResponseCodeDoc::new(200, "Success - Returns component counts"))
             .add_response_code(ResponseCodeDoc::new(500, "Internal server error"))
+            .with_response_schema::<ComponentCatalogStats>()
     )
     .add_endpoint(
         EndpointDoc::new("/api/v1/components/cpus", HttpMethod::Get, 
             "Get all CPU component types with pagination")
             .add_query_parameter(ParameterDoc::new("page", ParameterType::Integer, "Page number", false).with_default("1"))
             .add_query_parameter(ParameterDoc::new("per_page", ParameterType::Integer, "Items per page", false).with_default("10"))
+            .add_query_parameter(ParameterDoc::new("format", ParameterType::String, "csv or ndjson streams every matching row as a download (no page cap); columns, search and filters apply", false))
             .add_example(ExampleDoc::new("Get CPU types", "/api/v1/components/cpus?page=1&per_page=25"))
             .add_response_code(ResponseCodeDoc::new(200, "Success"))
             .add_response_code(ResponseCodeDoc::new(400, "Invalid parameters"))
+            .with_response_schema::<Vec<ComponentCpuType>>()
     )
     .add_endpoint(
         EndpointDoc::new("/api/v1/components/memory", HttpMethod::Get, 
             "Get all memory component types with pagination")
             .add_query_parameter(ParameterDoc::new("page", ParameterType::Integer, "Page number", false).with_default("1"))
             .add_query_parameter(ParameterDoc::new("per_page", ParameterType::Integer, "Items per page", false).with_default("10"))
+            .add_query_parameter(ParameterDoc::new("format", ParameterType::String, "csv or ndjson streams every matching row as a download (no page cap); columns, search and filters apply", false))
             .add_example(ExampleDoc::new("Get memory types", "/api/v1/components/memory"))
             .add_response_code(ResponseCodeDoc::new(200, "Success"))
             .add_response_code(ResponseCodeDoc::new(400, "Invalid parameters"))
+            .with_response_schema::<Vec<ComponentMemoryType>>()
     )
     .add_endpoint(
         EndpointDoc::new("/api/v1/components/disks", HttpMethod::Get, 
             "Get all disk component types with pagination")
             .add_query_parameter(ParameterDoc::new("page", ParameterType::Integer, "Page number", false).with_default("1"))
             .add_query_parameter(ParameterDoc::new("per_page", ParameterType::Integer, "Items per page", false).with_default("10"))
+            .add_query_parameter(ParameterDoc::new("format", ParameterType::String, "csv or ndjson streams every matching row as a download (no page cap); columns, search and filters apply", false))
             .add_example(ExampleDoc::new("Get disk types", "/api/v1/components/disks"))
             .add_response_code(ResponseCodeDoc::new(200, "Success"))
             .add_response_code(ResponseCodeDoc::new(400, "Invalid parameters"))
+            .with_response_schema::<Vec<ComponentDiskType>>()
     )
     .add_endpoint(
         EndpointDoc::new("/api/v1/components/network", HttpMethod::Get, 
             "Get all network interface component types with pagination")
             .add_query_parameter(ParameterDoc::new("page", ParameterType::Integer, "Page number", false).with_default("1"))
             .add_query_parameter(ParameterDoc::new("per_page", ParameterType::Integer, "Items per page", false).with_default("10"))
+            .add_query_parameter(ParameterDoc::new("format", ParameterType::String, "csv or ndjson streams every matching row as a download (no page cap); columns, search and filters apply", false))
             .add_example(ExampleDoc::new("Get network types", "/api/v1/components/network"))
             .add_response_code(ResponseCodeDoc::new(200, "Success"))
             .add_response_code(ResponseCodeDoc::new(400, "Invalid parameters"))
+            .with_response_schema::<Vec<ComponentNetworkType>>()
     )
     .add_endpoint(
         EndpointDoc::new("/api/v1/components/gpus", HttpMethod::Get, 
             "Get all GPU component types with pagination")
             .add_query_parameter(ParameterDoc::new("page", ParameterType::Integer, "Page number", false).with_default("1"))
             .add_query_parameter(ParameterDoc::new("per_page", ParameterType::Integer, "Items per page", false).with_default("10"))
+            .add_query_parameter(ParameterDoc::new("format", ParameterType::String, "csv or ndjson streams every matching row as a download (no page cap); columns, search and filters apply", false))
             .add_example(ExampleDoc::new("Get GPU types", "/api/v1/components/gpus"))
             .add_response_code(ResponseCodeDoc::new(200, "Success"))
             .add_response_code(ResponseCodeDoc::new(400, "Invalid parameters"))
+            .with_response_schema::<Vec<ComponentGpuType>>()
     )
     .add_endpoint(
         EndpointDoc::new("/api/v1/components/motherboards", HttpMethod::Get, 
             "Get all motherboard component types with pagination")
             .add_query_parameter(ParameterDoc::new("page", ParameterType::Integer, "Page number", false).with_default("1"))
             .add_query_parameter(ParameterDoc::new("per_page", ParameterType::Integer, "Items per page", false).with_default("10"))
+            .add_query_parameter(ParameterDoc::new("format", ParameterType::String, "csv or ndjson streams every matching row as a download (no page cap); columns, search and filters apply", false))
             .add_example(ExampleDoc::new("Get motherboard types", "/api/v1/components/motherboards"))
             .add_response_code(ResponseCodeDoc::new(200, "Success"))
             .add_response_code(ResponseCodeDoc::new(400, "Invalid parameters"))
+            .with_response_schema::<Vec<ComponentMotherboardType>>()
     )
     .add_endpoint(
         EndpointDoc::new("/api/v1/components/bmcs", HttpMethod::Get, 
             "Get all BMC component types with pagination")
             .add_query_parameter(ParameterDoc::new("page", ParameterType::Integer, "Page number", false).with_default("1"))
             .add_query_parameter(ParameterDoc::new("per_page", ParameterType::Integer, "Items per page", false).with_default("10"))
+            .add_query_parameter(ParameterDoc::new("format", ParameterType::String, "csv or ndjson streams every matching row as a download (no page cap); columns, search and filters apply", false))
             .add_example(ExampleDoc::new("Get BMC types", "/api/v1/components/bmcs"))
             .add_response_code(ResponseCodeDoc::new(200, "Success"))
             .add_response_code(ResponseCodeDoc::new(400, "Invalid parameters"))
-    );
+            .with_response_schema::<Vec<ComponentBmcType>>()
+    )
+}
 
-    let response = ApiResponse::success(documentation);
+#[get("")]
+pub async fn index() -> impl Responder {
+    let response = ApiResponse::success(documentation());
     HttpResponse::Ok().json(response)
 }
 
@@ -139,9 +162,26 @@ pub async fn get_all_cpu_types(
     app_state: web::Data<AppState>,
     query: web::Query<CommonPaginationQuery>
 ) -> impl Responder {
-    match app_state.component_repo().get_all_cpu_types(query.into_inner()).await {
-        Ok(cpu_types) => {
-            let response = ApiResponse::success(cpu_types);
+    if let Err(e) = QueryParser::validate(&query, &ComponentCpuType::SCHEMA) {
+        return e.error_response();
+    }
+
+    if let Some(format) = ExportFormat::from_query(&query) {
+        let rows = app_state.component_repo().stream_cpu_types(query.clone().into_inner());
+        return export_response(format, &ComponentCpuType::SCHEMA, &query, rows);
+    }
+
+    let query = query.into_inner();
+    match app_state.component_repo().get_all_cpu_types(query.clone()).await {
+        Ok((cpu_types, total_count)) => {
+            let last_key = cpu_types.last().map(|cpu_type| cpu_type.component_cpu_id as i64);
+            let meta = ApiMeta {
+                pagination: Some(QueryParser::pagination_meta(&query, &ComponentCpuType::SCHEMA, cpu_types.len(), total_count, last_key)),
+                filters_applied: None,
+                request_id: None,
+                timestamp: chrono::Utc::now(),
+            };
+            let response = ApiResponse::success_with_meta(cpu_types, meta);
             HttpResponse::Ok().json(response)
         },
         Err(e) => {
@@ -161,9 +201,26 @@ pub async fn get_all_memory_types(
     app_state: web::Data<AppState>,
     query: web::Query<CommonPaginationQuery>
 ) -> impl Responder {
-    match app_state.component_repo().get_all_memory_types(query.into_inner()).await {
-        Ok(memory_types) => {
-            let response = ApiResponse::success(memory_types);
+    if let Err(e) = QueryParser::validate(&query, &ComponentMemoryType::SCHEMA) {
+        return e.error_response();
+    }
+
+    if let Some(format) = ExportFormat::from_query(&query) {
+        let rows = app_state.component_repo().stream_memory_types(query.clone().into_inner());
+        return export_response(format, &ComponentMemoryType::SCHEMA, &query, rows);
+    }
+
+    let query = query.into_inner();
+    match app_state.component_repo().get_all_memory_types(query.clone()).await {
+        Ok((memory_types, total_count)) => {
+            let last_key = memory_types.last().map(|memory_type| memory_type.component_memory_id as i64);
+            let meta = ApiMeta {
+                pagination: Some(QueryParser::pagination_meta(&query, &ComponentMemoryType::SCHEMA, memory_types.len(), total_count, last_key)),
+                filters_applied: None,
+                request_id: None,
+                timestamp: chrono::Utc::now(),
+            };
+            let response = ApiResponse::success_with_meta(memory_types, meta);
             HttpResponse::Ok().json(response)
         },
         Err(e) => {
@@ -183,9 +240,26 @@ pub async fn get_all_disk_types(
     app_state: web::Data<AppState>,
     query: web::Query<CommonPaginationQuery>
 ) -> impl Responder {
-    match app_state.component_repo().get_all_disk_types(query.into_inner()).await {
-        Ok(disk_types) => {
-            let response = ApiResponse::success(disk_types);
+    if let Err(e) = QueryParser::validate(&query, &ComponentDiskType::SCHEMA) {
+        return e.error_response();
+    }
+
+    if let Some(format) = ExportFormat::from_query(&query) {
+        let rows = app_state.component_repo().stream_disk_types(query.clone().into_inner());
+        return export_response(format, &ComponentDiskType::SCHEMA, &query, rows);
+    }
+
+    let query = query.into_inner();
+    match app_state.component_repo().get_all_disk_types(query.clone()).await {
+        Ok((disk_types, total_count)) => {
+            let last_key = disk_types.last().map(|disk_type| disk_type.component_disk_id as i64);
+            let meta = ApiMeta {
+                pagination: Some(QueryParser::pagination_meta(&query, &ComponentDiskType::SCHEMA, disk_types.len(), total_count, last_key)),
+                filters_applied: None,
+                request_id: None,
+                timestamp: chrono::Utc::now(),
+            };
+            let response = ApiResponse::success_with_meta(disk_types, meta);
             HttpResponse::Ok().json(response)
         },
         Err(e) => {
@@ -205,9 +279,26 @@ pub async fn get_all_network_types(
     app_state: web::Data<AppState>,
     query: web::Query<CommonPaginationQuery>
 ) -> impl Responder {
-    match app_state.component_repo().get_all_network_types(query.into_inner()).await {
-        Ok(network_types) => {
-            let response = ApiResponse::success(network_types);
+    if let Err(e) = QueryParser::validate(&query, &ComponentNetworkType::SCHEMA) {
+        return e.error_response();
+    }
+
+    if let Some(format) = ExportFormat::from_query(&query) {
+        let rows = app_state.component_repo().stream_network_types(query.clone().into_inner());
+        return export_response(format, &ComponentNetworkType::SCHEMA, &query, rows);
+    }
+
+    let query = query.into_inner();
+    match app_state.component_repo().get_all_network_types(query.clone()).await {
+        Ok((network_types, total_count)) => {
+            let last_key = network_types.last().map(|network_type| network_type.component_network_id as i64);
+            let meta = ApiMeta {
+                pagination: Some(QueryParser::pagination_meta(&query, &ComponentNetworkType::SCHEMA, network_types.len(), total_count, last_key)),
+                filters_applied: None,
+                request_id: None,
+                timestamp: chrono::Utc::now(),
+            };
+            let response = ApiResponse::success_with_meta(network_types, meta);
             HttpResponse::Ok().json(response)
         },
         Err(e) => {
@@ -227,9 +318,26 @@ pub async fn get_all_gpu_types(
     app_state: web::Data<AppState>,
     query: web::Query<CommonPaginationQuery>
 ) -> impl Responder {
-    match app_state.component_repo().get_all_gpu_types(query.into_inner()).await {
-        Ok(gpu_types) => {
-            let response = ApiResponse::success(gpu_types);
+    if let Err(e) = QueryParser::validate(&query, &ComponentGpuType::SCHEMA) {
+        return e.error_response();
+    }
+
+    if let Some(format) = ExportFormat::from_query(&query) {
+        let rows = app_state.component_repo().stream_gpu_types(query.clone().into_inner());
+        return export_response(format, &ComponentGpuType::SCHEMA, &query, rows);
+    }
+
+    let query = query.into_inner();
+    match app_state.component_repo().get_all_gpu_types(query.clone()).await {
+        Ok((gpu_types, total_count)) => {
+            let last_key = gpu_types.last().map(|gpu_type| gpu_type.component_gpu_id as i64);
+            let meta = ApiMeta {
+                pagination: Some(QueryParser::pagination_meta(&query, &ComponentGpuType::SCHEMA, gpu_types.len(), total_count, last_key)),
+                filters_applied: None,
+                request_id: None,
+                timestamp: chrono::Utc::now(),
+            };
+            let response = ApiResponse::success_with_meta(gpu_types, meta);
             HttpResponse::Ok().json(response)
         },
         Err(e) => {
@@ -249,9 +357,26 @@ pub async fn get_all_motherboard_types(
     app_state: web::Data<AppState>,
     query: web::Query<CommonPaginationQuery>
 ) -> impl Responder {
-    match app_state.component_repo().get_all_motherboard_types(query.into_inner()).await {
-        Ok(motherboard_types) => {
-            let response = ApiResponse::success(motherboard_types);
+    if let Err(e) = QueryParser::validate(&query, &ComponentMotherboardType::SCHEMA) {
+        return e.error_response();
+    }
+
+    if let Some(format) = ExportFormat::from_query(&query) {
+        let rows = app_state.component_repo().stream_motherboard_types(query.clone().into_inner());
+        return export_response(format, &ComponentMotherboardType::SCHEMA, &query, rows);
+    }
+
+    let query = query.into_inner();
+    match app_state.component_repo().get_all_motherboard_types(query.clone()).await {
+        Ok((motherboard_types, total_count)) => {
+            let last_key = motherboard_types.last().map(|motherboard_type| motherboard_type.component_motherboard_id as i64);
+            let meta = ApiMeta {
+                pagination: Some(QueryParser::pagination_meta(&query, &ComponentMotherboardType::SCHEMA, motherboard_types.len(), total_count, last_key)),
+                filters_applied: None,
+                request_id: None,
+                timestamp: chrono::Utc::now(),
+            };
+            let response = ApiResponse::success_with_meta(motherboard_types, meta);
             HttpResponse::Ok().json(response)
         },
         Err(e) => {
@@ -271,9 +396,26 @@ pub async fn get_all_bmc_types(
     app_state: web::Data<AppState>,
     query: web::Query<CommonPaginationQuery>
 ) -> impl Responder {
-    match app_state.component_repo().get_all_bmc_types(query.into_inner()).await {
-        Ok(bmc_types) => {
-            let response = ApiResponse::success(bmc_types);
+    if let Err(e) = QueryParser::validate(&query, &ComponentBmcType::SCHEMA) {
+        return e.error_response();
+    }
+
+    if let Some(format) = ExportFormat::from_query(&query) {
+        let rows = app_state.component_repo().stream_bmc_types(query.clone().into_inner());
+        return export_response(format, &ComponentBmcType::SCHEMA, &query, rows);
+    }
+
+    let query = query.into_inner();
+    match app_state.component_repo().get_all_bmc_types(query.clone()).await {
+        Ok((bmc_types, total_count)) => {
+            let last_key = bmc_types.last().map(|bmc_type| bmc_type.component_bmc_id as i64);
+            let meta = ApiMeta {
+                pagination: Some(QueryParser::pagination_meta(&query, &ComponentBmcType::SCHEMA, bmc_types.len(), total_count, last_key)),
+                filters_applied: None,
+                request_id: None,
+                timestamp: chrono::Utc::now(),
+            };
+            let response = ApiResponse::success_with_meta(bmc_types, meta);
             HttpResponse::Ok().json(response)
         },
         Err(e) => {
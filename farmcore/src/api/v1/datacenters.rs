@@ -1,15 +1,17 @@
-use actix_web::{delete, get, post, put, web, HttpResponse, Responder};
+use actix_web::{delete, get, post, put, web, HttpRequest, HttpResponse, Responder, ResponseError};
 use std::collections::HashMap;
 
-use crate::api::responses::ApiResponse;
+use crate::api::responses::{ApiResponse, ApiMeta};
 use crate::api::documentation::*;
-use crate::api::query_parser::CommonPaginationQuery;
+use crate::api::export::{export_response, ExportFormat};
+use crate::api::import::{parse_document, ImportFormat, ImportQuery};
+use crate::api::query_parser::{CommonPaginationQuery, QueryParser};
 use crate::state::AppState;
-use crate::models::{Datacenter, DatacenterRack, DatacenterRackPosition};
+use crate::models::{Datacenter, DatacenterRack, DatacenterRackPosition, DatacenterWithRacks, ImportReport, RackWithPositions};
 
-#[get("")]
-pub async fn index() -> impl Responder {
-    let documentation = ApiDocumentation::new(
+/// Documentation for the datacenter endpoints; also feeds /api/v1/openapi.json
+pub fn documentation() -> ApiDocumentation {
+    ApiDocumentation::new(
         "Farm Datacenter API",
         "v1",
         "Datacenter management endpoints",
@@ -17,25 +19,30 @@ pub async fn index() -> impl Responder {
     )
     .with_response_format(standard_response_format())
     .add_endpoint(
-        EndpointDoc::new("/api/v1/datacenters", HttpMethod::Get, 
+        EndpointDoc::new("/api/v1/datacenters/list", HttpMethod::Get, 
             "List all datacenters with pagination and filtering")
             .add_query_parameter(ParameterDoc::new("page", ParameterType::Integer, "Page number", false).with_default("1"))
             .add_query_parameter(ParameterDoc::new("per_page", ParameterType::Integer, "Items per page", false).with_default("10"))
             .add_query_parameter(ParameterDoc::new("columns", ParameterType::String, "Comma-separated column list", false))
+            .add_query_parameter(ParameterDoc::new("sort", ParameterType::String, "Comma-separated sort columns, prefixed with - for descending", false).with_example("data_center_name"))
+            .add_query_parameter(ParameterDoc::new("format", ParameterType::String, "csv or ndjson streams every matching row as a download (no page cap); columns, search and filters apply", false))
             .add_response_code(ResponseCodeDoc::new(200, "Success"))
             .add_response_code(ResponseCodeDoc::new(400, "Invalid parameters"))
+            .with_response_schema::<Vec<Datacenter>>()
     )
     .add_endpoint(
         EndpointDoc::new("/api/v1/datacenters/{id}", HttpMethod::Get, "Get specific datacenter by ID")
             .add_path_parameter(ParameterDoc::new("id", ParameterType::Integer, "Datacenter ID", true))
             .add_response_code(ResponseCodeDoc::new(200, "Success"))
             .add_response_code(ResponseCodeDoc::new(404, "Datacenter not found"))
+            .with_response_schema::<Datacenter>()
     )
     .add_endpoint(
         EndpointDoc::new("/api/v1/datacenters/{id}/with-racks", HttpMethod::Get, "Get datacenter with all its racks")
             .add_path_parameter(ParameterDoc::new("id", ParameterType::Integer, "Datacenter ID", true))
             .add_response_code(ResponseCodeDoc::new(200, "Success"))
             .add_response_code(ResponseCodeDoc::new(404, "Datacenter not found"))
+            .with_response_schema::<DatacenterWithRacks>()
     )
     .add_endpoint(
         EndpointDoc::new("/api/v1/datacenters/stats", HttpMethod::Get, "Get statistics for all datacenters")
@@ -51,12 +58,29 @@ pub async fn index() -> impl Responder {
         EndpointDoc::new("/api/v1/datacenters", HttpMethod::Post, "Create a new datacenter")
             .add_response_code(ResponseCodeDoc::new(201, "Datacenter created"))
             .add_response_code(ResponseCodeDoc::new(400, "Invalid data"))
+            .with_request_schema::<Datacenter>()
+    )
+    .add_endpoint(
+        EndpointDoc::new("/api/v1/datacenters/import", HttpMethod::Post,
+            "Bulk import datacenters, racks, rack positions and switches from CSV or YAML in one transaction. \
+             YAML takes datacenters/racks/positions/switches sections; CSV takes one row per record with a kind column or kind=. \
+             Rows name their parents with data_center_code, rack_code and (switches) u_position. \
+             Positions are checked for U overlap like single creates; if any row fails, nothing is committed and every row error is returned.")
+            .add_query_parameter(ParameterDoc::new("format", ParameterType::String, "csv or yaml; defaults from the Content-Type", false))
+            .add_query_parameter(ParameterDoc::new("kind", ParameterType::String, "datacenters, racks, positions or switches, for CSV without a kind column or a YAML list of rows", false))
+            .add_query_parameter(ParameterDoc::new("dry_run", ParameterType::Boolean, "Validate every row and roll back", false).with_default("false"))
+            .add_response_code(ResponseCodeDoc::new(200, "Dry run passed"))
+            .add_response_code(ResponseCodeDoc::new(201, "Rows imported"))
+            .add_response_code(ResponseCodeDoc::new(400, "Unreadable document"))
+            .add_response_code(ResponseCodeDoc::new(422, "Row validation failed; details list each row error"))
+            .with_response_schema::<ImportReport>()
     )
     .add_endpoint(
         EndpointDoc::new("/api/v1/datacenters/{id}", HttpMethod::Put, "Update datacenter fields")
             .add_path_parameter(ParameterDoc::new("id", ParameterType::Integer, "Datacenter ID", true))
             .add_response_code(ResponseCodeDoc::new(200, "Success"))
             .add_response_code(ResponseCodeDoc::new(404, "Datacenter not found"))
+            .with_request_schema::<HashMap<String, serde_json::Value>>()
     )
     .add_endpoint(
         EndpointDoc::new("/api/v1/datacenters/{id}", HttpMethod::Delete, "Delete a datacenter")
@@ -68,18 +92,21 @@ pub async fn index() -> impl Responder {
         EndpointDoc::new("/api/v1/datacenters/{id}/racks", HttpMethod::Get, "Get all racks for a datacenter")
             .add_path_parameter(ParameterDoc::new("id", ParameterType::Integer, "Datacenter ID", true))
             .add_response_code(ResponseCodeDoc::new(200, "Success"))
+            .with_response_schema::<Vec<DatacenterRack>>()
     )
     .add_endpoint(
         EndpointDoc::new("/api/v1/datacenters/racks/{rack_id}", HttpMethod::Get, "Get specific rack by ID")
             .add_path_parameter(ParameterDoc::new("rack_id", ParameterType::Integer, "Rack ID", true))
             .add_response_code(ResponseCodeDoc::new(200, "Success"))
             .add_response_code(ResponseCodeDoc::new(404, "Rack not found"))
+            .with_response_schema::<DatacenterRack>()
     )
     .add_endpoint(
         EndpointDoc::new("/api/v1/datacenters/racks/{rack_id}/with-positions", HttpMethod::Get, "Get rack with all its positions")
             .add_path_parameter(ParameterDoc::new("rack_id", ParameterType::Integer, "Rack ID", true))
             .add_response_code(ResponseCodeDoc::new(200, "Success"))
             .add_response_code(ResponseCodeDoc::new(404, "Rack not found"))
+            .with_response_schema::<RackWithPositions>()
     )
     .add_endpoint(
         EndpointDoc::new("/api/v1/datacenters/racks/{rack_id}/utilization", HttpMethod::Get, "Get rack utilization statistics")
@@ -92,12 +119,14 @@ pub async fn index() -> impl Responder {
             .add_path_parameter(ParameterDoc::new("id", ParameterType::Integer, "Datacenter ID", true))
             .add_response_code(ResponseCodeDoc::new(201, "Rack created"))
             .add_response_code(ResponseCodeDoc::new(400, "Invalid data"))
+            .with_request_schema::<DatacenterRack>()
     )
     .add_endpoint(
         EndpointDoc::new("/api/v1/datacenters/racks/{rack_id}", HttpMethod::Put, "Update rack fields")
             .add_path_parameter(ParameterDoc::new("rack_id", ParameterType::Integer, "Rack ID", true))
             .add_response_code(ResponseCodeDoc::new(200, "Success"))
             .add_response_code(ResponseCodeDoc::new(404, "Rack not found"))
+            .with_request_schema::<HashMap<String, serde_json::Value>>()
     )
     .add_endpoint(
         EndpointDoc::new("/api/v1/datacenters/racks/{rack_id}", HttpMethod::Delete, "Delete a rack")
@@ -108,34 +137,44 @@ pub async fn index() -> impl Responder {
     .add_endpoint(
         EndpointDoc::new("/api/v1/datacenters/racks/{rack_id}/positions", HttpMethod::Get, "Get all positions for a rack")
             .add_path_parameter(ParameterDoc::new("rack_id", ParameterType::Integer, "Rack ID", true))
+            .add_query_parameter(ParameterDoc::new("columns", ParameterType::String, "Comma-separated column list", false))
+            .add_query_parameter(ParameterDoc::new("format", ParameterType::String, "csv or ndjson streams the rack's positions as a download; columns, search and filters apply", false))
             .add_response_code(ResponseCodeDoc::new(200, "Success"))
+            .add_response_code(ResponseCodeDoc::new(400, "Invalid parameters"))
+            .with_response_schema::<Vec<DatacenterRackPosition>>()
     )
     .add_endpoint(
         EndpointDoc::new("/api/v1/datacenters/positions/{position_id}", HttpMethod::Get, "Get specific position by ID")
             .add_path_parameter(ParameterDoc::new("position_id", ParameterType::Integer, "Position ID", true))
             .add_response_code(ResponseCodeDoc::new(200, "Success"))
             .add_response_code(ResponseCodeDoc::new(404, "Position not found"))
+            .with_response_schema::<DatacenterRackPosition>()
     )
     .add_endpoint(
         EndpointDoc::new("/api/v1/datacenters/racks/{rack_id}/positions", HttpMethod::Post, "Create a new position in a rack")
             .add_path_parameter(ParameterDoc::new("rack_id", ParameterType::Integer, "Rack ID", true))
             .add_response_code(ResponseCodeDoc::new(201, "Position created"))
             .add_response_code(ResponseCodeDoc::new(400, "Invalid data"))
+            .with_request_schema::<DatacenterRackPosition>()
     )
     .add_endpoint(
         EndpointDoc::new("/api/v1/datacenters/positions/{position_id}", HttpMethod::Put, "Update position fields")
             .add_path_parameter(ParameterDoc::new("position_id", ParameterType::Integer, "Position ID", true))
             .add_response_code(ResponseCodeDoc::new(200, "Success"))
             .add_response_code(ResponseCodeDoc::new(404, "Position not found"))
+            .with_request_schema::<HashMap<String, serde_json::Value>>()
     )
     .add_endpoint(
         EndpointDoc::new("/api/v1/datacenters/positions/{position_id}", HttpMethod::Delete, "Delete a position")
             .add_path_parameter(ParameterDoc::new("position_id", ParameterType::Integer, "Position ID", true))
             .add_response_code(ResponseCodeDoc::new(200, "Success"))
             .add_response_code(ResponseCodeDoc::new(404, "Position not found"))
-    );
+    )
+}
 
-    let response = ApiResponse::success(documentation);
+#[get("")]
+pub async fn index() -> impl Responder {
+    let response = ApiResponse::success(documentation());
     HttpResponse::Ok().json(response)
 }
 
@@ -146,9 +185,26 @@ pub async fn get_all_datacenters(
     app_state: web::Data<AppState>, 
     query: web::Query<CommonPaginationQuery>
 ) -> impl Responder {
-    match app_state.datacenter_repo().get_all_datacenters(query.into_inner()).await {
-        Ok(datacenters) => {
-            let response = ApiResponse::success(datacenters);
+    if let Err(e) = QueryParser::validate(&query, &Datacenter::SCHEMA) {
+        return e.error_response();
+    }
+
+    if let Some(format) = ExportFormat::from_query(&query) {
+        let rows = app_state.datacenter_repo().stream_datacenters(query.clone().into_inner());
+        return export_response(format, &Datacenter::SCHEMA, &query, rows);
+    }
+
+    let query = query.into_inner();
+    match app_state.datacenter_repo().get_all_datacenters(query.clone()).await {
+        Ok((datacenters, total_count)) => {
+            let last_key = datacenters.last().map(|datacenter| datacenter.data_center_id as i64);
+            let meta = ApiMeta {
+                pagination: Some(QueryParser::pagination_meta(&query, &Datacenter::SCHEMA, datacenters.len(), total_count, last_key)),
+                filters_applied: None,
+                request_id: None,
+                timestamp: chrono::Utc::now(),
+            };
+            let response = ApiResponse::success_with_meta(datacenters, meta);
             HttpResponse::Ok().json(response)
         },
         Err(e) => {
@@ -297,6 +353,56 @@ pub async fn create_datacenter(
     }
 }
 
+#[post("/import")]
+pub async fn import_inventory(
+    app_state: web::Data<AppState>,
+    req: HttpRequest,
+    query: web::Query<ImportQuery>,
+    body: web::Bytes,
+) -> impl Responder {
+    let query = query.into_inner();
+    let parsed = ImportFormat::detect(&query, &req)
+        .and_then(|format| parse_document(format, &query, &body));
+    let (rows, mut errors) = match parsed {
+        Ok(parsed) => parsed,
+        Err(e) => return e.error_response(),
+    };
+    if rows.is_empty() && errors.is_empty() {
+        return HttpResponse::BadRequest()
+            .json(ApiResponse::<()>::error("VALIDATION_ERROR", "Import contains no rows"));
+    }
+
+    // Rows that failed to parse are already errors: validate the rest, but never commit
+    let dry_run = query.dry_run || !errors.is_empty();
+    let mut report = match app_state.datacenter_repo().import(rows, dry_run).await {
+        Ok(report) => report,
+        Err(e) => {
+            log::error!("Error importing datacenter inventory: {}", e);
+            return HttpResponse::InternalServerError()
+                .json(ApiResponse::<()>::error("DATABASE_ERROR", "Failed to import datacenter inventory"));
+        }
+    };
+    report.dry_run = query.dry_run;
+    errors.append(&mut report.errors);
+    errors.sort_by_key(|error| (error.kind, error.row));
+    report.errors = errors;
+
+    if !report.errors.is_empty() {
+        let mut failed_rows: Vec<_> = report.errors.iter().map(|error| (error.kind, error.row)).collect();
+        failed_rows.dedup();
+        let message = format!("{} row(s) failed validation; nothing was imported", failed_rows.len());
+        let details = serde_json::to_value(&report).unwrap_or_default();
+        return HttpResponse::UnprocessableEntity()
+            .json(ApiResponse::<()>::error_with_details("IMPORT_VALIDATION_ERROR", &message, details));
+    }
+
+    if report.committed {
+        HttpResponse::Created().json(ApiResponse::success(report))
+    } else {
+        HttpResponse::Ok().json(ApiResponse::success(report))
+    }
+}
+
 #[put("/{id}")]
 pub async fn update_datacenter(
     app_state: web::Data<AppState>,
@@ -600,9 +706,21 @@ pub async fn delete_rack(
 #[get("/racks/{rack_id}/positions")]
 pub async fn get_positions_by_rack(
     app_state: web::Data<AppState>,
-    rack_id: web::Path<i64>
+    rack_id: web::Path<i64>,
+    query: web::Query<CommonPaginationQuery>
 ) -> impl Responder {
     let rack_id = rack_id.into_inner() as i32;
+
+    if let Err(e) = QueryParser::validate(&query, &DatacenterRackPosition::SCHEMA) {
+        return e.error_response();
+    }
+
+    if let Some(format) = ExportFormat::from_query(&query) {
+        let mut query = query.into_inner();
+        query.filters.insert("rack_id".to_string(), rack_id.to_string());
+        let rows = app_state.datacenter_repo().stream_positions(query.clone());
+        return export_response(format, &DatacenterRackPosition::SCHEMA, &query, rows);
+    }
     
     match app_state.datacenter_repo().get_positions_by_rack(rack_id).await {
         Ok(positions) => {
@@ -662,21 +780,8 @@ pub async fn create_position(
     let mut position_data = position.into_inner();
     position_data.rack_id = rack_id;
 
-    // Determine u_height for the incoming item:
-    // For SERVER device type, read the server's own u_height; everything else is 1U.
-    let u_height: i32 = if position_data.device_type.as_deref() == Some("SERVER") {
-        if let Some(server_id) = position_data.server_id {
-            match crate::database::DatabaseHelper::get_by_id::<crate::models::Server>(
-                &app_state.pool(),
-                crate::models::Server::TABLE,
-                crate::models::Server::KEY,
-                server_id as i64,
-            ).await {
-                Ok(Some(srv)) => srv.u_height.unwrap_or(1),
-                _ => 1,
-            }
-        } else { 1 }
-    } else { 1 };
+    // A SERVER occupies its own u_height; everything else is 1U
+    let u_height = app_state.datacenter_repo().position_u_height(&position_data).await.unwrap_or(1);
 
     // Validate that the requested U-range does not overlap any existing position
     match app_state.datacenter_repo().check_position_overlap(
@@ -755,12 +860,7 @@ pub async fn update_position(
 
                 // Get the effective u_height from the server if one is linked
                 let effective_u_height: i32 = if let Some(server_id) = existing.server_id {
-                    match crate::database::DatabaseHelper::get_by_id::<crate::models::Server>(
-                        &app_state.pool(),
-                        crate::models::Server::TABLE,
-                        crate::models::Server::KEY,
-                        server_id as i64,
-                    ).await {
+                    match app_state.server_repo().get_by_id(server_id as i64).await {
                         Ok(Some(srv)) => srv.u_height.unwrap_or(1),
                         _ => 1,
                     }
@@ -878,6 +978,7 @@ pub fn configure_datacenter_routes(cfg: &mut web::ServiceConfig) {
             .service(get_datacenter_with_racks)
             .service(get_datacenter_stats)
             .service(create_datacenter)
+            .service(import_inventory)
             .service(update_datacenter)
             .service(delete_datacenter)
             .service(get_racks_by_datacenter)
@@ -0,0 +1,189 @@
+use actix_web::http::header;
+use actix_web::web::Bytes;
+use actix_web::{get, web, HttpRequest, HttpResponse, Responder, ResponseError};
+use futures_util::stream;
+use serde::Deserialize;
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::broadcast;
+
+use crate::api::documentation::*;
+use crate::api::query_parser::QueryError;
+use crate::domain::{DomainEvent, EventEntity, EventType, Replay};
+use crate::state::AppState;
+
+/// Comment line sent when nothing else has been, so proxies keep the connection open
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Reconnect delay suggested to EventSource clients
+const RETRY_MS: u64 = 3000;
+
+/// Documentation for the event feed; also feeds /api/v1/openapi.json.
+/// The scope root serves the stream itself, so there is no index handler.
+pub fn documentation() -> ApiDocumentation {
+    ApiDocumentation::new(
+        "Farm Event Feed API",
+        "v1",
+        "Server-Sent Events stream of inventory, update and power changes",
+        "/api/v1/events",
+    )
+    .with_authentication(bearer_auth_doc())
+    .add_endpoint(
+        EndpointDoc::new("/api/v1/events", HttpMethod::Get,
+            "text/event-stream of changes as they are committed. Each event has an id, an event name equal to its type \
+             and a JSON data line. Reconnect with the Last-Event-ID header (or last_event_id=) to receive what was missed; \
+             if those events are no longer held (or the server restarted), a `resync` event is sent first and the client \
+             should refetch the state it tracks.")
+            .add_query_parameter(ParameterDoc::new("entity", ParameterType::String, "Comma-separated entities: server, vm, k8s_cluster", false))
+            .add_query_parameter(ParameterDoc::new("type", ParameterType::String, "Comma-separated event types", false).with_example("server.created,server.power_action"))
+            .add_query_parameter(ParameterDoc::new("entity_id", ParameterType::Integer, "Only events for this entity ID", false))
+            .add_query_parameter(ParameterDoc::new("last_event_id", ParameterType::Integer, "Resume after this event; the Last-Event-ID header takes precedence", false))
+            .add_example(ExampleDoc::new(
+                "Watch power actions on server 42",
+                "/api/v1/events?type=server.power_action&entity_id=42",
+            ))
+            .add_response_code(ResponseCodeDoc::new(200, "Event stream"))
+            .add_response_code(ResponseCodeDoc::new(400, "Invalid parameters"))
+            .with_response_schema::<DomainEvent>(),
+    )
+}
+
+#[derive(Debug, Deserialize)]
+pub struct EventQuery {
+    pub entity: Option<String>,
+    #[serde(rename = "type")]
+    pub event_type: Option<String>,
+    pub entity_id: Option<i32>,
+    pub last_event_id: Option<u64>,
+}
+
+/// Which events a subscriber asked for; empty lists match everything
+struct EventFilter {
+    entities: Vec<EventEntity>,
+    types: Vec<EventType>,
+    entity_id: Option<i32>,
+}
+
+impl EventFilter {
+    fn from_query(query: &EventQuery) -> Result<Self, QueryError> {
+        Ok(Self {
+            entities: parse_list(query.entity.as_deref(), "entity", EventEntity::parse, EventEntity::NAMES)?,
+            types: parse_list(query.event_type.as_deref(), "type", EventType::parse, EventType::NAMES)?,
+            entity_id: query.entity_id,
+        })
+    }
+
+    fn matches(&self, event: &DomainEvent) -> bool {
+        (self.entities.is_empty() || self.entities.contains(&event.entity))
+            && (self.types.is_empty() || self.types.contains(&event.event_type))
+            && self.entity_id.is_none_or(|id| id == event.entity_id)
+    }
+}
+
+fn parse_list<T>(
+    value: Option<&str>,
+    parameter: &str,
+    parse: fn(&str) -> Option<T>,
+    allowed: &[&str],
+) -> Result<Vec<T>, QueryError> {
+    value.unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(|name| parse(name).ok_or_else(|| {
+            QueryError::new(parameter, format!("Unknown {} '{}'", parameter, name)).with_allowed(allowed)
+        }))
+        .collect()
+}
+
+fn event_frame(event: &DomainEvent) -> Bytes {
+    let data = serde_json::to_string(event).unwrap_or_default();
+    Bytes::from(format!("id: {}\nevent: {}\ndata: {}\n\n", event.id, event.event_type.name(), data))
+}
+
+/// Sent instead of events the subscriber can no longer be given. It has no id, so the
+/// client keeps the last ID it saw.
+fn resync_frame(reason: &str) -> Bytes {
+    Bytes::from(format!("event: resync\ndata: {}\n\n", serde_json::json!({ "reason": reason })))
+}
+
+struct Subscription {
+    pending: VecDeque<Bytes>,
+    receiver: broadcast::Receiver<Arc<DomainEvent>>,
+    filter: EventFilter,
+    heartbeat: tokio::time::Interval,
+}
+
+impl Subscription {
+    /// Next frame to send, or None once the bus is gone
+    async fn next_frame(&mut self) -> Option<Bytes> {
+        if let Some(frame) = self.pending.pop_front() {
+            return Some(frame);
+        }
+        loop {
+            tokio::select! {
+                received = self.receiver.recv() => match received {
+                    Ok(event) if self.filter.matches(&event) => {
+                        self.heartbeat.reset();
+                        return Some(event_frame(&event));
+                    }
+                    Ok(_) => {}
+                    Err(broadcast::error::RecvError::Lagged(_)) => return Some(resync_frame("lagged")),
+                    Err(broadcast::error::RecvError::Closed) => return None,
+                },
+                _ = self.heartbeat.tick() => return Some(Bytes::from_static(b": heartbeat\n\n")),
+            }
+        }
+    }
+}
+
+#[get("")]
+pub async fn stream_events(
+    app_state: web::Data<AppState>,
+    req: HttpRequest,
+    query: web::Query<EventQuery>,
+) -> impl Responder {
+    let filter = match EventFilter::from_query(&query) {
+        Ok(filter) => filter,
+        Err(e) => return e.error_response(),
+    };
+    let last_event_id = match req.headers().get("Last-Event-ID") {
+        Some(value) => match value.to_str().ok().and_then(|id| id.trim().parse().ok()) {
+            Some(id) => Some(id),
+            None => return QueryError::new("Last-Event-ID", "Expected an event ID").error_response(),
+        },
+        None => query.last_event_id,
+    };
+
+    let (replay, receiver) = app_state.events().subscribe(last_event_id);
+    let mut pending = VecDeque::from([Bytes::from(format!("retry: {}\n\n", RETRY_MS))]);
+    match replay {
+        Replay::Events(events) => pending.extend(
+            events.iter().filter(|event| filter.matches(event)).map(|event| event_frame(event))
+        ),
+        Replay::Gap => pending.push_back(resync_frame("history")),
+    }
+
+    let mut heartbeat = tokio::time::interval(HEARTBEAT_INTERVAL);
+    heartbeat.reset();
+    let subscription = Subscription { pending, receiver, filter, heartbeat };
+    let body = stream::unfold(subscription, |mut subscription| async move {
+        let frame = subscription.next_frame().await?;
+        Some((Ok::<_, actix_web::Error>(frame), subscription))
+    });
+
+    HttpResponse::Ok()
+        .content_type("text/event-stream")
+        .insert_header((header::CACHE_CONTROL, "no-cache"))
+        // Stop nginx from buffering the stream
+        .insert_header(("X-Accel-Buffering", "no"))
+        .streaming(body)
+}
+
+pub fn configure_event_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/events")
+            .service(stream_events)
+    );
+}
@@ -0,0 +1,244 @@
+use actix_web::{get, web, HttpResponse, Responder, ResponseError};
+
+use crate::api::documentation::*;
+use crate::api::export::{export_response, ExportFormat};
+use crate::api::query_parser::{CommonPaginationQuery, QueryError, QueryParser};
+use crate::api::responses::{ApiMeta, ApiResponse};
+use crate::models::{ServerIdentityConflict, ServerInventoryChange, ServerMissingComponent};
+use crate::state::AppState;
+
+/// Documentation for the fleet-wide inventory endpoints; also feeds /api/v1/openapi.json.
+/// Per-server history is documented with the server endpoints.
+pub fn documentation() -> ApiDocumentation {
+    ApiDocumentation::new(
+        "Farm Inventory API",
+        "v1",
+        "Hardware change history, missing components and server identity conflicts recorded by the inventory sync",
+        "/api/v1/inventory",
+    )
+    .with_response_format(standard_response_format())
+    .add_endpoint(
+        EndpointDoc::new("/api/v1/inventory/changes", HttpMethod::Get, "Component changes across the fleet, newest first")
+            .add_query_parameter(ParameterDoc::new("page", ParameterType::Integer, "Page number", false).with_default("1"))
+            .add_query_parameter(ParameterDoc::new("per_page", ParameterType::Integer, "Items per page (max 100)", false).with_default("10"))
+            .add_query_parameter(ParameterDoc::new("columns", ParameterType::String, "Comma-separated column list", false))
+            .add_query_parameter(ParameterDoc::new("sort", ParameterType::String, "Comma-separated sort columns, prefixed with - for descending", false).with_example("-detected_at"))
+            .add_query_parameter(ParameterDoc::new("format", ParameterType::String, "csv or ndjson streams every matching row as a download (no page cap); columns, search and filters apply", false))
+            .add_query_parameter(ParameterDoc::new("after", ParameterType::String, "Cursor from meta.pagination.next_cursor; empty for the first page. Pages by primary key, up to 1000 per page", false))
+            .add_query_parameter(ParameterDoc::new("count", ParameterType::Boolean, "Set to false to skip the total count", false).with_default("true"))
+            .add_query_parameter(ParameterDoc::new("search", ParameterType::String,
+                "JSON search criteria; server.server_name, server.serial_number, server.cluster_id and server.data_center_id \
+                 match on the server the component belongs to", false))
+            .add_example(ExampleDoc::new(
+                "When did DIMM B2 on node-17 get swapped?",
+                "/api/v1/inventory/changes?server.server_name=node-17&component_type=memory&component_identifier=B2",
+            ))
+            .add_example(ExampleDoc::new(
+                "Disks removed in the last week",
+                r#"/api/v1/inventory/changes?component_type=disk&change_type=removed&search=[{"column":"detected_at","comparisonOperator":">=","term":"now-7d"}]"#,
+            ))
+            .add_response_code(ResponseCodeDoc::new(200, "Success"))
+            .add_response_code(ResponseCodeDoc::new(400, "Invalid parameters"))
+            .with_response_schema::<Vec<ServerInventoryChange>>(),
+    )
+    .add_endpoint(
+        EndpointDoc::new("/api/v1/inventory/missing", HttpMethod::Get,
+            "Components that disappeared from a server's inventory and have not been reported since, newest first")
+            .add_query_parameter(ParameterDoc::new("include_resolved", ParameterType::Boolean,
+                "Also list entries resolved because the part's serial, slot, PCI address, UUID or MAC was reported again", false).with_default("false"))
+            .add_query_parameter(ParameterDoc::new("page", ParameterType::Integer, "Page number", false).with_default("1"))
+            .add_query_parameter(ParameterDoc::new("per_page", ParameterType::Integer, "Items per page (max 100)", false).with_default("10"))
+            .add_query_parameter(ParameterDoc::new("columns", ParameterType::String, "Comma-separated column list", false))
+            .add_query_parameter(ParameterDoc::new("sort", ParameterType::String, "Comma-separated sort columns, prefixed with - for descending", false).with_example("missing_since"))
+            .add_query_parameter(ParameterDoc::new("format", ParameterType::String, "csv or ndjson streams every matching row as a download (no page cap); columns, search and filters apply", false))
+            .add_query_parameter(ParameterDoc::new("after", ParameterType::String, "Cursor from meta.pagination.next_cursor; empty for the first page. Pages by primary key, up to 1000 per page", false))
+            .add_query_parameter(ParameterDoc::new("count", ParameterType::Boolean, "Set to false to skip the total count", false).with_default("true"))
+            .add_query_parameter(ParameterDoc::new("search", ParameterType::String,
+                "JSON search criteria; the server.* columns of /api/v1/inventory/changes apply", false))
+            .add_example(ExampleDoc::new("GPUs missing anywhere in the fleet", "/api/v1/inventory/missing?component_type=gpu"))
+            .add_example(ExampleDoc::new("Missing parts in cluster 3", "/api/v1/inventory/missing?server.cluster_id=3"))
+            .add_response_code(ResponseCodeDoc::new(200, "Success"))
+            .add_response_code(ResponseCodeDoc::new(400, "Invalid parameters"))
+            .with_response_schema::<Vec<ServerMissingComponent>>(),
+    )
+    .add_endpoint(
+        EndpointDoc::new("/api/v1/inventory/identity-conflicts", HttpMethod::Get,
+            "Inventory reports that matched several servers, or matched one against a contradicting serial, BMC MAC or \
+             system UUID, newest first. REJECTED reports were refused with 409; MATCHED and CREATED ones were applied.")
+            .add_query_parameter(ParameterDoc::new("include_resolved", ParameterType::Boolean,
+                "Also list conflicts that have been resolved", false).with_default("false"))
+            .add_query_parameter(ParameterDoc::new("page", ParameterType::Integer, "Page number", false).with_default("1"))
+            .add_query_parameter(ParameterDoc::new("per_page", ParameterType::Integer, "Items per page (max 100)", false).with_default("10"))
+            .add_query_parameter(ParameterDoc::new("columns", ParameterType::String, "Comma-separated column list", false))
+            .add_query_parameter(ParameterDoc::new("sort", ParameterType::String, "Comma-separated sort columns, prefixed with - for descending", false).with_example("-confidence"))
+            .add_query_parameter(ParameterDoc::new("format", ParameterType::String, "csv or ndjson streams every matching row as a download (no page cap); columns, search and filters apply", false))
+            .add_query_parameter(ParameterDoc::new("after", ParameterType::String, "Cursor from meta.pagination.next_cursor; empty for the first page. Pages by primary key, up to 1000 per page", false))
+            .add_query_parameter(ParameterDoc::new("count", ParameterType::Boolean, "Set to false to skip the total count", false).with_default("true"))
+            .add_query_parameter(ParameterDoc::new("search", ParameterType::String,
+                "JSON search criteria; the server.* columns of /api/v1/inventory/changes apply", false))
+            .add_example(ExampleDoc::new("Reports currently refused", "/api/v1/inventory/identity-conflicts?resolution=REJECTED"))
+            .add_example(ExampleDoc::new("Conflicts reported by node-17", "/api/v1/inventory/identity-conflicts?hostname=node-17&include_resolved=true"))
+            .add_response_code(ResponseCodeDoc::new(200, "Success"))
+            .add_response_code(ResponseCodeDoc::new(400, "Invalid parameters"))
+            .with_response_schema::<Vec<ServerIdentityConflict>>(),
+    )
+}
+
+/// Take the include_resolved flag out of the query before the remaining parameters are parsed
+/// as filters
+fn take_include_resolved(query: &mut CommonPaginationQuery) -> Result<bool, HttpResponse> {
+    match query.filters.remove("include_resolved").as_deref() {
+        None | Some("false") => Ok(false),
+        Some("true") => Ok(true),
+        Some(other) => {
+            let message = format!("include_resolved must be true or false, got '{}'", other);
+            Err(QueryError::new("include_resolved", message).error_response())
+        }
+    }
+}
+
+/// Page of component changes, or the export stream the query asks for. Shared by the
+/// fleet feed and /servers/{id}/changes, which pins server_id.
+pub(crate) async fn list_changes(app_state: &AppState, query: CommonPaginationQuery) -> HttpResponse {
+    if let Err(e) = QueryParser::validate(&query, &ServerInventoryChange::SCHEMA) {
+        return e.error_response();
+    }
+
+    if let Some(format) = ExportFormat::from_query(&query) {
+        let rows = app_state.inventory_change_repo().stream_changes(query.clone());
+        return export_response(format, &ServerInventoryChange::SCHEMA, &query, rows);
+    }
+
+    match app_state.inventory_change_repo().get_all_changes(query.clone()).await {
+        Ok((changes, total_count)) => {
+            let last_key = changes.last().map(|change| change.change_id as i64);
+            let meta = ApiMeta {
+                pagination: Some(QueryParser::pagination_meta(&query, &ServerInventoryChange::SCHEMA, changes.len(), total_count, last_key)),
+                filters_applied: None,
+                request_id: None,
+                timestamp: chrono::Utc::now(),
+            };
+            HttpResponse::Ok().json(ApiResponse::success_with_meta(changes, meta))
+        }
+        Err(e) => {
+            log::error!("Database error fetching inventory changes: {}", e);
+            HttpResponse::InternalServerError()
+                .json(ApiResponse::<()>::error("DATABASE_ERROR", "Failed to fetch inventory changes"))
+        }
+    }
+}
+
+/// Page of missing components, or the export stream the query asks for. Unresolved entries
+/// only unless the query sets include_resolved. Shared by the fleet list and
+/// /servers/{id}/missing, which pins server_id.
+pub(crate) async fn list_missing(app_state: &AppState, mut query: CommonPaginationQuery) -> HttpResponse {
+    let include_resolved = match take_include_resolved(&mut query) {
+        Ok(include_resolved) => include_resolved,
+        Err(response) => return response,
+    };
+
+    if let Err(e) = QueryParser::validate(&query, &ServerMissingComponent::SCHEMA) {
+        return e.error_response();
+    }
+
+    if let Some(format) = ExportFormat::from_query(&query) {
+        let rows = app_state.missing_component_repo().stream_missing_components(query.clone(), include_resolved);
+        return export_response(format, &ServerMissingComponent::SCHEMA, &query, rows);
+    }
+
+    match app_state.missing_component_repo().get_missing_components(query.clone(), include_resolved).await {
+        Ok((missing, total_count)) => {
+            let last_key = missing.last().map(|entry| entry.missing_id as i64);
+            let meta = ApiMeta {
+                pagination: Some(QueryParser::pagination_meta(&query, &ServerMissingComponent::SCHEMA, missing.len(), total_count, last_key)),
+                filters_applied: None,
+                request_id: None,
+                timestamp: chrono::Utc::now(),
+            };
+            HttpResponse::Ok().json(ApiResponse::success_with_meta(missing, meta))
+        }
+        Err(e) => {
+            log::error!("Database error fetching missing components: {}", e);
+            HttpResponse::InternalServerError()
+                .json(ApiResponse::<()>::error("DATABASE_ERROR", "Failed to fetch missing components"))
+        }
+    }
+}
+
+/// Page of identity conflicts, or the export stream the query asks for. Open conflicts only
+/// unless the query sets include_resolved. Shared by the fleet list and
+/// /servers/{id}/identity-conflicts, which pins server_id.
+pub(crate) async fn list_identity_conflicts(app_state: &AppState, mut query: CommonPaginationQuery) -> HttpResponse {
+    let include_resolved = match take_include_resolved(&mut query) {
+        Ok(include_resolved) => include_resolved,
+        Err(response) => return response,
+    };
+
+    if let Err(e) = QueryParser::validate(&query, &ServerIdentityConflict::SCHEMA) {
+        return e.error_response();
+    }
+
+    if let Some(format) = ExportFormat::from_query(&query) {
+        let rows = app_state.identity_conflict_repo().stream_identity_conflicts(query.clone(), include_resolved);
+        return export_response(format, &ServerIdentityConflict::SCHEMA, &query, rows);
+    }
+
+    match app_state.identity_conflict_repo().get_identity_conflicts(query.clone(), include_resolved).await {
+        Ok((conflicts, total_count)) => {
+            let last_key = conflicts.last().map(|conflict| conflict.conflict_id as i64);
+            let meta = ApiMeta {
+                pagination: Some(QueryParser::pagination_meta(&query, &ServerIdentityConflict::SCHEMA, conflicts.len(), total_count, last_key)),
+                filters_applied: None,
+                request_id: None,
+                timestamp: chrono::Utc::now(),
+            };
+            HttpResponse::Ok().json(ApiResponse::success_with_meta(conflicts, meta))
+        }
+        Err(e) => {
+            log::error!("Database error fetching identity conflicts: {}", e);
+            HttpResponse::InternalServerError()
+                .json(ApiResponse::<()>::error("DATABASE_ERROR", "Failed to fetch identity conflicts"))
+        }
+    }
+}
+
+// ===================================================================
+// CHANGE HISTORY ENDPOINTS
+// ===================================================================
+
+#[get("/changes")]
+pub async fn get_inventory_changes(
+    app_state: web::Data<AppState>,
+    query: web::Query<CommonPaginationQuery>,
+) -> impl Responder {
+    list_changes(&app_state, query.into_inner()).await
+}
+
+#[get("/missing")]
+pub async fn get_missing_components(
+    app_state: web::Data<AppState>,
+    query: web::Query<CommonPaginationQuery>,
+) -> impl Responder {
+    list_missing(&app_state, query.into_inner()).await
+}
+
+#[get("/identity-conflicts")]
+pub async fn get_identity_conflicts(
+    app_state: web::Data<AppState>,
+    query: web::Query<CommonPaginationQuery>,
+) -> impl Responder {
+    list_identity_conflicts(&app_state, query.into_inner()).await
+}
+
+// ===================================================================
+// ROUTE CONFIGURATION
+// ===================================================================
+
+pub fn configure_inventory_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/inventory")
+            .service(get_inventory_changes)
+            .service(get_missing_components)
+            .service(get_identity_conflicts),
+    );
+}
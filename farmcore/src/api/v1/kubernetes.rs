@@ -1,14 +1,23 @@
-use actix_web::{get, post, put, web, HttpResponse, Responder};
+use actix_web::{get, post, put, web, HttpResponse, Responder, ResponseError};
 use std::collections::HashMap;
 
-use crate::api::responses::ApiResponse;
+use crate::api::responses::{ApiMeta, ApiResponse};
 use crate::api::documentation::*;
-use crate::api::query_parser::CommonPaginationQuery;
+use crate::api::export::{export_response, ExportFormat};
+use crate::api::query_parser::{CommonPaginationQuery, QueryParser};
+use crate::api::v1::decode_agent_payload;
 use crate::state::AppState;
-
-#[get("")]
-pub async fn index() -> impl Responder {
-    let documentation = ApiDocumentation::new(
+use crate::domain::NewEvent;
+use crate::metrics;
+use crate::models::{
+    KubernetesCluster, KubernetesEvent, KubernetesNamespace, KubernetesNode, KubernetesPod,
+    KubernetesService, KubernetesWorkload, NamespaceWithResources, NodeWithMetrics,
+};
+use crate::repositories::kubernetes_repository::K8sInventory;
+
+/// Documentation for the Kubernetes endpoints; also feeds /api/v1/openapi.json
+pub fn documentation() -> ApiDocumentation {
+    ApiDocumentation::new(
         "Farm Kubernetes API",
         "v1",
         "Kubernetes cluster and resource management endpoints",
@@ -21,17 +30,21 @@ pub async fn index() -> impl Responder {
             .add_query_parameter(ParameterDoc::new("page", ParameterType::Integer, "Page number", false).with_default("1"))
             .add_query_parameter(ParameterDoc::new("per_page", ParameterType::Integer, "Items per page", false).with_default("10"))
             .add_query_parameter(ParameterDoc::new("columns", ParameterType::String, "Comma-separated column list", false))
+            .add_query_parameter(ParameterDoc::new("sort", ParameterType::String, "Comma-separated sort columns, prefixed with - for descending", false).with_example("-created_at"))
+            .add_query_parameter(ParameterDoc::new("format", ParameterType::String, "csv or ndjson streams every matching row as a download (no page cap); columns, search and filters apply", false))
             .add_example(ExampleDoc::new("Get production clusters", "/api/v1/k8s/clusters?environment=production"))
             .add_example(ExampleDoc::new("Search k3s clusters", "/api/v1/k8s/clusters?distribution=k3s"))
             .add_response_code(ResponseCodeDoc::new(200, "Success"))
             .add_response_code(ResponseCodeDoc::new(400, "Invalid parameters"))
+            .with_response_schema::<Vec<KubernetesCluster>>()
     )
     .add_endpoint(
         EndpointDoc::new("/api/v1/k8s/clusters/{id}", HttpMethod::Get, "Get specific cluster by ID with complete details")
             .add_path_parameter(ParameterDoc::new("id", ParameterType::Integer, "Cluster ID", true))
             .add_example(ExampleDoc::new("Get cluster 1", "/api/v1/k8s/clusters/1"))
-            .add_response_code(ResponseCodeDoc::new(200, "Success - Returns cluster with nodes, node groups, and namespaces"))
+            .add_response_code(ResponseCodeDoc::new(200, "Success"))
             .add_response_code(ResponseCodeDoc::new(404, "Cluster not found"))
+            .with_response_schema::<KubernetesCluster>()
     )
     .add_endpoint(
         EndpointDoc::new("/api/v1/k8s/clusters/{id}/overview", HttpMethod::Get, "Get cluster overview statistics")
@@ -47,6 +60,7 @@ pub async fn index() -> impl Responder {
             .add_example(ExampleDoc::new("Get all nodes", "/api/v1/k8s/clusters/1/nodes"))
             .add_example(ExampleDoc::new("Get ready nodes", "/api/v1/k8s/clusters/1/nodes?state=ready"))
             .add_response_code(ResponseCodeDoc::new(200, "Success"))
+            .with_response_schema::<Vec<KubernetesNode>>()
     )
     .add_endpoint(
         EndpointDoc::new("/api/v1/k8s/nodes/{id}", HttpMethod::Get, "Get specific node with metrics and pods")
@@ -54,12 +68,22 @@ pub async fn index() -> impl Responder {
             .add_example(ExampleDoc::new("Get node details", "/api/v1/k8s/nodes/1"))
             .add_response_code(ResponseCodeDoc::new(200, "Success"))
             .add_response_code(ResponseCodeDoc::new(404, "Node not found"))
+            .with_response_schema::<NodeWithMetrics>()
+    )
+    .add_endpoint(
+        EndpointDoc::new("/api/v1/k8s/nodes/{id}", HttpMethod::Put, "Update node fields")
+            .add_path_parameter(ParameterDoc::new("id", ParameterType::Integer, "Node ID", true))
+            .add_response_code(ResponseCodeDoc::new(200, "Success - Node updated"))
+            .add_response_code(ResponseCodeDoc::new(400, "No fields provided"))
+            .add_response_code(ResponseCodeDoc::new(404, "Node not found"))
+            .with_request_schema::<HashMap<String, serde_json::Value>>()
     )
     .add_endpoint(
         EndpointDoc::new("/api/v1/k8s/clusters/{id}/namespaces", HttpMethod::Get, "Get all namespaces for a cluster")
             .add_path_parameter(ParameterDoc::new("id", ParameterType::Integer, "Cluster ID", true))
             .add_example(ExampleDoc::new("Get namespaces", "/api/v1/k8s/clusters/1/namespaces"))
             .add_response_code(ResponseCodeDoc::new(200, "Success"))
+            .with_response_schema::<Vec<KubernetesNamespace>>()
     )
     .add_endpoint(
         EndpointDoc::new("/api/v1/k8s/namespaces/{id}", HttpMethod::Get, "Get namespace with all resources")
@@ -67,6 +91,7 @@ pub async fn index() -> impl Responder {
             .add_example(ExampleDoc::new("Get namespace details", "/api/v1/k8s/namespaces/1"))
             .add_response_code(ResponseCodeDoc::new(200, "Success - Returns namespace with workloads, services, ingresses, and pods"))
             .add_response_code(ResponseCodeDoc::new(404, "Namespace not found"))
+            .with_response_schema::<NamespaceWithResources>()
     )
     .add_endpoint(
         EndpointDoc::new("/api/v1/k8s/clusters/{id}/workloads", HttpMethod::Get, "Get all workloads for a cluster")
@@ -76,6 +101,14 @@ pub async fn index() -> impl Responder {
             .add_example(ExampleDoc::new("Get all workloads", "/api/v1/k8s/clusters/1/workloads"))
             .add_example(ExampleDoc::new("Get deployments", "/api/v1/k8s/clusters/1/workloads?type=deployment"))
             .add_response_code(ResponseCodeDoc::new(200, "Success"))
+            .with_response_schema::<Vec<KubernetesWorkload>>()
+    )
+    .add_endpoint(
+        EndpointDoc::new("/api/v1/k8s/workloads/{id}", HttpMethod::Get, "Get specific workload by ID")
+            .add_path_parameter(ParameterDoc::new("id", ParameterType::Integer, "Workload ID", true))
+            .add_response_code(ResponseCodeDoc::new(200, "Success"))
+            .add_response_code(ResponseCodeDoc::new(404, "Workload not found"))
+            .with_response_schema::<KubernetesWorkload>()
     )
     .add_endpoint(
         EndpointDoc::new("/api/v1/k8s/clusters/{id}/pods", HttpMethod::Get, "Get all pods for a cluster")
@@ -83,6 +116,30 @@ pub async fn index() -> impl Responder {
             .add_query_parameter(ParameterDoc::new("namespace_id", ParameterType::Integer, "Filter by namespace", false))
             .add_example(ExampleDoc::new("Get all pods", "/api/v1/k8s/clusters/1/pods"))
             .add_response_code(ResponseCodeDoc::new(200, "Success"))
+            .with_response_schema::<Vec<KubernetesPod>>()
+    )
+    .add_endpoint(
+        EndpointDoc::new("/api/v1/k8s/pods", HttpMethod::Get,
+            "List pods across all clusters with pagination and filtering; use after= to walk every pod with a cursor")
+            .add_query_parameter(ParameterDoc::new("page", ParameterType::Integer, "Page number", false).with_default("1"))
+            .add_query_parameter(ParameterDoc::new("per_page", ParameterType::Integer, "Items per page (up to 1000 with after)", false).with_default("10"))
+            .add_query_parameter(ParameterDoc::new("columns", ParameterType::String, "Comma-separated column list", false))
+            .add_query_parameter(ParameterDoc::new("sort", ParameterType::String, "Comma-separated sort columns, prefixed with - for descending", false).with_example("pod_name"))
+            .add_query_parameter(ParameterDoc::new("format", ParameterType::String, "csv or ndjson streams every matching row as a download (no page cap); columns, search and filters apply", false))
+            .add_query_parameter(ParameterDoc::new("after", ParameterType::String, "Cursor from meta.pagination.next_cursor; empty for the first page", false))
+            .add_query_parameter(ParameterDoc::new("count", ParameterType::Boolean, "Set to false to skip the total count", false).with_default("true"))
+            .add_example(ExampleDoc::new("Running pods in cluster 1", "/api/v1/k8s/pods?cluster_id=1&pod_phase=running"))
+            .add_example(ExampleDoc::new("Walk every pod", "/api/v1/k8s/pods?after=&per_page=1000&count=false"))
+            .add_response_code(ResponseCodeDoc::new(200, "Success"))
+            .add_response_code(ResponseCodeDoc::new(400, "Invalid parameters"))
+            .with_response_schema::<Vec<KubernetesPod>>()
+    )
+    .add_endpoint(
+        EndpointDoc::new("/api/v1/k8s/pods/{id}", HttpMethod::Get, "Get specific pod by ID")
+            .add_path_parameter(ParameterDoc::new("id", ParameterType::Integer, "Pod ID", true))
+            .add_response_code(ResponseCodeDoc::new(200, "Success"))
+            .add_response_code(ResponseCodeDoc::new(404, "Pod not found"))
+            .with_response_schema::<KubernetesPod>()
     )
     .add_endpoint(
         EndpointDoc::new("/api/v1/k8s/clusters/{id}/services", HttpMethod::Get, "Get all services for a cluster")
@@ -90,6 +147,14 @@ pub async fn index() -> impl Responder {
             .add_query_parameter(ParameterDoc::new("namespace_id", ParameterType::Integer, "Filter by namespace", false))
             .add_example(ExampleDoc::new("Get all services", "/api/v1/k8s/clusters/1/services"))
             .add_response_code(ResponseCodeDoc::new(200, "Success"))
+            .with_response_schema::<Vec<KubernetesService>>()
+    )
+    .add_endpoint(
+        EndpointDoc::new("/api/v1/k8s/services/{id}", HttpMethod::Get, "Get specific service by ID")
+            .add_path_parameter(ParameterDoc::new("id", ParameterType::Integer, "Service ID", true))
+            .add_response_code(ResponseCodeDoc::new(200, "Success"))
+            .add_response_code(ResponseCodeDoc::new(404, "Service not found"))
+            .with_response_schema::<KubernetesService>()
     )
     .add_endpoint(
         EndpointDoc::new("/api/v1/k8s/clusters/{id}/events", HttpMethod::Get, "Get recent events for a cluster")
@@ -97,6 +162,7 @@ pub async fn index() -> impl Responder {
             .add_query_parameter(ParameterDoc::new("limit", ParameterType::Integer, "Number of events to return", false).with_default("50"))
             .add_example(ExampleDoc::new("Get recent events", "/api/v1/k8s/clusters/1/events?limit=100"))
             .add_response_code(ResponseCodeDoc::new(200, "Success"))
+            .with_response_schema::<Vec<KubernetesEvent>>()
     )
     .add_endpoint(
         EndpointDoc::new("/api/v1/k8s/clusters/{id}", HttpMethod::Put, "Update cluster fields")
@@ -104,16 +170,21 @@ pub async fn index() -> impl Responder {
             .add_response_code(ResponseCodeDoc::new(200, "Success - Cluster updated"))
             .add_response_code(ResponseCodeDoc::new(400, "Invalid field or value"))
             .add_response_code(ResponseCodeDoc::new(404, "Cluster not found"))
+            .with_request_schema::<HashMap<String, serde_json::Value>>()
     )
     .add_endpoint(
         EndpointDoc::new("/api/v1/k8s/inventory", HttpMethod::Post, "Create or update Kubernetes cluster from inventory data")
             .add_example(ExampleDoc::new("Post K8s inventory", "/api/v1/k8s/inventory"))
             .add_response_code(ResponseCodeDoc::new(200, "Success - Cluster and nodes created or updated"))
-            .add_response_code(ResponseCodeDoc::new(400, "Invalid inventory data"))
+            .add_response_code(ResponseCodeDoc::new(400, "Invalid inventory data or unsupported schema_version"))
             .add_response_code(ResponseCodeDoc::new(500, "Database error"))
-    );
+            .with_request_schema::<K8sInventory>()
+    )
+}
 
-    let response = ApiResponse::success(documentation);
+#[get("")]
+pub async fn index() -> impl Responder {
+    let response = ApiResponse::success(documentation());
     HttpResponse::Ok().json(response)
 }
 
@@ -126,9 +197,26 @@ pub async fn get_all_clusters(
     app_state: web::Data<AppState>, 
     query: web::Query<CommonPaginationQuery>
 ) -> impl Responder {
-    match app_state.k8s_repo().get_all_clusters(query.into_inner()).await {
-        Ok(clusters) => {
-            let response = ApiResponse::success(clusters);
+    if let Err(e) = QueryParser::validate(&query, &KubernetesCluster::SCHEMA) {
+        return e.error_response();
+    }
+
+    if let Some(format) = ExportFormat::from_query(&query) {
+        let rows = app_state.k8s_repo().stream_clusters(query.clone().into_inner());
+        return export_response(format, &KubernetesCluster::SCHEMA, &query, rows);
+    }
+
+    let query = query.into_inner();
+    match app_state.k8s_repo().get_all_clusters(query.clone()).await {
+        Ok((clusters, total_count)) => {
+            let last_key = clusters.last().map(|cluster| cluster.cluster_id as i64);
+            let meta = ApiMeta {
+                pagination: Some(QueryParser::pagination_meta(&query, &KubernetesCluster::SCHEMA, clusters.len(), total_count, last_key)),
+                filters_applied: None,
+                request_id: None,
+                timestamp: chrono::Utc::now(),
+            };
+            let response = ApiResponse::success_with_meta(clusters, meta);
             HttpResponse::Ok().json(response)
         },
         Err(e) => {
@@ -529,6 +617,45 @@ pub async fn get_cluster_pods(
     }
 }
 
+#[get("/pods")]
+pub async fn get_pods(
+    app_state: web::Data<AppState>,
+    query: web::Query<CommonPaginationQuery>
+) -> impl Responder {
+    if let Err(e) = QueryParser::validate(&query, &KubernetesPod::SCHEMA) {
+        return e.error_response();
+    }
+
+    if let Some(format) = ExportFormat::from_query(&query) {
+        let rows = app_state.k8s_repo().stream_pods(query.clone().into_inner());
+        return export_response(format, &KubernetesPod::SCHEMA, &query, rows);
+    }
+
+    let query = query.into_inner();
+    match app_state.k8s_repo().get_pods(query.clone()).await {
+        Ok((pods, total_count)) => {
+            let last_key = pods.last().map(|pod| pod.pod_id as i64);
+            let meta = ApiMeta {
+                pagination: Some(QueryParser::pagination_meta(&query, &KubernetesPod::SCHEMA, pods.len(), total_count, last_key)),
+                filters_applied: None,
+                request_id: None,
+                timestamp: chrono::Utc::now(),
+            };
+            let response = ApiResponse::success_with_meta(pods, meta);
+            HttpResponse::Ok().json(response)
+        },
+        Err(e) => {
+            log::error!("Database error fetching pods: {}", e);
+
+            let response = ApiResponse::<()>::error(
+                "DATABASE_ERROR",
+                "Failed to fetch pods"
+            );
+            HttpResponse::InternalServerError().json(response)
+        }
+    }
+}
+
 #[get("/pods/{id}")]
 pub async fn get_pod_by_id(
     app_state: web::Data<AppState>,
@@ -667,13 +794,22 @@ pub async fn get_cluster_events(
 #[post("/inventory")]
 pub async fn upsert_k8s_inventory(
     app_state: web::Data<AppState>,
-    inventory: web::Json<crate::repositories::kubernetes_repository::K8sInventory>
+    payload: web::Json<serde_json::Value>
 ) -> impl Responder {
+    let inventory: K8sInventory = match decode_agent_payload(payload.into_inner()) {
+        Ok(inventory) => inventory,
+        Err(response) => return response,
+    };
     log::info!("Received Kubernetes inventory for cluster: {}, Nodes count: {}", 
         inventory.cluster_name, inventory.nodes.len());
     
-    match app_state.k8s_repo().upsert_cluster_from_inventory(inventory.into_inner()).await {
+    let cluster_name = inventory.cluster_name.clone();
+    let node_count = inventory.nodes.len();
+    let result = app_state.k8s_repo().upsert_cluster_from_inventory(inventory).await;
+    metrics::observe_inventory_ingest("k8s", result.is_ok());
+    match result {
         Ok(cluster_id) => {
+            app_state.publish(NewEvent::k8s_cluster_inventory(cluster_id, &cluster_name, node_count)).await;
             log::info!("Kubernetes inventory processed successfully for cluster_id: {}", cluster_id);
             
             let response = ApiResponse::success(serde_json::json!({
@@ -725,6 +861,7 @@ pub fn configure_k8s_routes(cfg: &mut web::ServiceConfig) {
             // Workload routes
             .service(get_workload_by_id)
             // Pod routes
+            .service(get_pods)
             .service(get_pod_by_id)
             // Service routes
             .service(get_service_by_id)
@@ -4,20 +4,16 @@ use crate::api::responses::ApiResponse;
 use crate::api::documentation::*;
 use crate::database::schema::migrations;
 
-#[get("")]
-pub async fn migrations_index() -> impl Responder {
-    let documentation = ApiDocumentation::new(
+/// Documentation for the migration endpoints; also feeds /api/v1/openapi.json
+pub fn documentation() -> ApiDocumentation {
+    ApiDocumentation::new(
         "Database Migration Management API",
         "1.0.0",
         "Endpoints for managing database schema migrations",
         "/api/v1/migrations"
     )
     .with_response_format(standard_response_format())
-    .with_authentication(AuthDoc {
-        type_name: "None".to_string(),
-        description: "No authentication required (development mode)".to_string(),
-        header_name: None,
-    })
+    .with_authentication(bearer_auth_doc())
     .with_rate_limiting(RateLimitDoc {
         requests_per_minute: Some(10),
         requests_per_hour: Some(100),
@@ -38,6 +34,7 @@ pub async fn migrations_index() -> impl Responder {
                 }
             })))
             .add_response_code(ResponseCodeDoc::new(200, "Migrations executed successfully"))
+            .add_response_code(ResponseCodeDoc::new(403, "Requires the ADMIN role"))
             .add_response_code(ResponseCodeDoc::new(500, "Migration execution failed")),
 
         // Migration status endpoint
@@ -57,6 +54,7 @@ pub async fn migrations_index() -> impl Responder {
                 }
             })))
             .add_response_code(ResponseCodeDoc::new(200, "Migration tracking reset successfully"))
+            .add_response_code(ResponseCodeDoc::new(403, "Requires the ADMIN role"))
             .add_response_code(ResponseCodeDoc::new(500, "Migration reset failed")),
 
         // Seed data endpoint
@@ -73,16 +71,21 @@ pub async fn migrations_index() -> impl Responder {
                 }
             })))
             .add_response_code(ResponseCodeDoc::new(200, "Seed data applied successfully"))
-            .add_response_code(ResponseCodeDoc::new(500, "Seed data failed")),
-    ]);
+            .add_response_code(ResponseCodeDoc::new(403, "Requires the ADMIN role"))
+            .add_response_code(ResponseCodeDoc::new(500, "Seed data failed"))
+            .add_response_code(ResponseCodeDoc::new(501, "The server runs on SQLite; the seed data is MySQL-only")),
+    ])
+}
 
-    let response = ApiResponse::success(documentation);
+#[get("")]
+pub async fn migrations_index() -> impl Responder {
+    let response = ApiResponse::success(documentation());
     HttpResponse::Ok().json(response)
 }
 
 #[post("/run")]
 pub async fn run_migrations(app_state: web::Data<AppState>) -> impl Responder {
-    match migrations::run_all(app_state.pool()).await {
+    match migrations::run_all(app_state.storage()).await {
         Ok(message) => {
             let response = ApiResponse::success(serde_json::json!({
                 "message": message,
@@ -105,7 +108,7 @@ pub async fn run_migrations(app_state: web::Data<AppState>) -> impl Responder {
 
 #[post("/reset")]
 pub async fn reset_all_migrations(app_state: web::Data<AppState>) -> impl Responder {
-    match migrations::reset_all_migrations(app_state.pool()).await {
+    match migrations::reset_all_migrations(app_state.storage()).await {
         Ok(message) => {
             let response = ApiResponse::success(serde_json::json!({
                 "message": message,
@@ -127,8 +130,16 @@ pub async fn reset_all_migrations(app_state: web::Data<AppState>) -> impl Respon
 #[post("/seed")]
 pub async fn run_seed_data(app_state: web::Data<AppState>) -> impl Responder {
     use crate::database::schema::seeds;
-    
-    match seeds::run_development_seeds(app_state.pool()).await {
+
+    let pool = match app_state.pool() {
+        Ok(pool) => pool,
+        Err(e) => {
+            let response = ApiResponse::<()>::error("NOT_SUPPORTED", &format!("Seed data is written for MySQL: {}", e));
+            return HttpResponse::NotImplemented().json(response);
+        }
+    };
+
+    match seeds::run_development_seeds(pool).await {
         Ok(_) => {
             let response = ApiResponse::success(serde_json::json!({
                 "message": "Development seed data applied successfully",
@@ -7,20 +7,76 @@ pub mod kubernetes;
 pub mod datacenters;
 pub mod clusters;
 pub mod switches;
+pub mod topology;
+pub mod tokens;
+pub mod audit;
+pub mod events;
+pub mod webhooks;
+pub mod inventory;
 
-use actix_web::web;
+use actix_web::{middleware::from_fn, web, HttpResponse};
+
+use crate::api::audit::record_audit_event;
+use crate::api::documentation::ApiDocumentation;
+use crate::api::auth::require_bearer_token;
+use crate::api::responses::ApiResponse;
+
+/// Documentation of every v1 module, paired with the path its documentation index is
+/// served at (None when the module has no index). Keep in sync with `configure_v1_routes`.
+pub fn module_documentation() -> Vec<(Option<&'static str>, ApiDocumentation)> {
+    vec![
+        (Some("/api/v1"), root::documentation()),
+        (Some("/api/v1/servers"), servers::documentation()),
+        (Some("/api/v1/components"), components::documentation()),
+        (Some("/api/v1/migrations"), migrations::documentation()),
+        (Some("/api/v1/vms"), vms::documentation()),
+        (Some("/api/v1/k8s"), kubernetes::documentation()),
+        (Some("/api/v1/datacenters"), datacenters::documentation()),
+        (Some("/api/v1/clusters"), clusters::documentation()),
+        (Some("/api/v1/switches"), switches::documentation()),
+        (Some("/api/v1/topology"), topology::documentation()),
+        (Some("/api/v1/tokens"), tokens::documentation()),
+        (None, audit::documentation()),
+        (None, events::documentation()),
+        (Some("/api/v1/webhooks"), webhooks::documentation()),
+        (None, inventory::documentation()),
+    ]
+}
+
+/// Decode an inventory payload posted by the agent, upgrading it from any schema version this
+/// server still accepts. Payloads that cannot be decoded get a 400 naming the accepted versions.
+pub(crate) fn decode_agent_payload<T: farm_proto::Payload>(payload: serde_json::Value) -> Result<T, HttpResponse> {
+    farm_proto::decode(payload).map_err(|e| {
+        log::warn!("Rejected agent payload: {}", e);
+        let details = serde_json::json!({
+            "min_schema_version": farm_proto::MIN_SCHEMA_VERSION,
+            "schema_version": farm_proto::SCHEMA_VERSION,
+        });
+        let response = ApiResponse::<()>::error_with_details("VALIDATION_ERROR", &e.to_string(), details);
+        HttpResponse::BadRequest().json(response)
+    })
+}
 
 pub fn configure_v1_routes(cfg: &mut web::ServiceConfig) {
-    cfg.service(
-        web::scope("/v1")
-            .configure(root::configure_health_routes)
-            .configure(servers::configure_server_routes)
-            .configure(components::configure_component_routes)
-            .configure(migrations::configure_migration_routes)
-            .configure(vms::configure_vm_routes)
-            .configure(kubernetes::configure_k8s_routes)
-            .configure(datacenters::configure_datacenter_routes)
-            .configure(clusters::configure_cluster_routes)
-            .configure(switches::configure_switch_routes)
-    );
+    let scope = web::scope("/v1")
+        // Wrap order matters: authentication runs first so the audit layer sees the principal
+        .wrap(from_fn(record_audit_event))
+        .wrap(from_fn(require_bearer_token))
+        .configure(root::configure_health_routes)
+        .configure(servers::configure_server_routes)
+        .configure(components::configure_component_routes)
+        .configure(migrations::configure_migration_routes)
+        .configure(vms::configure_vm_routes)
+        .configure(kubernetes::configure_k8s_routes)
+        .configure(datacenters::configure_datacenter_routes)
+        .configure(clusters::configure_cluster_routes)
+        .configure(switches::configure_switch_routes)
+        .configure(topology::configure_topology_routes)
+        .configure(tokens::configure_token_routes)
+        .configure(audit::configure_audit_routes)
+        .configure(events::configure_event_routes)
+        .configure(webhooks::configure_webhook_routes)
+        .configure(inventory::configure_inventory_routes);
+
+    cfg.service(scope);
 }
\ No newline at end of file
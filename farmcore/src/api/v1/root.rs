@@ -1,23 +1,20 @@
 use actix_web::{get, web, Responder, HttpResponse};
-use crate::database::DbPool;
+use crate::database::Storage;
 use crate::api::responses::ApiResponse;
 use crate::api::documentation::*;
 use crate::state::AppState;
+use std::sync::OnceLock;
 
-#[get("")]
-pub async fn index() -> impl Responder {
-    let documentation = ApiDocumentation::new(
+/// Documentation for the root and health endpoints; also feeds /api/v1/openapi.json
+pub fn documentation() -> ApiDocumentation {
+    ApiDocumentation::new(
         "Farm API Server",
         "1.0.0",
         "Infrastructure management API",
         "/api/v1"
     )
     .with_response_format(standard_response_format())
-    .with_authentication(AuthDoc {
-        type_name: "None".to_string(),
-        description: "No authentication required (development mode)".to_string(),
-        header_name: None,
-    })
+    .with_authentication(bearer_auth_doc())
     .with_rate_limiting(RateLimitDoc {
         requests_per_minute: None,
         requests_per_hour: None,
@@ -45,6 +42,26 @@ pub async fn index() -> impl Responder {
             .add_response_code(ResponseCodeDoc::new(200, "Service is healthy"))
             .add_response_code(ResponseCodeDoc::new(503, "Service is unhealthy")),
 
+        // Prometheus metrics endpoint
+        EndpointDoc::new("/metrics", HttpMethod::Get,
+            "Prometheus metrics in text format (API token, any role): request counts and latencies per route, \
+             database pool usage, inventory ingests with the time of the last success per kind, and Redfish call outcomes per BMC")
+            .with_tags(vec!["health".to_string(), "monitoring".to_string()])
+            .add_response_code(ResponseCodeDoc::new(200, "Metrics"))
+            .add_response_code(ResponseCodeDoc::new(401, "Missing or invalid token")),
+
+        // Fleet inventory gauges
+        EndpointDoc::new("/metrics/fleet", HttpMethod::Get,
+            "Prometheus gauges of the fleet inventory, computed from the database on each scrape (API token, any role): \
+             servers by status/type/cluster/datacenter, stale agents, GPUs by model, disks by SMART health, rack height and free U, \
+             VMs with their vCPUs and memory, switches and switch ports. Scrape every minute or less often.")
+            .with_tags(vec!["monitoring".to_string()])
+            .add_query_parameter(ParameterDoc::new("stale_after", ParameterType::Integer,
+                "Seconds without an inventory report after which an ACTIVE server's agent counts as stale", false).with_default("3600"))
+            .add_response_code(ResponseCodeDoc::new(200, "Metrics"))
+            .add_response_code(ResponseCodeDoc::new(401, "Missing or invalid token"))
+            .add_response_code(ResponseCodeDoc::new(500, "Database error")),
+
         // Database test endpoint
         EndpointDoc::new("/api/v1/db-test", HttpMethod::Get, "Database connectivity test")
             .with_tags(vec!["health".to_string(), "database".to_string()])
@@ -70,15 +87,27 @@ pub async fn index() -> impl Responder {
                 "/api/v1/servers/"
             ))
             .add_response_code(ResponseCodeDoc::new(200, "Server API documentation retrieved successfully")),
-    ]);
 
-    let response = ApiResponse::success(documentation);
+        // OpenAPI specification endpoint
+        EndpointDoc::new("/api/v1/openapi.json", HttpMethod::Get, "OpenAPI 3.1 specification of every v1 route (no authentication required)")
+            .with_tags(vec!["documentation".to_string()])
+            .add_example(ExampleDoc::new(
+                "Download the spec for client generation",
+                "/api/v1/openapi.json"
+            ))
+            .add_response_code(ResponseCodeDoc::new(200, "OpenAPI document")),
+    ])
+}
+
+#[get("")]
+pub async fn index() -> impl Responder {
+    let response = ApiResponse::success(documentation());
     HttpResponse::Ok().json(response)
 }
 
 #[get("/health")]
 pub async fn health_check(state: web::Data<AppState>) -> impl Responder {
-    let pool = state.pool();
+    let storage = state.storage();
     let start_time = std::time::Instant::now();
     let mut overall_healthy = true;
 
@@ -86,11 +115,12 @@ pub async fn health_check(state: web::Data<AppState>) -> impl Responder {
     let api_check = "ok";
 
     // Check database by calling our test_db logic
-    let (db_status, db_details) = match test_database_connection(pool).await {
+    let (db_status, db_details) = match test_database_connection(storage).await {
         Ok(response_time) => (
             "ok",
             serde_json::json!({
                 "connection": "passed",
+                "backend": storage.backend_name(),
                 "response_time_ms": response_time
             })
         ),
@@ -130,8 +160,8 @@ pub async fn health_check(state: web::Data<AppState>) -> impl Responder {
 
 #[get("/db-test")]
 pub async fn test_db(state: web::Data<AppState>) -> impl Responder {
-    let pool = state.pool();
-    match test_database_connection(pool).await {
+    let storage = state.storage();
+    match test_database_connection(storage).await {
         Ok(response_time) => {
             let response = ApiResponse::success(serde_json::json!({
                 "database": "connected",
@@ -152,13 +182,10 @@ pub async fn test_db(state: web::Data<AppState>) -> impl Responder {
 }
 
 // Helper function to test database connection and measure response time
-async fn test_database_connection(pool: &DbPool) -> Result<u128, String> {
+async fn test_database_connection(storage: &Storage) -> Result<u128, String> {
     let start_time = std::time::Instant::now();
     
-    match sqlx::query("SELECT 1 as test")
-        .fetch_one(pool)
-        .await
-    {
+    match storage.ping().await {
         Ok(_) => {
             let response_time = start_time.elapsed().as_millis();
             Ok(response_time)
@@ -167,8 +194,19 @@ async fn test_database_connection(pool: &DbPool) -> Result<u128, String> {
     }
 }
 
+#[get("/openapi.json")]
+pub async fn openapi_spec() -> impl Responder {
+    // Documentation is static, so the spec only needs to be built once
+    static SPEC: OnceLock<serde_json::Value> = OnceLock::new();
+    let spec = SPEC.get_or_init(|| {
+        openapi::openapi_document("Farm API Server", "1.0.0", &super::module_documentation())
+    });
+    HttpResponse::Ok().json(spec)
+}
+
 pub fn configure_health_routes(cfg: &mut web::ServiceConfig) {
     cfg.service(index)
+       .service(openapi_spec)
        .service(health_check)
        .service(test_db);
 }
\ No newline at end of file
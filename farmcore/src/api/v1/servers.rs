@@ -1,11 +1,25 @@
-use actix_web::{get, post, put, web, HttpResponse, Responder};
+use actix_web::{get, post, put, web, HttpResponse, Responder, ResponseError};
 use std::collections::HashMap;
 
-use crate::api::responses::{ApiResponse, ApiMeta, PaginationMeta};
+use crate::api::responses::{ApiResponse, ApiMeta};
 use crate::api::documentation::*;
+use crate::api::export::{export_response, ExportFormat};
 use crate::api::query_parser::{CommonPaginationQuery, QueryParser};
+use crate::api::v1::decode_agent_payload;
 use crate::state::AppState;
-use crate::domain::bmc::RedfishClient;
+use crate::domain::bmc::{RedfishClient, RedfishError};
+use crate::domain::identity::MIN_CONFIDENCE;
+use crate::domain::{diff_inventory, DuplicateServers, NewEvent};
+use crate::metrics;
+use crate::models::{
+    InventorySnapshot, InventorySnapshotDiff, InventorySnapshotSummary, MergeServers, Server, ServerIdentityConflict, ServerInventoryChange, ServerMergeReport,
+    ServerMissingComponent, ServerWithAllComponents,
+};
+use crate::repositories::server_repository::ServerInventory;
+
+/// Snapshots listed when the request does not set a limit
+const DEFAULT_SNAPSHOT_LIMIT: i64 = 50;
+const MAX_SNAPSHOT_LIMIT: i64 = 500;
 
 /// Helper function to get BMC client for a server
 async fn get_bmc_client(
@@ -70,9 +84,9 @@ async fn get_bmc_client(
     }
 }
 
-#[get("")]
-pub async fn index() -> impl Responder {
-    let documentation = ApiDocumentation::new(
+/// Documentation for the server endpoints; also feeds /api/v1/openapi.json
+pub fn documentation() -> ApiDocumentation {
+    ApiDocumentation::new(
         "Farm Server API",
         "v1",
         "Server management endpoints",
@@ -85,10 +99,32 @@ pub async fn index() -> impl Responder {
             .add_query_parameter(ParameterDoc::new("page", ParameterType::Integer, "Page number", false).with_default("1"))
             .add_query_parameter(ParameterDoc::new("per_page", ParameterType::Integer, "Items per page", false).with_default("10"))
             .add_query_parameter(ParameterDoc::new("columns", ParameterType::String, "Comma-separated column list", false))
+            .add_query_parameter(ParameterDoc::new("sort", ParameterType::String, "Comma-separated sort columns, prefixed with - for descending", false).with_example("-last_inventory_at,server_name"))
+            .add_query_parameter(ParameterDoc::new("format", ParameterType::String, "csv or ndjson streams every matching row as a download (no page cap); columns, search and filters apply", false))
+            .add_query_parameter(ParameterDoc::new("after", ParameterType::String, "Cursor from meta.pagination.next_cursor; empty for the first page. Pages by primary key, up to 1000 per page", false))
+            .add_query_parameter(ParameterDoc::new("count", ParameterType::Boolean, "Set to false to skip the total count", false).with_default("true"))
+            .add_query_parameter(ParameterDoc::new("search", ParameterType::String,
+                "JSON array of criteria {column, comparisonOperator, term|terms, operator} and groups {group: [...], operator}. \
+                 Operators: =, !=, >, <, >=, <=, LIKE, NOT LIKE, IN, NOT IN, BETWEEN, IS NULL, IS NOT NULL. \
+                 Timestamps accept relative dates such as now-1d. Component criteria use gpu.*, memory.*, disk.* and nic.* \
+                 columns (e.g. gpu.model, disk.smart_health, nic.switch_name) and match servers with at least one such \
+                 component; <component>.count compares the number installed", false))
             .add_example(ExampleDoc::new("Get active servers", "/api/v1/servers/get_servers?status=active"))
-            .add_example(ExampleDoc::new("Search web servers", "/api/v1/servers/get_servers?host_name_like=web"))
+            .add_example(ExampleDoc::new("Get active or maintenance servers in clusters 3 and 4", "/api/v1/servers/get_servers?status=ACTIVE,MAINTENANCE&cluster_id=3,4"))
+            .add_example(ExampleDoc::new(
+                "Servers in maintenance or RMA not inventoried for a day",
+                r#"/api/v1/servers/get_servers?search=[{"group":[{"column":"status","term":"MAINTENANCE","operator":"OR"},{"column":"status","term":"RMA"}],"operator":"AND"},{"column":"last_inventory_at","comparisonOperator":"<","term":"now-1d"}]"#,
+            ))
+            .add_example(ExampleDoc::new("Servers with an NVIDIA H100", r#"/api/v1/servers/get_servers?search=[{"column":"gpu.model","term":"H100","comparisonOperator":"LIKE"}]"#))
+            .add_example(ExampleDoc::new("Servers with fewer than 16 DIMMs", r#"/api/v1/servers/get_servers?search=[{"column":"memory.count","comparisonOperator":"<","term":"16"}]"#))
+            .add_example(ExampleDoc::new("Servers with a failing disk", "/api/v1/servers/get_servers?disk.smart_health=FAILED"))
+            .add_example(ExampleDoc::new("Servers cabled to a switch", "/api/v1/servers/get_servers?nic.switch_name=leaf-a12"))
+            .add_example(ExampleDoc::new("Export every server, 1000 at a time", "/api/v1/servers/get_servers?after=&per_page=1000&count=false"))
+            .add_example(ExampleDoc::new("Spreadsheet of active servers", "/api/v1/servers/get_servers?format=csv&status=ACTIVE&columns=server_name,serial_number,rack_id,rack_position_id"))
+            .add_example(ExampleDoc::new("Search web servers", r#"/api/v1/servers/get_servers?search=[{"column":"server_name","term":"web","comparisonOperator":"LIKE"}]"#))
             .add_response_code(ResponseCodeDoc::new(200, "Success"))
             .add_response_code(ResponseCodeDoc::new(400, "Invalid parameters"))
+            .with_response_schema::<Vec<Server>>()
     )
     .add_endpoint(
         EndpointDoc::new("/api/v1/servers/{id}", HttpMethod::Get, "Get specific server by ID with complete component details")
@@ -96,62 +132,170 @@ pub async fn index() -> impl Responder {
             .add_example(ExampleDoc::new("Get server 123", "/api/v1/servers/123"))
             .add_response_code(ResponseCodeDoc::new(200, "Success - Returns server with CPU, memory, disk, and network component details"))
             .add_response_code(ResponseCodeDoc::new(404, "Server not found"))
+            .with_response_schema::<ServerWithAllComponents>()
     )
     .add_endpoint(
         EndpointDoc::new("/api/v1/servers/overview", HttpMethod::Get, "Get server overview statistics")
             .add_response_code(ResponseCodeDoc::new(200, "Success"))
     )
-    .add_endpoint(
-        EndpointDoc::new("/api/v1/servers/components", HttpMethod::Get, "Get all component types catalog")
-            .add_response_code(ResponseCodeDoc::new(200, "Success - Returns all unique component types (CPUs, Memory, Disks, Network, GPUs, Motherboards)"))
-    )
     .add_endpoint(
         EndpointDoc::new("/api/v1/servers/{id}", HttpMethod::Put, "Update server fields")
             .add_path_parameter(ParameterDoc::new("id", ParameterType::Integer, "Server ID", true))
             .add_response_code(ResponseCodeDoc::new(200, "Success - Server updated"))
             .add_response_code(ResponseCodeDoc::new(400, "Invalid field or value"))
             .add_response_code(ResponseCodeDoc::new(404, "Server not found"))
+            .with_request_schema::<HashMap<String, serde_json::Value>>()
     )
     .add_endpoint(
-        EndpointDoc::new("/api/v1/servers/inventory", HttpMethod::Post, "Create or update server from inventory data")
-            .add_response_code(ResponseCodeDoc::new(200, "Success - Server created or updated"))
-            .add_response_code(ResponseCodeDoc::new(400, "Invalid inventory data"))
+        EndpointDoc::new("/api/v1/servers/inventory", HttpMethod::Post,
+            "Create or update server from inventory data. The server is recognised by its system UUID, system, chassis and \
+             board serials, BMC MAC and NIC MACs; every existing server sharing one of them is scored 0-100.")
+            .add_response_code(ResponseCodeDoc::new(200, "Success - Server created or updated; `unchanged` is true when the payload matched the latest snapshot and only last_inventory_at was updated. \
+                `identity` gives the resolution, the confidence of the match, the identifiers it matched on and, when the match was not clear-cut, the conflict_id it was recorded under"))
+            .add_response_code(ResponseCodeDoc::new(400, "Invalid inventory data, unsupported schema_version, or nothing to identify the server by"))
+            .add_response_code(ResponseCodeDoc::new(409, "Identity conflict - the report matches several servers without a clear best match, or only a server it contradicts, or it would create a server under a hostname that is taken; see /api/v1/inventory/identity-conflicts"))
             .add_response_code(ResponseCodeDoc::new(500, "Database error"))
+            .with_request_schema::<ServerInventory>()
+    )
+    .add_endpoint(
+        EndpointDoc::new("/api/v1/servers/{id}/snapshots", HttpMethod::Get, "List inventory snapshots of a server, newest first, without their payloads")
+            .add_path_parameter(ParameterDoc::new("id", ParameterType::Integer, "Server ID", true))
+            .add_query_parameter(ParameterDoc::new("limit", ParameterType::Integer, "Maximum number of snapshots (up to 500)", false).with_default("50"))
+            .add_example(ExampleDoc::new("Last 10 snapshots of server 123", "/api/v1/servers/123/snapshots?limit=10"))
+            .add_response_code(ResponseCodeDoc::new(200, "Success"))
+            .with_response_schema::<Vec<InventorySnapshotSummary>>()
+    )
+    .add_endpoint(
+        EndpointDoc::new("/api/v1/servers/{id}/snapshots/diff", HttpMethod::Get, "Compare two inventory snapshots of a server")
+            .add_path_parameter(ParameterDoc::new("id", ParameterType::Integer, "Server ID", true))
+            .add_query_parameter(ParameterDoc::new("from", ParameterType::Integer, "Snapshot ID of the older side", true))
+            .add_query_parameter(ParameterDoc::new("to", ParameterType::Integer, "Snapshot ID of the newer side", true))
+            .add_example(ExampleDoc::new("What changed between snapshots 40 and 52", "/api/v1/servers/123/snapshots/diff?from=40&to=52"))
+            .add_response_code(ResponseCodeDoc::new(200, "Success - Changes are listed by path; component lists are matched on UUID, serial, MAC, socket or slot"))
+            .add_response_code(ResponseCodeDoc::new(400, "from or to missing"))
+            .add_response_code(ResponseCodeDoc::new(404, "Snapshot not found for this server"))
+            .with_response_schema::<InventorySnapshotDiff>()
+    )
+    .add_endpoint(
+        EndpointDoc::new("/api/v1/servers/{id}/snapshots/{snapshot_id}", HttpMethod::Get, "Get an inventory snapshot with its payload")
+            .add_path_parameter(ParameterDoc::new("id", ParameterType::Integer, "Server ID", true))
+            .add_path_parameter(ParameterDoc::new("snapshot_id", ParameterType::Integer, "Snapshot ID", true))
+            .add_response_code(ResponseCodeDoc::new(200, "Success"))
+            .add_response_code(ResponseCodeDoc::new(404, "Snapshot not found for this server"))
+            .with_response_schema::<InventorySnapshot>()
+    )
+    .add_endpoint(
+        EndpointDoc::new("/api/v1/servers/{id}/changes", HttpMethod::Get, "Hardware change history of a server, newest first")
+            .add_path_parameter(ParameterDoc::new("id", ParameterType::Integer, "Server ID", true))
+            .add_query_parameter(ParameterDoc::new("page", ParameterType::Integer, "Page number", false).with_default("1"))
+            .add_query_parameter(ParameterDoc::new("per_page", ParameterType::Integer, "Items per page (max 100)", false).with_default("10"))
+            .add_query_parameter(ParameterDoc::new("after", ParameterType::String, "Cursor from meta.pagination.next_cursor; empty for the first page", false))
+            .add_query_parameter(ParameterDoc::new("format", ParameterType::String, "csv or ndjson streams every matching row as a download", false))
+            .add_query_parameter(ParameterDoc::new("search", ParameterType::String, "JSON search criteria, as for /api/v1/inventory/changes", false))
+            .add_example(ExampleDoc::new("Memory changes on server 17", "/api/v1/servers/17/changes?component_type=memory"))
+            .add_response_code(ResponseCodeDoc::new(200, "Success"))
+            .add_response_code(ResponseCodeDoc::new(400, "Invalid parameters"))
+            .with_response_schema::<Vec<ServerInventoryChange>>()
+    )
+    .add_endpoint(
+        EndpointDoc::new("/api/v1/servers/{id}/missing", HttpMethod::Get, "Components missing from a server's latest inventory, newest first")
+            .add_path_parameter(ParameterDoc::new("id", ParameterType::Integer, "Server ID", true))
+            .add_query_parameter(ParameterDoc::new("include_resolved", ParameterType::Boolean, "Also list parts that have been reported again", false).with_default("false"))
+            .add_query_parameter(ParameterDoc::new("page", ParameterType::Integer, "Page number", false).with_default("1"))
+            .add_query_parameter(ParameterDoc::new("per_page", ParameterType::Integer, "Items per page (max 100)", false).with_default("10"))
+            .add_query_parameter(ParameterDoc::new("format", ParameterType::String, "csv or ndjson streams every matching row as a download", false))
+            .add_query_parameter(ParameterDoc::new("search", ParameterType::String, "JSON search criteria, as for /api/v1/inventory/missing", false))
+            .add_example(ExampleDoc::new("Missing and returned DIMMs on server 17", "/api/v1/servers/17/missing?component_type=memory&include_resolved=true"))
+            .add_response_code(ResponseCodeDoc::new(200, "Success"))
+            .add_response_code(ResponseCodeDoc::new(400, "Invalid parameters"))
+            .with_response_schema::<Vec<ServerMissingComponent>>()
+    )
+    .add_endpoint(
+        EndpointDoc::new("/api/v1/servers/{id}/identity-conflicts", HttpMethod::Get, "Identity conflicts of inventory reports applied to a server, newest first")
+            .add_path_parameter(ParameterDoc::new("id", ParameterType::Integer, "Server ID", true))
+            .add_query_parameter(ParameterDoc::new("include_resolved", ParameterType::Boolean, "Also list resolved conflicts", false).with_default("false"))
+            .add_query_parameter(ParameterDoc::new("page", ParameterType::Integer, "Page number", false).with_default("1"))
+            .add_query_parameter(ParameterDoc::new("per_page", ParameterType::Integer, "Items per page (max 100)", false).with_default("10"))
+            .add_query_parameter(ParameterDoc::new("format", ParameterType::String, "csv or ndjson streams every matching row as a download", false))
+            .add_query_parameter(ParameterDoc::new("search", ParameterType::String, "JSON search criteria, as for /api/v1/inventory/identity-conflicts", false))
+            .add_example(ExampleDoc::new("Open conflicts on server 17", "/api/v1/servers/17/identity-conflicts"))
+            .add_response_code(ResponseCodeDoc::new(200, "Success"))
+            .add_response_code(ResponseCodeDoc::new(400, "Invalid parameters"))
+            .with_response_schema::<Vec<ServerIdentityConflict>>()
+    )
+    .add_endpoint(
+        EndpointDoc::new("/api/v1/servers/duplicates", HttpMethod::Get,
+            "Pairs of server records that probably describe the same machine, most likely first. Records sharing a serial, \
+             system UUID, NIC or BMC MAC, BMC IP or short hostname are scored like an inventory report against a stored server; \
+             records with different system UUIDs are never paired.")
+            .add_query_parameter(ParameterDoc::new("min_confidence", ParameterType::Integer, "Lowest confidence (0-100) to list", false).with_default("50"))
+            .add_example(ExampleDoc::new("Every pair sharing anything", "/api/v1/servers/duplicates?min_confidence=0"))
+            .add_response_code(ResponseCodeDoc::new(200, "Success - suggested_server_id is the record inventoried last, to merge the other into"))
+            .with_response_schema::<Vec<DuplicateServers>>()
+    )
+    .add_endpoint(
+        EndpointDoc::new("/api/v1/servers/{id}/merge", HttpMethod::Post,
+            "Merge duplicate records into a server in one transaction and delete them. The components (CPUs, DIMMs, disks, \
+             NICs, BMCs, ...) are those of the record with the newest inventory snapshot, so they match the inventory the \
+             server is compared against on its next report; without snapshots the server keeps its own and takes a merged \
+             record's where it has none. VMs, credentials, \
+             snapshots, change history, missing components, identity conflicts, Kubernetes nodes and rack positions are moved. \
+             Blank identifiers, placement and cluster are filled in from the merged records. Open identity conflicts naming a \
+             merged record are resolved. The merge is recorded in the audit log.")
+            .add_path_parameter(ParameterDoc::new("id", ParameterType::Integer, "ID of the server to keep", true))
+            .add_query_parameter(ParameterDoc::new("dry_run", ParameterType::Boolean, "Merge inside a transaction that is rolled back and report what would change", false).with_default("false"))
+            .add_response_code(ResponseCodeDoc::new(200, "Success - counts of moved and discarded rows by table"))
+            .add_response_code(ResponseCodeDoc::new(400, "server_ids is empty or names the server itself"))
+            .add_response_code(ResponseCodeDoc::new(403, "Requires the ADMIN role"))
+            .add_response_code(ResponseCodeDoc::new(404, "Server not found"))
+            .add_response_code(ResponseCodeDoc::new(409, "The records have different system UUIDs, or VMs of the same name"))
+            .with_request_schema::<MergeServers>()
+            .with_response_schema::<ServerMergeReport>()
     )
     .add_endpoint(
         EndpointDoc::new("/api/v1/servers/{id}/power/on", HttpMethod::Post, "Power on a server via BMC")
             .add_path_parameter(ParameterDoc::new("id", ParameterType::Integer, "Server ID", true))
             .add_response_code(ResponseCodeDoc::new(200, "Success - Server powered on"))
+            .add_response_code(ResponseCodeDoc::new(403, "Requires the OPERATOR role"))
             .add_response_code(ResponseCodeDoc::new(404, "Server not found"))
             .add_response_code(ResponseCodeDoc::new(500, "BMC operation failed"))
+            .add_response_code(ResponseCodeDoc::new(501, "The BMC does not offer this reset type"))
     )
     .add_endpoint(
         EndpointDoc::new("/api/v1/servers/{id}/power/off", HttpMethod::Post, "Power off a server via BMC (graceful)")
             .add_path_parameter(ParameterDoc::new("id", ParameterType::Integer, "Server ID", true))
             .add_response_code(ResponseCodeDoc::new(200, "Success - Server powered off"))
+            .add_response_code(ResponseCodeDoc::new(403, "Requires the OPERATOR role"))
             .add_response_code(ResponseCodeDoc::new(404, "Server not found"))
             .add_response_code(ResponseCodeDoc::new(500, "BMC operation failed"))
+            .add_response_code(ResponseCodeDoc::new(501, "The BMC does not offer this reset type"))
     )
     .add_endpoint(
         EndpointDoc::new("/api/v1/servers/{id}/power/restart", HttpMethod::Post, "Restart a server via BMC (graceful)")
             .add_path_parameter(ParameterDoc::new("id", ParameterType::Integer, "Server ID", true))
             .add_response_code(ResponseCodeDoc::new(200, "Success - Server restarting"))
+            .add_response_code(ResponseCodeDoc::new(403, "Requires the OPERATOR role"))
             .add_response_code(ResponseCodeDoc::new(404, "Server not found"))
             .add_response_code(ResponseCodeDoc::new(500, "BMC operation failed"))
+            .add_response_code(ResponseCodeDoc::new(501, "The BMC does not offer this reset type"))
     )
     .add_endpoint(
         EndpointDoc::new("/api/v1/servers/{id}/power/force-off", HttpMethod::Post, "Force power off a server via BMC")
             .add_path_parameter(ParameterDoc::new("id", ParameterType::Integer, "Server ID", true))
             .add_response_code(ResponseCodeDoc::new(200, "Success - Server force powered off"))
+            .add_response_code(ResponseCodeDoc::new(403, "Requires the OPERATOR role"))
             .add_response_code(ResponseCodeDoc::new(404, "Server not found"))
             .add_response_code(ResponseCodeDoc::new(500, "BMC operation failed"))
+            .add_response_code(ResponseCodeDoc::new(501, "The BMC does not offer this reset type"))
     )
     .add_endpoint(
         EndpointDoc::new("/api/v1/servers/{id}/power/force-restart", HttpMethod::Post, "Force restart a server via BMC")
             .add_path_parameter(ParameterDoc::new("id", ParameterType::Integer, "Server ID", true))
             .add_response_code(ResponseCodeDoc::new(200, "Success - Server force restarting"))
+            .add_response_code(ResponseCodeDoc::new(403, "Requires the OPERATOR role"))
             .add_response_code(ResponseCodeDoc::new(404, "Server not found"))
             .add_response_code(ResponseCodeDoc::new(500, "BMC operation failed"))
+            .add_response_code(ResponseCodeDoc::new(501, "The BMC does not offer this reset type"))
     )
     .add_endpoint(
         EndpointDoc::new("/api/v1/servers/{id}/power/status", HttpMethod::Get, "Get server power state via BMC")
@@ -159,9 +303,12 @@ pub async fn index() -> impl Responder {
             .add_response_code(ResponseCodeDoc::new(200, "Success - Returns power state"))
             .add_response_code(ResponseCodeDoc::new(404, "Server not found"))
             .add_response_code(ResponseCodeDoc::new(500, "BMC operation failed"))
-    );
+    )
+}
 
-    let response = ApiResponse::success(documentation);
+#[get("")]
+pub async fn index() -> impl Responder {
+    let response = ApiResponse::success(documentation());
     HttpResponse::Ok().json(response)
 }
 
@@ -170,27 +317,21 @@ pub async fn get_all_servers(
     app_state: web::Data<AppState>, 
     query: web::Query<CommonPaginationQuery>
 ) -> impl Responder {
-    let pagination = QueryParser::parse_pagination(&query);
-    let (page, per_page, _) = match pagination {
-        Ok(p) => p,
-        Err(e) => {
-            let response = ApiResponse::<()>::error("INVALID_PARAMS", &e);
-            return HttpResponse::BadRequest().json(response);
-        }
-    };
+    if let Err(e) = QueryParser::validate(&query, &Server::SCHEMA) {
+        return e.error_response();
+    }
 
-    match app_state.server_repo().get_all_servers(query.into_inner()).await {
+    if let Some(format) = ExportFormat::from_query(&query) {
+        let rows = app_state.server_repo().stream_servers(query.clone().into_inner());
+        return export_response(format, &Server::SCHEMA, &query, rows);
+    }
+
+    let query = query.into_inner();
+    match app_state.server_repo().get_all_servers(query.clone()).await {
         Ok((servers, total_count)) => {
-            let total_pages = (total_count + per_page - 1) / per_page;
+            let last_key = servers.last().map(|server| server.server_id as i64);
             let meta = ApiMeta {
-                pagination: Some(PaginationMeta {
-                    current_page: page,
-                    per_page,
-                    total_count,
-                    total_pages,
-                    has_next: page < total_pages,
-                    has_prev: page > 1,
-                }),
+                pagination: Some(QueryParser::pagination_meta(&query, &Server::SCHEMA, servers.len(), total_count, last_key)),
                 filters_applied: None,
                 request_id: None,
                 timestamp: chrono::Utc::now(),
@@ -269,6 +410,20 @@ pub async fn update_server(
 ) -> impl Responder {
     let server_id = id.into_inner() as i32;
     let update_map = updates.into_inner();
+    let fields: Vec<String> = update_map.keys().cloned().collect();
+
+    // Read the old status first so a change can be announced with both values
+    let new_status = update_map.get("status").and_then(|status| status.as_str()).map(str::to_string);
+    let old_status = match new_status {
+        Some(_) => match app_state.server_repo().get_by_id(server_id as i64).await {
+            Ok(server) => server.and_then(|server| server.status),
+            Err(e) => {
+                log::error!("Error fetching server {}: {}", server_id, e);
+                None
+            }
+        },
+        None => None,
+    };
 
     if update_map.is_empty() {
         let response = ApiResponse::<()>::error(
@@ -280,6 +435,10 @@ pub async fn update_server(
 
     match app_state.server_repo().update_server(server_id, update_map).await {
         Ok(true) => {
+            app_state.publish(NewEvent::server_updated(server_id, fields)).await;
+            if let Some(status) = new_status.filter(|status| old_status.as_deref() != Some(status.as_str())) {
+                app_state.publish(NewEvent::server_status_changed(server_id, old_status.as_deref(), &status)).await;
+            }
             let response = ApiResponse::success(serde_json::json!({
                 "message": "Server updated successfully",
                 "server_id": server_id
@@ -314,14 +473,29 @@ pub async fn update_server(
 #[actix_web::post("/inventory")]
 pub async fn upsert_server_inventory(
     app_state: web::Data<AppState>,
-    inventory: web::Json<crate::repositories::server_repository::ServerInventory>
+    payload: web::Json<serde_json::Value>
 ) -> impl Responder {
-    let inventory_data = inventory.into_inner();
-
-    match app_state.server_repo().upsert_server_from_inventory(inventory_data).await {
-        Ok((server_id, was_created)) => {
+    let inventory_data: ServerInventory = match decode_agent_payload(payload.into_inner()) {
+        Ok(inventory) => inventory,
+        Err(response) => return response,
+    };
+    let hostname = inventory_data.node.hostname.clone();
+
+    let result = app_state.server_repo().upsert_server_from_inventory(inventory_data).await;
+    metrics::observe_inventory_ingest("server", result.is_ok());
+    match result {
+        Ok(upsert) => {
+            let (server_id, was_created) = (upsert.server_id, upsert.created);
+            if !upsert.unchanged {
+                app_state.publish(NewEvent::server_inventory(server_id, was_created, Some(&hostname))).await;
+            }
+            for component in &upsert.removed_components {
+                app_state.publish(NewEvent::server_component_removed(server_id, component)).await;
+            }
             let message = if was_created {
                 format!("Server created successfully with ID {}", server_id)
+            } else if upsert.unchanged {
+                format!("Server {} inventory unchanged", server_id)
             } else {
                 format!("Server {} updated successfully", server_id)
             };
@@ -329,7 +503,14 @@ pub async fn upsert_server_inventory(
             let response = ApiResponse::success(serde_json::json!({
                 "message": message,
                 "server_id": server_id,
-                "created": was_created
+                "created": was_created,
+                "unchanged": upsert.unchanged,
+                "identity": {
+                    "resolution": upsert.identity.resolution,
+                    "confidence": upsert.identity.confidence,
+                    "matched_by": upsert.identity.matched_by,
+                    "conflict_id": upsert.conflict_id
+                }
             }));
             HttpResponse::Ok().json(response)
         },
@@ -337,8 +518,9 @@ pub async fn upsert_server_inventory(
             log::error!("Error upserting server from inventory: {}", e);
             
             let error_message = e.to_string();
-            let (mut status_code, error_code) = if error_message.contains("No primary network interface") 
-                || error_message.contains("MAC address") 
+            let (mut status_code, error_code) = if error_message.contains("Server identity conflict") {
+                (HttpResponse::Conflict(), "IDENTITY_CONFLICT")
+            } else if error_message.contains("MAC address")
                 || error_message.contains("does not exist") {
                 (HttpResponse::BadRequest(), "VALIDATION_ERROR")
             } else {
@@ -351,6 +533,209 @@ pub async fn upsert_server_inventory(
     }
 }
 
+// ===================================================================
+// INVENTORY SNAPSHOTS AND CHANGES
+// ===================================================================
+
+#[get("/{id}/changes")]
+pub async fn get_server_changes(
+    app_state: web::Data<AppState>,
+    id: web::Path<i32>,
+    query: web::Query<CommonPaginationQuery>
+) -> impl Responder {
+    let mut query = query.into_inner();
+    query.filters.insert("server_id".to_string(), id.into_inner().to_string());
+    crate::api::v1::inventory::list_changes(&app_state, query).await
+}
+
+#[get("/{id}/missing")]
+pub async fn get_server_missing_components(
+    app_state: web::Data<AppState>,
+    id: web::Path<i32>,
+    query: web::Query<CommonPaginationQuery>
+) -> impl Responder {
+    let mut query = query.into_inner();
+    query.filters.insert("server_id".to_string(), id.into_inner().to_string());
+    crate::api::v1::inventory::list_missing(&app_state, query).await
+}
+
+#[get("/{id}/identity-conflicts")]
+pub async fn get_server_identity_conflicts(
+    app_state: web::Data<AppState>,
+    id: web::Path<i32>,
+    query: web::Query<CommonPaginationQuery>
+) -> impl Responder {
+    let mut query = query.into_inner();
+    query.filters.insert("server_id".to_string(), id.into_inner().to_string());
+    crate::api::v1::inventory::list_identity_conflicts(&app_state, query).await
+}
+
+#[derive(serde::Deserialize)]
+pub struct DuplicateQuery {
+    min_confidence: Option<i32>,
+}
+
+#[get("/duplicates")]
+pub async fn get_duplicate_servers(
+    app_state: web::Data<AppState>,
+    query: web::Query<DuplicateQuery>
+) -> impl Responder {
+    let min_confidence = query.min_confidence.unwrap_or(MIN_CONFIDENCE).clamp(0, 100);
+
+    match app_state.server_merge_repo().find_duplicate_servers(min_confidence).await {
+        Ok(duplicates) => HttpResponse::Ok().json(ApiResponse::success(duplicates)),
+        Err(e) => {
+            log::error!("Database error looking for duplicate servers: {}", e);
+            let response = ApiResponse::<()>::error("DATABASE_ERROR", "Failed to look for duplicate servers");
+            HttpResponse::InternalServerError().json(response)
+        }
+    }
+}
+
+#[derive(serde::Deserialize)]
+pub struct MergeQuery {
+    #[serde(default)]
+    dry_run: bool,
+}
+
+#[post("/{id}/merge")]
+pub async fn merge_servers(
+    app_state: web::Data<AppState>,
+    id: web::Path<i32>,
+    query: web::Query<MergeQuery>,
+    body: web::Json<MergeServers>
+) -> impl Responder {
+    let server_id = id.into_inner();
+
+    match app_state.server_merge_repo().merge_servers(server_id, body.into_inner().server_ids, query.dry_run).await {
+        Ok(report) => {
+            if !report.dry_run {
+                app_state.publish(NewEvent::server_merged(server_id, &report.merged_server_ids)).await;
+            }
+            HttpResponse::Ok().json(ApiResponse::success(report))
+        },
+        Err(e) => {
+            log::error!("Error merging servers into {}: {}", server_id, e);
+
+            let error_message = e.to_string();
+            let (mut status_code, error_code) = if error_message.contains("not found") {
+                (HttpResponse::NotFound(), "NOT_FOUND")
+            } else if error_message.contains("cannot be merged into server") {
+                (HttpResponse::Conflict(), "MERGE_CONFLICT")
+            } else if error_message.contains("cannot be merged") || error_message.contains("No servers to merge") {
+                (HttpResponse::BadRequest(), "VALIDATION_ERROR")
+            } else {
+                (HttpResponse::InternalServerError(), "DATABASE_ERROR")
+            };
+
+            let response = ApiResponse::<()>::error(error_code, &error_message);
+            status_code.json(response)
+        }
+    }
+}
+
+#[derive(serde::Deserialize)]
+pub struct SnapshotListQuery {
+    limit: Option<i64>,
+}
+
+#[get("/{id}/snapshots")]
+pub async fn get_server_snapshots(
+    app_state: web::Data<AppState>,
+    id: web::Path<i32>,
+    query: web::Query<SnapshotListQuery>
+) -> impl Responder {
+    let server_id = id.into_inner();
+    let limit = query.limit.unwrap_or(DEFAULT_SNAPSHOT_LIMIT).clamp(1, MAX_SNAPSHOT_LIMIT);
+
+    match app_state.server_repo().get_inventory_snapshots(server_id, limit).await {
+        Ok(snapshots) => HttpResponse::Ok().json(ApiResponse::success(snapshots)),
+        Err(e) => {
+            log::error!("Database error fetching snapshots of server {}: {}", server_id, e);
+            let response = ApiResponse::<()>::error("DATABASE_ERROR", "Failed to fetch inventory snapshots");
+            HttpResponse::InternalServerError().json(response)
+        }
+    }
+}
+
+/// Helper function to load a snapshot of a server, or the error response to send
+async fn fetch_snapshot(
+    app_state: &AppState,
+    server_id: i32,
+    snapshot_id: i32,
+) -> Result<InventorySnapshot, HttpResponse> {
+    match app_state.server_repo().get_inventory_snapshot(server_id, snapshot_id).await {
+        Ok(Some(snapshot)) => Ok(snapshot),
+        Ok(None) => {
+            let response = ApiResponse::<()>::error(
+                "NOT_FOUND",
+                &format!("Snapshot {} not found for server {}", snapshot_id, server_id)
+            );
+            Err(HttpResponse::NotFound().json(response))
+        },
+        Err(e) => {
+            log::error!("Database error fetching snapshot {} of server {}: {}", snapshot_id, server_id, e);
+            let response = ApiResponse::<()>::error("DATABASE_ERROR", "Failed to fetch inventory snapshot");
+            Err(HttpResponse::InternalServerError().json(response))
+        }
+    }
+}
+
+#[derive(serde::Deserialize)]
+pub struct SnapshotDiffQuery {
+    from: i32,
+    to: i32,
+}
+
+#[get("/{id}/snapshots/diff")]
+pub async fn diff_server_snapshots(
+    app_state: web::Data<AppState>,
+    id: web::Path<i32>,
+    query: web::Query<SnapshotDiffQuery>
+) -> impl Responder {
+    let server_id = id.into_inner();
+
+    let from = match fetch_snapshot(&app_state, server_id, query.from).await {
+        Ok(snapshot) => snapshot,
+        Err(response) => return response,
+    };
+    let to = match fetch_snapshot(&app_state, server_id, query.to).await {
+        Ok(snapshot) => snapshot,
+        Err(response) => return response,
+    };
+
+    let diff = InventorySnapshotDiff {
+        server_id,
+        changes: diff_inventory(&from.inventory_data, &to.inventory_data),
+        from: from.into(),
+        to: to.into(),
+    };
+    HttpResponse::Ok().json(ApiResponse::success(diff))
+}
+
+#[get("/{id}/snapshots/{snapshot_id}")]
+pub async fn get_server_snapshot(
+    app_state: web::Data<AppState>,
+    path: web::Path<(i32, i32)>
+) -> impl Responder {
+    let (server_id, snapshot_id) = path.into_inner();
+
+    match fetch_snapshot(&app_state, server_id, snapshot_id).await {
+        Ok(snapshot) => HttpResponse::Ok().json(ApiResponse::success(snapshot)),
+        Err(response) => response,
+    }
+}
+
+/// Response for a failed BMC call. A reset type the BMC does not offer is not a failure of
+/// the BMC, so it is told apart from one.
+fn bmc_error_response(error: &RedfishError, context: &str) -> HttpResponse {
+    let message = format!("{}: {}", context, error);
+    match error {
+        RedfishError::NotSupported(_) => HttpResponse::NotImplemented().json(ApiResponse::<()>::error("NOT_SUPPORTED", &message)),
+        _ => HttpResponse::InternalServerError().json(ApiResponse::<()>::error("BMC_ERROR", &message)),
+    }
+}
+
 #[post("/{id}/power/on")]
 pub async fn power_on_server(
     app_state: web::Data<AppState>,
@@ -363,7 +748,9 @@ pub async fn power_on_server(
         Err(response) => return response,
     };
 
-    match client.power_on(None).await {
+    let result = client.power_on(None).await;
+    app_state.publish(NewEvent::server_power_action(server_id, "on", result.as_ref().err().map(|e| e.to_string()))).await;
+    match result {
         Ok(_) => {
             let response = ApiResponse::success(serde_json::json!({
                 "message": "Server power on command sent",
@@ -371,10 +758,7 @@ pub async fn power_on_server(
             }));
             HttpResponse::Ok().json(response)
         },
-        Err(e) => {
-            let response = ApiResponse::<()>::error("BMC_ERROR", &format!("Power on failed: {}", e));
-            HttpResponse::InternalServerError().json(response)
-        }
+        Err(e) => bmc_error_response(&e, "Power on failed"),
     }
 }
 
@@ -390,7 +774,9 @@ pub async fn power_off_server(
         Err(response) => return response,
     };
 
-    match client.power_off(None).await {
+    let result = client.power_off(None).await;
+    app_state.publish(NewEvent::server_power_action(server_id, "off", result.as_ref().err().map(|e| e.to_string()))).await;
+    match result {
         Ok(_) => {
             let response = ApiResponse::success(serde_json::json!({
                 "message": "Server graceful shutdown command sent",
@@ -398,10 +784,7 @@ pub async fn power_off_server(
             }));
             HttpResponse::Ok().json(response)
         },
-        Err(e) => {
-            let response = ApiResponse::<()>::error("BMC_ERROR", &format!("Power off failed: {}", e));
-            HttpResponse::InternalServerError().json(response)
-        }
+        Err(e) => bmc_error_response(&e, "Power off failed"),
     }
 }
 
@@ -417,7 +800,9 @@ pub async fn restart_server(
         Err(response) => return response,
     };
 
-    match client.reboot(None).await {
+    let result = client.reboot(None).await;
+    app_state.publish(NewEvent::server_power_action(server_id, "restart", result.as_ref().err().map(|e| e.to_string()))).await;
+    match result {
         Ok(_) => {
             let response = ApiResponse::success(serde_json::json!({
                 "message": "Server graceful restart command sent",
@@ -425,10 +810,7 @@ pub async fn restart_server(
             }));
             HttpResponse::Ok().json(response)
         },
-        Err(e) => {
-            let response = ApiResponse::<()>::error("BMC_ERROR", &format!("Restart failed: {}", e));
-            HttpResponse::InternalServerError().json(response)
-        }
+        Err(e) => bmc_error_response(&e, "Restart failed"),
     }
 }
 
@@ -444,7 +826,9 @@ pub async fn force_power_off_server(
         Err(response) => return response,
     };
 
-    match client.force_power_off(None).await {
+    let result = client.force_power_off(None).await;
+    app_state.publish(NewEvent::server_power_action(server_id, "force_off", result.as_ref().err().map(|e| e.to_string()))).await;
+    match result {
         Ok(_) => {
             let response = ApiResponse::success(serde_json::json!({
                 "message": "Server force power off command sent",
@@ -452,10 +836,7 @@ pub async fn force_power_off_server(
             }));
             HttpResponse::Ok().json(response)
         },
-        Err(e) => {
-            let response = ApiResponse::<()>::error("BMC_ERROR", &format!("Force power off failed: {}", e));
-            HttpResponse::InternalServerError().json(response)
-        }
+        Err(e) => bmc_error_response(&e, "Force power off failed"),
     }
 }
 
@@ -471,7 +852,9 @@ pub async fn force_restart_server(
         Err(response) => return response,
     };
 
-    match client.force_reboot(None).await {
+    let result = client.force_reboot(None).await;
+    app_state.publish(NewEvent::server_power_action(server_id, "force_restart", result.as_ref().err().map(|e| e.to_string()))).await;
+    match result {
         Ok(_) => {
             let response = ApiResponse::success(serde_json::json!({
                 "message": "Server force restart command sent",
@@ -479,10 +862,7 @@ pub async fn force_restart_server(
             }));
             HttpResponse::Ok().json(response)
         },
-        Err(e) => {
-            let response = ApiResponse::<()>::error("BMC_ERROR", &format!("Force restart failed: {}", e));
-            HttpResponse::InternalServerError().json(response)
-        }
+        Err(e) => bmc_error_response(&e, "Force restart failed"),
     }
 }
 
@@ -506,10 +886,7 @@ pub async fn get_power_status(
             }));
             HttpResponse::Ok().json(response)
         },
-        Err(e) => {
-            let response = ApiResponse::<()>::error("BMC_ERROR", &format!("Failed to get power state: {}", e));
-            HttpResponse::InternalServerError().json(response)
-        }
+        Err(e) => bmc_error_response(&e, "Failed to get power state"),
     }
 }
 
@@ -519,9 +896,17 @@ pub fn configure_server_routes(cfg: &mut web::ServiceConfig) {
             .service(index)
             .service(get_all_servers)
             .service(get_server_overview)
+            .service(get_duplicate_servers)
             .service(upsert_server_inventory)
             .service(get_server_by_id)
             .service(update_server)
+            .service(get_server_changes)
+            .service(get_server_missing_components)
+            .service(get_server_identity_conflicts)
+            .service(merge_servers)
+            .service(get_server_snapshots)
+            .service(diff_server_snapshots)
+            .service(get_server_snapshot)
             .service(power_on_server)
             .service(power_off_server)
             .service(restart_server)
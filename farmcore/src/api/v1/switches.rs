@@ -1,19 +1,20 @@
-use actix_web::{delete, get, post, put, web, HttpResponse, Responder};
+use actix_web::{delete, get, post, put, web, HttpResponse, Responder, ResponseError};
 use std::collections::HashMap;
 
 use crate::api::documentation::*;
-use crate::api::query_parser::CommonPaginationQuery;
-use crate::api::responses::ApiResponse;
-use crate::models::{Switch, SwitchPort, SwitchVlan};
+use crate::api::export::{export_response, ExportFormat};
+use crate::api::query_parser::{CommonPaginationQuery, QueryParser};
+use crate::api::responses::{ApiResponse, ApiMeta};
+use crate::models::{Switch, SwitchPort, SwitchVlan, SwitchWithPorts};
 use crate::state::AppState;
 
 // ===================================================================
 // API DOCUMENTATION (index)
 // ===================================================================
 
-#[get("")]
-pub async fn index() -> impl Responder {
-    let documentation = ApiDocumentation::new(
+/// Documentation for the switch endpoints; also feeds /api/v1/openapi.json
+pub fn documentation() -> ApiDocumentation {
+    ApiDocumentation::new(
         "Farm Switch API",
         "v1",
         "Network switch management endpoints",
@@ -22,14 +23,17 @@ pub async fn index() -> impl Responder {
     .with_response_format(standard_response_format())
     // --- Switches ---
     .add_endpoint(
-        EndpointDoc::new("/api/v1/switches", HttpMethod::Get, "List all switches with pagination and filtering")
+        EndpointDoc::new("/api/v1/switches/get_switches", HttpMethod::Get, "List all switches with pagination and filtering")
             .add_query_parameter(ParameterDoc::new("page", ParameterType::Integer, "Page number", false).with_default("1"))
             .add_query_parameter(ParameterDoc::new("per_page", ParameterType::Integer, "Items per page (max 100)", false).with_default("10"))
             .add_query_parameter(ParameterDoc::new("columns", ParameterType::String, "Comma-separated column list", false))
+            .add_query_parameter(ParameterDoc::new("sort", ParameterType::String, "Comma-separated sort columns, prefixed with - for descending", false).with_example("switch_name"))
+            .add_query_parameter(ParameterDoc::new("format", ParameterType::String, "csv or ndjson streams every matching row as a download (no page cap); columns, search and filters apply", false))
             .add_query_parameter(ParameterDoc::new("status", ParameterType::String, "Filter by status (ACTIVE, INACTIVE, MAINTENANCE, NEW, RMA, DECOMMISSIONED)", false))
             .add_query_parameter(ParameterDoc::new("switch_role", ParameterType::String, "Filter by role (ACCESS, DISTRIBUTION, CORE, EDGE, MANAGEMENT, OOB)", false))
             .add_response_code(ResponseCodeDoc::new(200, "Success"))
-            .add_response_code(ResponseCodeDoc::new(400, "Invalid parameters")),
+            .add_response_code(ResponseCodeDoc::new(400, "Invalid parameters"))
+            .with_response_schema::<Vec<Switch>>(),
     )
     .add_endpoint(
         EndpointDoc::new("/api/v1/switches/stats", HttpMethod::Get, "Get aggregate statistics for all switches")
@@ -39,7 +43,8 @@ pub async fn index() -> impl Responder {
         EndpointDoc::new("/api/v1/switches/{id}", HttpMethod::Get, "Get specific switch by ID (with ports and VLANs)")
             .add_path_parameter(ParameterDoc::new("id", ParameterType::Integer, "Switch ID", true))
             .add_response_code(ResponseCodeDoc::new(200, "Success"))
-            .add_response_code(ResponseCodeDoc::new(404, "Switch not found")),
+            .add_response_code(ResponseCodeDoc::new(404, "Switch not found"))
+            .with_response_schema::<SwitchWithPorts>(),
     )
     .add_endpoint(
         EndpointDoc::new("/api/v1/switches/{id}/stats", HttpMethod::Get, "Get statistics for a specific switch")
@@ -50,13 +55,15 @@ pub async fn index() -> impl Responder {
     .add_endpoint(
         EndpointDoc::new("/api/v1/switches", HttpMethod::Post, "Create a new switch")
             .add_response_code(ResponseCodeDoc::new(201, "Switch created"))
-            .add_response_code(ResponseCodeDoc::new(400, "Invalid data")),
+            .add_response_code(ResponseCodeDoc::new(400, "Invalid data"))
+            .with_request_schema::<Switch>(),
     )
     .add_endpoint(
         EndpointDoc::new("/api/v1/switches/{id}", HttpMethod::Put, "Update switch fields")
             .add_path_parameter(ParameterDoc::new("id", ParameterType::Integer, "Switch ID", true))
             .add_response_code(ResponseCodeDoc::new(200, "Success"))
-            .add_response_code(ResponseCodeDoc::new(404, "Switch not found")),
+            .add_response_code(ResponseCodeDoc::new(404, "Switch not found"))
+            .with_request_schema::<HashMap<String, serde_json::Value>>(),
     )
     .add_endpoint(
         EndpointDoc::new("/api/v1/switches/{id}", HttpMethod::Delete, "Delete a switch (cascades to ports and VLANs)")
@@ -68,24 +75,28 @@ pub async fn index() -> impl Responder {
     .add_endpoint(
         EndpointDoc::new("/api/v1/switches/{id}/ports", HttpMethod::Get, "Get all ports for a switch")
             .add_path_parameter(ParameterDoc::new("id", ParameterType::Integer, "Switch ID", true))
-            .add_response_code(ResponseCodeDoc::new(200, "Success")),
+            .add_response_code(ResponseCodeDoc::new(200, "Success"))
+            .with_response_schema::<Vec<SwitchPort>>(),
     )
     .add_endpoint(
         EndpointDoc::new("/api/v1/switches/ports/{port_id}", HttpMethod::Get, "Get a specific port by ID")
             .add_path_parameter(ParameterDoc::new("port_id", ParameterType::Integer, "Port ID", true))
             .add_response_code(ResponseCodeDoc::new(200, "Success"))
-            .add_response_code(ResponseCodeDoc::new(404, "Port not found")),
+            .add_response_code(ResponseCodeDoc::new(404, "Port not found"))
+            .with_response_schema::<SwitchPort>(),
     )
     .add_endpoint(
         EndpointDoc::new("/api/v1/switches/{id}/ports", HttpMethod::Post, "Add a port to a switch")
             .add_path_parameter(ParameterDoc::new("id", ParameterType::Integer, "Switch ID", true))
-            .add_response_code(ResponseCodeDoc::new(201, "Port created")),
+            .add_response_code(ResponseCodeDoc::new(201, "Port created"))
+            .with_request_schema::<SwitchPort>(),
     )
     .add_endpoint(
         EndpointDoc::new("/api/v1/switches/ports/{port_id}", HttpMethod::Put, "Update a port")
             .add_path_parameter(ParameterDoc::new("port_id", ParameterType::Integer, "Port ID", true))
             .add_response_code(ResponseCodeDoc::new(200, "Success"))
-            .add_response_code(ResponseCodeDoc::new(404, "Port not found")),
+            .add_response_code(ResponseCodeDoc::new(404, "Port not found"))
+            .with_request_schema::<HashMap<String, serde_json::Value>>(),
     )
     .add_endpoint(
         EndpointDoc::new("/api/v1/switches/ports/{port_id}", HttpMethod::Delete, "Delete a port")
@@ -97,21 +108,26 @@ pub async fn index() -> impl Responder {
     .add_endpoint(
         EndpointDoc::new("/api/v1/switches/{id}/vlans", HttpMethod::Get, "Get all VLANs configured on a switch")
             .add_path_parameter(ParameterDoc::new("id", ParameterType::Integer, "Switch ID", true))
-            .add_response_code(ResponseCodeDoc::new(200, "Success")),
+            .add_response_code(ResponseCodeDoc::new(200, "Success"))
+            .with_response_schema::<Vec<SwitchVlan>>(),
     )
     .add_endpoint(
         EndpointDoc::new("/api/v1/switches/{id}/vlans", HttpMethod::Post, "Add a VLAN to a switch")
             .add_path_parameter(ParameterDoc::new("id", ParameterType::Integer, "Switch ID", true))
-            .add_response_code(ResponseCodeDoc::new(201, "VLAN created")),
+            .add_response_code(ResponseCodeDoc::new(201, "VLAN created"))
+            .with_request_schema::<SwitchVlan>(),
     )
     .add_endpoint(
         EndpointDoc::new("/api/v1/switches/vlans/{vlan_db_id}", HttpMethod::Delete, "Remove a VLAN from a switch")
             .add_path_parameter(ParameterDoc::new("vlan_db_id", ParameterType::Integer, "VLAN DB record ID", true))
             .add_response_code(ResponseCodeDoc::new(200, "Success"))
             .add_response_code(ResponseCodeDoc::new(404, "VLAN not found")),
-    );
+    )
+}
 
-    let response = ApiResponse::success(documentation);
+#[get("")]
+pub async fn index() -> impl Responder {
+    let response = ApiResponse::success(documentation());
     HttpResponse::Ok().json(response)
 }
 
@@ -124,8 +140,28 @@ pub async fn get_all_switches(
     app_state: web::Data<AppState>,
     query: web::Query<CommonPaginationQuery>,
 ) -> impl Responder {
-    match app_state.switch_repo().get_all_switches(query.into_inner()).await {
-        Ok(switches) => HttpResponse::Ok().json(ApiResponse::success(switches)),
+    if let Err(e) = QueryParser::validate(&query, &Switch::SCHEMA) {
+        return e.error_response();
+    }
+
+    if let Some(format) = ExportFormat::from_query(&query) {
+        let rows = app_state.switch_repo().stream_switches(query.clone().into_inner());
+        return export_response(format, &Switch::SCHEMA, &query, rows);
+    }
+
+    let query = query.into_inner();
+    match app_state.switch_repo().get_all_switches(query.clone()).await {
+        Ok((switches, total_count)) => {
+            let last_key = switches.last().map(|switch| switch.switch_id as i64);
+            let meta = ApiMeta {
+                pagination: Some(QueryParser::pagination_meta(&query, &Switch::SCHEMA, switches.len(), total_count, last_key)),
+                filters_applied: None,
+                request_id: None,
+                timestamp: chrono::Utc::now(),
+            };
+            let response = ApiResponse::success_with_meta(switches, meta);
+            HttpResponse::Ok().json(response)
+        },
         Err(e) => {
             log::error!("Database error fetching switches: {}", e);
             HttpResponse::InternalServerError()
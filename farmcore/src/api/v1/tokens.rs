@@ -0,0 +1,179 @@
+use actix_web::{delete, get, post, web, HttpResponse, Responder};
+
+use crate::api::documentation::*;
+use crate::api::responses::ApiResponse;
+use crate::models::{ApiToken, CreateApiToken, IssuedApiToken, TokenScope};
+use crate::state::AppState;
+
+// ===================================================================
+// API DOCUMENTATION (index)
+// ===================================================================
+
+/// Documentation for the API token endpoints; also feeds /api/v1/openapi.json
+pub fn documentation() -> ApiDocumentation {
+    ApiDocumentation::new(
+        "Farm API Token Management",
+        "v1",
+        "Issue, list, and revoke bearer tokens for API clients and agents (ADMIN role required)",
+        "/api/v1",
+    )
+    .with_response_format(standard_response_format())
+    .with_authentication(bearer_auth_doc())
+    .add_endpoint(
+        EndpointDoc::new("/api/v1/tokens/list", HttpMethod::Get, "List all API tokens (hashes are never returned)")
+            .add_response_code(ResponseCodeDoc::new(200, "Success"))
+            .with_response_schema::<Vec<ApiToken>>(),
+    )
+    .add_endpoint(
+        EndpointDoc::new("/api/v1/tokens/{id}", HttpMethod::Get, "Get a specific token's metadata")
+            .add_path_parameter(ParameterDoc::new("id", ParameterType::Integer, "Token ID", true))
+            .add_response_code(ResponseCodeDoc::new(200, "Success"))
+            .add_response_code(ResponseCodeDoc::new(404, "Token not found"))
+            .with_response_schema::<ApiToken>(),
+    )
+    .add_endpoint(
+        EndpointDoc::new("/api/v1/tokens", HttpMethod::Post, "Issue a new API or AGENT token; API tokens take a role (VIEWER by default), AGENT tokens have none. The plaintext is only returned once")
+            .add_example(
+                ExampleDoc::new("Issue an agent token", "/api/v1/tokens")
+                    .with_method(HttpMethod::Post)
+                    .with_request_body(serde_json::json!({
+                        "token_name": "rack-a12-agents",
+                        "scope": "AGENT",
+                        "description": "farm-manager agents in rack A12"
+                    })),
+            )
+            .add_example(
+                ExampleDoc::new("Issue an operator token for automation", "/api/v1/tokens")
+                    .with_method(HttpMethod::Post)
+                    .with_request_body(serde_json::json!({
+                        "token_name": "power-automation",
+                        "scope": "API",
+                        "role": "OPERATOR"
+                    })),
+            )
+            .add_response_code(ResponseCodeDoc::new(201, "Token issued"))
+            .add_response_code(ResponseCodeDoc::new(400, "Invalid data"))
+            .add_response_code(ResponseCodeDoc::new(409, "A token with this name already exists"))
+            .with_request_schema::<CreateApiToken>()
+            .with_response_schema::<IssuedApiToken>(),
+    )
+    .add_endpoint(
+        EndpointDoc::new("/api/v1/tokens/{id}", HttpMethod::Delete, "Revoke a token")
+            .add_path_parameter(ParameterDoc::new("id", ParameterType::Integer, "Token ID", true))
+            .add_response_code(ResponseCodeDoc::new(200, "Token revoked"))
+            .add_response_code(ResponseCodeDoc::new(404, "Token not found or already revoked")),
+    )
+}
+
+#[get("")]
+pub async fn index() -> impl Responder {
+    let response = ApiResponse::success(documentation());
+    HttpResponse::Ok().json(response)
+}
+
+// ===================================================================
+// TOKEN ENDPOINTS
+// ===================================================================
+
+#[get("/list")]
+pub async fn get_all_tokens(app_state: web::Data<AppState>) -> impl Responder {
+    match app_state.token_repo().get_all_tokens().await {
+        Ok(tokens) => HttpResponse::Ok().json(ApiResponse::success(tokens)),
+        Err(e) => {
+            log::error!("Database error fetching API tokens: {}", e);
+            HttpResponse::InternalServerError()
+                .json(ApiResponse::<()>::error("DATABASE_ERROR", "Failed to fetch API tokens"))
+        }
+    }
+}
+
+#[get("/{id}")]
+pub async fn get_token_by_id(
+    app_state: web::Data<AppState>,
+    id: web::Path<i64>,
+) -> impl Responder {
+    let token_id = id.into_inner() as i32;
+    match app_state.token_repo().get_token_by_id(token_id).await {
+        Ok(Some(token)) => HttpResponse::Ok().json(ApiResponse::success(token)),
+        Ok(None) => HttpResponse::NotFound().json(ApiResponse::<()>::error(
+            "NOT_FOUND",
+            &format!("Token with ID {} not found", token_id),
+        )),
+        Err(e) => {
+            log::error!("Database error fetching token {}: {}", token_id, e);
+            HttpResponse::InternalServerError()
+                .json(ApiResponse::<()>::error("DATABASE_ERROR", "Failed to fetch API token"))
+        }
+    }
+}
+
+#[post("")]
+pub async fn create_token(
+    app_state: web::Data<AppState>,
+    request: web::Json<CreateApiToken>,
+) -> impl Responder {
+    let request = request.into_inner();
+
+    if request.token_name.trim().is_empty() {
+        return HttpResponse::BadRequest()
+            .json(ApiResponse::<()>::error("VALIDATION_ERROR", "token_name must not be empty"));
+    }
+
+    if request.scope == TokenScope::Agent && request.role.is_some() {
+        return HttpResponse::BadRequest()
+            .json(ApiResponse::<()>::error("VALIDATION_ERROR", "AGENT tokens do not take a role"));
+    }
+
+    match app_state.token_repo().create_token(request).await {
+        Ok(issued) => HttpResponse::Created().json(ApiResponse::success(issued)),
+        Err(e) => {
+            log::error!("Error creating API token: {}", e);
+            if e.to_string().contains("Duplicate entry") {
+                return HttpResponse::Conflict().json(ApiResponse::<()>::error(
+                    "CONFLICT",
+                    "A token with this name already exists",
+                ));
+            }
+            HttpResponse::InternalServerError()
+                .json(ApiResponse::<()>::error("DATABASE_ERROR", "Failed to create API token"))
+        }
+    }
+}
+
+#[delete("/{id}")]
+pub async fn revoke_token(
+    app_state: web::Data<AppState>,
+    id: web::Path<i64>,
+) -> impl Responder {
+    let token_id = id.into_inner() as i32;
+    match app_state.token_repo().revoke_token(token_id).await {
+        Ok(true) => HttpResponse::Ok().json(ApiResponse::success(serde_json::json!({
+            "message": "Token revoked successfully",
+            "token_id": token_id
+        }))),
+        Ok(false) => HttpResponse::NotFound().json(ApiResponse::<()>::error(
+            "NOT_FOUND",
+            &format!("Token with ID {} not found or already revoked", token_id),
+        )),
+        Err(e) => {
+            log::error!("Error revoking token {}: {}", token_id, e);
+            HttpResponse::InternalServerError()
+                .json(ApiResponse::<()>::error("DELETE_ERROR", "Failed to revoke token"))
+        }
+    }
+}
+
+// ===================================================================
+// ROUTE CONFIGURATION
+// ===================================================================
+
+pub fn configure_token_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/tokens")
+            .service(index)
+            .service(get_all_tokens)
+            .service(get_token_by_id)
+            .service(create_token)
+            .service(revoke_token),
+    );
+}
@@ -0,0 +1,120 @@
+use actix_web::{get, post, web, HttpResponse, Responder};
+use serde::Deserialize;
+
+use crate::api::responses::ApiResponse;
+use crate::api::documentation::*;
+use crate::domain::TopologyFailure;
+use crate::state::AppState;
+use crate::models::{TopologyApplyResult, TopologyDocument, TopologyPlan};
+
+/// Documentation for the topology endpoints; also feeds /api/v1/openapi.json
+pub fn documentation() -> ApiDocumentation {
+    ApiDocumentation::new(
+        "Farm Topology API",
+        "v1",
+        "Declarative plan/apply of datacenters, racks, clusters, switches and VLANs",
+        "/api/v1"
+    )
+    .with_response_format(standard_response_format())
+    .add_endpoint(
+        EndpointDoc::new("/api/v1/topology/plan", HttpMethod::Post,
+            "Compare a desired-state YAML document with the database and list the creates, updates and deletes that would reconcile them. \
+             The document has datacenters (with racks), clusters (with sub_clusters) and switches (with vlans) sections, identified by code. \
+             A section or child list that is present is authoritative: existing entries it leaves out are planned for deletion. \
+             Updates list the drifted fields with their current and desired values.")
+            .add_response_code(ResponseCodeDoc::new(200, "Plan computed"))
+            .add_response_code(ResponseCodeDoc::new(400, "Unreadable YAML"))
+            .add_response_code(ResponseCodeDoc::new(422, "Invalid document; details list each error"))
+            .with_response_schema::<TopologyPlan>()
+    )
+    .add_endpoint(
+        EndpointDoc::new("/api/v1/topology/apply", HttpMethod::Post,
+            "Plan the document and apply the changes in one transaction. Pass the fingerprint of a reviewed plan \
+             to refuse the apply if the database has changed since.")
+            .add_query_parameter(ParameterDoc::new("plan", ParameterType::String, "Fingerprint returned by /plan", false))
+            .add_response_code(ResponseCodeDoc::new(200, "Applied, or nothing to change"))
+            .add_response_code(ResponseCodeDoc::new(400, "Unreadable YAML"))
+            .add_response_code(ResponseCodeDoc::new(409, "The plan no longer matches the fingerprint; details hold the current plan"))
+            .add_response_code(ResponseCodeDoc::new(422, "Invalid document; details list each error"))
+            .with_response_schema::<TopologyApplyResult>()
+    )
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ApplyQuery {
+    /// Fingerprint of the reviewed plan
+    pub plan: Option<String>,
+}
+
+#[get("")]
+pub async fn index() -> impl Responder {
+    let response = ApiResponse::success(documentation());
+    HttpResponse::Ok().json(response)
+}
+
+#[post("/plan")]
+pub async fn plan_topology(app_state: web::Data<AppState>, body: web::Bytes) -> impl Responder {
+    let document = match parse_document(&body) {
+        Ok(document) => document,
+        Err(response) => return response,
+    };
+
+    match app_state.topology_service().plan(document).await {
+        Ok(plan) => HttpResponse::Ok().json(ApiResponse::success(plan)),
+        Err(e) => failure_response(e, "plan"),
+    }
+}
+
+#[post("/apply")]
+pub async fn apply_topology(
+    app_state: web::Data<AppState>,
+    query: web::Query<ApplyQuery>,
+    body: web::Bytes,
+) -> impl Responder {
+    let document = match parse_document(&body) {
+        Ok(document) => document,
+        Err(response) => return response,
+    };
+
+    match app_state.topology_service().apply(document, query.plan.as_deref()).await {
+        Ok(result) => HttpResponse::Ok().json(ApiResponse::success(result)),
+        Err(e) => failure_response(e, "apply"),
+    }
+}
+
+fn parse_document(body: &[u8]) -> Result<TopologyDocument, HttpResponse> {
+    serde_yaml::from_slice(body).map_err(|e| {
+        HttpResponse::BadRequest()
+            .json(ApiResponse::<()>::error("VALIDATION_ERROR", &format!("Invalid topology document: {}", e)))
+    })
+}
+
+fn failure_response(failure: TopologyFailure, operation: &str) -> HttpResponse {
+    match failure {
+        TopologyFailure::Invalid(errors) => {
+            let message = format!("{} error(s) in the topology document; nothing was changed", errors.len());
+            let details = serde_json::json!({ "errors": errors });
+            HttpResponse::UnprocessableEntity()
+                .json(ApiResponse::<()>::error_with_details("TOPOLOGY_VALIDATION_ERROR", &message, details))
+        }
+        TopologyFailure::Stale(plan) => {
+            let details = serde_json::to_value(&plan).unwrap_or_default();
+            HttpResponse::Conflict()
+                .json(ApiResponse::<()>::error_with_details("PLAN_STALE", "The topology changed since the plan was made", details))
+        }
+        TopologyFailure::Database(e) => {
+            log::error!("Error during topology {}: {}", operation, e);
+            HttpResponse::InternalServerError()
+                .json(ApiResponse::<()>::error("DATABASE_ERROR", &format!("Failed to {} topology", operation)))
+        }
+    }
+}
+
+pub fn configure_topology_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/topology")
+            .service(index)
+            .service(plan_topology)
+            .service(apply_topology)
+    );
+}
@@ -1,14 +1,20 @@
-use actix_web::{get, post, put, web, HttpResponse, Responder};
+use actix_web::{get, post, put, web, HttpResponse, Responder, ResponseError};
 use std::collections::HashMap;
 
-use crate::api::responses::ApiResponse;
+use crate::api::responses::{ApiResponse, ApiMeta};
 use crate::api::documentation::*;
-use crate::api::query_parser::CommonPaginationQuery;
+use crate::api::export::{export_response, ExportFormat};
+use crate::api::query_parser::{CommonPaginationQuery, QueryParser};
+use crate::api::v1::decode_agent_payload;
 use crate::state::AppState;
+use crate::domain::NewEvent;
+use crate::metrics;
+use crate::models::{VirtualMachine, VmWithAllComponents};
+use crate::repositories::vm_repository::VmInventory;
 
-#[get("")]
-pub async fn index() -> impl Responder {
-    let documentation = ApiDocumentation::new(
+/// Documentation for the virtual machine endpoints; also feeds /api/v1/openapi.json
+pub fn documentation() -> ApiDocumentation {
+    ApiDocumentation::new(
         "Farm VM API",
         "v1",
         "Virtual machine management endpoints",
@@ -21,10 +27,15 @@ pub async fn index() -> impl Responder {
             .add_query_parameter(ParameterDoc::new("page", ParameterType::Integer, "Page number", false).with_default("1"))
             .add_query_parameter(ParameterDoc::new("per_page", ParameterType::Integer, "Items per page", false).with_default("10"))
             .add_query_parameter(ParameterDoc::new("columns", ParameterType::String, "Comma-separated column list", false))
+            .add_query_parameter(ParameterDoc::new("sort", ParameterType::String, "Comma-separated sort columns, prefixed with - for descending", false).with_example("-created_at,vm_name"))
+            .add_query_parameter(ParameterDoc::new("format", ParameterType::String, "csv or ndjson streams every matching row as a download (no page cap); columns, search and filters apply", false))
+            .add_query_parameter(ParameterDoc::new("after", ParameterType::String, "Cursor from meta.pagination.next_cursor; empty for the first page. Pages by primary key, up to 1000 per page", false))
+            .add_query_parameter(ParameterDoc::new("count", ParameterType::Boolean, "Set to false to skip the total count", false).with_default("true"))
             .add_example(ExampleDoc::new("Get running VMs", "/api/v1/vms/get_vms?vm_state=running"))
-            .add_example(ExampleDoc::new("Search Ubuntu VMs", "/api/v1/vms/get_vms?guest_os_version_like=ubuntu"))
+            .add_example(ExampleDoc::new("Search Ubuntu VMs", r#"/api/v1/vms/get_vms?search=[{"column":"guest_os_version","term":"ubuntu","comparisonOperator":"LIKE"}]"#))
             .add_response_code(ResponseCodeDoc::new(200, "Success"))
             .add_response_code(ResponseCodeDoc::new(400, "Invalid parameters"))
+            .with_response_schema::<Vec<VirtualMachine>>()
     )
     .add_endpoint(
         EndpointDoc::new("/api/v1/vms/{id}", HttpMethod::Get, "Get specific VM by ID with complete details")
@@ -32,6 +43,7 @@ pub async fn index() -> impl Responder {
             .add_example(ExampleDoc::new("Get VM 123", "/api/v1/vms/123"))
             .add_response_code(ResponseCodeDoc::new(200, "Success - Returns VM with disks, network interfaces, and snapshots"))
             .add_response_code(ResponseCodeDoc::new(404, "VM not found"))
+            .with_response_schema::<VmWithAllComponents>()
     )
     .add_endpoint(
         EndpointDoc::new("/api/v1/vms/server/{server_id}", HttpMethod::Get, "Get all VMs for a specific server")
@@ -39,6 +51,7 @@ pub async fn index() -> impl Responder {
             .add_example(ExampleDoc::new("Get VMs for server 5", "/api/v1/vms/server/5"))
             .add_response_code(ResponseCodeDoc::new(200, "Success - Returns list of VMs for the server"))
             .add_response_code(ResponseCodeDoc::new(404, "Server not found"))
+            .with_response_schema::<Vec<VirtualMachine>>()
     )
     .add_endpoint(
         EndpointDoc::new("/api/v1/vms/overview", HttpMethod::Get, "Get VM overview statistics")
@@ -50,16 +63,21 @@ pub async fn index() -> impl Responder {
             .add_response_code(ResponseCodeDoc::new(200, "Success - VM updated"))
             .add_response_code(ResponseCodeDoc::new(400, "Invalid field or value"))
             .add_response_code(ResponseCodeDoc::new(404, "VM not found"))
+            .with_request_schema::<HashMap<String, serde_json::Value>>()
     )
     .add_endpoint(
         EndpointDoc::new("/api/v1/vms/inventory", HttpMethod::Post, "Create or update VMs from inventory data. Server is identified by host MAC address.")
             .add_example(ExampleDoc::new("Post VM inventory", "/api/v1/vms/inventory"))
             .add_response_code(ResponseCodeDoc::new(200, "Success - VMs created or updated"))
-            .add_response_code(ResponseCodeDoc::new(400, "Invalid inventory data or server not found"))
+            .add_response_code(ResponseCodeDoc::new(400, "Invalid inventory data, unsupported schema_version or server not found"))
             .add_response_code(ResponseCodeDoc::new(500, "Database error"))
-    );
+            .with_request_schema::<VmInventory>()
+    )
+}
 
-    let response = ApiResponse::success(documentation);
+#[get("")]
+pub async fn index() -> impl Responder {
+    let response = ApiResponse::success(documentation());
     HttpResponse::Ok().json(response)
 }
 
@@ -68,9 +86,26 @@ pub async fn get_all_vms(
     app_state: web::Data<AppState>, 
     query: web::Query<CommonPaginationQuery>
 ) -> impl Responder {
-    match app_state.vm_repo().get_all_vms(query.into_inner()).await {
-        Ok(vms) => {
-            let response = ApiResponse::success(vms);
+    if let Err(e) = QueryParser::validate(&query, &VirtualMachine::SCHEMA) {
+        return e.error_response();
+    }
+
+    if let Some(format) = ExportFormat::from_query(&query) {
+        let rows = app_state.vm_repo().stream_vms(query.clone().into_inner());
+        return export_response(format, &VirtualMachine::SCHEMA, &query, rows);
+    }
+
+    let query = query.into_inner();
+    match app_state.vm_repo().get_all_vms(query.clone()).await {
+        Ok((vms, total_count)) => {
+            let last_key = vms.last().map(|vm| vm.vm_id as i64);
+            let meta = ApiMeta {
+                pagination: Some(QueryParser::pagination_meta(&query, &VirtualMachine::SCHEMA, vms.len(), total_count, last_key)),
+                filters_applied: None,
+                request_id: None,
+                timestamp: chrono::Utc::now(),
+            };
+            let response = ApiResponse::success_with_meta(vms, meta);
             HttpResponse::Ok().json(response)
         },
         Err(e) => {
@@ -214,13 +249,23 @@ pub async fn update_vm(
 #[post("/inventory")]
 pub async fn upsert_vm_inventory(
     app_state: web::Data<AppState>,
-    inventory: web::Json<crate::repositories::vm_repository::VmInventory>
+    payload: web::Json<serde_json::Value>
 ) -> impl Responder {
+    let inventory: VmInventory = match decode_agent_payload(payload.into_inner()) {
+        Ok(inventory) => inventory,
+        Err(response) => return response,
+    };
     log::info!("Received VM inventory for host MAC: {}, VMs count: {}", 
         inventory.host_mac_address, inventory.vms.len());
     
-    match app_state.vm_repo().upsert_vm_from_inventory(inventory.into_inner()).await {
+    let host_mac_address = inventory.host_mac_address.clone();
+    let result = app_state.vm_repo().upsert_vm_from_inventory(inventory).await;
+    metrics::observe_inventory_ingest("vm", result.is_ok());
+    match result {
         Ok(results) => {
+            for (vm_id, was_created) in &results {
+                app_state.publish(NewEvent::vm_inventory(*vm_id, *was_created, &host_mac_address)).await;
+            }
             let created_count = results.iter().filter(|(_, was_created)| *was_created).count();
             let updated_count = results.len() - created_count;
             let vm_ids: Vec<i32> = results.iter().map(|(id, _)| *id).collect();
@@ -0,0 +1,421 @@
+use actix_web::{delete, get, post, put, web, HttpResponse, Responder, ResponseError};
+
+use crate::api::documentation::*;
+use crate::api::query_parser::{CommonPaginationQuery, QueryParser};
+use crate::api::responses::{ApiMeta, ApiResponse};
+use crate::domain::{EventEntity, EventType};
+use crate::models::{CreateWebhook, CreatedWebhook, UpdateWebhook, WebhookDelivery, WebhookSubscription};
+use crate::state::AppState;
+
+/// Event type of the deliveries queued by POST /webhooks/{id}/ping
+const PING_EVENT: &str = "webhook.ping";
+
+// ===================================================================
+// API DOCUMENTATION (index)
+// ===================================================================
+
+/// Documentation for the webhook endpoints; also feeds /api/v1/openapi.json
+pub fn documentation() -> ApiDocumentation {
+    ApiDocumentation::new(
+        "Farm Webhook API",
+        "v1",
+        "Subscribe HTTP endpoints to domain events, with signed deliveries, retries and a delivery log (ADMIN role required)",
+        "/api/v1",
+    )
+    .with_response_format(standard_response_format())
+    .with_authentication(bearer_auth_doc())
+    .add_endpoint(
+        EndpointDoc::new("/api/v1/webhooks/list", HttpMethod::Get, "List all webhooks (secrets are never returned)")
+            .add_response_code(ResponseCodeDoc::new(200, "Success"))
+            .with_response_schema::<Vec<WebhookSubscription>>(),
+    )
+    .add_endpoint(
+        EndpointDoc::new("/api/v1/webhooks/{id}", HttpMethod::Get, "Get a specific webhook")
+            .add_path_parameter(ParameterDoc::new("id", ParameterType::Integer, "Webhook ID", true))
+            .add_response_code(ResponseCodeDoc::new(200, "Success"))
+            .add_response_code(ResponseCodeDoc::new(404, "Webhook not found"))
+            .with_response_schema::<WebhookSubscription>(),
+    )
+    .add_endpoint(
+        EndpointDoc::new("/api/v1/webhooks", HttpMethod::Post,
+            "Subscribe a URL to events. event_types takes event names (see /api/v1/events), `entity.*` or `*`. \
+             Each delivery is a POST of the event as JSON (the same document as the SSE data line) with headers \
+             X-Farm-Event, X-Farm-Delivery, X-Farm-Timestamp and X-Farm-Signature: sha256=<hex HMAC-SHA256 of \
+             \"{timestamp}.{body}\" keyed with the secret>. Receivers should recompute the signature and reject stale \
+             timestamps. Any 2xx response acknowledges a delivery; otherwise it is retried with exponential backoff \
+             (10s doubling, at most 1h apart) and marked FAILED after 8 attempts. \
+             The secret is generated unless given, and only returned in this response.")
+            .add_example(
+                ExampleDoc::new("Alert on RMA and failed power actions", "/api/v1/webhooks")
+                    .with_method(HttpMethod::Post)
+                    .with_request_body(serde_json::json!({
+                        "webhook_name": "ops-alerts",
+                        "url": "https://alerts.example.com/farm",
+                        "event_types": ["server.status_changed", "server.component_removed", "server.power_action_failed"]
+                    })),
+            )
+            .add_response_code(ResponseCodeDoc::new(201, "Webhook created"))
+            .add_response_code(ResponseCodeDoc::new(400, "Invalid data"))
+            .add_response_code(ResponseCodeDoc::new(409, "A webhook with this name already exists"))
+            .with_request_schema::<CreateWebhook>()
+            .with_response_schema::<CreatedWebhook>(),
+    )
+    .add_endpoint(
+        EndpointDoc::new("/api/v1/webhooks/{id}", HttpMethod::Put,
+            "Change a webhook; omitted fields are left as they are. Deliveries of an inactive webhook are held, not dropped")
+            .add_path_parameter(ParameterDoc::new("id", ParameterType::Integer, "Webhook ID", true))
+            .add_response_code(ResponseCodeDoc::new(200, "Webhook updated"))
+            .add_response_code(ResponseCodeDoc::new(400, "Invalid data"))
+            .add_response_code(ResponseCodeDoc::new(404, "Webhook not found"))
+            .add_response_code(ResponseCodeDoc::new(409, "A webhook with this name already exists"))
+            .with_request_schema::<UpdateWebhook>()
+            .with_response_schema::<WebhookSubscription>(),
+    )
+    .add_endpoint(
+        EndpointDoc::new("/api/v1/webhooks/{id}", HttpMethod::Delete, "Delete a webhook and its delivery log")
+            .add_path_parameter(ParameterDoc::new("id", ParameterType::Integer, "Webhook ID", true))
+            .add_response_code(ResponseCodeDoc::new(200, "Webhook deleted"))
+            .add_response_code(ResponseCodeDoc::new(404, "Webhook not found")),
+    )
+    .add_endpoint(
+        EndpointDoc::new("/api/v1/webhooks/{id}/ping", HttpMethod::Post,
+            "Queue a signed `webhook.ping` delivery to check a receiver; follow it in the delivery log")
+            .add_path_parameter(ParameterDoc::new("id", ParameterType::Integer, "Webhook ID", true))
+            .add_response_code(ResponseCodeDoc::new(202, "Ping queued"))
+            .add_response_code(ResponseCodeDoc::new(404, "Webhook not found")),
+    )
+    .add_endpoint(
+        EndpointDoc::new("/api/v1/webhooks/deliveries", HttpMethod::Get, "Paginated delivery log, newest first")
+            .add_query_parameter(ParameterDoc::new("page", ParameterType::Integer, "Page number", false).with_default("1"))
+            .add_query_parameter(ParameterDoc::new("per_page", ParameterType::Integer, "Items per page (max 100)", false).with_default("10"))
+            .add_query_parameter(ParameterDoc::new("sort", ParameterType::String, "Comma-separated sort columns, prefixed with - for descending", false).with_example("-created_at"))
+            .add_query_parameter(ParameterDoc::new("after", ParameterType::String, "Cursor from meta.pagination.next_cursor; empty for the first page. Pages by primary key, up to 1000 per page", false))
+            .add_query_parameter(ParameterDoc::new("count", ParameterType::Boolean, "Set to false to skip the total count", false).with_default("true"))
+            .add_query_parameter(ParameterDoc::new("search", ParameterType::String, "JSON search criteria", false))
+            .add_example(ExampleDoc::new(
+                "Failed deliveries of webhook 3",
+                "/api/v1/webhooks/deliveries?webhook_id=3&status=FAILED",
+            ))
+            .add_response_code(ResponseCodeDoc::new(200, "Success"))
+            .add_response_code(ResponseCodeDoc::new(400, "Invalid parameters"))
+            .with_response_schema::<Vec<WebhookDelivery>>(),
+    )
+    .add_endpoint(
+        EndpointDoc::new("/api/v1/webhooks/deliveries/{id}", HttpMethod::Get, "Get a single delivery with its payload and last error")
+            .add_path_parameter(ParameterDoc::new("id", ParameterType::Integer, "Delivery ID", true))
+            .add_response_code(ResponseCodeDoc::new(200, "Success"))
+            .add_response_code(ResponseCodeDoc::new(404, "Delivery not found"))
+            .with_response_schema::<WebhookDelivery>(),
+    )
+}
+
+#[get("")]
+pub async fn index() -> impl Responder {
+    let response = ApiResponse::success(documentation());
+    HttpResponse::Ok().json(response)
+}
+
+// ===================================================================
+// VALIDATION
+// ===================================================================
+
+fn validate_url(value: &str) -> Result<(), String> {
+    match url::Url::parse(value) {
+        Ok(url) if matches!(url.scheme(), "http" | "https") && url.host().is_some() => Ok(()),
+        Ok(_) => Err("url must be an absolute http or https URL".to_string()),
+        Err(e) => Err(format!("Invalid url: {}", e)),
+    }
+}
+
+/// Each entry is an event name, `entity.*` or `*`
+fn validate_event_types(event_types: &[String]) -> Result<(), String> {
+    if event_types.is_empty() {
+        return Err("event_types must list at least one event type".to_string());
+    }
+    for pattern in event_types {
+        let valid = match pattern.strip_suffix(".*") {
+            Some(entity) => EventEntity::parse(entity).is_some(),
+            None => pattern == "*" || EventType::parse(pattern).is_some(),
+        };
+        if !valid {
+            return Err(format!(
+                "Unknown event type '{}'; expected one of {}, an entity wildcard like server.* or *",
+                pattern,
+                EventType::NAMES.join(", ")
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn validation_error(message: &str) -> HttpResponse {
+    HttpResponse::BadRequest().json(ApiResponse::<()>::error("VALIDATION_ERROR", message))
+}
+
+fn is_duplicate_name(e: &sqlx::Error) -> bool {
+    e.as_database_error().is_some_and(|db| db.is_unique_violation())
+}
+
+// ===================================================================
+// WEBHOOK ENDPOINTS
+// ===================================================================
+
+#[get("/list")]
+pub async fn get_all_webhooks(app_state: web::Data<AppState>) -> impl Responder {
+    match app_state.webhook_repo().get_all_webhooks().await {
+        Ok(webhooks) => HttpResponse::Ok().json(ApiResponse::success(webhooks)),
+        Err(e) => {
+            log::error!("Database error fetching webhooks: {}", e);
+            HttpResponse::InternalServerError()
+                .json(ApiResponse::<()>::error("DATABASE_ERROR", "Failed to fetch webhooks"))
+        }
+    }
+}
+
+#[get("/{id}")]
+pub async fn get_webhook_by_id(
+    app_state: web::Data<AppState>,
+    id: web::Path<i64>,
+) -> impl Responder {
+    let webhook_id = id.into_inner() as i32;
+    match app_state.webhook_repo().get_webhook_by_id(webhook_id).await {
+        Ok(Some(webhook)) => HttpResponse::Ok().json(ApiResponse::success(webhook)),
+        Ok(None) => HttpResponse::NotFound().json(ApiResponse::<()>::error(
+            "NOT_FOUND",
+            &format!("Webhook with ID {} not found", webhook_id),
+        )),
+        Err(e) => {
+            log::error!("Database error fetching webhook {}: {}", webhook_id, e);
+            HttpResponse::InternalServerError()
+                .json(ApiResponse::<()>::error("DATABASE_ERROR", "Failed to fetch webhook"))
+        }
+    }
+}
+
+#[post("")]
+pub async fn create_webhook(
+    app_state: web::Data<AppState>,
+    request: web::Json<CreateWebhook>,
+) -> impl Responder {
+    let request = request.into_inner();
+
+    if request.webhook_name.trim().is_empty() {
+        return validation_error("webhook_name must not be empty");
+    }
+    if request.secret.as_deref().is_some_and(|secret| secret.is_empty()) {
+        return validation_error("secret must not be empty; omit it to have one generated");
+    }
+    if let Err(message) = validate_url(&request.url).and_then(|_| validate_event_types(&request.event_types)) {
+        return validation_error(&message);
+    }
+
+    match app_state.webhook_repo().create_webhook(request).await {
+        Ok(created) => HttpResponse::Created().json(ApiResponse::success(created)),
+        Err(e) if is_duplicate_name(&e) => HttpResponse::Conflict().json(ApiResponse::<()>::error(
+            "CONFLICT",
+            "A webhook with this name already exists",
+        )),
+        Err(e) => {
+            log::error!("Error creating webhook: {}", e);
+            HttpResponse::InternalServerError()
+                .json(ApiResponse::<()>::error("DATABASE_ERROR", "Failed to create webhook"))
+        }
+    }
+}
+
+#[put("/{id}")]
+pub async fn update_webhook(
+    app_state: web::Data<AppState>,
+    id: web::Path<i64>,
+    request: web::Json<UpdateWebhook>,
+) -> impl Responder {
+    let webhook_id = id.into_inner() as i32;
+    let request = request.into_inner();
+
+    if request.webhook_name.as_deref().is_some_and(|name| name.trim().is_empty()) {
+        return validation_error("webhook_name must not be empty");
+    }
+    if request.secret.as_deref().is_some_and(|secret| secret.is_empty()) {
+        return validation_error("secret must not be empty");
+    }
+    if let Some(url) = &request.url {
+        if let Err(message) = validate_url(url) {
+            return validation_error(&message);
+        }
+    }
+    if let Some(event_types) = &request.event_types {
+        if let Err(message) = validate_event_types(event_types) {
+            return validation_error(&message);
+        }
+    }
+
+    let repo = app_state.webhook_repo();
+    match repo.update_webhook(webhook_id, request).await {
+        Ok(_) => {}
+        Err(e) if is_duplicate_name(&e) => {
+            return HttpResponse::Conflict().json(ApiResponse::<()>::error(
+                "CONFLICT",
+                "A webhook with this name already exists",
+            ));
+        }
+        Err(e) => {
+            log::error!("Error updating webhook {}: {}", webhook_id, e);
+            return HttpResponse::InternalServerError()
+                .json(ApiResponse::<()>::error("UPDATE_ERROR", "Failed to update webhook"));
+        }
+    }
+
+    // An update that changes nothing affects no rows, so existence is settled by the read
+    match repo.get_webhook_by_id(webhook_id).await {
+        Ok(Some(webhook)) => HttpResponse::Ok().json(ApiResponse::success(webhook)),
+        Ok(None) => HttpResponse::NotFound().json(ApiResponse::<()>::error(
+            "NOT_FOUND",
+            &format!("Webhook with ID {} not found", webhook_id),
+        )),
+        Err(e) => {
+            log::error!("Database error fetching webhook {}: {}", webhook_id, e);
+            HttpResponse::InternalServerError()
+                .json(ApiResponse::<()>::error("DATABASE_ERROR", "Failed to fetch webhook"))
+        }
+    }
+}
+
+#[delete("/{id}")]
+pub async fn delete_webhook(
+    app_state: web::Data<AppState>,
+    id: web::Path<i64>,
+) -> impl Responder {
+    let webhook_id = id.into_inner() as i32;
+    match app_state.webhook_repo().delete_webhook(webhook_id).await {
+        Ok(true) => HttpResponse::Ok().json(ApiResponse::success(serde_json::json!({
+            "message": "Webhook deleted successfully",
+            "webhook_id": webhook_id
+        }))),
+        Ok(false) => HttpResponse::NotFound().json(ApiResponse::<()>::error(
+            "NOT_FOUND",
+            &format!("Webhook with ID {} not found", webhook_id),
+        )),
+        Err(e) => {
+            log::error!("Error deleting webhook {}: {}", webhook_id, e);
+            HttpResponse::InternalServerError()
+                .json(ApiResponse::<()>::error("DELETE_ERROR", "Failed to delete webhook"))
+        }
+    }
+}
+
+#[post("/{id}/ping")]
+pub async fn ping_webhook(
+    app_state: web::Data<AppState>,
+    id: web::Path<i64>,
+) -> impl Responder {
+    let webhook_id = id.into_inner() as i32;
+    let repo = app_state.webhook_repo();
+
+    match repo.get_webhook_by_id(webhook_id).await {
+        Ok(Some(_)) => {}
+        Ok(None) => {
+            return HttpResponse::NotFound().json(ApiResponse::<()>::error(
+                "NOT_FOUND",
+                &format!("Webhook with ID {} not found", webhook_id),
+            ));
+        }
+        Err(e) => {
+            log::error!("Database error fetching webhook {}: {}", webhook_id, e);
+            return HttpResponse::InternalServerError()
+                .json(ApiResponse::<()>::error("DATABASE_ERROR", "Failed to fetch webhook"));
+        }
+    }
+
+    let payload = serde_json::json!({
+        "type": PING_EVENT,
+        "webhook_id": webhook_id,
+        "occurred_at": chrono::Utc::now(),
+    });
+    match repo.enqueue_delivery(webhook_id, None, PING_EVENT, &payload).await {
+        Ok(delivery_id) => {
+            app_state.webhook_outbox().wake();
+            HttpResponse::Accepted().json(ApiResponse::success(serde_json::json!({
+                "message": "Ping queued",
+                "delivery_id": delivery_id
+            })))
+        }
+        Err(e) => {
+            log::error!("Error queueing ping for webhook {}: {}", webhook_id, e);
+            HttpResponse::InternalServerError()
+                .json(ApiResponse::<()>::error("DATABASE_ERROR", "Failed to queue ping"))
+        }
+    }
+}
+
+// ===================================================================
+// DELIVERY LOG ENDPOINTS
+// ===================================================================
+
+#[get("/deliveries")]
+pub async fn get_deliveries(
+    app_state: web::Data<AppState>,
+    query: web::Query<CommonPaginationQuery>,
+) -> impl Responder {
+    if let Err(e) = QueryParser::validate(&query, &WebhookDelivery::SCHEMA) {
+        return e.error_response();
+    }
+
+    let query = query.into_inner();
+    match app_state.webhook_repo().get_all_deliveries(query.clone()).await {
+        Ok((deliveries, total_count)) => {
+            let last_key = deliveries.last().map(|delivery| delivery.delivery_id);
+            let meta = ApiMeta {
+                pagination: Some(QueryParser::pagination_meta(&query, &WebhookDelivery::SCHEMA, deliveries.len(), total_count, last_key)),
+                filters_applied: None,
+                request_id: None,
+                timestamp: chrono::Utc::now(),
+            };
+            HttpResponse::Ok().json(ApiResponse::success_with_meta(deliveries, meta))
+        }
+        Err(e) => {
+            log::error!("Database error fetching webhook deliveries: {}", e);
+            HttpResponse::InternalServerError()
+                .json(ApiResponse::<()>::error("DATABASE_ERROR", "Failed to fetch webhook deliveries"))
+        }
+    }
+}
+
+#[get("/deliveries/{id}")]
+pub async fn get_delivery_by_id(
+    app_state: web::Data<AppState>,
+    id: web::Path<i64>,
+) -> impl Responder {
+    let delivery_id = id.into_inner();
+    match app_state.webhook_repo().get_delivery_by_id(delivery_id).await {
+        Ok(Some(delivery)) => HttpResponse::Ok().json(ApiResponse::success(delivery)),
+        Ok(None) => HttpResponse::NotFound().json(ApiResponse::<()>::error(
+            "NOT_FOUND",
+            &format!("Webhook delivery with ID {} not found", delivery_id),
+        )),
+        Err(e) => {
+            log::error!("Database error fetching webhook delivery {}: {}", delivery_id, e);
+            HttpResponse::InternalServerError()
+                .json(ApiResponse::<()>::error("DATABASE_ERROR", "Failed to fetch webhook delivery"))
+        }
+    }
+}
+
+// ===================================================================
+// ROUTE CONFIGURATION
+// ===================================================================
+
+pub fn configure_webhook_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/webhooks")
+            .service(index)
+            .service(get_all_webhooks)
+            // Before /{id} so "deliveries" is not taken for a webhook ID
+            .service(get_deliveries)
+            .service(get_delivery_by_id)
+            .service(get_webhook_by_id)
+            .service(create_webhook)
+            .service(update_webhook)
+            .service(delete_webhook)
+            .service(ping_webhook),
+    );
+}
@@ -1,8 +1,52 @@
-use sqlx::{mysql::MySqlPoolOptions, MySql, Pool};
+use sqlx::mysql::MySqlPoolOptions;
+use sqlx::sqlite::{SqliteConnectOptions, SqliteJournalMode, SqlitePoolOptions};
+use sqlx::{MySql, MySqlPool, Pool, SqlitePool};
 use std::env;
+use std::str::FromStr;
+
+use crate::database::schema::sqlite_migrations;
 
 pub type DbPool = Pool<MySql>;
 
+/// Storage backend the server runs against.
+/// MySQL is the production backend; SQLite serves integration tests and single-rack edge sites.
+#[derive(Clone)]
+pub enum Storage {
+    MySql(MySqlPool),
+    Sqlite(SqlitePool),
+}
+
+impl Storage {
+    pub fn backend_name(&self) -> &'static str {
+        match self {
+            Storage::MySql(_) => "mysql",
+            Storage::Sqlite(_) => "sqlite",
+        }
+    }
+
+    /// Run a trivial query to check the backend is reachable
+    pub async fn ping(&self) -> Result<(), sqlx::Error> {
+        match self {
+            Storage::MySql(pool) => sqlx::query("SELECT 1").execute(pool).await.map(|_| ()),
+            Storage::Sqlite(pool) => sqlx::query("SELECT 1").execute(pool).await.map(|_| ()),
+        }
+    }
+}
+
+/// An operation that has no implementation on the storage backend in use
+#[derive(Debug, Clone, Copy)]
+pub struct UnsupportedOnBackend {
+    pub backend: &'static str,
+}
+
+impl std::fmt::Display for UnsupportedOnBackend {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Not available on the {} storage backend", self.backend)
+    }
+}
+
+impl std::error::Error for UnsupportedOnBackend {}
+
 /// Initialize the database connection pool
 pub async fn init_db_pool() -> Result<DbPool, sqlx::Error> {
     let mysql_user = env::var("MYSQL_USER").unwrap_or_else(|_| "farm".to_string());
@@ -15,9 +59,52 @@ pub async fn init_db_pool() -> Result<DbPool, sqlx::Error> {
         mysql_user, mysql_password, mysql_host, mysql_database
     );
 
+    connect_mysql(&database_url).await
+}
+
+async fn connect_mysql(database_url: &str) -> Result<DbPool, sqlx::Error> {
     MySqlPoolOptions::new()
         .max_connections(50)
         .min_connections(10)
-        .connect(&database_url)
+        .connect(database_url)
         .await
-}
\ No newline at end of file
+}
+
+/// Open an SQLite database (e.g. `sqlite://farm.db` or `sqlite::memory:`) and apply its migrations.
+/// Unlike MySQL, where migrations are run through the API, an SQLite database is always brought
+/// up to date on open so a fresh file or in-memory database is usable straight away.
+pub async fn init_sqlite_pool(database_url: &str) -> Result<SqlitePool, sqlx::Error> {
+    let options = SqliteConnectOptions::from_str(database_url)?
+        .create_if_missing(true)
+        .foreign_keys(true)
+        .journal_mode(SqliteJournalMode::Wal);
+
+    // Every connection to an in-memory database is a separate database, so keep exactly one alive
+    let in_memory = database_url.contains(":memory:") || database_url.contains("mode=memory");
+    let pool_options = if in_memory {
+        SqlitePoolOptions::new()
+            .max_connections(1)
+            .min_connections(1)
+            .idle_timeout(None)
+            .max_lifetime(None)
+    } else {
+        SqlitePoolOptions::new().max_connections(8)
+    };
+
+    let pool = pool_options.connect_with(options).await?;
+    sqlite_migrations::run_all(&pool).await?;
+    Ok(pool)
+}
+
+/// Pick the storage backend from `FARM_DATABASE_URL`: a `sqlite:` URL selects SQLite, a `mysql:`
+/// URL selects MySQL. Without it, MySQL is configured from the MYSQL_* variables as before.
+pub async fn init_storage() -> Result<Storage, sqlx::Error> {
+    match env::var("FARM_DATABASE_URL") {
+        Ok(url) if url.starts_with("sqlite:") => Ok(Storage::Sqlite(init_sqlite_pool(&url).await?)),
+        Ok(url) if url.starts_with("mysql:") => Ok(Storage::MySql(connect_mysql(&url).await?)),
+        Ok(url) => Err(sqlx::Error::Configuration(
+            format!("Unsupported FARM_DATABASE_URL scheme: {}", url.split(':').next().unwrap_or_default()).into(),
+        )),
+        Err(_) => Ok(Storage::MySql(init_db_pool().await?)),
+    }
+}
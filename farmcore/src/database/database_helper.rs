@@ -1,22 +1,27 @@
-use sqlx::{MySqlPool, FromRow};
+use sqlx::database::HasArguments;
+use sqlx::{Acquire, Encode, Executor, FromRow, IntoArguments, MySqlPool, Pool, Type};
 use std::collections::HashMap;
+use crate::database::SqlDialect;
+use crate::domain::{AuditRecorder, RowChange};
 
-/// Type alias for MySQL query to make function signatures cleaner
-type MySqlQuery<'q> = sqlx::query::Query<'q, sqlx::MySql, sqlx::mysql::MySqlArguments>;
-
-/// Generic database helper for simple CRUD operations
+/// Generic database helper for simple CRUD operations.
+/// Helpers that take a `Pool<DB>` work on every storage backend; the rest are MySQL-only.
 pub struct DatabaseHelper;
 
 impl DatabaseHelper {
     /// Find a single record by ID
-    pub async fn get_by_id<T>(
-        pool: &MySqlPool,
+    pub async fn get_by_id<DB, T>(
+        pool: &Pool<DB>,
         table_name: &str,
         key_column: &str,
         id: i64,
     ) -> Result<Option<T>, sqlx::Error>
     where
-        T: for<'r> FromRow<'r, sqlx::mysql::MySqlRow> + Unpin + Send,
+        DB: sqlx::Database,
+        T: for<'r> FromRow<'r, DB::Row> + Unpin + Send,
+        for<'c> &'c mut DB::Connection: Executor<'c, Database = DB>,
+        for<'q> <DB as HasArguments<'q>>::Arguments: IntoArguments<'q, DB>,
+        for<'q> i64: Encode<'q, DB> + Type<DB>,
     {
         let sql = format!("SELECT * FROM {} WHERE {} = ?", table_name, key_column);
 
@@ -27,12 +32,15 @@ impl DatabaseHelper {
     }
 
     /// Get all records from a table
-    pub async fn find_all<T>(
-        pool: &MySqlPool,
+    pub async fn find_all<DB, T>(
+        pool: &Pool<DB>,
         table_name: &str,
     ) -> Result<Vec<T>, sqlx::Error>
     where
-        T: for<'r> FromRow<'r, sqlx::mysql::MySqlRow> + Unpin + Send,
+        DB: sqlx::Database,
+        T: for<'r> FromRow<'r, DB::Row> + Unpin + Send,
+        for<'c> &'c mut DB::Connection: Executor<'c, Database = DB>,
+        for<'q> <DB as HasArguments<'q>>::Arguments: IntoArguments<'q, DB>,
     {
         let sql = format!("SELECT * FROM {}", table_name);
         sqlx::query_as::<_, T>(&sql)
@@ -41,10 +49,16 @@ impl DatabaseHelper {
     }
 
     /// Get total count of records in a table
-    pub async fn get_total_count(
-        pool: &MySqlPool,
+    pub async fn get_total_count<DB>(
+        pool: &Pool<DB>,
         table_name: &str,
-    ) -> Result<i64, sqlx::Error> {
+    ) -> Result<i64, sqlx::Error>
+    where
+        DB: sqlx::Database,
+        for<'r> (i64,): FromRow<'r, DB::Row>,
+        for<'c> &'c mut DB::Connection: Executor<'c, Database = DB>,
+        for<'q> <DB as HasArguments<'q>>::Arguments: IntoArguments<'q, DB>,
+    {
         let query = format!("SELECT COUNT(*) FROM {}", table_name);
         sqlx::query_scalar(&query)
             .fetch_one(pool)
@@ -76,21 +90,27 @@ impl DatabaseHelper {
         Ok(result)
     }
 
-    /// Delete a record by ID
-    pub async fn delete_by_id(
-        pool: &MySqlPool,
+    /// Delete a record by ID, on a pool or inside a caller's transaction
+    pub async fn delete_by_id<'e, DB, E>(
+        executor: E,
         table_name: &str,
         key_column: &str,
         id: i64,
-    ) -> Result<u64, sqlx::Error> {
+    ) -> Result<u64, sqlx::Error>
+    where
+        DB: SqlDialect,
+        E: Executor<'e, Database = DB>,
+        for<'q> <DB as HasArguments<'q>>::Arguments: IntoArguments<'q, DB>,
+        for<'q> i64: Encode<'q, DB> + Type<DB>,
+    {
         let sql = format!("DELETE FROM {} WHERE {} = ?", table_name, key_column);
         
         let result = sqlx::query(&sql)
             .bind(id)
-            .execute(pool)
+            .execute(executor)
             .await?;
             
-        Ok(result.rows_affected())
+        Ok(DB::rows_affected(&result))
     }
 
     /// Check if a record exists by ID
@@ -134,14 +154,52 @@ impl DatabaseHelper {
     /// * `Ok(true)` if rows were updated
     /// * `Ok(false)` if no rows were updated
     /// * `Err` if blacklisted field was provided or query failed
-    pub async fn update(
-        pool: &MySqlPool,
+    pub async fn update<DB>(
+        pool: &Pool<DB>,
         table_name: &str,
         key_column: &str,
         id: i32,
         updates: HashMap<String, serde_json::Value>,
         blacklisted_fields: &[&str],
-    ) -> Result<bool, sqlx::Error> {
+    ) -> Result<bool, sqlx::Error>
+    where
+        DB: SqlDialect,
+        for<'r> (String,): FromRow<'r, DB::Row>,
+        for<'c> &'c mut DB::Connection: Executor<'c, Database = DB>,
+        for<'q> <DB as HasArguments<'q>>::Arguments: IntoArguments<'q, DB>,
+        for<'q> Option<String>: Encode<'q, DB> + Type<DB>,
+        for<'q> String: Encode<'q, DB> + Type<DB>,
+        for<'q> i64: Encode<'q, DB> + Type<DB>,
+        for<'q> f64: Encode<'q, DB> + Type<DB>,
+        for<'q> i8: Encode<'q, DB> + Type<DB>,
+        for<'q> i32: Encode<'q, DB> + Type<DB>,
+    {
+        Self::update_with(pool, table_name, key_column, id, updates, blacklisted_fields).await
+    }
+
+    /// `update` on a connection or transaction the caller already holds. The statement and
+    /// its audit snapshots run in a nested transaction, which is a savepoint inside an open one.
+    pub async fn update_with<'a, DB, A>(
+        conn: A,
+        table_name: &str,
+        key_column: &str,
+        id: i32,
+        updates: HashMap<String, serde_json::Value>,
+        blacklisted_fields: &[&str],
+    ) -> Result<bool, sqlx::Error>
+    where
+        DB: SqlDialect,
+        A: Acquire<'a, Database = DB>,
+        for<'r> (String,): FromRow<'r, DB::Row>,
+        for<'c> &'c mut DB::Connection: Executor<'c, Database = DB>,
+        for<'q> <DB as HasArguments<'q>>::Arguments: IntoArguments<'q, DB>,
+        for<'q> Option<String>: Encode<'q, DB> + Type<DB>,
+        for<'q> String: Encode<'q, DB> + Type<DB>,
+        for<'q> i64: Encode<'q, DB> + Type<DB>,
+        for<'q> f64: Encode<'q, DB> + Type<DB>,
+        for<'q> i8: Encode<'q, DB> + Type<DB>,
+        for<'q> i32: Encode<'q, DB> + Type<DB>,
+    {
         if updates.is_empty() {
             return Ok(false);
         }
@@ -172,9 +230,10 @@ impl DatabaseHelper {
             .collect();
 
         let sql = format!(
-            "UPDATE {} SET {}, updated_at = NOW() WHERE {} = ?",
+            "UPDATE {} SET {}, updated_at = {} WHERE {} = ?",
             table_name,
             set_clauses.join(", "),
+            DB::CURRENT_TIMESTAMP,
             key_column
         );
 
@@ -198,8 +257,100 @@ impl DatabaseHelper {
             };
         }
 
-        let result = q.bind(id).execute(pool).await?;
-        Ok(result.rows_affected() > 0)
+        // When running inside an audited request, capture the touched columns before and
+        // after the update in the same transaction so the audit log gets an exact diff
+        let audited = AuditRecorder::is_recording();
+        let fields: Vec<&str> = filtered.iter().map(|(field, _)| field.as_str()).collect();
+        let mut tx = conn.begin().await?;
+
+        let before = if audited {
+            Self::snapshot_columns(&mut tx, table_name, key_column, id, &fields).await?
+        } else {
+            None
+        };
+
+        let result = q.bind(id).execute(&mut *tx).await?;
+
+        let after = if audited {
+            Self::snapshot_columns(&mut tx, table_name, key_column, id, &fields).await?
+        } else {
+            None
+        };
+
+        tx.commit().await?;
+
+        if let (Some(before), Some(after)) = (before, after) {
+            let (old, new) = Self::diff_columns(before, after);
+            if !old.is_empty() {
+                AuditRecorder::record_change(RowChange {
+                    table: table_name.to_string(),
+                    key_column: key_column.to_string(),
+                    id,
+                    old: serde_json::Value::Object(old),
+                    new: serde_json::Value::Object(new),
+                });
+            }
+        }
+
+        Ok(DB::rows_affected(&result) > 0)
+    }
+
+    /// Read the given columns of a row as a JSON object (column names must already be validated)
+    async fn snapshot_columns<DB>(
+        tx: &mut sqlx::Transaction<'_, DB>,
+        table_name: &str,
+        key_column: &str,
+        id: i32,
+        fields: &[&str],
+    ) -> Result<Option<serde_json::Map<String, serde_json::Value>>, sqlx::Error>
+    where
+        DB: SqlDialect,
+        for<'r> (String,): FromRow<'r, DB::Row>,
+        for<'c> &'c mut DB::Connection: Executor<'c, Database = DB>,
+        for<'q> <DB as HasArguments<'q>>::Arguments: IntoArguments<'q, DB>,
+        for<'q> i32: Encode<'q, DB> + Type<DB>,
+    {
+        let pairs: Vec<String> = fields.iter()
+            .map(|field| format!("'{}', `{}`", field, field))
+            .collect();
+        let sql = format!(
+            "SELECT {} FROM {} WHERE {} = ?{}",
+            DB::json_object_text(&pairs.join(", ")),
+            table_name,
+            key_column,
+            DB::LOCK_ROWS
+        );
+
+        let row: Option<String> = sqlx::query_scalar(&sql)
+            .bind(id)
+            .fetch_optional(&mut **tx)
+            .await?;
+
+        match row {
+            Some(json) => match serde_json::from_str(&json) {
+                Ok(serde_json::Value::Object(map)) => Ok(Some(map)),
+                Ok(_) => Ok(None),
+                Err(e) => Err(sqlx::Error::Decode(Box::new(e))),
+            },
+            None => Ok(None),
+        }
+    }
+
+    /// Keep only the columns whose value actually changed
+    fn diff_columns(
+        before: serde_json::Map<String, serde_json::Value>,
+        mut after: serde_json::Map<String, serde_json::Value>,
+    ) -> (serde_json::Map<String, serde_json::Value>, serde_json::Map<String, serde_json::Value>) {
+        let mut old = serde_json::Map::new();
+        let mut new = serde_json::Map::new();
+        for (field, old_value) in before {
+            let new_value = after.remove(&field).unwrap_or(serde_json::Value::Null);
+            if old_value != new_value {
+                old.insert(field.clone(), old_value);
+                new.insert(field, new_value);
+            }
+        }
+        (old, new)
     }
 
     /// Generic upsert function - inserts if not exists, updates if exists
@@ -0,0 +1,81 @@
+use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
+use rust_decimal::Decimal;
+use sqlx::database::{HasArguments, HasValueRef};
+use sqlx::encode::IsNull;
+use sqlx::error::BoxDynError;
+use sqlx::{Decode, Encode, MySql, Sqlite, Type, ValueRef};
+
+/// A nullable DECIMAL column on any storage backend. MySQL reads and writes it as DECIMAL;
+/// SQLite has no decimal type, so there it is stored as REAL and read back as the shortest
+/// decimal that round-trips, e.g. 52.52 rather than 52.520000000000003. Models keep
+/// `Option<Decimal>` and read the column through `#[sqlx(try_from = "DecimalColumn")]`.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct DecimalColumn(pub Option<Decimal>);
+
+impl From<DecimalColumn> for Option<Decimal> {
+    fn from(column: DecimalColumn) -> Self {
+        column.0
+    }
+}
+
+impl Type<MySql> for DecimalColumn {
+    fn type_info() -> <MySql as sqlx::Database>::TypeInfo {
+        <Decimal as Type<MySql>>::type_info()
+    }
+
+    fn compatible(ty: &<MySql as sqlx::Database>::TypeInfo) -> bool {
+        <Decimal as Type<MySql>>::compatible(ty)
+    }
+}
+
+impl<'r> Decode<'r, MySql> for DecimalColumn {
+    fn decode(value: <MySql as HasValueRef<'r>>::ValueRef) -> Result<Self, BoxDynError> {
+        <Option<Decimal> as Decode<MySql>>::decode(value).map(DecimalColumn)
+    }
+}
+
+impl<'q> Encode<'q, MySql> for DecimalColumn {
+    fn encode_by_ref(&self, buf: &mut <MySql as HasArguments<'q>>::ArgumentBuffer) -> IsNull {
+        <Option<Decimal> as Encode<MySql>>::encode_by_ref(&self.0, buf)
+    }
+
+    fn produces(&self) -> Option<<MySql as sqlx::Database>::TypeInfo> {
+        <Option<Decimal> as Encode<MySql>>::produces(&self.0)
+    }
+}
+
+impl Type<Sqlite> for DecimalColumn {
+    fn type_info() -> <Sqlite as sqlx::Database>::TypeInfo {
+        <f64 as Type<Sqlite>>::type_info()
+    }
+
+    // A REAL column holding a whole number comes back as INTEGER
+    fn compatible(ty: &<Sqlite as sqlx::Database>::TypeInfo) -> bool {
+        <f64 as Type<Sqlite>>::compatible(ty) || <i64 as Type<Sqlite>>::compatible(ty)
+    }
+}
+
+impl<'r> Decode<'r, Sqlite> for DecimalColumn {
+    fn decode(value: <Sqlite as HasValueRef<'r>>::ValueRef) -> Result<Self, BoxDynError> {
+        if value.is_null() {
+            return Ok(DecimalColumn(None));
+        }
+        let number = <f64 as Decode<Sqlite>>::decode(value)?;
+        let decimal = number.to_string().parse::<Decimal>()
+            .ok()
+            .or_else(|| Decimal::from_f64(number))
+            .ok_or_else(|| format!("{} does not fit a DECIMAL", number))?;
+        Ok(DecimalColumn(Some(decimal)))
+    }
+}
+
+impl<'q> Encode<'q, Sqlite> for DecimalColumn {
+    fn encode_by_ref(&self, buf: &mut <Sqlite as HasArguments<'q>>::ArgumentBuffer) -> IsNull {
+        let number = self.0.and_then(|decimal| decimal.to_f64());
+        <Option<f64> as Encode<Sqlite>>::encode_by_ref(&number, buf)
+    }
+
+    fn produces(&self) -> Option<<Sqlite as sqlx::Database>::TypeInfo> {
+        Some(<f64 as Type<Sqlite>>::type_info())
+    }
+}
@@ -0,0 +1,61 @@
+use sqlx::{MySql, Sqlite};
+
+/// The few pieces of SQL that differ between the supported storage backends.
+/// Everything else the shared helpers emit (backtick-quoted identifiers, `?`
+/// placeholders, LIMIT/OFFSET) is understood by both MySQL and SQLite.
+pub trait SqlDialect: sqlx::Database {
+    /// Expression for the current timestamp
+    const CURRENT_TIMESTAMP: &'static str;
+
+    /// Suffix for a SELECT that must lock the rows it reads until the transaction ends
+    const LOCK_ROWS: &'static str;
+
+    /// Type to CAST a DECIMAL column to so it decodes as f64
+    const DOUBLE: &'static str;
+
+    /// Expression rendering `pairs` (`'name', column, ...`) as JSON text
+    fn json_object_text(pairs: &str) -> String;
+
+    /// Rows changed by an INSERT/UPDATE/DELETE
+    fn rows_affected(result: &Self::QueryResult) -> u64;
+
+    /// Key generated by an INSERT into a table with an auto-increment key
+    fn last_insert_id(result: &Self::QueryResult) -> i64;
+}
+
+impl SqlDialect for MySql {
+    const CURRENT_TIMESTAMP: &'static str = "NOW()";
+    const LOCK_ROWS: &'static str = " FOR UPDATE";
+    const DOUBLE: &'static str = "DOUBLE";
+
+    fn json_object_text(pairs: &str) -> String {
+        format!("CAST(JSON_OBJECT({}) AS CHAR)", pairs)
+    }
+
+    fn rows_affected(result: &Self::QueryResult) -> u64 {
+        result.rows_affected()
+    }
+
+    fn last_insert_id(result: &Self::QueryResult) -> i64 {
+        result.last_insert_id() as i64
+    }
+}
+
+impl SqlDialect for Sqlite {
+    const CURRENT_TIMESTAMP: &'static str = "CURRENT_TIMESTAMP";
+    // SQLite serializes writers on the whole database, so there is no row lock to take
+    const LOCK_ROWS: &'static str = "";
+    const DOUBLE: &'static str = "REAL";
+
+    fn json_object_text(pairs: &str) -> String {
+        format!("json_object({})", pairs)
+    }
+
+    fn rows_affected(result: &Self::QueryResult) -> u64 {
+        result.rows_affected()
+    }
+
+    fn last_insert_id(result: &Self::QueryResult) -> i64 {
+        result.last_insert_rowid()
+    }
+}
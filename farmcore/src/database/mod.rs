@@ -1,8 +1,12 @@
 pub mod connection;
+pub mod dialect;
+pub mod decimal;
 pub mod query_builder;
 pub mod database_helper;
 pub mod schema;
 
 pub use connection::*;
+pub use dialect::*;
+pub use decimal::*;
 pub use query_builder::*;
 pub use database_helper::*;
\ No newline at end of file
@@ -1,120 +1,446 @@
-use sqlx::{MySql, MySqlPool, QueryBuilder, FromRow};
-use crate::models::{QueryOptions, WhereCondition, QueryValue};
+use futures_util::stream::{self, BoxStream, StreamExt};
+use sqlx::database::HasArguments;
+use sqlx::query::QueryAs;
+use sqlx::{Database, Encode, Executor, FromRow, IntoArguments, Pool, Type};
+use chrono::NaiveDateTime;
+use crate::models::{
+    FilterTarget, Predicate, QueryOptions, Relation, WhereCondition, QueryValue, TableSchema, COMPARISON_OPERATORS,
+    LIST_OPERATORS, NULL_OPERATORS, RELATION_ALIAS,
+};
 
-/// Dynamic query builder for complex database operations
+/// Rows of a query read incrementally, for exports that must not hold a whole table in memory
+pub type RowStream<T> = BoxStream<'static, Result<T, sqlx::Error>>;
+
+/// Rows buffered between the database and a slow reader of a `RowStream`
+const STREAM_BUFFER: usize = 256;
+
+/// Dynamic query builder for complex database operations.
+/// The generated SQL (backtick-quoted identifiers, `?` placeholders) runs on every storage backend.
+/// Identifiers and operators are only ever taken from the table's `TableSchema`; every value is bound.
 pub struct QueryBuilderHelper;
 
 impl QueryBuilderHelper {
     /// Flexible select with dynamic conditions, ordering, and pagination
-    pub async fn select<T>(
-        pool: &MySqlPool,
-        table_name: &str,
+    pub async fn select<DB, T>(
+        pool: &Pool<DB>,
+        schema: &TableSchema,
         options: QueryOptions,
     ) -> Result<Vec<T>, sqlx::Error>
     where
-        T: for<'r> FromRow<'r, sqlx::mysql::MySqlRow> + Unpin + Send,
+        DB: Database,
+        T: for<'r> FromRow<'r, DB::Row> + Unpin + Send,
+        for<'c> &'c mut DB::Connection: Executor<'c, Database = DB>,
+        for<'q> <DB as HasArguments<'q>>::Arguments: IntoArguments<'q, DB>,
+        for<'q> String: Encode<'q, DB> + Type<DB>,
+        for<'q> i64: Encode<'q, DB> + Type<DB>,
+        for<'q> f64: Encode<'q, DB> + Type<DB>,
+        for<'q> bool: Encode<'q, DB> + Type<DB>,
+        for<'q> NaiveDateTime: Encode<'q, DB> + Type<DB>,
+    {
+        let (sql, values) = Self::build_select(schema, options)?;
+
+        Self::bind_values(sqlx::query_as::<DB, T>(&sql), values)
+            .fetch_all(pool)
+            .await
+    }
+
+    /// Like `select`, but rows are read as the consumer asks for them. The query runs on a
+    /// background task that holds its own pool handle, so the stream outlives the caller.
+    pub fn stream<DB, T>(
+        pool: Pool<DB>,
+        schema: &TableSchema,
+        options: QueryOptions,
+    ) -> Result<RowStream<T>, sqlx::Error>
+    where
+        DB: Database,
+        T: for<'r> FromRow<'r, DB::Row> + Unpin + Send + 'static,
+        for<'c> &'c mut DB::Connection: Executor<'c, Database = DB>,
+        for<'q> <DB as HasArguments<'q>>::Arguments: IntoArguments<'q, DB>,
+        for<'q> String: Encode<'q, DB> + Type<DB>,
+        for<'q> i64: Encode<'q, DB> + Type<DB>,
+        for<'q> f64: Encode<'q, DB> + Type<DB>,
+        for<'q> bool: Encode<'q, DB> + Type<DB>,
+        for<'q> NaiveDateTime: Encode<'q, DB> + Type<DB>,
     {
-        let mut qb = QueryBuilder::<MySql>::new("SELECT ");
-        
+        let (sql, values) = Self::build_select(schema, options)?;
+        let (tx, rx) = tokio::sync::mpsc::channel(STREAM_BUFFER);
+
+        tokio::spawn(async move {
+            let mut rows = Self::bind_values(sqlx::query_as::<DB, T>(&sql), values).fetch(&pool);
+            while let Some(row) = rows.next().await {
+                let failed = row.is_err();
+                // A closed channel means the client went away; stop reading
+                if tx.send(row).await.is_err() || failed {
+                    break;
+                }
+            }
+        });
+
+        Ok(stream::unfold(rx, |mut rx| async move {
+            rx.recv().await.map(|row| (row, rx))
+        }).boxed())
+    }
+
+    /// SELECT statement and its bind values for `select` and `stream`
+    fn build_select(schema: &TableSchema, options: QueryOptions) -> Result<(String, Vec<QueryValue>), sqlx::Error> {
+        let mut sql = String::from("SELECT ");
+        let mut values = Vec::new();
+
         // Handle column selection
         match options.columns {
             Some(cols) if !cols.is_empty() => {
-                for (i, col) in cols.iter().enumerate() {
-                    if i > 0 { qb.push(", "); }
-                    qb.push("`").push(col).push("`");
+                let mut quoted = Vec::with_capacity(cols.len());
+                for col in &cols {
+                    let column = schema.column(col)
+                        .ok_or_else(|| sqlx::Error::Protocol(format!("Unknown column '{}' for {}", col, schema.table)))?;
+                    quoted.push(format!("`{}`", column.name));
                 }
+                sql.push_str(&quoted.join(", "));
             }
             _ => {
-                qb.push("*");
+                sql.push('*');
             }
         }
-        
-        qb.push(" FROM `").push(table_name).push("`");
-        
+
+        sql.push_str(&format!(" FROM `{}`", schema.table));
+
         // Handle WHERE conditions
         if !options.where_conditions.is_empty() {
-            qb.push(" WHERE ");
-            Self::add_where_conditions(&mut qb, &options.where_conditions);
+            sql.push_str(" WHERE ");
+            Self::add_where_conditions(&mut sql, &mut values, schema, &options.where_conditions)?;
         }
-        
+
         // Handle ORDER BY
         if let Some(order) = options.order_by {
-            qb.push(" ORDER BY ").push(order);
+            sql.push_str(" ORDER BY ");
+            sql.push_str(&order);
         }
-        
+
         // Handle LIMIT
         if let Some(limit) = options.limit {
-            qb.push(" LIMIT ").push_bind(limit);
+            sql.push_str(" LIMIT ?");
+            values.push(QueryValue::Integer(limit));
         }
-        
+
         // Handle OFFSET
         if let Some(offset) = options.offset {
-            qb.push(" OFFSET ").push_bind(offset);
+            sql.push_str(" OFFSET ?");
+            values.push(QueryValue::Integer(offset));
         }
-        
-        qb.build_query_as::<T>().fetch_all(pool).await
+
+        Ok((sql, values))
     }
 
     /// Count rows matching the given WHERE conditions (for pagination metadata)
-    pub async fn count(
-        pool: &MySqlPool,
-        table_name: &str,
-        where_conditions: &[crate::models::WhereCondition],
-    ) -> Result<i64, sqlx::Error> {
-        let mut qb = QueryBuilder::<MySql>::new("SELECT COUNT(*) FROM `");
-        qb.push(table_name).push("`");
+    pub async fn count<DB>(
+        pool: &Pool<DB>,
+        schema: &TableSchema,
+        where_conditions: &[WhereCondition],
+    ) -> Result<i64, sqlx::Error>
+    where
+        DB: Database,
+        for<'r> (i64,): FromRow<'r, DB::Row>,
+        for<'c> &'c mut DB::Connection: Executor<'c, Database = DB>,
+        for<'q> <DB as HasArguments<'q>>::Arguments: IntoArguments<'q, DB>,
+        for<'q> String: Encode<'q, DB> + Type<DB>,
+        for<'q> i64: Encode<'q, DB> + Type<DB>,
+        for<'q> f64: Encode<'q, DB> + Type<DB>,
+        for<'q> bool: Encode<'q, DB> + Type<DB>,
+        for<'q> NaiveDateTime: Encode<'q, DB> + Type<DB>,
+    {
+        let mut sql = format!("SELECT COUNT(*) FROM `{}`", schema.table);
+        let mut values = Vec::new();
 
         if !where_conditions.is_empty() {
-            qb.push(" WHERE ");
-            Self::add_where_conditions(&mut qb, where_conditions);
+            sql.push_str(" WHERE ");
+            Self::add_where_conditions(&mut sql, &mut values, schema, where_conditions)?;
         }
 
-        qb.build_query_scalar::<i64>().fetch_one(pool).await
+        let (count,) = Self::bind_values(sqlx::query_as::<DB, (i64,)>(&sql), values)
+            .fetch_one(pool)
+            .await?;
+        Ok(count)
     }
 
-    /// Helper function to add WHERE conditions to a query builder
-    fn add_where_conditions<'a>(qb: &mut QueryBuilder<'a, MySql>, where_conditions: &'a [WhereCondition]) {
+    /// Helper function to add WHERE conditions to the SQL, collecting the values to bind in order.
+    /// Conditions come from `QueryParser`, which has already validated them; anything outside the
+    /// schema here is a programming error and is refused rather than interpolated.
+    fn add_where_conditions(
+        sql: &mut String,
+        values: &mut Vec<QueryValue>,
+        schema: &TableSchema,
+        where_conditions: &[WhereCondition],
+    ) -> Result<(), sqlx::Error> {
         for (i, condition) in where_conditions.iter().enumerate() {
             // Add logical operator (AND/OR) if not the first condition
             if i > 0 {
-                let logical_op = condition.logical_operator.as_deref().unwrap_or("AND");
-                qb.push(" ").push(logical_op).push(" ");
+                let logical_op = match condition.logical_operator.as_deref() {
+                    Some("OR") => "OR",
+                    _ => "AND",
+                };
+                sql.push_str(&format!(" {} ", logical_op));
             }
-            
-            // Handle raw SQL conditions (for grouped OR conditions)
-            if condition.column == "RAW_SQL" && condition.operator == "RAW" {
-                if let QueryValue::String(raw_sql) = &condition.value {
-                    qb.push(raw_sql);
+
+            match &condition.predicate {
+                Predicate::Group(conditions) => {
+                    if conditions.is_empty() {
+                        return Err(sqlx::Error::Protocol("Empty condition group".to_string()));
+                    }
+                    sql.push('(');
+                    Self::add_where_conditions(sql, values, schema, conditions)?;
+                    sql.push(')');
+                }
+                Predicate::Compare { column, operator, value } => {
+                    Self::add_comparison(sql, values, schema, column, operator, value)?;
                 }
-                continue;
             }
-            
-            qb.push("`").push(&condition.column).push("` ")
-              .push(&condition.operator).push(" ");
-            
-            match &condition.value {
-                QueryValue::String(s) => { 
-                    if condition.operator == "IN" {
-                        // For IN clauses, don't bind the parameter, use it directly
-                        qb.push(s);
-                    } else {
-                        qb.push_bind(s);
-                    }
-                },
-                QueryValue::Integer(i) => { qb.push_bind(*i); },
-                QueryValue::Float(f) => { qb.push_bind(*f); },
-                QueryValue::Boolean(b) => { qb.push_bind(*b); },
-                QueryValue::Null => { 
-                    // Use proper IS NULL syntax instead of = NULL
-                    if condition.operator == "=" {
-                        qb.push("IS NULL");
-                    } else if condition.operator == "!=" {
-                        qb.push("IS NOT NULL");
-                    } else {
-                        qb.push("IS NULL");  // Default fallback
-                    }
-                },
+        }
+        Ok(())
+    }
+
+    fn add_comparison(
+        sql: &mut String,
+        values: &mut Vec<QueryValue>,
+        schema: &TableSchema,
+        column: &str,
+        operator: &str,
+        value: &QueryValue,
+    ) -> Result<(), sqlx::Error> {
+        let target = schema.filter(column)
+            .ok_or_else(|| sqlx::Error::Protocol(format!("Unknown column '{}' for {}", column, schema.table)))?;
+        // The operator is interpolated, so it must be one of the fixed set the column allows
+        if !COMPARISON_OPERATORS.contains(&operator) || !target.column().allows(operator) {
+            return Err(sqlx::Error::Protocol(format!(
+                "Operator '{}' is not supported for column '{}'", operator, column
+            )));
+        }
+
+        match target {
+            FilterTarget::Column(column) => {
+                Self::add_operand(sql, values, &format!("`{}`", column.name), operator, value)
+            }
+            // Some related row matches: EXISTS (SELECT 1 FROM child c ... WHERE c.fk = parent.key AND c.col op ?)
+            FilterTarget::Related(relation, related) => {
+                sql.push_str("EXISTS (SELECT 1");
+                Self::add_relation_scope(sql, schema, relation);
+                sql.push_str(" AND ");
+                let operand = format!("{}.`{}`", related.source, related.column.name);
+                Self::add_operand(sql, values, &operand, operator, value)?;
+                sql.push(')');
+                Ok(())
+            }
+            // (SELECT COUNT(*) FROM child c WHERE c.fk = parent.key) op ?
+            FilterTarget::Count(relation) => {
+                let mut operand = String::from("(SELECT COUNT(*)");
+                Self::add_relation_scope(&mut operand, schema, relation);
+                operand.push(')');
+                Self::add_operand(sql, values, &operand, operator, value)
+            }
+        }
+    }
+
+    /// ` FROM child c [LEFT JOIN ...] WHERE c.fk = parent.key` for a relation subquery
+    fn add_relation_scope(sql: &mut String, schema: &TableSchema, relation: &Relation) {
+        sql.push_str(&format!(" FROM `{}` {}", relation.table, RELATION_ALIAS));
+        for join in relation.joins {
+            sql.push_str(&format!(" LEFT JOIN `{}` {} ON {}", join.table, join.alias, join.on));
+        }
+        sql.push_str(&format!(
+            " WHERE {}.`{}` = `{}`.`{}`",
+            RELATION_ALIAS, relation.foreign_key, schema.table, relation.parent_key
+        ));
+    }
+
+    /// `operand operator value`, with the operator already validated against the column
+    fn add_operand(
+        sql: &mut String,
+        values: &mut Vec<QueryValue>,
+        operand: &str,
+        operator: &str,
+        value: &QueryValue,
+    ) -> Result<(), sqlx::Error> {
+        match (operator, value) {
+            (op, _) if NULL_OPERATORS.contains(&op) => {
+                sql.push_str(&format!("{} {}", operand, op));
+            }
+            // Legacy = NULL / != NULL
+            (op, QueryValue::Null) => {
+                let null_op = if op == "!=" { "IS NOT NULL" } else { "IS NULL" };
+                sql.push_str(&format!("{} {}", operand, null_op));
+            }
+            ("BETWEEN", QueryValue::List(bounds)) if bounds.len() == 2 => {
+                sql.push_str(&format!("{} BETWEEN ? AND ?", operand));
+                values.extend(bounds.iter().cloned());
+            }
+            (op, QueryValue::List(items)) if LIST_OPERATORS.contains(&op) && !items.is_empty() => {
+                let placeholders = vec!["?"; items.len()].join(", ");
+                sql.push_str(&format!("{} {} ({})", operand, op, placeholders));
+                values.extend(items.iter().cloned());
+            }
+            (op, QueryValue::List(_)) => {
+                return Err(sqlx::Error::Protocol(format!("Invalid value list for operator '{}'", op)));
+            }
+            (op, value) if !LIST_OPERATORS.contains(&op) && op != "BETWEEN" => {
+                sql.push_str(&format!("{} {} ?", operand, op));
+                values.push(value.clone());
+            }
+            (op, _) => {
+                return Err(sqlx::Error::Protocol(format!("Operator '{}' needs a list of values", op)));
+            }
+        }
+        Ok(())
+    }
+
+    /// Bind collected values to their `?` placeholders
+    fn bind_values<'q, DB, T>(
+        mut query: QueryAs<'q, DB, T, <DB as HasArguments<'q>>::Arguments>,
+        values: Vec<QueryValue>,
+    ) -> QueryAs<'q, DB, T, <DB as HasArguments<'q>>::Arguments>
+    where
+        DB: Database,
+        String: Encode<'q, DB> + Type<DB>,
+        i64: Encode<'q, DB> + Type<DB>,
+        f64: Encode<'q, DB> + Type<DB>,
+        bool: Encode<'q, DB> + Type<DB>,
+        NaiveDateTime: Encode<'q, DB> + Type<DB>,
+    {
+        for value in values {
+            query = match value {
+                QueryValue::String(s) => query.bind(s),
+                QueryValue::Integer(i) => query.bind(i),
+                QueryValue::Float(f) => query.bind(f),
+                QueryValue::Boolean(b) => query.bind(b),
+                QueryValue::Timestamp(ts) => query.bind(ts),
+                // Lists are flattened into their elements when the SQL is built
+                QueryValue::List(_) | QueryValue::Null => query,
             };
         }
+        query
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Server;
+
+    fn options(where_conditions: Vec<WhereCondition>) -> QueryOptions {
+        QueryOptions { columns: None, where_conditions, limit: None, offset: None, order_by: None }
+    }
+
+    fn string(value: &str) -> QueryValue {
+        QueryValue::String(value.to_string())
+    }
+
+    #[test]
+    fn values_are_bound_and_never_interpolated() {
+        let name = "gpu-node-01' OR '1'='1";
+        let (sql, values) = QueryBuilderHelper::build_select(&Server::SCHEMA, QueryOptions {
+            columns: Some(vec!["server_id".to_string(), "server_name".to_string()]),
+            where_conditions: vec![
+                WhereCondition::compare("server_name", "=", string(name), None),
+                WhereCondition::compare("status", "IN", QueryValue::List(vec![string("ACTIVE"), string("RMA")]), Some("AND".to_string())),
+                WhereCondition::compare("cluster_id", "BETWEEN", QueryValue::List(vec![QueryValue::Integer(3), QueryValue::Integer(7)]), Some("OR".to_string())),
+                WhereCondition::compare("rack_id", "IS NULL", QueryValue::Null, Some("AND".to_string())),
+            ],
+            limit: Some(20),
+            offset: Some(40),
+            order_by: Some("`server_id` ASC".to_string()),
+        }).unwrap();
+
+        assert_eq!(
+            sql,
+            "SELECT `server_id`, `server_name` FROM `servers` WHERE `server_name` = ? AND `status` IN (?, ?) \
+             OR `cluster_id` BETWEEN ? AND ? AND `rack_id` IS NULL ORDER BY `server_id` ASC LIMIT ? OFFSET ?"
+        );
+        assert!(!sql.contains(name));
+        assert_eq!(sql.matches('?').count(), values.len());
+        assert_eq!(values, [
+            string(name),
+            string("ACTIVE"),
+            string("RMA"),
+            QueryValue::Integer(3),
+            QueryValue::Integer(7),
+            QueryValue::Integer(20),
+            QueryValue::Integer(40),
+        ]);
+    }
+
+    #[test]
+    fn groups_are_parenthesized_in_bind_order() {
+        let group = WhereCondition {
+            predicate: Predicate::Group(vec![
+                WhereCondition::compare("status", "=", string("ACTIVE"), None),
+                WhereCondition::compare("status", "=", string("MAINTENANCE"), Some("OR".to_string())),
+            ]),
+            logical_operator: None,
+        };
+        let (sql, values) = QueryBuilderHelper::build_select(&Server::SCHEMA, options(vec![
+            group,
+            WhereCondition::compare("cluster_id", "=", QueryValue::Integer(3), Some("AND".to_string())),
+        ])).unwrap();
+
+        assert_eq!(sql, "SELECT * FROM `servers` WHERE (`status` = ? OR `status` = ?) AND `cluster_id` = ?");
+        assert_eq!(values, [string("ACTIVE"), string("MAINTENANCE"), QueryValue::Integer(3)]);
+    }
+
+    #[test]
+    fn relation_columns_become_exists_subqueries() {
+        let (sql, values) = QueryBuilderHelper::build_select(&Server::SCHEMA, options(vec![
+            WhereCondition::compare("gpu.model", "IN", QueryValue::List(vec![string("A100"), string("H100")]), None),
+            WhereCondition::compare("nic.switch_name", "=", string("leaf-01"), Some("AND".to_string())),
+        ])).unwrap();
+
+        assert_eq!(
+            sql,
+            "SELECT * FROM `servers` WHERE \
+             EXISTS (SELECT 1 FROM `server_gpus` c LEFT JOIN `component_gpu_types` t ON t.`component_gpu_id` = c.`component_gpu_id` \
+             WHERE c.`server_id` = `servers`.`server_id` AND t.`model` IN (?, ?)) AND \
+             EXISTS (SELECT 1 FROM `server_network_interfaces` c \
+             LEFT JOIN `component_network_types` t ON t.`component_network_id` = c.`component_network_id` \
+             LEFT JOIN `switch_ports` sp ON sp.`switch_port_id` = c.`switch_port_id` \
+             LEFT JOIN `switches` sw ON sw.`switch_id` = sp.`switch_id` \
+             WHERE c.`server_id` = `servers`.`server_id` AND sw.`switch_name` = ?)"
+        );
+        assert_eq!(values, [string("A100"), string("H100"), string("leaf-01")]);
+    }
+
+    #[test]
+    fn relation_counts_become_count_subqueries() {
+        let (sql, values) = QueryBuilderHelper::build_select(&Server::SCHEMA, options(vec![
+            WhereCondition::compare("memory.count", "<", QueryValue::Integer(16), None),
+            WhereCondition::compare("disk.smart_health", "IS NULL", QueryValue::Null, Some("OR".to_string())),
+        ])).unwrap();
+
+        assert_eq!(
+            sql,
+            "SELECT * FROM `servers` WHERE \
+             (SELECT COUNT(*) FROM `server_memory_dimms` c \
+             LEFT JOIN `component_memory_types` t ON t.`component_memory_id` = c.`component_memory_id` \
+             WHERE c.`server_id` = `servers`.`server_id`) < ? OR \
+             EXISTS (SELECT 1 FROM `server_disks` c LEFT JOIN `component_disk_types` t ON t.`component_disk_id` = c.`component_disk_id` \
+             WHERE c.`server_id` = `servers`.`server_id` AND c.`smart_health` IS NULL)"
+        );
+        assert_eq!(values, [QueryValue::Integer(16)]);
+    }
+
+    #[test]
+    fn identifiers_outside_the_schema_are_refused() {
+        let unknown_column = options(vec![WhereCondition::compare("1=1 OR server_name", "=", string("x"), None)]);
+        assert!(QueryBuilderHelper::build_select(&Server::SCHEMA, unknown_column).is_err());
+
+        let unknown_operator = options(vec![WhereCondition::compare("server_name", "= '' OR 1=1 --", string("x"), None)]);
+        assert!(QueryBuilderHelper::build_select(&Server::SCHEMA, unknown_operator).is_err());
+
+        for column in ["gpu.serial", "psu.model", "gpu.", ".model", "gpu.model.name"] {
+            let unknown_relation_column = options(vec![WhereCondition::compare(column, "=", string("x"), None)]);
+            assert!(QueryBuilderHelper::build_select(&Server::SCHEMA, unknown_relation_column).is_err(), "{}", column);
+        }
+
+        let unknown_projection = QueryOptions { columns: Some(vec!["password".to_string()]), ..options(Vec::new()) };
+        assert!(QueryBuilderHelper::build_select(&Server::SCHEMA, unknown_projection).is_err());
+
+        let empty_group = options(vec![WhereCondition { predicate: Predicate::Group(Vec::new()), logical_operator: None }]);
+        assert!(QueryBuilderHelper::build_select(&Server::SCHEMA, empty_group).is_err());
     }
-}
\ No newline at end of file
+}
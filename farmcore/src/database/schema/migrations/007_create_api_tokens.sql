@@ -0,0 +1,34 @@
+-- Create API token tables for bearer-token authentication
+-- Description: Stores hashed API tokens used by the v1 API middleware. Plaintext tokens are
+--              only returned once at creation time; the database keeps a SHA-256 hash.
+
+-- ===================================================================
+-- API TOKENS
+-- ===================================================================
+
+-- API Tokens Table
+-- scope = API   -> general API access (dashboards, automation, operators)
+-- scope = AGENT -> farm-manager agents posting to the inventory ingest routes only
+CREATE TABLE IF NOT EXISTS api_tokens (
+    token_id INT PRIMARY KEY AUTO_INCREMENT,
+
+    -- Identification
+    token_name VARCHAR(255) NOT NULL UNIQUE,
+    token_prefix VARCHAR(16) NOT NULL, -- First characters of the token, safe to display
+    token_hash CHAR(64) NOT NULL UNIQUE, -- SHA-256 hex digest of the full token
+    description TEXT,
+
+    -- Access Control
+    scope ENUM('API', 'AGENT') NOT NULL DEFAULT 'API',
+
+    -- Lifecycle
+    expires_at TIMESTAMP NULL,
+    last_used_at TIMESTAMP NULL,
+    revoked_at TIMESTAMP NULL,
+
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+
+    INDEX idx_scope (scope),
+    INDEX idx_revoked (revoked_at)
+);
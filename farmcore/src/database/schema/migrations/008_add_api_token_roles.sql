@@ -0,0 +1,17 @@
+-- Add roles to API tokens for role-based access control
+-- Description: Attaches a viewer/operator/admin role to API-scoped tokens. The middleware
+--              checks the role against a per-route permission map. AGENT tokens ignore it.
+
+-- ===================================================================
+-- API TOKEN ROLES
+-- ===================================================================
+
+-- role = VIEWER   -> read-only access (GET routes)
+-- role = OPERATOR -> viewer + inventory edits and Redfish power actions
+-- role = ADMIN    -> operator + token management and database migrations/seeding
+ALTER TABLE api_tokens
+    ADD COLUMN role ENUM('VIEWER', 'OPERATOR', 'ADMIN') NOT NULL DEFAULT 'VIEWER' AFTER scope,
+    ADD INDEX idx_role (role);
+
+-- Tokens issued before roles existed had unrestricted access; keep it that way
+UPDATE api_tokens SET role = 'ADMIN' WHERE scope = 'API';
@@ -0,0 +1,46 @@
+-- Create audit log table
+-- Description: Records every mutating v1 API call (PUT/POST/PATCH/DELETE) with the calling
+--              principal, the matched route, the outcome, column-level diffs captured from
+--              DatabaseHelper::update, and side effects such as Redfish power actions.
+
+-- ===================================================================
+-- AUDIT EVENTS
+-- ===================================================================
+
+-- Audit Events Table
+-- Principal fields are copied from the token at request time so events stay readable
+-- after a token is revoked or renamed.
+CREATE TABLE IF NOT EXISTS audit_events (
+    audit_id BIGINT PRIMARY KEY AUTO_INCREMENT,
+
+    -- Principal
+    token_id INT NULL, -- NULL for the bootstrap token
+    principal_name VARCHAR(255) NOT NULL,
+    principal_scope ENUM('API', 'AGENT') NOT NULL,
+    principal_role ENUM('VIEWER', 'OPERATOR', 'ADMIN') NULL, -- NULL for AGENT tokens
+    client_ip VARCHAR(64),
+
+    -- Request
+    http_method VARCHAR(10) NOT NULL,
+    route VARCHAR(255) NOT NULL, -- Matched route pattern, e.g. /api/v1/servers/{id}/power/on
+    request_path VARCHAR(1024) NOT NULL,
+    resource VARCHAR(64), -- First path segment under /api/v1 (servers, datacenters, ...)
+    entity_id VARCHAR(64), -- Id taken from the route parameters, if any
+
+    -- Outcome
+    status_code INT NOT NULL,
+    result ENUM('SUCCESS', 'FAILURE') NOT NULL,
+    duration_ms INT,
+
+    -- Details
+    changes JSON, -- [{table, key_column, id, old: {...}, new: {...}}]
+    actions JSON, -- [{action, target, detail, success, error}]
+
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+
+    INDEX idx_token (token_id),
+    INDEX idx_principal (principal_name),
+    INDEX idx_resource_entity (resource, entity_id),
+    INDEX idx_result (result),
+    INDEX idx_created (created_at)
+);
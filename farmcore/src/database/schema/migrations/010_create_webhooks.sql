@@ -0,0 +1,71 @@
+-- Create outbound webhook tables
+-- Description: Webhook subscriptions (target URL, signing secret, event filter) and a durable
+--              delivery log. Every matching domain event is queued as a delivery row first and
+--              sent by the background dispatcher, which retries with exponential backoff.
+
+-- ===================================================================
+-- WEBHOOK SUBSCRIPTIONS
+-- ===================================================================
+
+-- Webhook Subscriptions Table
+-- The secret is the HMAC-SHA256 key for the X-Farm-Signature header. It has to be kept in
+-- the clear to sign with, so it is only ever returned once, when the webhook is created.
+CREATE TABLE IF NOT EXISTS webhook_subscriptions (
+    webhook_id INT PRIMARY KEY AUTO_INCREMENT,
+
+    -- Identification
+    webhook_name VARCHAR(255) NOT NULL UNIQUE,
+    description TEXT,
+
+    -- Target
+    url VARCHAR(2048) NOT NULL,
+    secret VARCHAR(255) NOT NULL,
+
+    -- Filter
+    event_types JSON NOT NULL, -- ["server.status_changed", "server.*"]; ["*"] for every event
+
+    is_active BOOLEAN NOT NULL DEFAULT TRUE,
+
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+
+    INDEX idx_active (is_active)
+);
+
+-- ===================================================================
+-- WEBHOOK DELIVERIES
+-- ===================================================================
+
+-- Webhook Deliveries Table
+-- PENDING   -> waiting for next_attempt_at (new, or retrying after a failed attempt)
+-- SUCCEEDED -> the receiver answered 2xx
+-- FAILED    -> every attempt failed; not retried again
+CREATE TABLE IF NOT EXISTS webhook_deliveries (
+    delivery_id BIGINT PRIMARY KEY AUTO_INCREMENT,
+    webhook_id INT NOT NULL,
+
+    -- Event
+    event_id BIGINT NULL, -- Domain event ID; NULL for pings
+    event_type VARCHAR(64) NOT NULL,
+    payload JSON NOT NULL, -- Request body, exactly as signed
+
+    -- Progress
+    status ENUM('PENDING', 'SUCCEEDED', 'FAILED') NOT NULL DEFAULT 'PENDING',
+    attempts INT NOT NULL DEFAULT 0,
+    next_attempt_at TIMESTAMP NULL,
+    last_status_code INT NULL,
+    last_error TEXT,
+    delivered_at TIMESTAMP NULL,
+
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+
+    CONSTRAINT fk_delivery_webhook
+        FOREIGN KEY (webhook_id) REFERENCES webhook_subscriptions(webhook_id)
+        ON DELETE CASCADE,
+
+    INDEX idx_webhook (webhook_id),
+    INDEX idx_due (status, next_attempt_at),
+    INDEX idx_event_type (event_type),
+    INDEX idx_created (created_at)
+);
@@ -0,0 +1,9 @@
+-- Record routing table changes in the component change history
+-- Description: The inventory sync now stores power supplies and routes. PSU changes use the
+--              existing 'power' component type; routes get a 'route' type of their own.
+
+-- ===================================================================
+-- INVENTORY CHANGE HISTORY
+-- ===================================================================
+ALTER TABLE server_inventory_changes
+    MODIFY component_type ENUM('cpu', 'memory', 'disk', 'network', 'route', 'gpu', 'power', 'bios', 'bmc', 'chassis') NOT NULL;
@@ -0,0 +1,36 @@
+-- Identify servers by more than the MAC of their primary interface
+-- Description: Inventory is matched to a server on its SMBIOS system UUID, system, chassis and
+--              board serial numbers, BMC MAC and NIC MACs. Reports that match several servers,
+--              or match one against contradicting identifiers, are kept as identity conflicts.
+
+-- ===================================================================
+-- SERVERS
+-- ===================================================================
+ALTER TABLE servers
+    ADD COLUMN system_uuid VARCHAR(36) NULL AFTER serial_number,
+    ADD INDEX idx_system_uuid (system_uuid),
+    ADD INDEX idx_chassis_serial_number (chassis_serial_number);
+
+-- ===================================================================
+-- IDENTITY CONFLICTS
+-- ===================================================================
+CREATE TABLE IF NOT EXISTS server_identity_conflicts (
+    conflict_id INT PRIMARY KEY AUTO_INCREMENT,
+    server_id INT NULL, -- Server the inventory was applied to; NULL when it was rejected
+
+    resolution ENUM('MATCHED', 'CREATED', 'REJECTED') NOT NULL,
+    hostname VARCHAR(255),
+    confidence INT NOT NULL, -- Score (0-100) of the best-matching existing server
+    candidates JSON NOT NULL, -- Every server the report matched, with its score and identifiers
+
+    detected_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    resolved_at TIMESTAMP NULL,
+
+    CONSTRAINT fk_identity_conflict_server
+        FOREIGN KEY (server_id) REFERENCES servers(server_id)
+        ON DELETE SET NULL,
+
+    INDEX idx_identity_conflict_server (server_id),
+    INDEX idx_identity_conflict_detected (detected_at),
+    INDEX idx_identity_conflict_unresolved (resolved_at)
+);
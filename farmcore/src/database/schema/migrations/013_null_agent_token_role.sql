@@ -0,0 +1,13 @@
+-- Drop the role from AGENT tokens
+-- Description: Roles only apply to API-scoped tokens. AGENT tokens were stored with the VIEWER
+--              default, which showed up in token listings as if it meant something.
+
+-- ===================================================================
+-- API TOKEN ROLES
+-- ===================================================================
+
+-- role is NULL for AGENT tokens and set for API tokens
+ALTER TABLE api_tokens
+    MODIFY COLUMN role ENUM('VIEWER', 'OPERATOR', 'ADMIN') NULL DEFAULT 'VIEWER';
+
+UPDATE api_tokens SET role = NULL WHERE scope = 'AGENT';
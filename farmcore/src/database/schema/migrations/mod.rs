@@ -1,6 +1,9 @@
-use sqlx::{MySqlPool, Row, FromRow};
+use sqlx::{Acquire, MySqlPool, Row, FromRow, SqliteConnection, SqlitePool};
 use serde::Serialize;
 
+use crate::database::schema::sqlite_migrations;
+use crate::database::Storage;
+
 #[derive(Debug, Serialize, FromRow)]
 pub struct MigrationInfo {
     pub version: i64,
@@ -11,9 +14,13 @@ pub struct MigrationInfo {
     pub installed_on: chrono::DateTime<chrono::Utc>,
 }
 
-pub async fn run_all(pool: &MySqlPool) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+pub async fn run_all(storage: &Storage) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
     // Use SQLx built-in migration system
-    match sqlx::migrate!("./src/database/schema/migrations").run(pool).await {
+    let result = match storage {
+        Storage::MySql(pool) => sqlx::migrate!("./src/database/schema/migrations").run(pool).await,
+        Storage::Sqlite(pool) => sqlite_migrations::run_all(pool).await,
+    };
+    match result {
         Ok(_) => {
             println!("✅ All migrations completed successfully");
             Ok("All migrations completed successfully".to_string())
@@ -26,7 +33,14 @@ pub async fn run_all(pool: &MySqlPool) -> Result<String, Box<dyn std::error::Err
 }
 
 /// Reset all migrations (DANGER: This will drop all data!)
-pub async fn reset_all_migrations(pool: &MySqlPool) -> Result<String, sqlx::Error> {
+pub async fn reset_all_migrations(storage: &Storage) -> Result<String, sqlx::Error> {
+    match storage {
+        Storage::MySql(pool) => reset_mysql(pool).await,
+        Storage::Sqlite(pool) => reset_sqlite(pool).await,
+    }
+}
+
+async fn reset_mysql(pool: &MySqlPool) -> Result<String, sqlx::Error> {
     // Get list of all applied migrations first
     let migrations = sqlx::query("SELECT version, description FROM _sqlx_migrations ORDER BY version DESC")
         .fetch_all(pool)
@@ -73,4 +87,50 @@ pub async fn reset_all_migrations(pool: &MySqlPool) -> Result<String, sqlx::Erro
         "Reset migration tracking table. {} migrations were previously applied. {} tables dropped.",
         migration_count, table_count
     ))
-}
\ No newline at end of file
+}
+
+async fn reset_sqlite(pool: &SqlitePool) -> Result<String, sqlx::Error> {
+    let migrations = sqlx::query("SELECT version, description FROM _sqlx_migrations ORDER BY version DESC")
+        .fetch_all(pool)
+        .await?;
+
+    // foreign_keys cannot be switched inside a transaction, so turn it off on the connection
+    // the transaction runs on, and back on whether or not the drops succeed
+    let mut conn = pool.acquire().await?;
+    sqlx::query("PRAGMA foreign_keys = OFF")
+        .execute(&mut *conn)
+        .await?;
+    let dropped = drop_sqlite_tables(&mut conn).await;
+    sqlx::query("PRAGMA foreign_keys = ON")
+        .execute(&mut *conn)
+        .await?;
+    let table_count = dropped?;
+
+    Ok(format!(
+        "Reset migration tracking table. {} migrations were previously applied. {} tables dropped.",
+        migrations.len(), table_count
+    ))
+}
+
+async fn drop_sqlite_tables(conn: &mut SqliteConnection) -> Result<usize, sqlx::Error> {
+    let mut tx = conn.begin().await?;
+
+    let tables: Vec<String> = sqlx::query_scalar::<_, String>(
+        "SELECT name FROM sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%' AND name != '_sqlx_migrations'"
+    )
+    .fetch_all(&mut *tx)
+    .await?;
+
+    // Indexes and triggers go with their tables
+    for table in &tables {
+        let drop_sql = format!("DROP TABLE IF EXISTS \"{}\"", table);
+        sqlx::query(&drop_sql).execute(&mut *tx).await?;
+    }
+
+    sqlx::query("DELETE FROM _sqlx_migrations")
+        .execute(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+    Ok(tables.len())
+}
@@ -1,2 +1,3 @@
 pub mod migrations;
-pub mod seeds;
+pub mod sqlite_migrations;
+pub mod seeds;
\ No newline at end of file
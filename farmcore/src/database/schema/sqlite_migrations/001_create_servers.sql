@@ -0,0 +1,694 @@
+-- Create initial server inventory tables and component reference tables
+-- Description: Creates the core server inventory schema including component reference tables,
+--              server tables, and all related inventory tracking tables.
+--
+-- SQLite port of migrations/001_create_servers.sql. ENUM columns become TEXT with a CHECK constraint,
+-- JSON columns become TEXT and ON UPDATE CURRENT_TIMESTAMP becomes an AFTER UPDATE trigger.
+
+-- ===================================================================
+-- COMPONENT REFERENCE TABLES (Must be created first for foreign keys)
+-- ===================================================================
+
+-- CPU Component Types
+CREATE TABLE IF NOT EXISTS component_cpu_types (
+    component_cpu_id INTEGER PRIMARY KEY AUTOINCREMENT,
+    
+    manufacturer VARCHAR(255) NOT NULL,
+    model_name VARCHAR(255) NOT NULL,
+    num_cores INT,
+    num_threads INT,
+    capacity_mhz INT,
+    
+    -- Cache Information
+    l1_cache_kb INT,
+    l2_cache_kb INT,
+    l3_cache_kb INT,
+    
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS uk_component_cpu_types_cpu_model ON component_cpu_types (manufacturer, model_name, num_cores, num_threads, capacity_mhz);
+CREATE INDEX IF NOT EXISTS idx_component_cpu_types_manufacturer_model ON component_cpu_types (manufacturer, model_name);
+
+-- Memory/DIMM Component Types  
+CREATE TABLE IF NOT EXISTS component_memory_types (
+    component_memory_id INTEGER PRIMARY KEY AUTOINCREMENT,
+    
+    manufacturer VARCHAR(255) NOT NULL,
+    part_number VARCHAR(255) NOT NULL,
+    size_bytes BIGINT NOT NULL,
+    mem_type VARCHAR(50) NOT NULL,
+    speed_mt_s INT,
+    
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS uk_component_memory_types_memory_part ON component_memory_types (manufacturer, part_number);
+CREATE INDEX IF NOT EXISTS idx_component_memory_types_memory_specs ON component_memory_types (mem_type, size_bytes, speed_mt_s);
+
+-- Motherboard Component Types
+CREATE TABLE IF NOT EXISTS component_motherboard_types (
+    component_motherboard_id INTEGER PRIMARY KEY AUTOINCREMENT,
+    
+    manufacturer VARCHAR(255) NOT NULL,
+    product_name VARCHAR(255) NOT NULL,
+    version VARCHAR(255),
+    bios_version VARCHAR(255),  -- Latest/recommended BIOS version for this motherboard model
+    bmc_firmware_version VARCHAR(255),  -- Latest/recommended BMC firmware version for this motherboard model
+    
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS uk_component_motherboard_types_motherboard_model ON component_motherboard_types (manufacturer, product_name, version);
+CREATE INDEX IF NOT EXISTS idx_component_motherboard_types_manufacturer_product ON component_motherboard_types (manufacturer, product_name);
+CREATE INDEX IF NOT EXISTS idx_component_motherboard_types_bios_version ON component_motherboard_types (bios_version);
+CREATE INDEX IF NOT EXISTS idx_component_motherboard_types_bmc_firmware ON component_motherboard_types (bmc_firmware_version);
+
+-- Disk Component Types
+CREATE TABLE IF NOT EXISTS component_disk_types (
+    component_disk_id INTEGER PRIMARY KEY AUTOINCREMENT,
+    
+    manufacturer VARCHAR(255),
+    model VARCHAR(255) NOT NULL,
+    size_bytes BIGINT,
+    rotational BOOLEAN DEFAULT FALSE,
+    bus_type VARCHAR(50),
+    
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS uk_component_disk_types_disk_model ON component_disk_types (manufacturer, model, size_bytes);
+CREATE INDEX IF NOT EXISTS idx_component_disk_types_disk_specs ON component_disk_types (bus_type, size_bytes, rotational);
+
+-- Network Interface Component Types
+CREATE TABLE IF NOT EXISTS component_network_types (
+    component_network_id INTEGER PRIMARY KEY AUTOINCREMENT,
+    
+    vendor_name VARCHAR(255),
+    device_name VARCHAR(255) NOT NULL,
+    driver VARCHAR(128),
+    max_speed_mbps INT,
+    
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS uk_component_network_types_network_device ON component_network_types (vendor_name, device_name, driver);
+CREATE INDEX IF NOT EXISTS idx_component_network_types_vendor_device ON component_network_types (vendor_name, device_name);
+
+-- GPU Component Types
+CREATE TABLE IF NOT EXISTS component_gpu_types (
+    component_gpu_id INTEGER PRIMARY KEY AUTOINCREMENT,
+    
+    vendor VARCHAR(255) NOT NULL,
+    model VARCHAR(255) NOT NULL,
+    vram_mb INT,
+    
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS uk_component_gpu_types_gpu_model ON component_gpu_types (vendor, model, vram_mb);
+CREATE INDEX IF NOT EXISTS idx_component_gpu_types_vendor_model ON component_gpu_types (vendor, model);
+
+-- Switch Model/Hardware Types
+CREATE TABLE IF NOT EXISTS component_switch_types (
+    component_switch_id INTEGER PRIMARY KEY AUTOINCREMENT,
+    
+    vendor VARCHAR(255) NOT NULL,
+    model VARCHAR(255) NOT NULL,
+    series VARCHAR(255),
+    
+    -- Hardware specifications
+    total_ports INT,
+    max_power_watts INT,
+    form_factor TEXT CHECK (form_factor IN ('1U', '2U', '3U', '4U', 'Desktop', 'Modular', 'Other')),
+    rack_units REAL,
+    
+    -- Port capabilities
+    gigabit_ports INT DEFAULT 0,
+    ten_gig_ports INT DEFAULT 0,
+    twenty_five_gig_ports INT DEFAULT 0,
+    forty_gig_ports INT DEFAULT 0,
+    hundred_gig_ports INT DEFAULT 0,
+    
+    -- Management capabilities
+    supports_snmp BOOLEAN DEFAULT TRUE,
+    supports_ssh BOOLEAN DEFAULT TRUE,
+    supports_telnet BOOLEAN DEFAULT FALSE,
+    supports_web_ui BOOLEAN DEFAULT TRUE,
+    
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS uk_component_switch_types_switch_model ON component_switch_types (vendor, model, series);
+CREATE INDEX IF NOT EXISTS idx_component_switch_types_vendor_model ON component_switch_types (vendor, model);
+CREATE INDEX IF NOT EXISTS idx_component_switch_types_form_factor ON component_switch_types (form_factor);
+CREATE INDEX IF NOT EXISTS idx_component_switch_types_port_count ON component_switch_types (total_ports);
+
+-- BMC (Baseboard Management Controller) Component Types
+CREATE TABLE IF NOT EXISTS component_bmc_types (
+    component_bmc_id INTEGER PRIMARY KEY AUTOINCREMENT,
+    
+    vendor VARCHAR(255) NOT NULL,
+    model VARCHAR(255) NOT NULL,
+    firmware_version VARCHAR(255),  -- Latest/recommended firmware version for this BMC model
+    
+    -- Management capabilities
+    supports_ipmi BOOLEAN DEFAULT TRUE,
+    supports_redfish BOOLEAN DEFAULT FALSE,
+    supports_web_interface BOOLEAN DEFAULT TRUE,
+    supports_kvm BOOLEAN DEFAULT FALSE,
+    supports_virtual_media BOOLEAN DEFAULT FALSE,
+    
+    -- Network specifications
+    has_dedicated_port BOOLEAN DEFAULT TRUE,
+    max_speed_mbps INT DEFAULT 1000,
+    
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS uk_component_bmc_types_bmc_model ON component_bmc_types (vendor, model, firmware_version);
+CREATE INDEX IF NOT EXISTS idx_component_bmc_types_vendor_model ON component_bmc_types (vendor, model);
+CREATE INDEX IF NOT EXISTS idx_component_bmc_types_capabilities ON component_bmc_types (supports_ipmi, supports_redfish);
+
+-- ===================================================================
+-- CORE SERVERS TABLE
+-- ===================================================================
+CREATE TABLE IF NOT EXISTS servers (
+    server_id INTEGER PRIMARY KEY AUTOINCREMENT,
+    
+    -- Node Information
+    server_name VARCHAR(255) NOT NULL UNIQUE,
+    architecture VARCHAR(50) DEFAULT 'x86_64',
+    product_name VARCHAR(255),
+    manufacturer VARCHAR(255),
+    serial_number VARCHAR(255),
+    
+    -- Chassis Information
+    chassis_manufacturer VARCHAR(255),
+    chassis_serial_number VARCHAR(255),
+    
+    -- Management Fields
+    server_type TEXT DEFAULT 'BAREMETAL' CHECK (server_type IN ('BAREMETAL', 'HOST', 'STORAGE', 'COMPUTE')),
+    status TEXT NOT NULL DEFAULT 'INACTIVE' CHECK (status IN ('ACTIVE', 'INACTIVE', 'MAINTENANCE', 'RMA', 'DECOMMISSIONED')),
+    state TEXT NOT NULL DEFAULT 'NEW' CHECK (state IN ('NEW', 'ONBOARDING', 'PROVISIONING', 'RUNNING', 'SUSPENDED', 'DEPROVISIONING', 'FAILED')),
+    stage TEXT NOT NULL DEFAULT 'NONE' CHECK (stage IN ('NONE', 'DISCOVERY', 'ALLOCATE_RESOURCES', 'INSTALL_OS', 'CONFIGURE_NETWORK', 'WIPE_DISKS', 'WIPE_NIC_CONFIG', 'RELEASE_IPS', 'FINALIZE')),
+    environment_type TEXT DEFAULT 'PRODUCTION' CHECK (environment_type IN ('PRODUCTION', 'DEVELOPMENT', 'QA', 'STAGING', 'TESTING')),
+    
+    -- Location Fields
+    cluster_id INT DEFAULT 0,
+    sub_cluster_id INT DEFAULT 0,
+    data_center_id INT DEFAULT 0,
+    rack_id INT DEFAULT 0,
+    rack_position_id INT DEFAULT 0,
+    u_height INT NOT NULL DEFAULT 1,
+    
+    -- Inventory Tracking
+    last_inventory_at TIMESTAMP NULL,
+    agent_version VARCHAR(50),
+    
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_servers_server_name ON servers (server_name);
+CREATE INDEX IF NOT EXISTS idx_servers_serial_number ON servers (serial_number);
+CREATE INDEX IF NOT EXISTS idx_servers_status ON servers (status);
+CREATE INDEX IF NOT EXISTS idx_servers_last_inventory ON servers (last_inventory_at);
+
+CREATE TRIGGER IF NOT EXISTS trg_servers_updated_at
+    AFTER UPDATE ON servers FOR EACH ROW
+    WHEN NEW.updated_at = OLD.updated_at
+BEGIN
+    UPDATE servers SET updated_at = CURRENT_TIMESTAMP WHERE server_id = NEW.server_id;
+END;
+
+-- ===================================================================
+-- CPU INFORMATION (References component_cpu_types)
+-- ===================================================================
+CREATE TABLE IF NOT EXISTS server_cpus (
+    cpu_id INTEGER PRIMARY KEY AUTOINCREMENT,
+    server_id INT NOT NULL,
+    component_cpu_id INT NOT NULL,
+    
+    socket_number INT NOT NULL,
+    slot VARCHAR(50),
+    
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    
+    CONSTRAINT fk_cpu_server
+        FOREIGN KEY (server_id) REFERENCES servers(server_id)
+        ON DELETE CASCADE,
+        
+    CONSTRAINT fk_cpu_component
+        FOREIGN KEY (component_cpu_id) REFERENCES component_cpu_types(component_cpu_id)
+        ON DELETE RESTRICT
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS uk_server_cpus_server_socket ON server_cpus (server_id, socket_number);
+CREATE INDEX IF NOT EXISTS idx_server_cpus_component_cpu ON server_cpus (component_cpu_id);
+
+CREATE TRIGGER IF NOT EXISTS trg_server_cpus_updated_at
+    AFTER UPDATE ON server_cpus FOR EACH ROW
+    WHEN NEW.updated_at = OLD.updated_at
+BEGIN
+    UPDATE server_cpus SET updated_at = CURRENT_TIMESTAMP WHERE cpu_id = NEW.cpu_id;
+END;
+
+-- ===================================================================
+-- MOTHERBOARD INFORMATION (References component_motherboard_types)
+-- ===================================================================
+CREATE TABLE IF NOT EXISTS server_motherboards (
+    motherboard_id INTEGER PRIMARY KEY AUTOINCREMENT,
+    server_id INT NOT NULL,
+    component_motherboard_id INT NOT NULL,
+    
+    serial_number VARCHAR(255),
+    
+    -- BIOS Information (actual installed version)
+    bios_vendor VARCHAR(255),
+    bios_version VARCHAR(255),
+    bios_release_date DATE,
+    
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    
+    CONSTRAINT fk_motherboard_server
+        FOREIGN KEY (server_id) REFERENCES servers(server_id)
+        ON DELETE CASCADE,
+        
+    CONSTRAINT fk_motherboard_component
+        FOREIGN KEY (component_motherboard_id) REFERENCES component_motherboard_types(component_motherboard_id)
+        ON DELETE RESTRICT
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS uk_server_motherboards_server_motherboard ON server_motherboards (server_id);
+CREATE INDEX IF NOT EXISTS idx_server_motherboards_component_motherboard ON server_motherboards (component_motherboard_id);
+CREATE INDEX IF NOT EXISTS idx_server_motherboards_serial_number ON server_motherboards (serial_number);
+
+CREATE TRIGGER IF NOT EXISTS trg_server_motherboards_updated_at
+    AFTER UPDATE ON server_motherboards FOR EACH ROW
+    WHEN NEW.updated_at = OLD.updated_at
+BEGIN
+    UPDATE server_motherboards SET updated_at = CURRENT_TIMESTAMP WHERE motherboard_id = NEW.motherboard_id;
+END;
+
+-- ===================================================================
+-- MEMORY/DIMM INFORMATION (References component_memory_types)
+-- ===================================================================
+CREATE TABLE IF NOT EXISTS server_memory_dimms (
+    dimm_id INTEGER PRIMARY KEY AUTOINCREMENT,
+    server_id INT NOT NULL,
+    component_memory_id INT NOT NULL,
+    
+    slot VARCHAR(255) NOT NULL,
+    serial_number VARCHAR(255),
+    
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    
+    CONSTRAINT fk_dimm_server
+        FOREIGN KEY (server_id) REFERENCES servers(server_id)
+        ON DELETE CASCADE,
+        
+    CONSTRAINT fk_dimm_component
+        FOREIGN KEY (component_memory_id) REFERENCES component_memory_types(component_memory_id)
+        ON DELETE RESTRICT
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS uk_server_memory_dimms_server_dimm_slot ON server_memory_dimms (server_id, slot);
+CREATE INDEX IF NOT EXISTS idx_server_memory_dimms_component_memory ON server_memory_dimms (component_memory_id);
+CREATE INDEX IF NOT EXISTS idx_server_memory_dimms_serial_number ON server_memory_dimms (serial_number);
+
+CREATE TRIGGER IF NOT EXISTS trg_server_memory_dimms_updated_at
+    AFTER UPDATE ON server_memory_dimms FOR EACH ROW
+    WHEN NEW.updated_at = OLD.updated_at
+BEGIN
+    UPDATE server_memory_dimms SET updated_at = CURRENT_TIMESTAMP WHERE dimm_id = NEW.dimm_id;
+END;
+
+-- ===================================================================
+-- DISK/STORAGE INFORMATION (References component_disk_types)
+-- ===================================================================
+CREATE TABLE IF NOT EXISTS server_disks (
+    disk_id INTEGER PRIMARY KEY AUTOINCREMENT,
+    server_id INT NOT NULL,
+    component_disk_id INT NOT NULL,
+    
+    name VARCHAR(100) NOT NULL,
+    dev_path VARCHAR(255),
+    serial VARCHAR(255),
+    firmware_version VARCHAR(255),
+    
+    -- SMART Information
+    smart_health VARCHAR(50),
+    
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    
+    CONSTRAINT fk_disk_server
+        FOREIGN KEY (server_id) REFERENCES servers(server_id)
+        ON DELETE CASCADE,
+        
+    CONSTRAINT fk_disk_component
+        FOREIGN KEY (component_disk_id) REFERENCES component_disk_types(component_disk_id)
+        ON DELETE RESTRICT
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS uk_server_disks_server_disk_name ON server_disks (server_id, name);
+CREATE INDEX IF NOT EXISTS idx_server_disks_component_disk ON server_disks (component_disk_id);
+CREATE INDEX IF NOT EXISTS idx_server_disks_serial ON server_disks (serial);
+CREATE INDEX IF NOT EXISTS idx_server_disks_smart_health ON server_disks (smart_health);
+
+CREATE TRIGGER IF NOT EXISTS trg_server_disks_updated_at
+    AFTER UPDATE ON server_disks FOR EACH ROW
+    WHEN NEW.updated_at = OLD.updated_at
+BEGIN
+    UPDATE server_disks SET updated_at = CURRENT_TIMESTAMP WHERE disk_id = NEW.disk_id;
+END;
+
+-- ===================================================================
+-- NETWORK INTERFACES (References component_network_types)
+-- ===================================================================
+CREATE TABLE IF NOT EXISTS server_network_interfaces (
+    interface_id INTEGER PRIMARY KEY AUTOINCREMENT,
+    server_id INT NOT NULL,
+    component_network_id INT NOT NULL,
+    
+    name VARCHAR(64) NOT NULL,
+    mac_address VARCHAR(17),
+    ip_address VARCHAR(45), -- Primary IP address for this interface
+    mtu INT,
+    speed_mbps INT,
+    firmware_version VARCHAR(255),
+    pci_address VARCHAR(64),
+    
+    -- Primary interface flag for SSH/main access
+    is_primary BOOLEAN DEFAULT FALSE,
+    -- Interface type (REGULAR, MANAGEMENT)
+    interface_type TEXT DEFAULT 'REGULAR' CHECK (interface_type IN ('REGULAR', 'MANAGEMENT')),
+    -- Bond/team group identifier for grouped interfaces
+    bond_group VARCHAR(64),
+    -- Master interface for bonded/teamed setups
+    bond_master VARCHAR(64),
+    
+    -- Switch port connection
+    switch_port_id INT,
+    
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    
+    CONSTRAINT fk_interface_server
+        FOREIGN KEY (server_id) REFERENCES servers(server_id)
+        ON DELETE CASCADE,
+        
+    CONSTRAINT fk_interface_component
+        FOREIGN KEY (component_network_id) REFERENCES component_network_types(component_network_id)
+        ON DELETE RESTRICT
+        
+    -- Note: switch_port_id will reference m002_create_switches.switch_ports(switch_port_id)
+    -- Foreign key constraint added after switch tables exist
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS uk_server_network_interfaces_server_interface_name ON server_network_interfaces (server_id, name);
+CREATE INDEX IF NOT EXISTS idx_server_network_interfaces_component_network ON server_network_interfaces (component_network_id);
+CREATE INDEX IF NOT EXISTS idx_server_network_interfaces_mac_address ON server_network_interfaces (mac_address);
+CREATE INDEX IF NOT EXISTS idx_server_network_interfaces_pci_address ON server_network_interfaces (pci_address);
+CREATE INDEX IF NOT EXISTS idx_server_network_interfaces_is_primary ON server_network_interfaces (is_primary);
+CREATE INDEX IF NOT EXISTS idx_server_network_interfaces_interface_type ON server_network_interfaces (interface_type);
+CREATE INDEX IF NOT EXISTS idx_server_network_interfaces_bond_group ON server_network_interfaces (bond_group);
+CREATE INDEX IF NOT EXISTS idx_server_network_interfaces_bond_master ON server_network_interfaces (bond_master);
+CREATE INDEX IF NOT EXISTS idx_server_network_interfaces_switch_port ON server_network_interfaces (switch_port_id);
+
+CREATE TRIGGER IF NOT EXISTS trg_server_network_interfaces_updated_at
+    AFTER UPDATE ON server_network_interfaces FOR EACH ROW
+    WHEN NEW.updated_at = OLD.updated_at
+BEGIN
+    UPDATE server_network_interfaces SET updated_at = CURRENT_TIMESTAMP WHERE interface_id = NEW.interface_id;
+END;
+
+-- ===================================================================
+-- BMC INTERFACES (Dedicated table for Baseboard Management Controllers)
+-- ===================================================================
+CREATE TABLE IF NOT EXISTS server_bmc_interfaces (
+    bmc_interface_id INTEGER PRIMARY KEY AUTOINCREMENT,
+    server_id INT NULL, -- NULL = unassigned/pre-onboarding BMC
+    component_bmc_id INT NOT NULL,
+    
+    name VARCHAR(64) NOT NULL DEFAULT 'bmc0',
+    mac_address VARCHAR(17),
+    ip_address VARCHAR(45), -- BMC IP address
+    
+    -- BMC Authentication
+    username VARCHAR(255),
+    password TEXT,
+    
+    -- BMC-specific information (actual installed version)
+    firmware_version VARCHAR(255),
+    release_date DATE,
+    
+    -- Connection status
+    is_accessible BOOLEAN DEFAULT FALSE,
+    last_ping_at TIMESTAMP NULL,
+    
+    -- Switch port connection (if BMC is connected to a managed switch)
+    switch_port_id INT,
+    
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+        
+    CONSTRAINT fk_bmc_component
+        FOREIGN KEY (component_bmc_id) REFERENCES component_bmc_types(component_bmc_id)
+        ON DELETE RESTRICT
+        
+    -- Note: switch_port_id will reference m002_create_switches.switch_ports(switch_port_id)
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS uk_server_bmc_interfaces_server_bmc_name ON server_bmc_interfaces (server_id, name);
+CREATE INDEX IF NOT EXISTS idx_server_bmc_interfaces_bmc_component ON server_bmc_interfaces (component_bmc_id);
+CREATE INDEX IF NOT EXISTS idx_server_bmc_interfaces_bmc_mac_address ON server_bmc_interfaces (mac_address);
+CREATE INDEX IF NOT EXISTS idx_server_bmc_interfaces_bmc_ip_address ON server_bmc_interfaces (ip_address);
+CREATE INDEX IF NOT EXISTS idx_server_bmc_interfaces_bmc_accessible ON server_bmc_interfaces (is_accessible);
+CREATE INDEX IF NOT EXISTS idx_server_bmc_interfaces_bmc_switch_port ON server_bmc_interfaces (switch_port_id);
+
+CREATE TRIGGER IF NOT EXISTS trg_server_bmc_interfaces_updated_at
+    AFTER UPDATE ON server_bmc_interfaces FOR EACH ROW
+    WHEN NEW.updated_at = OLD.updated_at
+BEGIN
+    UPDATE server_bmc_interfaces SET updated_at = CURRENT_TIMESTAMP WHERE bmc_interface_id = NEW.bmc_interface_id;
+END;
+
+-- ===================================================================
+-- NETWORK INTERFACE IP ADDRESSES
+-- ===================================================================
+CREATE TABLE IF NOT EXISTS server_network_addresses (
+    address_id INTEGER PRIMARY KEY AUTOINCREMENT,
+    interface_id INT NOT NULL,
+    
+    family TEXT NOT NULL CHECK (family IN ('inet', 'inet6')),
+    address VARCHAR(128) NOT NULL,
+    prefix_length INT NOT NULL,
+    
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    
+    CONSTRAINT fk_address_interface
+        FOREIGN KEY (interface_id) REFERENCES server_network_interfaces(interface_id)
+        ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_server_network_addresses_address ON server_network_addresses (address);
+
+-- ===================================================================
+-- NETWORK ROUTES
+-- ===================================================================
+CREATE TABLE IF NOT EXISTS server_network_routes (
+    route_id INTEGER PRIMARY KEY AUTOINCREMENT,
+    server_id INT NOT NULL,
+    
+    destination VARCHAR(128) NOT NULL,
+    gateway VARCHAR(128),
+    interface_name VARCHAR(64) NOT NULL,
+    
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    
+    CONSTRAINT fk_route_server
+        FOREIGN KEY (server_id) REFERENCES servers(server_id)
+        ON DELETE CASCADE
+);
+
+CREATE TRIGGER IF NOT EXISTS trg_server_network_routes_updated_at
+    AFTER UPDATE ON server_network_routes FOR EACH ROW
+    WHEN NEW.updated_at = OLD.updated_at
+BEGIN
+    UPDATE server_network_routes SET updated_at = CURRENT_TIMESTAMP WHERE route_id = NEW.route_id;
+END;
+
+-- ===================================================================
+-- GPU INFORMATION (References component_gpu_types)
+-- ===================================================================
+CREATE TABLE IF NOT EXISTS server_gpus (
+    gpu_id INTEGER PRIMARY KEY AUTOINCREMENT,
+    server_id INT NOT NULL,
+    component_gpu_id INT NOT NULL,
+    
+    pci_address VARCHAR(64),
+    driver_version VARCHAR(255),
+    uuid VARCHAR(255),
+    
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    
+    CONSTRAINT fk_gpu_server
+        FOREIGN KEY (server_id) REFERENCES servers(server_id)
+        ON DELETE CASCADE,
+        
+    CONSTRAINT fk_gpu_component
+        FOREIGN KEY (component_gpu_id) REFERENCES component_gpu_types(component_gpu_id)
+        ON DELETE RESTRICT
+);
+
+CREATE INDEX IF NOT EXISTS idx_server_gpus_component_gpu ON server_gpus (component_gpu_id);
+CREATE INDEX IF NOT EXISTS idx_server_gpus_uuid ON server_gpus (uuid);
+CREATE INDEX IF NOT EXISTS idx_server_gpus_pci_address ON server_gpus (pci_address);
+
+CREATE TRIGGER IF NOT EXISTS trg_server_gpus_updated_at
+    AFTER UPDATE ON server_gpus FOR EACH ROW
+    WHEN NEW.updated_at = OLD.updated_at
+BEGIN
+    UPDATE server_gpus SET updated_at = CURRENT_TIMESTAMP WHERE gpu_id = NEW.gpu_id;
+END;
+
+-- ===================================================================
+-- POWER SUPPLY INFORMATION
+-- ===================================================================
+CREATE TABLE IF NOT EXISTS server_power_supplies (
+    psu_id INTEGER PRIMARY KEY AUTOINCREMENT,
+    server_id INT NOT NULL,
+    
+    name VARCHAR(255),
+    manufacturer VARCHAR(255),
+    model VARCHAR(255),
+    serial_number VARCHAR(255),
+    part_number VARCHAR(255),
+    max_power_watts INT,
+    efficiency_rating VARCHAR(50),
+    status VARCHAR(50),
+    input_voltage REAL,
+    input_current REAL,
+    output_voltage REAL,
+    output_current REAL,
+    temperature_c INT,
+    fan_speed_rpm INT,
+    
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    
+    CONSTRAINT fk_psu_server
+        FOREIGN KEY (server_id) REFERENCES servers(server_id)
+        ON DELETE CASCADE
+);
+
+CREATE TRIGGER IF NOT EXISTS trg_server_power_supplies_updated_at
+    AFTER UPDATE ON server_power_supplies FOR EACH ROW
+    WHEN NEW.updated_at = OLD.updated_at
+BEGIN
+    UPDATE server_power_supplies SET updated_at = CURRENT_TIMESTAMP WHERE psu_id = NEW.psu_id;
+END;
+
+-- ===================================================================
+-- INVENTORY SNAPSHOTS FOR HISTORICAL TRACKING
+-- ===================================================================
+CREATE TABLE IF NOT EXISTS server_inventory_snapshots (
+    snapshot_id INTEGER PRIMARY KEY AUTOINCREMENT,
+    server_id INT NOT NULL,
+    
+    agent_version VARCHAR(50),
+    inventory_data TEXT NOT NULL,
+    checksum VARCHAR(64) NOT NULL,
+    
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    
+    CONSTRAINT fk_snapshot_server
+        FOREIGN KEY (server_id) REFERENCES servers(server_id)
+        ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_server_inventory_snapshots_server_created ON server_inventory_snapshots (server_id, created_at);
+CREATE INDEX IF NOT EXISTS idx_server_inventory_snapshots_checksum ON server_inventory_snapshots (checksum);
+
+-- ===================================================================
+-- INVENTORY CHANGES FOR TRACKING DIFFERENCES
+-- ===================================================================
+CREATE TABLE IF NOT EXISTS server_inventory_changes (
+    change_id INTEGER PRIMARY KEY AUTOINCREMENT,
+    server_id INT NOT NULL,
+    
+    component_type TEXT NOT NULL CHECK (component_type IN ('cpu', 'memory', 'disk', 'network', 'gpu', 'power', 'bios', 'bmc', 'chassis')),
+    component_identifier VARCHAR(255) NOT NULL, -- slot, name, pci_address, etc.
+    change_type TEXT NOT NULL CHECK (change_type IN ('added', 'removed', 'modified')),
+    
+    old_data TEXT,
+    new_data TEXT,
+    
+    detected_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    
+    CONSTRAINT fk_change_server
+        FOREIGN KEY (server_id) REFERENCES servers(server_id)
+        ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_server_inventory_changes_server_component ON server_inventory_changes (server_id, component_type);
+CREATE INDEX IF NOT EXISTS idx_server_inventory_changes_change_type ON server_inventory_changes (change_type);
+CREATE INDEX IF NOT EXISTS idx_server_inventory_changes_detected_at ON server_inventory_changes (detected_at);
+
+-- ===================================================================
+-- MISSING COMPONENTS FOR EASY REPORTING
+-- ===================================================================
+CREATE TABLE IF NOT EXISTS server_missing_components (
+    missing_id INTEGER PRIMARY KEY AUTOINCREMENT,
+    server_id INT NOT NULL,
+    
+    component_type TEXT NOT NULL CHECK (component_type IN ('cpu', 'memory', 'disk', 'network', 'gpu', 'power')),
+    component_identifier VARCHAR(255) NOT NULL,
+    last_seen_data TEXT NOT NULL,
+    
+    missing_since TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    resolved_at TIMESTAMP NULL,
+    
+    CONSTRAINT fk_missing_server
+        FOREIGN KEY (server_id) REFERENCES servers(server_id)
+        ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_server_missing_components_server_missing ON server_missing_components (server_id, component_type);
+CREATE INDEX IF NOT EXISTS idx_server_missing_components_missing_since ON server_missing_components (missing_since);
+CREATE INDEX IF NOT EXISTS idx_server_missing_components_unresolved ON server_missing_components (resolved_at); -- For finding currently missing components
+
+-- ===================================================================
+-- SERVER CREDENTIALS
+-- ===================================================================
+CREATE TABLE IF NOT EXISTS server_credentials (
+    credential_id INTEGER PRIMARY KEY AUTOINCREMENT,
+    server_id INT NOT NULL,
+    
+    credential_type TEXT NOT NULL DEFAULT 'OS' CHECK (credential_type IN ('OS')),
+    username VARCHAR(255) NOT NULL,
+    password TEXT NOT NULL,
+    
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    
+    CONSTRAINT fk_credential_server
+        FOREIGN KEY (server_id) REFERENCES servers(server_id)
+        ON DELETE CASCADE
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS uk_server_credentials_server_credential_type ON server_credentials (server_id, credential_type, username);
+
+CREATE TRIGGER IF NOT EXISTS trg_server_credentials_updated_at
+    AFTER UPDATE ON server_credentials FOR EACH ROW
+    WHEN NEW.updated_at = OLD.updated_at
+BEGIN
+    UPDATE server_credentials SET updated_at = CURRENT_TIMESTAMP WHERE credential_id = NEW.credential_id;
+END;
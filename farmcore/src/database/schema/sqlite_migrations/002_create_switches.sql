@@ -0,0 +1,291 @@
+-- Create network switch inventory tables and switching infrastructure schema
+-- Description: Creates the complete network switch management schema including switch types,
+--              switch inventory, port management, VLAN configuration, and network topology tracking.
+--
+-- SQLite port of migrations/002_create_switches.sql. ENUM columns become TEXT with a CHECK constraint,
+-- JSON columns become TEXT and ON UPDATE CURRENT_TIMESTAMP becomes an AFTER UPDATE trigger.
+
+-- ===================================================================
+-- SWITCH COMPONENT REFERENCE TABLES (Must be created first for foreign keys)
+-- ===================================================================
+
+-- ===================================================================
+-- CORE SWITCHES TABLE
+-- ===================================================================
+CREATE TABLE IF NOT EXISTS switches (
+    switch_id INTEGER PRIMARY KEY AUTOINCREMENT,
+
+    -- Switch Information
+    switch_name VARCHAR(255) NOT NULL UNIQUE,
+    component_switch_id INT,
+    serial_number VARCHAR(255),
+    asset_tag VARCHAR(255),
+    
+    -- Firmware/OS Information
+    os_type VARCHAR(100),
+    os_version VARCHAR(255),
+    bootrom_version VARCHAR(255),
+    
+    -- Management Interface
+    mgmt_ip_address VARCHAR(45),
+    mgmt_mac_address VARCHAR(17),
+    mgmt_vlan_id INT,
+    
+    -- Physical Information  
+    uptime_seconds BIGINT,
+    temperature_celsius INT,
+    fan_status VARCHAR(50),
+    power_consumption_watts INT,
+    
+    -- Management Fields
+    switch_role TEXT DEFAULT 'ACCESS' CHECK (switch_role IN ('ACCESS', 'DISTRIBUTION', 'CORE', 'EDGE', 'MANAGEMENT', 'OOB')),
+    status TEXT DEFAULT 'NEW' CHECK (status IN ('ACTIVE', 'INACTIVE', 'MAINTENANCE', 'NEW', 'RMA', 'DECOMMISSIONED')),
+    environment_type TEXT DEFAULT 'PRODUCTION' CHECK (environment_type IN ('PRODUCTION', 'DEVELOPMENT', 'QA', 'STAGING', 'TESTING')),
+    
+    -- Location Fields
+    cluster_id INT DEFAULT 0,
+    sub_cluster_id INT DEFAULT 0,
+    data_center_id INT DEFAULT 0,
+    rack_id INT DEFAULT 0,
+    rack_position_id INT DEFAULT 0,
+    
+    -- Inventory Tracking
+    last_poll_at TIMESTAMP NULL,
+    poll_interval_seconds INT DEFAULT 300,
+    
+    -- Authentication Configuration
+    auth_method TEXT DEFAULT 'LOCAL' CHECK (auth_method IN ('LOCAL', 'RADIUS', 'TACACS', 'LDAP', 'CERTIFICATE')),
+    auth_server_ip VARCHAR(45),
+    auth_server_port INT,
+    auth_shared_secret VARCHAR(255),
+    
+    -- SNMP Configuration (typically uses dedicated credentials for performance/reliability)
+    snmp_community VARCHAR(255),
+    snmp_version TEXT DEFAULT 'v2c' CHECK (snmp_version IN ('v1', 'v2c', 'v3')),
+    snmp_auth_protocol TEXT CHECK (snmp_auth_protocol IN ('MD5', 'SHA', 'SHA224', 'SHA256', 'SHA384', 'SHA512')),
+    snmp_priv_protocol TEXT CHECK (snmp_priv_protocol IN ('DES', 'AES128', 'AES192', 'AES256')),
+    
+    -- Service Account (for automation that can't use interactive auth)
+    service_username VARCHAR(255),
+    service_password TEXT,
+    
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    
+    CONSTRAINT fk_switch_component
+        FOREIGN KEY (component_switch_id) REFERENCES component_switch_types(component_switch_id)
+        ON DELETE SET NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_switches_switch_name ON switches (switch_name);
+CREATE INDEX IF NOT EXISTS idx_switches_serial_number ON switches (serial_number);
+CREATE INDEX IF NOT EXISTS idx_switches_mgmt_ip ON switches (mgmt_ip_address);
+CREATE INDEX IF NOT EXISTS idx_switches_status ON switches (status);
+CREATE INDEX IF NOT EXISTS idx_switches_switch_role ON switches (switch_role);
+CREATE INDEX IF NOT EXISTS idx_switches_component_switch ON switches (component_switch_id);
+
+CREATE TRIGGER IF NOT EXISTS trg_switches_updated_at
+    AFTER UPDATE ON switches FOR EACH ROW
+    WHEN NEW.updated_at = OLD.updated_at
+BEGIN
+    UPDATE switches SET updated_at = CURRENT_TIMESTAMP WHERE switch_id = NEW.switch_id;
+END;
+
+-- ===================================================================
+-- SWITCH PORTS
+-- ===================================================================
+CREATE TABLE IF NOT EXISTS switch_ports (
+    switch_port_id INTEGER PRIMARY KEY AUTOINCREMENT,
+    switch_id INT NOT NULL,
+    
+    -- Port Identification
+    name VARCHAR(64) NOT NULL,           -- e.g. 'Gi1/0/1', 'Ethernet1/1'
+    port_index INT,                      -- SNMP ifIndex
+    port_type TEXT DEFAULT 'ETHERNET' CHECK (port_type IN ('ETHERNET', 'SFP', 'SFP+', 'QSFP', 'QSFP+', 'QSFP28', 'MANAGEMENT')),
+    
+    -- Port Configuration
+    admin_status TEXT DEFAULT 'DOWN' CHECK (admin_status IN ('UP', 'DOWN', 'TESTING')),
+    oper_status TEXT DEFAULT 'DOWN' CHECK (oper_status IN ('UP', 'DOWN', 'TESTING', 'UNKNOWN', 'DORMANT', 'NOTPRESENT', 'LOWERLAYERDOWN')),
+    speed_mbps BIGINT,
+    duplex TEXT DEFAULT 'AUTO' CHECK (duplex IN ('FULL', 'HALF', 'AUTO', 'UNKNOWN')),
+    mtu INT DEFAULT 1500,
+    
+    -- VLAN Configuration
+    access_vlan_id INT,
+    native_vlan_id INT,
+    port_mode TEXT DEFAULT 'ACCESS' CHECK (port_mode IN ('ACCESS', 'TRUNK', 'HYBRID')),
+    
+    -- Connected Device Information
+    connected_device_name VARCHAR(255),
+    connected_device_ip VARCHAR(45),
+    connected_device_mac VARCHAR(17),
+    connected_device_type TEXT CHECK (connected_device_type IN ('SERVER', 'SWITCH', 'ROUTER', 'OTHER')),
+    
+    -- Port Statistics (can be updated frequently)
+    bytes_in BIGINT DEFAULT 0,
+    bytes_out BIGINT DEFAULT 0,
+    packets_in BIGINT DEFAULT 0,
+    packets_out BIGINT DEFAULT 0,
+    errors_in BIGINT DEFAULT 0,
+    errors_out BIGINT DEFAULT 0,
+    
+    -- Physical/Optical Information
+    sfp_vendor VARCHAR(255),
+    sfp_part_number VARCHAR(255),
+    sfp_serial_number VARCHAR(255),
+    optical_power_dbm REAL,
+    optical_temperature_c INT,
+    
+    description VARCHAR(255),
+    last_flap_time TIMESTAMP NULL,
+    stats_last_updated TIMESTAMP NULL,
+    
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    
+    CONSTRAINT fk_port_switch
+        FOREIGN KEY (switch_id) REFERENCES switches(switch_id)
+        ON DELETE CASCADE
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS uk_switch_ports_switch_port_name ON switch_ports (switch_id, name);
+CREATE UNIQUE INDEX IF NOT EXISTS uk_switch_ports_switch_port_index ON switch_ports (switch_id, port_index);
+CREATE INDEX IF NOT EXISTS idx_switch_ports_port_status ON switch_ports (admin_status, oper_status);
+CREATE INDEX IF NOT EXISTS idx_switch_ports_connected_device ON switch_ports (connected_device_name, connected_device_type);
+CREATE INDEX IF NOT EXISTS idx_switch_ports_vlan ON switch_ports (access_vlan_id, native_vlan_id);
+CREATE INDEX IF NOT EXISTS idx_switch_ports_port_type ON switch_ports (port_type);
+CREATE INDEX IF NOT EXISTS idx_switch_ports_speed ON switch_ports (speed_mbps);
+
+CREATE TRIGGER IF NOT EXISTS trg_switch_ports_updated_at
+    AFTER UPDATE ON switch_ports FOR EACH ROW
+    WHEN NEW.updated_at = OLD.updated_at
+BEGIN
+    UPDATE switch_ports SET updated_at = CURRENT_TIMESTAMP WHERE switch_port_id = NEW.switch_port_id;
+END;
+
+-- ===================================================================
+-- SWITCH PORT VLANS (for trunk ports with multiple VLANs)
+-- ===================================================================
+CREATE TABLE IF NOT EXISTS switch_port_vlans (
+    port_vlan_id INTEGER PRIMARY KEY AUTOINCREMENT,
+    switch_port_id INT NOT NULL,
+    vlan_id INT NOT NULL,
+    vlan_name VARCHAR(255),
+    
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    
+    CONSTRAINT fk_port_vlan_port
+        FOREIGN KEY (switch_port_id) REFERENCES switch_ports(switch_port_id)
+        ON DELETE CASCADE
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS uk_switch_port_vlans_port_vlan ON switch_port_vlans (switch_port_id, vlan_id);
+CREATE INDEX IF NOT EXISTS idx_switch_port_vlans_vlan_id ON switch_port_vlans (vlan_id);
+
+-- ===================================================================
+-- SWITCH VLANS (Global VLAN database per switch)
+-- ===================================================================
+CREATE TABLE IF NOT EXISTS switch_vlans (
+    vlan_db_id INTEGER PRIMARY KEY AUTOINCREMENT,
+    switch_id INT NOT NULL,
+    vlan_id INT NOT NULL,
+    vlan_name VARCHAR(255),
+    vlan_status TEXT DEFAULT 'ACTIVE' CHECK (vlan_status IN ('ACTIVE', 'SUSPEND')),
+    
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    
+    CONSTRAINT fk_vlan_switch
+        FOREIGN KEY (switch_id) REFERENCES switches(switch_id)
+        ON DELETE CASCADE
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS uk_switch_vlans_switch_vlan ON switch_vlans (switch_id, vlan_id);
+CREATE INDEX IF NOT EXISTS idx_switch_vlans_vlan_name ON switch_vlans (vlan_name);
+CREATE INDEX IF NOT EXISTS idx_switch_vlans_vlan_status ON switch_vlans (vlan_status);
+
+CREATE TRIGGER IF NOT EXISTS trg_switch_vlans_updated_at
+    AFTER UPDATE ON switch_vlans FOR EACH ROW
+    WHEN NEW.updated_at = OLD.updated_at
+BEGIN
+    UPDATE switch_vlans SET updated_at = CURRENT_TIMESTAMP WHERE vlan_db_id = NEW.vlan_db_id;
+END;
+
+-- ===================================================================
+-- SWITCH MAC ADDRESS TABLE
+-- ===================================================================
+CREATE TABLE IF NOT EXISTS switch_mac_table (
+    mac_entry_id INTEGER PRIMARY KEY AUTOINCREMENT,
+    switch_id INT NOT NULL,
+    mac_address VARCHAR(17) NOT NULL,
+    vlan_id INT NOT NULL,
+    port_name VARCHAR(64),
+    entry_type TEXT DEFAULT 'DYNAMIC' CHECK (entry_type IN ('DYNAMIC', 'STATIC', 'SECURE')),
+    
+    -- Aging information
+    learned_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    last_seen_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    
+    CONSTRAINT fk_mac_switch
+        FOREIGN KEY (switch_id) REFERENCES switches(switch_id)
+        ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_switch_mac_table_mac_address ON switch_mac_table (mac_address);
+CREATE INDEX IF NOT EXISTS idx_switch_mac_table_switch_vlan_port ON switch_mac_table (switch_id, vlan_id, port_name);
+CREATE INDEX IF NOT EXISTS idx_switch_mac_table_last_seen ON switch_mac_table (last_seen_at);
+
+-- ===================================================================
+-- SWITCH CREDENTIALS (Simplified for essential cases only)
+-- ===================================================================
+CREATE TABLE IF NOT EXISTS switch_credentials (
+    credential_id INTEGER PRIMARY KEY AUTOINCREMENT,
+    switch_id INT NOT NULL,
+    
+    -- Only essential credential types for automation/emergency access
+    credential_type TEXT NOT NULL CHECK (credential_type IN ('SNMP_V3', 'LOCAL_ADMIN')),
+    username VARCHAR(255) NOT NULL,
+    password TEXT NOT NULL,
+    
+    -- Purpose/notes for this credential
+    purpose VARCHAR(255), -- e.g., 'monitoring', 'emergency access', 'automation'
+    
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    
+    CONSTRAINT fk_credential_switch
+        FOREIGN KEY (switch_id) REFERENCES switches(switch_id)
+        ON DELETE CASCADE
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS uk_switch_credentials_switch_credential_type ON switch_credentials (switch_id, credential_type, username);
+
+CREATE TRIGGER IF NOT EXISTS trg_switch_credentials_updated_at
+    AFTER UPDATE ON switch_credentials FOR EACH ROW
+    WHEN NEW.updated_at = OLD.updated_at
+BEGIN
+    UPDATE switch_credentials SET updated_at = CURRENT_TIMESTAMP WHERE credential_id = NEW.credential_id;
+END;
+
+-- ===================================================================
+-- SWITCH INVENTORY SNAPSHOTS FOR HISTORICAL TRACKING
+-- ===================================================================
+CREATE TABLE IF NOT EXISTS switch_inventory_snapshots (
+    snapshot_id INTEGER PRIMARY KEY AUTOINCREMENT,
+    switch_id INT NOT NULL,
+    
+    config_data TEXT NOT NULL,
+    port_data TEXT NOT NULL,
+    vlan_data TEXT NOT NULL,
+    mac_table_data TEXT NOT NULL,
+    checksum VARCHAR(64) NOT NULL,
+    
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    
+    CONSTRAINT fk_switch_snapshot
+        FOREIGN KEY (switch_id) REFERENCES switches(switch_id)
+        ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_switch_inventory_snapshots_switch_created ON switch_inventory_snapshots (switch_id, created_at);
+CREATE INDEX IF NOT EXISTS idx_switch_inventory_snapshots_checksum ON switch_inventory_snapshots (checksum);
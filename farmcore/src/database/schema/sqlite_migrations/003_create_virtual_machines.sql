@@ -0,0 +1,380 @@
+-- Create virtual machine management tables
+-- Description: Creates VM management schema including virtual machines, their resources,
+--              network interfaces, disks, and snapshots, all linked to host servers.
+--
+-- SQLite port of migrations/003_create_virtual_machines.sql. ENUM columns become TEXT with a CHECK constraint,
+-- JSON columns become TEXT and ON UPDATE CURRENT_TIMESTAMP becomes an AFTER UPDATE trigger.
+
+-- ===================================================================
+-- VIRTUAL MACHINES TABLE
+-- ===================================================================
+CREATE TABLE IF NOT EXISTS virtual_machines (
+    vm_id INTEGER PRIMARY KEY AUTOINCREMENT,
+    server_id INT NOT NULL, -- Host server where VM is running
+    
+    -- VM Identity
+    vm_name VARCHAR(255) NOT NULL,
+    vm_uuid VARCHAR(36) UNIQUE,
+    description TEXT,
+    
+    -- VM Configuration
+    hypervisor_type TEXT NOT NULL DEFAULT 'KVM' CHECK (hypervisor_type IN ('KVM', 'VMware', 'VirtualBox', 'Hyper-V', 'Xen', 'QEMU')),
+    guest_os_family VARCHAR(100), -- linux, windows, freebsd, etc.
+    guest_os_version VARCHAR(100), -- Ubuntu 22.04, Windows Server 2022, etc.
+    guest_os_architecture VARCHAR(20) DEFAULT 'x86_64',
+    
+    -- Resource Allocation
+    vcpu_count INT NOT NULL DEFAULT 1,
+    memory_mb INT NOT NULL DEFAULT 1024,
+    storage_gb INT DEFAULT 0, -- Total storage allocation
+    
+    -- VM State
+    vm_state TEXT NOT NULL DEFAULT 'stopped' CHECK (vm_state IN ('running', 'stopped', 'paused', 'suspended', 'crashed', 'unknown')),
+    vm_status TEXT NOT NULL DEFAULT 'inactive' CHECK (vm_status IN ('active', 'inactive', 'maintenance', 'migrating', 'backup', 'error')),
+    
+    -- Hypervisor Configuration
+    config_file_path VARCHAR(512), -- Path to VM config file (libvirt XML, VMX, etc.)
+    boot_order VARCHAR(100) DEFAULT 'hd,cdrom,network', -- Boot device priority
+    
+    -- VM Features
+    enable_vnc BOOLEAN DEFAULT TRUE,
+    vnc_port INT,
+    enable_spice BOOLEAN DEFAULT FALSE,
+    spice_port INT,
+    enable_ssh BOOLEAN DEFAULT TRUE,
+    ssh_port INT DEFAULT 22,
+    
+    -- Performance Settings
+    cpu_limit_percent INT DEFAULT 100, -- CPU usage limit
+    memory_balloon BOOLEAN DEFAULT TRUE, -- Memory ballooning enabled
+    io_priority TEXT DEFAULT 'normal' CHECK (io_priority IN ('low', 'normal', 'high')),
+    
+    -- Backup Settings
+    auto_backup_enabled BOOLEAN DEFAULT FALSE,
+    backup_retention_days INT DEFAULT 7,
+    last_backup_at TIMESTAMP NULL,
+    
+    -- VM Lifecycle
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    started_at TIMESTAMP NULL,
+    stopped_at TIMESTAMP NULL,
+    
+    -- VM Management
+    created_by VARCHAR(100), -- User who created the VM
+    managed_by VARCHAR(100), -- Current VM administrator
+    
+    CONSTRAINT fk_vm_server
+        FOREIGN KEY (server_id) REFERENCES servers(server_id)
+        ON DELETE CASCADE
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS uk_virtual_machines_server_vm_name ON virtual_machines (server_id, vm_name);
+CREATE INDEX IF NOT EXISTS idx_virtual_machines_vm_name ON virtual_machines (vm_name);
+CREATE INDEX IF NOT EXISTS idx_virtual_machines_vm_uuid ON virtual_machines (vm_uuid);
+CREATE INDEX IF NOT EXISTS idx_virtual_machines_vm_state ON virtual_machines (vm_state);
+CREATE INDEX IF NOT EXISTS idx_virtual_machines_vm_status ON virtual_machines (vm_status);
+CREATE INDEX IF NOT EXISTS idx_virtual_machines_hypervisor_type ON virtual_machines (hypervisor_type);
+CREATE INDEX IF NOT EXISTS idx_virtual_machines_guest_os ON virtual_machines (guest_os_family, guest_os_version);
+CREATE INDEX IF NOT EXISTS idx_virtual_machines_created_by ON virtual_machines (created_by);
+CREATE INDEX IF NOT EXISTS idx_virtual_machines_managed_by ON virtual_machines (managed_by);
+
+CREATE TRIGGER IF NOT EXISTS trg_virtual_machines_updated_at
+    AFTER UPDATE ON virtual_machines FOR EACH ROW
+    WHEN NEW.updated_at = OLD.updated_at
+BEGIN
+    UPDATE virtual_machines SET updated_at = CURRENT_TIMESTAMP WHERE vm_id = NEW.vm_id;
+END;
+
+-- ===================================================================
+-- VM VIRTUAL DISKS
+-- ===================================================================
+CREATE TABLE IF NOT EXISTS vm_disks (
+    vm_disk_id INTEGER PRIMARY KEY AUTOINCREMENT,
+    vm_id INT NOT NULL,
+    
+    -- Disk Identity
+    disk_name VARCHAR(255) NOT NULL, -- vda, sda, disk0, etc.
+    disk_uuid VARCHAR(36),
+    description TEXT,
+    
+    -- Disk Configuration
+    disk_type TEXT DEFAULT 'virtio' CHECK (disk_type IN ('ide', 'scsi', 'virtio', 'sata', 'nvme')),
+    disk_format TEXT DEFAULT 'qcow2' CHECK (disk_format IN ('raw', 'qcow2', 'vmdk', 'vhd', 'vhdx', 'vdi')),
+    disk_size_gb INT NOT NULL,
+    disk_path VARCHAR(512) NOT NULL, -- Path to disk file on host
+    
+    -- Storage Backend
+    storage_pool VARCHAR(255), -- Storage pool name (libvirt, etc.)
+    storage_type TEXT DEFAULT 'file' CHECK (storage_type IN ('file', 'block', 'lvm', 'iscsi', 'ceph', 'rbd', 'nfs', 'zfs', 'glusterfs')),
+    
+    -- Disk Properties
+    is_bootable BOOLEAN DEFAULT FALSE,
+    is_system_disk BOOLEAN DEFAULT FALSE,
+    disk_bus VARCHAR(50), -- virtio, ide, scsi
+    disk_device VARCHAR(50) DEFAULT 'disk', -- disk, cdrom, floppy
+    
+    -- Performance Settings
+    cache_mode TEXT DEFAULT 'writethrough' CHECK (cache_mode IN ('none', 'writethrough', 'writeback', 'unsafe', 'directsync')),
+    io_mode TEXT DEFAULT 'threads' CHECK (io_mode IN ('threads', 'native')),
+    discard_mode TEXT DEFAULT 'ignore' CHECK (discard_mode IN ('ignore', 'unmap')),
+    
+    -- Backup Settings
+    snapshot_enabled BOOLEAN DEFAULT TRUE,
+    backup_enabled BOOLEAN DEFAULT TRUE,
+    
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    
+    CONSTRAINT fk_vm_disk_vm
+        FOREIGN KEY (vm_id) REFERENCES virtual_machines(vm_id)
+        ON DELETE CASCADE
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS uk_vm_disks_vm_disk_name ON vm_disks (vm_id, disk_name);
+CREATE INDEX IF NOT EXISTS idx_vm_disks_vm_disk_uuid ON vm_disks (disk_uuid);
+CREATE INDEX IF NOT EXISTS idx_vm_disks_disk_type ON vm_disks (disk_type);
+CREATE INDEX IF NOT EXISTS idx_vm_disks_disk_format ON vm_disks (disk_format);
+CREATE INDEX IF NOT EXISTS idx_vm_disks_storage_pool ON vm_disks (storage_pool);
+CREATE INDEX IF NOT EXISTS idx_vm_disks_is_bootable ON vm_disks (is_bootable);
+CREATE INDEX IF NOT EXISTS idx_vm_disks_is_system_disk ON vm_disks (is_system_disk);
+
+CREATE TRIGGER IF NOT EXISTS trg_vm_disks_updated_at
+    AFTER UPDATE ON vm_disks FOR EACH ROW
+    WHEN NEW.updated_at = OLD.updated_at
+BEGIN
+    UPDATE vm_disks SET updated_at = CURRENT_TIMESTAMP WHERE vm_disk_id = NEW.vm_disk_id;
+END;
+
+-- ===================================================================
+-- VM VIRTUAL NETWORK INTERFACES
+-- ===================================================================
+CREATE TABLE IF NOT EXISTS vm_network_interfaces (
+    vm_interface_id INTEGER PRIMARY KEY AUTOINCREMENT,
+    vm_id INT NOT NULL,
+    
+    -- Interface Identity
+    interface_name VARCHAR(100) NOT NULL, -- eth0, ens3, etc.
+    interface_uuid VARCHAR(36),
+    description TEXT,
+    
+    -- Network Configuration
+    mac_address VARCHAR(17), -- VM's MAC address
+    ip_address VARCHAR(45), -- VM's IP address
+    netmask VARCHAR(45),
+    gateway VARCHAR(45),
+    
+    -- Interface Type
+    interface_type TEXT DEFAULT 'bridge' CHECK (interface_type IN ('bridge', 'nat', 'host-only', 'internal', 'external')),
+    network_bridge VARCHAR(100), -- Host bridge name (br0, virbr0, etc.)
+    vlan_id INT,
+    
+    -- Driver and Model
+    driver_type TEXT DEFAULT 'virtio' CHECK (driver_type IN ('virtio', 'e1000', 'e1000e', 'rtl8139', 'vmxnet3')),
+    link_state TEXT DEFAULT 'down' CHECK (link_state IN ('up', 'down')),
+    
+    -- Traffic Control
+    bandwidth_limit_mbps INT, -- Bandwidth limit in Mbps
+    packet_filter_enabled BOOLEAN DEFAULT FALSE,
+    
+    -- Interface Status
+    is_connected BOOLEAN DEFAULT TRUE,
+    is_primary BOOLEAN DEFAULT FALSE,
+    
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    
+    CONSTRAINT fk_vm_interface_vm
+        FOREIGN KEY (vm_id) REFERENCES virtual_machines(vm_id)
+        ON DELETE CASCADE
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS uk_vm_network_interfaces_vm_interface_name ON vm_network_interfaces (vm_id, interface_name);
+CREATE INDEX IF NOT EXISTS idx_vm_network_interfaces_vm_interface_uuid ON vm_network_interfaces (interface_uuid);
+CREATE INDEX IF NOT EXISTS idx_vm_network_interfaces_mac_address ON vm_network_interfaces (mac_address);
+CREATE INDEX IF NOT EXISTS idx_vm_network_interfaces_ip_address ON vm_network_interfaces (ip_address);
+CREATE INDEX IF NOT EXISTS idx_vm_network_interfaces_interface_type ON vm_network_interfaces (interface_type);
+CREATE INDEX IF NOT EXISTS idx_vm_network_interfaces_network_bridge ON vm_network_interfaces (network_bridge);
+CREATE INDEX IF NOT EXISTS idx_vm_network_interfaces_vlan_id ON vm_network_interfaces (vlan_id);
+CREATE INDEX IF NOT EXISTS idx_vm_network_interfaces_is_primary ON vm_network_interfaces (is_primary);
+
+CREATE TRIGGER IF NOT EXISTS trg_vm_network_interfaces_updated_at
+    AFTER UPDATE ON vm_network_interfaces FOR EACH ROW
+    WHEN NEW.updated_at = OLD.updated_at
+BEGIN
+    UPDATE vm_network_interfaces SET updated_at = CURRENT_TIMESTAMP WHERE vm_interface_id = NEW.vm_interface_id;
+END;
+
+-- ===================================================================
+-- VM SNAPSHOTS
+-- ===================================================================
+CREATE TABLE IF NOT EXISTS vm_snapshots (
+    snapshot_id INTEGER PRIMARY KEY AUTOINCREMENT,
+    vm_id INT NOT NULL,
+    
+    -- Snapshot Identity
+    snapshot_name VARCHAR(255) NOT NULL,
+    snapshot_uuid VARCHAR(36),
+    description TEXT,
+    
+    -- Snapshot Type
+    snapshot_type TEXT DEFAULT 'manual' CHECK (snapshot_type IN ('manual', 'automatic', 'backup', 'migration')),
+    include_memory BOOLEAN DEFAULT TRUE, -- Include RAM state in snapshot
+    
+    -- Snapshot State
+    snapshot_state TEXT DEFAULT 'creating' CHECK (snapshot_state IN ('creating', 'active', 'reverting', 'deleting', 'error')),
+    
+    -- File Information
+    snapshot_path VARCHAR(512), -- Path to snapshot file
+    snapshot_size_bytes BIGINT, -- Snapshot file size
+    
+    -- Parent Snapshot
+    parent_snapshot_id INT NULL, -- Reference to parent snapshot for incremental snapshots
+    
+    -- Metadata
+    vm_state_at_snapshot TEXT NOT NULL CHECK (vm_state_at_snapshot IN ('running', 'stopped', 'paused')),
+    created_by VARCHAR(100),
+    
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    expires_at TIMESTAMP NULL, -- When to auto-delete this snapshot
+    
+    CONSTRAINT fk_vm_snapshot_vm
+        FOREIGN KEY (vm_id) REFERENCES virtual_machines(vm_id)
+        ON DELETE CASCADE,
+    
+    CONSTRAINT fk_vm_snapshot_parent
+        FOREIGN KEY (parent_snapshot_id) REFERENCES vm_snapshots(snapshot_id)
+        ON DELETE SET NULL
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS uk_vm_snapshots_vm_snapshot_name ON vm_snapshots (vm_id, snapshot_name);
+CREATE INDEX IF NOT EXISTS idx_vm_snapshots_vm_snapshot_uuid ON vm_snapshots (snapshot_uuid);
+CREATE INDEX IF NOT EXISTS idx_vm_snapshots_snapshot_type ON vm_snapshots (snapshot_type);
+CREATE INDEX IF NOT EXISTS idx_vm_snapshots_snapshot_state ON vm_snapshots (snapshot_state);
+CREATE INDEX IF NOT EXISTS idx_vm_snapshots_created_by ON vm_snapshots (created_by);
+CREATE INDEX IF NOT EXISTS idx_vm_snapshots_expires_at ON vm_snapshots (expires_at);
+CREATE INDEX IF NOT EXISTS idx_vm_snapshots_parent_snapshot ON vm_snapshots (parent_snapshot_id);
+
+-- ===================================================================
+-- VM RESOURCE USAGE TRACKING
+-- ===================================================================
+CREATE TABLE IF NOT EXISTS vm_resource_usage (
+    usage_id INTEGER PRIMARY KEY AUTOINCREMENT,
+    vm_id INT NOT NULL,
+    
+    -- CPU Usage
+    cpu_usage_percent REAL, -- Current CPU usage percentage
+    cpu_time_seconds BIGINT, -- Total CPU time used
+    
+    -- Memory Usage
+    memory_used_mb INT,
+    memory_available_mb INT,
+    memory_cached_mb INT,
+    
+    -- Disk I/O
+    disk_read_bytes BIGINT,
+    disk_write_bytes BIGINT,
+    disk_read_iops INT,
+    disk_write_iops INT,
+    
+    -- Network I/O
+    network_rx_bytes BIGINT,
+    network_tx_bytes BIGINT,
+    network_rx_packets INT,
+    network_tx_packets INT,
+    
+    -- Timestamp
+    collected_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    
+    CONSTRAINT fk_vm_usage_vm
+        FOREIGN KEY (vm_id) REFERENCES virtual_machines(vm_id)
+        ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_vm_resource_usage_vm_collected ON vm_resource_usage (vm_id, collected_at);
+CREATE INDEX IF NOT EXISTS idx_vm_resource_usage_collected_at ON vm_resource_usage (collected_at);
+
+-- ===================================================================
+-- VM CONFIGURATION HISTORY
+-- ===================================================================
+CREATE TABLE IF NOT EXISTS vm_configuration_history (
+    config_id INTEGER PRIMARY KEY AUTOINCREMENT,
+    vm_id INT NOT NULL,
+    
+    -- Configuration Change
+    change_type TEXT NOT NULL CHECK (change_type IN ('created', 'started', 'stopped', 'paused', 'resumed', 'migrated', 'modified', 'deleted')),
+    change_description TEXT,
+    
+    -- Configuration Data
+    config_before TEXT, -- VM configuration before change
+    config_after TEXT, -- VM configuration after change
+    
+    -- Change Context
+    changed_by VARCHAR(100),
+    change_reason VARCHAR(255),
+    
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    
+    CONSTRAINT fk_vm_config_vm
+        FOREIGN KEY (vm_id) REFERENCES virtual_machines(vm_id)
+        ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_vm_configuration_history_vm_change_type ON vm_configuration_history (vm_id, change_type);
+CREATE INDEX IF NOT EXISTS idx_vm_configuration_history_changed_by ON vm_configuration_history (changed_by);
+CREATE INDEX IF NOT EXISTS idx_vm_configuration_history_created_at ON vm_configuration_history (created_at);
+
+-- ===================================================================
+-- VM MIGRATIONS
+-- ===================================================================
+CREATE TABLE IF NOT EXISTS vm_migrations (
+    migration_id INTEGER PRIMARY KEY AUTOINCREMENT,
+    vm_id INT NOT NULL,
+    
+    -- Migration Details
+    source_server_id INT NOT NULL, -- Source host server
+    target_server_id INT NOT NULL, -- Target host server
+    migration_type TEXT DEFAULT 'offline' CHECK (migration_type IN ('live', 'offline', 'storage')),
+    
+    -- Migration State
+    migration_state TEXT DEFAULT 'initiated' CHECK (migration_state IN ('initiated', 'preparing', 'running', 'completed', 'failed', 'cancelled')),
+    progress_percent REAL DEFAULT 0.00,
+    
+    -- Migration Settings
+    downtime_ms INT, -- Acceptable downtime in milliseconds for live migration
+    bandwidth_mbps INT, -- Migration bandwidth limit
+    
+    -- Migration Results
+    total_time_seconds INT,
+    downtime_actual_ms INT,
+    data_transferred_gb REAL,
+    
+    -- Error Information
+    error_message TEXT,
+    error_code VARCHAR(50),
+    
+    -- Migration Context
+    initiated_by VARCHAR(100),
+    migration_reason VARCHAR(255),
+    
+    started_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    completed_at TIMESTAMP NULL,
+    
+    CONSTRAINT fk_vm_migration_vm
+        FOREIGN KEY (vm_id) REFERENCES virtual_machines(vm_id)
+        ON DELETE CASCADE,
+    
+    CONSTRAINT fk_vm_migration_source
+        FOREIGN KEY (source_server_id) REFERENCES servers(server_id)
+        ON DELETE RESTRICT,
+    
+    CONSTRAINT fk_vm_migration_target
+        FOREIGN KEY (target_server_id) REFERENCES servers(server_id)
+        ON DELETE RESTRICT
+);
+
+CREATE INDEX IF NOT EXISTS idx_vm_migrations_vm_migration_state ON vm_migrations (migration_state);
+CREATE INDEX IF NOT EXISTS idx_vm_migrations_source_server ON vm_migrations (source_server_id);
+CREATE INDEX IF NOT EXISTS idx_vm_migrations_target_server ON vm_migrations (target_server_id);
+CREATE INDEX IF NOT EXISTS idx_vm_migrations_initiated_by ON vm_migrations (initiated_by);
+CREATE INDEX IF NOT EXISTS idx_vm_migrations_started_at ON vm_migrations (started_at);
@@ -0,0 +1,827 @@
+-- Create Kubernetes management tables
+-- Description: Creates Kubernetes schema including clusters, nodes, node groups,
+--              namespaces, workloads, services, ingresses, pods, events, metrics,
+--              secrets, and configmaps.
+--
+-- SQLite port of migrations/004_create_kubernetes.sql. ENUM columns become TEXT with a CHECK constraint,
+-- JSON columns become TEXT and ON UPDATE CURRENT_TIMESTAMP becomes an AFTER UPDATE trigger.
+
+-- ===================================================================
+-- KUBERNETES CLUSTERS TABLE
+-- ===================================================================
+CREATE TABLE IF NOT EXISTS kubernetes_clusters (
+    cluster_id INTEGER PRIMARY KEY AUTOINCREMENT,
+    
+    -- Cluster Identity
+    cluster_name VARCHAR(255) NOT NULL UNIQUE,
+    cluster_uuid VARCHAR(100) UNIQUE,
+    description TEXT,
+    
+    -- Cluster Configuration
+    cluster_version VARCHAR(50) NOT NULL, -- Kubernetes version (e.g., v1.28.2)
+    api_server_endpoint VARCHAR(512) NOT NULL, -- API server URL
+    cluster_domain VARCHAR(255) DEFAULT 'cluster.local',
+    
+    -- Distribution Information
+    distribution TEXT DEFAULT 'vanilla' CHECK (distribution IN ('vanilla', 'k3s', 'k0s', 'rke', 'rke2', 'eks', 'aks', 'gke', 'openshift', 'rancher', 'microk8s', 'kubeadm')),
+    distribution_version VARCHAR(50),
+    
+    -- Network Configuration
+    service_cidr VARCHAR(50), -- Service CIDR (e.g., 10.96.0.0/12)
+    pod_cidr VARCHAR(50), -- Pod CIDR (e.g., 10.244.0.0/16)
+    cni_plugin TEXT DEFAULT 'calico' CHECK (cni_plugin IN ('calico', 'flannel', 'weave', 'cilium', 'canal', 'antrea', 'kindnet', 'kubenet')),
+    cni_version VARCHAR(50),
+    
+    -- Container Runtime
+    container_runtime TEXT DEFAULT 'containerd' CHECK (container_runtime IN ('containerd', 'docker', 'cri-o', 'podman')),
+    runtime_version VARCHAR(50),
+    
+    -- Cluster State
+    cluster_state TEXT DEFAULT 'initializing' CHECK (cluster_state IN ('initializing', 'ready', 'degraded', 'offline', 'upgrading', 'error')),
+    cluster_status TEXT DEFAULT 'active' CHECK (cluster_status IN ('active', 'inactive', 'maintenance', 'archived')),
+    
+    -- High Availability
+    is_ha_enabled BOOLEAN DEFAULT FALSE,
+    control_plane_nodes INT DEFAULT 1,
+    
+    -- Access Configuration
+    kubeconfig_path VARCHAR(512),
+    certificate_authority_data TEXT,
+    
+    -- Cluster Features
+    rbac_enabled BOOLEAN DEFAULT TRUE,
+    network_policy_enabled BOOLEAN DEFAULT FALSE,
+    pod_security_policy_enabled BOOLEAN DEFAULT FALSE,
+    admission_controllers TEXT, -- Comma-separated list
+    
+    -- Monitoring & Logging
+    monitoring_enabled BOOLEAN DEFAULT FALSE,
+    monitoring_stack VARCHAR(100), -- prometheus, grafana, etc.
+    logging_enabled BOOLEAN DEFAULT FALSE,
+    logging_stack VARCHAR(100), -- efk, elk, loki, etc.
+    
+    -- Cluster Management
+    created_by VARCHAR(100),
+    managed_by VARCHAR(100),
+    organization VARCHAR(255),
+    environment TEXT DEFAULT 'development' CHECK (environment IN ('development', 'staging', 'production', 'testing')),
+    
+    -- Lifecycle
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    provisioned_at DATETIME NULL,
+    last_health_check DATETIME NULL,
+    
+    -- Metadata
+    labels TEXT, -- Custom labels
+    annotations TEXT, -- Custom annotations
+    tags VARCHAR(512) -- Comma-separated tags
+);
+
+CREATE INDEX IF NOT EXISTS idx_kubernetes_clusters_cluster_name ON kubernetes_clusters (cluster_name);
+CREATE INDEX IF NOT EXISTS idx_kubernetes_clusters_cluster_uuid ON kubernetes_clusters (cluster_uuid);
+CREATE INDEX IF NOT EXISTS idx_kubernetes_clusters_cluster_state ON kubernetes_clusters (cluster_state);
+CREATE INDEX IF NOT EXISTS idx_kubernetes_clusters_cluster_status ON kubernetes_clusters (cluster_status);
+CREATE INDEX IF NOT EXISTS idx_kubernetes_clusters_distribution ON kubernetes_clusters (distribution);
+CREATE INDEX IF NOT EXISTS idx_kubernetes_clusters_environment ON kubernetes_clusters (environment);
+CREATE INDEX IF NOT EXISTS idx_kubernetes_clusters_created_by ON kubernetes_clusters (created_by);
+CREATE INDEX IF NOT EXISTS idx_kubernetes_clusters_managed_by ON kubernetes_clusters (managed_by);
+
+CREATE TRIGGER IF NOT EXISTS trg_kubernetes_clusters_updated_at
+    AFTER UPDATE ON kubernetes_clusters FOR EACH ROW
+    WHEN NEW.updated_at = OLD.updated_at
+BEGIN
+    UPDATE kubernetes_clusters SET updated_at = CURRENT_TIMESTAMP WHERE cluster_id = NEW.cluster_id;
+END;
+
+-- ===================================================================
+-- KUBERNETES NODE GROUPS TABLE
+-- ===================================================================
+CREATE TABLE IF NOT EXISTS kubernetes_node_groups (
+    node_group_id INTEGER PRIMARY KEY AUTOINCREMENT,
+    cluster_id INT NOT NULL,
+    
+    -- Node Group Identity
+    node_group_name VARCHAR(255) NOT NULL,
+    node_group_uuid VARCHAR(100),
+    description TEXT,
+    
+    -- Node Group Type
+    node_group_type TEXT DEFAULT 'worker' CHECK (node_group_type IN ('control-plane', 'worker', 'etcd', 'mixed')),
+    
+    -- Scaling Configuration
+    min_nodes INT DEFAULT 1,
+    max_nodes INT DEFAULT 10,
+    desired_nodes INT DEFAULT 1,
+    current_nodes INT DEFAULT 0,
+    auto_scaling_enabled BOOLEAN DEFAULT FALSE,
+    
+    -- Node Configuration
+    node_instance_type VARCHAR(100), -- VM flavor or hardware type
+    node_image VARCHAR(255), -- OS image for nodes
+    node_disk_size_gb INT DEFAULT 50,
+    
+    -- Taints and Labels
+    node_labels TEXT, -- Labels to apply to nodes in this group
+    node_taints TEXT, -- Taints to apply to nodes in this group
+    
+    -- Node Group State
+    node_group_state TEXT DEFAULT 'creating' CHECK (node_group_state IN ('creating', 'active', 'updating', 'deleting', 'error')),
+    
+    -- Lifecycle
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    created_by VARCHAR(100),
+    
+    CONSTRAINT fk_node_group_cluster
+        FOREIGN KEY (cluster_id) REFERENCES kubernetes_clusters(cluster_id)
+        ON DELETE CASCADE
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS uk_kubernetes_node_groups_cluster_node_group ON kubernetes_node_groups (cluster_id, node_group_name);
+CREATE INDEX IF NOT EXISTS idx_kubernetes_node_groups_node_group_name ON kubernetes_node_groups (node_group_name);
+CREATE INDEX IF NOT EXISTS idx_kubernetes_node_groups_node_group_type ON kubernetes_node_groups (node_group_type);
+CREATE INDEX IF NOT EXISTS idx_kubernetes_node_groups_node_group_state ON kubernetes_node_groups (node_group_state);
+CREATE INDEX IF NOT EXISTS idx_kubernetes_node_groups_auto_scaling ON kubernetes_node_groups (auto_scaling_enabled);
+
+CREATE TRIGGER IF NOT EXISTS trg_kubernetes_node_groups_updated_at
+    AFTER UPDATE ON kubernetes_node_groups FOR EACH ROW
+    WHEN NEW.updated_at = OLD.updated_at
+BEGIN
+    UPDATE kubernetes_node_groups SET updated_at = CURRENT_TIMESTAMP WHERE node_group_id = NEW.node_group_id;
+END;
+
+-- ===================================================================
+-- KUBERNETES NODES TABLE
+-- ===================================================================
+CREATE TABLE IF NOT EXISTS kubernetes_nodes (
+    k8s_node_id INTEGER PRIMARY KEY AUTOINCREMENT,
+    cluster_id INT NOT NULL,
+    node_group_id INT NULL,
+    server_id INT NULL, -- Reference to physical server
+    vm_id INT NULL, -- Reference to virtual machine (if node runs on VM)
+    
+    -- Node Identity
+    node_name VARCHAR(255) NOT NULL,
+    node_uuid VARCHAR(100),
+    node_uid VARCHAR(100), -- Kubernetes UID
+    
+    -- Node Information
+    node_type TEXT DEFAULT 'worker' CHECK (node_type IN ('control-plane', 'master', 'worker', 'etcd')),
+    internal_ip VARCHAR(45),
+    external_ip VARCHAR(45),
+    hostname VARCHAR(255),
+    
+    -- Node Resources
+    cpu_capacity INT, -- Total CPU cores
+    memory_capacity_mb BIGINT, -- Total memory in MB
+    pod_capacity INT, -- Maximum number of pods
+    ephemeral_storage_gb INT, -- Ephemeral storage capacity
+    
+    -- Node Allocatable (resources available for scheduling)
+    cpu_allocatable INT,
+    memory_allocatable_mb BIGINT,
+    pod_allocatable INT,
+    
+    -- Node Status
+    node_state TEXT DEFAULT 'unknown' CHECK (node_state IN ('ready', 'not-ready', 'unknown', 'scheduling-disabled')),
+    is_schedulable BOOLEAN DEFAULT TRUE,
+    is_cordoned BOOLEAN DEFAULT FALSE,
+    
+    -- Node Conditions
+    ready_condition VARCHAR(50),
+    memory_pressure BOOLEAN DEFAULT FALSE,
+    disk_pressure BOOLEAN DEFAULT FALSE,
+    pid_pressure BOOLEAN DEFAULT FALSE,
+    network_unavailable BOOLEAN DEFAULT FALSE,
+    
+    -- Operating System
+    os_image VARCHAR(255),
+    os_architecture VARCHAR(50),
+    kernel_version VARCHAR(100),
+    
+    -- Container Runtime
+    container_runtime_version VARCHAR(100),
+    
+    -- Kubelet Information
+    kubelet_version VARCHAR(50),
+    kube_proxy_version VARCHAR(50),
+    
+    -- Node Roles
+    roles VARCHAR(255), -- Comma-separated roles
+    
+    -- Labels and Taints
+    labels TEXT,
+    taints TEXT,
+    annotations TEXT,
+    
+    -- Lifecycle
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    registered_at DATETIME NULL,
+    last_heartbeat DATETIME NULL,
+    
+    CONSTRAINT fk_k8s_node_cluster
+        FOREIGN KEY (cluster_id) REFERENCES kubernetes_clusters(cluster_id)
+        ON DELETE CASCADE,
+    
+    CONSTRAINT fk_k8s_node_group
+        FOREIGN KEY (node_group_id) REFERENCES kubernetes_node_groups(node_group_id)
+        ON DELETE SET NULL,
+    
+    CONSTRAINT fk_k8s_node_server
+        FOREIGN KEY (server_id) REFERENCES servers(server_id)
+        ON DELETE SET NULL,
+    
+    CONSTRAINT fk_k8s_node_vm
+        FOREIGN KEY (vm_id) REFERENCES virtual_machines(vm_id)
+        ON DELETE SET NULL
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS uk_kubernetes_nodes_cluster_node_name ON kubernetes_nodes (cluster_id, node_name);
+CREATE INDEX IF NOT EXISTS idx_kubernetes_nodes_node_name ON kubernetes_nodes (node_name);
+CREATE INDEX IF NOT EXISTS idx_kubernetes_nodes_node_uuid ON kubernetes_nodes (node_uuid);
+CREATE INDEX IF NOT EXISTS idx_kubernetes_nodes_node_uid ON kubernetes_nodes (node_uid);
+CREATE INDEX IF NOT EXISTS idx_kubernetes_nodes_node_type ON kubernetes_nodes (node_type);
+CREATE INDEX IF NOT EXISTS idx_kubernetes_nodes_node_state ON kubernetes_nodes (node_state);
+CREATE INDEX IF NOT EXISTS idx_kubernetes_nodes_internal_ip ON kubernetes_nodes (internal_ip);
+CREATE INDEX IF NOT EXISTS idx_kubernetes_nodes_external_ip ON kubernetes_nodes (external_ip);
+CREATE INDEX IF NOT EXISTS idx_kubernetes_nodes_is_schedulable ON kubernetes_nodes (is_schedulable);
+
+CREATE TRIGGER IF NOT EXISTS trg_kubernetes_nodes_updated_at
+    AFTER UPDATE ON kubernetes_nodes FOR EACH ROW
+    WHEN NEW.updated_at = OLD.updated_at
+BEGIN
+    UPDATE kubernetes_nodes SET updated_at = CURRENT_TIMESTAMP WHERE k8s_node_id = NEW.k8s_node_id;
+END;
+
+-- ===================================================================
+-- KUBERNETES NAMESPACES TABLE
+-- ===================================================================
+CREATE TABLE IF NOT EXISTS kubernetes_namespaces (
+    namespace_id INTEGER PRIMARY KEY AUTOINCREMENT,
+    cluster_id INT NOT NULL,
+    
+    -- Namespace Identity
+    namespace_name VARCHAR(255) NOT NULL,
+    namespace_uid VARCHAR(100),
+    description TEXT,
+    
+    -- Namespace Configuration
+    namespace_state TEXT DEFAULT 'active' CHECK (namespace_state IN ('active', 'terminating', 'inactive')),
+    
+    -- Resource Quotas
+    resource_quota_enabled BOOLEAN DEFAULT FALSE,
+    cpu_limit VARCHAR(50), -- e.g., "100", "1000m"
+    memory_limit VARCHAR(50), -- e.g., "1Gi", "500Mi"
+    storage_limit VARCHAR(50),
+    pod_limit INT,
+    service_limit INT,
+    
+    -- Network Policies
+    network_policy_enabled BOOLEAN DEFAULT FALSE,
+    default_deny_ingress BOOLEAN DEFAULT FALSE,
+    default_deny_egress BOOLEAN DEFAULT FALSE,
+    
+    -- Labels and Annotations
+    labels TEXT,
+    annotations TEXT,
+    
+    -- Lifecycle
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    deleted_at DATETIME NULL,
+    
+    -- Management
+    created_by VARCHAR(100),
+    team VARCHAR(255),
+    owner VARCHAR(100),
+    
+    CONSTRAINT fk_namespace_cluster
+        FOREIGN KEY (cluster_id) REFERENCES kubernetes_clusters(cluster_id)
+        ON DELETE CASCADE
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS uk_kubernetes_namespaces_cluster_namespace ON kubernetes_namespaces (cluster_id, namespace_name);
+CREATE INDEX IF NOT EXISTS idx_kubernetes_namespaces_namespace_name ON kubernetes_namespaces (namespace_name);
+CREATE INDEX IF NOT EXISTS idx_kubernetes_namespaces_namespace_uid ON kubernetes_namespaces (namespace_uid);
+CREATE INDEX IF NOT EXISTS idx_kubernetes_namespaces_namespace_state ON kubernetes_namespaces (namespace_state);
+CREATE INDEX IF NOT EXISTS idx_kubernetes_namespaces_team ON kubernetes_namespaces (team);
+CREATE INDEX IF NOT EXISTS idx_kubernetes_namespaces_owner ON kubernetes_namespaces (owner);
+
+CREATE TRIGGER IF NOT EXISTS trg_kubernetes_namespaces_updated_at
+    AFTER UPDATE ON kubernetes_namespaces FOR EACH ROW
+    WHEN NEW.updated_at = OLD.updated_at
+BEGIN
+    UPDATE kubernetes_namespaces SET updated_at = CURRENT_TIMESTAMP WHERE namespace_id = NEW.namespace_id;
+END;
+
+-- ===================================================================
+-- KUBERNETES WORKLOADS TABLE
+-- ===================================================================
+CREATE TABLE IF NOT EXISTS kubernetes_workloads (
+    workload_id INTEGER PRIMARY KEY AUTOINCREMENT,
+    cluster_id INT NOT NULL,
+    namespace_id INT NOT NULL,
+    
+    -- Workload Identity
+    workload_name VARCHAR(255) NOT NULL,
+    workload_uid VARCHAR(100),
+    
+    -- Workload Type
+    workload_type TEXT NOT NULL CHECK (workload_type IN ('deployment', 'statefulset', 'daemonset', 'job', 'cronjob', 'replicaset')),
+    
+    -- Workload Configuration
+    replicas_desired INT DEFAULT 1,
+    replicas_current INT DEFAULT 0,
+    replicas_ready INT DEFAULT 0,
+    replicas_available INT DEFAULT 0,
+    replicas_updated INT DEFAULT 0,
+    
+    -- Container Configuration
+    container_image VARCHAR(512),
+    container_images TEXT, -- Array of all container images in pod template
+    
+    -- Update Strategy
+    strategy_type VARCHAR(50), -- RollingUpdate, Recreate, OnDelete
+    max_surge INT,
+    max_unavailable INT,
+    
+    -- Workload State
+    workload_state TEXT DEFAULT 'unknown' CHECK (workload_state IN ('running', 'pending', 'failed', 'succeeded', 'unknown')),
+    is_paused BOOLEAN DEFAULT FALSE,
+    
+    -- Scheduling
+    node_selector TEXT,
+    node_affinity TEXT,
+    pod_affinity TEXT,
+    tolerations TEXT,
+    
+    -- Resource Requests and Limits
+    cpu_request VARCHAR(50),
+    memory_request VARCHAR(50),
+    cpu_limit VARCHAR(50),
+    memory_limit VARCHAR(50),
+    
+    -- CronJob specific
+    schedule VARCHAR(255), -- Cron schedule for CronJobs
+    last_schedule_time DATETIME NULL,
+    next_schedule_time DATETIME NULL,
+    
+    -- Labels and Annotations
+    labels TEXT,
+    annotations TEXT,
+    selector TEXT, -- Label selector for pods
+    
+    -- Lifecycle
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    created_by VARCHAR(100),
+    
+    CONSTRAINT fk_workload_cluster
+        FOREIGN KEY (cluster_id) REFERENCES kubernetes_clusters(cluster_id)
+        ON DELETE CASCADE,
+    
+    CONSTRAINT fk_workload_namespace
+        FOREIGN KEY (namespace_id) REFERENCES kubernetes_namespaces(namespace_id)
+        ON DELETE CASCADE
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS uk_kubernetes_workloads_namespace_workload ON kubernetes_workloads (namespace_id, workload_name, workload_type);
+CREATE INDEX IF NOT EXISTS idx_kubernetes_workloads_workload_name ON kubernetes_workloads (workload_name);
+CREATE INDEX IF NOT EXISTS idx_kubernetes_workloads_workload_uid ON kubernetes_workloads (workload_uid);
+CREATE INDEX IF NOT EXISTS idx_kubernetes_workloads_workload_type ON kubernetes_workloads (workload_type);
+CREATE INDEX IF NOT EXISTS idx_kubernetes_workloads_workload_state ON kubernetes_workloads (workload_state);
+CREATE INDEX IF NOT EXISTS idx_kubernetes_workloads_container_image ON kubernetes_workloads (container_image);
+
+CREATE TRIGGER IF NOT EXISTS trg_kubernetes_workloads_updated_at
+    AFTER UPDATE ON kubernetes_workloads FOR EACH ROW
+    WHEN NEW.updated_at = OLD.updated_at
+BEGIN
+    UPDATE kubernetes_workloads SET updated_at = CURRENT_TIMESTAMP WHERE workload_id = NEW.workload_id;
+END;
+
+-- ===================================================================
+-- KUBERNETES PODS TABLE
+-- ===================================================================
+CREATE TABLE IF NOT EXISTS kubernetes_pods (
+    pod_id INTEGER PRIMARY KEY AUTOINCREMENT,
+    cluster_id INT NOT NULL,
+    namespace_id INT NOT NULL,
+    workload_id INT NULL,
+    k8s_node_id INT NULL,
+    
+    -- Pod Identity
+    pod_name VARCHAR(255) NOT NULL,
+    pod_uid VARCHAR(100),
+    
+    -- Pod Configuration
+    pod_ip VARCHAR(45),
+    host_ip VARCHAR(45),
+    
+    -- Pod Status
+    pod_phase TEXT DEFAULT 'pending' CHECK (pod_phase IN ('pending', 'running', 'succeeded', 'failed', 'unknown')),
+    pod_state TEXT DEFAULT 'waiting' CHECK (pod_state IN ('waiting', 'running', 'terminated')),
+    is_ready BOOLEAN DEFAULT FALSE,
+    
+    -- Pod Conditions
+    initialized BOOLEAN DEFAULT FALSE,
+    containers_ready BOOLEAN DEFAULT FALSE,
+    pod_scheduled BOOLEAN DEFAULT FALSE,
+    
+    -- Container Information
+    container_count INT DEFAULT 1,
+    init_container_count INT DEFAULT 0,
+    restart_count INT DEFAULT 0,
+    
+    -- QoS Class
+    qos_class TEXT DEFAULT 'BestEffort' CHECK (qos_class IN ('Guaranteed', 'Burstable', 'BestEffort')),
+    
+    -- Resource Usage
+    cpu_request VARCHAR(50),
+    memory_request VARCHAR(50),
+    cpu_limit VARCHAR(50),
+    memory_limit VARCHAR(50),
+    
+    -- Labels and Annotations
+    labels TEXT,
+    annotations TEXT,
+    
+    -- Lifecycle
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    started_at DATETIME NULL,
+    finished_at DATETIME NULL,
+    deleted_at DATETIME NULL,
+    
+    CONSTRAINT fk_pod_cluster
+        FOREIGN KEY (cluster_id) REFERENCES kubernetes_clusters(cluster_id)
+        ON DELETE CASCADE,
+    
+    CONSTRAINT fk_pod_namespace
+        FOREIGN KEY (namespace_id) REFERENCES kubernetes_namespaces(namespace_id)
+        ON DELETE CASCADE,
+    
+    CONSTRAINT fk_pod_workload
+        FOREIGN KEY (workload_id) REFERENCES kubernetes_workloads(workload_id)
+        ON DELETE SET NULL,
+    
+    CONSTRAINT fk_pod_node
+        FOREIGN KEY (k8s_node_id) REFERENCES kubernetes_nodes(k8s_node_id)
+        ON DELETE SET NULL
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS uk_kubernetes_pods_cluster_pod_uid ON kubernetes_pods (cluster_id, pod_uid);
+CREATE INDEX IF NOT EXISTS idx_kubernetes_pods_pod_name ON kubernetes_pods (pod_name);
+CREATE INDEX IF NOT EXISTS idx_kubernetes_pods_pod_uid ON kubernetes_pods (pod_uid);
+CREATE INDEX IF NOT EXISTS idx_kubernetes_pods_pod_phase ON kubernetes_pods (pod_phase);
+CREATE INDEX IF NOT EXISTS idx_kubernetes_pods_pod_state ON kubernetes_pods (pod_state);
+CREATE INDEX IF NOT EXISTS idx_kubernetes_pods_pod_ip ON kubernetes_pods (pod_ip);
+CREATE INDEX IF NOT EXISTS idx_kubernetes_pods_is_ready ON kubernetes_pods (is_ready);
+
+CREATE TRIGGER IF NOT EXISTS trg_kubernetes_pods_updated_at
+    AFTER UPDATE ON kubernetes_pods FOR EACH ROW
+    WHEN NEW.updated_at = OLD.updated_at
+BEGIN
+    UPDATE kubernetes_pods SET updated_at = CURRENT_TIMESTAMP WHERE pod_id = NEW.pod_id;
+END;
+
+-- ===================================================================
+-- KUBERNETES SERVICES TABLE
+-- ===================================================================
+CREATE TABLE IF NOT EXISTS kubernetes_services (
+    service_id INTEGER PRIMARY KEY AUTOINCREMENT,
+    cluster_id INT NOT NULL,
+    namespace_id INT NOT NULL,
+    
+    -- Service Identity
+    service_name VARCHAR(255) NOT NULL,
+    service_uid VARCHAR(100),
+    
+    -- Service Type
+    service_type TEXT DEFAULT 'ClusterIP' CHECK (service_type IN ('ClusterIP', 'NodePort', 'LoadBalancer', 'ExternalName')),
+    
+    -- Service Configuration
+    cluster_ip VARCHAR(45),
+    external_ips TEXT, -- Array of external IPs
+    load_balancer_ip VARCHAR(45),
+    external_name VARCHAR(255), -- For ExternalName type
+    
+    -- Port Configuration
+    ports TEXT, -- Array of port configurations {port, targetPort, protocol, nodePort}
+    
+    -- Session Affinity
+    session_affinity TEXT DEFAULT 'None' CHECK (session_affinity IN ('None', 'ClientIP')),
+    session_affinity_timeout_seconds INT,
+    
+    -- Selector
+    selector TEXT, -- Label selector for pods
+    
+    -- External Traffic Policy
+    external_traffic_policy TEXT DEFAULT 'Cluster' CHECK (external_traffic_policy IN ('Cluster', 'Local')),
+    
+    -- Health Check
+    health_check_node_port INT,
+    
+    -- Labels and Annotations
+    labels TEXT,
+    annotations TEXT,
+    
+    -- Lifecycle
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    created_by VARCHAR(100),
+    
+    CONSTRAINT fk_service_cluster
+        FOREIGN KEY (cluster_id) REFERENCES kubernetes_clusters(cluster_id)
+        ON DELETE CASCADE,
+    
+    CONSTRAINT fk_service_namespace
+        FOREIGN KEY (namespace_id) REFERENCES kubernetes_namespaces(namespace_id)
+        ON DELETE CASCADE
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS uk_kubernetes_services_namespace_service ON kubernetes_services (namespace_id, service_name);
+CREATE INDEX IF NOT EXISTS idx_kubernetes_services_service_name ON kubernetes_services (service_name);
+CREATE INDEX IF NOT EXISTS idx_kubernetes_services_service_uid ON kubernetes_services (service_uid);
+CREATE INDEX IF NOT EXISTS idx_kubernetes_services_service_type ON kubernetes_services (service_type);
+CREATE INDEX IF NOT EXISTS idx_kubernetes_services_cluster_ip ON kubernetes_services (cluster_ip);
+CREATE INDEX IF NOT EXISTS idx_kubernetes_services_load_balancer_ip ON kubernetes_services (load_balancer_ip);
+
+CREATE TRIGGER IF NOT EXISTS trg_kubernetes_services_updated_at
+    AFTER UPDATE ON kubernetes_services FOR EACH ROW
+    WHEN NEW.updated_at = OLD.updated_at
+BEGIN
+    UPDATE kubernetes_services SET updated_at = CURRENT_TIMESTAMP WHERE service_id = NEW.service_id;
+END;
+
+-- ===================================================================
+-- KUBERNETES INGRESSES TABLE
+-- ===================================================================
+CREATE TABLE IF NOT EXISTS kubernetes_ingresses (
+    ingress_id INTEGER PRIMARY KEY AUTOINCREMENT,
+    cluster_id INT NOT NULL,
+    namespace_id INT NOT NULL,
+    
+    -- Ingress Identity
+    ingress_name VARCHAR(255) NOT NULL,
+    ingress_uid VARCHAR(100),
+    
+    -- Ingress Controller
+    ingress_class VARCHAR(100), -- nginx, traefik, haproxy, etc.
+    
+    -- Ingress Configuration
+    rules TEXT, -- Array of ingress rules {host, paths[{path, pathType, serviceName, servicePort}]}
+    tls_config TEXT, -- TLS configuration {hosts[], secretName}
+    
+    -- Default Backend
+    default_backend_service VARCHAR(255),
+    default_backend_port INT,
+    
+    -- Load Balancer
+    load_balancer_ingress TEXT, -- Array of {ip, hostname}
+    
+    -- Labels and Annotations
+    labels TEXT,
+    annotations TEXT,
+    
+    -- Lifecycle
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    created_by VARCHAR(100),
+    
+    CONSTRAINT fk_ingress_cluster
+        FOREIGN KEY (cluster_id) REFERENCES kubernetes_clusters(cluster_id)
+        ON DELETE CASCADE,
+    
+    CONSTRAINT fk_ingress_namespace
+        FOREIGN KEY (namespace_id) REFERENCES kubernetes_namespaces(namespace_id)
+        ON DELETE CASCADE
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS uk_kubernetes_ingresses_namespace_ingress ON kubernetes_ingresses (namespace_id, ingress_name);
+CREATE INDEX IF NOT EXISTS idx_kubernetes_ingresses_ingress_name ON kubernetes_ingresses (ingress_name);
+CREATE INDEX IF NOT EXISTS idx_kubernetes_ingresses_ingress_uid ON kubernetes_ingresses (ingress_uid);
+CREATE INDEX IF NOT EXISTS idx_kubernetes_ingresses_ingress_class ON kubernetes_ingresses (ingress_class);
+
+CREATE TRIGGER IF NOT EXISTS trg_kubernetes_ingresses_updated_at
+    AFTER UPDATE ON kubernetes_ingresses FOR EACH ROW
+    WHEN NEW.updated_at = OLD.updated_at
+BEGIN
+    UPDATE kubernetes_ingresses SET updated_at = CURRENT_TIMESTAMP WHERE ingress_id = NEW.ingress_id;
+END;
+
+-- ===================================================================
+-- KUBERNETES EVENTS TABLE
+-- ===================================================================
+CREATE TABLE IF NOT EXISTS kubernetes_events (
+    event_id INTEGER PRIMARY KEY AUTOINCREMENT,
+    cluster_id INT NOT NULL,
+    namespace_id INT NULL,
+    
+    -- Event Identity
+    event_name VARCHAR(255),
+    event_uid VARCHAR(100),
+    
+    -- Event Details
+    event_type TEXT DEFAULT 'Normal' CHECK (event_type IN ('Normal', 'Warning', 'Error')),
+    reason VARCHAR(255), -- Event reason (e.g., Started, Failed, Created)
+    message TEXT, -- Event message
+    
+    -- Involved Object
+    involved_object_kind VARCHAR(100), -- Pod, Node, Service, etc.
+    involved_object_name VARCHAR(255),
+    involved_object_uid VARCHAR(100),
+    involved_object_namespace VARCHAR(255),
+    
+    -- Source
+    source_component VARCHAR(255), -- kubelet, kube-scheduler, etc.
+    source_host VARCHAR(255),
+    
+    -- Count
+    event_count INT DEFAULT 1,
+    first_occurrence DATETIME NULL,
+    last_occurrence DATETIME NULL,
+    
+    -- Action
+    action VARCHAR(255),
+    reporting_controller VARCHAR(255),
+    reporting_instance VARCHAR(255),
+    
+    -- Lifecycle
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    
+    CONSTRAINT fk_event_cluster
+        FOREIGN KEY (cluster_id) REFERENCES kubernetes_clusters(cluster_id)
+        ON DELETE CASCADE,
+    
+    CONSTRAINT fk_event_namespace
+        FOREIGN KEY (namespace_id) REFERENCES kubernetes_namespaces(namespace_id)
+        ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_kubernetes_events_event_type ON kubernetes_events (event_type);
+CREATE INDEX IF NOT EXISTS idx_kubernetes_events_reason ON kubernetes_events (reason);
+CREATE INDEX IF NOT EXISTS idx_kubernetes_events_involved_object ON kubernetes_events (involved_object_kind, involved_object_name);
+CREATE INDEX IF NOT EXISTS idx_kubernetes_events_involved_object_uid ON kubernetes_events (involved_object_uid);
+CREATE INDEX IF NOT EXISTS idx_kubernetes_events_source_component ON kubernetes_events (source_component);
+CREATE INDEX IF NOT EXISTS idx_kubernetes_events_created_at ON kubernetes_events (created_at);
+CREATE INDEX IF NOT EXISTS idx_kubernetes_events_last_occurrence ON kubernetes_events (last_occurrence);
+
+-- ===================================================================
+-- KUBERNETES METRICS TABLE
+-- ===================================================================
+CREATE TABLE IF NOT EXISTS kubernetes_metrics (
+    metric_id INTEGER PRIMARY KEY AUTOINCREMENT,
+    cluster_id INT NOT NULL,
+    
+    -- Metric Target
+    resource_type TEXT NOT NULL CHECK (resource_type IN ('cluster', 'node', 'namespace', 'pod', 'workload')),
+    resource_id INT NOT NULL, -- ID of the related resource
+    resource_name VARCHAR(255),
+    
+    -- CPU Metrics
+    cpu_usage_cores REAL, -- CPU usage in cores
+    cpu_usage_percent REAL,
+    cpu_request_cores REAL,
+    cpu_limit_cores REAL,
+    
+    -- Memory Metrics
+    memory_usage_bytes BIGINT,
+    memory_usage_mb INT,
+    memory_usage_percent REAL,
+    memory_request_mb INT,
+    memory_limit_mb INT,
+    memory_working_set_bytes BIGINT,
+    
+    -- Storage Metrics
+    storage_usage_bytes BIGINT,
+    storage_usage_gb REAL,
+    storage_available_bytes BIGINT,
+    
+    -- Network Metrics
+    network_rx_bytes BIGINT,
+    network_tx_bytes BIGINT,
+    network_rx_errors INT,
+    network_tx_errors INT,
+    
+    -- File System Metrics
+    fs_reads BIGINT,
+    fs_writes BIGINT,
+    fs_read_bytes BIGINT,
+    fs_write_bytes BIGINT,
+    
+    -- Timestamp
+    collected_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    
+    CONSTRAINT fk_metric_cluster
+        FOREIGN KEY (cluster_id) REFERENCES kubernetes_clusters(cluster_id)
+        ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_kubernetes_metrics_resource_metrics ON kubernetes_metrics (resource_type, resource_id, collected_at);
+CREATE INDEX IF NOT EXISTS idx_kubernetes_metrics_collected_at ON kubernetes_metrics (collected_at);
+CREATE INDEX IF NOT EXISTS idx_kubernetes_metrics_resource_name ON kubernetes_metrics (resource_name);
+
+-- ===================================================================
+-- KUBERNETES SECRETS TABLE
+-- ===================================================================
+CREATE TABLE IF NOT EXISTS kubernetes_secrets (
+    secret_id INTEGER PRIMARY KEY AUTOINCREMENT,
+    cluster_id INT NOT NULL,
+    namespace_id INT NOT NULL,
+    
+    -- Secret Identity
+    secret_name VARCHAR(255) NOT NULL,
+    secret_uid VARCHAR(100),
+    
+    -- Secret Type
+    secret_type TEXT DEFAULT 'Opaque' CHECK (secret_type IN ('Opaque', 'kubernetes.io/service-account-token', 'kubernetes.io/dockercfg', 'kubernetes.io/dockerconfigjson', 'kubernetes.io/basic-auth', 'kubernetes.io/ssh-auth', 'kubernetes.io/tls', 'bootstrap.kubernetes.io/token')),
+    
+    -- Secret Data
+    data_keys TEXT, -- Array of key names (not the actual secret values)
+    data_size_bytes INT, -- Total size of secret data
+    
+    -- Immutability
+    is_immutable BOOLEAN DEFAULT FALSE,
+    
+    -- Labels and Annotations
+    labels TEXT,
+    annotations TEXT,
+    
+    -- Lifecycle
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    created_by VARCHAR(100),
+    
+    CONSTRAINT fk_secret_cluster
+        FOREIGN KEY (cluster_id) REFERENCES kubernetes_clusters(cluster_id)
+        ON DELETE CASCADE,
+    
+    CONSTRAINT fk_secret_namespace
+        FOREIGN KEY (namespace_id) REFERENCES kubernetes_namespaces(namespace_id)
+        ON DELETE CASCADE
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS uk_kubernetes_secrets_namespace_secret ON kubernetes_secrets (namespace_id, secret_name);
+CREATE INDEX IF NOT EXISTS idx_kubernetes_secrets_secret_name ON kubernetes_secrets (secret_name);
+CREATE INDEX IF NOT EXISTS idx_kubernetes_secrets_secret_uid ON kubernetes_secrets (secret_uid);
+CREATE INDEX IF NOT EXISTS idx_kubernetes_secrets_secret_type ON kubernetes_secrets (secret_type);
+CREATE INDEX IF NOT EXISTS idx_kubernetes_secrets_is_immutable ON kubernetes_secrets (is_immutable);
+
+CREATE TRIGGER IF NOT EXISTS trg_kubernetes_secrets_updated_at
+    AFTER UPDATE ON kubernetes_secrets FOR EACH ROW
+    WHEN NEW.updated_at = OLD.updated_at
+BEGIN
+    UPDATE kubernetes_secrets SET updated_at = CURRENT_TIMESTAMP WHERE secret_id = NEW.secret_id;
+END;
+
+-- ===================================================================
+-- KUBERNETES CONFIGMAPS TABLE
+-- ===================================================================
+CREATE TABLE IF NOT EXISTS kubernetes_configmaps (
+    configmap_id INTEGER PRIMARY KEY AUTOINCREMENT,
+    cluster_id INT NOT NULL,
+    namespace_id INT NOT NULL,
+    
+    -- ConfigMap Identity
+    configmap_name VARCHAR(255) NOT NULL,
+    configmap_uid VARCHAR(100),
+    
+    -- ConfigMap Data
+    data_keys TEXT, -- Array of configuration key names
+    binary_data_keys TEXT, -- Array of binary data key names
+    data_size_bytes INT, -- Total size of config data
+    
+    -- Immutability
+    is_immutable BOOLEAN DEFAULT FALSE,
+    
+    -- Labels and Annotations
+    labels TEXT,
+    annotations TEXT,
+    
+    -- Lifecycle
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    created_by VARCHAR(100),
+    
+    CONSTRAINT fk_configmap_cluster
+        FOREIGN KEY (cluster_id) REFERENCES kubernetes_clusters(cluster_id)
+        ON DELETE CASCADE,
+    
+    CONSTRAINT fk_configmap_namespace
+        FOREIGN KEY (namespace_id) REFERENCES kubernetes_namespaces(namespace_id)
+        ON DELETE CASCADE
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS uk_kubernetes_configmaps_namespace_configmap ON kubernetes_configmaps (namespace_id, configmap_name);
+CREATE INDEX IF NOT EXISTS idx_kubernetes_configmaps_configmap_name ON kubernetes_configmaps (configmap_name);
+CREATE INDEX IF NOT EXISTS idx_kubernetes_configmaps_configmap_uid ON kubernetes_configmaps (configmap_uid);
+CREATE INDEX IF NOT EXISTS idx_kubernetes_configmaps_is_immutable ON kubernetes_configmaps (is_immutable);
+
+CREATE TRIGGER IF NOT EXISTS trg_kubernetes_configmaps_updated_at
+    AFTER UPDATE ON kubernetes_configmaps FOR EACH ROW
+    WHEN NEW.updated_at = OLD.updated_at
+BEGIN
+    UPDATE kubernetes_configmaps SET updated_at = CURRENT_TIMESTAMP WHERE configmap_id = NEW.configmap_id;
+END;
@@ -0,0 +1,188 @@
+-- Create datacenter infrastructure tables
+-- Description: Creates datacenter, rack, and rack position tables for physical infrastructure
+--              management with capacity tracking and proper relationships.
+--
+-- SQLite port of migrations/005_create_datacenters.sql. ENUM columns become TEXT with a CHECK constraint,
+-- JSON columns become TEXT and ON UPDATE CURRENT_TIMESTAMP becomes an AFTER UPDATE trigger.
+
+-- ===================================================================
+-- DATACENTER INFRASTRUCTURE TABLES
+-- ===================================================================
+
+-- Datacenters Table
+-- Represents physical datacenter locations
+CREATE TABLE IF NOT EXISTS datacenters (
+    data_center_id INTEGER PRIMARY KEY AUTOINCREMENT,
+    
+    -- Basic Information
+    data_center_name VARCHAR(255) NOT NULL UNIQUE,
+    data_center_code VARCHAR(50) NOT NULL UNIQUE,
+    description TEXT,
+    
+    -- Location Information
+    address TEXT,
+    city VARCHAR(100),
+    state_province VARCHAR(100),
+    country VARCHAR(100) NOT NULL,
+    postal_code VARCHAR(20),
+    region VARCHAR(100),
+    
+    -- Geographic Coordinates
+    latitude REAL,
+    longitude REAL,
+    
+    -- Provider Information
+    provider VARCHAR(255),
+    provider_facility_id VARCHAR(100),
+    
+    -- Facility Details
+    tier_level TEXT DEFAULT 'UNKNOWN' CHECK (tier_level IN ('TIER_I', 'TIER_II', 'TIER_III', 'TIER_IV', 'UNKNOWN')),
+    total_floor_space_sqm REAL,
+    power_capacity_kw REAL,
+    cooling_capacity_kw REAL,
+    
+    -- Status and Management
+    status TEXT NOT NULL DEFAULT 'ACTIVE' CHECK (status IN ('ACTIVE', 'INACTIVE', 'MAINTENANCE', 'CONSTRUCTION', 'DECOMMISSIONED')),
+    
+    -- Capacity Tracking
+    total_racks INT DEFAULT 0,
+    occupied_racks INT DEFAULT 0,
+    total_servers INT DEFAULT 0,
+    
+    -- Contact Information
+    facility_manager VARCHAR(255),
+    contact_phone VARCHAR(50),
+    contact_email VARCHAR(255),
+    emergency_contact VARCHAR(255),
+    emergency_phone VARCHAR(50),
+    
+    -- Operational Details
+    timezone VARCHAR(50),
+    operating_hours VARCHAR(100),
+    
+    -- Metadata
+    tags TEXT,
+    metadata TEXT,
+    
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_datacenters_data_center_name ON datacenters (data_center_name);
+CREATE INDEX IF NOT EXISTS idx_datacenters_data_center_code ON datacenters (data_center_code);
+CREATE INDEX IF NOT EXISTS idx_datacenters_status ON datacenters (status);
+CREATE INDEX IF NOT EXISTS idx_datacenters_country ON datacenters (country);
+CREATE INDEX IF NOT EXISTS idx_datacenters_region ON datacenters (region);
+
+CREATE TRIGGER IF NOT EXISTS trg_datacenters_updated_at
+    AFTER UPDATE ON datacenters FOR EACH ROW
+    WHEN NEW.updated_at = OLD.updated_at
+BEGIN
+    UPDATE datacenters SET updated_at = CURRENT_TIMESTAMP WHERE data_center_id = NEW.data_center_id;
+END;
+
+-- Racks Table
+-- Represents physical racks within a datacenter
+CREATE TABLE IF NOT EXISTS datacenter_racks (
+    rack_id INTEGER PRIMARY KEY AUTOINCREMENT,
+    data_center_id INT NOT NULL,
+    
+    -- Basic Information
+    rack_name VARCHAR(255) NOT NULL,
+    rack_code VARCHAR(50) NOT NULL,
+    description TEXT,
+    
+    -- Physical Specifications
+    rack_height_u INT NOT NULL DEFAULT 42,
+    rack_width_mm INT DEFAULT 600,
+    rack_depth_mm INT DEFAULT 1000,
+    
+    -- Location in Datacenter
+    row_name VARCHAR(50),
+    aisle_name VARCHAR(50),
+    room_name VARCHAR(100),
+    floor_level INT DEFAULT 1,
+    
+    -- Power and Cooling
+    power_capacity_w INT,
+    power_usage_w INT DEFAULT 0,
+    cooling_type TEXT DEFAULT 'AIR' CHECK (cooling_type IN ('AIR', 'LIQUID', 'HYBRID', 'NONE')),
+    
+    -- Network
+    network_zone VARCHAR(100),
+    
+    -- Status
+    status TEXT NOT NULL DEFAULT 'ACTIVE' CHECK (status IN ('ACTIVE', 'INACTIVE', 'MAINTENANCE', 'RESERVED', 'DECOMMISSIONED')),
+    
+    -- Capacity Tracking
+    total_u_available INT,
+    occupied_u INT DEFAULT 0,
+    reserved_u INT DEFAULT 0,
+    free_u INT,
+    
+    -- Access Control
+    access_level TEXT DEFAULT 'RESTRICTED' CHECK (access_level IN ('PUBLIC', 'RESTRICTED', 'HIGH_SECURITY')),
+    
+    -- Metadata
+    tags TEXT,
+    metadata TEXT,
+    
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    
+    FOREIGN KEY (data_center_id) REFERENCES datacenters(data_center_id) ON DELETE CASCADE
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS uk_datacenter_racks_rack ON datacenter_racks (data_center_id, rack_code);
+CREATE INDEX IF NOT EXISTS idx_datacenter_racks_rack_name ON datacenter_racks (rack_name);
+CREATE INDEX IF NOT EXISTS idx_datacenter_racks_data_center_id ON datacenter_racks (data_center_id);
+CREATE INDEX IF NOT EXISTS idx_datacenter_racks_status ON datacenter_racks (status);
+CREATE INDEX IF NOT EXISTS idx_datacenter_racks_row_aisle ON datacenter_racks (row_name, aisle_name);
+
+CREATE TRIGGER IF NOT EXISTS trg_datacenter_racks_updated_at
+    AFTER UPDATE ON datacenter_racks FOR EACH ROW
+    WHEN NEW.updated_at = OLD.updated_at
+BEGIN
+    UPDATE datacenter_racks SET updated_at = CURRENT_TIMESTAMP WHERE rack_id = NEW.rack_id;
+END;
+
+-- Rack Positions Table
+-- Represents specific U positions within a rack
+CREATE TABLE IF NOT EXISTS datacenter_rack_positions (
+    rack_position_id INTEGER PRIMARY KEY AUTOINCREMENT,
+    rack_id INT NOT NULL,
+    
+    -- Position Information
+    u_position INT NOT NULL,
+    
+    -- Status
+    status TEXT NOT NULL DEFAULT 'AVAILABLE' CHECK (status IN ('AVAILABLE', 'OCCUPIED', 'RESERVED', 'BLOCKED')),
+    
+    -- Reservation Details
+    reserved_for VARCHAR(255),
+    reservation_notes TEXT,
+    
+    -- Current Occupancy
+    server_id INT,
+    device_type TEXT CHECK (device_type IN ('SERVER', 'SWITCH', 'STORAGE', 'PDU', 'UPS', 'OTHER')),
+    
+    -- Metadata
+    notes TEXT,
+    
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    
+    FOREIGN KEY (rack_id) REFERENCES datacenter_racks(rack_id) ON DELETE CASCADE
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS uk_datacenter_rack_positions_rack_position ON datacenter_rack_positions (rack_id, u_position);
+CREATE INDEX IF NOT EXISTS idx_datacenter_rack_positions_rack_id ON datacenter_rack_positions (rack_id);
+CREATE INDEX IF NOT EXISTS idx_datacenter_rack_positions_status ON datacenter_rack_positions (status);
+CREATE INDEX IF NOT EXISTS idx_datacenter_rack_positions_server_id ON datacenter_rack_positions (server_id);
+
+CREATE TRIGGER IF NOT EXISTS trg_datacenter_rack_positions_updated_at
+    AFTER UPDATE ON datacenter_rack_positions FOR EACH ROW
+    WHEN NEW.updated_at = OLD.updated_at
+BEGIN
+    UPDATE datacenter_rack_positions SET updated_at = CURRENT_TIMESTAMP WHERE rack_position_id = NEW.rack_position_id;
+END;
@@ -0,0 +1,107 @@
+-- Create server cluster tables with proper relationships
+-- Description: Creates cluster hierarchy tables to organize servers into clusters and sub-clusters
+--              with metadata, capacity tracking, and proper foreign key relationships.
+-- Note: This migration depends on 005_create_datacenters.sql being run first.
+--
+-- SQLite port of migrations/006_create_server_clusters.sql. ENUM columns become TEXT with a CHECK constraint,
+-- JSON columns become TEXT and ON UPDATE CURRENT_TIMESTAMP becomes an AFTER UPDATE trigger.
+
+-- ===================================================================
+-- SERVER CLUSTER TABLES
+-- ===================================================================
+
+-- Main Clusters Table
+-- Represents the top-level cluster grouping for servers
+CREATE TABLE IF NOT EXISTS server_clusters (
+    cluster_id INTEGER PRIMARY KEY AUTOINCREMENT,
+    
+    -- Basic Information
+    cluster_name VARCHAR(255) NOT NULL UNIQUE,
+    cluster_code VARCHAR(50) NOT NULL UNIQUE,
+    description TEXT,
+    
+    -- Location Information
+    data_center_id INT DEFAULT 0,
+    region VARCHAR(100),
+    availability_zone VARCHAR(100),
+    
+    -- Management Fields
+    status TEXT NOT NULL DEFAULT 'ACTIVE' CHECK (status IN ('ACTIVE', 'INACTIVE', 'MAINTENANCE', 'DECOMMISSIONED')),
+    environment_type TEXT DEFAULT 'PRODUCTION' CHECK (environment_type IN ('PRODUCTION', 'DEVELOPMENT', 'QA', 'STAGING', 'TESTING')),
+    
+    -- Capacity Tracking
+    total_servers INT DEFAULT 0,
+    active_servers INT DEFAULT 0,
+    max_capacity INT,
+    
+    -- Metadata
+    owner VARCHAR(255),
+    contact_email VARCHAR(255),
+    tags TEXT,
+    metadata TEXT,
+    
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_server_clusters_cluster_name ON server_clusters (cluster_name);
+CREATE INDEX IF NOT EXISTS idx_server_clusters_cluster_code ON server_clusters (cluster_code);
+CREATE INDEX IF NOT EXISTS idx_server_clusters_status ON server_clusters (status);
+CREATE INDEX IF NOT EXISTS idx_server_clusters_environment ON server_clusters (environment_type);
+
+CREATE TRIGGER IF NOT EXISTS trg_server_clusters_updated_at
+    AFTER UPDATE ON server_clusters FOR EACH ROW
+    WHEN NEW.updated_at = OLD.updated_at
+BEGIN
+    UPDATE server_clusters SET updated_at = CURRENT_TIMESTAMP WHERE cluster_id = NEW.cluster_id;
+END;
+
+-- Sub-Clusters Table
+-- Represents logical groupings within a cluster (e.g., compute pool, storage pool, etc.)
+-- sub_cluster_id is a globally unique AUTO_INCREMENT PK; cluster_id is a FK to server_clusters.
+CREATE TABLE IF NOT EXISTS server_sub_clusters (
+    sub_cluster_id INTEGER PRIMARY KEY AUTOINCREMENT,
+    cluster_id INT NOT NULL,
+    
+    -- Basic Information
+    sub_cluster_name VARCHAR(255) NOT NULL,
+    sub_cluster_code VARCHAR(50) NOT NULL,
+    description TEXT,
+    
+    -- Sub-cluster Type/Purpose
+    sub_cluster_type TEXT DEFAULT 'MIXED' CHECK (sub_cluster_type IN ('COMPUTE', 'STORAGE', 'MIXED', 'MANAGEMENT', 'NETWORK', 'OTHER')),
+    
+    -- Management Fields
+    status TEXT NOT NULL DEFAULT 'ACTIVE' CHECK (status IN ('ACTIVE', 'INACTIVE', 'MAINTENANCE', 'DECOMMISSIONED')),
+    
+    -- Capacity Tracking
+    total_servers INT DEFAULT 0,
+    active_servers INT DEFAULT 0,
+    max_capacity INT,
+    
+    -- Workload Information
+    workload_type VARCHAR(100),
+    priority_level TEXT DEFAULT 'MEDIUM' CHECK (priority_level IN ('HIGH', 'MEDIUM', 'LOW')),
+    
+    -- Metadata
+    tags TEXT,
+    metadata TEXT,
+    
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    
+    FOREIGN KEY (cluster_id) REFERENCES server_clusters(cluster_id) ON DELETE CASCADE
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS uk_server_sub_clusters_sub_cluster_code ON server_sub_clusters (cluster_id, sub_cluster_code);
+CREATE INDEX IF NOT EXISTS idx_server_sub_clusters_cluster ON server_sub_clusters (cluster_id);
+CREATE INDEX IF NOT EXISTS idx_server_sub_clusters_sub_cluster_name ON server_sub_clusters (sub_cluster_name);
+CREATE INDEX IF NOT EXISTS idx_server_sub_clusters_status ON server_sub_clusters (status);
+CREATE INDEX IF NOT EXISTS idx_server_sub_clusters_type ON server_sub_clusters (sub_cluster_type);
+
+CREATE TRIGGER IF NOT EXISTS trg_server_sub_clusters_updated_at
+    AFTER UPDATE ON server_sub_clusters FOR EACH ROW
+    WHEN NEW.updated_at = OLD.updated_at
+BEGIN
+    UPDATE server_sub_clusters SET updated_at = CURRENT_TIMESTAMP WHERE sub_cluster_id = NEW.sub_cluster_id;
+END;
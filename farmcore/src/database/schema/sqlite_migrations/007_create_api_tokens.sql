@@ -0,0 +1,44 @@
+-- Create API token tables for bearer-token authentication
+-- Description: Stores hashed API tokens used by the v1 API middleware. Plaintext tokens are
+--              only returned once at creation time; the database keeps a SHA-256 hash.
+--
+-- SQLite port of migrations/007_create_api_tokens.sql. ENUM columns become TEXT with a CHECK constraint,
+-- JSON columns become TEXT and ON UPDATE CURRENT_TIMESTAMP becomes an AFTER UPDATE trigger.
+
+-- ===================================================================
+-- API TOKENS
+-- ===================================================================
+
+-- API Tokens Table
+-- scope = API   -> general API access (dashboards, automation, operators)
+-- scope = AGENT -> farm-manager agents posting to the inventory ingest routes only
+CREATE TABLE IF NOT EXISTS api_tokens (
+    token_id INTEGER PRIMARY KEY AUTOINCREMENT,
+
+    -- Identification
+    token_name VARCHAR(255) NOT NULL UNIQUE,
+    token_prefix VARCHAR(16) NOT NULL, -- First characters of the token, safe to display
+    token_hash CHAR(64) NOT NULL UNIQUE, -- SHA-256 hex digest of the full token
+    description TEXT,
+
+    -- Access Control
+    scope TEXT NOT NULL DEFAULT 'API' CHECK (scope IN ('API', 'AGENT')),
+
+    -- Lifecycle
+    expires_at TIMESTAMP NULL,
+    last_used_at TIMESTAMP NULL,
+    revoked_at TIMESTAMP NULL,
+
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_api_tokens_scope ON api_tokens (scope);
+CREATE INDEX IF NOT EXISTS idx_api_tokens_revoked ON api_tokens (revoked_at);
+
+CREATE TRIGGER IF NOT EXISTS trg_api_tokens_updated_at
+    AFTER UPDATE ON api_tokens FOR EACH ROW
+    WHEN NEW.updated_at = OLD.updated_at
+BEGIN
+    UPDATE api_tokens SET updated_at = CURRENT_TIMESTAMP WHERE token_id = NEW.token_id;
+END;
@@ -0,0 +1,20 @@
+-- Add roles to API tokens for role-based access control
+-- Description: Attaches a viewer/operator/admin role to API-scoped tokens. The middleware
+--              checks the role against a per-route permission map. AGENT tokens ignore it.
+--
+-- SQLite port of migrations/008_add_api_token_roles.sql.
+
+-- ===================================================================
+-- API TOKEN ROLES
+-- ===================================================================
+
+-- role = VIEWER   -> read-only access (GET routes)
+-- role = OPERATOR -> viewer + inventory edits and Redfish power actions
+-- role = ADMIN    -> operator + token management and database migrations/seeding
+ALTER TABLE api_tokens
+    ADD COLUMN role TEXT NOT NULL DEFAULT 'VIEWER' CHECK (role IN ('VIEWER', 'OPERATOR', 'ADMIN'));
+
+CREATE INDEX IF NOT EXISTS idx_api_tokens_role ON api_tokens (role);
+
+-- Tokens issued before roles existed had unrestricted access; keep it that way
+UPDATE api_tokens SET role = 'ADMIN' WHERE scope = 'API';
@@ -0,0 +1,49 @@
+-- Create audit log table
+-- Description: Records every mutating v1 API call (PUT/POST/PATCH/DELETE) with the calling
+--              principal, the matched route, the outcome, column-level diffs captured from
+--              DatabaseHelper::update, and side effects such as Redfish power actions.
+--
+-- SQLite port of migrations/009_create_audit_events.sql. ENUM columns become TEXT with a CHECK constraint,
+-- JSON columns become TEXT and ON UPDATE CURRENT_TIMESTAMP becomes an AFTER UPDATE trigger.
+
+-- ===================================================================
+-- AUDIT EVENTS
+-- ===================================================================
+
+-- Audit Events Table
+-- Principal fields are copied from the token at request time so events stay readable
+-- after a token is revoked or renamed.
+CREATE TABLE IF NOT EXISTS audit_events (
+    audit_id INTEGER PRIMARY KEY AUTOINCREMENT,
+
+    -- Principal
+    token_id INT NULL, -- NULL for the bootstrap token
+    principal_name VARCHAR(255) NOT NULL,
+    principal_scope TEXT NOT NULL CHECK (principal_scope IN ('API', 'AGENT')),
+    principal_role TEXT NULL CHECK (principal_role IN ('VIEWER', 'OPERATOR', 'ADMIN')), -- NULL for AGENT tokens
+    client_ip VARCHAR(64),
+
+    -- Request
+    http_method VARCHAR(10) NOT NULL,
+    route VARCHAR(255) NOT NULL, -- Matched route pattern, e.g. /api/v1/servers/{id}/power/on
+    request_path VARCHAR(1024) NOT NULL,
+    resource VARCHAR(64), -- First path segment under /api/v1 (servers, datacenters, ...)
+    entity_id VARCHAR(64), -- Id taken from the route parameters, if any
+
+    -- Outcome
+    status_code INT NOT NULL,
+    result TEXT NOT NULL CHECK (result IN ('SUCCESS', 'FAILURE')),
+    duration_ms INT,
+
+    -- Details
+    changes TEXT, -- [{table, key_column, id, old: {...}, new: {...}}]
+    actions TEXT, -- [{action, target, detail, success, error}]
+
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_audit_events_token ON audit_events (token_id);
+CREATE INDEX IF NOT EXISTS idx_audit_events_principal ON audit_events (principal_name);
+CREATE INDEX IF NOT EXISTS idx_audit_events_resource_entity ON audit_events (resource, entity_id);
+CREATE INDEX IF NOT EXISTS idx_audit_events_result ON audit_events (result);
+CREATE INDEX IF NOT EXISTS idx_audit_events_created ON audit_events (created_at);
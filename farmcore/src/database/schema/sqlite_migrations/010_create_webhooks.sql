@@ -0,0 +1,88 @@
+-- Create outbound webhook tables
+-- Description: Webhook subscriptions (target URL, signing secret, event filter) and a durable
+--              delivery log. Every matching domain event is queued as a delivery row first and
+--              sent by the background dispatcher, which retries with exponential backoff.
+--
+-- SQLite port of migrations/010_create_webhooks.sql. ENUM columns become TEXT with a CHECK constraint,
+-- JSON columns become TEXT and ON UPDATE CURRENT_TIMESTAMP becomes an AFTER UPDATE trigger.
+
+-- ===================================================================
+-- WEBHOOK SUBSCRIPTIONS
+-- ===================================================================
+
+-- Webhook Subscriptions Table
+-- The secret is the HMAC-SHA256 key for the X-Farm-Signature header. It has to be kept in
+-- the clear to sign with, so it is only ever returned once, when the webhook is created.
+CREATE TABLE IF NOT EXISTS webhook_subscriptions (
+    webhook_id INTEGER PRIMARY KEY AUTOINCREMENT,
+
+    -- Identification
+    webhook_name VARCHAR(255) NOT NULL UNIQUE,
+    description TEXT,
+
+    -- Target
+    url VARCHAR(2048) NOT NULL,
+    secret VARCHAR(255) NOT NULL,
+
+    -- Filter
+    event_types TEXT NOT NULL, -- ["server.status_changed", "server.*"]; ["*"] for every event
+
+    is_active BOOLEAN NOT NULL DEFAULT TRUE,
+
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_webhook_subscriptions_active ON webhook_subscriptions (is_active);
+
+CREATE TRIGGER IF NOT EXISTS trg_webhook_subscriptions_updated_at
+    AFTER UPDATE ON webhook_subscriptions FOR EACH ROW
+    WHEN NEW.updated_at = OLD.updated_at
+BEGIN
+    UPDATE webhook_subscriptions SET updated_at = CURRENT_TIMESTAMP WHERE webhook_id = NEW.webhook_id;
+END;
+
+-- ===================================================================
+-- WEBHOOK DELIVERIES
+-- ===================================================================
+
+-- Webhook Deliveries Table
+-- PENDING   -> waiting for next_attempt_at (new, or retrying after a failed attempt)
+-- SUCCEEDED -> the receiver answered 2xx
+-- FAILED    -> every attempt failed; not retried again
+CREATE TABLE IF NOT EXISTS webhook_deliveries (
+    delivery_id INTEGER PRIMARY KEY AUTOINCREMENT,
+    webhook_id INT NOT NULL,
+
+    -- Event
+    event_id BIGINT NULL, -- Domain event ID; NULL for pings
+    event_type VARCHAR(64) NOT NULL,
+    payload TEXT NOT NULL, -- Request body, exactly as signed
+
+    -- Progress
+    status TEXT NOT NULL DEFAULT 'PENDING' CHECK (status IN ('PENDING', 'SUCCEEDED', 'FAILED')),
+    attempts INT NOT NULL DEFAULT 0,
+    next_attempt_at TIMESTAMP NULL,
+    last_status_code INT NULL,
+    last_error TEXT,
+    delivered_at TIMESTAMP NULL,
+
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+
+    CONSTRAINT fk_delivery_webhook
+        FOREIGN KEY (webhook_id) REFERENCES webhook_subscriptions(webhook_id)
+        ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_webhook_deliveries_webhook ON webhook_deliveries (webhook_id);
+CREATE INDEX IF NOT EXISTS idx_webhook_deliveries_due ON webhook_deliveries (status, next_attempt_at);
+CREATE INDEX IF NOT EXISTS idx_webhook_deliveries_event_type ON webhook_deliveries (event_type);
+CREATE INDEX IF NOT EXISTS idx_webhook_deliveries_created ON webhook_deliveries (created_at);
+
+CREATE TRIGGER IF NOT EXISTS trg_webhook_deliveries_updated_at
+    AFTER UPDATE ON webhook_deliveries FOR EACH ROW
+    WHEN NEW.updated_at = OLD.updated_at
+BEGIN
+    UPDATE webhook_deliveries SET updated_at = CURRENT_TIMESTAMP WHERE delivery_id = NEW.delivery_id;
+END;
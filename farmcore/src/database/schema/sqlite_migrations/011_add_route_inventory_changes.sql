@@ -0,0 +1,40 @@
+-- Record routing table changes in the component change history
+-- Description: The inventory sync now stores power supplies and routes. PSU changes use the
+--              existing 'power' component type; routes get a 'route' type of their own.
+--
+-- SQLite port of migrations/011_add_route_inventory_changes.sql. A CHECK constraint cannot be
+-- altered, so the table is rebuilt with the wider one.
+
+-- ===================================================================
+-- INVENTORY CHANGE HISTORY
+-- ===================================================================
+CREATE TABLE server_inventory_changes_new (
+    change_id INTEGER PRIMARY KEY AUTOINCREMENT,
+    server_id INT NOT NULL,
+    
+    component_type TEXT NOT NULL CHECK (component_type IN ('cpu', 'memory', 'disk', 'network', 'route', 'gpu', 'power', 'bios', 'bmc', 'chassis')),
+    component_identifier VARCHAR(255) NOT NULL, -- slot, name, pci_address, etc.
+    change_type TEXT NOT NULL CHECK (change_type IN ('added', 'removed', 'modified')),
+    
+    old_data TEXT,
+    new_data TEXT,
+    
+    detected_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    
+    CONSTRAINT fk_change_server
+        FOREIGN KEY (server_id) REFERENCES servers(server_id)
+        ON DELETE CASCADE
+);
+
+INSERT INTO server_inventory_changes_new (
+    change_id, server_id, component_type, component_identifier, change_type, old_data, new_data, detected_at
+)
+SELECT change_id, server_id, component_type, component_identifier, change_type, old_data, new_data, detected_at
+FROM server_inventory_changes;
+
+DROP TABLE server_inventory_changes;
+ALTER TABLE server_inventory_changes_new RENAME TO server_inventory_changes;
+
+CREATE INDEX IF NOT EXISTS idx_server_inventory_changes_server_component ON server_inventory_changes (server_id, component_type);
+CREATE INDEX IF NOT EXISTS idx_server_inventory_changes_change_type ON server_inventory_changes (change_type);
+CREATE INDEX IF NOT EXISTS idx_server_inventory_changes_detected_at ON server_inventory_changes (detected_at);
@@ -0,0 +1,38 @@
+-- Identify servers by more than the MAC of their primary interface
+-- Description: Inventory is matched to a server on its SMBIOS system UUID, system, chassis and
+--              board serial numbers, BMC MAC and NIC MACs. Reports that match several servers,
+--              or match one against contradicting identifiers, are kept as identity conflicts.
+--
+-- SQLite port of migrations/012_add_server_identity.sql
+
+-- ===================================================================
+-- SERVERS
+-- ===================================================================
+ALTER TABLE servers ADD COLUMN system_uuid VARCHAR(36) NULL;
+
+CREATE INDEX IF NOT EXISTS idx_servers_system_uuid ON servers (system_uuid);
+CREATE INDEX IF NOT EXISTS idx_servers_chassis_serial_number ON servers (chassis_serial_number);
+
+-- ===================================================================
+-- IDENTITY CONFLICTS
+-- ===================================================================
+CREATE TABLE IF NOT EXISTS server_identity_conflicts (
+    conflict_id INTEGER PRIMARY KEY AUTOINCREMENT,
+    server_id INT NULL, -- Server the inventory was applied to; NULL when it was rejected
+
+    resolution TEXT NOT NULL CHECK (resolution IN ('MATCHED', 'CREATED', 'REJECTED')),
+    hostname VARCHAR(255),
+    confidence INT NOT NULL, -- Score (0-100) of the best-matching existing server
+    candidates TEXT NOT NULL, -- Every server the report matched, with its score and identifiers
+
+    detected_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    resolved_at TIMESTAMP NULL,
+
+    CONSTRAINT fk_identity_conflict_server
+        FOREIGN KEY (server_id) REFERENCES servers(server_id)
+        ON DELETE SET NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_server_identity_conflicts_server ON server_identity_conflicts (server_id);
+CREATE INDEX IF NOT EXISTS idx_server_identity_conflicts_detected ON server_identity_conflicts (detected_at);
+CREATE INDEX IF NOT EXISTS idx_server_identity_conflicts_unresolved ON server_identity_conflicts (resolved_at);
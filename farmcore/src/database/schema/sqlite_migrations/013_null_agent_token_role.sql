@@ -0,0 +1,24 @@
+-- Drop the role from AGENT tokens
+-- Description: Roles only apply to API-scoped tokens. AGENT tokens were stored with the VIEWER
+--              default, which showed up in token listings as if it meant something.
+--
+-- SQLite port of migrations/013_null_agent_token_role.sql. SQLite cannot change a column's
+-- constraints in place, so the column is replaced by a nullable one.
+
+-- ===================================================================
+-- API TOKEN ROLES
+-- ===================================================================
+
+DROP INDEX IF EXISTS idx_api_tokens_role;
+
+ALTER TABLE api_tokens RENAME COLUMN role TO role_before_013;
+
+-- role is NULL for AGENT tokens and set for API tokens
+ALTER TABLE api_tokens
+    ADD COLUMN role TEXT NULL DEFAULT 'VIEWER' CHECK (role IN ('VIEWER', 'OPERATOR', 'ADMIN'));
+
+UPDATE api_tokens SET role = CASE WHEN scope = 'AGENT' THEN NULL ELSE role_before_013 END;
+
+ALTER TABLE api_tokens DROP COLUMN role_before_013;
+
+CREATE INDEX IF NOT EXISTS idx_api_tokens_role ON api_tokens (role);
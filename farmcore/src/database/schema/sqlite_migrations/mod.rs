@@ -0,0 +1,7 @@
+use sqlx::SqlitePool;
+
+/// Apply the SQLite port of the schema. Every MySQL migration has a port with the same version
+/// number, so the two histories line up.
+pub async fn run_all(pool: &SqlitePool) -> Result<(), sqlx::migrate::MigrateError> {
+    sqlx::migrate!("./src/database/schema/sqlite_migrations").run(pool).await
+}
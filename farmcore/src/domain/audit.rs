@@ -0,0 +1,75 @@
+use serde::Serialize;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+
+// ===================================================================
+// AUDIT RECORDER
+// ===================================================================
+//
+// The audit middleware runs each mutating request inside an `AuditRecorder`
+// scope. Lower layers (DatabaseHelper, RedfishClient) push what they actually
+// did into the current recorder; outside of a request scope those calls are
+// no-ops, so background jobs and the CLI are unaffected.
+
+tokio::task_local! {
+    static CURRENT: Arc<AuditRecorder>;
+}
+
+/// Old vs new values of the columns touched by a single row update
+#[derive(Debug, Clone, Serialize)]
+pub struct RowChange {
+    pub table: String,
+    pub key_column: String,
+    pub id: i32,
+    pub old: serde_json::Value,
+    pub new: serde_json::Value,
+}
+
+/// A side effect outside the database, e.g. a BMC power action
+#[derive(Debug, Clone, Serialize)]
+pub struct AuditAction {
+    pub action: String,
+    pub target: String,
+    pub detail: serde_json::Value,
+    pub success: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Default)]
+pub struct AuditRecorder {
+    changes: Mutex<Vec<RowChange>>,
+    actions: Mutex<Vec<AuditAction>>,
+}
+
+impl AuditRecorder {
+    /// Run `future` with this recorder as the current audit scope
+    pub async fn scope<F: Future>(self: Arc<Self>, future: F) -> F::Output {
+        CURRENT.scope(self, future).await
+    }
+
+    /// Whether the caller is running inside an audit scope
+    pub fn is_recording() -> bool {
+        CURRENT.try_with(|_| ()).is_ok()
+    }
+
+    pub fn record_change(change: RowChange) {
+        let _ = CURRENT.try_with(|recorder| {
+            recorder.changes.lock().unwrap_or_else(|e| e.into_inner()).push(change)
+        });
+    }
+
+    pub fn record_action(action: AuditAction) {
+        let _ = CURRENT.try_with(|recorder| {
+            recorder.actions.lock().unwrap_or_else(|e| e.into_inner()).push(action)
+        });
+    }
+
+    pub fn take_changes(&self) -> Vec<RowChange> {
+        std::mem::take(&mut *self.changes.lock().unwrap_or_else(|e| e.into_inner()))
+    }
+
+    pub fn take_actions(&self) -> Vec<AuditAction> {
+        std::mem::take(&mut *self.actions.lock().unwrap_or_else(|e| e.into_inner()))
+    }
+}
@@ -1,8 +1,61 @@
-use reqwest::Client;
+use reqwest::{Client, StatusCode};
 use serde::{Deserialize, Serialize};
-use std::time::Duration;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+use crate::domain::{AuditAction, AuditRecorder};
+use crate::metrics;
 use crate::models::bmc::{PowerState, SystemInfo};
 
+/// Member IDs under the Systems collection differ by vendor: System.Embedded.1 on iDRAC,
+/// 1 on iLO, XCC and Supermicro, system on OpenBMC. They are discovered, never assumed.
+const SYSTEMS_PATH: &str = "/redfish/v1/Systems";
+
+/// How long discovered systems and reset types are trusted before the BMC is asked again
+const DISCOVERY_TTL: Duration = Duration::from_secs(600);
+
+/// What each BMC was found to offer, by base URL. Clients are built per request, so this
+/// outlives them.
+static DISCOVERED: LazyLock<Mutex<HashMap<String, DiscoveredBmc>>> = LazyLock::new(Default::default);
+
+#[derive(Debug, Clone)]
+struct DiscoveredBmc {
+    fetched_at: Instant,
+    /// @odata.id of each member of the Systems collection, in the order the BMC lists them
+    systems: Vec<String>,
+    /// Reset action of each system read so far, by @odata.id
+    resets: HashMap<String, ResetCapability>,
+}
+
+impl DiscoveredBmc {
+    fn new() -> Self {
+        Self {
+            fetched_at: Instant::now(),
+            systems: Vec::new(),
+            resets: HashMap::new(),
+        }
+    }
+}
+
+/// `ComputerSystem.Reset` as a system advertises it
+#[derive(Debug, Clone)]
+struct ResetCapability {
+    target: String,
+    /// ResetType values the BMC accepts; None when it does not list them
+    allowed: Option<Vec<String>>,
+}
+
+impl ResetCapability {
+    /// First of `preferred` the BMC accepts. A BMC that lists nothing gets the first one.
+    fn choose<'a>(&self, preferred: &[&'a str]) -> Option<&'a str> {
+        match &self.allowed {
+            None => preferred.first().copied(),
+            Some(allowed) => preferred.iter().copied().find(|reset_type| allowed.iter().any(|value| value == reset_type)),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct RedfishClient {
     base_url: String,
@@ -28,8 +81,23 @@ pub enum RedfishError {
     #[error("JSON parsing error: {0}")]
     Json(#[from] serde_json::Error),
     
-    #[error("Operation not supported")]
-    NotSupported,
+    #[error("Operation not supported: {0}")]
+    NotSupported(String),
+}
+
+impl RedfishError {
+    /// Short error class, used as the outcome label of the Redfish metrics
+    pub fn kind(&self) -> &'static str {
+        match self {
+            RedfishError::Http(e) if e.is_timeout() => "timeout",
+            RedfishError::Http(e) if e.is_connect() => "connection_error",
+            RedfishError::Http(_) => "http_error",
+            RedfishError::Connection(_) => "connection_error",
+            RedfishError::Authentication => "auth_error",
+            RedfishError::InvalidResponse(_) | RedfishError::Json(_) => "invalid_response",
+            RedfishError::NotSupported(_) => "not_supported",
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -41,9 +109,9 @@ struct ResetAction {
 impl RedfishClient {
     pub fn new(host: &str, username: &str, password: &str) -> Result<Self, RedfishError> {
         let base_url = if host.starts_with("http://") || host.starts_with("https://") {
-            host.to_string()
+            host.trim_end_matches('/').to_string()
         } else {
-            format!("https://{}", host)
+            format!("https://{}", host.trim_end_matches('/'))
         };
         
         let client = Client::builder()
@@ -73,25 +141,138 @@ impl RedfishClient {
         Ok(response.status().is_success())
     }
     
+    /// BMC address without the scheme, as used in metric labels
+    fn bmc_address(&self) -> &str {
+        let address = self.base_url.split_once("://").map_or(self.base_url.as_str(), |(_, rest)| rest);
+        address.trim_end_matches('/')
+    }
+
+    /// Count and time a finished call in the Redfish metrics
+    fn observe<T>(&self, operation: &str, started: Instant, result: &Result<T, RedfishError>) {
+        let outcome = result.as_ref().map_or_else(RedfishError::kind, |_| "success");
+        metrics::observe_redfish_call(self.bmc_address(), operation, outcome, started.elapsed());
+    }
+
     /// Get system information
     pub async fn get_system_info(&self, system_id: Option<&str>) -> Result<SystemInfo, RedfishError> {
-        let system_id = system_id.unwrap_or("System.Embedded.1");
-        let url = format!("{}/redfish/v1/Systems/{}", self.base_url, system_id);
-        
+        let started = Instant::now();
+        let result = self.fetch_system_info(system_id).await;
+        self.observe("get_system", started, &result);
+        result
+    }
+
+    async fn fetch_system_info(&self, system_id: Option<&str>) -> Result<SystemInfo, RedfishError> {
+        let path = self.system_path(system_id).await?;
+        let system = self.get_json(&path).await?;
+        Ok(serde_json::from_value(system)?)
+    }
+
+    /// GET a resource by its path. A 404 drops what was discovered about the BMC, as a
+    /// firmware update may have renamed its systems.
+    async fn get_json(&self, path: &str) -> Result<Value, RedfishError> {
         let response = self.client
-            .get(&url)
+            .get(format!("{}{}", self.base_url, path))
             .basic_auth(&self.username, Some(&self.password))
             .send()
             .await?;
-        
+
         if !response.status().is_success() {
+            if response.status() == StatusCode::NOT_FOUND {
+                self.forget();
+            }
             return Err(RedfishError::InvalidResponse(
                 format!("HTTP {}: {}", response.status(), response.text().await?)
             ));
         }
-        
-        let system_info: SystemInfo = response.json().await?;
-        Ok(system_info)
+
+        Ok(response.json().await?)
+    }
+
+    /// Read from the discovery cache, unless this BMC's entry has expired
+    fn cached<T>(&self, read: impl FnOnce(&DiscoveredBmc) -> Option<T>) -> Option<T> {
+        let discovered = DISCOVERED.lock().unwrap_or_else(|e| e.into_inner());
+        discovered
+            .get(&self.base_url)
+            .filter(|bmc| bmc.fetched_at.elapsed() < DISCOVERY_TTL)
+            .and_then(read)
+    }
+
+    fn remember(&self, update: impl FnOnce(&mut DiscoveredBmc)) {
+        let mut discovered = DISCOVERED.lock().unwrap_or_else(|e| e.into_inner());
+        let bmc = discovered.entry(self.base_url.clone()).or_insert_with(DiscoveredBmc::new);
+        if bmc.fetched_at.elapsed() >= DISCOVERY_TTL {
+            *bmc = DiscoveredBmc::new();
+        }
+        update(bmc);
+    }
+
+    fn forget(&self) {
+        DISCOVERED.lock().unwrap_or_else(|e| e.into_inner()).remove(&self.base_url);
+    }
+
+    /// Path of the system to act on: the given member ID, or else the first member of the
+    /// Systems collection. BMCs managing one host list exactly one.
+    async fn system_path(&self, system_id: Option<&str>) -> Result<String, RedfishError> {
+        if let Some(system_id) = system_id {
+            return Ok(format!("{}/{}", SYSTEMS_PATH, system_id));
+        }
+        if let Some(path) = self.cached(|bmc| bmc.systems.first().cloned()) {
+            return Ok(path);
+        }
+
+        let started = Instant::now();
+        let result = self.discover_systems().await;
+        self.observe("discover_systems", started, &result);
+        let systems = result?;
+        let first = systems.first().cloned().ok_or_else(|| {
+            RedfishError::InvalidResponse(format!("{} has no members", SYSTEMS_PATH))
+        })?;
+        self.remember(|bmc| bmc.systems = systems);
+        Ok(first)
+    }
+
+    async fn discover_systems(&self) -> Result<Vec<String>, RedfishError> {
+        let collection = self.get_json(SYSTEMS_PATH).await?;
+        let members = collection["Members"].as_array().ok_or_else(|| {
+            RedfishError::InvalidResponse(format!("{} has no Members array", SYSTEMS_PATH))
+        })?;
+        Ok(members
+            .iter()
+            .filter_map(|member| member["@odata.id"].as_str())
+            .map(|path| path.trim_end_matches('/').to_string())
+            .collect())
+    }
+
+    /// Reset action of the system at `path`. The allowed values are listed on the action
+    /// itself, or (OpenBMC) in a separate ActionInfo resource.
+    async fn reset_capability(&self, path: &str) -> Result<ResetCapability, RedfishError> {
+        if let Some(reset) = self.cached(|bmc| bmc.resets.get(path).cloned()) {
+            return Ok(reset);
+        }
+
+        let system = self.get_json(path).await?;
+        let action = &system["Actions"]["#ComputerSystem.Reset"];
+        if action.is_null() {
+            return Err(RedfishError::NotSupported(format!("{} offers no ComputerSystem.Reset action", path)));
+        }
+        let target = action["target"]
+            .as_str()
+            .map_or_else(|| format!("{}/Actions/ComputerSystem.Reset", path), str::to_string);
+        let mut allowed = allowable_values(&action["ResetType@Redfish.AllowableValues"]);
+        if allowed.is_none() {
+            if let Some(info_path) = action["@Redfish.ActionInfo"].as_str() {
+                // An unreadable ActionInfo leaves the values unknown rather than failing the reset
+                if let Ok(info) = self.get_json(info_path).await {
+                    allowed = action_info_values(&info, "ResetType");
+                }
+            }
+        }
+
+        let reset = ResetCapability { target, allowed };
+        self.remember(|bmc| {
+            bmc.resets.insert(path.to_string(), reset.clone());
+        });
+        Ok(reset)
     }
     
     /// Get current power state
@@ -100,18 +281,50 @@ impl RedfishClient {
         Ok(PowerState::from(system_info.power_state))
     }
     
-    /// Set power state (On, ForceOff, GracefulShutdown, ForceRestart, GracefulRestart, etc.)
-    pub async fn set_power_state(
-        &self,
-        reset_type: &str,
-        system_id: Option<&str>,
-    ) -> Result<(), RedfishError> {
-        let system_id = system_id.unwrap_or("System.Embedded.1");
-        let url = format!(
-            "{}/redfish/v1/Systems/{}/Actions/ComputerSystem.Reset",
-            self.base_url, system_id
-        );
-        
+    /// Set power state with the first of `preferred` reset types (On, ForceOff, GracefulShutdown,
+    /// ForceRestart, GracefulRestart, etc.) the system accepts. Fails with `NotSupported` when
+    /// the BMC lists none of them.
+    pub async fn set_power_state(&self, preferred: &[&str], system_id: Option<&str>) -> Result<(), RedfishError> {
+        let started = Instant::now();
+        let mut system = None;
+        let mut chosen = None;
+        let result = async {
+            let path = self.system_path(system_id).await?;
+            system = Some(path.clone());
+            let reset = self.reset_capability(&path).await?;
+            let reset_type = reset.choose(preferred).ok_or_else(|| {
+                RedfishError::NotSupported(format!(
+                    "{} accepts none of {} (offers {})",
+                    path,
+                    preferred.join(", "),
+                    reset.allowed.as_deref().unwrap_or_default().join(", ")
+                ))
+            })?;
+            chosen = Some(reset_type);
+            self.send_reset(&reset.target, reset_type).await
+        }
+        .await;
+        self.observe("reset", started, &result);
+
+        AuditRecorder::record_action(AuditAction {
+            action: "redfish.reset".to_string(),
+            target: self.base_url.clone(),
+            detail: serde_json::json!({
+                "system_id": system_id,
+                "system": system,
+                "requested": preferred,
+                "reset_type": chosen,
+            }),
+            success: result.is_ok(),
+            error: result.as_ref().err().map(|e| e.to_string()),
+        });
+
+        result
+    }
+
+    async fn send_reset(&self, target: &str, reset_type: &str) -> Result<(), RedfishError> {
+        let url = format!("{}{}", self.base_url, target);
+
         let reset_action = ResetAction {
             reset_type: reset_type.to_string(),
         };
@@ -124,6 +337,9 @@ impl RedfishClient {
             .await?;
         
         if !response.status().is_success() {
+            if response.status() == StatusCode::NOT_FOUND {
+                self.forget();
+            }
             return Err(RedfishError::InvalidResponse(
                 format!("HTTP {}: {}", response.status(), response.text().await?)
             ));
@@ -132,28 +348,163 @@ impl RedfishClient {
         Ok(())
     }
     
-    /// Power on the system
+    /// Power on the system; ForceOn where plain On is not offered
     pub async fn power_on(&self, system_id: Option<&str>) -> Result<(), RedfishError> {
-        self.set_power_state("On", system_id).await
+        self.set_power_state(POWER_ON, system_id).await
     }
     
-    /// Gracefully power off the system
+    /// Gracefully power off the system; PushPowerButton, which asks the OS to shut down, where
+    /// GracefulShutdown is not offered (iLO 4). The button turns a system that is off back on,
+    /// so it is only pressed while the system is on.
     pub async fn power_off(&self, system_id: Option<&str>) -> Result<(), RedfishError> {
-        self.set_power_state("GracefulShutdown", system_id).await
+        let path = self.system_path(system_id).await?;
+        let graceful = self.reset_capability(&path).await?.choose(&["GracefulShutdown"]).is_some();
+        if !graceful && self.get_power_state(system_id).await? == PowerState::Off {
+            return Ok(());
+        }
+        self.set_power_state(POWER_OFF, system_id).await
     }
     
     /// Force power off the system
     pub async fn force_power_off(&self, system_id: Option<&str>) -> Result<(), RedfishError> {
-        self.set_power_state("ForceOff", system_id).await
+        self.set_power_state(FORCE_POWER_OFF, system_id).await
     }
     
-    /// Reboot the system
+    /// Reboot the system; ForceRestart where the BMC has no GracefulRestart (iLO 4), so a
+    /// reboot works everywhere. The audit entry records which reset type was sent.
     pub async fn reboot(&self, system_id: Option<&str>) -> Result<(), RedfishError> {
-        self.set_power_state("GracefulRestart", system_id).await
+        self.set_power_state(REBOOT, system_id).await
     }
     
-    /// Force reboot the system
+    /// Force reboot the system; PowerCycle where ForceRestart is not offered
     pub async fn force_reboot(&self, system_id: Option<&str>) -> Result<(), RedfishError> {
-        self.set_power_state("ForceRestart", system_id).await
+        self.set_power_state(FORCE_REBOOT, system_id).await
+    }
+}
+
+/// Reset types tried by each power action, in order of preference
+const POWER_ON: &[&str] = &["On", "ForceOn"];
+const POWER_OFF: &[&str] = &["GracefulShutdown", "PushPowerButton"];
+const FORCE_POWER_OFF: &[&str] = &["ForceOff"];
+const REBOOT: &[&str] = &["GracefulRestart", "ForceRestart"];
+const FORCE_REBOOT: &[&str] = &["ForceRestart", "PowerCycle"];
+
+/// AllowableValues of the named parameter in an ActionInfo resource
+fn action_info_values(info: &Value, parameter: &str) -> Option<Vec<String>> {
+    info["Parameters"]
+        .as_array()
+        .and_then(|parameters| parameters.iter().find(|candidate| candidate["Name"] == parameter))
+        .and_then(|parameter| allowable_values(&parameter["AllowableValues"]))
+}
+
+/// String values of an AllowableValues array, None when there is no array
+fn allowable_values(values: &Value) -> Option<Vec<String>> {
+    values
+        .as_array()
+        .map(|values| values.iter().filter_map(Value::as_str).map(str::to_string).collect())
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// Reset capability as a system resource advertises it inline
+    fn inline(system: Value) -> ResetCapability {
+        let action = &system["Actions"]["#ComputerSystem.Reset"];
+        ResetCapability {
+            target: action["target"].as_str().unwrap().to_string(),
+            allowed: allowable_values(&action["ResetType@Redfish.AllowableValues"]),
+        }
+    }
+
+    fn ilo(allowed: &[&str]) -> ResetCapability {
+        inline(json!({
+            "@odata.id": "/redfish/v1/Systems/1/",
+            "Actions": { "#ComputerSystem.Reset": {
+                "ResetType@Redfish.AllowableValues": allowed,
+                "target": "/redfish/v1/Systems/1/Actions/ComputerSystem.Reset/",
+            } },
+        }))
+    }
+
+    #[test]
+    fn ilo_gets_its_fallbacks_where_the_preferred_type_is_missing() {
+        let ilo5 = ilo(&["On", "ForceOff", "GracefulShutdown", "ForceRestart", "Nmi", "PushPowerButton", "GracefulRestart"]);
+        assert_eq!(ilo5.target, "/redfish/v1/Systems/1/Actions/ComputerSystem.Reset/");
+        assert_eq!(ilo5.choose(POWER_ON), Some("On"));
+        assert_eq!(ilo5.choose(POWER_OFF), Some("GracefulShutdown"));
+        assert_eq!(ilo5.choose(REBOOT), Some("GracefulRestart"));
+        assert_eq!(ilo5.choose(FORCE_REBOOT), Some("ForceRestart"));
+
+        // iLO 4 has neither GracefulShutdown nor GracefulRestart
+        let ilo4 = ilo(&["On", "ForceOff", "ForceRestart", "Nmi", "PushPowerButton"]);
+        assert_eq!(ilo4.choose(POWER_OFF), Some("PushPowerButton"));
+        assert_eq!(ilo4.choose(REBOOT), Some("ForceRestart"));
+        assert_eq!(ilo4.choose(FORCE_POWER_OFF), Some("ForceOff"));
+    }
+
+    #[test]
+    fn idrac_lists_its_reset_types_on_the_action() {
+        let idrac = inline(json!({
+            "@odata.id": "/redfish/v1/Systems/System.Embedded.1",
+            "Actions": { "#ComputerSystem.Reset": {
+                "target": "/redfish/v1/Systems/System.Embedded.1/Actions/ComputerSystem.Reset",
+                "ResetType@Redfish.AllowableValues": [
+                    "On", "ForceOff", "ForceRestart", "GracefulRestart", "GracefulShutdown", "PushPowerButton", "Nmi", "PowerCycle",
+                ],
+            } },
+        }));
+        assert_eq!(idrac.choose(POWER_ON), Some("On"));
+        assert_eq!(idrac.choose(POWER_OFF), Some("GracefulShutdown"));
+        assert_eq!(idrac.choose(FORCE_POWER_OFF), Some("ForceOff"));
+        assert_eq!(idrac.choose(REBOOT), Some("GracefulRestart"));
+        assert_eq!(idrac.choose(FORCE_REBOOT), Some("ForceRestart"));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn openbmc_lists_its_reset_types_in_action_info() {
+        let system = json!({
+            "@odata.id": "/redfish/v1/Systems/system",
+            "Actions": { "#ComputerSystem.Reset": {
+                "@Redfish.ActionInfo": "/redfish/v1/Systems/system/ResetActionInfo",
+                "target": "/redfish/v1/Systems/system/Actions/ComputerSystem.Reset",
+            } },
+        });
+        let action = &system["Actions"]["#ComputerSystem.Reset"];
+        assert_eq!(allowable_values(&action["ResetType@Redfish.AllowableValues"]), None);
+
+        let info = json!({
+            "@odata.id": "/redfish/v1/Systems/system/ResetActionInfo",
+            "@odata.type": "#ActionInfo.v1_1_2.ActionInfo",
+            "Id": "ResetActionInfo",
+            "Name": "Reset Action Info",
+            "Parameters": [{
+                "AllowableValues": ["On", "ForceOff", "ForceOn", "ForceRestart", "GracefulRestart", "GracefulShutdown", "PowerCycle", "Nmi"],
+                "DataType": "String",
+                "Name": "ResetType",
+                "Required": true,
+            }],
+        });
+        let openbmc = ResetCapability {
+            target: action["target"].as_str().unwrap().to_string(),
+            allowed: action_info_values(&info, "ResetType"),
+        };
+        assert_eq!(openbmc.allowed.as_ref().map(Vec::len), Some(8));
+        assert_eq!(openbmc.choose(POWER_OFF), Some("GracefulShutdown"));
+        assert_eq!(openbmc.choose(REBOOT), Some("GracefulRestart"));
+        assert_eq!(action_info_values(&info, "OperationApplyTime"), None);
+    }
+
+    #[test]
+    fn an_unlisted_reset_type_is_not_chosen() {
+        let limited = ilo(&["On", "ForceOff"]);
+        assert_eq!(limited.choose(REBOOT), None);
+        assert_eq!(limited.choose(POWER_OFF), None);
+
+        // A BMC that lists nothing is sent the preferred type
+        let unlisted = ResetCapability { target: "/redfish/v1/Systems/1/Actions/ComputerSystem.Reset".to_string(), allowed: None };
+        assert_eq!(unlisted.choose(REBOOT), Some("GracefulRestart"));
+        assert_eq!(allowable_values(&json!("On")), None);
+        assert_eq!(allowable_values(&json!(["On", 1, null])), Some(vec!["On".to_string()]));
+    }
+}
@@ -0,0 +1,244 @@
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::net::IpAddr;
+
+use crate::domain::identity::{IdentityCandidate, IdentityKey, ServerIdentity};
+
+// ===================================================================
+// DUPLICATE SERVERS
+// ===================================================================
+//
+// Server records left over from before inventory reports were matched on identity: a
+// machine that was renamed, had its primary NIC swapped or was re-imaged often got a second
+// record. Two records are compared the way a report is compared with a stored server, with
+// a shared BMC IP and the same short hostname as extra evidence. Records whose system UUIDs
+// differ are different machines whatever else they share.
+
+/// Added when both records have a BMC at the same IP address
+const BMC_IP_WEIGHT: i32 = 20;
+
+/// Added when the hostnames only differ in case or domain
+const HOSTNAME_WEIGHT: i32 = 20;
+
+/// A value shared by more records than this is a placeholder nobody listed, not evidence
+const MAX_SHARED_BY: usize = 16;
+
+/// What a stored server is known by
+#[derive(Debug, Clone)]
+pub struct ServerFingerprint {
+    pub server_id: i32,
+    pub server_name: String,
+    pub last_inventory_at: Option<DateTime<Utc>>,
+    pub identity: ServerIdentity,
+    pub bmc_ips: Vec<String>,
+}
+
+/// Two server records that probably describe the same machine
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, JsonSchema)]
+pub struct DuplicateServers {
+    /// The lower of the two IDs
+    pub server_id: i32,
+    pub server_name: String,
+    pub duplicate_server_id: i32,
+    pub duplicate_server_name: String,
+    /// 0-100
+    pub confidence: i32,
+    /// Identifiers both records have with the same value
+    pub matched_by: Vec<IdentityKey>,
+    /// Identifiers both records have with different values
+    pub mismatched: Vec<IdentityKey>,
+    pub shared_bmc_ip: bool,
+    pub same_hostname: bool,
+    /// The record to merge the other into: the one inventoried last, as its components are current
+    pub suggested_server_id: i32,
+}
+
+/// Every pair of records sharing an identifier, BMC IP or short hostname and scoring at
+/// least `min_confidence`, most likely first
+pub fn find_duplicate_servers(servers: &[ServerFingerprint], min_confidence: i32) -> Vec<DuplicateServers> {
+    let mut shared_by: HashMap<(&str, String), Vec<usize>> = HashMap::new();
+    for (index, server) in servers.iter().enumerate() {
+        let values = server.identity.claims()
+            .map(|(key, value)| (key.as_str(), value.to_string()))
+            .chain(server.bmc_ips.iter().map(|ip| ("bmc_ip", ip.clone())))
+            .chain(short_hostname(&server.server_name).map(|name| ("hostname", name)));
+        for value in values {
+            shared_by.entry(value).or_default().push(index);
+        }
+    }
+
+    let mut pairs = HashSet::new();
+    for indexes in shared_by.values().filter(|indexes| (2..=MAX_SHARED_BY).contains(&indexes.len())) {
+        for (position, &a) in indexes.iter().enumerate() {
+            for &b in &indexes[position + 1..] {
+                if a != b {
+                    pairs.insert((a.min(b), a.max(b)));
+                }
+            }
+        }
+    }
+
+    let mut duplicates: Vec<DuplicateServers> = pairs
+        .into_iter()
+        .filter_map(|(a, b)| compare(&servers[a], &servers[b]))
+        .filter(|pair| pair.confidence >= min_confidence)
+        .collect();
+    duplicates.sort_by(|a, b| {
+        b.confidence.cmp(&a.confidence)
+            .then(a.server_id.cmp(&b.server_id))
+            .then(a.duplicate_server_id.cmp(&b.duplicate_server_id))
+    });
+    duplicates
+}
+
+fn compare(a: &ServerFingerprint, b: &ServerFingerprint) -> Option<DuplicateServers> {
+    let (a, b) = if a.server_id <= b.server_id { (a, b) } else { (b, a) };
+    let candidate = IdentityCandidate::score(b.server_id, &a.identity, &b.identity);
+    if candidate.excluded {
+        return None;
+    }
+
+    let shared_bmc_ip = a.bmc_ips.iter().any(|ip| b.bmc_ips.contains(ip));
+    let same_hostname = short_hostname(&a.server_name).is_some_and(|name| short_hostname(&b.server_name) == Some(name));
+    let mut confidence = candidate.score;
+    if shared_bmc_ip {
+        confidence += BMC_IP_WEIGHT;
+    }
+    if same_hostname {
+        confidence += HOSTNAME_WEIGHT;
+    }
+
+    // Records never inventoried sort first, so an equal pair keeps the older record
+    let suggested = if b.last_inventory_at > a.last_inventory_at { b } else { a };
+    Some(DuplicateServers {
+        server_id: a.server_id,
+        server_name: a.server_name.clone(),
+        duplicate_server_id: b.server_id,
+        duplicate_server_name: b.server_name.clone(),
+        confidence: confidence.min(100),
+        matched_by: candidate.matched_by,
+        mismatched: candidate.mismatched,
+        shared_bmc_ip,
+        same_hostname,
+        suggested_server_id: suggested.server_id,
+    })
+}
+
+/// Lowercase hostname without its domain; names that are IP addresses are kept whole
+fn short_hostname(name: &str) -> Option<String> {
+    let name = name.trim().trim_end_matches('.').to_ascii_lowercase();
+    if name.parse::<IpAddr>().is_ok() {
+        return Some(name);
+    }
+    name.split('.').next().filter(|label| !label.is_empty()).map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    const UUID: &str = "4c4c4544-0042-3510-8056-b7c04f4e3332";
+    const OTHER_UUID: &str = "5b1e2c3d-4f5a-4b6c-8d7e-9f0a1b2c3d4e";
+
+    fn server(server_id: i32, server_name: &str, system_uuid: Option<&str>, system_serial: Option<&str>, bmc_ips: &[&str]) -> ServerFingerprint {
+        ServerFingerprint {
+            server_id,
+            server_name: server_name.to_string(),
+            last_inventory_at: None,
+            identity: ServerIdentity::new(system_uuid, system_serial, None, None, None, Vec::<&str>::new()),
+            bmc_ips: bmc_ips.iter().map(|ip| ip.to_string()).collect(),
+        }
+    }
+
+    fn inventoried(mut server: ServerFingerprint, day: u32) -> ServerFingerprint {
+        server.last_inventory_at = Some(Utc.with_ymd_and_hms(2026, 3, day, 12, 0, 0).unwrap());
+        server
+    }
+
+    #[test]
+    fn records_sharing_a_serial_are_paired() {
+        let servers = [
+            server(4, "gpu-node-01", None, Some("7XK2PL3"), &[]),
+            server(9, "rebuilt-01", None, Some("7XK2PL3"), &[]),
+            server(12, "gpu-node-02", None, Some("8YL3QM4"), &[]),
+        ];
+        let duplicates = find_duplicate_servers(&servers, 0);
+        assert_eq!(duplicates.len(), 1);
+        let pair = &duplicates[0];
+        assert_eq!((pair.server_id, pair.duplicate_server_id), (4, 9));
+        assert_eq!(pair.confidence, 35);
+        assert_eq!(pair.matched_by, [IdentityKey::SystemSerial]);
+        assert!(pair.mismatched.is_empty());
+        assert!(!pair.shared_bmc_ip && !pair.same_hostname);
+
+        assert!(find_duplicate_servers(&servers, 36).is_empty());
+    }
+
+    #[test]
+    fn another_system_uuid_is_never_a_duplicate() {
+        let servers = [
+            server(4, "gpu-node-01", Some(UUID), Some("7XK2PL3"), &["10.0.0.4"]),
+            server(9, "gpu-node-01.lab", Some(OTHER_UUID), Some("7XK2PL3"), &["10.0.0.4"]),
+        ];
+        assert!(find_duplicate_servers(&servers, 0).is_empty());
+    }
+
+    #[test]
+    fn a_shared_bmc_ip_and_hostname_add_confidence() {
+        let servers = [
+            server(4, "GPU-Node-01.lab.example.com", None, Some("7XK2PL3"), &["10.0.0.4"]),
+            server(9, "gpu-node-01", None, Some("7XK2PL3"), &["10.0.0.4", "10.0.1.4"]),
+        ];
+        let pair = &find_duplicate_servers(&servers, 0)[0];
+        assert!(pair.shared_bmc_ip);
+        assert!(pair.same_hostname);
+        assert_eq!(pair.confidence, 35 + BMC_IP_WEIGHT + HOSTNAME_WEIGHT);
+
+        // Either on its own is enough to compare the records, without any identifier
+        let servers = [server(4, "gpu-node-01", None, None, &["10.0.0.4"]), server(9, "gpu-node-07", None, None, &["10.0.0.4"])];
+        let pair = &find_duplicate_servers(&servers, 0)[0];
+        assert_eq!(pair.confidence, BMC_IP_WEIGHT);
+        assert!(pair.matched_by.is_empty());
+    }
+
+    #[test]
+    fn names_that_are_ip_addresses_are_compared_whole() {
+        assert_eq!(short_hostname("10.0.0.4").as_deref(), Some("10.0.0.4"));
+        assert_eq!(short_hostname("Node-01.Lab.").as_deref(), Some("node-01"));
+        assert_eq!(short_hostname(" "), None);
+
+        let servers = [server(4, "10.0.0.4", None, None, &[]), server(9, "10.0.0.5", None, None, &[])];
+        assert!(find_duplicate_servers(&servers, 0).is_empty());
+    }
+
+    #[test]
+    fn a_value_shared_by_too_many_records_is_ignored() {
+        let servers: Vec<ServerFingerprint> = (1..=MAX_SHARED_BY as i32 + 1)
+            .map(|id| server(id, &format!("node-{id:02}"), None, Some("CZJ0000001"), &[]))
+            .collect();
+        assert!(find_duplicate_servers(&servers, 0).is_empty());
+
+        let duplicates = find_duplicate_servers(&servers[..MAX_SHARED_BY], 0);
+        assert_eq!(duplicates.len(), MAX_SHARED_BY * (MAX_SHARED_BY - 1) / 2);
+    }
+
+    #[test]
+    fn the_record_inventoried_last_is_suggested() {
+        let older = server(4, "gpu-node-01", None, Some("7XK2PL3"), &[]);
+        let newer = server(9, "gpu-node-01", None, Some("7XK2PL3"), &[]);
+
+        let pair = &find_duplicate_servers(&[inventoried(older.clone(), 1), inventoried(newer.clone(), 2)], 0)[0];
+        assert_eq!(pair.suggested_server_id, 9);
+        let pair = &find_duplicate_servers(&[inventoried(older.clone(), 2), inventoried(newer.clone(), 1)], 0)[0];
+        assert_eq!(pair.suggested_server_id, 4);
+        // Never inventoried loses to inventoried
+        let pair = &find_duplicate_servers(&[older.clone(), inventoried(newer.clone(), 1)], 0)[0];
+        assert_eq!(pair.suggested_server_id, 9);
+        // A tie keeps the older record
+        let pair = &find_duplicate_servers(&[newer, older], 0)[0];
+        assert_eq!(pair.suggested_server_id, 4);
+    }
+}
@@ -0,0 +1,302 @@
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use tokio::sync::broadcast;
+
+use crate::repositories::server_repository::RemovedComponent;
+
+// ===================================================================
+// DOMAIN EVENT BUS
+// ===================================================================
+//
+// Handlers publish an event once a change has been committed; subscribers (the SSE feed
+// at /api/v1/events) receive it live. The last `HISTORY` events are kept so a client
+// reconnecting with Last-Event-ID can catch up. Events are in-process only, so IDs start
+// from the startup time: an ID from before a restart is always older than the history,
+// and like any ID the bus no longer holds, the client is told to resync instead.
+
+/// Events kept for replay
+const HISTORY: usize = 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum EventEntity {
+    Server,
+    Vm,
+    K8sCluster,
+}
+
+impl EventEntity {
+    pub const NAMES: &'static [&'static str] = &["server", "vm", "k8s_cluster"];
+
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "server" => Some(EventEntity::Server),
+            "vm" => Some(EventEntity::Vm),
+            "k8s_cluster" => Some(EventEntity::K8sCluster),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema)]
+pub enum EventType {
+    #[serde(rename = "server.created")]
+    ServerCreated,
+    #[serde(rename = "server.updated")]
+    ServerUpdated,
+    #[serde(rename = "server.status_changed")]
+    ServerStatusChanged,
+    #[serde(rename = "server.component_removed")]
+    ServerComponentRemoved,
+    #[serde(rename = "server.power_action")]
+    ServerPowerAction,
+    #[serde(rename = "server.power_action_failed")]
+    ServerPowerActionFailed,
+    #[serde(rename = "server.merged")]
+    ServerMerged,
+    #[serde(rename = "vm.created")]
+    VmCreated,
+    #[serde(rename = "vm.updated")]
+    VmUpdated,
+    #[serde(rename = "k8s_cluster.updated")]
+    K8sClusterUpdated,
+}
+
+impl EventType {
+    pub const ALL: &'static [EventType] = &[
+        EventType::ServerCreated,
+        EventType::ServerUpdated,
+        EventType::ServerStatusChanged,
+        EventType::ServerComponentRemoved,
+        EventType::ServerPowerAction,
+        EventType::ServerPowerActionFailed,
+        EventType::ServerMerged,
+        EventType::VmCreated,
+        EventType::VmUpdated,
+        EventType::K8sClusterUpdated,
+    ];
+    pub const NAMES: &'static [&'static str] = &[
+        "server.created",
+        "server.updated",
+        "server.status_changed",
+        "server.component_removed",
+        "server.power_action",
+        "server.power_action_failed",
+        "server.merged",
+        "vm.created",
+        "vm.updated",
+        "k8s_cluster.updated",
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            EventType::ServerCreated => "server.created",
+            EventType::ServerUpdated => "server.updated",
+            EventType::ServerStatusChanged => "server.status_changed",
+            EventType::ServerComponentRemoved => "server.component_removed",
+            EventType::ServerPowerAction => "server.power_action",
+            EventType::ServerPowerActionFailed => "server.power_action_failed",
+            EventType::ServerMerged => "server.merged",
+            EventType::VmCreated => "vm.created",
+            EventType::VmUpdated => "vm.updated",
+            EventType::K8sClusterUpdated => "k8s_cluster.updated",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Self> {
+        Self::ALL.iter().copied().find(|event_type| event_type.name() == name)
+    }
+
+    pub fn entity(self) -> EventEntity {
+        match self {
+            EventType::ServerCreated
+            | EventType::ServerUpdated
+            | EventType::ServerStatusChanged
+            | EventType::ServerComponentRemoved
+            | EventType::ServerPowerAction
+            | EventType::ServerPowerActionFailed
+            | EventType::ServerMerged => EventEntity::Server,
+            EventType::VmCreated | EventType::VmUpdated => EventEntity::Vm,
+            EventType::K8sClusterUpdated => EventEntity::K8sCluster,
+        }
+    }
+}
+
+/// A committed change, as delivered to subscribers
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct DomainEvent {
+    /// Increases by one per event; the SSE `id:` field
+    pub id: u64,
+    #[serde(rename = "type")]
+    pub event_type: EventType,
+    pub entity: EventEntity,
+    pub entity_id: i32,
+    pub occurred_at: DateTime<Utc>,
+    pub data: Value,
+}
+
+/// An event before the bus has numbered it. The constructors fix the payload of each type.
+#[derive(Debug, Clone)]
+pub struct NewEvent {
+    pub event_type: EventType,
+    pub entity_id: i32,
+    pub data: Value,
+}
+
+impl NewEvent {
+    /// Inventory upsert of a server by the agent
+    pub fn server_inventory(server_id: i32, created: bool, hostname: Option<&str>) -> Self {
+        Self {
+            event_type: if created { EventType::ServerCreated } else { EventType::ServerUpdated },
+            entity_id: server_id,
+            data: json!({ "source": "inventory", "hostname": hostname }),
+        }
+    }
+
+    /// Field update through the API; `fields` are the columns that were sent
+    pub fn server_updated(server_id: i32, fields: Vec<String>) -> Self {
+        Self {
+            event_type: EventType::ServerUpdated,
+            entity_id: server_id,
+            data: json!({ "source": "api", "fields": fields }),
+        }
+    }
+
+    /// `status` column changed through the API
+    pub fn server_status_changed(server_id: i32, from: Option<&str>, to: &str) -> Self {
+        Self {
+            event_type: EventType::ServerStatusChanged,
+            entity_id: server_id,
+            data: json!({ "from": from, "to": to }),
+        }
+    }
+
+    /// Component deleted by an inventory sync because the agent no longer reports it
+    pub fn server_component_removed(server_id: i32, component: &RemovedComponent) -> Self {
+        Self {
+            event_type: EventType::ServerComponentRemoved,
+            entity_id: server_id,
+            data: json!({ "component_type": component.component_type, "identifier": component.identifier }),
+        }
+    }
+
+    /// BMC power action; failures get their own type so they can be alerted on
+    pub fn server_power_action(server_id: i32, action: &str, error: Option<String>) -> Self {
+        Self {
+            event_type: if error.is_none() { EventType::ServerPowerAction } else { EventType::ServerPowerActionFailed },
+            entity_id: server_id,
+            data: json!({ "action": action, "success": error.is_none(), "error": error }),
+        }
+    }
+
+    /// Duplicate records merged into this server and deleted
+    pub fn server_merged(server_id: i32, merged_server_ids: &[i32]) -> Self {
+        Self {
+            event_type: EventType::ServerMerged,
+            entity_id: server_id,
+            data: json!({ "merged_server_ids": merged_server_ids }),
+        }
+    }
+
+    pub fn vm_inventory(vm_id: i32, created: bool, host_mac_address: &str) -> Self {
+        Self {
+            event_type: if created { EventType::VmCreated } else { EventType::VmUpdated },
+            entity_id: vm_id,
+            data: json!({ "source": "inventory", "host_mac_address": host_mac_address }),
+        }
+    }
+
+    pub fn k8s_cluster_inventory(cluster_id: i32, cluster_name: &str, node_count: usize) -> Self {
+        Self {
+            event_type: EventType::K8sClusterUpdated,
+            entity_id: cluster_id,
+            data: json!({ "source": "inventory", "cluster_name": cluster_name, "node_count": node_count }),
+        }
+    }
+}
+
+/// Where a new subscription starts
+pub enum Replay {
+    /// Events after the requested ID, all still in history
+    Events(Vec<Arc<DomainEvent>>),
+    /// The requested ID is older than the history (or from before a restart): events were
+    /// missed and the client must refetch state
+    Gap,
+}
+
+struct Shared {
+    /// ID of the first event published
+    first_id: u64,
+    sender: broadcast::Sender<Arc<DomainEvent>>,
+    history: Mutex<VecDeque<Arc<DomainEvent>>>,
+}
+
+#[derive(Clone)]
+pub struct EventBus {
+    shared: Arc<Shared>,
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EventBus {
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(HISTORY);
+        Self {
+            shared: Arc::new(Shared {
+                first_id: Utc::now().timestamp_millis().max(0) as u64 * 1000,
+                sender,
+                history: Mutex::new(VecDeque::with_capacity(HISTORY)),
+            }),
+        }
+    }
+
+    pub fn publish(&self, event: NewEvent) -> Arc<DomainEvent> {
+        // Numbering, history and send happen under one lock so subscribers see every event
+        // exactly once across replay and the live channel
+        let mut history = self.shared.history.lock().unwrap_or_else(|e| e.into_inner());
+        let id = history.back().map_or(self.shared.first_id, |last| last.id + 1);
+        let event = Arc::new(DomainEvent {
+            id,
+            event_type: event.event_type,
+            entity: event.event_type.entity(),
+            entity_id: event.entity_id,
+            occurred_at: Utc::now(),
+            data: event.data,
+        });
+        if history.len() == HISTORY {
+            history.pop_front();
+        }
+        history.push_back(event.clone());
+        // No receivers is not an error: nobody is listening
+        let _ = self.shared.sender.send(event.clone());
+        event
+    }
+
+    /// Subscribe to new events, first replaying those after `last_event_id`
+    pub fn subscribe(&self, last_event_id: Option<u64>) -> (Replay, broadcast::Receiver<Arc<DomainEvent>>) {
+        let history = self.shared.history.lock().unwrap_or_else(|e| e.into_inner());
+        let receiver = self.shared.sender.subscribe();
+
+        let replay = match last_event_id {
+            None => Replay::Events(Vec::new()),
+            Some(last) => {
+                let newest = history.back().map_or(self.shared.first_id - 1, |event| event.id);
+                let oldest = history.front().map_or(newest + 1, |event| event.id);
+                if last > newest || last + 1 < oldest {
+                    Replay::Gap
+                } else {
+                    Replay::Events(history.iter().filter(|event| event.id > last).cloned().collect())
+                }
+            }
+        };
+        (replay, receiver)
+    }
+}
@@ -0,0 +1,454 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use farm_proto::hardware::ServerInventory;
+
+// ===================================================================
+// SERVER IDENTITY
+// ===================================================================
+//
+// Which server an inventory report belongs to. A machine is recognised by everything it says
+// about itself: SMBIOS system UUID, system, chassis and board serials, BMC MAC and NIC MACs.
+// None of them is trusted alone: NICs get replaced and moved between machines, the primary
+// flag follows the cabling, and cheap boards ship with placeholder serials and UUIDs. Each
+// existing server that shares an identifier with the report is scored, and the report is
+// applied to the best one only when nothing else comes close. A report that only weakly
+// resembles servers it also contradicts comes from another machine and creates a new server.
+
+/// Score at which a report is applied to a server that other servers also matched
+pub const MIN_CONFIDENCE: i32 = 50;
+
+/// How far the best candidate has to be ahead of the next one to be picked
+pub const MIN_MARGIN: i32 = 25;
+
+/// SMBIOS UUIDs firmware vendors leave in place of a real one
+const PLACEHOLDER_UUIDS: &[&str] = &[
+    "00000000-0000-0000-0000-000000000000",
+    "ffffffff-ffff-ffff-ffff-ffffffffffff",
+    "03000200-0400-0500-0006-000700080009",
+];
+
+/// Serial numbers that say nothing about the machine, compared case-insensitively
+const PLACEHOLDER_SERIALS: &[&str] = &[
+    "to be filled by o.e.m.", "default string", "not specified", "not available", "not applicable",
+    "system serial number", "chassis serial number", "base board serial number", "0123456789",
+    "1234567890", "none", "n/a", "na", "unknown", "empty", "0", "00000000", "invalid",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum IdentityKey {
+    SystemUuid,
+    SystemSerial,
+    ChassisSerial,
+    BoardSerial,
+    BmcMac,
+    NicMac,
+}
+
+impl IdentityKey {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            IdentityKey::SystemUuid => "system_uuid",
+            IdentityKey::SystemSerial => "system_serial",
+            IdentityKey::ChassisSerial => "chassis_serial",
+            IdentityKey::BoardSerial => "board_serial",
+            IdentityKey::BmcMac => "bmc_mac",
+            IdentityKey::NicMac => "nic_mac",
+        }
+    }
+
+    /// What a match on this key adds to a candidate's score. NIC MACs score once for the first
+    /// shared MAC; see [`NIC_MAC_EXTRA`].
+    fn weight(self) -> i32 {
+        match self {
+            IdentityKey::SystemUuid => 60,
+            IdentityKey::BmcMac => 40,
+            IdentityKey::SystemSerial | IdentityKey::ChassisSerial | IdentityKey::BoardSerial => 35,
+            IdentityKey::NicMac => 30,
+        }
+    }
+
+    /// What a differing value takes off; None when it rules the candidate out
+    fn mismatch_penalty(self) -> Option<i32> {
+        match self {
+            IdentityKey::SystemUuid => None,
+            IdentityKey::SystemSerial | IdentityKey::ChassisSerial | IdentityKey::BoardSerial => Some(20),
+            IdentityKey::BmcMac => Some(10),
+            // A replaced card is not evidence of another machine
+            IdentityKey::NicMac => Some(0),
+        }
+    }
+}
+
+/// Added for every shared NIC MAC after the first
+const NIC_MAC_EXTRA: i32 = 10;
+
+/// The identifiers of one machine, normalised: lowercase UUID and MACs, trimmed serials,
+/// placeholders dropped
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ServerIdentity {
+    pub system_uuid: Option<String>,
+    pub system_serial: Option<String>,
+    pub chassis_serial: Option<String>,
+    pub board_serial: Option<String>,
+    pub bmc_mac: Option<String>,
+    pub nic_macs: Vec<String>,
+}
+
+impl ServerIdentity {
+    /// Build from raw values as reported or stored; anything unusable is dropped
+    pub fn new(
+        system_uuid: Option<&str>,
+        system_serial: Option<&str>,
+        chassis_serial: Option<&str>,
+        board_serial: Option<&str>,
+        bmc_mac: Option<&str>,
+        nic_macs: impl IntoIterator<Item = impl AsRef<str>>,
+    ) -> Self {
+        let mut nic_macs: Vec<String> = nic_macs.into_iter().filter_map(|mac| normalize_mac(mac.as_ref())).collect();
+        nic_macs.sort();
+        nic_macs.dedup();
+        Self {
+            system_uuid: system_uuid.and_then(normalize_uuid),
+            system_serial: system_serial.and_then(normalize_serial),
+            chassis_serial: chassis_serial.and_then(normalize_serial),
+            board_serial: board_serial.and_then(normalize_serial),
+            bmc_mac: bmc_mac.and_then(normalize_mac),
+            nic_macs,
+        }
+    }
+
+    pub fn from_inventory(inventory: &ServerInventory) -> Self {
+        let node = &inventory.node;
+        Self::new(
+            node.system_uuid.as_deref(),
+            node.serial_number.as_deref(),
+            node.chassis_serial_number.as_deref(),
+            node.motherboard.as_ref().and_then(|mb| mb.serial_number.as_deref()),
+            node.bmc.as_ref().and_then(|bmc| bmc.mac_address.as_deref()),
+            inventory.network.interfaces.iter().filter_map(|iface| iface.mac_address.as_deref()),
+        )
+    }
+
+    /// True when there is nothing to recognise the machine by
+    pub fn is_empty(&self) -> bool {
+        self.claims().next().is_none()
+    }
+
+    /// Every (key, value) pair, NIC MACs one by one
+    pub fn claims(&self) -> impl Iterator<Item = (IdentityKey, &str)> {
+        [
+            (IdentityKey::SystemUuid, &self.system_uuid),
+            (IdentityKey::SystemSerial, &self.system_serial),
+            (IdentityKey::ChassisSerial, &self.chassis_serial),
+            (IdentityKey::BoardSerial, &self.board_serial),
+            (IdentityKey::BmcMac, &self.bmc_mac),
+        ]
+        .into_iter()
+        .filter_map(|(key, value)| value.as_deref().map(|value| (key, value)))
+        .chain(self.nic_macs.iter().map(|mac| (IdentityKey::NicMac, mac.as_str())))
+    }
+
+    fn single(&self, key: IdentityKey) -> Option<&str> {
+        match key {
+            IdentityKey::SystemUuid => self.system_uuid.as_deref(),
+            IdentityKey::SystemSerial => self.system_serial.as_deref(),
+            IdentityKey::ChassisSerial => self.chassis_serial.as_deref(),
+            IdentityKey::BoardSerial => self.board_serial.as_deref(),
+            IdentityKey::BmcMac => self.bmc_mac.as_deref(),
+            IdentityKey::NicMac => None,
+        }
+    }
+}
+
+/// How well an existing server matches a report
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct IdentityCandidate {
+    pub server_id: i32,
+    /// 0-100
+    pub score: i32,
+    /// Keys both sides report with the same value
+    pub matched_by: Vec<IdentityKey>,
+    /// Keys both sides report with different values
+    pub mismatched: Vec<IdentityKey>,
+    /// A differing system UUID: certainly another machine
+    pub excluded: bool,
+}
+
+impl IdentityCandidate {
+    pub fn score(server_id: i32, reported: &ServerIdentity, stored: &ServerIdentity) -> Self {
+        let mut score = 0;
+        let mut matched_by = Vec::new();
+        let mut mismatched = Vec::new();
+        let mut excluded = false;
+
+        for key in [
+            IdentityKey::SystemUuid,
+            IdentityKey::SystemSerial,
+            IdentityKey::ChassisSerial,
+            IdentityKey::BoardSerial,
+            IdentityKey::BmcMac,
+        ] {
+            let (Some(reported), Some(stored)) = (reported.single(key), stored.single(key)) else {
+                continue;
+            };
+            if reported == stored {
+                score += key.weight();
+                matched_by.push(key);
+            } else {
+                match key.mismatch_penalty() {
+                    Some(penalty) => score -= penalty,
+                    None => excluded = true,
+                }
+                mismatched.push(key);
+            }
+        }
+
+        let shared_macs = reported.nic_macs.iter().filter(|mac| stored.nic_macs.contains(mac)).count() as i32;
+        if shared_macs > 0 {
+            score += IdentityKey::NicMac.weight() + NIC_MAC_EXTRA * (shared_macs - 1);
+            matched_by.push(IdentityKey::NicMac);
+        }
+
+        Self { server_id, score: score.clamp(0, 100), matched_by, mismatched, excluded }
+    }
+
+    /// Matched on something and contradicted by nothing
+    fn is_clean(&self) -> bool {
+        !self.excluded && self.mismatched.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum IdentityResolution {
+    /// Applied to an existing server
+    Matched,
+    /// A new server was created
+    Created,
+    /// Not applied: several servers match about as well, or the best match is too weak to trust
+    Rejected,
+}
+
+impl IdentityResolution {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            IdentityResolution::Matched => "MATCHED",
+            IdentityResolution::Created => "CREATED",
+            IdentityResolution::Rejected => "REJECTED",
+        }
+    }
+}
+
+/// What to do with a report, given every server that shares an identifier with it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IdentityDecision {
+    pub resolution: IdentityResolution,
+    /// The matched server; None when creating or rejecting
+    pub server_id: Option<i32>,
+    /// Score of the best-matching existing server, 0 when there is none
+    pub confidence: i32,
+    pub matched_by: Vec<IdentityKey>,
+    /// Best first, those ruled out by their system UUID last. Not empty when the decision
+    /// should be recorded as a conflict.
+    pub conflicting: Vec<IdentityCandidate>,
+}
+
+/// Pick the server a report belongs to. A single uncontradicted candidate is taken whatever
+/// it matched on, so servers known only by a NIC MAC keep being recognised; anything else is
+/// decided on score and recorded as a conflict.
+pub fn resolve_identity(mut candidates: Vec<IdentityCandidate>) -> IdentityDecision {
+    candidates.sort_by(|a, b| a.excluded.cmp(&b.excluded).then(b.score.cmp(&a.score)).then(a.server_id.cmp(&b.server_id)));
+
+    let eligible: Vec<&IdentityCandidate> = candidates.iter().filter(|c| !c.excluded).collect();
+    let confidence = eligible.first().map_or(0, |c| c.score);
+
+    let (resolution, matched) = match eligible.as_slice() {
+        // Nothing known, or only servers with another system UUID, e.g. one a NIC was moved from
+        [] => (IdentityResolution::Created, None),
+        [only] if only.is_clean() && candidates.len() == 1 => {
+            return IdentityDecision {
+                resolution: IdentityResolution::Matched,
+                server_id: Some(only.server_id),
+                confidence,
+                matched_by: only.matched_by.clone(),
+                conflicting: Vec::new(),
+            };
+        }
+        // Every server it resembles is contradicted by a serial or BMC MAC, and none strongly:
+        // another machine sharing a part with them, or a board serial used twice
+        [best, ..] if best.score < MIN_CONFIDENCE && eligible.iter().all(|c| !c.mismatched.is_empty()) => {
+            (IdentityResolution::Created, None)
+        }
+        [best, rest @ ..] => {
+            let runner_up = rest.first().map_or(0, |c| c.score);
+            if best.score >= MIN_CONFIDENCE && best.score - runner_up >= MIN_MARGIN {
+                (IdentityResolution::Matched, Some((best.server_id, best.matched_by.clone())))
+            } else {
+                (IdentityResolution::Rejected, None)
+            }
+        }
+    };
+
+    let (server_id, matched_by) = matched.unzip();
+    IdentityDecision {
+        resolution,
+        server_id,
+        confidence,
+        matched_by: matched_by.unwrap_or_default(),
+        conflicting: candidates,
+    }
+}
+
+fn normalize_uuid(uuid: &str) -> Option<String> {
+    let uuid = uuid.trim().to_ascii_lowercase();
+    let hex_digits = uuid.chars().filter(|c| c.is_ascii_hexdigit()).count();
+    if hex_digits != 32 || uuid.chars().any(|c| !c.is_ascii_hexdigit() && c != '-') || PLACEHOLDER_UUIDS.contains(&uuid.as_str()) {
+        return None;
+    }
+    Some(uuid)
+}
+
+fn normalize_serial(serial: &str) -> Option<String> {
+    let serial = serial.trim();
+    let lower = serial.to_ascii_lowercase();
+    if serial.is_empty() || PLACEHOLDER_SERIALS.contains(&lower.as_str()) || serial.chars().all(|c| c == '0' || c == 'X' || c == 'x') {
+        return None;
+    }
+    Some(serial.to_string())
+}
+
+/// A MAC that names one interface: not all zeros, not multicast and not locally administered
+/// (those are made up by software, e.g. bonds, bridges and VFs)
+fn normalize_mac(mac: &str) -> Option<String> {
+    let mac = mac.trim().to_ascii_lowercase().replace('-', ":");
+    let octets: Vec<u8> = mac.split(':').map(|octet| u8::from_str_radix(octet, 16)).collect::<Result<_, _>>().ok()?;
+    if octets.len() != 6 || octets.iter().all(|&b| b == 0) || octets[0] & 0b11 != 0 {
+        return None;
+    }
+    Some(octets.iter().map(|b| format!("{:02x}", b)).collect::<Vec<_>>().join(":"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const UUID: &str = "4c4c4544-0042-3510-8056-b7c04f4e3332";
+    const OTHER_UUID: &str = "5b1e2c3d-4f5a-4b6c-8d7e-9f0a1b2c3d4e";
+
+    fn identity(system_uuid: Option<&str>, system_serial: Option<&str>, bmc_mac: Option<&str>, nic_macs: &[&str]) -> ServerIdentity {
+        ServerIdentity::new(system_uuid, system_serial, None, None, bmc_mac, nic_macs)
+    }
+
+    #[test]
+    fn a_single_clean_match_is_applied() {
+        let reported = identity(Some(UUID), Some("CN7475139K0123"), None, &["3c:ec:ef:10:00:01"]);
+        let candidate = IdentityCandidate::score(7, &reported, &reported.clone());
+        assert_eq!(candidate.score, 100);
+        assert_eq!(candidate.matched_by, [IdentityKey::SystemUuid, IdentityKey::SystemSerial, IdentityKey::NicMac]);
+
+        // Known only by a NIC MAC, and still recognised
+        let stored = identity(None, None, None, &["3c:ec:ef:10:00:01"]);
+        let decision = resolve_identity(vec![IdentityCandidate::score(7, &reported, &stored)]);
+        assert_eq!(decision.resolution, IdentityResolution::Matched);
+        assert_eq!(decision.server_id, Some(7));
+        assert_eq!(decision.confidence, 30);
+        assert!(decision.conflicting.is_empty());
+    }
+
+    #[test]
+    fn another_system_uuid_rules_a_server_out() {
+        // A NIC moved from server 3 into another machine
+        let reported = identity(Some(UUID), None, None, &["3c:ec:ef:10:00:01", "3c:ec:ef:10:00:02"]);
+        let stored = identity(Some(OTHER_UUID), None, None, &["3c:ec:ef:10:00:01"]);
+        let candidate = IdentityCandidate::score(3, &reported, &stored);
+        assert!(candidate.excluded);
+        assert_eq!(candidate.mismatched, [IdentityKey::SystemUuid]);
+
+        let decision = resolve_identity(vec![candidate]);
+        assert_eq!(decision.resolution, IdentityResolution::Created);
+        assert_eq!(decision.server_id, None);
+        assert_eq!(decision.confidence, 0);
+        assert_eq!(decision.conflicting.len(), 1, "the exclusion is recorded");
+    }
+
+    #[test]
+    fn two_close_candidates_are_rejected() {
+        let reported = identity(None, Some("CN7475139K0123"), Some("3c:ec:ef:20:00:01"), &["3c:ec:ef:10:00:01", "3c:ec:ef:10:00:02"]);
+        let by_serial = identity(None, Some("CN7475139K0123"), None, &["3c:ec:ef:10:00:01"]);
+        let by_bmc = identity(None, None, Some("3c:ec:ef:20:00:01"), &["3c:ec:ef:10:00:02"]);
+
+        let decision = resolve_identity(vec![
+            IdentityCandidate::score(1, &reported, &by_serial),
+            IdentityCandidate::score(2, &reported, &by_bmc),
+        ]);
+        assert_eq!(decision.resolution, IdentityResolution::Rejected);
+        assert_eq!(decision.server_id, None);
+        assert_eq!(decision.confidence, 70);
+        let order: Vec<i32> = decision.conflicting.iter().map(|c| c.server_id).collect();
+        assert_eq!(order, [2, 1], "best first");
+    }
+
+    #[test]
+    fn a_clear_leader_among_several_is_matched() {
+        let reported = identity(Some(UUID), Some("CN7475139K0123"), None, &["3c:ec:ef:10:00:01"]);
+        let same = identity(Some(UUID), Some("CN7475139K0123"), None, &[]);
+        let nic_donor = identity(None, None, None, &["3c:ec:ef:10:00:01"]);
+
+        let decision = resolve_identity(vec![
+            IdentityCandidate::score(2, &reported, &nic_donor),
+            IdentityCandidate::score(1, &reported, &same),
+        ]);
+        assert_eq!(decision.resolution, IdentityResolution::Matched);
+        assert_eq!(decision.server_id, Some(1));
+        assert_eq!(decision.matched_by, [IdentityKey::SystemUuid, IdentityKey::SystemSerial]);
+        assert_eq!(decision.conflicting.len(), 2);
+    }
+
+    #[test]
+    fn a_weak_match_contradicting_the_stored_server_creates_one() {
+        // Shares a NIC MAC, but reports another serial number
+        let reported = identity(None, Some("CN7475139K0456"), None, &["3c:ec:ef:10:00:01"]);
+        let stored = identity(None, Some("CN7475139K0123"), None, &["3c:ec:ef:10:00:01"]);
+        let candidate = IdentityCandidate::score(5, &reported, &stored);
+        assert_eq!(candidate.score, 10);
+        assert_eq!(candidate.mismatched, [IdentityKey::SystemSerial]);
+        assert!(!candidate.excluded);
+
+        let decision = resolve_identity(vec![candidate]);
+        assert_eq!(decision.resolution, IdentityResolution::Created);
+        assert_eq!(decision.server_id, None);
+        assert_eq!(decision.confidence, 10);
+        assert_eq!(decision.conflicting.len(), 1);
+    }
+
+    #[test]
+    fn placeholder_serials_and_uuids_are_dropped() {
+        let identity = ServerIdentity::new(
+            Some("03000200-0400-0500-0006-000700080009"),
+            Some(" To Be Filled By O.E.M. "),
+            Some("  CZJ1234ABC "),
+            Some("XXXXXXXX"),
+            Some("02:00:00:00:00:01"), // locally administered
+            ["3C-EC-EF-10-00-01", "3c:ec:ef:10:00:01", "01:00:5e:00:00:01", "00:00:00:00:00:00"],
+        );
+        assert_eq!(identity, ServerIdentity {
+            chassis_serial: Some("CZJ1234ABC".to_string()),
+            nic_macs: vec!["3c:ec:ef:10:00:01".to_string()],
+            ..ServerIdentity::default()
+        });
+        assert!(ServerIdentity::new(None, Some("0"), None, None, None, ["not-a-mac"]).is_empty());
+        assert_eq!(
+            ServerIdentity::new(Some(" 4C4C4544-0042-3510-8056-B7C04F4E3332"), None, None, None, None, Vec::<&str>::new()).system_uuid.as_deref(),
+            Some(UUID)
+        );
+
+        // A placeholder serial neither matches nor contradicts a real one
+        let reported = ServerIdentity::new(None, Some("Default string"), None, None, None, ["3c:ec:ef:10:00:01"]);
+        let stored = ServerIdentity::new(None, Some("CN7475139K0123"), None, None, None, ["3c:ec:ef:10:00:01"]);
+        let candidate = IdentityCandidate::score(9, &reported, &stored);
+        assert!(candidate.mismatched.is_empty());
+        assert_eq!(candidate.matched_by, [IdentityKey::NicMac]);
+    }
+}
@@ -0,0 +1,348 @@
+use serde_json::{Map, Value};
+use std::collections::{HashMap, HashSet};
+
+use crate::models::{InventoryChangeType, InventoryDiffEntry};
+
+// ===================================================================
+// INVENTORY DIFF
+// ===================================================================
+//
+// Structural comparison of two inventory payloads. Objects are compared key by key; lists of
+// components are matched up on an identifying field (GPU UUID, serial, MAC, socket, slot...)
+// so removing one DIMM reports that DIMM rather than every entry after it. Lists without a
+// usable identity are compared by position.
+
+/// Fields tried, in order, to match list entries between two payloads. A field is used when
+/// every entry on both sides has it and no two entries on one side share it.
+const IDENTITY_FIELDS: &[&str] = &[
+    "uuid", "serial", "serial_number", "mac_address", "socket", "slot", "pci_address", "name", "address", "destination",
+];
+
+/// Every difference between `old` and `new`, in the order of `old` with additions after
+pub fn diff_inventory(old: &Value, new: &Value) -> Vec<InventoryDiffEntry> {
+    let mut changes = Vec::new();
+    diff_value("", old, new, &mut changes);
+    changes
+}
+
+fn diff_value(path: &str, old: &Value, new: &Value, changes: &mut Vec<InventoryDiffEntry>) {
+    match (old, new) {
+        (Value::Object(old_map), Value::Object(new_map)) => diff_object(path, old_map, new_map, changes),
+        (Value::Array(old_list), Value::Array(new_list)) => diff_list(path, old_list, new_list, changes),
+        _ if old != new => changes.push(entry(path, InventoryChangeType::Modified, Some(old), Some(new))),
+        _ => {}
+    }
+}
+
+fn diff_object(path: &str, old: &Map<String, Value>, new: &Map<String, Value>, changes: &mut Vec<InventoryDiffEntry>) {
+    for (key, old_value) in old {
+        let child = child_path(path, key);
+        match new.get(key) {
+            Some(new_value) => diff_value(&child, old_value, new_value, changes),
+            None => changes.push(entry(&child, InventoryChangeType::Removed, Some(old_value), None)),
+        }
+    }
+    for (key, new_value) in new {
+        if !old.contains_key(key) {
+            changes.push(entry(&child_path(path, key), InventoryChangeType::Added, None, Some(new_value)));
+        }
+    }
+}
+
+fn diff_list(path: &str, old: &[Value], new: &[Value], changes: &mut Vec<InventoryDiffEntry>) {
+    let Some(field) = IDENTITY_FIELDS.iter().copied().find(|field| identifies(field, old) && identifies(field, new)) else {
+        for index in 0..old.len().max(new.len()) {
+            let item = format!("{}[{}]", path, index);
+            match (old.get(index), new.get(index)) {
+                (Some(old_value), Some(new_value)) => diff_value(&item, old_value, new_value, changes),
+                (Some(old_value), None) => changes.push(entry(&item, InventoryChangeType::Removed, Some(old_value), None)),
+                (None, Some(new_value)) => changes.push(entry(&item, InventoryChangeType::Added, None, Some(new_value))),
+                (None, None) => {}
+            }
+        }
+        return;
+    };
+
+    let find = |list: &'_ [Value], key: &str| list.iter().find(|value| identity(value, field).as_deref() == Some(key)).cloned();
+    for old_value in old {
+        let key = identity(old_value, field).unwrap_or_default();
+        let item = format!("{}[{}={}]", path, field, key);
+        match find(new, &key) {
+            Some(new_value) => diff_value(&item, old_value, &new_value, changes),
+            None => changes.push(entry(&item, InventoryChangeType::Removed, Some(old_value), None)),
+        }
+    }
+    for new_value in new {
+        let key = identity(new_value, field).unwrap_or_default();
+        if find(old, &key).is_none() {
+            let item = format!("{}[{}={}]", path, field, key);
+            changes.push(entry(&item, InventoryChangeType::Added, None, Some(new_value)));
+        }
+    }
+}
+
+/// The entry's `field` as a path label, for scalar non-null values
+fn identity(value: &Value, field: &str) -> Option<String> {
+    match value.get(field)? {
+        Value::String(s) if !s.is_empty() => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+fn identifies(field: &str, list: &[Value]) -> bool {
+    let mut seen = HashSet::new();
+    list.iter().all(|value| identity(value, field).is_some_and(|key| seen.insert(key)))
+}
+
+fn child_path(path: &str, key: &str) -> String {
+    if path.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", path, key)
+    }
+}
+
+fn entry(path: &str, change: InventoryChangeType, old: Option<&Value>, new: Option<&Value>) -> InventoryDiffEntry {
+    InventoryDiffEntry {
+        path: path.to_string(),
+        change,
+        old: old.cloned(),
+        new: new.cloned(),
+    }
+}
+
+// ===================================================================
+// MISSING COMPONENTS
+// ===================================================================
+//
+// A part the sync removes is recorded as missing until a later report shows it again. The
+// part counts as back when any of its identifying values (serial, slot, PCI address, UUID,
+// MAC) is reported for the same component type, so a DIMM reseated in another slot or a
+// replacement card in the same PCI slot resolves the entry.
+
+/// Component types tracked in server_missing_components
+pub const MISSING_COMPONENT_TYPES: &[&str] = &["cpu", "memory", "disk", "network", "gpu", "power"];
+
+/// Fields that identify a physical part wherever it is reported
+const PART_FIELDS: &[&str] = &["serial", "serial_number", "uuid", "slot", "pci_address", "mac_address"];
+
+/// Each part an inventory payload reports, as (component type, identifier, entry). The
+/// identifiers are the ones the inventory sync matches components on.
+pub fn reported_parts(inventory: &Value) -> Vec<(&'static str, String, &Value)> {
+    let list = |path: &[&str]| {
+        path.iter()
+            .try_fold(inventory, |value, key| value.get(key))
+            .and_then(Value::as_array)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    };
+    let text = |entry: &Value, field: &str| identity(entry, field);
+
+    let mut parts = Vec::new();
+    for cpu in list(&["cpu", "cpus"]) {
+        if let Some(socket) = text(cpu, "socket") {
+            parts.push(("cpu", format!("socket {}", socket), cpu));
+        }
+    }
+    for dimm in list(&["memory", "dimms"]) {
+        if let Some(slot) = text(dimm, "slot").or_else(|| text(dimm, "serial_number")) {
+            parts.push(("memory", slot, dimm));
+        }
+    }
+    for disk in list(&["disks"]) {
+        if let Some(key) = text(disk, "serial").or_else(|| text(disk, "name")) {
+            parts.push(("disk", key, disk));
+        }
+    }
+    for iface in list(&["network", "interfaces"]) {
+        if let Some(mac) = text(iface, "mac_address") {
+            parts.push(("network", mac.to_lowercase(), iface));
+        }
+    }
+    for gpu in list(&["gpus"]) {
+        if let Some(key) = text(gpu, "uuid").or_else(|| text(gpu, "pci_address")) {
+            parts.push(("gpu", key, gpu));
+        }
+    }
+    for psu in list(&["power_supplies"]) {
+        if let Some(key) = text(psu, "serial_number").or_else(|| text(psu, "name")) {
+            parts.push(("power", key, psu));
+        }
+    }
+    parts
+}
+
+/// The identifier and identifying field values of one part, lowercased
+pub fn part_keys(identifier: &str, data: &Value) -> HashSet<String> {
+    PART_FIELDS.iter()
+        .filter_map(|field| identity(data, field))
+        .chain(std::iter::once(identifier.to_string()))
+        .map(|key| key.to_lowercase())
+        .collect()
+}
+
+/// Keys of the parts an inventory reports, per component type. A value reported by more than
+/// one part (a placeholder serial such as "Unknown") identifies nothing and is left out.
+pub fn reported_part_keys(inventory: &Value) -> HashMap<&'static str, HashSet<String>> {
+    let mut counts: HashMap<(&'static str, String), usize> = HashMap::new();
+    for (component_type, identifier, entry) in reported_parts(inventory) {
+        for key in part_keys(&identifier, entry) {
+            *counts.entry((component_type, key)).or_default() += 1;
+        }
+    }
+
+    let mut keys: HashMap<&'static str, HashSet<String>> = HashMap::new();
+    for ((component_type, key), count) in counts {
+        if count == 1 {
+            keys.entry(component_type).or_default().insert(key);
+        }
+    }
+    keys
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use InventoryChangeType::{Added, Modified, Removed};
+
+    fn changes(old: Value, new: Value) -> Vec<(String, InventoryChangeType)> {
+        diff_inventory(&old, &new).into_iter().map(|entry| (entry.path, entry.change)).collect()
+    }
+
+    fn dimm(slot: &str, serial_number: &str) -> Value {
+        json!({ "slot": slot, "serial_number": serial_number, "size_bytes": 34359738368u64 })
+    }
+
+    #[test]
+    fn objects_are_compared_key_by_key() {
+        let old = json!({ "node": { "hostname": "gpu-node-01", "kernel": "6.8.0" }, "agent_version": "1.4.0" });
+        let new = json!({ "node": { "hostname": "gpu-node-01", "os": "ubuntu" }, "agent_version": "1.5.0" });
+        assert_eq!(changes(old.clone(), new), [
+            ("agent_version".to_string(), Modified),
+            ("node.kernel".to_string(), Removed),
+            ("node.os".to_string(), Added),
+        ]);
+        assert!(diff_inventory(&old, &old).is_empty());
+
+        let entry = &diff_inventory(&json!({ "agent_version": "1.4.0" }), &json!({ "agent_version": "1.5.0" }))[0];
+        assert_eq!((entry.old.as_ref(), entry.new.as_ref()), (Some(&json!("1.4.0")), Some(&json!("1.5.0"))));
+    }
+
+    #[test]
+    fn removing_one_dimm_reports_only_that_dimm() {
+        let old = json!({ "memory": { "dimms": [dimm("A1", "S1"), dimm("A2", "S2"), dimm("A3", "S3")] } });
+        let new = json!({ "memory": { "dimms": [dimm("A1", "S1"), dimm("A3", "S3")] } });
+        assert_eq!(changes(old, new), [("memory.dimms[serial_number=S2]".to_string(), Removed)]);
+    }
+
+    #[test]
+    fn keyed_entries_are_matched_whatever_their_order() {
+        let old = json!({ "gpus": [{ "uuid": "GPU-1", "temperature_c": 40 }, { "uuid": "GPU-2", "temperature_c": 41 }] });
+        let new = json!({ "gpus": [
+            { "uuid": "GPU-3", "temperature_c": 38 },
+            { "uuid": "GPU-2", "temperature_c": 41 },
+            { "uuid": "GPU-1", "temperature_c": 55 },
+        ] });
+        assert_eq!(changes(old, new), [
+            ("gpus[uuid=GPU-1].temperature_c".to_string(), Modified),
+            ("gpus[uuid=GPU-3]".to_string(), Added),
+        ]);
+    }
+
+    #[test]
+    fn the_first_field_that_identifies_both_sides_is_used() {
+        // Serials are repeated on the new side, so DIMMs are matched on their slot instead
+        let old = json!([dimm("A1", "S1"), dimm("A2", "S2")]);
+        let new = json!([dimm("A1", "Unknown"), dimm("A2", "Unknown")]);
+        assert_eq!(changes(old, new), [
+            ("[slot=A1].serial_number".to_string(), Modified),
+            ("[slot=A2].serial_number".to_string(), Modified),
+        ]);
+
+        assert!(identifies("slot", &[dimm("A1", "S1"), dimm("A2", "S1")]));
+        assert!(!identifies("serial_number", &[dimm("A1", "S1"), dimm("A2", "S1")]));
+        assert!(!identifies("slot", &[dimm("A1", "S1"), json!({ "slot": "" })]));
+        assert!(!identifies("slot", &[dimm("A1", "S1"), json!({ "slot": null })]));
+    }
+
+    #[test]
+    fn lists_without_an_identity_are_compared_by_position() {
+        let old = json!({ "dns": { "search": ["lab.example.com", "example.com", "corp"] } });
+        let new = json!({ "dns": { "search": ["lab.example.com", "corp"] } });
+        assert_eq!(changes(old, new), [
+            ("dns.search[1]".to_string(), Modified),
+            ("dns.search[2]".to_string(), Removed),
+        ]);
+
+        let old = json!({ "dns": { "search": ["lab.example.com"] } });
+        let new = json!({ "dns": { "search": ["lab.example.com", "corp"] } });
+        assert_eq!(changes(old, new), [("dns.search[1]".to_string(), Added)]);
+    }
+
+    fn keys(values: &[&str]) -> HashSet<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    #[test]
+    fn parts_are_identified_as_the_sync_matches_them() {
+        let inventory = json!({
+            "cpu": { "cpus": [{ "socket": 0 }, { "model": "no socket" }] },
+            "memory": { "dimms": [dimm("A1", "S1"), { "slot": null, "serial_number": "S2" }] },
+            "disks": [{ "name": "nvme0n1", "serial": "PHLJ1234" }, { "name": "sda" }],
+            "network": { "interfaces": [{ "name": "eno1", "mac_address": "B4:96:91:AA:10:01" }, { "name": "lo" }] },
+            "gpus": [{ "uuid": "GPU-1", "pci_address": "0000:17:00.0" }, { "pci_address": "0000:65:00.0" }],
+            "power_supplies": [{ "name": "PSU1", "serial_number": "P1" }, { "name": "PSU2" }],
+        });
+        let parts: Vec<(&str, String)> = reported_parts(&inventory).into_iter()
+            .map(|(component_type, identifier, _)| (component_type, identifier))
+            .collect();
+        assert_eq!(parts, [
+            ("cpu", "socket 0".to_string()),
+            ("memory", "A1".to_string()),
+            ("memory", "S2".to_string()),
+            ("disk", "PHLJ1234".to_string()),
+            ("disk", "sda".to_string()),
+            ("network", "b4:96:91:aa:10:01".to_string()),
+            ("gpu", "GPU-1".to_string()),
+            ("gpu", "0000:65:00.0".to_string()),
+            ("power", "P1".to_string()),
+            ("power", "PSU2".to_string()),
+        ]);
+
+        assert_eq!(part_keys("A1", &dimm("A1", "S1")), keys(&["a1", "s1"]));
+        assert_eq!(part_keys("GPU-1", &inventory["gpus"][0]), keys(&["gpu-1", "0000:17:00.0"]));
+        assert_eq!(part_keys("b4:96:91:aa:10:01", &inventory["network"]["interfaces"][0]), keys(&["b4:96:91:aa:10:01"]));
+    }
+
+    #[test]
+    fn a_part_is_reported_by_its_serial_slot_or_pci_address() {
+        let reported = reported_part_keys(&json!({
+            "memory": { "dimms": [dimm("B2", "S1")] },
+            "gpus": [{ "uuid": "GPU-9", "pci_address": "0000:17:00.0" }],
+        }));
+        let is_reported = |component_type: &str, identifier: &str, last_seen: Value| {
+            reported.get(component_type).is_some_and(|reported| !reported.is_disjoint(&part_keys(identifier, &last_seen)))
+        };
+
+        // A DIMM reseated in another slot is the same DIMM: moved, not missing
+        assert!(is_reported("memory", "A1", dimm("A1", "S1")));
+        // A new DIMM in the slot of one that went missing
+        assert!(is_reported("memory", "B2", dimm("B2", "S7")));
+        assert!(!is_reported("memory", "A3", dimm("A3", "S3")));
+        // A replacement card in the same PCI slot
+        assert!(is_reported("gpu", "GPU-1", json!({ "uuid": "GPU-1", "pci_address": "0000:17:00.0" })));
+        assert!(!is_reported("gpu", "GPU-2", json!({ "uuid": "GPU-2", "pci_address": "0000:65:00.0" })));
+        // Keys only count for their own component type
+        assert!(!is_reported("disk", "S1", json!({ "serial": "S1" })));
+    }
+
+    #[test]
+    fn a_value_reported_by_several_parts_identifies_none_of_them() {
+        let reported = reported_part_keys(&json!({
+            "memory": { "dimms": [dimm("A1", "Unknown"), dimm("A2", "Unknown")] },
+        }));
+        assert_eq!(reported["memory"], keys(&["a1", "a2"]));
+    }
+}
@@ -1,3 +1,17 @@
+pub mod audit;
 pub mod bmc;
+pub mod duplicates;
+pub mod events;
+pub mod identity;
+pub mod inventory;
+pub mod topology;
+pub mod webhooks;
 
+pub use audit::{AuditAction, AuditRecorder, RowChange};
 pub use bmc::{RedfishClient, RedfishError};
+pub use duplicates::{find_duplicate_servers, DuplicateServers, ServerFingerprint};
+pub use events::{DomainEvent, EventBus, EventEntity, EventType, NewEvent, Replay};
+pub use identity::{resolve_identity, IdentityCandidate, IdentityDecision, IdentityKey, IdentityResolution, ServerIdentity};
+pub use inventory::{diff_inventory, part_keys, reported_part_keys, reported_parts, MISSING_COMPONENT_TYPES};
+pub use topology::{TopologyFailure, TopologyPlanner, TopologyService};
+pub use webhooks::{WebhookDispatcher, WebhookOutbox};
@@ -0,0 +1,872 @@
+use async_trait::async_trait;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+use sha2::{Digest, Sha256};
+use chrono::{DateTime, NaiveDateTime, Utc};
+use sqlx::database::HasArguments;
+use sqlx::{Acquire, ColumnIndex, Decode, Encode, Executor, FromRow, IntoArguments, MySql, Pool, Type};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use crate::database::{DatabaseHelper, DecimalColumn, SqlDialect};
+use crate::domain::{AuditAction, AuditRecorder};
+use crate::models::{
+    ColumnType, Datacenter, DatacenterRack, FieldDrift, PlanAction, PlanSummary, PlannedChange,
+    ServerCluster, ServerSubCluster, Switch, SwitchVlan, TopologyApplyResult, TopologyDocument,
+    TopologyError, TopologyKind, TopologyPlan, TopologyReference,
+};
+use crate::repositories::{ClusterRepository, DatacenterRepository, SwitchRepository};
+
+// ===================================================================
+// TOPOLOGY PLAN / APPLY
+// ===================================================================
+//
+// Compares a desired-state document (see models::topology) with the datacenter, cluster and
+// switch tables and works out the creates, updates and deletes that reconcile them. `plan`
+// only reads; `apply` re-plans inside a transaction and runs the changes through the same
+// inserts the repositories use, so a failing statement leaves the database untouched.
+
+/// Why a topology document could not be planned or applied
+#[derive(Debug)]
+pub enum TopologyFailure {
+    /// The document is invalid; nothing was written
+    Invalid(Vec<TopologyError>),
+    /// `apply` was pinned to a fingerprint the current plan no longer matches
+    Stale(TopologyPlan),
+    Database(sqlx::Error),
+}
+
+impl From<sqlx::Error> for TopologyFailure {
+    fn from(e: sqlx::Error) -> Self {
+        TopologyFailure::Database(e)
+    }
+}
+
+type Address = (TopologyKind, String);
+
+/// A resource as the document describes it or as the database holds it. `fields` holds
+/// managed column values and reference codes; rows read from the database fill in every
+/// managed column so whatever the document sets can be compared.
+#[derive(Debug, Clone)]
+struct Resource {
+    kind: TopologyKind,
+    path: String,
+    parent: Option<String>,
+    id: Option<i32>,
+    fields: Map<String, Value>,
+}
+
+impl Resource {
+    /// Path of the resource a reference field points at, e.g. `AMS1/R01` for a switch's rack
+    fn target(&self, reference: &TopologyReference) -> Result<Option<String>, String> {
+        let code = match self.fields.get(reference.field) {
+            Some(Value::String(code)) => code,
+            _ => return Ok(None),
+        };
+        match reference.scope {
+            None => Ok(Some(code.clone())),
+            Some(scope) => match self.fields.get(scope) {
+                Some(Value::String(parent)) => Ok(Some(format!("{}/{}", parent, code))),
+                _ => Err(format!("{} needs {} to identify its parent", reference.field, scope)),
+            },
+        }
+    }
+}
+
+#[derive(Default)]
+struct DesiredState {
+    resources: BTreeMap<Address, Resource>,
+    /// Lists the document gives in full, as (kind, parent path)
+    owned: BTreeSet<(TopologyKind, Option<String>)>,
+    errors: Vec<TopologyError>,
+}
+
+impl DesiredState {
+    fn from_document(document: TopologyDocument) -> Result<Self, Vec<TopologyError>> {
+        let mut desired = DesiredState::default();
+
+        if let Some(datacenters) = document.datacenters {
+            desired.owned.insert((TopologyKind::Datacenter, None));
+            for (index, datacenter) in datacenters.into_iter().enumerate() {
+                let path = desired.add(TopologyKind::Datacenter, None, index, datacenter.fields);
+                if let (Some(path), Some(racks)) = (path, datacenter.racks) {
+                    desired.add_children(TopologyKind::Rack, &path, racks);
+                }
+            }
+        }
+        if let Some(clusters) = document.clusters {
+            desired.owned.insert((TopologyKind::Cluster, None));
+            for (index, cluster) in clusters.into_iter().enumerate() {
+                let path = desired.add(TopologyKind::Cluster, None, index, cluster.fields);
+                if let (Some(path), Some(sub_clusters)) = (path, cluster.sub_clusters) {
+                    desired.add_children(TopologyKind::SubCluster, &path, sub_clusters);
+                }
+            }
+        }
+        if let Some(switches) = document.switches {
+            desired.owned.insert((TopologyKind::Switch, None));
+            for (index, switch) in switches.into_iter().enumerate() {
+                let path = desired.add(TopologyKind::Switch, None, index, switch.fields);
+                if let (Some(path), Some(vlans)) = (path, switch.vlans) {
+                    desired.add_children(TopologyKind::Vlan, &path, vlans);
+                }
+            }
+        }
+
+        if desired.errors.is_empty() {
+            Ok(desired)
+        } else {
+            Err(desired.errors)
+        }
+    }
+
+    fn add_children(&mut self, kind: TopologyKind, parent: &str, children: Vec<Map<String, Value>>) {
+        self.owned.insert((kind, Some(parent.to_string())));
+        for (index, child) in children.into_iter().enumerate() {
+            self.add(kind, Some(parent), index, child);
+        }
+    }
+
+    /// Type-check one document entry and record it under its path
+    fn add(&mut self, kind: TopologyKind, parent: Option<&str>, index: usize, raw: Map<String, Value>) -> Option<String> {
+        let columns = kind.columns();
+        let identity = columns.iter().find(|column| column.name == kind.identity())?;
+        let name = match raw.get(identity.name).cloned().map(|value| identity.json_value(value)) {
+            Some(Ok(Value::String(name))) => name,
+            Some(Ok(Value::Number(name))) => name.to_string(),
+            Some(Err(message)) => {
+                self.error(kind, &entry_position(parent, index), Some(identity.name), message);
+                return None;
+            }
+            _ => {
+                self.error(kind, &entry_position(parent, index), Some(identity.name), format!("{} is required", identity.name));
+                return None;
+            }
+        };
+        let path = match parent {
+            Some(parent) => format!("{}/{}", parent, name),
+            None => name,
+        };
+
+        let mut resource = Resource {
+            kind,
+            path: path.clone(),
+            parent: parent.map(str::to_string),
+            id: None,
+            fields: Map::new(),
+        };
+        for (field, value) in raw {
+            let converted = if let Some(column) = columns.iter().find(|column| column.name == field) {
+                column.json_value(value)
+            } else if kind.references().iter().any(|reference| reference.field == field) {
+                match value {
+                    Value::Null => Ok(Value::Null),
+                    Value::String(code) => Ok(Value::String(code)),
+                    Value::Number(code) => Ok(Value::String(code.to_string())),
+                    other => Err(format!("{} is not a code", other)),
+                }
+            } else {
+                Err(format!("Unknown field '{}' for a {}", field, kind.name()))
+            };
+            match converted {
+                Ok(value) => {
+                    resource.fields.insert(field, value);
+                }
+                Err(message) => self.error(kind, &path, Some(&field), message),
+            }
+        }
+
+        if self.resources.insert((kind, path.clone()), resource).is_some() {
+            self.error(kind, &path, Some(kind.identity()), format!("{} '{}' is listed more than once", kind.name(), path));
+        }
+        Some(path)
+    }
+
+    fn error(&mut self, kind: TopologyKind, address: &str, field: Option<&str>, message: impl Into<String>) {
+        self.errors.push(topology_error(kind, address, field, message));
+    }
+}
+
+/// Where an entry without a usable identity sits in its list, e.g. `AMS1/#3`
+fn entry_position(parent: Option<&str>, index: usize) -> String {
+    match parent {
+        Some(parent) => format!("{}/#{}", parent, index + 1),
+        None => format!("#{}", index + 1),
+    }
+}
+
+fn topology_error(kind: TopologyKind, address: &str, field: Option<&str>, message: impl Into<String>) -> TopologyError {
+    TopologyError {
+        kind,
+        address: address.to_string(),
+        field: field.map(str::to_string),
+        message: message.into(),
+    }
+}
+
+#[derive(Default)]
+struct CurrentState {
+    resources: BTreeMap<Address, Resource>,
+}
+
+impl CurrentState {
+    async fn load<DB>(conn: &mut DB::Connection) -> Result<Self, sqlx::Error>
+    where
+        DB: sqlx::Database,
+        for<'c> &'c mut DB::Connection: Executor<'c, Database = DB>,
+        for<'q> <DB as HasArguments<'q>>::Arguments: IntoArguments<'q, DB>,
+        for<'r> Datacenter: FromRow<'r, DB::Row>,
+        for<'r> DatacenterRack: FromRow<'r, DB::Row>,
+        for<'r> ServerCluster: FromRow<'r, DB::Row>,
+        for<'r> ServerSubCluster: FromRow<'r, DB::Row>,
+        for<'r> Switch: FromRow<'r, DB::Row>,
+        for<'r> SwitchVlan: FromRow<'r, DB::Row>,
+    {
+        let datacenters: Vec<Datacenter> = fetch_all(conn, Datacenter::TABLE).await?;
+        let racks: Vec<DatacenterRack> = fetch_all(conn, DatacenterRack::TABLE).await?;
+        let clusters: Vec<ServerCluster> = fetch_all(conn, ServerCluster::TABLE).await?;
+        let sub_clusters: Vec<ServerSubCluster> = fetch_all(conn, ServerSubCluster::TABLE).await?;
+        let switches: Vec<Switch> = fetch_all(conn, Switch::TABLE).await?;
+        let vlans: Vec<SwitchVlan> = fetch_all(conn, SwitchVlan::TABLE).await?;
+
+        let datacenter_codes: HashMap<i32, &str> = datacenters.iter()
+            .map(|datacenter| (datacenter.data_center_id, datacenter.data_center_code.as_str()))
+            .collect();
+        let rack_codes: HashMap<i32, &str> = racks.iter()
+            .map(|rack| (rack.rack_id, rack.rack_code.as_str()))
+            .collect();
+        let cluster_codes: HashMap<i32, &str> = clusters.iter()
+            .map(|cluster| (cluster.cluster_id, cluster.cluster_code.as_str()))
+            .collect();
+        let sub_cluster_codes: HashMap<i32, &str> = sub_clusters.iter()
+            .map(|sub_cluster| (sub_cluster.sub_cluster_id, sub_cluster.sub_cluster_code.as_str()))
+            .collect();
+        let switch_names: HashMap<i32, &str> = switches.iter()
+            .map(|switch| (switch.switch_id, switch.switch_name.as_str()))
+            .collect();
+        let code = |codes: &HashMap<i32, &str>, id: Option<i32>| id.and_then(|id| codes.get(&id)).map(|code| code.to_string());
+
+        let mut state = CurrentState::default();
+        for datacenter in &datacenters {
+            state.add(TopologyKind::Datacenter, None, datacenter.data_center_id, datacenter, &[]);
+        }
+        for rack in &racks {
+            let parent = code(&datacenter_codes, Some(rack.data_center_id));
+            state.add(TopologyKind::Rack, parent, rack.rack_id, rack, &[]);
+        }
+        for cluster in &clusters {
+            state.add(TopologyKind::Cluster, None, cluster.cluster_id, cluster, &[
+                ("data_center_code", code(&datacenter_codes, cluster.data_center_id)),
+            ]);
+        }
+        for sub_cluster in &sub_clusters {
+            let parent = code(&cluster_codes, Some(sub_cluster.cluster_id));
+            state.add(TopologyKind::SubCluster, parent, sub_cluster.sub_cluster_id, sub_cluster, &[]);
+        }
+        for switch in &switches {
+            state.add(TopologyKind::Switch, None, switch.switch_id, switch, &[
+                ("data_center_code", code(&datacenter_codes, switch.data_center_id)),
+                ("rack_code", code(&rack_codes, switch.rack_id)),
+                ("cluster_code", code(&cluster_codes, switch.cluster_id)),
+                ("sub_cluster_code", code(&sub_cluster_codes, switch.sub_cluster_id)),
+            ]);
+        }
+        for vlan in &vlans {
+            let parent = code(&switch_names, Some(vlan.switch_id));
+            state.add(TopologyKind::Vlan, parent, vlan.vlan_db_id, vlan, &[]);
+        }
+
+        Ok(state)
+    }
+
+    fn add<T: Serialize>(
+        &mut self,
+        kind: TopologyKind,
+        parent: Option<String>,
+        id: i32,
+        row: &T,
+        references: &[(&str, Option<String>)],
+    ) {
+        let row = match serde_json::to_value(row) {
+            Ok(Value::Object(row)) => row,
+            _ => return,
+        };
+        let mut fields: Map<String, Value> = kind.columns().iter()
+            .map(|column| (column.name.to_string(), row.get(column.name).cloned().unwrap_or(Value::Null)))
+            .collect();
+        for (field, code) in references {
+            fields.insert(field.to_string(), code.clone().map(Value::String).unwrap_or(Value::Null));
+        }
+
+        let name = identity_text(fields.get(kind.identity()).unwrap_or(&Value::Null));
+        let path = match &parent {
+            Some(parent) => format!("{}/{}", parent, name),
+            None => name,
+        };
+        self.resources.insert((kind, path.clone()), Resource { kind, path, parent, id: Some(id), fields });
+    }
+}
+
+async fn fetch_all<DB, T>(conn: &mut DB::Connection, table: &str) -> Result<Vec<T>, sqlx::Error>
+where
+    DB: sqlx::Database,
+    T: for<'r> FromRow<'r, DB::Row> + Send + Unpin,
+    for<'c> &'c mut DB::Connection: Executor<'c, Database = DB>,
+    for<'q> <DB as HasArguments<'q>>::Arguments: IntoArguments<'q, DB>,
+{
+    sqlx::query_as(&format!("SELECT * FROM {}", table))
+        .fetch_all(&mut *conn)
+        .await
+}
+
+fn identity_text(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// DECIMAL columns come back as strings, so numbers compare by value
+fn same_value(current: &Value, desired: &Value) -> bool {
+    fn number(value: &Value) -> Option<f64> {
+        match value {
+            Value::Number(n) => n.as_f64(),
+            Value::String(s) => s.parse().ok(),
+            _ => None,
+        }
+    }
+
+    if current == desired {
+        return true;
+    }
+    match (current, desired) {
+        (Value::String(_), Value::String(_)) => false,
+        _ => matches!((number(current), number(desired)), (Some(a), Some(b)) if (a - b).abs() < 1e-9),
+    }
+}
+
+/// A planned change together with the document entry it came from
+struct Step {
+    change: PlannedChange,
+    desired: Option<Resource>,
+}
+
+fn plan_steps(desired: &DesiredState, current: &CurrentState) -> Result<(Vec<Step>, PlanSummary), Vec<TopologyError>> {
+    let mut errors = Vec::new();
+    let mut summary = PlanSummary::default();
+
+    // Whatever an owned list leaves out goes, and so do its children (the foreign keys cascade)
+    let mut deleted: BTreeSet<Address> = BTreeSet::new();
+    for (address, resource) in &current.resources {
+        let unlisted = desired.owned.contains(&(resource.kind, resource.parent.clone()))
+            && !desired.resources.contains_key(address);
+        let orphaned = match (resource.kind.parent(), &resource.parent) {
+            (Some((parent_kind, _)), Some(parent)) => deleted.contains(&(parent_kind, parent.clone())),
+            _ => false,
+        };
+        if unlisted || orphaned {
+            deleted.insert(address.clone());
+        }
+    }
+
+    let exists = |kind: TopologyKind, path: &str| {
+        let address = (kind, path.to_string());
+        desired.resources.contains_key(&address)
+            || (current.resources.contains_key(&address) && !deleted.contains(&address))
+    };
+
+    let mut steps: Vec<Step> = Vec::new();
+    for address in deleted.iter().rev() {
+        let resource = &current.resources[address];
+        steps.push(Step {
+            change: PlannedChange {
+                action: PlanAction::Delete,
+                kind: resource.kind,
+                address: resource.path.clone(),
+                id: resource.id,
+                fields: Vec::new(),
+            },
+            desired: None,
+        });
+        summary.delete += 1;
+    }
+
+    for (address, resource) in &desired.resources {
+        let known_errors = errors.len();
+        for reference in resource.kind.references() {
+            match resource.target(reference) {
+                Ok(Some(target)) if !exists(reference.target, &target) => errors.push(topology_error(
+                    resource.kind,
+                    &resource.path,
+                    Some(reference.field),
+                    format!("No {} '{}' in the document or the database", reference.target.name(), target),
+                )),
+                Ok(_) => {}
+                Err(message) => errors.push(topology_error(resource.kind, &resource.path, Some(reference.field), message)),
+            }
+        }
+
+        match current.resources.get(address) {
+            None => {
+                // A broken reference already explains why the record would not build
+                if errors.len() == known_errors {
+                    if let Err(message) = build_record(resource, None) {
+                        errors.push(topology_error(resource.kind, &resource.path, None, message));
+                    }
+                }
+                let fields = resource.fields.iter()
+                    .map(|(field, value)| FieldDrift { field: field.clone(), current: Value::Null, desired: value.clone() })
+                    .collect();
+                steps.push(Step {
+                    change: PlannedChange { action: PlanAction::Create, kind: resource.kind, address: resource.path.clone(), id: None, fields },
+                    desired: Some(resource.clone()),
+                });
+                summary.create += 1;
+            }
+            Some(existing) => {
+                let fields = drift(existing, resource);
+                if fields.is_empty() {
+                    summary.unchanged += 1;
+                    continue;
+                }
+                steps.push(Step {
+                    change: PlannedChange { action: PlanAction::Update, kind: resource.kind, address: resource.path.clone(), id: existing.id, fields },
+                    desired: Some(resource.clone()),
+                });
+                summary.update += 1;
+            }
+        }
+    }
+
+    if errors.is_empty() {
+        Ok((steps, summary))
+    } else {
+        Err(errors)
+    }
+}
+
+/// Columns and references the document sets to something other than what the database has
+fn drift(current: &Resource, desired: &Resource) -> Vec<FieldDrift> {
+    let references = desired.kind.references();
+    let mut fields = Vec::new();
+
+    for (field, value) in &desired.fields {
+        if field == desired.kind.identity() || references.iter().any(|reference| reference.field == field) {
+            continue;
+        }
+        let existing = current.fields.get(field).unwrap_or(&Value::Null);
+        if !same_value(existing, value) {
+            fields.push(FieldDrift { field: field.clone(), current: existing.clone(), desired: value.clone() });
+        }
+    }
+
+    // References compare by the resource they point at, so moving a switch's rack to another
+    // datacenter shows up even when the rack code is unchanged
+    for reference in references {
+        if !desired.fields.contains_key(reference.field) {
+            continue;
+        }
+        let existing = current.target(reference).ok().flatten();
+        let wanted = desired.target(reference).ok().flatten();
+        if existing != wanted {
+            fields.push(FieldDrift {
+                field: reference.field.to_string(),
+                current: existing.map(Value::String).unwrap_or(Value::Null),
+                desired: wanted.map(Value::String).unwrap_or(Value::Null),
+            });
+        }
+    }
+    fields
+}
+
+/// Columns for inserting a document entry: its own values, the IDs of its parent and of the
+/// resources it references (zero while planning, when new rows have no ID yet) and the
+/// defaults the create endpoints rely on
+fn build_record(resource: &Resource, ids: Option<&HashMap<Address, i32>>) -> Result<Map<String, Value>, String> {
+    let kind = resource.kind;
+    let id_of = |target: TopologyKind, path: &str| match ids {
+        Some(ids) => ids.get(&(target, path.to_string())).copied().map(Value::from).unwrap_or(Value::Null),
+        None => json!(0),
+    };
+
+    let mut record = Map::new();
+    for (field, value) in &resource.fields {
+        if !kind.references().iter().any(|reference| reference.field == field) {
+            record.insert(field.clone(), value.clone());
+        }
+    }
+    record.insert(kind.key().to_string(), json!(0));
+    if let (Some((parent_kind, column)), Some(parent)) = (kind.parent(), &resource.parent) {
+        record.insert(column.to_string(), id_of(parent_kind, parent));
+    }
+    for reference in kind.references() {
+        if let Some(target) = resource.target(reference)? {
+            record.insert(reference.column.to_string(), id_of(reference.target, &target));
+        }
+    }
+
+    let defaults: &[(&str, Value)] = match kind {
+        TopologyKind::Datacenter | TopologyKind::Cluster | TopologyKind::SubCluster => &[("status", json!("ACTIVE"))],
+        TopologyKind::Rack => &[("status", json!("ACTIVE")), ("rack_height_u", json!(42))],
+        TopologyKind::Switch | TopologyKind::Vlan => &[],
+    };
+    for (column, value) in defaults {
+        record.entry(column.to_string()).or_insert_with(|| value.clone());
+    }
+
+    // Check the record decodes into the model now, so a missing column fails the plan
+    match kind {
+        TopologyKind::Datacenter => decode::<Datacenter>(&record).map(drop),
+        TopologyKind::Rack => decode::<DatacenterRack>(&record).map(drop),
+        TopologyKind::Cluster => decode::<ServerCluster>(&record).map(drop),
+        TopologyKind::SubCluster => decode::<ServerSubCluster>(&record).map(drop),
+        TopologyKind::Switch => decode::<Switch>(&record).map(drop),
+        TopologyKind::Vlan => decode::<SwitchVlan>(&record).map(drop),
+    }?;
+    Ok(record)
+}
+
+fn decode<T: DeserializeOwned>(record: &Map<String, Value>) -> Result<T, String> {
+    serde_json::from_value(Value::Object(record.clone())).map_err(|e| e.to_string())
+}
+
+fn fingerprint(changes: &[PlannedChange]) -> String {
+    let bytes = serde_json::to_vec(changes).unwrap_or_default();
+    Sha256::digest(&bytes).iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Plan and apply of topology documents, whatever the storage backend
+#[async_trait]
+pub trait TopologyPlanner: Send + Sync {
+    async fn plan(&self, document: TopologyDocument) -> Result<TopologyPlan, TopologyFailure>;
+    async fn apply(
+        &self,
+        document: TopologyDocument,
+        expected_fingerprint: Option<&str>,
+    ) -> Result<TopologyApplyResult, TopologyFailure>;
+}
+
+/// `TopologyPlanner` on any storage backend
+pub struct TopologyService<DB: sqlx::Database = MySql> {
+    pool: Pool<DB>,
+}
+
+impl<DB> TopologyService<DB>
+where
+    DB: SqlDialect,
+    for<'c> &'c mut DB::Connection: Executor<'c, Database = DB> + Acquire<'c, Database = DB>,
+    for<'q> <DB as HasArguments<'q>>::Arguments: IntoArguments<'q, DB>,
+    for<'r> &'r str: ColumnIndex<DB::Row>,
+    usize: ColumnIndex<DB::Row>,
+    for<'q> &'q str: Encode<'q, DB> + Type<DB>,
+    for<'q> Option<&'q str>: Encode<'q, DB>,
+    for<'q> Option<&'q String>: Encode<'q, DB>,
+    for<'q, 'r> String: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+    for<'q> Option<String>: Encode<'q, DB>,
+    for<'q, 'r> i32: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+    for<'q> Option<i32>: Encode<'q, DB>,
+    for<'q, 'r> i64: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+    for<'q> Option<i64>: Encode<'q, DB>,
+    for<'q, 'r> f64: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+    for<'q> Option<f64>: Encode<'q, DB>,
+    for<'q, 'r> bool: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+    for<'q> Option<bool>: Encode<'q, DB>,
+    for<'q> i8: Encode<'q, DB> + Type<DB>,
+    for<'q, 'r> NaiveDateTime: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+    for<'q> Option<NaiveDateTime>: Encode<'q, DB>,
+    for<'q, 'r> DateTime<Utc>: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+    for<'q> Option<DateTime<Utc>>: Encode<'q, DB>,
+    for<'q, 'r> serde_json::Value: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+    for<'q> Option<serde_json::Value>: Encode<'q, DB>,
+    for<'q, 'r> DecimalColumn: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+{
+    pub fn new(pool: Pool<DB>) -> Self {
+        Self { pool }
+    }
+
+    /// Work out the changes that would bring the database in line with `document`
+    pub async fn plan(&self, document: TopologyDocument) -> Result<TopologyPlan, TopologyFailure> {
+        let desired = DesiredState::from_document(document).map_err(TopologyFailure::Invalid)?;
+        let mut conn = self.pool.acquire().await?;
+        let current = CurrentState::load::<DB>(&mut conn).await?;
+        let (steps, summary) = plan_steps(&desired, &current).map_err(TopologyFailure::Invalid)?;
+
+        Ok(Self::plan_of(&steps, summary))
+    }
+
+    /// Plan and apply `document` in one transaction. With `expected_fingerprint`, refuse to
+    /// apply unless the plan is exactly the one that was reviewed.
+    pub async fn apply(
+        &self,
+        document: TopologyDocument,
+        expected_fingerprint: Option<&str>,
+    ) -> Result<TopologyApplyResult, TopologyFailure> {
+        let desired = DesiredState::from_document(document).map_err(TopologyFailure::Invalid)?;
+        let mut tx = self.pool.begin().await?;
+        let current = CurrentState::load::<DB>(&mut tx).await?;
+        let (steps, summary) = plan_steps(&desired, &current).map_err(TopologyFailure::Invalid)?;
+        let plan = Self::plan_of(&steps, summary);
+
+        if expected_fingerprint.is_some_and(|expected| expected != plan.fingerprint) {
+            return Err(TopologyFailure::Stale(plan));
+        }
+        if steps.is_empty() {
+            return Ok(TopologyApplyResult { applied: false, plan });
+        }
+
+        let mut ids: HashMap<Address, i32> = current.resources.iter()
+            .filter_map(|(address, resource)| resource.id.map(|id| (address.clone(), id)))
+            .collect();
+        for step in &steps {
+            let change = &step.change;
+            match (change.action, &step.desired, change.id) {
+                (PlanAction::Delete, _, Some(id)) => {
+                    // Children listed before their parent may already be gone through a cascade
+                    DatabaseHelper::delete_by_id(&mut *tx, change.kind.table(), change.kind.key(), id as i64).await?;
+                }
+                (PlanAction::Create, Some(resource), _) => {
+                    let id = Self::insert(&mut tx, resource, &ids).await?;
+                    ids.insert((resource.kind, resource.path.clone()), id);
+                }
+                (PlanAction::Update, Some(resource), Some(id)) => {
+                    Self::update(&mut tx, resource, id, &change.fields, &ids).await?;
+                }
+                _ => {}
+            }
+        }
+        tx.commit().await?;
+
+        AuditRecorder::record_action(AuditAction {
+            action: "topology.apply".to_string(),
+            target: "topology".to_string(),
+            detail: json!({ "fingerprint": plan.fingerprint, "summary": plan.summary }),
+            success: true,
+            error: None,
+        });
+
+        Ok(TopologyApplyResult { applied: true, plan })
+    }
+
+    fn plan_of(steps: &[Step], summary: PlanSummary) -> TopologyPlan {
+        let changes: Vec<PlannedChange> = steps.iter().map(|step| step.change.clone()).collect();
+        TopologyPlan { fingerprint: fingerprint(&changes), summary, changes }
+    }
+
+    async fn insert(conn: &mut DB::Connection, resource: &Resource, ids: &HashMap<Address, i32>) -> Result<i32, TopologyFailure> {
+        let record = build_record(resource, Some(ids)).map_err(|message| {
+            TopologyFailure::Invalid(vec![topology_error(resource.kind, &resource.path, None, message)])
+        })?;
+        let invalid = |message: String| TopologyFailure::Invalid(vec![topology_error(resource.kind, &resource.path, None, message)]);
+
+        let id = match resource.kind {
+            TopologyKind::Datacenter => DatacenterRepository::<DB>::insert_datacenter(&mut *conn, &decode(&record).map_err(invalid)?).await?,
+            TopologyKind::Rack => DatacenterRepository::<DB>::insert_rack(&mut *conn, &decode(&record).map_err(invalid)?).await?,
+            TopologyKind::Cluster => ClusterRepository::<DB>::insert_cluster(&mut *conn, &decode(&record).map_err(invalid)?).await?,
+            TopologyKind::SubCluster => ClusterRepository::<DB>::insert_sub_cluster(&mut *conn, &decode(&record).map_err(invalid)?).await?,
+            TopologyKind::Switch => SwitchRepository::<DB>::insert_switch(&mut *conn, &decode(&record).map_err(invalid)?).await?,
+            TopologyKind::Vlan => SwitchRepository::<DB>::insert_vlan(&mut *conn, &decode(&record).map_err(invalid)?).await?,
+        };
+        Ok(id)
+    }
+
+    async fn update(
+        conn: &mut DB::Connection,
+        resource: &Resource,
+        id: i32,
+        fields: &[FieldDrift],
+        ids: &HashMap<Address, i32>,
+    ) -> Result<(), TopologyFailure> {
+        let kind = resource.kind;
+        let mut updates = HashMap::new();
+
+        for drift in fields {
+            if let Some(reference) = kind.references().iter().find(|reference| reference.field == drift.field) {
+                let target = match &drift.desired {
+                    Value::String(path) => ids.get(&(reference.target, path.clone())).copied().map(Value::from),
+                    _ => None,
+                };
+                updates.insert(reference.column.to_string(), target.unwrap_or(Value::Null));
+                continue;
+            }
+
+            let is_json = kind.columns().iter()
+                .any(|column| column.name == drift.field && matches!(column.column_type, ColumnType::Json));
+            let value = match &drift.desired {
+                // DatabaseHelper binds scalars only; JSON columns accept the document as text
+                value @ (Value::Array(_) | Value::Object(_)) if is_json => Value::String(value.to_string()),
+                value => value.clone(),
+            };
+            updates.insert(drift.field.clone(), value);
+        }
+
+        DatabaseHelper::update_with(&mut *conn, kind.table(), kind.key(), id, updates, &[]).await?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<DB> TopologyPlanner for TopologyService<DB>
+where
+    DB: SqlDialect,
+    for<'c> &'c mut DB::Connection: Executor<'c, Database = DB> + Acquire<'c, Database = DB>,
+    for<'q> <DB as HasArguments<'q>>::Arguments: IntoArguments<'q, DB>,
+    for<'r> &'r str: ColumnIndex<DB::Row>,
+    usize: ColumnIndex<DB::Row>,
+    for<'q> &'q str: Encode<'q, DB> + Type<DB>,
+    for<'q> Option<&'q str>: Encode<'q, DB>,
+    for<'q> Option<&'q String>: Encode<'q, DB>,
+    for<'q, 'r> String: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+    for<'q> Option<String>: Encode<'q, DB>,
+    for<'q, 'r> i32: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+    for<'q> Option<i32>: Encode<'q, DB>,
+    for<'q, 'r> i64: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+    for<'q> Option<i64>: Encode<'q, DB>,
+    for<'q, 'r> f64: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+    for<'q> Option<f64>: Encode<'q, DB>,
+    for<'q, 'r> bool: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+    for<'q> Option<bool>: Encode<'q, DB>,
+    for<'q> i8: Encode<'q, DB> + Type<DB>,
+    for<'q, 'r> NaiveDateTime: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+    for<'q> Option<NaiveDateTime>: Encode<'q, DB>,
+    for<'q, 'r> DateTime<Utc>: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+    for<'q> Option<DateTime<Utc>>: Encode<'q, DB>,
+    for<'q, 'r> serde_json::Value: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+    for<'q> Option<serde_json::Value>: Encode<'q, DB>,
+    for<'q, 'r> DecimalColumn: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+{
+    async fn plan(&self, document: TopologyDocument) -> Result<TopologyPlan, TopologyFailure> {
+        TopologyService::plan(self, document).await
+    }
+
+    async fn apply(
+        &self,
+        document: TopologyDocument,
+        expected_fingerprint: Option<&str>,
+    ) -> Result<TopologyApplyResult, TopologyFailure> {
+        TopologyService::apply(self, document, expected_fingerprint).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn desired_state(document: Value) -> DesiredState {
+        let document: TopologyDocument = serde_json::from_value(document).expect("document should deserialize");
+        DesiredState::from_document(document).expect("document should be valid")
+    }
+
+    /// Add a row the way `CurrentState::load` would, with `fields` as the database holds them
+    fn existing(state: &mut CurrentState, kind: TopologyKind, parent: Option<&str>, id: i32, fields: Value) {
+        let fields = match fields {
+            Value::Object(fields) => fields,
+            _ => panic!("fields must be an object"),
+        };
+        let name = identity_text(&fields[kind.identity()]);
+        let path = match parent {
+            Some(parent) => format!("{}/{}", parent, name),
+            None => name,
+        };
+        let resource = Resource { kind, path: path.clone(), parent: parent.map(str::to_string), id: Some(id), fields };
+        state.resources.insert((kind, path), resource);
+    }
+
+    fn changes(steps: &[Step]) -> Vec<(PlanAction, TopologyKind, &str)> {
+        steps.iter().map(|step| (step.change.action, step.change.kind, step.change.address.as_str())).collect()
+    }
+
+    #[test]
+    fn child_left_out_of_a_listed_list_is_deleted() {
+        let mut current = CurrentState::default();
+        existing(&mut current, TopologyKind::Datacenter, None, 1, json!({ "data_center_code": "AMS1" }));
+        existing(&mut current, TopologyKind::Rack, Some("AMS1"), 10, json!({ "rack_code": "R01" }));
+        existing(&mut current, TopologyKind::Rack, Some("AMS1"), 11, json!({ "rack_code": "R02" }));
+
+        let desired = desired_state(json!({
+            "datacenters": [{ "data_center_code": "AMS1", "racks": [{ "rack_code": "R01" }] }],
+        }));
+        let (steps, summary) = plan_steps(&desired, &current).expect("plan");
+
+        assert_eq!(changes(&steps), vec![(PlanAction::Delete, TopologyKind::Rack, "AMS1/R02")]);
+        assert_eq!(steps[0].change.id, Some(11));
+        assert_eq!((summary.delete, summary.unchanged), (1, 2));
+    }
+
+    #[test]
+    fn children_of_a_deleted_parent_are_deleted_first() {
+        let mut current = CurrentState::default();
+        existing(&mut current, TopologyKind::Datacenter, None, 1, json!({ "data_center_code": "AMS1" }));
+        existing(&mut current, TopologyKind::Datacenter, None, 2, json!({ "data_center_code": "LON1" }));
+        existing(&mut current, TopologyKind::Rack, Some("AMS1"), 10, json!({ "rack_code": "R01" }));
+
+        // The racks list is left out, so racks are unmanaged, but AMS1/R01 cannot outlive AMS1
+        let desired = desired_state(json!({ "datacenters": [{ "data_center_code": "LON1" }] }));
+        let (steps, summary) = plan_steps(&desired, &current).expect("plan");
+
+        assert_eq!(changes(&steps), vec![
+            (PlanAction::Delete, TopologyKind::Rack, "AMS1/R01"),
+            (PlanAction::Delete, TopologyKind::Datacenter, "AMS1"),
+        ]);
+        assert_eq!((summary.delete, summary.unchanged), (2, 1));
+    }
+
+    #[test]
+    fn reference_to_an_unknown_resource_is_an_error() {
+        let current = CurrentState::default();
+        let desired = desired_state(json!({
+            "clusters": [{ "cluster_code": "AMS1-PROD", "cluster_name": "Prod", "data_center_code": "AMS9" }],
+        }));
+
+        let errors = plan_steps(&desired, &current).err().expect("plan should fail");
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].kind, TopologyKind::Cluster);
+        assert_eq!(errors[0].address, "AMS1-PROD");
+        assert_eq!(errors[0].field.as_deref(), Some("data_center_code"));
+        assert_eq!(errors[0].message, "No datacenter 'AMS9' in the document or the database");
+    }
+
+    #[test]
+    fn decimal_read_back_as_text_matches_the_document_number() {
+        let mut current = CurrentState::default();
+        existing(&mut current, TopologyKind::Datacenter, None, 1, json!({
+            "data_center_code": "AMS1",
+            "latitude": "52.370000",
+            "longitude": "4.890000",
+        }));
+
+        let desired = desired_state(json!({
+            "datacenters": [{ "data_center_code": "AMS1", "latitude": 52.37, "longitude": 4.95 }],
+        }));
+        let (steps, summary) = plan_steps(&desired, &current).expect("plan");
+
+        assert_eq!(changes(&steps), vec![(PlanAction::Update, TopologyKind::Datacenter, "AMS1")]);
+        let drifted: Vec<&str> = steps[0].change.fields.iter().map(|drift| drift.field.as_str()).collect();
+        assert_eq!(drifted, vec!["longitude"]);
+        assert_eq!(summary.update, 1);
+
+        let desired = desired_state(json!({ "datacenters": [{ "data_center_code": "AMS1", "latitude": 52.37 }] }));
+        let (steps, summary) = plan_steps(&desired, &current).expect("plan");
+        assert!(steps.is_empty());
+        assert_eq!(summary.unchanged, 1);
+    }
+
+    #[test]
+    fn invalid_entries_are_reported_by_position() {
+        let document: TopologyDocument = serde_json::from_value(json!({
+            "datacenters": [
+                { "data_center_code": "AMS1", "racks": [{ "rack_name": "no code" }] },
+                { "data_center_code": "AMS1", "colour": "blue" },
+            ],
+        })).unwrap();
+
+        let errors = DesiredState::from_document(document).err().expect("document should be invalid");
+        let reported: Vec<(&str, Option<&str>)> = errors.iter()
+            .map(|error| (error.address.as_str(), error.field.as_deref()))
+            .collect();
+        assert_eq!(reported, vec![
+            ("AMS1/#1", Some("rack_code")),
+            ("AMS1", Some("colour")),
+            ("AMS1", Some("data_center_code")),
+        ]);
+    }
+}
@@ -0,0 +1,255 @@
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use reqwest::{header, redirect, Client};
+use sha2::Sha256;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Notify;
+
+use crate::domain::events::DomainEvent;
+use crate::models::{DeliveryAttempt, DueDelivery};
+use crate::repositories::WebhookRepo;
+
+// ===================================================================
+// WEBHOOK DISPATCHER
+// ===================================================================
+//
+// `AppState::publish` writes every event to webhook_deliveries once per subscribed webhook
+// before the handler responds, so deliveries survive a restart and cannot be dropped by a
+// subscriber falling behind the in-process bus. The dispatcher only sends the due
+// deliveries. A failed attempt is retried with exponential backoff until it succeeds or
+// MAX_ATTEMPTS is reached.
+//
+// Each request carries X-Farm-Signature: sha256=<hex HMAC-SHA256(secret, "{timestamp}.{body}")>
+// where timestamp is the X-Farm-Timestamp header, so receivers can reject forged and
+// replayed requests.
+
+/// Attempts before a delivery is given up as FAILED
+const MAX_ATTEMPTS: i32 = 8;
+
+/// Delay before the first retry; doubled on each further one
+const BASE_BACKOFF: Duration = Duration::from_secs(10);
+
+/// Longest delay between two attempts
+const MAX_BACKOFF: Duration = Duration::from_secs(3600);
+
+/// How often the outbox is checked for due retries when no new event arrives
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Deliveries taken per outbox check
+const BATCH_SIZE: i64 = 50;
+
+/// How long a claimed delivery is hidden from other checks; longer than the request timeout
+const CLAIM_LEASE: Duration = Duration::from_secs(60);
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Response bodies are kept in last_error up to this many characters
+const MAX_ERROR_LENGTH: usize = 500;
+
+/// Queues webhook deliveries and wakes the dispatcher to send them
+#[derive(Clone, Default)]
+pub struct WebhookOutbox {
+    wake: Arc<Notify>,
+}
+
+impl WebhookOutbox {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queue a delivery of `event` to every active webhook subscribed to its type
+    pub async fn queue(&self, repo: &dyn WebhookRepo, event: &DomainEvent) {
+        let targets = match repo.get_active_targets().await {
+            Ok(targets) => targets,
+            Err(e) => {
+                log::warn!("Failed to load webhooks for event {}: {}", event.id, e);
+                return;
+            }
+        };
+        let event_type = event.event_type.name();
+        let payload = serde_json::to_value(event).unwrap_or_default();
+        let mut queued = false;
+        for target in targets.iter().filter(|target| target.wants(event_type)) {
+            match repo.enqueue_delivery(target.webhook_id, Some(event.id as i64), event_type, &payload).await {
+                Ok(_) => queued = true,
+                Err(e) => log::warn!("Failed to queue event {} for webhook {}: {}", event.id, target.webhook_id, e),
+            }
+        }
+        if queued {
+            self.wake();
+        }
+    }
+
+    /// Have the dispatcher look for due deliveries now rather than at its next poll
+    pub fn wake(&self) {
+        self.wake.notify_one();
+    }
+}
+
+pub struct WebhookDispatcher {
+    repo: Box<dyn WebhookRepo>,
+    outbox: WebhookOutbox,
+    client: Client,
+}
+
+impl WebhookDispatcher {
+    pub fn new(repo: Box<dyn WebhookRepo>, outbox: WebhookOutbox) -> Self {
+        let client = Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .connect_timeout(Duration::from_secs(5))
+            // A redirect would resend the signed payload to a URL nobody subscribed
+            .redirect(redirect::Policy::none())
+            .user_agent(concat!("farm-core-webhooks/", env!("CARGO_PKG_VERSION")))
+            .build()
+            .expect("webhook HTTP client configuration is valid");
+        Self { repo, outbox, client }
+    }
+
+    /// Start sending due deliveries in the background
+    pub fn spawn(self) {
+        tokio::spawn(Arc::new(self).send_due());
+    }
+
+    async fn send_due(self: Arc<Self>) {
+        loop {
+            let due = match self.repo.get_due_deliveries(BATCH_SIZE).await {
+                Ok(due) => due,
+                Err(e) => {
+                    log::warn!("Failed to load due webhook deliveries: {}", e);
+                    Vec::new()
+                }
+            };
+            let full_batch = due.len() as i64 == BATCH_SIZE;
+            for delivery in due {
+                let lease = Utc::now() + CLAIM_LEASE;
+                match self.repo.claim_delivery(delivery.delivery_id, lease).await {
+                    Ok(true) => {
+                        tokio::spawn(self.clone().attempt(delivery));
+                    }
+                    Ok(false) => {}
+                    Err(e) => log::warn!("Failed to claim webhook delivery {}: {}", delivery.delivery_id, e),
+                }
+            }
+            if !full_batch {
+                tokio::select! {
+                    _ = self.outbox.wake.notified() => {}
+                    _ = tokio::time::sleep(POLL_INTERVAL) => {}
+                }
+            }
+        }
+    }
+
+    async fn attempt(self: Arc<Self>, delivery: DueDelivery) {
+        let body = serde_json::to_vec(&delivery.payload).unwrap_or_default();
+        let timestamp = Utc::now().timestamp();
+
+        let response = self.client
+            .post(&delivery.url)
+            .header(header::CONTENT_TYPE, "application/json")
+            .header("X-Farm-Event", &delivery.event_type)
+            .header("X-Farm-Delivery", delivery.delivery_id)
+            .header("X-Farm-Timestamp", timestamp)
+            .header("X-Farm-Signature", format!("sha256={}", sign(&delivery.secret, timestamp, &body)))
+            .body(body)
+            .send()
+            .await;
+
+        let (status_code, error) = match response {
+            Ok(response) if response.status().is_success() => (Some(response.status().as_u16() as i32), None),
+            Ok(response) => {
+                let status = response.status();
+                let text = response.text().await.unwrap_or_default();
+                let text: String = text.chars().take(MAX_ERROR_LENGTH).collect();
+                (Some(status.as_u16() as i32), Some(format!("HTTP {}: {}", status, text.trim())))
+            }
+            Err(e) => (None, Some(e.to_string())),
+        };
+
+        let attempts = delivery.attempts + 1;
+        let attempt = outcome(attempts, status_code, error);
+        if attempt.status == "FAILED" {
+            log::warn!("Webhook delivery {} failed after {} attempts: {}",
+                delivery.delivery_id, attempts, attempt.error.as_deref().unwrap_or_default());
+        }
+        if let Err(e) = self.repo.record_attempt(delivery.delivery_id, attempt).await {
+            log::warn!("Failed to record attempt for webhook delivery {}: {}", delivery.delivery_id, e);
+        }
+    }
+}
+
+/// Where a delivery stands after its `attempts`-th attempt ended with `error` (None on success)
+fn outcome(attempts: i32, status_code: Option<i32>, error: Option<String>) -> DeliveryAttempt {
+    match error {
+        None => DeliveryAttempt { status: "SUCCEEDED", status_code, error: None, next_attempt_at: None },
+        Some(error) if attempts >= MAX_ATTEMPTS => {
+            DeliveryAttempt { status: "FAILED", status_code, error: Some(error), next_attempt_at: None }
+        }
+        Some(error) => DeliveryAttempt {
+            status: "PENDING",
+            status_code,
+            error: Some(error),
+            next_attempt_at: Some(Utc::now() + backoff(attempts)),
+        },
+    }
+}
+
+/// Delay after the given number of failed attempts
+fn backoff(attempts: i32) -> Duration {
+    let exponent = attempts.saturating_sub(1).clamp(0, 16) as u32;
+    BASE_BACKOFF.saturating_mul(1 << exponent).min(MAX_BACKOFF)
+}
+
+/// Hex HMAC-SHA256 of "{timestamp}.{body}" keyed with the webhook secret
+fn sign(secret: &str, timestamp: i64, body: &[u8]) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+        .expect("HMAC accepts keys of any length");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body);
+    mac.finalize().into_bytes().iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backoff_doubles_from_the_base_up_to_the_cap() {
+        assert_eq!(backoff(1), BASE_BACKOFF);
+        assert_eq!(backoff(2), BASE_BACKOFF * 2);
+        assert_eq!(backoff(4), BASE_BACKOFF * 8);
+        assert_eq!(backoff(9), BASE_BACKOFF * 256);
+        assert_eq!(backoff(10), MAX_BACKOFF);
+        assert_eq!(backoff(i32::MAX), MAX_BACKOFF);
+        // A delivery never attempted waits no less than the base delay
+        assert_eq!(backoff(0), BASE_BACKOFF);
+    }
+
+    #[test]
+    fn signature_is_hmac_of_timestamp_and_body() {
+        let signature = sign("topsecret", 1_700_000_000, br#"{"type":"webhook.ping"}"#);
+        assert_eq!(signature, "306ae51f278c869b736850b213fb8b2d58ea9db42bdf536c47afe711fd96fb69");
+        assert_ne!(sign("topsecret", 1_700_000_001, br#"{"type":"webhook.ping"}"#), signature);
+        assert_ne!(sign("other", 1_700_000_000, br#"{"type":"webhook.ping"}"#), signature);
+    }
+
+    #[test]
+    fn failed_attempts_are_retried_until_the_last_one() {
+        let before = Utc::now();
+        let retry = outcome(1, Some(503), Some("HTTP 503".to_string()));
+        assert_eq!(retry.status, "PENDING");
+        assert_eq!(retry.status_code, Some(503));
+        let wait = retry.next_attempt_at.expect("retry time") - before;
+        assert!(wait >= chrono::Duration::from_std(BASE_BACKOFF).unwrap());
+
+        let last = outcome(MAX_ATTEMPTS, None, Some("connection refused".to_string()));
+        assert_eq!(last.status, "FAILED");
+        assert_eq!(last.next_attempt_at, None);
+        assert_eq!(last.error.as_deref(), Some("connection refused"));
+
+        let delivered = outcome(3, Some(204), None);
+        assert_eq!(delivered.status, "SUCCEEDED");
+        assert_eq!((delivered.error, delivered.next_attempt_at), (None, None));
+    }
+}
@@ -7,4 +7,5 @@ pub mod api;
 pub mod repositories;
 pub mod state;
 pub mod search;
-pub mod domain;
\ No newline at end of file
+pub mod domain;
+pub mod metrics;
\ No newline at end of file
@@ -5,9 +5,9 @@ mod search;
 mod repositories;
 mod state;
 mod domain;
+mod metrics;
 
-use actix_web::{web, App, HttpServer, HttpResponse, Result};
-use database::DbPool;
+use actix_web::{middleware::from_fn, web, App, HttpServer, HttpResponse, Result};
 use state::AppState;
 use tracing_actix_web::TracingLogger;
 use tracing::{info, error, warn};
@@ -35,10 +35,10 @@ async fn main() -> std::io::Result<()> {
 
     info!("🚀 Starting Farm API Server initialization");
 
-    let pool: DbPool = match database::init_db_pool().await {
-        Ok(pool) => {
-            info!("✓ Database pool initialized successfully");
-            pool
+    let storage = match database::init_storage().await {
+        Ok(storage) => {
+            info!("✓ Database pool initialized successfully ({})", storage.backend_name());
+            storage
         },
         Err(e) => {
             error!("✗ Failed to initialize database pool: {}", e);
@@ -47,16 +47,21 @@ async fn main() -> std::io::Result<()> {
     };
 
     // Create application state with all repositories
-    let app_state = AppState::new(pool);
+    let app_state = AppState::new(storage);
     info!("✓ Application state and repositories initialized");
 
+    domain::WebhookDispatcher::new(app_state.webhook_repo(), app_state.webhook_outbox().clone()).spawn();
+    info!("✓ Webhook dispatcher started");
+
     info!("🌐 Starting Farm API Server on 127.0.0.1:6183");
 
     HttpServer::new(move || {
         App::new()
+            .wrap(from_fn(api::metrics::record_request_metrics))
             .wrap(TracingLogger::default()) // Automatic HTTP request/response logging
             .app_data(web::Data::new(app_state.clone()))
             .configure(api::configure_api_routes)
+            .configure(api::metrics::configure_metrics_routes)
             .default_service(web::route().to(not_found))
     })
     .workers(12)
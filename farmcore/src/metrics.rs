@@ -0,0 +1,225 @@
+//! Operational metrics of farm-core itself, served in Prometheus text format at /metrics,
+//! and gauges of the fleet inventory, served at /metrics/fleet.
+//!
+//! The service collectors live in one process-wide registry so lower layers (the Redfish
+//! client, inventory handlers) can record without having the application state at hand.
+//! Pool gauges are sampled when the endpoint is scraped. Fleet gauges are built from the
+//! database on each scrape, into a fresh registry so groups that disappear stop reporting.
+
+use prometheus::{
+    Encoder, GaugeVec, HistogramOpts, HistogramVec, IntCounterVec, IntGauge, IntGaugeVec, Opts, Registry, TextEncoder,
+};
+use std::sync::LazyLock;
+use std::time::Duration;
+
+use crate::database::Storage;
+use crate::models::FleetStats;
+
+/// Prefix of every metric name
+const NAMESPACE: &str = "farm";
+
+/// Redfish calls time out after 10s, so buckets stop just above that
+const REDFISH_BUCKETS: &[f64] = &[0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 15.0];
+
+static METRICS: LazyLock<Metrics> = LazyLock::new(Metrics::new);
+
+struct Metrics {
+    registry: Registry,
+    http_requests: IntCounterVec,
+    http_request_duration: HistogramVec,
+    db_pool_connections: IntGaugeVec,
+    db_pool_max_connections: IntGaugeVec,
+    inventory_ingests: IntCounterVec,
+    inventory_last_success: GaugeVec,
+    redfish_requests: IntCounterVec,
+    redfish_request_duration: HistogramVec,
+}
+
+impl Metrics {
+    fn new() -> Self {
+        let registry = Registry::new();
+
+        let http_requests = IntCounterVec::new(
+            Opts::new("http_requests_total", "HTTP requests handled, by route pattern and status").namespace(NAMESPACE),
+            &["method", "route", "status"],
+        ).expect("valid metric");
+        let http_request_duration = HistogramVec::new(
+            HistogramOpts::new("http_request_duration_seconds", "Time until the response head was ready, by route pattern").namespace(NAMESPACE),
+            &["method", "route"],
+        ).expect("valid metric");
+        let db_pool_connections = IntGaugeVec::new(
+            Opts::new("db_pool_connections", "Open database connections, idle or in use").namespace(NAMESPACE),
+            &["backend", "state"],
+        ).expect("valid metric");
+        let db_pool_max_connections = IntGaugeVec::new(
+            Opts::new("db_pool_max_connections", "Configured maximum size of the database pool").namespace(NAMESPACE),
+            &["backend"],
+        ).expect("valid metric");
+        let inventory_ingests = IntCounterVec::new(
+            Opts::new("inventory_ingests_total", "Inventory reports from agents, by kind and result").namespace(NAMESPACE),
+            &["kind", "result"],
+        ).expect("valid metric");
+        let inventory_last_success = GaugeVec::new(
+            Opts::new("inventory_last_success_timestamp_seconds", "Unix time of the last inventory report that was stored").namespace(NAMESPACE),
+            &["kind"],
+        ).expect("valid metric");
+        let redfish_requests = IntCounterVec::new(
+            Opts::new("redfish_requests_total", "Redfish calls to BMCs, by BMC address, operation and outcome").namespace(NAMESPACE),
+            &["bmc", "operation", "outcome"],
+        ).expect("valid metric");
+        let redfish_request_duration = HistogramVec::new(
+            HistogramOpts::new("redfish_request_duration_seconds", "Duration of Redfish calls, by operation").namespace(NAMESPACE)
+                .buckets(REDFISH_BUCKETS.to_vec()),
+            &["operation"],
+        ).expect("valid metric");
+
+        for collector in [
+            Box::new(http_requests.clone()) as Box<dyn prometheus::core::Collector>,
+            Box::new(http_request_duration.clone()),
+            Box::new(db_pool_connections.clone()),
+            Box::new(db_pool_max_connections.clone()),
+            Box::new(inventory_ingests.clone()),
+            Box::new(inventory_last_success.clone()),
+            Box::new(redfish_requests.clone()),
+            Box::new(redfish_request_duration.clone()),
+        ] {
+            registry.register(collector).expect("metric names are unique");
+        }
+
+        Self {
+            registry,
+            http_requests,
+            http_request_duration,
+            db_pool_connections,
+            db_pool_max_connections,
+            inventory_ingests,
+            inventory_last_success,
+            redfish_requests,
+            redfish_request_duration,
+        }
+    }
+}
+
+/// A handled HTTP request. `route` is the matched pattern (e.g. /api/v1/servers/{id}),
+/// never the raw path, to keep the label set bounded.
+pub fn observe_http_request(method: &str, route: &str, status: u16, elapsed: Duration) {
+    let metrics = &*METRICS;
+    metrics.http_requests.with_label_values(&[method, route, &status.to_string()]).inc();
+    metrics.http_request_duration.with_label_values(&[method, route]).observe(elapsed.as_secs_f64());
+}
+
+/// An inventory report of `kind` (server, vm or k8s) that was stored or rejected
+pub fn observe_inventory_ingest(kind: &str, success: bool) {
+    let metrics = &*METRICS;
+    let result = if success { "success" } else { "failure" };
+    metrics.inventory_ingests.with_label_values(&[kind, result]).inc();
+    if success {
+        let now = chrono::Utc::now().timestamp_millis() as f64 / 1000.0;
+        metrics.inventory_last_success.with_label_values(&[kind]).set(now);
+    }
+}
+
+/// A Redfish call; `outcome` is "success" or the kind of error
+pub fn observe_redfish_call(bmc: &str, operation: &str, outcome: &str, elapsed: Duration) {
+    let metrics = &*METRICS;
+    metrics.redfish_requests.with_label_values(&[bmc, operation, outcome]).inc();
+    metrics.redfish_request_duration.with_label_values(&[operation]).observe(elapsed.as_secs_f64());
+}
+
+fn sample_pool(storage: &Storage) {
+    let (size, idle, max) = match storage {
+        Storage::MySql(pool) => (pool.size(), pool.num_idle(), pool.options().get_max_connections()),
+        Storage::Sqlite(pool) => (pool.size(), pool.num_idle(), pool.options().get_max_connections()),
+    };
+    let metrics = &*METRICS;
+    let backend = storage.backend_name();
+    let idle = idle as i64;
+    metrics.db_pool_connections.with_label_values(&[backend, "idle"]).set(idle);
+    metrics.db_pool_connections.with_label_values(&[backend, "in_use"]).set((size as i64 - idle).max(0));
+    metrics.db_pool_max_connections.with_label_values(&[backend]).set(max as i64);
+}
+
+fn encode(registry: &Registry) -> String {
+    let mut buffer = Vec::new();
+    if let Err(e) = TextEncoder::new().encode(&registry.gather(), &mut buffer) {
+        log::error!("Failed to encode metrics: {}", e);
+    }
+    String::from_utf8(buffer).unwrap_or_default()
+}
+
+/// Every service metric in the Prometheus text exposition format
+pub fn render(storage: &Storage) -> String {
+    sample_pool(storage);
+    encode(&METRICS.registry)
+}
+
+/// Gauge vector registered in `registry` under the fleet subsystem
+fn fleet_gauge(registry: &Registry, name: &str, help: &str, labels: &[&str]) -> IntGaugeVec {
+    let gauge = IntGaugeVec::new(Opts::new(name, help).namespace(NAMESPACE).subsystem("fleet"), labels)
+        .expect("valid metric");
+    registry.register(Box::new(gauge.clone())).expect("metric names are unique");
+    gauge
+}
+
+/// Fleet inventory gauges in the Prometheus text exposition format
+pub fn render_fleet(stats: &FleetStats, stale_after: Duration) -> String {
+    let registry = Registry::new();
+
+    let servers = fleet_gauge(&registry, "servers", "Servers by status, type, cluster and datacenter",
+        &["status", "server_type", "cluster", "datacenter"]);
+    for row in &stats.servers {
+        servers.with_label_values(&[&row.status, &row.server_type, &row.cluster, &row.datacenter]).set(row.count);
+    }
+
+    let stale_threshold = IntGauge::with_opts(
+        Opts::new("stale_agent_threshold_seconds", "Time without an inventory report after which an agent counts as stale")
+            .namespace(NAMESPACE).subsystem("fleet"),
+    ).expect("valid metric");
+    stale_threshold.set(stale_after.as_secs() as i64);
+    registry.register(Box::new(stale_threshold)).expect("metric names are unique");
+
+    let stale_agents = fleet_gauge(&registry, "stale_agents", "ACTIVE servers whose agent has not reported within the threshold",
+        &["cluster", "datacenter"]);
+    for row in &stats.stale_agents {
+        stale_agents.with_label_values(&[&row.cluster, &row.datacenter]).set(row.count);
+    }
+
+    let gpus = fleet_gauge(&registry, "gpus", "Installed GPUs by model", &["vendor", "model"]);
+    for row in &stats.gpus {
+        gpus.with_label_values(&[&row.vendor, &row.model]).set(row.count);
+    }
+
+    let disks = fleet_gauge(&registry, "disks", "Server disks by SMART health (PASSED, FAILED or UNKNOWN)", &["smart_health"]);
+    for row in &stats.disks {
+        disks.with_label_values(&[&row.smart_health]).set(row.count);
+    }
+
+    let rack_height = fleet_gauge(&registry, "rack_height_u", "Rack height in U", &["datacenter", "rack"]);
+    let rack_free = fleet_gauge(&registry, "rack_free_u", "Rack units not taken by servers", &["datacenter", "rack"]);
+    for row in &stats.racks {
+        rack_height.with_label_values(&[&row.datacenter, &row.rack]).set(row.height_u);
+        rack_free.with_label_values(&[&row.datacenter, &row.rack]).set((row.height_u - row.occupied_u).max(0));
+    }
+
+    let vms = fleet_gauge(&registry, "vms", "Virtual machines by hypervisor and state", &["hypervisor", "state"]);
+    let vm_vcpus = fleet_gauge(&registry, "vm_vcpus", "vCPUs allocated to virtual machines", &["hypervisor", "state"]);
+    let vm_memory = fleet_gauge(&registry, "vm_memory_bytes", "Memory allocated to virtual machines", &["hypervisor", "state"]);
+    for row in &stats.vms {
+        let labels = [row.hypervisor_type.as_str(), row.vm_state.as_str()];
+        vms.with_label_values(&labels).set(row.count);
+        vm_vcpus.with_label_values(&labels).set(row.vcpus);
+        vm_memory.with_label_values(&labels).set(row.memory_mb * 1024 * 1024);
+    }
+
+    let switches = fleet_gauge(&registry, "switches", "Switches by status and role", &["status", "role"]);
+    for row in &stats.switches {
+        switches.with_label_values(&[&row.status, &row.switch_role]).set(row.count);
+    }
+
+    let switch_ports = fleet_gauge(&registry, "switch_ports", "Switch ports by operational status", &["oper_status"]);
+    for row in &stats.switch_ports {
+        switch_ports.with_label_values(&[&row.oper_status]).set(row.count);
+    }
+
+    encode(&registry)
+}
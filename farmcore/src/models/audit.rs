@@ -0,0 +1,87 @@
+use sqlx::FromRow;
+use serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
+use crate::models::query::{Column, TableSchema};
+
+// ===================================================================
+// AUDIT EVENT MODEL
+// ===================================================================
+
+#[derive(FromRow, Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct AuditEvent {
+    pub audit_id: i64,
+
+    // Principal
+    pub token_id: Option<i32>,
+    pub principal_name: String,
+    pub principal_scope: String,        // ENUM: API, AGENT
+    pub principal_role: Option<String>, // ENUM: VIEWER, OPERATOR, ADMIN
+    pub client_ip: Option<String>,
+
+    // Request
+    pub http_method: String,
+    pub route: String,
+    pub request_path: String,
+    pub resource: Option<String>,
+    pub entity_id: Option<String>,
+
+    // Outcome
+    pub status_code: i32,
+    pub result: String, // ENUM: SUCCESS, FAILURE
+    pub duration_ms: Option<i32>,
+
+    // Details
+    pub changes: Option<serde_json::Value>,
+    pub actions: Option<serde_json::Value>,
+
+    pub created_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+impl AuditEvent {
+    pub const TABLE: &'static str = "audit_events";
+    pub const KEY: &'static str = "audit_id";
+    pub const SCHEMA: TableSchema = TableSchema {
+        table: Self::TABLE,
+        key: Self::KEY,
+        columns: &[
+            Column::integer("audit_id"),
+            Column::integer("token_id"),
+            Column::string("principal_name"),
+            Column::enumeration("principal_scope", &["API", "AGENT"]),
+            Column::enumeration("principal_role", &["VIEWER", "OPERATOR", "ADMIN"]),
+            Column::string("client_ip"),
+            Column::string("http_method"),
+            Column::string("route"),
+            Column::string("request_path"),
+            Column::string("resource"),
+            Column::string("entity_id"),
+            Column::integer("status_code"),
+            Column::enumeration("result", &["SUCCESS", "FAILURE"]),
+            Column::integer("duration_ms"),
+            Column::json("changes"),
+            Column::json("actions"),
+            Column::timestamp("created_at"),
+        ],
+        relations: &[],
+    };
+}
+
+/// A new audit event as assembled by the audit middleware
+#[derive(Debug, Clone)]
+pub struct NewAuditEvent {
+    pub token_id: Option<i32>,
+    pub principal_name: String,
+    pub principal_scope: String,
+    pub principal_role: Option<String>,
+    pub client_ip: Option<String>,
+    pub http_method: String,
+    pub route: String,
+    pub request_path: String,
+    pub resource: Option<String>,
+    pub entity_id: Option<String>,
+    pub status_code: i32,
+    pub success: bool,
+    pub duration_ms: i32,
+    pub changes: Option<serde_json::Value>,
+    pub actions: Option<serde_json::Value>,
+}
@@ -0,0 +1,108 @@
+use sqlx::FromRow;
+use serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
+
+// ===================================================================
+// API TOKEN MODEL
+// ===================================================================
+
+#[derive(FromRow, Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ApiToken {
+    pub token_id: i32,
+    pub token_name: String,
+    pub token_prefix: String,
+    pub description: Option<String>,
+    pub scope: String, // ENUM: API, AGENT
+    pub role: Option<String>, // ENUM: VIEWER, OPERATOR, ADMIN; NULL for AGENT tokens
+
+    pub expires_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub last_used_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub revoked_at: Option<chrono::DateTime<chrono::Utc>>,
+
+    // NOTE: token_hash is intentionally excluded from this struct — it is only
+    //       ever compared inside the repository and never serialised.
+
+    pub created_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub updated_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+impl ApiToken {
+    pub const TABLE: &'static str = "api_tokens";
+    pub const KEY: &'static str = "token_id";
+}
+
+/// Scope a token was issued for. Agent tokens may only reach the inventory
+/// ingest routes; API tokens may reach everything else.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum TokenScope {
+    Api,
+    Agent,
+}
+
+impl TokenScope {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TokenScope::Api => "API",
+            TokenScope::Agent => "AGENT",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_uppercase().as_str() {
+            "API" => Some(TokenScope::Api),
+            "AGENT" => Some(TokenScope::Agent),
+            _ => None,
+        }
+    }
+}
+
+/// Role attached to an API-scoped token. Roles are ordered, so a higher role
+/// satisfies every permission of the roles below it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum Role {
+    Viewer,
+    Operator,
+    Admin,
+}
+
+impl Role {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Role::Viewer => "VIEWER",
+            Role::Operator => "OPERATOR",
+            Role::Admin => "ADMIN",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_uppercase().as_str() {
+            "VIEWER" => Some(Role::Viewer),
+            "OPERATOR" => Some(Role::Operator),
+            "ADMIN" => Some(Role::Admin),
+            _ => None,
+        }
+    }
+}
+
+/// Request body for issuing a new API token
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+pub struct CreateApiToken {
+    pub token_name: String,
+    pub scope: TokenScope,
+    pub role: Option<Role>, // Defaults to VIEWER; must be omitted for AGENT tokens
+    pub description: Option<String>,
+    pub expires_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// Returned exactly once when a token is created; the plaintext is not stored
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct IssuedApiToken {
+    pub token_id: i32,
+    pub token_name: String,
+    pub scope: TokenScope,
+    /// None for AGENT tokens
+    pub role: Option<Role>,
+    pub token: String,
+}
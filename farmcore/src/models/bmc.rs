@@ -1,6 +1,7 @@
 use serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
 pub enum PowerState {
     On,
     Off,
@@ -27,7 +28,7 @@ impl From<&str> for PowerState {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct SystemInfo {
     #[serde(rename = "Id")]
     pub id: String,
@@ -54,7 +55,7 @@ pub struct SystemInfo {
     pub bios_version: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Status {
     #[serde(rename = "State")]
     pub state: Option<String>,
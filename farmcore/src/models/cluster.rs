@@ -1,11 +1,13 @@
 use sqlx::FromRow;
 use serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
+use crate::models::query::{Column, TableSchema};
 
 // ===================================================================
 // SERVER CLUSTER MODEL
 // ===================================================================
 
-#[derive(FromRow, Debug, Clone, Serialize, Deserialize)]
+#[derive(FromRow, Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ServerCluster {
     pub cluster_id: i32,
     
@@ -41,13 +43,38 @@ pub struct ServerCluster {
 impl ServerCluster {
     pub const TABLE: &'static str = "server_clusters";
     pub const KEY: &'static str = "cluster_id";
+    pub const SCHEMA: TableSchema = TableSchema {
+        table: Self::TABLE,
+        key: Self::KEY,
+        columns: &[
+            Column::integer("cluster_id"),
+            Column::string("cluster_name"),
+            Column::string("cluster_code"),
+            Column::string("description"),
+            Column::integer("data_center_id"),
+            Column::string("region"),
+            Column::string("availability_zone"),
+            Column::enumeration("status", &["ACTIVE", "INACTIVE", "MAINTENANCE", "DECOMMISSIONED"]),
+            Column::enumeration("environment_type", &["PRODUCTION", "DEVELOPMENT", "QA", "STAGING", "TESTING"]),
+            Column::integer("total_servers"),
+            Column::integer("active_servers"),
+            Column::integer("max_capacity"),
+            Column::string("owner"),
+            Column::string("contact_email"),
+            Column::json("tags"),
+            Column::json("metadata"),
+            Column::timestamp("created_at"),
+            Column::timestamp("updated_at"),
+        ],
+        relations: &[],
+    };
 }
 
 // ===================================================================
 // SERVER SUB-CLUSTER MODEL
 // ===================================================================
 
-#[derive(FromRow, Debug, Clone, Serialize, Deserialize)]
+#[derive(FromRow, Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ServerSubCluster {
     pub sub_cluster_id: i32,
     pub cluster_id: i32,
@@ -89,14 +116,14 @@ impl ServerSubCluster {
 // COMPOSITE STRUCTURES
 // ===================================================================
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ClusterWithSubClusters {
     #[serde(flatten)]
     pub cluster: ServerCluster,
     pub sub_clusters: Vec<ServerSubCluster>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ClusterWithServers {
     #[serde(flatten)]
     pub cluster: ServerCluster,
@@ -1,7 +1,9 @@
 use sqlx::FromRow;
 use serde::{Serialize, Deserialize};
+use schemars::JsonSchema;
+use crate::models::query::{Column, TableSchema};
 
-#[derive(FromRow, Debug, Clone, Serialize, Deserialize)]
+#[derive(FromRow, Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ComponentCpuType {
     pub component_cpu_id: i32,
     pub manufacturer: String,
@@ -15,7 +17,27 @@ pub struct ComponentCpuType {
     pub created_at: Option<chrono::DateTime<chrono::Utc>>,
 }
 
-#[derive(FromRow, Debug, Clone, Serialize, Deserialize)]
+impl ComponentCpuType {
+    pub const SCHEMA: TableSchema = TableSchema {
+        table: "component_cpu_types",
+        key: "component_cpu_id",
+        columns: &[
+            Column::integer("component_cpu_id"),
+            Column::string("manufacturer"),
+            Column::string("model_name"),
+            Column::integer("num_cores"),
+            Column::integer("num_threads"),
+            Column::integer("capacity_mhz"),
+            Column::integer("l1_cache_kb"),
+            Column::integer("l2_cache_kb"),
+            Column::integer("l3_cache_kb"),
+            Column::timestamp("created_at"),
+        ],
+        relations: &[],
+    };
+}
+
+#[derive(FromRow, Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ComponentMemoryType {
     pub component_memory_id: i32,
     pub manufacturer: String,
@@ -26,7 +48,24 @@ pub struct ComponentMemoryType {
     pub created_at: Option<chrono::DateTime<chrono::Utc>>,
 }
 
-#[derive(FromRow, Debug, Clone, Serialize, Deserialize)]
+impl ComponentMemoryType {
+    pub const SCHEMA: TableSchema = TableSchema {
+        table: "component_memory_types",
+        key: "component_memory_id",
+        columns: &[
+            Column::integer("component_memory_id"),
+            Column::string("manufacturer"),
+            Column::string("part_number"),
+            Column::integer("size_bytes"),
+            Column::string("mem_type"),
+            Column::integer("speed_mt_s"),
+            Column::timestamp("created_at"),
+        ],
+        relations: &[],
+    };
+}
+
+#[derive(FromRow, Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ComponentMotherboardType {
     pub component_motherboard_id: i32,
     pub manufacturer: String,
@@ -37,7 +76,24 @@ pub struct ComponentMotherboardType {
     pub created_at: Option<chrono::DateTime<chrono::Utc>>,
 }
 
-#[derive(FromRow, Debug, Clone, Serialize, Deserialize)]
+impl ComponentMotherboardType {
+    pub const SCHEMA: TableSchema = TableSchema {
+        table: "component_motherboard_types",
+        key: "component_motherboard_id",
+        columns: &[
+            Column::integer("component_motherboard_id"),
+            Column::string("manufacturer"),
+            Column::string("product_name"),
+            Column::string("version"),
+            Column::string("bios_version"),
+            Column::string("bmc_firmware_version"),
+            Column::timestamp("created_at"),
+        ],
+        relations: &[],
+    };
+}
+
+#[derive(FromRow, Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ComponentDiskType {
     pub component_disk_id: i32,
     pub manufacturer: Option<String>,
@@ -48,7 +104,24 @@ pub struct ComponentDiskType {
     pub created_at: Option<chrono::DateTime<chrono::Utc>>,
 }
 
-#[derive(FromRow, Debug, Clone, Serialize, Deserialize)]
+impl ComponentDiskType {
+    pub const SCHEMA: TableSchema = TableSchema {
+        table: "component_disk_types",
+        key: "component_disk_id",
+        columns: &[
+            Column::integer("component_disk_id"),
+            Column::string("manufacturer"),
+            Column::string("model"),
+            Column::integer("size_bytes"),
+            Column::boolean("rotational"),
+            Column::string("bus_type"),
+            Column::timestamp("created_at"),
+        ],
+        relations: &[],
+    };
+}
+
+#[derive(FromRow, Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ComponentNetworkType {
     pub component_network_id: i32,
     pub vendor_name: Option<String>,
@@ -58,7 +131,23 @@ pub struct ComponentNetworkType {
     pub created_at: Option<chrono::DateTime<chrono::Utc>>,
 }
 
-#[derive(FromRow, Debug, Clone, Serialize, Deserialize)]
+impl ComponentNetworkType {
+    pub const SCHEMA: TableSchema = TableSchema {
+        table: "component_network_types",
+        key: "component_network_id",
+        columns: &[
+            Column::integer("component_network_id"),
+            Column::string("vendor_name"),
+            Column::string("device_name"),
+            Column::string("driver"),
+            Column::integer("max_speed_mbps"),
+            Column::timestamp("created_at"),
+        ],
+        relations: &[],
+    };
+}
+
+#[derive(FromRow, Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ComponentGpuType {
     pub component_gpu_id: i32,
     pub vendor: String,
@@ -67,7 +156,22 @@ pub struct ComponentGpuType {
     pub created_at: Option<chrono::DateTime<chrono::Utc>>,
 }
 
-#[derive(FromRow, Debug, Clone, Serialize, Deserialize)]
+impl ComponentGpuType {
+    pub const SCHEMA: TableSchema = TableSchema {
+        table: "component_gpu_types",
+        key: "component_gpu_id",
+        columns: &[
+            Column::integer("component_gpu_id"),
+            Column::string("vendor"),
+            Column::string("model"),
+            Column::integer("vram_mb"),
+            Column::timestamp("created_at"),
+        ],
+        relations: &[],
+    };
+}
+
+#[derive(FromRow, Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ComponentBmcType {
     pub component_bmc_id: i32,
     pub vendor: String,
@@ -83,12 +187,34 @@ pub struct ComponentBmcType {
     pub created_at: Option<chrono::DateTime<chrono::Utc>>,
 }
 
+impl ComponentBmcType {
+    pub const SCHEMA: TableSchema = TableSchema {
+        table: "component_bmc_types",
+        key: "component_bmc_id",
+        columns: &[
+            Column::integer("component_bmc_id"),
+            Column::string("vendor"),
+            Column::string("model"),
+            Column::string("firmware_version"),
+            Column::boolean("supports_ipmi"),
+            Column::boolean("supports_redfish"),
+            Column::boolean("supports_web_interface"),
+            Column::boolean("supports_kvm"),
+            Column::boolean("supports_virtual_media"),
+            Column::boolean("has_dedicated_port"),
+            Column::integer("max_speed_mbps"),
+            Column::timestamp("created_at"),
+        ],
+        relations: &[],
+    };
+}
+
 // ===================================================================
 // COMPONENT CATALOG/MANAGEMENT STRUCTS
 // ===================================================================
 
 /// For component catalog/inventory management endpoints
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ComponentCatalog {
     pub cpus: Vec<ComponentCpuType>,
     pub memory: Vec<ComponentMemoryType>,
@@ -100,7 +226,7 @@ pub struct ComponentCatalog {
 }
 
 /// Summary stats for component catalog
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ComponentCatalogStats {
     pub total_cpu_types: i32,
     pub total_memory_types: i32,
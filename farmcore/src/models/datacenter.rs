@@ -1,12 +1,15 @@
 use sqlx::FromRow;
 use serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
 use rust_decimal::Decimal;
+use crate::database::DecimalColumn;
+use crate::models::query::{Column, TableSchema};
 
 // ===================================================================
 // DATACENTER MODEL
 // ===================================================================
 
-#[derive(FromRow, Debug, Clone, Serialize, Deserialize)]
+#[derive(FromRow, Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Datacenter {
     pub data_center_id: i32,
     pub data_center_name: String,
@@ -22,7 +25,9 @@ pub struct Datacenter {
     pub region: Option<String>,
     
     // Geographic Coordinates
+    #[sqlx(try_from = "DecimalColumn")]
     pub latitude: Option<Decimal>,
+    #[sqlx(try_from = "DecimalColumn")]
     pub longitude: Option<Decimal>,
     
     // Provider Information
@@ -31,8 +36,11 @@ pub struct Datacenter {
     
     // Facility Details
     pub tier_level: Option<String>, // ENUM: TIER_I, TIER_II, TIER_III, TIER_IV, UNKNOWN
+    #[sqlx(try_from = "DecimalColumn")]
     pub total_floor_space_sqm: Option<Decimal>,
+    #[sqlx(try_from = "DecimalColumn")]
     pub power_capacity_kw: Option<Decimal>,
+    #[sqlx(try_from = "DecimalColumn")]
     pub cooling_capacity_kw: Option<Decimal>,
     
     // Status and Management
@@ -65,13 +73,53 @@ pub struct Datacenter {
 impl Datacenter {
     pub const TABLE: &'static str = "datacenters";
     pub const KEY: &'static str = "data_center_id";
+    pub const SCHEMA: TableSchema = TableSchema {
+        table: Self::TABLE,
+        key: Self::KEY,
+        columns: &[
+            Column::integer("data_center_id"),
+            Column::string("data_center_name"),
+            Column::string("data_center_code"),
+            Column::string("description"),
+            Column::string("address"),
+            Column::string("city"),
+            Column::string("state_province"),
+            Column::string("country"),
+            Column::string("postal_code"),
+            Column::string("region"),
+            Column::float("latitude"),
+            Column::float("longitude"),
+            Column::string("provider"),
+            Column::string("provider_facility_id"),
+            Column::enumeration("tier_level", &["TIER_I", "TIER_II", "TIER_III", "TIER_IV", "UNKNOWN"]),
+            Column::float("total_floor_space_sqm"),
+            Column::float("power_capacity_kw"),
+            Column::float("cooling_capacity_kw"),
+            Column::enumeration("status", &["ACTIVE", "INACTIVE", "MAINTENANCE", "CONSTRUCTION", "DECOMMISSIONED"]),
+            Column::integer("total_racks"),
+            Column::integer("occupied_racks"),
+            Column::integer("total_servers"),
+            Column::string("facility_manager"),
+            Column::string("contact_phone"),
+            Column::string("contact_email"),
+            Column::string("emergency_contact"),
+            Column::string("emergency_phone"),
+            Column::string("timezone"),
+            Column::string("operating_hours"),
+            Column::json("tags"),
+            Column::json("metadata"),
+            Column::timestamp("created_at"),
+            Column::timestamp("updated_at"),
+        ],
+        relations: &[],
+    };
 }
 
 // ===================================================================
 // DATACENTER RACK MODEL
 // ===================================================================
 
-#[derive(FromRow, Debug, Clone, Serialize, Deserialize)]
+#[derive(FromRow, Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct DatacenterRack {
     pub rack_id: i32,
     pub data_center_id: i32,
@@ -123,13 +171,46 @@ pub struct DatacenterRack {
 impl DatacenterRack {
     pub const TABLE: &'static str = "datacenter_racks";
     pub const KEY: &'static str = "rack_id";
+    pub const SCHEMA: TableSchema = TableSchema {
+        table: Self::TABLE,
+        key: Self::KEY,
+        columns: &[
+            Column::integer("rack_id"),
+            Column::integer("data_center_id"),
+            Column::string("rack_name"),
+            Column::string("rack_code"),
+            Column::string("description"),
+            Column::integer("rack_height_u"),
+            Column::integer("rack_width_mm"),
+            Column::integer("rack_depth_mm"),
+            Column::string("row_name"),
+            Column::string("aisle_name"),
+            Column::string("room_name"),
+            Column::integer("floor_level"),
+            Column::integer("power_capacity_w"),
+            Column::integer("power_usage_w"),
+            Column::enumeration("cooling_type", &["AIR", "LIQUID", "HYBRID", "NONE"]),
+            Column::string("network_zone"),
+            Column::enumeration("status", &["ACTIVE", "INACTIVE", "MAINTENANCE", "RESERVED", "DECOMMISSIONED"]),
+            Column::integer("total_u_available"),
+            Column::integer("occupied_u"),
+            Column::integer("reserved_u"),
+            Column::integer("free_u"),
+            Column::enumeration("access_level", &["PUBLIC", "RESTRICTED", "HIGH_SECURITY"]),
+            Column::json("tags"),
+            Column::json("metadata"),
+            Column::timestamp("created_at"),
+            Column::timestamp("updated_at"),
+        ],
+        relations: &[],
+    };
 }
 
 // ===================================================================
 // DATACENTER RACK POSITION MODEL
 // ===================================================================
 
-#[derive(FromRow, Debug, Clone, Serialize, Deserialize)]
+#[derive(FromRow, Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct DatacenterRackPosition {
     pub rack_position_id: i32,
     pub rack_id: i32,
@@ -158,20 +239,38 @@ pub struct DatacenterRackPosition {
 impl DatacenterRackPosition {
     pub const TABLE: &'static str = "datacenter_rack_positions";
     pub const KEY: &'static str = "rack_position_id";
+    pub const SCHEMA: TableSchema = TableSchema {
+        table: Self::TABLE,
+        key: Self::KEY,
+        columns: &[
+            Column::integer("rack_position_id"),
+            Column::integer("rack_id"),
+            Column::integer("u_position"),
+            Column::enumeration("status", &["AVAILABLE", "OCCUPIED", "RESERVED", "BLOCKED"]),
+            Column::string("reserved_for"),
+            Column::string("reservation_notes"),
+            Column::integer("server_id"),
+            Column::enumeration("device_type", &["SERVER", "SWITCH", "STORAGE", "PDU", "UPS", "OTHER"]),
+            Column::string("notes"),
+            Column::timestamp("created_at"),
+            Column::timestamp("updated_at"),
+        ],
+        relations: &[],
+    };
 }
 
 // ===================================================================
 // COMPOSITE STRUCTURES
 // ===================================================================
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct DatacenterWithRacks {
     #[serde(flatten)]
     pub datacenter: Datacenter,
     pub racks: Vec<DatacenterRack>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct RackWithPositions {
     #[serde(flatten)]
     pub rack: DatacenterRack,
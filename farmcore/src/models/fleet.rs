@@ -0,0 +1,84 @@
+use sqlx::FromRow;
+
+// ===================================================================
+// FLEET INVENTORY STATS
+// ===================================================================
+//
+// Grouped counts behind the fleet gauges at /metrics/fleet. Cluster, datacenter and rack
+// labels are codes; an empty string means unassigned.
+
+#[derive(FromRow, Debug, Clone)]
+pub struct ServerGroupCount {
+    pub status: String,
+    pub server_type: String,
+    pub cluster: String,
+    pub datacenter: String,
+    pub count: i64,
+}
+
+/// ACTIVE servers whose agent has not reported within the stale threshold
+#[derive(FromRow, Debug, Clone)]
+pub struct StaleAgentCount {
+    pub cluster: String,
+    pub datacenter: String,
+    pub count: i64,
+}
+
+#[derive(FromRow, Debug, Clone)]
+pub struct GpuModelCount {
+    pub vendor: String,
+    pub model: String,
+    pub count: i64,
+}
+
+#[derive(FromRow, Debug, Clone)]
+pub struct DiskHealthCount {
+    /// PASSED, FAILED, or UNKNOWN when the agent could not read SMART
+    pub smart_health: String,
+    pub count: i64,
+}
+
+/// Height of a rack and the units taken by the servers placed in it
+#[derive(FromRow, Debug, Clone)]
+pub struct RackCapacity {
+    pub datacenter: String,
+    pub rack: String,
+    pub height_u: i64,
+    pub occupied_u: i64,
+}
+
+#[derive(FromRow, Debug, Clone)]
+pub struct VmGroupCount {
+    pub hypervisor_type: String,
+    pub vm_state: String,
+    pub count: i64,
+    pub vcpus: i64,
+    pub memory_mb: i64,
+}
+
+#[derive(FromRow, Debug, Clone)]
+pub struct SwitchGroupCount {
+    pub status: String,
+    pub switch_role: String,
+    pub count: i64,
+}
+
+#[derive(FromRow, Debug, Clone)]
+pub struct SwitchPortCount {
+    pub oper_status: String,
+    pub count: i64,
+}
+
+/// Everything the fleet exporter publishes, gathered in one pass
+#[derive(Debug, Clone, Default)]
+pub struct FleetStats {
+    pub servers: Vec<ServerGroupCount>,
+    pub stale_agents: Vec<StaleAgentCount>,
+    pub gpus: Vec<GpuModelCount>,
+    pub disks: Vec<DiskHealthCount>,
+    /// Empty on SQLite, which has no datacenter tables
+    pub racks: Vec<RackCapacity>,
+    pub vms: Vec<VmGroupCount>,
+    pub switches: Vec<SwitchGroupCount>,
+    pub switch_ports: Vec<SwitchPortCount>,
+}
@@ -0,0 +1,124 @@
+use serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
+use crate::models::query::{Column, TableSchema};
+use crate::models::{Datacenter, DatacenterRack, DatacenterRackPosition, Switch};
+
+// ===================================================================
+// BULK SITE IMPORT
+// ===================================================================
+
+/// Record types a bulk import can create. Rows are applied in this order so racks can
+/// name a datacenter, positions a rack and switches a position from the same document.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportKind {
+    Datacenters,
+    Racks,
+    Positions,
+    Switches,
+}
+
+impl ImportKind {
+    pub const NAMES: &'static [&'static str] = &["datacenters", "racks", "positions", "switches"];
+
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "datacenters" => Some(ImportKind::Datacenters),
+            "racks" => Some(ImportKind::Racks),
+            "positions" => Some(ImportKind::Positions),
+            "switches" => Some(ImportKind::Switches),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ImportKind::Datacenters => "datacenters",
+            ImportKind::Racks => "racks",
+            ImportKind::Positions => "positions",
+            ImportKind::Switches => "switches",
+        }
+    }
+
+    pub fn schema(self) -> &'static TableSchema {
+        match self {
+            ImportKind::Datacenters => &Datacenter::SCHEMA,
+            ImportKind::Racks => &DatacenterRack::SCHEMA,
+            ImportKind::Positions => &DatacenterRackPosition::SCHEMA,
+            ImportKind::Switches => &Switch::SCHEMA,
+        }
+    }
+
+    /// Extra fields that look up a parent by its natural key instead of its ID:
+    /// `data_center_code`, `rack_code` (within that datacenter) and `u_position` (within that rack)
+    pub fn references(self) -> &'static [Column] {
+        const DATACENTER_CODE: Column = Column::string("data_center_code");
+        const RACK_CODE: Column = Column::string("rack_code");
+        const U_POSITION: Column = Column::integer("u_position");
+
+        match self {
+            ImportKind::Datacenters => &[],
+            ImportKind::Racks => &[DATACENTER_CODE],
+            ImportKind::Positions => &[DATACENTER_CODE, RACK_CODE],
+            ImportKind::Switches => &[DATACENTER_CODE, RACK_CODE, U_POSITION],
+        }
+    }
+}
+
+/// One record of an import document, with its values already converted to the column types
+#[derive(Debug, Clone)]
+pub struct ImportRow {
+    pub kind: ImportKind,
+    /// CSV line number, or 1-based index within the YAML section
+    pub row: usize,
+    pub fields: serde_json::Map<String, serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct ImportRowError {
+    pub kind: ImportKind,
+    pub row: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub field: Option<String>,
+    pub message: String,
+}
+
+impl ImportRowError {
+    pub fn new(row: &ImportRow, field: Option<&str>, message: impl Into<String>) -> Self {
+        Self {
+            kind: row.kind,
+            row: row.row,
+            field: field.map(str::to_string),
+            message: message.into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, JsonSchema)]
+pub struct ImportCounts {
+    pub datacenters: usize,
+    pub racks: usize,
+    pub positions: usize,
+    pub switches: usize,
+}
+
+impl ImportCounts {
+    pub fn add(&mut self, kind: ImportKind) {
+        match kind {
+            ImportKind::Datacenters => self.datacenters += 1,
+            ImportKind::Racks => self.racks += 1,
+            ImportKind::Positions => self.positions += 1,
+            ImportKind::Switches => self.switches += 1,
+        }
+    }
+}
+
+/// Outcome of a bulk import. Nothing is committed unless every row is valid and
+/// `dry_run` is off; `created` counts the rows that were (or would have been) inserted.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct ImportReport {
+    pub dry_run: bool,
+    pub committed: bool,
+    pub created: ImportCounts,
+    pub errors: Vec<ImportRowError>,
+}
@@ -0,0 +1,227 @@
+use sqlx::FromRow;
+use serde::{Serialize, Deserialize};
+use schemars::JsonSchema;
+use crate::models::query::{Column, Relation, RelationColumn, TableSchema};
+
+// ===================================================================
+// INVENTORY SNAPSHOTS
+// ===================================================================
+//
+// Every inventory report that changed something is kept as a snapshot of the normalized
+// payload. The checksum is the SHA-256 of that payload, so an agent re-sending the same
+// inventory is recognised without comparing components.
+
+/// Snapshot metadata, as listed for a server
+#[derive(FromRow, Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct InventorySnapshotSummary {
+    pub snapshot_id: i32,
+    pub server_id: i32,
+    pub agent_version: Option<String>,
+    pub checksum: String,
+    pub created_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+#[derive(FromRow, Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct InventorySnapshot {
+    pub snapshot_id: i32,
+    pub server_id: i32,
+    pub agent_version: Option<String>,
+    /// The inventory payload as it was accepted
+    pub inventory_data: serde_json::Value,
+    pub checksum: String,
+    pub created_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+impl From<InventorySnapshot> for InventorySnapshotSummary {
+    fn from(snapshot: InventorySnapshot) -> Self {
+        Self {
+            snapshot_id: snapshot.snapshot_id,
+            server_id: snapshot.server_id,
+            agent_version: snapshot.agent_version,
+            checksum: snapshot.checksum,
+            created_at: snapshot.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum InventoryChangeType {
+    Added,
+    Removed,
+    Modified,
+}
+
+impl InventoryChangeType {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            InventoryChangeType::Added => "added",
+            InventoryChangeType::Removed => "removed",
+            InventoryChangeType::Modified => "modified",
+        }
+    }
+}
+
+/// One difference between two snapshots. Paths name list entries by their identifying
+/// field where there is one, e.g. `memory.dimms[slot=DIMM_A1].size_bytes`.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct InventoryDiffEntry {
+    pub path: String,
+    pub change: InventoryChangeType,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub old: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub new: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct InventorySnapshotDiff {
+    pub server_id: i32,
+    pub from: InventorySnapshotSummary,
+    pub to: InventorySnapshotSummary,
+    pub changes: Vec<InventoryDiffEntry>,
+}
+
+// ===================================================================
+// COMPONENT CHANGE HISTORY
+// ===================================================================
+//
+// Written by the inventory sync for every component it adds, removes or modifies on an
+// existing server (a new server's first inventory is only kept as a snapshot). Old and new
+// data hold the fields the sync compares, so a swapped DIMM shows up as a modified memory
+// slot with the old and new serial numbers.
+
+#[derive(FromRow, Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ServerInventoryChange {
+    pub change_id: i32,
+    pub server_id: i32,
+    pub component_type: String, // ENUM: cpu, memory, disk, network, route, gpu, power, bios, bmc, chassis
+    /// Socket, slot, serial, MAC, UUID... whatever the sync matched the component on
+    pub component_identifier: String,
+    pub change_type: String, // ENUM: added, removed, modified
+    pub old_data: Option<serde_json::Value>,
+    pub new_data: Option<serde_json::Value>,
+    pub detected_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+impl ServerInventoryChange {
+    pub const TABLE: &'static str = "server_inventory_changes";
+    pub const KEY: &'static str = "change_id";
+    pub const RELATIONS: &'static [Relation] = &[
+        Relation {
+            name: "server",
+            table: "servers",
+            foreign_key: "server_id",
+            parent_key: "server_id",
+            joins: &[],
+            columns: &[
+                RelationColumn::own(Column::string("server_name")),
+                RelationColumn::own(Column::string("serial_number")),
+                RelationColumn::own(Column::integer("cluster_id")),
+                RelationColumn::own(Column::integer("data_center_id")),
+            ],
+        },
+    ];
+    pub const SCHEMA: TableSchema = TableSchema {
+        table: Self::TABLE,
+        key: Self::KEY,
+        columns: &[
+            Column::integer("change_id"),
+            Column::integer("server_id"),
+            Column::enumeration("component_type", &["cpu", "memory", "disk", "network", "route", "gpu", "power", "bios", "bmc", "chassis"]),
+            Column::string("component_identifier"),
+            Column::enumeration("change_type", &["added", "removed", "modified"]),
+            Column::json("old_data"),
+            Column::json("new_data"),
+            Column::timestamp("detected_at"),
+        ],
+        relations: Self::RELATIONS,
+    };
+}
+
+// ===================================================================
+// MISSING COMPONENTS
+// ===================================================================
+//
+// Parts the inventory sync removed and no later report has shown again. The sync resolves an
+// entry when the part's serial, slot, PCI address, UUID or MAC is reported again.
+
+#[derive(FromRow, Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ServerMissingComponent {
+    pub missing_id: i32,
+    pub server_id: i32,
+    pub component_type: String, // ENUM: cpu, memory, disk, network, gpu, power
+    /// Identifier the sync matched the component on, as in the change history
+    pub component_identifier: String,
+    /// The component's entry in the last inventory that reported it
+    pub last_seen_data: serde_json::Value,
+    pub missing_since: Option<chrono::DateTime<chrono::Utc>>,
+    /// Set once the part is reported again; null while it is missing
+    pub resolved_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+impl ServerMissingComponent {
+    pub const TABLE: &'static str = "server_missing_components";
+    pub const KEY: &'static str = "missing_id";
+    pub const SCHEMA: TableSchema = TableSchema {
+        table: Self::TABLE,
+        key: Self::KEY,
+        columns: &[
+            Column::integer("missing_id"),
+            Column::integer("server_id"),
+            Column::enumeration("component_type", &["cpu", "memory", "disk", "network", "gpu", "power"]),
+            Column::string("component_identifier"),
+            Column::json("last_seen_data"),
+            Column::timestamp("missing_since"),
+            Column::timestamp("resolved_at"),
+        ],
+        relations: ServerInventoryChange::RELATIONS,
+    };
+}
+
+// ===================================================================
+// IDENTITY CONFLICTS
+// ===================================================================
+//
+// Inventory reports the sync could not attribute to a server without doubt: several servers
+// share identifiers with the report, or the one that does contradicts it. MATCHED and CREATED
+// reports were applied anyway; REJECTED ones were refused with 409 until someone sorts out
+// which server the machine is.
+
+#[derive(FromRow, Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ServerIdentityConflict {
+    pub conflict_id: i32,
+    /// Server the report was applied to; null when it was rejected
+    pub server_id: Option<i32>,
+    pub resolution: String, // ENUM: MATCHED, CREATED, REJECTED
+    /// Hostname the agent reported
+    pub hostname: Option<String>,
+    /// Score (0-100) of the best-matching existing server
+    pub confidence: i32,
+    /// Every server that shared an identifier with the report, best first: server_id, score,
+    /// matched_by, mismatched, and excluded when its system UUID differs
+    pub candidates: serde_json::Value,
+    pub detected_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// Null while the conflict is open
+    pub resolved_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+impl ServerIdentityConflict {
+    pub const TABLE: &'static str = "server_identity_conflicts";
+    pub const KEY: &'static str = "conflict_id";
+    pub const SCHEMA: TableSchema = TableSchema {
+        table: Self::TABLE,
+        key: Self::KEY,
+        columns: &[
+            Column::integer("conflict_id"),
+            Column::integer("server_id"),
+            Column::enumeration("resolution", &["MATCHED", "CREATED", "REJECTED"]),
+            Column::string("hostname"),
+            Column::integer("confidence"),
+            Column::json("candidates"),
+            Column::timestamp("detected_at"),
+            Column::timestamp("resolved_at"),
+        ],
+        relations: ServerInventoryChange::RELATIONS,
+    };
+}
@@ -1,10 +1,12 @@
 use serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
 use sqlx::FromRow;
+use crate::models::query::{Column, TableSchema};
 
 // ===================================================================
 // KUBERNETES CLUSTER MODEL
 // ===================================================================
-#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, JsonSchema)]
 pub struct KubernetesCluster {
     pub cluster_id: i32,
     pub cluster_name: String,
@@ -50,12 +52,60 @@ pub struct KubernetesCluster {
 
 impl KubernetesCluster {
     pub const TABLE: &'static str = "kubernetes_clusters";
+    pub const KEY: &'static str = "cluster_id";
+    pub const SCHEMA: TableSchema = TableSchema {
+        table: Self::TABLE,
+        key: Self::KEY,
+        columns: &[
+            Column::integer("cluster_id"),
+            Column::string("cluster_name"),
+            Column::string("cluster_uuid"),
+            Column::string("description"),
+            Column::string("cluster_version"),
+            Column::string("api_server_endpoint"),
+            Column::string("cluster_domain"),
+            Column::enumeration("distribution", &["vanilla", "k3s", "k0s", "rke", "rke2", "eks", "aks", "gke", "openshift", "rancher", "microk8s", "kubeadm"]),
+            Column::string("distribution_version"),
+            Column::string("service_cidr"),
+            Column::string("pod_cidr"),
+            Column::enumeration("cni_plugin", &["calico", "flannel", "weave", "cilium", "canal", "antrea", "kindnet", "kubenet"]),
+            Column::string("cni_version"),
+            Column::enumeration("container_runtime", &["containerd", "docker", "cri-o", "podman"]),
+            Column::string("runtime_version"),
+            Column::enumeration("cluster_state", &["initializing", "ready", "degraded", "offline", "upgrading", "error"]),
+            Column::enumeration("cluster_status", &["active", "inactive", "maintenance", "archived"]),
+            Column::boolean("is_ha_enabled"),
+            Column::integer("control_plane_nodes"),
+            Column::string("kubeconfig_path"),
+            Column::string("certificate_authority_data"),
+            Column::boolean("rbac_enabled"),
+            Column::boolean("network_policy_enabled"),
+            Column::boolean("pod_security_policy_enabled"),
+            Column::string("admission_controllers"),
+            Column::boolean("monitoring_enabled"),
+            Column::string("monitoring_stack"),
+            Column::boolean("logging_enabled"),
+            Column::string("logging_stack"),
+            Column::string("created_by"),
+            Column::string("managed_by"),
+            Column::string("organization"),
+            Column::enumeration("environment", &["development", "staging", "production", "testing"]),
+            Column::timestamp("created_at"),
+            Column::timestamp("updated_at"),
+            Column::timestamp("provisioned_at"),
+            Column::timestamp("last_health_check"),
+            Column::json("labels"),
+            Column::json("annotations"),
+            Column::string("tags"),
+        ],
+        relations: &[],
+    };
 }
 
 // ===================================================================
 // KUBERNETES NODE GROUP MODEL
 // ===================================================================
-#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, JsonSchema)]
 pub struct KubernetesNodeGroup {
     pub node_group_id: i32,
     pub cluster_id: i32,
@@ -86,7 +136,7 @@ impl KubernetesNodeGroup {
 // ===================================================================
 // KUBERNETES NODE MODEL
 // ===================================================================
-#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, JsonSchema)]
 pub struct KubernetesNode {
     pub k8s_node_id: i32,
     pub cluster_id: i32,
@@ -138,7 +188,7 @@ impl KubernetesNode {
 // ===================================================================
 // KUBERNETES NAMESPACE MODEL
 // ===================================================================
-#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, JsonSchema)]
 pub struct KubernetesNamespace {
     pub namespace_id: i32,
     pub cluster_id: i32,
@@ -175,7 +225,7 @@ impl KubernetesNamespace {
 // ===================================================================
 // KUBERNETES WORKLOAD MODEL
 // ===================================================================
-#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, JsonSchema)]
 pub struct KubernetesWorkload {
     pub workload_id: i32,
     pub cluster_id: i32,
@@ -226,7 +276,7 @@ impl KubernetesWorkload {
 // ===================================================================
 // KUBERNETES POD MODEL
 // ===================================================================
-#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, JsonSchema)]
 pub struct KubernetesPod {
     pub pod_id: i32,
     pub cluster_id: i32,
@@ -269,12 +319,50 @@ pub struct KubernetesPod {
 
 impl KubernetesPod {
     pub const TABLE: &'static str = "kubernetes_pods";
+    pub const KEY: &'static str = "pod_id";
+    pub const SCHEMA: TableSchema = TableSchema {
+        table: Self::TABLE,
+        key: Self::KEY,
+        columns: &[
+            Column::integer("pod_id"),
+            Column::integer("cluster_id"),
+            Column::integer("namespace_id"),
+            Column::integer("workload_id"),
+            Column::integer("k8s_node_id"),
+            Column::string("pod_name"),
+            Column::string("pod_uid"),
+            Column::string("pod_ip"),
+            Column::string("host_ip"),
+            Column::enumeration("pod_phase", &["pending", "running", "succeeded", "failed", "unknown"]),
+            Column::enumeration("pod_state", &["waiting", "running", "terminated"]),
+            Column::boolean("is_ready"),
+            Column::boolean("initialized"),
+            Column::boolean("containers_ready"),
+            Column::boolean("pod_scheduled"),
+            Column::integer("container_count"),
+            Column::integer("init_container_count"),
+            Column::integer("restart_count"),
+            Column::enumeration("qos_class", &["Guaranteed", "Burstable", "BestEffort"]),
+            Column::string("cpu_request"),
+            Column::string("memory_request"),
+            Column::string("cpu_limit"),
+            Column::string("memory_limit"),
+            Column::json("labels"),
+            Column::json("annotations"),
+            Column::timestamp("created_at"),
+            Column::timestamp("updated_at"),
+            Column::timestamp("started_at"),
+            Column::timestamp("finished_at"),
+            Column::timestamp("deleted_at"),
+        ],
+        relations: &[],
+    };
 }
 
 // ===================================================================
 // KUBERNETES SERVICE MODEL
 // ===================================================================
-#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, JsonSchema)]
 pub struct KubernetesService {
     pub service_id: i32,
     pub cluster_id: i32,
@@ -309,7 +397,7 @@ impl KubernetesService {
 // ===================================================================
 // KUBERNETES INGRESS MODEL
 // ===================================================================
-#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, JsonSchema)]
 pub struct KubernetesIngress {
     pub ingress_id: i32,
     pub cluster_id: i32,
@@ -336,7 +424,7 @@ impl KubernetesIngress {
 // ===================================================================
 // KUBERNETES EVENT MODEL
 // ===================================================================
-#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, JsonSchema)]
 pub struct KubernetesEvent {
     pub event_id: i32,
     pub cluster_id: i32,
@@ -368,7 +456,7 @@ impl KubernetesEvent {
 // ===================================================================
 // KUBERNETES METRIC MODEL
 // ===================================================================
-#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, JsonSchema)]
 pub struct KubernetesMetric {
     pub metric_id: i32,
     pub cluster_id: i32,
@@ -406,7 +494,7 @@ impl KubernetesMetric {
 // ===================================================================
 // KUBERNETES SECRET MODEL
 // ===================================================================
-#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, JsonSchema)]
 pub struct KubernetesSecret {
     pub secret_id: i32,
     pub cluster_id: i32,
@@ -431,7 +519,7 @@ impl KubernetesSecret {
 // ===================================================================
 // KUBERNETES CONFIGMAP MODEL
 // ===================================================================
-#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, JsonSchema)]
 pub struct KubernetesConfigMap {
     pub configmap_id: i32,
     pub cluster_id: i32,
@@ -456,7 +544,7 @@ impl KubernetesConfigMap {
 // ===================================================================
 // COMPOSITE STRUCTURES
 // ===================================================================
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ClusterWithDetails {
     pub cluster: KubernetesCluster,
     pub node_groups: Vec<KubernetesNodeGroup>,
@@ -464,7 +552,7 @@ pub struct ClusterWithDetails {
     pub namespaces: Vec<KubernetesNamespace>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct NamespaceWithResources {
     pub namespace: KubernetesNamespace,
     pub workloads: Vec<KubernetesWorkload>,
@@ -473,7 +561,7 @@ pub struct NamespaceWithResources {
     pub pods: Vec<KubernetesPod>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct NodeWithMetrics {
     pub node: KubernetesNode,
     pub metrics: Option<KubernetesMetric>,
@@ -0,0 +1,44 @@
+use serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
+use std::collections::BTreeMap;
+
+// ===================================================================
+// SERVER MERGE
+// ===================================================================
+
+/// Request body for merging duplicate records into a server
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+pub struct MergeServers {
+    /// Records to fold into the server and delete
+    pub server_ids: Vec<i32>,
+}
+
+/// Outcome of a merge. Component tables hold the hardware as last reported, so the surviving
+/// server keeps its own rows of each kind and takes a merged server's only where it has none;
+/// VMs, credentials and history are moved.
+#[derive(Debug, Clone, Default, Serialize, JsonSchema)]
+pub struct ServerMergeReport {
+    pub server_id: i32,
+    pub merged_server_ids: Vec<i32>,
+    pub dry_run: bool,
+    /// Rows moved (or that would have been) to the surviving server, by table
+    pub moved: BTreeMap<String, u64>,
+    /// Rows dropped because the surviving server keeps the other record's, by table
+    pub discarded: BTreeMap<String, u64>,
+    /// Open identity conflicts that named a merged server and were closed
+    pub resolved_conflicts: u64,
+}
+
+impl ServerMergeReport {
+    pub fn count_moved(&mut self, table: &str, rows: u64) {
+        if rows > 0 {
+            *self.moved.entry(table.to_string()).or_default() += rows;
+        }
+    }
+
+    pub fn count_discarded(&mut self, table: &str, rows: u64) {
+        if rows > 0 {
+            *self.discarded.entry(table.to_string()).or_default() += rows;
+        }
+    }
+}
@@ -7,6 +7,14 @@ pub mod kubernetes;
 pub mod datacenter;
 pub mod cluster;
 pub mod switch;
+pub mod auth;
+pub mod audit;
+pub mod import;
+pub mod topology;
+pub mod webhook;
+pub mod fleet;
+pub mod inventory;
+pub mod merge;
 
 pub use server::*;
 pub use components::*;
@@ -16,4 +24,12 @@ pub use vm::*;
 pub use kubernetes::*;
 pub use datacenter::*;
 pub use cluster::*;
-pub use switch::*;
\ No newline at end of file
+pub use switch::*;
+pub use auth::*;
+pub use audit::*;
+pub use import::*;
+pub use topology::*;
+pub use webhook::*;
+pub use fleet::*;
+pub use inventory::*;
+pub use merge::*;
\ No newline at end of file
@@ -1,3 +1,5 @@
+use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime, Utc};
+
 #[derive(Debug, Default, Clone)]
 pub struct QueryOptions {
     pub columns: Option<Vec<String>>,
@@ -9,17 +11,391 @@ pub struct QueryOptions {
 
 #[derive(Debug, Clone)]
 pub struct WhereCondition {
-    pub column: String,
-    pub operator: String,
-    pub value: QueryValue,
-    pub logical_operator: Option<String>, // NEW: AND/OR for this condition
+    pub predicate: Predicate,
+    pub logical_operator: Option<String>, // AND/OR joining this condition to the previous one
 }
 
 #[derive(Debug, Clone)]
+pub enum Predicate {
+    /// `column operator value`. IN/NOT IN take a `QueryValue::List`, BETWEEN a two-element
+    /// list, and IS NULL/IS NOT NULL take `QueryValue::Null`.
+    Compare {
+        column: String,
+        operator: String,
+        value: QueryValue,
+    },
+    /// Conditions evaluated together in parentheses
+    Group(Vec<WhereCondition>),
+}
+
+impl WhereCondition {
+    pub fn compare(column: &str, operator: &str, value: QueryValue, logical_operator: Option<String>) -> Self {
+        Self {
+            predicate: Predicate::Compare {
+                column: column.to_string(),
+                operator: operator.to_string(),
+                value,
+            },
+            logical_operator,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub enum QueryValue {
     String(String),
     Integer(i64),
     Float(f64),
     Boolean(bool),
+    Timestamp(NaiveDateTime),
+    List(Vec<QueryValue>),
     Null,
-}
\ No newline at end of file
+}
+
+impl QueryValue {
+    pub fn into_json(self) -> serde_json::Value {
+        match self {
+            QueryValue::String(s) => serde_json::Value::String(s),
+            QueryValue::Integer(i) => serde_json::Value::from(i),
+            QueryValue::Float(f) => serde_json::Value::from(f),
+            QueryValue::Boolean(b) => serde_json::Value::Bool(b),
+            QueryValue::Timestamp(t) => serde_json::Value::String(t.and_utc().to_rfc3339()),
+            QueryValue::List(values) => serde_json::Value::Array(values.into_iter().map(QueryValue::into_json).collect()),
+            QueryValue::Null => serde_json::Value::Null,
+        }
+    }
+}
+
+/// Operators that take no value
+pub const NULL_OPERATORS: &[&str] = &["IS NULL", "IS NOT NULL"];
+/// Operators that take a list of values
+pub const LIST_OPERATORS: &[&str] = &["IN", "NOT IN"];
+
+/// Comparison operators for numeric and timestamp columns
+const ORDERED_OPERATORS: &[&str] = &["=", "!=", ">", "<", ">=", "<=", "IN", "NOT IN", "BETWEEN", "IS NULL", "IS NOT NULL"];
+/// Comparison operators for free-text columns
+const TEXT_OPERATORS: &[&str] = &["=", "!=", "LIKE", "NOT LIKE", "IN", "NOT IN", "IS NULL", "IS NOT NULL"];
+/// Comparison operators for ENUMs
+const ENUM_OPERATORS: &[&str] = &["=", "!=", "IN", "NOT IN", "IS NULL", "IS NOT NULL"];
+/// Comparison operators for booleans
+const BOOLEAN_OPERATORS: &[&str] = &["=", "!=", "IS NULL", "IS NOT NULL"];
+
+/// Every operator a column may allow; the query builder refuses anything else
+pub const COMPARISON_OPERATORS: &[&str] = &[
+    "=", "!=", ">", "<", ">=", "<=", "LIKE", "NOT LIKE", "IN", "NOT IN", "BETWEEN", "IS NULL", "IS NOT NULL",
+];
+
+/// SQL type of a column, as far as filtering is concerned
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ColumnType {
+    Integer,
+    Float,
+    Boolean,
+    String,
+    Enum(&'static [&'static str]),
+    Timestamp,
+    Json,
+}
+
+/// A column that list queries may select and filter on
+#[derive(Debug, Clone, Copy)]
+pub struct Column {
+    pub name: &'static str,
+    pub column_type: ColumnType,
+    pub operators: &'static [&'static str],
+}
+
+impl Column {
+    pub const fn integer(name: &'static str) -> Self {
+        Self { name, column_type: ColumnType::Integer, operators: ORDERED_OPERATORS }
+    }
+
+    pub const fn float(name: &'static str) -> Self {
+        Self { name, column_type: ColumnType::Float, operators: ORDERED_OPERATORS }
+    }
+
+    pub const fn boolean(name: &'static str) -> Self {
+        Self { name, column_type: ColumnType::Boolean, operators: BOOLEAN_OPERATORS }
+    }
+
+    pub const fn string(name: &'static str) -> Self {
+        Self { name, column_type: ColumnType::String, operators: TEXT_OPERATORS }
+    }
+
+    pub const fn enumeration(name: &'static str, values: &'static [&'static str]) -> Self {
+        Self { name, column_type: ColumnType::Enum(values), operators: ENUM_OPERATORS }
+    }
+
+    pub const fn timestamp(name: &'static str) -> Self {
+        Self { name, column_type: ColumnType::Timestamp, operators: ORDERED_OPERATORS }
+    }
+
+    /// JSON columns can be selected and null-checked but not otherwise filtered on
+    pub const fn json(name: &'static str) -> Self {
+        Self { name, column_type: ColumnType::Json, operators: NULL_OPERATORS }
+    }
+
+    /// JSON columns have no meaningful order
+    pub fn sortable(&self) -> bool {
+        !matches!(self.column_type, ColumnType::Json)
+    }
+
+    pub fn allows(&self, operator: &str) -> bool {
+        self.operators.contains(&operator)
+    }
+
+    /// Convert a query-string term into a value of this column's type
+    pub fn parse_value(&self, term: &str, operator: &str) -> Result<QueryValue, String> {
+        match self.column_type {
+            ColumnType::Integer => term.parse::<i64>()
+                .map(QueryValue::Integer)
+                .map_err(|_| format!("'{}' is not an integer", term)),
+            ColumnType::Float => term.parse::<f64>()
+                .map(QueryValue::Float)
+                .map_err(|_| format!("'{}' is not a number", term)),
+            ColumnType::Boolean => match term.to_ascii_lowercase().as_str() {
+                "true" | "1" => Ok(QueryValue::Boolean(true)),
+                "false" | "0" => Ok(QueryValue::Boolean(false)),
+                _ => Err(format!("'{}' is not a boolean", term)),
+            },
+            ColumnType::String if operator.ends_with("LIKE") => Ok(QueryValue::String(format!("%{}%", term))),
+            ColumnType::String => Ok(QueryValue::String(term.to_string())),
+            // ENUM comparisons are case-insensitive in MySQL, so accept any case and bind the canonical value
+            ColumnType::Enum(values) => values.iter()
+                .find(|value| value.eq_ignore_ascii_case(term))
+                .map(|value| QueryValue::String(value.to_string()))
+                .ok_or_else(|| format!("'{}' is not one of {}", term, values.join(", "))),
+            ColumnType::Timestamp => parse_timestamp(term)
+                .map(QueryValue::Timestamp)
+                .ok_or_else(|| format!(
+                    "'{}' is not a timestamp (expected RFC 3339, 'YYYY-MM-DD HH:MM:SS', 'YYYY-MM-DD' or a relative date such as 'now-1d')",
+                    term
+                )),
+            ColumnType::Json => Err("JSON columns cannot be filtered".to_string()),
+        }
+    }
+
+    /// Convert a value written by a client (a CSV cell, a YAML or JSON scalar) into this
+    /// column's type, the way it serializes in API responses
+    pub fn json_value(&self, value: serde_json::Value) -> Result<serde_json::Value, String> {
+        use serde_json::Value;
+
+        match (self.column_type, value) {
+            (_, Value::Null) => Ok(Value::Null),
+            (ColumnType::Json, Value::String(text)) => serde_json::from_str(&text)
+                .map_err(|_| format!("'{}' is not valid JSON", text)),
+            (ColumnType::Json, value) => Ok(value),
+            (_, Value::String(text)) => self.parse_value(&text, "=").map(QueryValue::into_json),
+            // YAML reads unquoted codes such as `rack_code: 12` as numbers
+            (ColumnType::String, value @ (Value::Number(_) | Value::Bool(_))) => Ok(Value::String(value.to_string())),
+            (ColumnType::Integer, Value::Number(n)) if n.is_i64() => Ok(Value::Number(n)),
+            (ColumnType::Float, Value::Number(n)) => Ok(Value::Number(n)),
+            (ColumnType::Boolean, Value::Bool(b)) => Ok(Value::Bool(b)),
+            (_, value) => Err(format!("{} is not a valid value for {}", value, self.name)),
+        }
+    }
+}
+
+/// Timestamps are stored in UTC, so RFC 3339 input is converted to UTC and the rest is taken as UTC
+fn parse_timestamp(term: &str) -> Option<NaiveDateTime> {
+    if let Some(relative) = parse_relative_timestamp(term) {
+        return Some(relative);
+    }
+    if let Ok(dt) = DateTime::parse_from_rfc3339(term) {
+        return Some(dt.naive_utc());
+    }
+    if let Ok(dt) = NaiveDateTime::parse_from_str(term, "%Y-%m-%d %H:%M:%S") {
+        return Some(dt);
+    }
+    if let Ok(dt) = NaiveDateTime::parse_from_str(term, "%Y-%m-%dT%H:%M:%S") {
+        return Some(dt);
+    }
+    NaiveDate::parse_from_str(term, "%Y-%m-%d").ok().and_then(|d| d.and_hms_opt(0, 0, 0))
+}
+
+/// Relative dates: `now`, or `now` plus or minus an amount in s, m, h, d or w (e.g. `now-1d`, `now+30m`).
+/// Resolved once when the query is parsed and bound like any other timestamp.
+fn parse_relative_timestamp(term: &str) -> Option<NaiveDateTime> {
+    let rest = term.trim().strip_prefix("now")?;
+    let now = Utc::now().naive_utc();
+    if rest.is_empty() {
+        return Some(now);
+    }
+
+    let (sign, offset) = if let Some(offset) = rest.strip_prefix('+') {
+        (1, offset)
+    } else if let Some(offset) = rest.strip_prefix('-') {
+        (-1, offset)
+    } else {
+        return None;
+    };
+    let unit = offset.chars().last()?;
+    let amount: i64 = offset.strip_suffix(unit)?.parse().ok()?;
+    let duration = match unit {
+        's' => Duration::try_seconds(amount)?,
+        'm' => Duration::try_minutes(amount)?,
+        'h' => Duration::try_hours(amount)?,
+        'd' => Duration::try_days(amount)?,
+        'w' => Duration::try_weeks(amount)?,
+        _ => return None,
+    };
+    now.checked_add_signed(duration * sign)
+}
+
+/// Column metadata for a table exposed through list queries. Only the columns listed
+/// here can appear in `columns=`, filters, or search criteria; `relations` add
+/// `name.column` criteria over child tables.
+#[derive(Debug, Clone, Copy)]
+pub struct TableSchema {
+    pub table: &'static str,
+    /// Integer primary key; orders and bounds cursor pagination
+    pub key: &'static str,
+    pub columns: &'static [Column],
+    pub relations: &'static [Relation],
+}
+
+impl TableSchema {
+    pub fn column(&self, name: &str) -> Option<&Column> {
+        self.columns.iter().find(|column| column.name == name)
+    }
+
+    pub fn column_names(&self) -> Vec<&'static str> {
+        self.columns.iter().map(|column| column.name).collect()
+    }
+
+    pub fn sortable_column_names(&self) -> Vec<&'static str> {
+        self.columns.iter().filter(|column| column.sortable()).map(|column| column.name).collect()
+    }
+
+    /// Resolve a filter or search column: a plain column, `relation.column` or `relation.count`
+    pub fn filter(&self, name: &str) -> Option<FilterTarget<'_>> {
+        let Some((relation, column)) = name.split_once('.') else {
+            return self.column(name).map(FilterTarget::Column);
+        };
+        let relation = self.relations.iter().find(|r| r.name == relation)?;
+        if column == COUNT_COLUMN.name {
+            return Some(FilterTarget::Count(relation));
+        }
+        relation.column(column).map(|related| FilterTarget::Related(relation, related))
+    }
+
+    /// Every name `filter` accepts
+    pub fn filter_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.columns.iter().map(|column| column.name.to_string()).collect();
+        for relation in self.relations {
+            names.extend(relation.columns.iter().map(|related| format!("{}.{}", relation.name, related.column.name)));
+            names.push(format!("{}.{}", relation.name, COUNT_COLUMN.name));
+        }
+        names
+    }
+}
+
+/// A table joined onto a relation's rows, e.g. the component type behind each installed part
+#[derive(Debug, Clone, Copy)]
+pub struct RelationJoin {
+    pub table: &'static str,
+    pub alias: &'static str,
+    /// Join condition over the relation's aliases; a fixed SQL fragment, never user input
+    pub on: &'static str,
+}
+
+/// An attribute of a related row, read from the relation's own table (alias `c`) or from one of its joins
+#[derive(Debug, Clone, Copy)]
+pub struct RelationColumn {
+    pub source: &'static str,
+    pub column: Column,
+}
+
+impl RelationColumn {
+    pub const fn own(column: Column) -> Self {
+        Self { source: RELATION_ALIAS, column }
+    }
+
+    pub const fn joined(source: &'static str, column: Column) -> Self {
+        Self { source, column }
+    }
+}
+
+/// Alias of a relation's own table inside its subquery
+pub const RELATION_ALIAS: &str = "c";
+
+/// Child rows of a table that list queries can search through, addressed as `name.column`
+/// (e.g. `gpu.model`) or `name.count`. Criteria compile to EXISTS / COUNT(*) subqueries
+/// correlated on `foreign_key = parent_key`.
+#[derive(Debug, Clone, Copy)]
+pub struct Relation {
+    pub name: &'static str,
+    pub table: &'static str,
+    pub foreign_key: &'static str,
+    pub parent_key: &'static str,
+    pub joins: &'static [RelationJoin],
+    pub columns: &'static [RelationColumn],
+}
+
+impl Relation {
+    pub fn column(&self, name: &str) -> Option<&RelationColumn> {
+        self.columns.iter().find(|related| related.column.name == name)
+    }
+}
+
+/// Comparison operators for a relation's row count
+const COUNT_OPERATORS: &[&str] = &["=", "!=", ">", "<", ">=", "<=", "IN", "NOT IN", "BETWEEN"];
+
+/// The `count` pseudo-column every relation has
+const COUNT_COLUMN: Column = Column { name: "count", column_type: ColumnType::Integer, operators: COUNT_OPERATORS };
+
+/// What a filter or search column name refers to
+#[derive(Debug, Clone, Copy)]
+pub enum FilterTarget<'a> {
+    /// A column of the table itself
+    Column(&'a Column),
+    /// An attribute of a related row: matches when at least one related row satisfies the comparison
+    Related(&'a Relation, &'a RelationColumn),
+    /// The number of related rows
+    Count(&'a Relation),
+}
+
+impl FilterTarget<'_> {
+    pub fn column(&self) -> &Column {
+        match self {
+            FilterTarget::Column(column) => column,
+            FilterTarget::Related(_, related) => &related.column,
+            FilterTarget::Count(_) => &COUNT_COLUMN,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Resolve `term` and check it lands `offset` from the clock at the time of the call
+    fn assert_relative(term: &str, offset: Duration) {
+        let before = Utc::now().naive_utc();
+        let resolved = parse_relative_timestamp(term).unwrap_or_else(|| panic!("{} did not parse", term));
+        let after = Utc::now().naive_utc();
+        assert!(before + offset <= resolved && resolved <= after + offset, "{} resolved to {}", term, resolved);
+    }
+
+    #[test]
+    fn relative_timestamps_are_offsets_from_now() {
+        assert_relative("now", Duration::zero());
+        assert_relative("now-1d", -Duration::days(1));
+        assert_relative("now+30m", Duration::minutes(30));
+        assert_relative(" now-2w ", -Duration::weeks(2));
+    }
+
+    #[test]
+    fn malformed_relative_timestamps_are_rejected() {
+        for term in ["now-1y", "now*1d", "now1d", "now-", "now-d", "nowish", "yesterday"] {
+            assert_eq!(parse_relative_timestamp(term), None, "{}", term);
+        }
+    }
+
+    #[test]
+    fn a_multibyte_suffix_does_not_panic() {
+        for term in ["now€", "now-1€", "now+é", "now-1日"] {
+            assert_eq!(parse_relative_timestamp(term), None, "{}", term);
+        }
+    }
+}
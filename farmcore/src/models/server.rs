@@ -1,8 +1,10 @@
 use sqlx::{FromRow};
 use serde::{Serialize, Deserialize};
+use schemars::JsonSchema;
+use crate::models::query::{Column, Relation, RelationColumn, RelationJoin, TableSchema};
 
 // Server details
-#[derive(FromRow, Default, Debug, Clone, Serialize, Deserialize)]
+#[derive(FromRow, Default, Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Server {
     pub server_id: i32,
     pub agent_version: Option<String>,
@@ -27,11 +29,13 @@ pub struct Server {
     pub state: Option<String>, // ENUM in DB: 'NEW', 'ONBOARDING', 'PROVISIONING', 'RUNNING', 'SUSPENDED', 'DEPROVISIONING', 'FAILED'
     pub status: Option<String>, // ENUM in DB: 'ACTIVE', 'INACTIVE', 'MAINTENANCE', 'RMA', 'DECOMMISSIONED'
     pub sub_cluster_id: Option<i32>,
+    /// SMBIOS system UUID, lowercase; null when the agent could not read one
+    pub system_uuid: Option<String>,
     pub updated_at: Option<chrono::DateTime<chrono::Utc>>,
 }
 
 // Server credential details
-#[derive(FromRow, Debug, Clone, Serialize, Deserialize)]
+#[derive(FromRow, Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ServerCredential {
     pub credential_id: i32,
     pub credential_type: String, // BMC or OS
@@ -40,7 +44,7 @@ pub struct ServerCredential {
 }
 
 // Motherboard component details
-#[derive(FromRow, Debug, Clone, Serialize, Deserialize)]
+#[derive(FromRow, Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ServerMotherboardDetail {
     // Server-specific motherboard data (from server_motherboards table)
     pub motherboard_id: i32,
@@ -58,7 +62,7 @@ pub struct ServerMotherboardDetail {
 }
 
 // BMC interface component details
-#[derive(FromRow, Debug, Clone, Serialize, Deserialize)]
+#[derive(FromRow, Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ServerBmcDetail {
     pub bmc_interface_id: i32,
     pub name: String,
@@ -84,7 +88,7 @@ pub struct ServerBmcDetail {
 }
 
 // Complete server details with all components
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ServerWithAllComponents {
     #[serde(flatten)]
     pub server: Server,
@@ -92,14 +96,16 @@ pub struct ServerWithAllComponents {
     pub memory: Vec<ServerMemoryDetail>,
     pub disks: Vec<ServerDiskDetail>,
     pub network_interfaces: Vec<ServerNetworkDetail>,
+    pub network_routes: Vec<ServerNetworkRoute>,
     pub gpus: Vec<ServerGpuDetail>,
+    pub power_supplies: Vec<ServerPowerSupplyDetail>,
     pub bmc_interfaces: Vec<ServerBmcDetail>,
     pub credentials: Vec<ServerCredential>,
     pub motherboard_detail: Option<ServerMotherboardDetail>,
 }
 
 // CPU component details
-#[derive(FromRow, Debug, Clone, Serialize, Deserialize)]
+#[derive(FromRow, Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ServerCpuDetail {
     // Server-specific CPU data
     pub cpu_id: i32,
@@ -117,7 +123,7 @@ pub struct ServerCpuDetail {
 }
 
 // Memory component details
-#[derive(FromRow, Debug, Clone, Serialize, Deserialize)]
+#[derive(FromRow, Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ServerMemoryDetail {
     // Server-specific memory data
     pub dimm_id: i32,
@@ -134,7 +140,7 @@ pub struct ServerMemoryDetail {
 }
 
 // Disk component details
-#[derive(FromRow, Debug, Clone, Serialize, Deserialize)]
+#[derive(FromRow, Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ServerDiskDetail {
     // Server-specific disk data
     pub disk_id: i32,
@@ -153,7 +159,7 @@ pub struct ServerDiskDetail {
 }
 
 // GPU component details
-#[derive(FromRow, Debug, Clone, Serialize, Deserialize)]
+#[derive(FromRow, Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ServerGpuDetail {
     // Server-specific GPU data
     pub gpu_id: i32,
@@ -166,8 +172,37 @@ pub struct ServerGpuDetail {
     pub vram_mb: Option<i32>,
 }
 
+// Power supply details; the readings are from the latest inventory
+#[derive(FromRow, Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ServerPowerSupplyDetail {
+    pub psu_id: i32,
+    pub name: Option<String>,
+    pub manufacturer: Option<String>,
+    pub model: Option<String>,
+    pub serial_number: Option<String>,
+    pub part_number: Option<String>,
+    pub max_power_watts: Option<i32>,
+    pub efficiency_rating: Option<String>,
+    pub status: Option<String>,
+    pub input_voltage: Option<f64>,
+    pub input_current: Option<f64>,
+    pub output_voltage: Option<f64>,
+    pub output_current: Option<f64>,
+    pub temperature_c: Option<i32>,
+    pub fan_speed_rpm: Option<i32>,
+}
+
+// Routing table entry
+#[derive(FromRow, Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ServerNetworkRoute {
+    pub route_id: i32,
+    pub destination: String,
+    pub gateway: Option<String>,
+    pub interface_name: String,
+}
+
 // Network interface component details
-#[derive(FromRow, Debug, Clone, Serialize, Deserialize)]
+#[derive(FromRow, Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ServerNetworkDetail {
     // Server-specific network interface data
     pub interface_id: i32,
@@ -201,4 +236,125 @@ pub struct ServerNetworkDetail {
 impl Server {
     pub const TABLE: &'static str = "servers";
     pub const KEY: &'static str = "server_id";
+    /// Installed components searchable as `gpu.model`, `memory.count`, `disk.smart_health`, `nic.switch_name`, ...
+    pub const RELATIONS: &'static [Relation] = &[
+        Relation {
+            name: "gpu",
+            table: "server_gpus",
+            foreign_key: "server_id",
+            parent_key: Self::KEY,
+            joins: &[
+                RelationJoin { table: "component_gpu_types", alias: "t", on: "t.`component_gpu_id` = c.`component_gpu_id`" },
+            ],
+            columns: &[
+                RelationColumn::own(Column::string("pci_address")),
+                RelationColumn::own(Column::string("driver_version")),
+                RelationColumn::own(Column::string("uuid")),
+                RelationColumn::joined("t", Column::string("vendor")),
+                RelationColumn::joined("t", Column::string("model")),
+                RelationColumn::joined("t", Column::integer("vram_mb")),
+            ],
+        },
+        Relation {
+            name: "memory",
+            table: "server_memory_dimms",
+            foreign_key: "server_id",
+            parent_key: Self::KEY,
+            joins: &[
+                RelationJoin { table: "component_memory_types", alias: "t", on: "t.`component_memory_id` = c.`component_memory_id`" },
+            ],
+            columns: &[
+                RelationColumn::own(Column::string("slot")),
+                RelationColumn::own(Column::string("serial_number")),
+                RelationColumn::joined("t", Column::string("manufacturer")),
+                RelationColumn::joined("t", Column::string("part_number")),
+                RelationColumn::joined("t", Column::integer("size_bytes")),
+                RelationColumn::joined("t", Column::string("mem_type")),
+                RelationColumn::joined("t", Column::integer("speed_mt_s")),
+            ],
+        },
+        Relation {
+            name: "disk",
+            table: "server_disks",
+            foreign_key: "server_id",
+            parent_key: Self::KEY,
+            joins: &[
+                RelationJoin { table: "component_disk_types", alias: "t", on: "t.`component_disk_id` = c.`component_disk_id`" },
+            ],
+            columns: &[
+                RelationColumn::own(Column::string("name")),
+                RelationColumn::own(Column::string("dev_path")),
+                RelationColumn::own(Column::string("serial")),
+                RelationColumn::own(Column::string("firmware_version")),
+                RelationColumn::own(Column::string("smart_health")),
+                RelationColumn::joined("t", Column::string("manufacturer")),
+                RelationColumn::joined("t", Column::string("model")),
+                RelationColumn::joined("t", Column::integer("size_bytes")),
+                RelationColumn::joined("t", Column::boolean("rotational")),
+                RelationColumn::joined("t", Column::string("bus_type")),
+            ],
+        },
+        Relation {
+            name: "nic",
+            table: "server_network_interfaces",
+            foreign_key: "server_id",
+            parent_key: Self::KEY,
+            joins: &[
+                RelationJoin { table: "component_network_types", alias: "t", on: "t.`component_network_id` = c.`component_network_id`" },
+                RelationJoin { table: "switch_ports", alias: "sp", on: "sp.`switch_port_id` = c.`switch_port_id`" },
+                RelationJoin { table: "switches", alias: "sw", on: "sw.`switch_id` = sp.`switch_id`" },
+            ],
+            columns: &[
+                RelationColumn::own(Column::string("name")),
+                RelationColumn::own(Column::string("mac_address")),
+                RelationColumn::own(Column::string("ip_address")),
+                RelationColumn::own(Column::integer("mtu")),
+                RelationColumn::own(Column::integer("speed_mbps")),
+                RelationColumn::own(Column::string("firmware_version")),
+                RelationColumn::own(Column::string("pci_address")),
+                RelationColumn::own(Column::boolean("is_primary")),
+                RelationColumn::own(Column::enumeration("interface_type", &["REGULAR", "MANAGEMENT"])),
+                RelationColumn::own(Column::string("bond_group")),
+                RelationColumn::own(Column::string("bond_master")),
+                RelationColumn::own(Column::integer("switch_port_id")),
+                RelationColumn::joined("t", Column::string("vendor_name")),
+                RelationColumn::joined("t", Column::string("device_name")),
+                RelationColumn::joined("t", Column::string("driver")),
+                RelationColumn::joined("t", Column::integer("max_speed_mbps")),
+                RelationColumn::joined("sp", Column::integer("switch_id")),
+                RelationColumn::joined("sw", Column::string("switch_name")),
+            ],
+        },
+    ];
+    pub const SCHEMA: TableSchema = TableSchema {
+        table: Self::TABLE,
+        key: Self::KEY,
+        columns: &[
+            Column::integer("server_id"),
+            Column::string("server_name"),
+            Column::string("architecture"),
+            Column::string("product_name"),
+            Column::string("manufacturer"),
+            Column::string("serial_number"),
+            Column::string("system_uuid"),
+            Column::string("chassis_manufacturer"),
+            Column::string("chassis_serial_number"),
+            Column::enumeration("server_type", &["BAREMETAL", "HOST", "STORAGE", "COMPUTE"]),
+            Column::enumeration("status", &["ACTIVE", "INACTIVE", "MAINTENANCE", "RMA", "DECOMMISSIONED"]),
+            Column::enumeration("state", &["NEW", "ONBOARDING", "PROVISIONING", "RUNNING", "SUSPENDED", "DEPROVISIONING", "FAILED"]),
+            Column::enumeration("stage", &["NONE", "DISCOVERY", "ALLOCATE_RESOURCES", "INSTALL_OS", "CONFIGURE_NETWORK", "WIPE_DISKS", "WIPE_NIC_CONFIG", "RELEASE_IPS", "FINALIZE"]),
+            Column::enumeration("environment_type", &["PRODUCTION", "DEVELOPMENT", "QA", "STAGING", "TESTING"]),
+            Column::integer("cluster_id"),
+            Column::integer("sub_cluster_id"),
+            Column::integer("data_center_id"),
+            Column::integer("rack_id"),
+            Column::integer("rack_position_id"),
+            Column::integer("u_height"),
+            Column::timestamp("last_inventory_at"),
+            Column::string("agent_version"),
+            Column::timestamp("created_at"),
+            Column::timestamp("updated_at"),
+        ],
+        relations: Self::RELATIONS,
+    };
 }
\ No newline at end of file
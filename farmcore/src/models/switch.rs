@@ -1,12 +1,15 @@
 use sqlx::FromRow;
 use serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
 use rust_decimal::Decimal;
+use crate::database::DecimalColumn;
+use crate::models::query::{Column, TableSchema};
 
 // ===================================================================
 // SWITCH MODEL
 // ===================================================================
 
-#[derive(FromRow, Debug, Clone, Serialize, Deserialize)]
+#[derive(FromRow, Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Switch {
     pub switch_id: i32,
     pub switch_name: String,
@@ -65,13 +68,57 @@ pub struct Switch {
 impl Switch {
     pub const TABLE: &'static str = "switches";
     pub const KEY: &'static str = "switch_id";
+    pub const SCHEMA: TableSchema = TableSchema {
+        table: Self::TABLE,
+        key: Self::KEY,
+        columns: &[
+            Column::integer("switch_id"),
+            Column::string("switch_name"),
+            Column::integer("component_switch_id"),
+            Column::string("serial_number"),
+            Column::string("asset_tag"),
+            Column::string("os_type"),
+            Column::string("os_version"),
+            Column::string("bootrom_version"),
+            Column::string("mgmt_ip_address"),
+            Column::string("mgmt_mac_address"),
+            Column::integer("mgmt_vlan_id"),
+            Column::integer("uptime_seconds"),
+            Column::integer("temperature_celsius"),
+            Column::string("fan_status"),
+            Column::integer("power_consumption_watts"),
+            Column::enumeration("switch_role", &["ACCESS", "DISTRIBUTION", "CORE", "EDGE", "MANAGEMENT", "OOB"]),
+            Column::enumeration("status", &["ACTIVE", "INACTIVE", "MAINTENANCE", "NEW", "RMA", "DECOMMISSIONED"]),
+            Column::enumeration("environment_type", &["PRODUCTION", "DEVELOPMENT", "QA", "STAGING", "TESTING"]),
+            Column::integer("cluster_id"),
+            Column::integer("sub_cluster_id"),
+            Column::integer("data_center_id"),
+            Column::integer("rack_id"),
+            Column::integer("rack_position_id"),
+            Column::timestamp("last_poll_at"),
+            Column::integer("poll_interval_seconds"),
+            Column::enumeration("auth_method", &["LOCAL", "RADIUS", "TACACS", "LDAP", "CERTIFICATE"]),
+            Column::string("auth_server_ip"),
+            Column::integer("auth_server_port"),
+            Column::string("auth_shared_secret"),
+            Column::string("snmp_community"),
+            Column::enumeration("snmp_version", &["v1", "v2c", "v3"]),
+            Column::enumeration("snmp_auth_protocol", &["MD5", "SHA", "SHA224", "SHA256", "SHA384", "SHA512"]),
+            Column::enumeration("snmp_priv_protocol", &["DES", "AES128", "AES192", "AES256"]),
+            Column::string("service_username"),
+            Column::string("service_password"),
+            Column::timestamp("created_at"),
+            Column::timestamp("updated_at"),
+        ],
+        relations: &[],
+    };
 }
 
 // ===================================================================
 // SWITCH PORT MODEL
 // ===================================================================
 
-#[derive(FromRow, Debug, Clone, Serialize, Deserialize)]
+#[derive(FromRow, Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct SwitchPort {
     pub switch_port_id: i32,
     pub switch_id: i32,
@@ -111,6 +158,7 @@ pub struct SwitchPort {
     pub sfp_vendor: Option<String>,
     pub sfp_part_number: Option<String>,
     pub sfp_serial_number: Option<String>,
+    #[sqlx(try_from = "DecimalColumn")]
     pub optical_power_dbm: Option<Decimal>,
     pub optical_temperature_c: Option<i32>,
 
@@ -131,7 +179,7 @@ impl SwitchPort {
 // SWITCH VLAN MODEL
 // ===================================================================
 
-#[derive(FromRow, Debug, Clone, Serialize, Deserialize)]
+#[derive(FromRow, Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct SwitchVlan {
     pub vlan_db_id: i32,
     pub switch_id: i32,
@@ -152,7 +200,7 @@ impl SwitchVlan {
 // COMPOSITE STRUCTURE
 // ===================================================================
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct SwitchWithPorts {
     #[serde(flatten)]
     pub switch: Switch,
@@ -0,0 +1,360 @@
+use serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
+use serde_json::{Map, Value};
+use crate::models::query::Column;
+use crate::models::{Datacenter, DatacenterRack, ServerCluster, ServerSubCluster, Switch, SwitchVlan};
+
+// ===================================================================
+// DESIRED-STATE DOCUMENT
+// ===================================================================
+//
+// The physical topology as kept in git. Resources are identified by their codes rather
+// than database IDs, and children are nested under their parent:
+//
+//   datacenters:
+//     - data_center_code: AMS1
+//       data_center_name: Amsterdam 1
+//       country: NL
+//       racks:
+//         - { rack_code: R01, rack_name: A01 }
+//   clusters:
+//     - cluster_code: AMS1-PROD
+//       cluster_name: Amsterdam production
+//       data_center_code: AMS1
+//       sub_clusters:
+//         - { sub_cluster_code: COMPUTE, sub_cluster_name: Compute }
+//   switches:
+//     - switch_name: ams1-tor-r01
+//       data_center_code: AMS1
+//       rack_code: R01
+//       vlans:
+//         - { vlan_id: 100, vlan_name: prod }
+//
+// A list that is present is authoritative: anything of that kind missing from it (a top-level
+// section, or the racks of a listed datacenter) is planned for deletion. Leave a list out to
+// leave those resources unmanaged. Only the columns a resource sets are compared.
+
+#[derive(Debug, Clone, Default, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct TopologyDocument {
+    pub datacenters: Option<Vec<DesiredDatacenter>>,
+    pub clusters: Option<Vec<DesiredCluster>>,
+    pub switches: Option<Vec<DesiredSwitch>>,
+}
+
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+pub struct DesiredDatacenter {
+    pub racks: Option<Vec<Map<String, Value>>>,
+    #[serde(flatten)]
+    pub fields: Map<String, Value>,
+}
+
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+pub struct DesiredCluster {
+    pub sub_clusters: Option<Vec<Map<String, Value>>>,
+    #[serde(flatten)]
+    pub fields: Map<String, Value>,
+}
+
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+pub struct DesiredSwitch {
+    pub vlans: Option<Vec<Map<String, Value>>>,
+    #[serde(flatten)]
+    pub fields: Map<String, Value>,
+}
+
+/// Resource types a topology document manages, in dependency order: parents are created
+/// before their children and deleted after them
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TopologyKind {
+    Datacenter,
+    Rack,
+    Cluster,
+    SubCluster,
+    Switch,
+    Vlan,
+}
+
+/// A field naming another resource by code, stored as that resource's ID in `column`
+#[derive(Debug, Clone, Copy)]
+pub struct TopologyReference {
+    pub field: &'static str,
+    pub column: &'static str,
+    pub target: TopologyKind,
+    /// Reference field naming the target's parent, for codes that are only unique within it
+    pub scope: Option<&'static str>,
+}
+
+impl TopologyKind {
+    pub fn name(self) -> &'static str {
+        match self {
+            TopologyKind::Datacenter => "datacenter",
+            TopologyKind::Rack => "rack",
+            TopologyKind::Cluster => "cluster",
+            TopologyKind::SubCluster => "sub_cluster",
+            TopologyKind::Switch => "switch",
+            TopologyKind::Vlan => "vlan",
+        }
+    }
+
+    pub fn table(self) -> &'static str {
+        match self {
+            TopologyKind::Datacenter => Datacenter::TABLE,
+            TopologyKind::Rack => DatacenterRack::TABLE,
+            TopologyKind::Cluster => ServerCluster::TABLE,
+            TopologyKind::SubCluster => ServerSubCluster::TABLE,
+            TopologyKind::Switch => Switch::TABLE,
+            TopologyKind::Vlan => SwitchVlan::TABLE,
+        }
+    }
+
+    pub fn key(self) -> &'static str {
+        match self {
+            TopologyKind::Datacenter => Datacenter::KEY,
+            TopologyKind::Rack => DatacenterRack::KEY,
+            TopologyKind::Cluster => ServerCluster::KEY,
+            TopologyKind::SubCluster => ServerSubCluster::KEY,
+            TopologyKind::Switch => Switch::KEY,
+            TopologyKind::Vlan => SwitchVlan::KEY,
+        }
+    }
+
+    /// Column that names the resource, within its parent for nested kinds
+    pub fn identity(self) -> &'static str {
+        match self {
+            TopologyKind::Datacenter => "data_center_code",
+            TopologyKind::Rack => "rack_code",
+            TopologyKind::Cluster => "cluster_code",
+            TopologyKind::SubCluster => "sub_cluster_code",
+            TopologyKind::Switch => "switch_name",
+            TopologyKind::Vlan => "vlan_id",
+        }
+    }
+
+    /// Parent kind and the column holding the parent's ID, for kinds nested in the document
+    pub fn parent(self) -> Option<(TopologyKind, &'static str)> {
+        match self {
+            TopologyKind::Rack => Some((TopologyKind::Datacenter, "data_center_id")),
+            TopologyKind::SubCluster => Some((TopologyKind::Cluster, "cluster_id")),
+            TopologyKind::Vlan => Some((TopologyKind::Switch, "switch_id")),
+            _ => None,
+        }
+    }
+
+    /// Columns a document may set: the ones the create endpoints write, less IDs
+    pub fn columns(self) -> &'static [Column] {
+        match self {
+            TopologyKind::Datacenter => {
+                const COLUMNS: &[Column] = &[
+                    Column::string("data_center_code"),
+                    Column::string("data_center_name"),
+                    Column::string("description"),
+                    Column::string("address"),
+                    Column::string("city"),
+                    Column::string("state_province"),
+                    Column::string("country"),
+                    Column::string("postal_code"),
+                    Column::string("region"),
+                    Column::float("latitude"),
+                    Column::float("longitude"),
+                    Column::string("provider"),
+                    Column::string("provider_facility_id"),
+                    Column::enumeration("tier_level", &["TIER_I", "TIER_II", "TIER_III", "TIER_IV", "UNKNOWN"]),
+                    Column::float("total_floor_space_sqm"),
+                    Column::float("power_capacity_kw"),
+                    Column::float("cooling_capacity_kw"),
+                    Column::enumeration("status", &["ACTIVE", "INACTIVE", "MAINTENANCE", "CONSTRUCTION", "DECOMMISSIONED"]),
+                    Column::string("facility_manager"),
+                    Column::string("contact_phone"),
+                    Column::string("contact_email"),
+                    Column::string("emergency_contact"),
+                    Column::string("emergency_phone"),
+                    Column::string("timezone"),
+                    Column::string("operating_hours"),
+                    Column::json("tags"),
+                    Column::json("metadata"),
+                ];
+                COLUMNS
+            }
+            TopologyKind::Rack => {
+                const COLUMNS: &[Column] = &[
+                    Column::string("rack_code"),
+                    Column::string("rack_name"),
+                    Column::string("description"),
+                    Column::integer("rack_height_u"),
+                    Column::integer("rack_width_mm"),
+                    Column::integer("rack_depth_mm"),
+                    Column::string("row_name"),
+                    Column::string("aisle_name"),
+                    Column::string("room_name"),
+                    Column::integer("floor_level"),
+                    Column::integer("power_capacity_w"),
+                    Column::integer("power_usage_w"),
+                    Column::enumeration("cooling_type", &["AIR", "LIQUID", "HYBRID", "NONE"]),
+                    Column::string("network_zone"),
+                    Column::enumeration("status", &["ACTIVE", "INACTIVE", "MAINTENANCE", "RESERVED", "DECOMMISSIONED"]),
+                    Column::integer("total_u_available"),
+                    Column::integer("occupied_u"),
+                    Column::integer("reserved_u"),
+                    Column::integer("free_u"),
+                    Column::enumeration("access_level", &["PUBLIC", "RESTRICTED", "HIGH_SECURITY"]),
+                    Column::json("tags"),
+                    Column::json("metadata"),
+                ];
+                COLUMNS
+            }
+            TopologyKind::Cluster => {
+                const COLUMNS: &[Column] = &[
+                    Column::string("cluster_code"),
+                    Column::string("cluster_name"),
+                    Column::string("description"),
+                    Column::string("region"),
+                    Column::string("availability_zone"),
+                    Column::enumeration("status", &["ACTIVE", "INACTIVE", "MAINTENANCE", "DECOMMISSIONED"]),
+                    Column::enumeration("environment_type", &["PRODUCTION", "DEVELOPMENT", "QA", "STAGING", "TESTING"]),
+                    Column::integer("max_capacity"),
+                    Column::string("owner"),
+                    Column::string("contact_email"),
+                    Column::json("tags"),
+                    Column::json("metadata"),
+                ];
+                COLUMNS
+            }
+            TopologyKind::SubCluster => {
+                const COLUMNS: &[Column] = &[
+                    Column::string("sub_cluster_code"),
+                    Column::string("sub_cluster_name"),
+                    Column::string("description"),
+                    Column::enumeration("sub_cluster_type", &["COMPUTE", "STORAGE", "MIXED", "MANAGEMENT", "NETWORK", "OTHER"]),
+                    Column::enumeration("status", &["ACTIVE", "INACTIVE", "MAINTENANCE", "DECOMMISSIONED"]),
+                    Column::integer("max_capacity"),
+                    Column::string("workload_type"),
+                    Column::enumeration("priority_level", &["HIGH", "MEDIUM", "LOW"]),
+                    Column::json("tags"),
+                    Column::json("metadata"),
+                ];
+                COLUMNS
+            }
+            TopologyKind::Switch => {
+                const COLUMNS: &[Column] = &[
+                    Column::string("switch_name"),
+                    Column::integer("component_switch_id"),
+                    Column::string("serial_number"),
+                    Column::string("asset_tag"),
+                    Column::string("os_type"),
+                    Column::string("os_version"),
+                    Column::string("bootrom_version"),
+                    Column::string("mgmt_ip_address"),
+                    Column::string("mgmt_mac_address"),
+                    Column::integer("mgmt_vlan_id"),
+                    Column::enumeration("switch_role", &["ACCESS", "DISTRIBUTION", "CORE", "EDGE", "MANAGEMENT", "OOB"]),
+                    Column::enumeration("status", &["ACTIVE", "INACTIVE", "MAINTENANCE", "NEW", "RMA", "DECOMMISSIONED"]),
+                    Column::enumeration("environment_type", &["PRODUCTION", "DEVELOPMENT", "QA", "STAGING", "TESTING"]),
+                    Column::integer("rack_position_id"),
+                    Column::integer("poll_interval_seconds"),
+                    Column::enumeration("auth_method", &["LOCAL", "RADIUS", "TACACS", "LDAP", "CERTIFICATE"]),
+                    Column::enumeration("snmp_version", &["v1", "v2c", "v3"]),
+                ];
+                COLUMNS
+            }
+            TopologyKind::Vlan => {
+                const COLUMNS: &[Column] = &[
+                    Column::integer("vlan_id"),
+                    Column::string("vlan_name"),
+                    Column::enumeration("vlan_status", &["ACTIVE", "SUSPEND"]),
+                ];
+                COLUMNS
+            }
+        }
+    }
+
+    /// Fields naming resources outside the resource's own parent
+    pub fn references(self) -> &'static [TopologyReference] {
+        const DATACENTER: TopologyReference = TopologyReference {
+            field: "data_center_code", column: "data_center_id", target: TopologyKind::Datacenter, scope: None,
+        };
+        const RACK: TopologyReference = TopologyReference {
+            field: "rack_code", column: "rack_id", target: TopologyKind::Rack, scope: Some("data_center_code"),
+        };
+        const CLUSTER: TopologyReference = TopologyReference {
+            field: "cluster_code", column: "cluster_id", target: TopologyKind::Cluster, scope: None,
+        };
+        const SUB_CLUSTER: TopologyReference = TopologyReference {
+            field: "sub_cluster_code", column: "sub_cluster_id", target: TopologyKind::SubCluster, scope: Some("cluster_code"),
+        };
+
+        match self {
+            TopologyKind::Cluster => &[DATACENTER],
+            TopologyKind::Switch => &[DATACENTER, RACK, CLUSTER, SUB_CLUSTER],
+            _ => &[],
+        }
+    }
+}
+
+// ===================================================================
+// PLAN
+// ===================================================================
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum PlanAction {
+    Create,
+    Update,
+    Delete,
+}
+
+/// A column whose database value differs from the document
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct FieldDrift {
+    pub field: String,
+    pub current: Value,
+    pub desired: Value,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct PlannedChange {
+    pub action: PlanAction,
+    pub kind: TopologyKind,
+    /// Codes from the outermost parent down, e.g. `AMS1/R01` for a rack
+    pub address: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<i32>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub fields: Vec<FieldDrift>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, JsonSchema)]
+pub struct PlanSummary {
+    pub create: usize,
+    pub update: usize,
+    pub delete: usize,
+    pub unchanged: usize,
+}
+
+/// Changes that bring the database in line with a topology document, in the order they are
+/// applied: deletes (children first), then creates and updates (parents first). Update
+/// entries are the drift between the database and the file.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct TopologyPlan {
+    /// SHA-256 of the changes; pass it to apply to refuse a plan the database has moved past
+    pub fingerprint: String,
+    pub summary: PlanSummary,
+    pub changes: Vec<PlannedChange>,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct TopologyApplyResult {
+    pub applied: bool,
+    pub plan: TopologyPlan,
+}
+
+/// A problem with the document, reported before anything is planned or applied
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct TopologyError {
+    pub kind: TopologyKind,
+    pub address: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub field: Option<String>,
+    pub message: String,
+}
@@ -1,8 +1,10 @@
 use sqlx::FromRow;
 use serde::{Serialize, Deserialize};
+use schemars::JsonSchema;
+use crate::models::query::{Column, TableSchema};
 
 // Virtual Machine details
-#[derive(FromRow, Default, Debug, Clone, Serialize, Deserialize)]
+#[derive(FromRow, Default, Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct VirtualMachine {
     pub vm_id: i32,
     pub server_id: i32,
@@ -41,7 +43,7 @@ pub struct VirtualMachine {
 }
 
 // VM Disk details
-#[derive(FromRow, Debug, Clone, Serialize, Deserialize)]
+#[derive(FromRow, Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct VmDisk {
     pub vm_disk_id: i32,
     pub vm_id: i32,
@@ -68,7 +70,7 @@ pub struct VmDisk {
 }
 
 // VM Network Interface details
-#[derive(FromRow, Debug, Clone, Serialize, Deserialize)]
+#[derive(FromRow, Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct VmNetworkInterface {
     pub vm_interface_id: i32,
     pub vm_id: i32,
@@ -93,7 +95,7 @@ pub struct VmNetworkInterface {
 }
 
 // VM Snapshot details
-#[derive(FromRow, Debug, Clone, Serialize, Deserialize)]
+#[derive(FromRow, Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct VmSnapshot {
     pub snapshot_id: i32,
     pub vm_id: i32,
@@ -111,7 +113,7 @@ pub struct VmSnapshot {
 }
 
 // Complete VM details with all components
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct VmWithAllComponents {
     #[serde(flatten)]
     pub vm: VirtualMachine,
@@ -122,4 +124,46 @@ pub struct VmWithAllComponents {
 
 impl VirtualMachine {
     pub const TABLE: &'static str = "virtual_machines";
+    pub const KEY: &'static str = "vm_id";
+    pub const SCHEMA: TableSchema = TableSchema {
+        table: Self::TABLE,
+        key: Self::KEY,
+        columns: &[
+            Column::integer("vm_id"),
+            Column::integer("server_id"),
+            Column::string("vm_name"),
+            Column::string("vm_uuid"),
+            Column::string("description"),
+            Column::enumeration("hypervisor_type", &["KVM", "VMware", "VirtualBox", "Hyper-V", "Xen", "QEMU"]),
+            Column::string("guest_os_family"),
+            Column::string("guest_os_version"),
+            Column::string("guest_os_architecture"),
+            Column::integer("vcpu_count"),
+            Column::integer("memory_mb"),
+            Column::integer("storage_gb"),
+            Column::enumeration("vm_state", &["running", "stopped", "paused", "suspended", "crashed", "unknown"]),
+            Column::enumeration("vm_status", &["active", "inactive", "maintenance", "migrating", "backup", "error"]),
+            Column::string("config_file_path"),
+            Column::string("boot_order"),
+            Column::boolean("enable_vnc"),
+            Column::integer("vnc_port"),
+            Column::boolean("enable_spice"),
+            Column::integer("spice_port"),
+            Column::boolean("enable_ssh"),
+            Column::integer("ssh_port"),
+            Column::integer("cpu_limit_percent"),
+            Column::boolean("memory_balloon"),
+            Column::enumeration("io_priority", &["low", "normal", "high"]),
+            Column::boolean("auto_backup_enabled"),
+            Column::integer("backup_retention_days"),
+            Column::timestamp("last_backup_at"),
+            Column::timestamp("created_at"),
+            Column::timestamp("updated_at"),
+            Column::timestamp("started_at"),
+            Column::timestamp("stopped_at"),
+            Column::string("created_by"),
+            Column::string("managed_by"),
+        ],
+        relations: &[],
+    };
 }
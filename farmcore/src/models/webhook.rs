@@ -0,0 +1,152 @@
+use sqlx::FromRow;
+use serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
+use crate::models::query::{Column, TableSchema};
+
+// ===================================================================
+// WEBHOOK SUBSCRIPTION MODEL
+// ===================================================================
+
+#[derive(FromRow, Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct WebhookSubscription {
+    pub webhook_id: i32,
+    pub webhook_name: String,
+    pub description: Option<String>,
+    pub url: String,
+    /// Event types delivered: exact names, `entity.*` prefixes or `*`
+    #[sqlx(json)]
+    pub event_types: Vec<String>,
+    pub is_active: bool,
+
+    // NOTE: the signing secret is intentionally excluded from this struct — it is only
+    //       read by the dispatcher (see DueDelivery) and returned once on creation.
+
+    pub created_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub updated_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+impl WebhookSubscription {
+    pub const TABLE: &'static str = "webhook_subscriptions";
+    pub const KEY: &'static str = "webhook_id";
+}
+
+/// An active webhook's event filter, which the dispatcher matches events against
+#[derive(FromRow, Debug, Clone)]
+pub struct WebhookTarget {
+    pub webhook_id: i32,
+    #[sqlx(json)]
+    pub event_types: Vec<String>,
+}
+
+impl WebhookTarget {
+    /// Whether `event_type` passes this webhook's filter
+    pub fn wants(&self, event_type: &str) -> bool {
+        self.event_types.iter().any(|pattern| match pattern.strip_suffix('*') {
+            Some(prefix) => event_type.starts_with(prefix),
+            None => pattern == event_type,
+        })
+    }
+}
+
+/// Request body for creating a webhook
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+pub struct CreateWebhook {
+    pub webhook_name: String,
+    pub url: String,
+    pub event_types: Vec<String>,
+    /// HMAC key; one is generated when omitted
+    pub secret: Option<String>,
+    pub description: Option<String>,
+    pub is_active: Option<bool>,
+}
+
+/// Request body for changing a webhook; omitted fields are left as they are
+#[derive(Debug, Clone, Default, Deserialize, JsonSchema)]
+pub struct UpdateWebhook {
+    pub webhook_name: Option<String>,
+    pub url: Option<String>,
+    pub event_types: Option<Vec<String>>,
+    pub secret: Option<String>,
+    pub description: Option<String>,
+    pub is_active: Option<bool>,
+}
+
+/// Returned exactly once when a webhook is created, with its signing secret
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct CreatedWebhook {
+    #[serde(flatten)]
+    pub webhook: WebhookSubscription,
+    pub secret: String,
+}
+
+// ===================================================================
+// WEBHOOK DELIVERY MODEL
+// ===================================================================
+
+#[derive(FromRow, Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct WebhookDelivery {
+    pub delivery_id: i64,
+    pub webhook_id: i32,
+
+    // Event
+    pub event_id: Option<i64>,
+    pub event_type: String,
+    pub payload: serde_json::Value,
+
+    // Progress
+    pub status: String, // ENUM: PENDING, SUCCEEDED, FAILED
+    pub attempts: i32,
+    pub next_attempt_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub last_status_code: Option<i32>,
+    pub last_error: Option<String>,
+    pub delivered_at: Option<chrono::DateTime<chrono::Utc>>,
+
+    pub created_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub updated_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+impl WebhookDelivery {
+    pub const TABLE: &'static str = "webhook_deliveries";
+    pub const KEY: &'static str = "delivery_id";
+    pub const SCHEMA: TableSchema = TableSchema {
+        table: Self::TABLE,
+        key: Self::KEY,
+        columns: &[
+            Column::integer("delivery_id"),
+            Column::integer("webhook_id"),
+            Column::integer("event_id"),
+            Column::string("event_type"),
+            Column::json("payload"),
+            Column::enumeration("status", &["PENDING", "SUCCEEDED", "FAILED"]),
+            Column::integer("attempts"),
+            Column::timestamp("next_attempt_at"),
+            Column::integer("last_status_code"),
+            Column::string("last_error"),
+            Column::timestamp("delivered_at"),
+            Column::timestamp("created_at"),
+            Column::timestamp("updated_at"),
+        ],
+        relations: &[],
+    };
+}
+
+/// A delivery that is due, joined with where and how to send it
+#[derive(FromRow, Debug, Clone)]
+pub struct DueDelivery {
+    pub delivery_id: i64,
+    pub event_type: String,
+    pub payload: serde_json::Value,
+    pub attempts: i32,
+    pub url: String,
+    pub secret: String,
+}
+
+/// Result of one delivery attempt, as written back to the delivery row
+#[derive(Debug, Clone)]
+pub struct DeliveryAttempt {
+    /// SUCCEEDED, PENDING (retry at `next_attempt_at`) or FAILED
+    pub status: &'static str,
+    pub status_code: Option<i32>,
+    pub error: Option<String>,
+    pub next_attempt_at: Option<chrono::DateTime<chrono::Utc>>,
+}
@@ -1,18 +1,21 @@
-use sqlx::MySqlPool;
+use sqlx::database::HasArguments;
+use sqlx::{ColumnIndex, Decode, Encode, Executor, IntoArguments, MySql, Pool, Type};
 use async_trait::async_trait;
 use std::collections::HashMap;
-use crate::database::{QueryBuilderHelper, DatabaseHelper};
+use chrono::{DateTime, NaiveDateTime, Utc};
+use crate::database::{QueryBuilderHelper, DatabaseHelper, DecimalColumn, RowStream, SqlDialect};
 use crate::models::{
     ServerCluster, ServerSubCluster, ClusterWithSubClusters,
-    ClusterWithServers, QueryOptions, Server
+    ClusterWithServers, Server
 };
-use crate::api::query_parser::{CommonPaginationQuery, QueryParser};
+use crate::api::query_parser::{CommonPaginationQuery, ParsedQuery, QueryParser};
 
 
 #[async_trait]
 pub trait ClusterRepo: Send + Sync {
     // Cluster CRUD operations
-    async fn get_all_clusters(&self, query: CommonPaginationQuery) -> Result<Vec<ServerCluster>, sqlx::Error>;
+    async fn get_all_clusters(&self, query: CommonPaginationQuery) -> Result<(Vec<ServerCluster>, Option<i64>), sqlx::Error>;
+    fn stream_clusters(&self, query: CommonPaginationQuery) -> Result<RowStream<ServerCluster>, sqlx::Error>;
     async fn get_cluster_by_id(&self, cluster_id: i32) -> Result<Option<ServerCluster>, sqlx::Error>;
     async fn get_cluster_with_sub_clusters(&self, cluster_id: i32) -> Result<Option<ClusterWithSubClusters>, sqlx::Error>;
     async fn get_cluster_with_servers(&self, cluster_id: i32) -> Result<Option<ClusterWithServers>, sqlx::Error>;
@@ -33,33 +36,78 @@ pub trait ClusterRepo: Send + Sync {
     async fn get_sub_cluster_stats(&self, sub_cluster_id: i32) -> Result<serde_json::Value, sqlx::Error>;
 }
 
-#[derive(Clone)]
-pub struct ClusterRepository {
-    pool: MySqlPool,
+/// `ClusterRepo` on any storage backend; `SqliteClusterRepository` is this on SQLite
+pub struct ClusterRepository<DB: sqlx::Database = MySql> {
+    pool: Pool<DB>,
 }
 
-impl ClusterRepository {
-    pub fn new(pool: MySqlPool) -> Self {
+impl<DB: sqlx::Database> Clone for ClusterRepository<DB> {
+    fn clone(&self) -> Self {
+        Self { pool: self.pool.clone() }
+    }
+}
+
+impl<DB> ClusterRepository<DB>
+where
+    DB: SqlDialect,
+    for<'c> &'c mut DB::Connection: Executor<'c, Database = DB>,
+    for<'q> <DB as HasArguments<'q>>::Arguments: IntoArguments<'q, DB>,
+    for<'r> &'r str: ColumnIndex<DB::Row>,
+    usize: ColumnIndex<DB::Row>,
+    for<'q> &'q str: Encode<'q, DB> + Type<DB>,
+    for<'q> Option<&'q str>: Encode<'q, DB>,
+    for<'q> Option<&'q String>: Encode<'q, DB>,
+    for<'q, 'r> String: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+    for<'q> Option<String>: Encode<'q, DB>,
+    for<'q, 'r> i32: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+    for<'q> Option<i32>: Encode<'q, DB>,
+    for<'q, 'r> i64: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+    for<'q> Option<i64>: Encode<'q, DB>,
+    for<'q, 'r> f64: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+    for<'q> Option<f64>: Encode<'q, DB>,
+    for<'q, 'r> bool: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+    for<'q> Option<bool>: Encode<'q, DB>,
+    for<'q> i8: Encode<'q, DB> + Type<DB>,
+    for<'q, 'r> NaiveDateTime: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+    for<'q> Option<NaiveDateTime>: Encode<'q, DB>,
+    for<'q, 'r> DateTime<Utc>: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+    for<'q> Option<DateTime<Utc>>: Encode<'q, DB>,
+    for<'q, 'r> serde_json::Value: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+    for<'q> Option<serde_json::Value>: Encode<'q, DB>,
+    for<'q, 'r> DecimalColumn: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+{
+    pub fn new(pool: Pool<DB>) -> Self {
         Self { pool }
     }
 
 
     /// Get all clusters with pagination and filtering
-    pub async fn get_all_clusters(&self, query: CommonPaginationQuery) -> Result<Vec<ServerCluster>, sqlx::Error> {
-        let (_, per_page, offset, columns, where_conditions, _) = QueryParser::parse_all(
+    pub async fn get_all_clusters(&self, query: CommonPaginationQuery) -> Result<(Vec<ServerCluster>, Option<i64>), sqlx::Error> {
+        let ParsedQuery { where_conditions, options, .. } = QueryParser::parse_all(
             &query,
+            &ServerCluster::SCHEMA,
             Some("cluster_name ASC".to_string())
         ).map_err(|e| sqlx::Error::Protocol(format!("Query parsing error: {}", e)))?;
 
-        let options = QueryOptions {
-            columns,
-            where_conditions,
-            order_by: Some("cluster_name ASC".to_string()),
-            limit: Some(per_page as i64),
-            offset: Some(offset as i64),
+        let total_count = if query.include_total() {
+            Some(QueryBuilderHelper::count(&self.pool, &ServerCluster::SCHEMA, &where_conditions).await?)
+        } else {
+            None
         };
 
-        QueryBuilderHelper::select(&self.pool, ServerCluster::TABLE, options).await
+        let clusters = QueryBuilderHelper::select(&self.pool, &ServerCluster::SCHEMA, options).await?;
+        Ok((clusters, total_count))
+    }
+
+    /// Stream all clusters matching the query, for exports
+    pub fn stream_clusters(&self, query: CommonPaginationQuery) -> Result<RowStream<ServerCluster>, sqlx::Error> {
+        let ParsedQuery { options, .. } = QueryParser::parse_all(
+            &query,
+            &ServerCluster::SCHEMA,
+            Some("cluster_name ASC".to_string())
+        ).map_err(|e| sqlx::Error::Protocol(format!("Query parsing error: {}", e)))?;
+
+        QueryBuilderHelper::stream(self.pool.clone(), &ServerCluster::SCHEMA, QueryParser::export_options(options))
     }
 
     /// Get a single cluster by ID
@@ -103,6 +151,13 @@ impl ClusterRepository {
 
     /// Create a new cluster
     pub async fn create_cluster(&self, cluster: ServerCluster) -> Result<i32, sqlx::Error> {
+        Self::insert_cluster(&self.pool, &cluster).await
+    }
+
+    pub(crate) async fn insert_cluster<'e, E>(executor: E, cluster: &ServerCluster) -> Result<i32, sqlx::Error>
+    where
+        E: Executor<'e, Database = DB>,
+    {
         let query = r#"
             INSERT INTO server_clusters (
                 cluster_name, cluster_code, description,
@@ -128,10 +183,10 @@ impl ClusterRepository {
             .bind(&cluster.contact_email)
             .bind(&cluster.tags)
             .bind(&cluster.metadata)
-            .execute(&self.pool)
+            .execute(executor)
             .await?;
 
-        Ok(result.last_insert_id() as i32)
+        Ok(DB::last_insert_id(&result) as i32)
     }
 
     /// Update cluster with dynamic field updates
@@ -163,7 +218,7 @@ impl ClusterRepository {
             .execute(&self.pool)
             .await?;
 
-        Ok(result.rows_affected() > 0)
+        Ok(DB::rows_affected(&result) > 0)
     }
 
     /// Get all sub-clusters for a cluster, with live server counts computed from the servers table
@@ -214,6 +269,13 @@ impl ClusterRepository {
 
     /// Create a new sub-cluster
     pub async fn create_sub_cluster(&self, sub_cluster: ServerSubCluster) -> Result<i32, sqlx::Error> {
+        Self::insert_sub_cluster(&self.pool, &sub_cluster).await
+    }
+
+    pub(crate) async fn insert_sub_cluster<'e, E>(executor: E, sub_cluster: &ServerSubCluster) -> Result<i32, sqlx::Error>
+    where
+        E: Executor<'e, Database = DB>,
+    {
         let query = r#"
             INSERT INTO server_sub_clusters (
                 cluster_id, sub_cluster_name, sub_cluster_code, description,
@@ -236,10 +298,10 @@ impl ClusterRepository {
             .bind(&sub_cluster.priority_level)
             .bind(&sub_cluster.tags)
             .bind(&sub_cluster.metadata)
-            .execute(&self.pool)
+            .execute(executor)
             .await?;
 
-        Ok(result.last_insert_id() as i32)
+        Ok(DB::last_insert_id(&result) as i32)
     }
 
     /// Update sub-cluster with dynamic field updates
@@ -271,7 +333,7 @@ impl ClusterRepository {
             .execute(&self.pool)
             .await?;
 
-        Ok(result.rows_affected() > 0)
+        Ok(DB::rows_affected(&result) > 0)
     }
 
     // ===================================================================
@@ -451,11 +513,43 @@ impl ClusterRepository {
 
 
 #[async_trait]
-impl ClusterRepo for ClusterRepository {
-    async fn get_all_clusters(&self, query: CommonPaginationQuery) -> Result<Vec<ServerCluster>, sqlx::Error> {
+impl<DB> ClusterRepo for ClusterRepository<DB>
+where
+    DB: SqlDialect,
+    for<'c> &'c mut DB::Connection: Executor<'c, Database = DB>,
+    for<'q> <DB as HasArguments<'q>>::Arguments: IntoArguments<'q, DB>,
+    for<'r> &'r str: ColumnIndex<DB::Row>,
+    usize: ColumnIndex<DB::Row>,
+    for<'q> &'q str: Encode<'q, DB> + Type<DB>,
+    for<'q> Option<&'q str>: Encode<'q, DB>,
+    for<'q> Option<&'q String>: Encode<'q, DB>,
+    for<'q, 'r> String: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+    for<'q> Option<String>: Encode<'q, DB>,
+    for<'q, 'r> i32: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+    for<'q> Option<i32>: Encode<'q, DB>,
+    for<'q, 'r> i64: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+    for<'q> Option<i64>: Encode<'q, DB>,
+    for<'q, 'r> f64: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+    for<'q> Option<f64>: Encode<'q, DB>,
+    for<'q, 'r> bool: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+    for<'q> Option<bool>: Encode<'q, DB>,
+    for<'q> i8: Encode<'q, DB> + Type<DB>,
+    for<'q, 'r> NaiveDateTime: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+    for<'q> Option<NaiveDateTime>: Encode<'q, DB>,
+    for<'q, 'r> DateTime<Utc>: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+    for<'q> Option<DateTime<Utc>>: Encode<'q, DB>,
+    for<'q, 'r> serde_json::Value: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+    for<'q> Option<serde_json::Value>: Encode<'q, DB>,
+    for<'q, 'r> DecimalColumn: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+{
+    async fn get_all_clusters(&self, query: CommonPaginationQuery) -> Result<(Vec<ServerCluster>, Option<i64>), sqlx::Error> {
         self.get_all_clusters(query).await
     }
 
+    fn stream_clusters(&self, query: CommonPaginationQuery) -> Result<RowStream<ServerCluster>, sqlx::Error> {
+        Self::stream_clusters(self, query)
+    }
+
     async fn get_cluster_by_id(&self, cluster_id: i32) -> Result<Option<ServerCluster>, sqlx::Error> {
         self.get_cluster_by_id(cluster_id).await
     }
@@ -1,48 +1,57 @@
-use sqlx::MySqlPool;
+use sqlx::database::HasArguments;
+use sqlx::{ColumnIndex, Decode, Encode, Executor, FromRow, IntoArguments, MySql, Pool, Type};
+use chrono::{DateTime, NaiveDateTime, Utc};
 use async_trait::async_trait;
 use std::collections::HashMap;
 use crate::models::{
     ComponentCpuType, ComponentMemoryType, ComponentMotherboardType,
     ComponentDiskType, ComponentNetworkType, ComponentGpuType, ComponentBmcType,
-    ComponentCatalog, ComponentCatalogStats, QueryOptions
+    ComponentCatalog, ComponentCatalogStats, TableSchema
 };
-use crate::database::{QueryBuilderHelper, DatabaseHelper};
-use crate::api::query_parser::{CommonPaginationQuery, QueryParser};
+use crate::database::{QueryBuilderHelper, DatabaseHelper, RowStream, SqlDialect};
+use crate::api::query_parser::{CommonPaginationQuery, ParsedQuery, QueryParser};
 
 #[async_trait]
 pub trait ComponentRepo: Send + Sync {
     // CPU Types
-    async fn get_all_cpu_types(&self, query: CommonPaginationQuery) -> Result<Vec<ComponentCpuType>, sqlx::Error>;
+    async fn get_all_cpu_types(&self, query: CommonPaginationQuery) -> Result<(Vec<ComponentCpuType>, Option<i64>), sqlx::Error>;
+    fn stream_cpu_types(&self, query: CommonPaginationQuery) -> Result<RowStream<ComponentCpuType>, sqlx::Error>;
     async fn get_cpu_type_by_id(&self, id: i32) -> Result<Option<ComponentCpuType>, sqlx::Error>;
     async fn create_cpu_type(&self, cpu_type: &ComponentCpuType) -> Result<i32, sqlx::Error>;
     
     // Memory Types
-    async fn get_all_memory_types(&self, query: CommonPaginationQuery) -> Result<Vec<ComponentMemoryType>, sqlx::Error>;
+    async fn get_all_memory_types(&self, query: CommonPaginationQuery) -> Result<(Vec<ComponentMemoryType>, Option<i64>), sqlx::Error>;
+    fn stream_memory_types(&self, query: CommonPaginationQuery) -> Result<RowStream<ComponentMemoryType>, sqlx::Error>;
     async fn get_memory_type_by_id(&self, id: i32) -> Result<Option<ComponentMemoryType>, sqlx::Error>;
     async fn create_memory_type(&self, memory_type: &ComponentMemoryType) -> Result<i32, sqlx::Error>;
     
     // Motherboard Types
-    async fn get_all_motherboard_types(&self, query: CommonPaginationQuery) -> Result<Vec<ComponentMotherboardType>, sqlx::Error>;
+    async fn get_all_motherboard_types(&self, query: CommonPaginationQuery) -> Result<(Vec<ComponentMotherboardType>, Option<i64>), sqlx::Error>;
+    fn stream_motherboard_types(&self, query: CommonPaginationQuery) -> Result<RowStream<ComponentMotherboardType>, sqlx::Error>;
     async fn get_motherboard_type_by_id(&self, id: i32) -> Result<Option<ComponentMotherboardType>, sqlx::Error>;
     async fn create_motherboard_type(&self, motherboard_type: &ComponentMotherboardType) -> Result<i32, sqlx::Error>;
     
     // Disk Types
-    async fn get_all_disk_types(&self, query: CommonPaginationQuery) -> Result<Vec<ComponentDiskType>, sqlx::Error>;
+    async fn get_all_disk_types(&self, query: CommonPaginationQuery) -> Result<(Vec<ComponentDiskType>, Option<i64>), sqlx::Error>;
+    fn stream_disk_types(&self, query: CommonPaginationQuery) -> Result<RowStream<ComponentDiskType>, sqlx::Error>;
     async fn get_disk_type_by_id(&self, id: i32) -> Result<Option<ComponentDiskType>, sqlx::Error>;
     async fn create_disk_type(&self, disk_type: &ComponentDiskType) -> Result<i32, sqlx::Error>;
     
     // Network Types
-    async fn get_all_network_types(&self, query: CommonPaginationQuery) -> Result<Vec<ComponentNetworkType>, sqlx::Error>;
+    async fn get_all_network_types(&self, query: CommonPaginationQuery) -> Result<(Vec<ComponentNetworkType>, Option<i64>), sqlx::Error>;
+    fn stream_network_types(&self, query: CommonPaginationQuery) -> Result<RowStream<ComponentNetworkType>, sqlx::Error>;
     async fn get_network_type_by_id(&self, id: i32) -> Result<Option<ComponentNetworkType>, sqlx::Error>;
     async fn create_network_type(&self, network_type: &ComponentNetworkType) -> Result<i32, sqlx::Error>;
     
     // GPU Types
-    async fn get_all_gpu_types(&self, query: CommonPaginationQuery) -> Result<Vec<ComponentGpuType>, sqlx::Error>;
+    async fn get_all_gpu_types(&self, query: CommonPaginationQuery) -> Result<(Vec<ComponentGpuType>, Option<i64>), sqlx::Error>;
+    fn stream_gpu_types(&self, query: CommonPaginationQuery) -> Result<RowStream<ComponentGpuType>, sqlx::Error>;
     async fn get_gpu_type_by_id(&self, id: i32) -> Result<Option<ComponentGpuType>, sqlx::Error>;
     async fn create_gpu_type(&self, gpu_type: &ComponentGpuType) -> Result<i32, sqlx::Error>;
     
     // BMC Types
-    async fn get_all_bmc_types(&self, query: CommonPaginationQuery) -> Result<Vec<ComponentBmcType>, sqlx::Error>;
+    async fn get_all_bmc_types(&self, query: CommonPaginationQuery) -> Result<(Vec<ComponentBmcType>, Option<i64>), sqlx::Error>;
+    fn stream_bmc_types(&self, query: CommonPaginationQuery) -> Result<RowStream<ComponentBmcType>, sqlx::Error>;
     async fn get_bmc_type_by_id(&self, id: i32) -> Result<Option<ComponentBmcType>, sqlx::Error>;
     async fn create_bmc_type(&self, bmc_type: &ComponentBmcType) -> Result<i32, sqlx::Error>;
     
@@ -51,45 +60,105 @@ pub trait ComponentRepo: Send + Sync {
     async fn get_catalog_stats(&self) -> Result<ComponentCatalogStats, sqlx::Error>;
 }
 
-pub struct ComponentRepository {
-    pool: MySqlPool,
+/// `ComponentRepo` on any storage backend; `SqliteComponentRepository` is this on SQLite
+pub struct ComponentRepository<DB: sqlx::Database = MySql> {
+    pool: Pool<DB>,
 }
 
-impl ComponentRepository {
-    pub fn new(pool: MySqlPool) -> Self {
+impl<DB> ComponentRepository<DB>
+where
+    DB: SqlDialect,
+    for<'c> &'c mut DB::Connection: Executor<'c, Database = DB>,
+    for<'q> <DB as HasArguments<'q>>::Arguments: IntoArguments<'q, DB>,
+    for<'r> &'r str: ColumnIndex<DB::Row>,
+    usize: ColumnIndex<DB::Row>,
+    for<'q, 'r> String: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+    for<'q> Option<String>: Encode<'q, DB>,
+    for<'q> &'q String: Encode<'q, DB>,
+    for<'q> Option<&'q String>: Encode<'q, DB>,
+    for<'q, 'r> i32: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+    for<'q> Option<i32>: Encode<'q, DB>,
+    for<'q, 'r> i64: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+    for<'q> Option<i64>: Encode<'q, DB>,
+    for<'q> f64: Encode<'q, DB> + Type<DB>,
+    for<'q, 'r> bool: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+    for<'q> Option<bool>: Encode<'q, DB>,
+    for<'q, 'r> NaiveDateTime: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+    for<'r> DateTime<Utc>: Decode<'r, DB> + Type<DB>,
+{
+    pub fn new(pool: Pool<DB>) -> Self {
         Self { pool }
     }
+
+    /// Stream all component types of one kind (the table behind `schema`) matching the query,
+    /// for exports. Unsorted exports follow the table's key.
+    pub fn stream_types<T>(&self, schema: &TableSchema, query: CommonPaginationQuery) -> Result<RowStream<T>, sqlx::Error>
+    where
+        T: for<'r> FromRow<'r, DB::Row> + Unpin + Send + 'static,
+    {
+        let ParsedQuery { options, .. } = QueryParser::parse_all(
+            &query,
+            schema,
+            Some(format!("`{}` ASC", schema.key))
+        ).map_err(|e| sqlx::Error::Protocol(format!("Query parsing error: {}", e)))?;
+
+        QueryBuilderHelper::stream(self.pool.clone(), schema, QueryParser::export_options(options))
+    }
 }
 
 #[async_trait]
-impl ComponentRepo for ComponentRepository {
+impl<DB> ComponentRepo for ComponentRepository<DB>
+where
+    DB: SqlDialect,
+    for<'c> &'c mut DB::Connection: Executor<'c, Database = DB>,
+    for<'q> <DB as HasArguments<'q>>::Arguments: IntoArguments<'q, DB>,
+    for<'r> &'r str: ColumnIndex<DB::Row>,
+    usize: ColumnIndex<DB::Row>,
+    for<'q, 'r> String: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+    for<'q> Option<String>: Encode<'q, DB>,
+    for<'q> &'q String: Encode<'q, DB>,
+    for<'q> Option<&'q String>: Encode<'q, DB>,
+    for<'q, 'r> i32: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+    for<'q> Option<i32>: Encode<'q, DB>,
+    for<'q, 'r> i64: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+    for<'q> Option<i64>: Encode<'q, DB>,
+    for<'q> f64: Encode<'q, DB> + Type<DB>,
+    for<'q, 'r> bool: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+    for<'q> Option<bool>: Encode<'q, DB>,
+    for<'q, 'r> NaiveDateTime: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+    for<'r> DateTime<Utc>: Decode<'r, DB> + Type<DB>,
+{
     // ===================================================================
     // CPU TYPES
     // ===================================================================
     
-    async fn get_all_cpu_types(&self, query: CommonPaginationQuery) -> Result<Vec<ComponentCpuType>, sqlx::Error> {
-        let (_, per_page, offset, columns, where_conditions, _) = QueryParser::parse_all(
+    async fn get_all_cpu_types(&self, query: CommonPaginationQuery) -> Result<(Vec<ComponentCpuType>, Option<i64>), sqlx::Error> {
+        let ParsedQuery { where_conditions, options, .. } = QueryParser::parse_all(
             &query,
+            &ComponentCpuType::SCHEMA,
             Some("manufacturer, model_name".to_string())
         ).map_err(|e| sqlx::Error::Protocol(format!("Query parsing error: {}", e)))?;
 
-        let options = QueryOptions {
-            columns,
-            where_conditions,
-            limit: Some(per_page),
-            offset: Some(offset),
-            order_by: Some("manufacturer, model_name".to_string()),
+        let total_count = if query.include_total() {
+            Some(QueryBuilderHelper::count(&self.pool, &ComponentCpuType::SCHEMA, &where_conditions).await?)
+        } else {
+            None
         };
 
-        QueryBuilderHelper::select::<ComponentCpuType>(
+        let cpu_types = QueryBuilderHelper::select::<_, ComponentCpuType>(
             &self.pool,
-            "component_cpu_types",
+            &ComponentCpuType::SCHEMA,
             options
-        ).await
+        ).await?;
+        Ok((cpu_types, total_count))
+    }
+
+    fn stream_cpu_types(&self, query: CommonPaginationQuery) -> Result<RowStream<ComponentCpuType>, sqlx::Error> {
+        self.stream_types(&ComponentCpuType::SCHEMA, query)
     }
 
     async fn get_cpu_type_by_id(&self, id: i32) -> Result<Option<ComponentCpuType>, sqlx::Error> {
-        DatabaseHelper::get_by_id::<ComponentCpuType>(
+        DatabaseHelper::get_by_id::<_, ComponentCpuType>(
             &self.pool,
             "component_cpu_types",
             "component_cpu_id",
@@ -113,36 +182,40 @@ impl ComponentRepo for ComponentRepository {
             .execute(&self.pool)
             .await?;
 
-        Ok(result.last_insert_id() as i32)
+        Ok(DB::last_insert_id(&result) as i32)
     }
 
     // ===================================================================
     // MEMORY TYPES
     // ===================================================================
     
-    async fn get_all_memory_types(&self, query: CommonPaginationQuery) -> Result<Vec<ComponentMemoryType>, sqlx::Error> {
-        let (_, per_page, offset, columns, where_conditions, _) = QueryParser::parse_all(
+    async fn get_all_memory_types(&self, query: CommonPaginationQuery) -> Result<(Vec<ComponentMemoryType>, Option<i64>), sqlx::Error> {
+        let ParsedQuery { where_conditions, options, .. } = QueryParser::parse_all(
             &query,
+            &ComponentMemoryType::SCHEMA,
             Some("manufacturer, part_number".to_string())
         ).map_err(|e| sqlx::Error::Protocol(format!("Query parsing error: {}", e)))?;
 
-        let options = QueryOptions {
-            columns,
-            where_conditions,
-            limit: Some(per_page),
-            offset: Some(offset),
-            order_by: Some("manufacturer, part_number".to_string()),
+        let total_count = if query.include_total() {
+            Some(QueryBuilderHelper::count(&self.pool, &ComponentMemoryType::SCHEMA, &where_conditions).await?)
+        } else {
+            None
         };
 
-        QueryBuilderHelper::select::<ComponentMemoryType>(
+        let memory_types = QueryBuilderHelper::select::<_, ComponentMemoryType>(
             &self.pool,
-            "component_memory_types",
+            &ComponentMemoryType::SCHEMA,
             options
-        ).await
+        ).await?;
+        Ok((memory_types, total_count))
+    }
+
+    fn stream_memory_types(&self, query: CommonPaginationQuery) -> Result<RowStream<ComponentMemoryType>, sqlx::Error> {
+        self.stream_types(&ComponentMemoryType::SCHEMA, query)
     }
 
     async fn get_memory_type_by_id(&self, id: i32) -> Result<Option<ComponentMemoryType>, sqlx::Error> {
-        DatabaseHelper::get_by_id::<ComponentMemoryType>(
+        DatabaseHelper::get_by_id::<_, ComponentMemoryType>(
             &self.pool,
             "component_memory_types",
             "component_memory_id",
@@ -163,36 +236,40 @@ impl ComponentRepo for ComponentRepository {
             .execute(&self.pool)
             .await?;
 
-        Ok(result.last_insert_id() as i32)
+        Ok(DB::last_insert_id(&result) as i32)
     }
 
     // ===================================================================
     // MOTHERBOARD TYPES
     // ===================================================================
     
-    async fn get_all_motherboard_types(&self, query: CommonPaginationQuery) -> Result<Vec<ComponentMotherboardType>, sqlx::Error> {
-        let (_, per_page, offset, columns, where_conditions, _) = QueryParser::parse_all(
+    async fn get_all_motherboard_types(&self, query: CommonPaginationQuery) -> Result<(Vec<ComponentMotherboardType>, Option<i64>), sqlx::Error> {
+        let ParsedQuery { where_conditions, options, .. } = QueryParser::parse_all(
             &query,
+            &ComponentMotherboardType::SCHEMA,
             Some("manufacturer, product_name".to_string())
         ).map_err(|e| sqlx::Error::Protocol(format!("Query parsing error: {}", e)))?;
 
-        let options = QueryOptions {
-            columns,
-            where_conditions,
-            limit: Some(per_page),
-            offset: Some(offset),
-            order_by: Some("manufacturer, product_name".to_string()),
+        let total_count = if query.include_total() {
+            Some(QueryBuilderHelper::count(&self.pool, &ComponentMotherboardType::SCHEMA, &where_conditions).await?)
+        } else {
+            None
         };
 
-        QueryBuilderHelper::select::<ComponentMotherboardType>(
+        let motherboard_types = QueryBuilderHelper::select::<_, ComponentMotherboardType>(
             &self.pool,
-            "component_motherboard_types",
+            &ComponentMotherboardType::SCHEMA,
             options
-        ).await
+        ).await?;
+        Ok((motherboard_types, total_count))
+    }
+
+    fn stream_motherboard_types(&self, query: CommonPaginationQuery) -> Result<RowStream<ComponentMotherboardType>, sqlx::Error> {
+        self.stream_types(&ComponentMotherboardType::SCHEMA, query)
     }
 
     async fn get_motherboard_type_by_id(&self, id: i32) -> Result<Option<ComponentMotherboardType>, sqlx::Error> {
-        DatabaseHelper::get_by_id::<ComponentMotherboardType>(
+        DatabaseHelper::get_by_id::<_, ComponentMotherboardType>(
             &self.pool,
             "component_motherboard_types",
             "component_motherboard_id",
@@ -214,36 +291,40 @@ impl ComponentRepo for ComponentRepository {
             .execute(&self.pool)
             .await?;
 
-        Ok(result.last_insert_id() as i32)
+        Ok(DB::last_insert_id(&result) as i32)
     }
 
     // ===================================================================
     // DISK TYPES
     // ===================================================================
     
-    async fn get_all_disk_types(&self, query: CommonPaginationQuery) -> Result<Vec<ComponentDiskType>, sqlx::Error> {
-        let (_, per_page, offset, columns, where_conditions, _) = QueryParser::parse_all(
+    async fn get_all_disk_types(&self, query: CommonPaginationQuery) -> Result<(Vec<ComponentDiskType>, Option<i64>), sqlx::Error> {
+        let ParsedQuery { where_conditions, options, .. } = QueryParser::parse_all(
             &query,
+            &ComponentDiskType::SCHEMA,
             Some("manufacturer, model".to_string())
         ).map_err(|e| sqlx::Error::Protocol(format!("Query parsing error: {}", e)))?;
 
-        let options = QueryOptions {
-            columns,
-            where_conditions,
-            limit: Some(per_page),
-            offset: Some(offset),
-            order_by: Some("manufacturer, model".to_string()),
+        let total_count = if query.include_total() {
+            Some(QueryBuilderHelper::count(&self.pool, &ComponentDiskType::SCHEMA, &where_conditions).await?)
+        } else {
+            None
         };
 
-        QueryBuilderHelper::select::<ComponentDiskType>(
+        let disk_types = QueryBuilderHelper::select::<_, ComponentDiskType>(
             &self.pool,
-            "component_disk_types",
+            &ComponentDiskType::SCHEMA,
             options
-        ).await
+        ).await?;
+        Ok((disk_types, total_count))
+    }
+
+    fn stream_disk_types(&self, query: CommonPaginationQuery) -> Result<RowStream<ComponentDiskType>, sqlx::Error> {
+        self.stream_types(&ComponentDiskType::SCHEMA, query)
     }
 
     async fn get_disk_type_by_id(&self, id: i32) -> Result<Option<ComponentDiskType>, sqlx::Error> {
-        DatabaseHelper::get_by_id::<ComponentDiskType>(
+        DatabaseHelper::get_by_id::<_, ComponentDiskType>(
             &self.pool,
             "component_disk_types",
             "component_disk_id",
@@ -264,36 +345,40 @@ impl ComponentRepo for ComponentRepository {
             .execute(&self.pool)
             .await?;
 
-        Ok(result.last_insert_id() as i32)
+        Ok(DB::last_insert_id(&result) as i32)
     }
 
     // ===================================================================
     // NETWORK TYPES
     // ===================================================================
     
-    async fn get_all_network_types(&self, query: CommonPaginationQuery) -> Result<Vec<ComponentNetworkType>, sqlx::Error> {
-        let (_, per_page, offset, columns, where_conditions, _) = QueryParser::parse_all(
+    async fn get_all_network_types(&self, query: CommonPaginationQuery) -> Result<(Vec<ComponentNetworkType>, Option<i64>), sqlx::Error> {
+        let ParsedQuery { where_conditions, options, .. } = QueryParser::parse_all(
             &query,
+            &ComponentNetworkType::SCHEMA,
             Some("vendor_name, device_name".to_string())
         ).map_err(|e| sqlx::Error::Protocol(format!("Query parsing error: {}", e)))?;
 
-        let options = QueryOptions {
-            columns,
-            where_conditions,
-            limit: Some(per_page),
-            offset: Some(offset),
-            order_by: Some("vendor_name, device_name".to_string()),
+        let total_count = if query.include_total() {
+            Some(QueryBuilderHelper::count(&self.pool, &ComponentNetworkType::SCHEMA, &where_conditions).await?)
+        } else {
+            None
         };
 
-        QueryBuilderHelper::select::<ComponentNetworkType>(
+        let network_types = QueryBuilderHelper::select::<_, ComponentNetworkType>(
             &self.pool,
-            "component_network_types",
+            &ComponentNetworkType::SCHEMA,
             options
-        ).await
+        ).await?;
+        Ok((network_types, total_count))
+    }
+
+    fn stream_network_types(&self, query: CommonPaginationQuery) -> Result<RowStream<ComponentNetworkType>, sqlx::Error> {
+        self.stream_types(&ComponentNetworkType::SCHEMA, query)
     }
 
     async fn get_network_type_by_id(&self, id: i32) -> Result<Option<ComponentNetworkType>, sqlx::Error> {
-        DatabaseHelper::get_by_id::<ComponentNetworkType>(
+        DatabaseHelper::get_by_id::<_, ComponentNetworkType>(
             &self.pool,
             "component_network_types",
             "component_network_id",
@@ -313,36 +398,40 @@ impl ComponentRepo for ComponentRepository {
             .execute(&self.pool)
             .await?;
 
-        Ok(result.last_insert_id() as i32)
+        Ok(DB::last_insert_id(&result) as i32)
     }
 
     // ===================================================================
     // GPU TYPES
     // ===================================================================
     
-    async fn get_all_gpu_types(&self, query: CommonPaginationQuery) -> Result<Vec<ComponentGpuType>, sqlx::Error> {
-        let (_, per_page, offset, columns, where_conditions, _) = QueryParser::parse_all(
+    async fn get_all_gpu_types(&self, query: CommonPaginationQuery) -> Result<(Vec<ComponentGpuType>, Option<i64>), sqlx::Error> {
+        let ParsedQuery { where_conditions, options, .. } = QueryParser::parse_all(
             &query,
+            &ComponentGpuType::SCHEMA,
             Some("vendor, model".to_string())
         ).map_err(|e| sqlx::Error::Protocol(format!("Query parsing error: {}", e)))?;
 
-        let options = QueryOptions {
-            columns,
-            where_conditions,
-            limit: Some(per_page),
-            offset: Some(offset),
-            order_by: Some("vendor, model".to_string()),
+        let total_count = if query.include_total() {
+            Some(QueryBuilderHelper::count(&self.pool, &ComponentGpuType::SCHEMA, &where_conditions).await?)
+        } else {
+            None
         };
 
-        QueryBuilderHelper::select::<ComponentGpuType>(
+        let gpu_types = QueryBuilderHelper::select::<_, ComponentGpuType>(
             &self.pool,
-            "component_gpu_types",
+            &ComponentGpuType::SCHEMA,
             options
-        ).await
+        ).await?;
+        Ok((gpu_types, total_count))
+    }
+
+    fn stream_gpu_types(&self, query: CommonPaginationQuery) -> Result<RowStream<ComponentGpuType>, sqlx::Error> {
+        self.stream_types(&ComponentGpuType::SCHEMA, query)
     }
 
     async fn get_gpu_type_by_id(&self, id: i32) -> Result<Option<ComponentGpuType>, sqlx::Error> {
-        DatabaseHelper::get_by_id::<ComponentGpuType>(
+        DatabaseHelper::get_by_id::<_, ComponentGpuType>(
             &self.pool,
             "component_gpu_types",
             "component_gpu_id",
@@ -361,36 +450,40 @@ impl ComponentRepo for ComponentRepository {
             .execute(&self.pool)
             .await?;
 
-        Ok(result.last_insert_id() as i32)
+        Ok(DB::last_insert_id(&result) as i32)
     }
 
     // ===================================================================
     // BMC TYPES
     // ===================================================================
     
-    async fn get_all_bmc_types(&self, query: CommonPaginationQuery) -> Result<Vec<ComponentBmcType>, sqlx::Error> {
-        let (_, per_page, offset, columns, where_conditions, _) = QueryParser::parse_all(
+    async fn get_all_bmc_types(&self, query: CommonPaginationQuery) -> Result<(Vec<ComponentBmcType>, Option<i64>), sqlx::Error> {
+        let ParsedQuery { where_conditions, options, .. } = QueryParser::parse_all(
             &query,
+            &ComponentBmcType::SCHEMA,
             Some("vendor, model".to_string())
         ).map_err(|e| sqlx::Error::Protocol(format!("Query parsing error: {}", e)))?;
 
-        let options = QueryOptions {
-            columns,
-            where_conditions,
-            limit: Some(per_page),
-            offset: Some(offset),
-            order_by: Some("vendor, model".to_string()),
+        let total_count = if query.include_total() {
+            Some(QueryBuilderHelper::count(&self.pool, &ComponentBmcType::SCHEMA, &where_conditions).await?)
+        } else {
+            None
         };
 
-        QueryBuilderHelper::select::<ComponentBmcType>(
+        let bmc_types = QueryBuilderHelper::select::<_, ComponentBmcType>(
             &self.pool,
-            "component_bmc_types",
+            &ComponentBmcType::SCHEMA,
             options
-        ).await
+        ).await?;
+        Ok((bmc_types, total_count))
+    }
+
+    fn stream_bmc_types(&self, query: CommonPaginationQuery) -> Result<RowStream<ComponentBmcType>, sqlx::Error> {
+        self.stream_types(&ComponentBmcType::SCHEMA, query)
     }
 
     async fn get_bmc_type_by_id(&self, id: i32) -> Result<Option<ComponentBmcType>, sqlx::Error> {
-        DatabaseHelper::get_by_id::<ComponentBmcType>(
+        DatabaseHelper::get_by_id::<_, ComponentBmcType>(
             &self.pool,
             "component_bmc_types",
             "component_bmc_id",
@@ -418,7 +511,7 @@ impl ComponentRepo for ComponentRepository {
             .execute(&self.pool)
             .await?;
 
-        Ok(result.last_insert_id() as i32)
+        Ok(DB::last_insert_id(&result) as i32)
     }
 
     // ===================================================================
@@ -431,16 +524,20 @@ impl ComponentRepo for ComponentRepository {
             per_page: Some(1000), // Large number to get all items
             columns: None,
             search: None,
+            sort: None,
+            after: None,
+            count: Some(false), // The catalog has no pagination to report
+            format: None,
             filters: HashMap::new(),
         };
         
-        let cpus = self.get_all_cpu_types(empty_query.clone()).await?;
-        let memory = self.get_all_memory_types(empty_query.clone()).await?;
-        let disks = self.get_all_disk_types(empty_query.clone()).await?;
-        let network_interfaces = self.get_all_network_types(empty_query.clone()).await?;
-        let gpus = self.get_all_gpu_types(empty_query.clone()).await?;
-        let motherboards = self.get_all_motherboard_types(empty_query.clone()).await?;
-        let bmcs = self.get_all_bmc_types(empty_query).await?;
+        let (cpus, _) = self.get_all_cpu_types(empty_query.clone()).await?;
+        let (memory, _) = self.get_all_memory_types(empty_query.clone()).await?;
+        let (disks, _) = self.get_all_disk_types(empty_query.clone()).await?;
+        let (network_interfaces, _) = self.get_all_network_types(empty_query.clone()).await?;
+        let (gpus, _) = self.get_all_gpu_types(empty_query.clone()).await?;
+        let (motherboards, _) = self.get_all_motherboard_types(empty_query.clone()).await?;
+        let (bmcs, _) = self.get_all_bmc_types(empty_query).await?;
 
         Ok(ComponentCatalog {
             cpus,
@@ -1,17 +1,25 @@
-use sqlx::MySqlPool;
+use sqlx::database::HasArguments;
+use sqlx::{ColumnIndex, Decode, Encode, Executor, IntoArguments, MySql, Pool, Type};
 use async_trait::async_trait;
 use std::collections::HashMap;
-use crate::database::{QueryBuilderHelper, DatabaseHelper};
+use chrono::{DateTime, NaiveDateTime, Utc};
+use serde::de::DeserializeOwned;
+use serde_json::{json, Map, Value};
+use crate::database::{QueryBuilderHelper, DatabaseHelper, DecimalColumn, RowStream, SqlDialect};
 use crate::models::{
     Datacenter, DatacenterRack, DatacenterRackPosition,
-    DatacenterWithRacks, RackWithPositions, QueryOptions
+    DatacenterWithRacks, RackWithPositions,
+    ImportCounts, ImportKind, ImportReport, ImportRow, ImportRowError, Switch,
 };
-use crate::api::query_parser::{CommonPaginationQuery, QueryParser};
+use crate::domain::{AuditAction, AuditRecorder};
+use crate::repositories::SwitchRepository;
+use crate::api::query_parser::{CommonPaginationQuery, ParsedQuery, QueryParser};
 
 #[async_trait]
 pub trait DatacenterRepo: Send + Sync {
     // Datacenter CRUD operations
-    async fn get_all_datacenters(&self, query: CommonPaginationQuery) -> Result<Vec<Datacenter>, sqlx::Error>;
+    async fn get_all_datacenters(&self, query: CommonPaginationQuery) -> Result<(Vec<Datacenter>, Option<i64>), sqlx::Error>;
+    fn stream_datacenters(&self, query: CommonPaginationQuery) -> Result<RowStream<Datacenter>, sqlx::Error>;
     async fn get_datacenter_by_id(&self, datacenter_id: i32) -> Result<Option<Datacenter>, sqlx::Error>;
     async fn get_datacenter_with_racks(&self, datacenter_id: i32) -> Result<Option<DatacenterWithRacks>, sqlx::Error>;
     async fn create_datacenter(&self, datacenter: Datacenter) -> Result<i32, sqlx::Error>;
@@ -28,11 +36,16 @@ pub trait DatacenterRepo: Send + Sync {
     
     // Rack Position CRUD operations
     async fn get_positions_by_rack(&self, rack_id: i32) -> Result<Vec<DatacenterRackPosition>, sqlx::Error>;
+    fn stream_positions(&self, query: CommonPaginationQuery) -> Result<RowStream<DatacenterRackPosition>, sqlx::Error>;
     async fn get_position_by_id(&self, position_id: i32) -> Result<Option<DatacenterRackPosition>, sqlx::Error>;
     async fn check_position_overlap(&self, rack_id: i32, u_position: i32, u_height: i32, exclude_position_id: Option<i32>) -> Result<bool, sqlx::Error>;
+    async fn position_u_height(&self, position: &DatacenterRackPosition) -> Result<i32, sqlx::Error>;
     async fn create_position(&self, position: DatacenterRackPosition) -> Result<i32, sqlx::Error>;
     async fn update_position(&self, position_id: i32, updates: HashMap<String, serde_json::Value>) -> Result<bool, sqlx::Error>;
     async fn delete_position(&self, position_id: i32) -> Result<bool, sqlx::Error>;
+
+    // Bulk import
+    async fn import(&self, rows: Vec<ImportRow>, dry_run: bool) -> Result<ImportReport, sqlx::Error>;
     
     // Statistics and reporting
     async fn get_datacenter_stats(&self, datacenter_id: i32) -> Result<serde_json::Value, sqlx::Error>;
@@ -40,32 +53,95 @@ pub trait DatacenterRepo: Send + Sync {
     async fn get_rack_utilization(&self, rack_id: i32) -> Result<serde_json::Value, sqlx::Error>;
 }
 
-#[derive(Clone)]
-pub struct DatacenterRepository {
-    pool: MySqlPool,
+/// `DatacenterRepo` on any storage backend; `SqliteDatacenterRepository` is this on SQLite
+pub struct DatacenterRepository<DB: sqlx::Database = MySql> {
+    pool: Pool<DB>,
+}
+
+impl<DB: sqlx::Database> Clone for DatacenterRepository<DB> {
+    fn clone(&self) -> Self {
+        Self { pool: self.pool.clone() }
+    }
+}
+
+/// Why a bulk import row was not inserted
+enum RowFailure {
+    Invalid(ImportRowError),
+    Database(sqlx::Error),
+}
+
+impl RowFailure {
+    fn invalid(row: &ImportRow, field: &str, message: impl Into<String>) -> Self {
+        RowFailure::Invalid(ImportRowError::new(row, Some(field), message))
+    }
+}
+
+impl From<sqlx::Error> for RowFailure {
+    fn from(e: sqlx::Error) -> Self {
+        RowFailure::Database(e)
+    }
 }
 
-impl DatacenterRepository {
-    pub fn new(pool: MySqlPool) -> Self {
+impl<DB> DatacenterRepository<DB>
+where
+    DB: SqlDialect,
+    for<'c> &'c mut DB::Connection: Executor<'c, Database = DB>,
+    for<'q> <DB as HasArguments<'q>>::Arguments: IntoArguments<'q, DB>,
+    for<'r> &'r str: ColumnIndex<DB::Row>,
+    usize: ColumnIndex<DB::Row>,
+    for<'q> &'q str: Encode<'q, DB> + Type<DB>,
+    for<'q> Option<&'q str>: Encode<'q, DB>,
+    for<'q> Option<&'q String>: Encode<'q, DB>,
+    for<'q, 'r> String: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+    for<'q> Option<String>: Encode<'q, DB>,
+    for<'q, 'r> i32: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+    for<'q> Option<i32>: Encode<'q, DB>,
+    for<'q, 'r> i64: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+    for<'q> Option<i64>: Encode<'q, DB>,
+    for<'q, 'r> f64: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+    for<'q> Option<f64>: Encode<'q, DB>,
+    for<'q, 'r> bool: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+    for<'q> Option<bool>: Encode<'q, DB>,
+    for<'q> i8: Encode<'q, DB> + Type<DB>,
+    for<'q, 'r> NaiveDateTime: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+    for<'q> Option<NaiveDateTime>: Encode<'q, DB>,
+    for<'q, 'r> DateTime<Utc>: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+    for<'q> Option<DateTime<Utc>>: Encode<'q, DB>,
+    for<'q, 'r> serde_json::Value: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+    for<'q> Option<serde_json::Value>: Encode<'q, DB>,
+    for<'q, 'r> DecimalColumn: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+{
+    pub fn new(pool: Pool<DB>) -> Self {
         Self { pool }
     }
 
     /// Get all datacenters with pagination and filtering
-    pub async fn get_all_datacenters(&self, query: CommonPaginationQuery) -> Result<Vec<Datacenter>, sqlx::Error> {
-        let (_, per_page, offset, columns, where_conditions, _) = QueryParser::parse_all(
+    pub async fn get_all_datacenters(&self, query: CommonPaginationQuery) -> Result<(Vec<Datacenter>, Option<i64>), sqlx::Error> {
+        let ParsedQuery { where_conditions, options, .. } = QueryParser::parse_all(
             &query,
+            &Datacenter::SCHEMA,
             Some("data_center_name ASC".to_string())
         ).map_err(|e| sqlx::Error::Protocol(format!("Query parsing error: {}", e)))?;
 
-        let options = QueryOptions {
-            columns,
-            where_conditions,
-            order_by: Some("data_center_name ASC".to_string()),
-            limit: Some(per_page as i64),
-            offset: Some(offset as i64),
+        let total_count = if query.include_total() {
+            Some(QueryBuilderHelper::count(&self.pool, &Datacenter::SCHEMA, &where_conditions).await?)
+        } else {
+            None
         };
 
-        QueryBuilderHelper::select(&self.pool, Datacenter::TABLE, options).await
+        let datacenters = QueryBuilderHelper::select(&self.pool, &Datacenter::SCHEMA, options).await?;
+        Ok((datacenters, total_count))
+    }
+
+    /// Stream all datacenters matching the query, for exports
+    pub fn stream_datacenters(&self, query: CommonPaginationQuery) -> Result<RowStream<Datacenter>, sqlx::Error> {
+        let ParsedQuery { options, .. } = QueryParser::parse_all(
+            &query,
+            &Datacenter::SCHEMA,
+            Some("data_center_name ASC".to_string())
+        ).map_err(|e| sqlx::Error::Protocol(format!("Query parsing error: {}", e)))?;
+
+        QueryBuilderHelper::stream(self.pool.clone(), &Datacenter::SCHEMA, QueryParser::export_options(options))
     }
 
     /// Get a single datacenter by ID
@@ -90,6 +166,13 @@ impl DatacenterRepository {
 
     /// Create a new datacenter
     pub async fn create_datacenter(&self, datacenter: Datacenter) -> Result<i32, sqlx::Error> {
+        Self::insert_datacenter(&self.pool, &datacenter).await
+    }
+
+    pub(crate) async fn insert_datacenter<'e, E>(executor: E, datacenter: &Datacenter) -> Result<i32, sqlx::Error>
+    where
+        E: Executor<'e, Database = DB>,
+    {
         let query = r#"
             INSERT INTO datacenters (
                 data_center_name, data_center_code, description,
@@ -114,14 +197,14 @@ impl DatacenterRepository {
             .bind(&datacenter.country)
             .bind(&datacenter.postal_code)
             .bind(&datacenter.region)
-            .bind(datacenter.latitude)
-            .bind(datacenter.longitude)
+            .bind(DecimalColumn(datacenter.latitude))
+            .bind(DecimalColumn(datacenter.longitude))
             .bind(&datacenter.provider)
             .bind(&datacenter.provider_facility_id)
             .bind(&datacenter.tier_level)
-            .bind(datacenter.total_floor_space_sqm)
-            .bind(datacenter.power_capacity_kw)
-            .bind(datacenter.cooling_capacity_kw)
+            .bind(DecimalColumn(datacenter.total_floor_space_sqm))
+            .bind(DecimalColumn(datacenter.power_capacity_kw))
+            .bind(DecimalColumn(datacenter.cooling_capacity_kw))
             .bind(&datacenter.status)
             .bind(&datacenter.facility_manager)
             .bind(&datacenter.contact_phone)
@@ -132,10 +215,10 @@ impl DatacenterRepository {
             .bind(&datacenter.operating_hours)
             .bind(&datacenter.tags)
             .bind(&datacenter.metadata)
-            .execute(&self.pool)
+            .execute(executor)
             .await?;
 
-        Ok(result.last_insert_id() as i32)
+        Ok(DB::last_insert_id(&result) as i32)
     }
 
     /// Update datacenter with dynamic field updates
@@ -167,7 +250,7 @@ impl DatacenterRepository {
             .execute(&self.pool)
             .await?;
 
-        Ok(result.rows_affected() > 0)
+        Ok(DB::rows_affected(&result) > 0)
     }
 
     /// Get all racks for a datacenter
@@ -205,6 +288,13 @@ impl DatacenterRepository {
 
     /// Create a new rack
     pub async fn create_rack(&self, rack: DatacenterRack) -> Result<i32, sqlx::Error> {
+        Self::insert_rack(&self.pool, &rack).await
+    }
+
+    pub(crate) async fn insert_rack<'e, E>(executor: E, rack: &DatacenterRack) -> Result<i32, sqlx::Error>
+    where
+        E: Executor<'e, Database = DB>,
+    {
         let query = r#"
             INSERT INTO datacenter_racks (
                 data_center_id, rack_name, rack_code, description,
@@ -241,10 +331,10 @@ impl DatacenterRepository {
             .bind(&rack.access_level)
             .bind(&rack.tags)
             .bind(&rack.metadata)
-            .execute(&self.pool)
+            .execute(executor)
             .await?;
 
-        Ok(result.last_insert_id() as i32)
+        Ok(DB::last_insert_id(&result) as i32)
     }
 
     /// Update rack with dynamic field updates
@@ -276,7 +366,7 @@ impl DatacenterRepository {
             .execute(&self.pool)
             .await?;
 
-        Ok(result.rows_affected() > 0)
+        Ok(DB::rows_affected(&result) > 0)
     }
 
     /// Get all positions for a rack
@@ -292,6 +382,17 @@ impl DatacenterRepository {
             .await
     }
 
+    /// Stream all positions matching the query, for exports
+    pub fn stream_positions(&self, query: CommonPaginationQuery) -> Result<RowStream<DatacenterRackPosition>, sqlx::Error> {
+        let ParsedQuery { options, .. } = QueryParser::parse_all(
+            &query,
+            &DatacenterRackPosition::SCHEMA,
+            Some("u_position ASC".to_string())
+        ).map_err(|e| sqlx::Error::Protocol(format!("Query parsing error: {}", e)))?;
+
+        QueryBuilderHelper::stream(self.pool.clone(), &DatacenterRackPosition::SCHEMA, QueryParser::export_options(options))
+    }
+
     /// Get a single position by ID
     pub async fn get_position_by_id(&self, position_id: i32) -> Result<Option<DatacenterRackPosition>, sqlx::Error> {
         DatabaseHelper::get_by_id(&self.pool, DatacenterRackPosition::TABLE, DatacenterRackPosition::KEY, position_id as i64).await
@@ -307,6 +408,19 @@ impl DatacenterRepository {
         u_height: i32,
         exclude_position_id: Option<i32>,
     ) -> Result<bool, sqlx::Error> {
+        Self::position_overlaps(&self.pool, rack_id, u_position, u_height, exclude_position_id).await
+    }
+
+    async fn position_overlaps<'e, E>(
+        executor: E,
+        rack_id: i32,
+        u_position: i32,
+        u_height: i32,
+        exclude_position_id: Option<i32>,
+    ) -> Result<bool, sqlx::Error>
+    where
+        E: Executor<'e, Database = DB>,
+    {
         // Two ranges [a, a+ah) and [b, b+bh) overlap when a < b+bh AND b < a+ah.
         // Here (a, ah) is the candidate; (b, bh) are existing rows.
         let new_end = u_position + u_height; // exclusive upper bound
@@ -326,7 +440,7 @@ impl DatacenterRepository {
             .bind(exclude_id)
             .bind(new_end)
             .bind(u_position)
-            .fetch_one(&self.pool)
+            .fetch_one(executor)
             .await?
         } else {
             sqlx::query_as(
@@ -341,15 +455,44 @@ impl DatacenterRepository {
             .bind(rack_id)
             .bind(new_end)
             .bind(u_position)
-            .fetch_one(&self.pool)
+            .fetch_one(executor)
             .await?
         };
 
         Ok(count.0 > 0)
     }
 
+    /// Number of rack units a new position will occupy: a SERVER takes the server's own
+    /// u_height, every other device type is 1U
+    pub async fn position_u_height(&self, position: &DatacenterRackPosition) -> Result<i32, sqlx::Error> {
+        Self::device_u_height(&self.pool, position).await
+    }
+
+    async fn device_u_height<'e, E>(executor: E, position: &DatacenterRackPosition) -> Result<i32, sqlx::Error>
+    where
+        E: Executor<'e, Database = DB>,
+    {
+        let server_id = match (position.device_type.as_deref(), position.server_id) {
+            (Some("SERVER"), Some(server_id)) => server_id,
+            _ => return Ok(1),
+        };
+        let u_height: Option<(Option<i32>,)> = sqlx::query_as("SELECT u_height FROM servers WHERE server_id = ?")
+            .bind(server_id)
+            .fetch_optional(executor)
+            .await?;
+
+        Ok(u_height.and_then(|(u_height,)| u_height).unwrap_or(1))
+    }
+
     /// Create a new position
     pub async fn create_position(&self, position: DatacenterRackPosition) -> Result<i32, sqlx::Error> {
+        Self::insert_position(&self.pool, &position).await
+    }
+
+    async fn insert_position<'e, E>(executor: E, position: &DatacenterRackPosition) -> Result<i32, sqlx::Error>
+    where
+        E: Executor<'e, Database = DB>,
+    {
         let query = r#"
             INSERT INTO datacenter_rack_positions (
                 rack_id, u_position, status,
@@ -367,10 +510,10 @@ impl DatacenterRepository {
             .bind(position.server_id)
             .bind(&position.device_type)
             .bind(&position.notes)
-            .execute(&self.pool)
+            .execute(executor)
             .await?;
 
-        Ok(result.last_insert_id() as i32)
+        Ok(DB::last_insert_id(&result) as i32)
     }
 
     /// Update position with dynamic field updates
@@ -402,7 +545,210 @@ impl DatacenterRepository {
             .execute(&self.pool)
             .await?;
 
-        Ok(result.rows_affected() > 0)
+        Ok(DB::rows_affected(&result) > 0)
+    }
+
+    /// Apply a bulk import in a single transaction. Datacenters are inserted first, then racks,
+    /// positions and switches, so rows can reference ones created earlier in the same import.
+    /// Each row goes through the same checks as the single-row endpoints; the transaction is
+    /// rolled back on a dry run or when any row failed, so an import never lands half-applied.
+    pub async fn import(&self, mut rows: Vec<ImportRow>, dry_run: bool) -> Result<ImportReport, sqlx::Error> {
+        rows.sort_by_key(|row| row.kind);
+
+        let mut tx = self.pool.begin().await?;
+        let mut created = ImportCounts::default();
+        let mut errors = Vec::new();
+
+        for row in &rows {
+            match Self::import_row(&mut tx, row).await {
+                Ok(()) => created.add(row.kind),
+                Err(RowFailure::Invalid(error)) => errors.push(error),
+                // Constraint violations (duplicate codes, unknown IDs) belong to the row. MySQL
+                // and SQLite only roll back the failed statement, so the rest of the import
+                // carries on.
+                Err(RowFailure::Database(sqlx::Error::Database(e))) => {
+                    errors.push(ImportRowError::new(row, None, e.message()));
+                }
+                Err(RowFailure::Database(e)) => return Err(e),
+            }
+        }
+
+        let committed = !dry_run && errors.is_empty();
+        if committed {
+            tx.commit().await?;
+            AuditRecorder::record_action(AuditAction {
+                action: "datacenters.import".to_string(),
+                target: Datacenter::TABLE.to_string(),
+                detail: json!(created),
+                success: true,
+                error: None,
+            });
+        } else {
+            tx.rollback().await?;
+        }
+
+        Ok(ImportReport { dry_run, committed, created, errors })
+    }
+
+    async fn import_row(conn: &mut DB::Connection, row: &ImportRow) -> Result<(), RowFailure> {
+        let mut fields = row.fields.clone();
+
+        match row.kind {
+            ImportKind::Datacenters => {
+                let datacenter: Datacenter = Self::import_record(row, fields, &[("status", json!("ACTIVE"))])?;
+                Self::insert_datacenter(&mut *conn, &datacenter).await?;
+            }
+            ImportKind::Racks => {
+                if let Some(data_center_id) = Self::resolve_datacenter(conn, row, &mut fields).await? {
+                    fields.insert("data_center_id".to_string(), json!(data_center_id));
+                }
+                let rack: DatacenterRack = Self::import_record(
+                    row,
+                    fields,
+                    &[("status", json!("ACTIVE")), ("rack_height_u", json!(42))],
+                )?;
+                Self::insert_rack(&mut *conn, &rack).await?;
+            }
+            ImportKind::Positions => {
+                let data_center_id = Self::resolve_datacenter(conn, row, &mut fields).await?;
+                if let Some(rack_id) = Self::resolve_rack(conn, row, &mut fields, data_center_id).await? {
+                    fields.insert("rack_id".to_string(), json!(rack_id));
+                }
+                let position: DatacenterRackPosition = Self::import_record(row, fields, &[("status", json!("AVAILABLE"))])?;
+
+                let u_height = Self::device_u_height(&mut *conn, &position).await?;
+                if Self::position_overlaps(&mut *conn, position.rack_id, position.u_position, u_height, None).await? {
+                    return Err(RowFailure::invalid(row, "u_position", format!(
+                        "U positions {}-{} overlap with an existing position in rack {}",
+                        position.u_position,
+                        position.u_position + u_height - 1,
+                        position.rack_id,
+                    )));
+                }
+                Self::insert_position(&mut *conn, &position).await?;
+            }
+            ImportKind::Switches => {
+                if let Some(data_center_id) = Self::resolve_datacenter(conn, row, &mut fields).await? {
+                    fields.insert("data_center_id".to_string(), json!(data_center_id));
+                }
+                let data_center_id = Self::field_id(&fields, "data_center_id");
+                if let Some(rack_id) = Self::resolve_rack(conn, row, &mut fields, data_center_id).await? {
+                    fields.insert("rack_id".to_string(), json!(rack_id));
+                }
+                let rack_id = Self::field_id(&fields, "rack_id");
+                if let Some(position_id) = Self::resolve_position(conn, row, &mut fields, rack_id).await? {
+                    fields.insert("rack_position_id".to_string(), json!(position_id));
+                }
+                let switch: Switch = Self::import_record(row, fields, &[])?;
+                SwitchRepository::<DB>::insert_switch(&mut *conn, &switch).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Build the model for an import row. The key is a placeholder, omitted columns take the
+    /// table defaults, and rows that still don't fit report serde's message
+    /// (e.g. "missing field `rack_name`").
+    fn import_record<T: DeserializeOwned>(
+        row: &ImportRow,
+        mut fields: Map<String, Value>,
+        defaults: &[(&str, Value)],
+    ) -> Result<T, RowFailure> {
+        fields.insert(row.kind.schema().key.to_string(), json!(0));
+        for (column, value) in defaults {
+            fields.entry(column.to_string()).or_insert_with(|| value.clone());
+        }
+
+        serde_json::from_value(Value::Object(fields))
+            .map_err(|e| RowFailure::Invalid(ImportRowError::new(row, None, e.to_string())))
+    }
+
+    fn field_id(fields: &Map<String, Value>, column: &str) -> Option<i32> {
+        fields.get(column).and_then(Value::as_i64).map(|id| id as i32)
+    }
+
+    /// Replace `data_center_code` with the datacenter's ID. Lookups run inside the import's
+    /// transaction, so they see rows inserted earlier in the same import.
+    async fn resolve_datacenter(
+        conn: &mut DB::Connection,
+        row: &ImportRow,
+        fields: &mut Map<String, Value>,
+    ) -> Result<Option<i32>, RowFailure> {
+        let code = match fields.remove("data_center_code") {
+            Some(Value::String(code)) => code,
+            _ => return Ok(None),
+        };
+
+        let found: Option<(i32,)> = sqlx::query_as("SELECT data_center_id FROM datacenters WHERE data_center_code = ?")
+            .bind(&code)
+            .fetch_optional(&mut *conn)
+            .await?;
+
+        match found {
+            Some((data_center_id,)) => Ok(Some(data_center_id)),
+            None => Err(RowFailure::invalid(row, "data_center_code", format!("No datacenter with code '{}'", code))),
+        }
+    }
+
+    /// Replace `rack_code` with the rack's ID; rack codes are only unique within a datacenter
+    async fn resolve_rack(
+        conn: &mut DB::Connection,
+        row: &ImportRow,
+        fields: &mut Map<String, Value>,
+        data_center_id: Option<i32>,
+    ) -> Result<Option<i32>, RowFailure> {
+        let code = match fields.remove("rack_code") {
+            Some(Value::String(code)) => code,
+            _ => return Ok(None),
+        };
+        let data_center_id = data_center_id
+            .ok_or_else(|| RowFailure::invalid(row, "rack_code", "rack_code needs data_center_code to identify the datacenter"))?;
+
+        let found: Option<(i32,)> = sqlx::query_as(
+            "SELECT rack_id FROM datacenter_racks WHERE data_center_id = ? AND rack_code = ?"
+        )
+        .bind(data_center_id)
+        .bind(&code)
+        .fetch_optional(&mut *conn)
+        .await?;
+
+        match found {
+            Some((rack_id,)) => Ok(Some(rack_id)),
+            None => Err(RowFailure::invalid(row, "rack_code", format!(
+                "No rack with code '{}' in datacenter {}", code, data_center_id
+            ))),
+        }
+    }
+
+    /// Replace `u_position` with the ID of the position at that U in the switch's rack
+    async fn resolve_position(
+        conn: &mut DB::Connection,
+        row: &ImportRow,
+        fields: &mut Map<String, Value>,
+        rack_id: Option<i32>,
+    ) -> Result<Option<i32>, RowFailure> {
+        let u_position = match fields.remove("u_position").as_ref().and_then(Value::as_i64) {
+            Some(u_position) => u_position,
+            None => return Ok(None),
+        };
+        let rack_id = rack_id
+            .ok_or_else(|| RowFailure::invalid(row, "u_position", "u_position needs rack_code or rack_id to identify the rack"))?;
+
+        let found: Option<(i32,)> = sqlx::query_as(
+            "SELECT rack_position_id FROM datacenter_rack_positions WHERE rack_id = ? AND u_position = ?"
+        )
+        .bind(rack_id)
+        .bind(u_position)
+        .fetch_optional(&mut *conn)
+        .await?;
+
+        match found {
+            Some((position_id,)) => Ok(Some(position_id)),
+            None => Err(RowFailure::invalid(row, "u_position", format!(
+                "Rack {} has no position at U{}", rack_id, u_position
+            ))),
+        }
     }
 
     /// Get statistics for a specific datacenter
@@ -526,11 +872,43 @@ impl DatacenterRepository {
 }
 
 #[async_trait]
-impl DatacenterRepo for DatacenterRepository {
-    async fn get_all_datacenters(&self, query: CommonPaginationQuery) -> Result<Vec<Datacenter>, sqlx::Error> {
+impl<DB> DatacenterRepo for DatacenterRepository<DB>
+where
+    DB: SqlDialect,
+    for<'c> &'c mut DB::Connection: Executor<'c, Database = DB>,
+    for<'q> <DB as HasArguments<'q>>::Arguments: IntoArguments<'q, DB>,
+    for<'r> &'r str: ColumnIndex<DB::Row>,
+    usize: ColumnIndex<DB::Row>,
+    for<'q> &'q str: Encode<'q, DB> + Type<DB>,
+    for<'q> Option<&'q str>: Encode<'q, DB>,
+    for<'q> Option<&'q String>: Encode<'q, DB>,
+    for<'q, 'r> String: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+    for<'q> Option<String>: Encode<'q, DB>,
+    for<'q, 'r> i32: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+    for<'q> Option<i32>: Encode<'q, DB>,
+    for<'q, 'r> i64: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+    for<'q> Option<i64>: Encode<'q, DB>,
+    for<'q, 'r> f64: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+    for<'q> Option<f64>: Encode<'q, DB>,
+    for<'q, 'r> bool: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+    for<'q> Option<bool>: Encode<'q, DB>,
+    for<'q> i8: Encode<'q, DB> + Type<DB>,
+    for<'q, 'r> NaiveDateTime: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+    for<'q> Option<NaiveDateTime>: Encode<'q, DB>,
+    for<'q, 'r> DateTime<Utc>: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+    for<'q> Option<DateTime<Utc>>: Encode<'q, DB>,
+    for<'q, 'r> serde_json::Value: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+    for<'q> Option<serde_json::Value>: Encode<'q, DB>,
+    for<'q, 'r> DecimalColumn: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+{
+    async fn get_all_datacenters(&self, query: CommonPaginationQuery) -> Result<(Vec<Datacenter>, Option<i64>), sqlx::Error> {
         self.get_all_datacenters(query).await
     }
 
+    fn stream_datacenters(&self, query: CommonPaginationQuery) -> Result<RowStream<Datacenter>, sqlx::Error> {
+        Self::stream_datacenters(self, query)
+    }
+
     async fn get_datacenter_by_id(&self, datacenter_id: i32) -> Result<Option<Datacenter>, sqlx::Error> {
         self.get_datacenter_by_id(datacenter_id).await
     }
@@ -579,6 +957,10 @@ impl DatacenterRepo for DatacenterRepository {
         self.get_positions_by_rack(rack_id).await
     }
 
+    fn stream_positions(&self, query: CommonPaginationQuery) -> Result<RowStream<DatacenterRackPosition>, sqlx::Error> {
+        Self::stream_positions(self, query)
+    }
+
     async fn get_position_by_id(&self, position_id: i32) -> Result<Option<DatacenterRackPosition>, sqlx::Error> {
         self.get_position_by_id(position_id).await
     }
@@ -587,6 +969,10 @@ impl DatacenterRepo for DatacenterRepository {
         self.check_position_overlap(rack_id, u_position, u_height, exclude_position_id).await
     }
 
+    async fn position_u_height(&self, position: &DatacenterRackPosition) -> Result<i32, sqlx::Error> {
+        self.position_u_height(position).await
+    }
+
     async fn create_position(&self, position: DatacenterRackPosition) -> Result<i32, sqlx::Error> {
         self.create_position(position).await
     }
@@ -599,6 +985,10 @@ impl DatacenterRepo for DatacenterRepository {
         self.delete_position(position_id).await
     }
 
+    async fn import(&self, rows: Vec<ImportRow>, dry_run: bool) -> Result<ImportReport, sqlx::Error> {
+        self.import(rows, dry_run).await
+    }
+
     async fn get_datacenter_stats(&self, datacenter_id: i32) -> Result<serde_json::Value, sqlx::Error> {
         self.get_datacenter_stats(datacenter_id).await
     }
@@ -0,0 +1,125 @@
+use sqlx::MySqlPool;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use crate::models::FleetStats;
+
+#[async_trait]
+pub trait FleetRepo: Send + Sync {
+    async fn get_fleet_stats(&self, stale_before: DateTime<Utc>) -> Result<FleetStats, sqlx::Error>;
+}
+
+#[derive(Clone)]
+pub struct FleetRepository {
+    pool: MySqlPool,
+}
+
+impl FleetRepository {
+    pub fn new(pool: MySqlPool) -> Self {
+        Self { pool }
+    }
+
+    /// Grouped fleet counts for the inventory gauges. Agents of ACTIVE servers that have
+    /// not reported since `stale_before` count as stale.
+    pub async fn get_fleet_stats(&self, stale_before: DateTime<Utc>) -> Result<FleetStats, sqlx::Error> {
+        let servers = sqlx::query_as(
+            r#"
+            SELECT s.status, COALESCE(s.server_type, '') AS server_type,
+                   COALESCE(c.cluster_code, '') AS cluster, COALESCE(d.data_center_code, '') AS datacenter,
+                   COUNT(*) AS count
+            FROM servers s
+            LEFT JOIN server_clusters c ON c.cluster_id = s.cluster_id
+            LEFT JOIN datacenters d ON d.data_center_id = s.data_center_id
+            GROUP BY s.status, s.server_type, c.cluster_code, d.data_center_code
+            "#,
+        )
+        .fetch_all(&self.pool);
+
+        let stale_agents = sqlx::query_as(
+            r#"
+            SELECT COALESCE(c.cluster_code, '') AS cluster, COALESCE(d.data_center_code, '') AS datacenter,
+                   COUNT(*) AS count
+            FROM servers s
+            LEFT JOIN server_clusters c ON c.cluster_id = s.cluster_id
+            LEFT JOIN datacenters d ON d.data_center_id = s.data_center_id
+            WHERE s.status = 'ACTIVE' AND (s.last_inventory_at IS NULL OR s.last_inventory_at < ?)
+            GROUP BY c.cluster_code, d.data_center_code
+            "#,
+        )
+        .bind(stale_before)
+        .fetch_all(&self.pool);
+
+        let gpus = sqlx::query_as(
+            r#"
+            SELECT t.vendor, t.model, COUNT(*) AS count
+            FROM server_gpus g
+            JOIN component_gpu_types t ON t.component_gpu_id = g.component_gpu_id
+            GROUP BY t.vendor, t.model
+            "#,
+        )
+        .fetch_all(&self.pool);
+
+        let disks = sqlx::query_as(
+            r#"
+            SELECT COALESCE(smart_health, 'UNKNOWN') AS smart_health, COUNT(*) AS count
+            FROM server_disks
+            GROUP BY COALESCE(smart_health, 'UNKNOWN')
+            "#,
+        )
+        .fetch_all(&self.pool);
+
+        // occupied_u on the rack row is not maintained, so count the servers placed in it
+        let racks = sqlx::query_as(
+            r#"
+            SELECT d.data_center_code AS datacenter, r.rack_code AS rack, r.rack_height_u AS height_u,
+                   CAST(COALESCE(SUM(s.u_height), 0) AS SIGNED) AS occupied_u
+            FROM datacenter_racks r
+            JOIN datacenters d ON d.data_center_id = r.data_center_id
+            LEFT JOIN servers s ON s.rack_id = r.rack_id
+            GROUP BY r.rack_id, d.data_center_code, r.rack_code, r.rack_height_u
+            "#,
+        )
+        .fetch_all(&self.pool);
+
+        let vms = sqlx::query_as(
+            r#"
+            SELECT hypervisor_type, vm_state, COUNT(*) AS count,
+                   CAST(COALESCE(SUM(vcpu_count), 0) AS SIGNED) AS vcpus,
+                   CAST(COALESCE(SUM(memory_mb), 0) AS SIGNED) AS memory_mb
+            FROM virtual_machines
+            GROUP BY hypervisor_type, vm_state
+            "#,
+        )
+        .fetch_all(&self.pool);
+
+        let switches = sqlx::query_as(
+            r#"
+            SELECT COALESCE(status, '') AS status, COALESCE(switch_role, '') AS switch_role, COUNT(*) AS count
+            FROM switches
+            GROUP BY status, switch_role
+            "#,
+        )
+        .fetch_all(&self.pool);
+
+        let switch_ports = sqlx::query_as(
+            r#"
+            SELECT COALESCE(oper_status, 'UNKNOWN') AS oper_status, COUNT(*) AS count
+            FROM switch_ports
+            GROUP BY COALESCE(oper_status, 'UNKNOWN')
+            "#,
+        )
+        .fetch_all(&self.pool);
+
+        let (servers, stale_agents, gpus, disks, racks, vms, switches, switch_ports) = tokio::try_join!(
+            servers, stale_agents, gpus, disks, racks, vms, switches, switch_ports
+        )?;
+
+        Ok(FleetStats { servers, stale_agents, gpus, disks, racks, vms, switches, switch_ports })
+    }
+}
+
+#[async_trait]
+impl FleetRepo for FleetRepository {
+    async fn get_fleet_stats(&self, stale_before: DateTime<Utc>) -> Result<FleetStats, sqlx::Error> {
+        self.get_fleet_stats(stale_before).await
+    }
+}
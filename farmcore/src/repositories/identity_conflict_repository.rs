@@ -0,0 +1,74 @@
+use sqlx::MySqlPool;
+use async_trait::async_trait;
+use crate::database::{QueryBuilderHelper, RowStream};
+use crate::models::{QueryOptions, QueryValue, ServerIdentityConflict, WhereCondition};
+use crate::api::query_parser::{CommonPaginationQuery, ParsedQuery, QueryParser};
+
+/// Read side of server_identity_conflicts; the inventory sync writes entries
+#[async_trait]
+pub trait IdentityConflictRepo: Send + Sync {
+    async fn get_identity_conflicts(&self, query: CommonPaginationQuery, include_resolved: bool) -> Result<(Vec<ServerIdentityConflict>, Option<i64>), sqlx::Error>;
+    fn stream_identity_conflicts(&self, query: CommonPaginationQuery, include_resolved: bool) -> Result<RowStream<ServerIdentityConflict>, sqlx::Error>;
+}
+
+/// Parse the query, newest first, limited to open conflicts unless `include_resolved`.
+/// Shared with the SQLite repository.
+pub(crate) fn parse_conflict_query(
+    query: &CommonPaginationQuery,
+    include_resolved: bool,
+) -> Result<(Vec<WhereCondition>, QueryOptions), sqlx::Error> {
+    let ParsedQuery { mut where_conditions, mut options, .. } = QueryParser::parse_all(
+        query,
+        &ServerIdentityConflict::SCHEMA,
+        Some("conflict_id DESC".to_string())
+    ).map_err(|e| sqlx::Error::Protocol(format!("Query parsing error: {}", e)))?;
+
+    if !include_resolved {
+        let unresolved = WhereCondition::compare("resolved_at", "IS NULL", QueryValue::Null, Some("AND".to_string()));
+        where_conditions.push(unresolved.clone());
+        options.where_conditions.push(unresolved);
+    }
+    Ok((where_conditions, options))
+}
+
+#[derive(Clone)]
+pub struct IdentityConflictRepository {
+    pool: MySqlPool,
+}
+
+impl IdentityConflictRepository {
+    pub fn new(pool: MySqlPool) -> Self {
+        Self { pool }
+    }
+
+    /// Get identity conflicts with pagination and filtering, newest first
+    pub async fn get_identity_conflicts(&self, query: CommonPaginationQuery, include_resolved: bool) -> Result<(Vec<ServerIdentityConflict>, Option<i64>), sqlx::Error> {
+        let (where_conditions, options) = parse_conflict_query(&query, include_resolved)?;
+
+        let total_count = if query.include_total() {
+            Some(QueryBuilderHelper::count(&self.pool, &ServerIdentityConflict::SCHEMA, &where_conditions).await?)
+        } else {
+            None
+        };
+
+        let conflicts = QueryBuilderHelper::select(&self.pool, &ServerIdentityConflict::SCHEMA, options).await?;
+        Ok((conflicts, total_count))
+    }
+
+    /// Stream all identity conflicts matching the query, for exports
+    pub fn stream_identity_conflicts(&self, query: CommonPaginationQuery, include_resolved: bool) -> Result<RowStream<ServerIdentityConflict>, sqlx::Error> {
+        let (_, options) = parse_conflict_query(&query, include_resolved)?;
+        QueryBuilderHelper::stream(self.pool.clone(), &ServerIdentityConflict::SCHEMA, QueryParser::export_options(options))
+    }
+}
+
+#[async_trait]
+impl IdentityConflictRepo for IdentityConflictRepository {
+    async fn get_identity_conflicts(&self, query: CommonPaginationQuery, include_resolved: bool) -> Result<(Vec<ServerIdentityConflict>, Option<i64>), sqlx::Error> {
+        self.get_identity_conflicts(query, include_resolved).await
+    }
+
+    fn stream_identity_conflicts(&self, query: CommonPaginationQuery, include_resolved: bool) -> Result<RowStream<ServerIdentityConflict>, sqlx::Error> {
+        Self::stream_identity_conflicts(self, query, include_resolved)
+    }
+}
@@ -0,0 +1,63 @@
+use sqlx::MySqlPool;
+use async_trait::async_trait;
+use crate::database::{QueryBuilderHelper, RowStream};
+use crate::models::ServerInventoryChange;
+use crate::api::query_parser::{CommonPaginationQuery, ParsedQuery, QueryParser};
+
+/// Read side of the component change history; the inventory sync writes it
+#[async_trait]
+pub trait InventoryChangeRepo: Send + Sync {
+    async fn get_all_changes(&self, query: CommonPaginationQuery) -> Result<(Vec<ServerInventoryChange>, Option<i64>), sqlx::Error>;
+    fn stream_changes(&self, query: CommonPaginationQuery) -> Result<RowStream<ServerInventoryChange>, sqlx::Error>;
+}
+
+#[derive(Clone)]
+pub struct InventoryChangeRepository {
+    pool: MySqlPool,
+}
+
+impl InventoryChangeRepository {
+    pub fn new(pool: MySqlPool) -> Self {
+        Self { pool }
+    }
+
+    /// Get component changes with pagination and filtering, newest first
+    pub async fn get_all_changes(&self, query: CommonPaginationQuery) -> Result<(Vec<ServerInventoryChange>, Option<i64>), sqlx::Error> {
+        let ParsedQuery { where_conditions, options, .. } = QueryParser::parse_all(
+            &query,
+            &ServerInventoryChange::SCHEMA,
+            Some("change_id DESC".to_string())
+        ).map_err(|e| sqlx::Error::Protocol(format!("Query parsing error: {}", e)))?;
+
+        let total_count = if query.include_total() {
+            Some(QueryBuilderHelper::count(&self.pool, &ServerInventoryChange::SCHEMA, &where_conditions).await?)
+        } else {
+            None
+        };
+
+        let changes = QueryBuilderHelper::select(&self.pool, &ServerInventoryChange::SCHEMA, options).await?;
+        Ok((changes, total_count))
+    }
+
+    /// Stream all component changes matching the query, for exports
+    pub fn stream_changes(&self, query: CommonPaginationQuery) -> Result<RowStream<ServerInventoryChange>, sqlx::Error> {
+        let ParsedQuery { options, .. } = QueryParser::parse_all(
+            &query,
+            &ServerInventoryChange::SCHEMA,
+            Some("change_id DESC".to_string())
+        ).map_err(|e| sqlx::Error::Protocol(format!("Query parsing error: {}", e)))?;
+
+        QueryBuilderHelper::stream(self.pool.clone(), &ServerInventoryChange::SCHEMA, QueryParser::export_options(options))
+    }
+}
+
+#[async_trait]
+impl InventoryChangeRepo for InventoryChangeRepository {
+    async fn get_all_changes(&self, query: CommonPaginationQuery) -> Result<(Vec<ServerInventoryChange>, Option<i64>), sqlx::Error> {
+        self.get_all_changes(query).await
+    }
+
+    fn stream_changes(&self, query: CommonPaginationQuery) -> Result<RowStream<ServerInventoryChange>, sqlx::Error> {
+        Self::stream_changes(self, query)
+    }
+}
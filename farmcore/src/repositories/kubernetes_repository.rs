@@ -1,102 +1,25 @@
-use sqlx::MySqlPool;
+use sqlx::database::HasArguments;
+use sqlx::{ColumnIndex, Decode, Encode, Executor, IntoArguments, MySql, Pool, Type};
 use async_trait::async_trait;
 use std::collections::HashMap;
-use crate::database::{QueryBuilderHelper, DatabaseHelper};
+use chrono::{DateTime, NaiveDateTime, Utc};
+use crate::database::{QueryBuilderHelper, DatabaseHelper, DecimalColumn, RowStream, SqlDialect};
 use crate::models::{
     KubernetesCluster, KubernetesNode, KubernetesNodeGroup, KubernetesNamespace,
     KubernetesWorkload, KubernetesPod, KubernetesService, KubernetesIngress,
     KubernetesEvent, KubernetesMetric, KubernetesSecret, KubernetesConfigMap,
-    ClusterWithDetails, NamespaceWithResources, NodeWithMetrics, QueryOptions
+    ClusterWithDetails, NamespaceWithResources, NodeWithMetrics
 };
-use crate::api::query_parser::{CommonPaginationQuery, QueryParser};
-
-// Inventory data structures matching the JSON format from farmmanager
-#[derive(Debug, serde::Deserialize)]
-pub struct K8sInventory {
-    pub cluster_name: String,
-    pub cluster_version: String,
-    pub api_server: String,
-    pub nodes: Vec<K8sNodeInventory>,
-    pub namespaces: Vec<K8sNamespaceInventory>,
-    pub pods: Option<Vec<K8sPodInventory>>,
-    pub services: Option<Vec<K8sServiceInventory>>,
-    pub workloads: Option<Vec<K8sWorkloadInventory>>,
-}
-
-#[derive(Debug, serde::Deserialize)]
-pub struct K8sNodeInventory {
-    pub node_name: String,
-    pub node_uid: Option<String>,
-    pub node_type: Option<String>,
-    pub internal_ip: Option<String>,
-    pub external_ip: Option<String>,
-    pub hostname: Option<String>,
-    pub cpu_capacity: Option<i32>,
-    pub memory_capacity_mb: Option<i64>,
-    pub pod_capacity: Option<i32>,
-    pub node_state: Option<String>,
-    pub kubelet_version: Option<String>,
-    pub os_image: Option<String>,
-    pub kernel_version: Option<String>,
-    pub container_runtime_version: Option<String>,
-    pub roles: Option<String>,
-    pub labels: Option<serde_json::Value>,
-    pub taints: Option<serde_json::Value>,
-}
+use crate::api::query_parser::{CommonPaginationQuery, ParsedQuery, QueryParser};
 
-#[derive(Debug, serde::Deserialize)]
-pub struct K8sNamespaceInventory {
-    pub namespace_name: String,
-    pub namespace_uid: Option<String>,
-    pub namespace_state: Option<String>,
-    pub labels: Option<serde_json::Value>,
-    pub annotations: Option<serde_json::Value>,
-}
-
-#[derive(Debug, serde::Deserialize)]
-pub struct K8sPodInventory {
-    pub pod_name: String,
-    pub pod_uid: Option<String>,
-    pub namespace: String,
-    pub pod_phase: Option<String>,
-    pub node_name: Option<String>,
-    pub pod_ip: Option<String>,
-    pub host_ip: Option<String>,
-    pub start_time: Option<String>,
-    pub labels: Option<serde_json::Value>,
-    pub annotations: Option<serde_json::Value>,
-}
-
-#[derive(Debug, serde::Deserialize)]
-pub struct K8sServiceInventory {
-    pub service_name: String,
-    pub service_uid: Option<String>,
-    pub namespace: String,
-    pub service_type: Option<String>,
-    pub cluster_ip: Option<String>,
-    pub external_ip: Option<String>,
-    pub ports: Option<serde_json::Value>,
-    pub selector: Option<serde_json::Value>,
-    pub labels: Option<serde_json::Value>,
-}
-
-#[derive(Debug, serde::Deserialize)]
-pub struct K8sWorkloadInventory {
-    pub workload_name: String,
-    pub workload_uid: Option<String>,
-    pub namespace: String,
-    pub workload_type: String,
-    pub replicas_desired: Option<i32>,
-    pub replicas_ready: Option<i32>,
-    pub replicas_available: Option<i32>,
-    pub labels: Option<serde_json::Value>,
-    pub selector: Option<serde_json::Value>,
-}
+// Inventory payloads are defined in farm-proto, shared with the agent
+pub use farm_proto::k8s::*;
 
 #[async_trait]
 pub trait K8sRepo: Send + Sync {
     // Cluster operations
-    async fn get_all_clusters(&self, query: CommonPaginationQuery) -> Result<Vec<KubernetesCluster>, sqlx::Error>;
+    async fn get_all_clusters(&self, query: CommonPaginationQuery) -> Result<(Vec<KubernetesCluster>, Option<i64>), sqlx::Error>;
+    fn stream_clusters(&self, query: CommonPaginationQuery) -> Result<RowStream<KubernetesCluster>, sqlx::Error>;
     async fn get_cluster_by_id(&self, cluster_id: i32) -> Result<Option<KubernetesCluster>, sqlx::Error>;
     async fn get_cluster_by_name(&self, cluster_name: &str) -> Result<Option<KubernetesCluster>, sqlx::Error>;
     async fn get_cluster_with_details(&self, cluster_id: i32) -> Result<Option<ClusterWithDetails>, sqlx::Error>;
@@ -122,6 +45,8 @@ pub trait K8sRepo: Send + Sync {
     async fn get_workloads_by_type(&self, cluster_id: i32, workload_type: &str) -> Result<Vec<KubernetesWorkload>, sqlx::Error>;
     
     // Pod operations
+    async fn get_pods(&self, query: CommonPaginationQuery) -> Result<(Vec<KubernetesPod>, Option<i64>), sqlx::Error>;
+    fn stream_pods(&self, query: CommonPaginationQuery) -> Result<RowStream<KubernetesPod>, sqlx::Error>;
     async fn get_all_pods(&self, cluster_id: i32, namespace_id: Option<i32>) -> Result<Vec<KubernetesPod>, sqlx::Error>;
     async fn get_pod_by_id(&self, pod_id: i32) -> Result<Option<KubernetesPod>, sqlx::Error>;
     async fn get_pods_by_node(&self, node_id: i32) -> Result<Vec<KubernetesPod>, sqlx::Error>;
@@ -161,13 +86,54 @@ pub trait K8sRepo: Send + Sync {
     async fn upsert_cluster_from_inventory(&self, inventory: K8sInventory) -> Result<i32, sqlx::Error>;
 }
 
-#[derive(Clone)]
-pub struct KubernetesRepository {
-    pool: MySqlPool,
+/// Kubernetes reports states and kinds capitalised ("Ready", "StatefulSet") while the ENUM
+/// columns list them in lower case. MySQL matches ENUM values case-insensitively; SQLite's
+/// CHECK does not.
+fn enum_value(reported: &str) -> String {
+    reported.to_lowercase()
 }
 
-impl KubernetesRepository {
-    pub fn new(pool: MySqlPool) -> Self {
+/// `K8sRepo` on any storage backend; `SqliteKubernetesRepository` is this on SQLite
+pub struct KubernetesRepository<DB: sqlx::Database = MySql> {
+    pool: Pool<DB>,
+}
+
+impl<DB: sqlx::Database> Clone for KubernetesRepository<DB> {
+    fn clone(&self) -> Self {
+        Self { pool: self.pool.clone() }
+    }
+}
+
+impl<DB> KubernetesRepository<DB>
+where
+    DB: SqlDialect,
+    for<'c> &'c mut DB::Connection: Executor<'c, Database = DB>,
+    for<'q> <DB as HasArguments<'q>>::Arguments: IntoArguments<'q, DB>,
+    for<'r> &'r str: ColumnIndex<DB::Row>,
+    usize: ColumnIndex<DB::Row>,
+    for<'q> &'q str: Encode<'q, DB> + Type<DB>,
+    for<'q> Option<&'q str>: Encode<'q, DB>,
+    for<'q> Option<&'q String>: Encode<'q, DB>,
+    for<'q, 'r> String: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+    for<'q> Option<String>: Encode<'q, DB>,
+    for<'q, 'r> i32: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+    for<'q> Option<i32>: Encode<'q, DB>,
+    for<'q, 'r> i64: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+    for<'q> Option<i64>: Encode<'q, DB>,
+    for<'q, 'r> f64: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+    for<'q> Option<f64>: Encode<'q, DB>,
+    for<'q, 'r> bool: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+    for<'q> Option<bool>: Encode<'q, DB>,
+    for<'q> i8: Encode<'q, DB> + Type<DB>,
+    for<'q, 'r> NaiveDateTime: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+    for<'q> Option<NaiveDateTime>: Encode<'q, DB>,
+    for<'q, 'r> DateTime<Utc>: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+    for<'q> Option<DateTime<Utc>>: Encode<'q, DB>,
+    for<'q, 'r> serde_json::Value: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+    for<'q> Option<serde_json::Value>: Encode<'q, DB>,
+    for<'q, 'r> DecimalColumn: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+{
+    pub fn new(pool: Pool<DB>) -> Self {
         Self { pool }
     }
 
@@ -176,21 +142,32 @@ impl KubernetesRepository {
     // ===================================================================
     
     /// Get all clusters with pagination
-    pub async fn get_all_clusters(&self, query: CommonPaginationQuery) -> Result<Vec<KubernetesCluster>, sqlx::Error> {
-        let (_, per_page, offset, columns, where_conditions, _) = QueryParser::parse_all(
+    pub async fn get_all_clusters(&self, query: CommonPaginationQuery) -> Result<(Vec<KubernetesCluster>, Option<i64>), sqlx::Error> {
+        let ParsedQuery { where_conditions, options, .. } = QueryParser::parse_all(
             &query,
+            &KubernetesCluster::SCHEMA,
             Some("cluster_id DESC".to_string())
         ).map_err(|e| sqlx::Error::Protocol(format!("Query parsing error: {}", e)))?;
 
-        let options = QueryOptions {
-            columns,
-            where_conditions,
-            limit: Some(per_page),
-            offset: Some(offset),
-            order_by: Some("cluster_id DESC".to_string()),
+        let total_count = if query.include_total() {
+            Some(QueryBuilderHelper::count(&self.pool, &KubernetesCluster::SCHEMA, &where_conditions).await?)
+        } else {
+            None
         };
 
-        QueryBuilderHelper::select(&self.pool, KubernetesCluster::TABLE, options).await
+        let clusters = QueryBuilderHelper::select(&self.pool, &KubernetesCluster::SCHEMA, options).await?;
+        Ok((clusters, total_count))
+    }
+
+    /// Stream all clusters matching the query, for exports
+    pub fn stream_clusters(&self, query: CommonPaginationQuery) -> Result<RowStream<KubernetesCluster>, sqlx::Error> {
+        let ParsedQuery { options, .. } = QueryParser::parse_all(
+            &query,
+            &KubernetesCluster::SCHEMA,
+            Some("cluster_id DESC".to_string())
+        ).map_err(|e| sqlx::Error::Protocol(format!("Query parsing error: {}", e)))?;
+
+        QueryBuilderHelper::stream(self.pool.clone(), &KubernetesCluster::SCHEMA, QueryParser::export_options(options))
     }
 
     /// Get cluster by ID
@@ -488,6 +465,35 @@ impl KubernetesRepository {
     // ===================================================================
     
     /// Get all pods for a cluster, optionally filtered by namespace
+    /// Get pods across all clusters with pagination and filtering
+    pub async fn get_pods(&self, query: CommonPaginationQuery) -> Result<(Vec<KubernetesPod>, Option<i64>), sqlx::Error> {
+        let ParsedQuery { where_conditions, options, .. } = QueryParser::parse_all(
+            &query,
+            &KubernetesPod::SCHEMA,
+            Some("pod_id ASC".to_string())
+        ).map_err(|e| sqlx::Error::Protocol(format!("Query parsing error: {}", e)))?;
+
+        let total_count = if query.include_total() {
+            Some(QueryBuilderHelper::count(&self.pool, &KubernetesPod::SCHEMA, &where_conditions).await?)
+        } else {
+            None
+        };
+
+        let pods = QueryBuilderHelper::select(&self.pool, &KubernetesPod::SCHEMA, options).await?;
+        Ok((pods, total_count))
+    }
+
+    /// Stream all pods matching the query, for exports
+    pub fn stream_pods(&self, query: CommonPaginationQuery) -> Result<RowStream<KubernetesPod>, sqlx::Error> {
+        let ParsedQuery { options, .. } = QueryParser::parse_all(
+            &query,
+            &KubernetesPod::SCHEMA,
+            Some("pod_id ASC".to_string())
+        ).map_err(|e| sqlx::Error::Protocol(format!("Query parsing error: {}", e)))?;
+
+        QueryBuilderHelper::stream(self.pool.clone(), &KubernetesPod::SCHEMA, QueryParser::export_options(options))
+    }
+
     pub async fn get_all_pods(&self, cluster_id: i32, namespace_id: Option<i32>) -> Result<Vec<KubernetesPod>, sqlx::Error> {
         let pods: Vec<KubernetesPod> = if let Some(ns_id) = namespace_id {
             sqlx::query_as(&format!(
@@ -777,49 +783,49 @@ impl KubernetesRepository {
     /// Get overview statistics for a cluster
     pub async fn get_cluster_overview_stats(&self, cluster_id: i32) -> Result<serde_json::Value, sqlx::Error> {
         let total_nodes: i64 = sqlx::query_scalar(
-            "SELECT CAST(COUNT(*) AS SIGNED) FROM kubernetes_nodes WHERE cluster_id = ?"
+            "SELECT COUNT(*) FROM kubernetes_nodes WHERE cluster_id = ?"
         )
         .bind(cluster_id)
         .fetch_one(&self.pool)
         .await?;
 
         let ready_nodes: i64 = sqlx::query_scalar(
-            "SELECT CAST(COUNT(*) AS SIGNED) FROM kubernetes_nodes WHERE cluster_id = ? AND node_state = 'ready'"
+            "SELECT COUNT(*) FROM kubernetes_nodes WHERE cluster_id = ? AND node_state = 'ready'"
         )
         .bind(cluster_id)
         .fetch_one(&self.pool)
         .await?;
 
         let total_pods: i64 = sqlx::query_scalar(
-            "SELECT CAST(COUNT(*) AS SIGNED) FROM kubernetes_pods WHERE cluster_id = ?"
+            "SELECT COUNT(*) FROM kubernetes_pods WHERE cluster_id = ?"
         )
         .bind(cluster_id)
         .fetch_one(&self.pool)
         .await?;
 
         let running_pods: i64 = sqlx::query_scalar(
-            "SELECT CAST(COUNT(*) AS SIGNED) FROM kubernetes_pods WHERE cluster_id = ? AND pod_phase = 'running'"
+            "SELECT COUNT(*) FROM kubernetes_pods WHERE cluster_id = ? AND pod_phase = 'running'"
         )
         .bind(cluster_id)
         .fetch_one(&self.pool)
         .await?;
 
         let total_workloads: i64 = sqlx::query_scalar(
-            "SELECT CAST(COUNT(*) AS SIGNED) FROM kubernetes_workloads WHERE cluster_id = ?"
+            "SELECT COUNT(*) FROM kubernetes_workloads WHERE cluster_id = ?"
         )
         .bind(cluster_id)
         .fetch_one(&self.pool)
         .await?;
 
         let total_namespaces: i64 = sqlx::query_scalar(
-            "SELECT CAST(COUNT(*) AS SIGNED) FROM kubernetes_namespaces WHERE cluster_id = ?"
+            "SELECT COUNT(*) FROM kubernetes_namespaces WHERE cluster_id = ?"
         )
         .bind(cluster_id)
         .fetch_one(&self.pool)
         .await?;
 
         let total_services: i64 = sqlx::query_scalar(
-            "SELECT CAST(COUNT(*) AS SIGNED) FROM kubernetes_services WHERE cluster_id = ?"
+            "SELECT COUNT(*) FROM kubernetes_services WHERE cluster_id = ?"
         )
         .bind(cluster_id)
         .fetch_one(&self.pool)
@@ -839,7 +845,7 @@ impl KubernetesRepository {
     /// Get pod counts grouped by phase
     pub async fn get_pod_counts_by_phase(&self, cluster_id: i32) -> Result<Vec<(String, i64)>, sqlx::Error> {
         let results: Vec<(String, i64)> = sqlx::query_as(
-            "SELECT pod_phase, CAST(COUNT(*) AS SIGNED) as count 
+            "SELECT pod_phase, COUNT(*) as count 
              FROM kubernetes_pods 
              WHERE cluster_id = ? 
              GROUP BY pod_phase"
@@ -854,7 +860,7 @@ impl KubernetesRepository {
     /// Get workload counts grouped by type
     pub async fn get_workload_counts_by_type(&self, cluster_id: i32) -> Result<Vec<(String, i64)>, sqlx::Error> {
         let results: Vec<(String, i64)> = sqlx::query_as(
-            "SELECT workload_type, CAST(COUNT(*) AS SIGNED) as count 
+            "SELECT workload_type, COUNT(*) as count 
              FROM kubernetes_workloads 
              WHERE cluster_id = ? 
              GROUP BY workload_type"
@@ -926,7 +932,7 @@ impl KubernetesRepository {
             .execute(&self.pool)
             .await?;
             
-            cluster_id_result.last_insert_id() as i32
+            DB::last_insert_id(&cluster_id_result) as i32
         };
 
         // Upsert namespaces first (needed for other resources)
@@ -943,9 +949,9 @@ impl KubernetesRepository {
             let ns_id = if let Some((ns_id,)) = existing_ns {
                 // Update existing namespace
                 sqlx::query(
-                    "UPDATE kubernetes_namespaces SET namespace_state = ?, labels = ?, annotations = ?, updated_at = NOW() WHERE namespace_id = ?"
+                    "UPDATE kubernetes_namespaces SET namespace_state = ?, labels = ?, annotations = ?, updated_at = CURRENT_TIMESTAMP WHERE namespace_id = ?"
                 )
-                .bind(ns_inv.namespace_state.as_deref().unwrap_or("Active"))
+                .bind(enum_value(ns_inv.namespace_state.as_deref().unwrap_or("Active")))
                 .bind(&ns_inv.labels)
                 .bind(&ns_inv.annotations)
                 .bind(ns_id)
@@ -961,12 +967,12 @@ impl KubernetesRepository {
                 .bind(cluster_id)
                 .bind(&ns_inv.namespace_name)
                 .bind(&ns_inv.namespace_uid)
-                .bind(ns_inv.namespace_state.as_deref().unwrap_or("Active"))
+                .bind(enum_value(ns_inv.namespace_state.as_deref().unwrap_or("Active")))
                 .bind(&ns_inv.labels)
                 .bind(&ns_inv.annotations)
                 .execute(&self.pool)
                 .await?;
-                result.last_insert_id() as i32
+                DB::last_insert_id(&result) as i32
             };
             namespace_map.insert(ns_inv.namespace_name.clone(), ns_id);
         }
@@ -988,7 +994,7 @@ impl KubernetesRepository {
                         node_uid = ?, node_type = ?, internal_ip = ?, external_ip = ?, hostname = ?,
                         cpu_capacity = ?, memory_capacity_mb = ?, pod_capacity = ?, node_state = ?, 
                         kubelet_version = ?, os_image = ?, kernel_version = ?, container_runtime_version = ?,
-                        roles = ?, labels = ?, taints = ?, last_heartbeat = NOW(), updated_at = NOW()
+                        roles = ?, labels = ?, taints = ?, last_heartbeat = CURRENT_TIMESTAMP, updated_at = CURRENT_TIMESTAMP
                      WHERE k8s_node_id = ?"
                 )
                 .bind(&node_inv.node_uid)
@@ -999,7 +1005,7 @@ impl KubernetesRepository {
                 .bind(node_inv.cpu_capacity)
                 .bind(node_inv.memory_capacity_mb)
                 .bind(node_inv.pod_capacity)
-                .bind(node_inv.node_state.as_deref().unwrap_or("unknown"))
+                .bind(enum_value(node_inv.node_state.as_deref().unwrap_or("unknown")))
                 .bind(&node_inv.kubelet_version)
                 .bind(&node_inv.os_image)
                 .bind(&node_inv.kernel_version)
@@ -1029,7 +1035,7 @@ impl KubernetesRepository {
                 .bind(node_inv.cpu_capacity)
                 .bind(node_inv.memory_capacity_mb)
                 .bind(node_inv.pod_capacity)
-                .bind(node_inv.node_state.as_deref().unwrap_or("unknown"))
+                .bind(enum_value(node_inv.node_state.as_deref().unwrap_or("unknown")))
                 .bind(&node_inv.kubelet_version)
                 .bind(&node_inv.os_image)
                 .bind(&node_inv.kernel_version)
@@ -1077,10 +1083,10 @@ impl KubernetesRepository {
                         // Update existing pod
                         sqlx::query(
                             "UPDATE kubernetes_pods SET pod_uid = ?, pod_phase = ?, k8s_node_id = ?, pod_ip = ?, host_ip = ?, 
-                             started_at = ?, labels = ?, annotations = ?, updated_at = NOW() WHERE pod_id = ?"
+                             started_at = ?, labels = ?, annotations = ?, updated_at = CURRENT_TIMESTAMP WHERE pod_id = ?"
                         )
                         .bind(&pod_inv.pod_uid)
-                        .bind(pod_inv.pod_phase.as_deref().unwrap_or("Unknown"))
+                        .bind(enum_value(pod_inv.pod_phase.as_deref().unwrap_or("Unknown")))
                         .bind(k8s_node_id)
                         .bind(&pod_inv.pod_ip)
                         .bind(&pod_inv.host_ip)
@@ -1108,7 +1114,7 @@ impl KubernetesRepository {
                         .bind(namespace_id)
                         .bind(&pod_inv.pod_name)
                         .bind(&pod_inv.pod_uid)
-                        .bind(pod_inv.pod_phase.as_deref().unwrap_or("Unknown"))
+                        .bind(enum_value(pod_inv.pod_phase.as_deref().unwrap_or("Unknown")))
                         .bind(k8s_node_id)
                         .bind(&pod_inv.pod_ip)
                         .bind(&pod_inv.host_ip)
@@ -1144,7 +1150,7 @@ impl KubernetesRepository {
                         // Update existing service
                         sqlx::query(
                             "UPDATE kubernetes_services SET service_uid = ?, service_type = ?, cluster_ip = ?, 
-                             external_ips = ?, ports = ?, selector = ?, labels = ?, updated_at = NOW() WHERE service_id = ?"
+                             external_ips = ?, ports = ?, selector = ?, labels = ?, updated_at = CURRENT_TIMESTAMP WHERE service_id = ?"
                         )
                         .bind(&svc_inv.service_uid)
                         .bind(svc_inv.service_type.as_deref().unwrap_or("ClusterIP"))
@@ -1197,11 +1203,11 @@ impl KubernetesRepository {
                         // Update existing workload
                         sqlx::query(
                             "UPDATE kubernetes_workloads SET workload_uid = ?, workload_type = ?, replicas_desired = ?, 
-                             replicas_ready = ?, replicas_available = ?, labels = ?, selector = ?, updated_at = NOW() 
+                             replicas_ready = ?, replicas_available = ?, labels = ?, selector = ?, updated_at = CURRENT_TIMESTAMP 
                              WHERE workload_id = ?"
                         )
                         .bind(&wl_inv.workload_uid)
-                        .bind(&wl_inv.workload_type)
+                        .bind(enum_value(&wl_inv.workload_type))
                         .bind(wl_inv.replicas_desired)
                         .bind(wl_inv.replicas_ready)
                         .bind(wl_inv.replicas_available)
@@ -1221,7 +1227,7 @@ impl KubernetesRepository {
                         .bind(namespace_id)
                         .bind(&wl_inv.workload_name)
                         .bind(&wl_inv.workload_uid)
-                        .bind(&wl_inv.workload_type)
+                        .bind(enum_value(&wl_inv.workload_type))
                         .bind(wl_inv.replicas_desired)
                         .bind(wl_inv.replicas_ready)
                         .bind(wl_inv.replicas_available)
@@ -1239,11 +1245,43 @@ impl KubernetesRepository {
 }
 
 #[async_trait]
-impl K8sRepo for KubernetesRepository {
-    async fn get_all_clusters(&self, query: CommonPaginationQuery) -> Result<Vec<KubernetesCluster>, sqlx::Error> {
+impl<DB> K8sRepo for KubernetesRepository<DB>
+where
+    DB: SqlDialect,
+    for<'c> &'c mut DB::Connection: Executor<'c, Database = DB>,
+    for<'q> <DB as HasArguments<'q>>::Arguments: IntoArguments<'q, DB>,
+    for<'r> &'r str: ColumnIndex<DB::Row>,
+    usize: ColumnIndex<DB::Row>,
+    for<'q> &'q str: Encode<'q, DB> + Type<DB>,
+    for<'q> Option<&'q str>: Encode<'q, DB>,
+    for<'q> Option<&'q String>: Encode<'q, DB>,
+    for<'q, 'r> String: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+    for<'q> Option<String>: Encode<'q, DB>,
+    for<'q, 'r> i32: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+    for<'q> Option<i32>: Encode<'q, DB>,
+    for<'q, 'r> i64: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+    for<'q> Option<i64>: Encode<'q, DB>,
+    for<'q, 'r> f64: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+    for<'q> Option<f64>: Encode<'q, DB>,
+    for<'q, 'r> bool: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+    for<'q> Option<bool>: Encode<'q, DB>,
+    for<'q> i8: Encode<'q, DB> + Type<DB>,
+    for<'q, 'r> NaiveDateTime: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+    for<'q> Option<NaiveDateTime>: Encode<'q, DB>,
+    for<'q, 'r> DateTime<Utc>: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+    for<'q> Option<DateTime<Utc>>: Encode<'q, DB>,
+    for<'q, 'r> serde_json::Value: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+    for<'q> Option<serde_json::Value>: Encode<'q, DB>,
+    for<'q, 'r> DecimalColumn: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+{
+    async fn get_all_clusters(&self, query: CommonPaginationQuery) -> Result<(Vec<KubernetesCluster>, Option<i64>), sqlx::Error> {
         self.get_all_clusters(query).await
     }
 
+    fn stream_clusters(&self, query: CommonPaginationQuery) -> Result<RowStream<KubernetesCluster>, sqlx::Error> {
+        Self::stream_clusters(self, query)
+    }
+
     async fn get_cluster_by_id(&self, cluster_id: i32) -> Result<Option<KubernetesCluster>, sqlx::Error> {
         self.get_cluster_by_id(cluster_id).await
     }
@@ -1304,6 +1342,14 @@ impl K8sRepo for KubernetesRepository {
         self.get_workloads_by_type(cluster_id, workload_type).await
     }
 
+    async fn get_pods(&self, query: CommonPaginationQuery) -> Result<(Vec<KubernetesPod>, Option<i64>), sqlx::Error> {
+        self.get_pods(query).await
+    }
+
+    fn stream_pods(&self, query: CommonPaginationQuery) -> Result<RowStream<KubernetesPod>, sqlx::Error> {
+        Self::stream_pods(self, query)
+    }
+
     async fn get_all_pods(&self, cluster_id: i32, namespace_id: Option<i32>) -> Result<Vec<KubernetesPod>, sqlx::Error> {
         self.get_all_pods(cluster_id, namespace_id).await
     }
@@ -0,0 +1,74 @@
+use sqlx::MySqlPool;
+use async_trait::async_trait;
+use crate::database::{QueryBuilderHelper, RowStream};
+use crate::models::{QueryOptions, QueryValue, ServerMissingComponent, WhereCondition};
+use crate::api::query_parser::{CommonPaginationQuery, ParsedQuery, QueryParser};
+
+/// Read side of server_missing_components; the inventory sync writes and resolves entries
+#[async_trait]
+pub trait MissingComponentRepo: Send + Sync {
+    async fn get_missing_components(&self, query: CommonPaginationQuery, include_resolved: bool) -> Result<(Vec<ServerMissingComponent>, Option<i64>), sqlx::Error>;
+    fn stream_missing_components(&self, query: CommonPaginationQuery, include_resolved: bool) -> Result<RowStream<ServerMissingComponent>, sqlx::Error>;
+}
+
+/// Parse the query, newest first, limited to unresolved entries unless `include_resolved`.
+/// Shared with the SQLite repository.
+pub(crate) fn parse_missing_query(
+    query: &CommonPaginationQuery,
+    include_resolved: bool,
+) -> Result<(Vec<WhereCondition>, QueryOptions), sqlx::Error> {
+    let ParsedQuery { mut where_conditions, mut options, .. } = QueryParser::parse_all(
+        query,
+        &ServerMissingComponent::SCHEMA,
+        Some("missing_id DESC".to_string())
+    ).map_err(|e| sqlx::Error::Protocol(format!("Query parsing error: {}", e)))?;
+
+    if !include_resolved {
+        let unresolved = WhereCondition::compare("resolved_at", "IS NULL", QueryValue::Null, Some("AND".to_string()));
+        where_conditions.push(unresolved.clone());
+        options.where_conditions.push(unresolved);
+    }
+    Ok((where_conditions, options))
+}
+
+#[derive(Clone)]
+pub struct MissingComponentRepository {
+    pool: MySqlPool,
+}
+
+impl MissingComponentRepository {
+    pub fn new(pool: MySqlPool) -> Self {
+        Self { pool }
+    }
+
+    /// Get missing components with pagination and filtering, newest first
+    pub async fn get_missing_components(&self, query: CommonPaginationQuery, include_resolved: bool) -> Result<(Vec<ServerMissingComponent>, Option<i64>), sqlx::Error> {
+        let (where_conditions, options) = parse_missing_query(&query, include_resolved)?;
+
+        let total_count = if query.include_total() {
+            Some(QueryBuilderHelper::count(&self.pool, &ServerMissingComponent::SCHEMA, &where_conditions).await?)
+        } else {
+            None
+        };
+
+        let missing = QueryBuilderHelper::select(&self.pool, &ServerMissingComponent::SCHEMA, options).await?;
+        Ok((missing, total_count))
+    }
+
+    /// Stream all missing components matching the query, for exports
+    pub fn stream_missing_components(&self, query: CommonPaginationQuery, include_resolved: bool) -> Result<RowStream<ServerMissingComponent>, sqlx::Error> {
+        let (_, options) = parse_missing_query(&query, include_resolved)?;
+        QueryBuilderHelper::stream(self.pool.clone(), &ServerMissingComponent::SCHEMA, QueryParser::export_options(options))
+    }
+}
+
+#[async_trait]
+impl MissingComponentRepo for MissingComponentRepository {
+    async fn get_missing_components(&self, query: CommonPaginationQuery, include_resolved: bool) -> Result<(Vec<ServerMissingComponent>, Option<i64>), sqlx::Error> {
+        self.get_missing_components(query, include_resolved).await
+    }
+
+    fn stream_missing_components(&self, query: CommonPaginationQuery, include_resolved: bool) -> Result<RowStream<ServerMissingComponent>, sqlx::Error> {
+        Self::stream_missing_components(self, query, include_resolved)
+    }
+}
@@ -5,6 +5,15 @@ pub mod kubernetes_repository;
 pub mod datacenter_repository;
 pub mod cluster_repository;
 pub mod switch_repository;
+pub mod token_repository;
+pub mod audit_repository;
+pub mod webhook_repository;
+pub mod fleet_repository;
+pub mod inventory_change_repository;
+pub mod missing_component_repository;
+pub mod identity_conflict_repository;
+pub mod server_merge_repository;
+pub mod sqlite;
 
 pub use server_repository::{ServerRepository, ServerRepo};
 pub use component_repository::{ComponentRepository, ComponentRepo};
@@ -12,4 +21,12 @@ pub use vm_repository::{VmRepository, VmRepo};
 pub use kubernetes_repository::{KubernetesRepository, K8sRepo};
 pub use datacenter_repository::{DatacenterRepository, DatacenterRepo};
 pub use cluster_repository::{ClusterRepository, ClusterRepo};
-pub use switch_repository::{SwitchRepository, SwitchRepo};
\ No newline at end of file
+pub use switch_repository::{SwitchRepository, SwitchRepo};
+pub use token_repository::{TokenRepository, TokenRepo};
+pub use audit_repository::{AuditRepository, AuditRepo};
+pub use webhook_repository::{WebhookRepository, WebhookRepo};
+pub use fleet_repository::{FleetRepository, FleetRepo};
+pub use inventory_change_repository::{InventoryChangeRepository, InventoryChangeRepo};
+pub use missing_component_repository::{MissingComponentRepository, MissingComponentRepo};
+pub use identity_conflict_repository::{IdentityConflictRepository, IdentityConflictRepo};
+pub use server_merge_repository::{ServerMergeRepository, ServerMergeRepo};
\ No newline at end of file
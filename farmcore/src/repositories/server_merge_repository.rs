@@ -0,0 +1,465 @@
+use sqlx::{MySqlConnection, MySqlPool};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+use crate::domain::{find_duplicate_servers, AuditAction, AuditRecorder, DuplicateServers, IdentityCandidate, RowChange, ServerFingerprint, ServerIdentity};
+use crate::models::{Server, ServerMergeReport};
+
+/// Tables holding a server's hardware as last reported. A merged server's rows are only
+/// kept for the kinds the surviving server has none of, so parts are never listed twice.
+pub(crate) const COMPONENT_TABLES: &[&str] = &[
+    "server_motherboards",
+    "server_cpus",
+    "server_memory_dimms",
+    "server_disks",
+    "server_network_interfaces",
+    "server_gpus",
+    "server_power_supplies",
+    "server_network_routes",
+    "server_bmc_interfaces",
+];
+
+/// Tables whose rows describe the past and all move to the surviving server
+pub(crate) const HISTORY_TABLES: &[&str] = &[
+    "server_inventory_snapshots",
+    "server_inventory_changes",
+    "server_missing_components",
+    "server_identity_conflicts",
+];
+
+/// Latest inventory snapshot of a server; the unchanged-report check compares against it
+pub(crate) const LATEST_SNAPSHOT_QUERY: &str = "SELECT MAX(snapshot_id) FROM server_inventory_snapshots WHERE server_id = ?";
+
+/// Whether the surviving server takes the merged record's components instead of keeping its
+/// own. The record with the newer inventory snapshot wins: that snapshot becomes the
+/// survivor's latest, and a report matching it skips the component sync, so the components
+/// must be the ones it describes. Without snapshots on either side, the survivor keeps its
+/// own rows and only fills tables it has none in.
+pub(crate) fn merged_components_win(survivor_snapshot: Option<i64>, merged_snapshot: Option<i64>, survivor_rows: i64) -> bool {
+    match (survivor_snapshot, merged_snapshot) {
+        (None, None) => survivor_rows == 0,
+        (survivor, merged) => merged > survivor,
+    }
+}
+
+/// server_id, server_name, last_inventory_at, system_uuid, serial_number, chassis_serial_number, board serial
+pub(crate) type FingerprintRow = (i32, String, Option<DateTime<Utc>>, Option<String>, Option<String>, Option<String>, Option<String>);
+
+pub(crate) const FINGERPRINT_QUERY: &str = r#"
+    SELECT s.server_id, s.server_name, s.last_inventory_at, s.system_uuid, s.serial_number, s.chassis_serial_number,
+        (SELECT mb.serial_number FROM server_motherboards mb WHERE mb.server_id = s.server_id LIMIT 1)
+    FROM servers s
+"#;
+
+/// Duplicate detection and merging of server records
+#[async_trait]
+pub trait ServerMergeRepo: Send + Sync {
+    async fn find_duplicate_servers(&self, min_confidence: i32) -> Result<Vec<DuplicateServers>, sqlx::Error>;
+    async fn merge_servers(&self, server_id: i32, merged_ids: Vec<i32>, dry_run: bool) -> Result<ServerMergeReport, sqlx::Error>;
+}
+
+/// Assemble fingerprints from the server rows, NIC MACs and BMC (MAC, IP) pairs.
+/// Shared with the SQLite repository.
+pub(crate) fn build_fingerprints(
+    servers: Vec<FingerprintRow>,
+    nics: Vec<(i32, Option<String>)>,
+    bmcs: Vec<(i32, Option<String>, Option<String>)>,
+) -> Vec<ServerFingerprint> {
+    let mut nic_macs: HashMap<i32, Vec<String>> = HashMap::new();
+    for (server_id, mac) in nics {
+        nic_macs.entry(server_id).or_default().extend(mac);
+    }
+    let mut bmc_addresses: HashMap<i32, (Option<String>, Vec<String>)> = HashMap::new();
+    for (server_id, mac, ip) in bmcs {
+        let (bmc_mac, ips) = bmc_addresses.entry(server_id).or_default();
+        if bmc_mac.is_none() {
+            *bmc_mac = mac;
+        }
+        ips.extend(ip.map(|ip| ip.trim().to_string()).filter(|ip| !ip.is_empty() && ip != "0.0.0.0"));
+    }
+
+    servers
+        .into_iter()
+        .map(|(server_id, server_name, last_inventory_at, system_uuid, serial_number, chassis_serial_number, board_serial_number)| {
+            let (bmc_mac, bmc_ips) = bmc_addresses.remove(&server_id).unwrap_or_default();
+            ServerFingerprint {
+                server_id,
+                server_name,
+                last_inventory_at,
+                identity: ServerIdentity::new(
+                    system_uuid.as_deref(),
+                    serial_number.as_deref(),
+                    chassis_serial_number.as_deref(),
+                    board_serial_number.as_deref(),
+                    bmc_mac.as_deref(),
+                    nic_macs.remove(&server_id).unwrap_or_default(),
+                ),
+                bmc_ips,
+            }
+        })
+        .collect()
+}
+
+/// The records to merge, in order and without repeats. Shared with the SQLite repository.
+pub(crate) fn merge_order(server_id: i32, merged_ids: Vec<i32>) -> Result<Vec<i32>, sqlx::Error> {
+    let mut ordered = Vec::with_capacity(merged_ids.len());
+    for merged_id in merged_ids {
+        if merged_id == server_id {
+            return Err(sqlx::Error::Protocol(format!("Server {} cannot be merged into itself", server_id)));
+        }
+        if !ordered.contains(&merged_id) {
+            ordered.push(merged_id);
+        }
+    }
+    if ordered.is_empty() {
+        return Err(sqlx::Error::Protocol("No servers to merge: server_ids is empty".to_string()));
+    }
+    Ok(ordered)
+}
+
+/// Refuse records the firmware says are different machines
+pub(crate) fn check_mergeable(survivor: &Server, merged: &Server) -> Result<(), sqlx::Error> {
+    let uuid = |server: &Server| ServerIdentity::new(server.system_uuid.as_deref(), None, None, None, None, Vec::<String>::new()).system_uuid;
+    if let (Some(own), Some(theirs)) = (uuid(survivor), uuid(merged)) {
+        if own != theirs {
+            return Err(sqlx::Error::Protocol(format!(
+                "Server {} cannot be merged into server {}: their system UUIDs differ",
+                merged.server_id, survivor.server_id
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Columns of the surviving server to fill in from a merged one, with their new values
+#[derive(Debug, Default)]
+pub(crate) struct InheritedColumns {
+    pub text: Vec<(&'static str, String)>,
+    pub integer: Vec<(&'static str, i32)>,
+}
+
+impl InheritedColumns {
+    pub fn is_empty(&self) -> bool {
+        self.text.is_empty() && self.integer.is_empty()
+    }
+
+    /// UPDATE binding the text values, then the integer values, then the server_id
+    pub fn update_sql(&self) -> String {
+        let assignments: Vec<String> = self.text.iter().map(|(column, _)| *column)
+            .chain(self.integer.iter().map(|(column, _)| *column))
+            .map(|column| format!("{} = ?", column))
+            .collect();
+        format!("UPDATE servers SET {} WHERE server_id = ?", assignments.join(", "))
+    }
+
+    /// Audit entry for the surviving server
+    pub fn change(&self, survivor: &Server) -> RowChange {
+        let before = serde_json::to_value(survivor).unwrap_or_default();
+        let mut old = serde_json::Map::new();
+        let mut new = serde_json::Map::new();
+        let columns = self.text.iter().map(|(column, value)| (*column, json!(value)))
+            .chain(self.integer.iter().map(|(column, value)| (*column, json!(value))));
+        for (column, value) in columns {
+            old.insert(column.to_string(), before.get(column).cloned().unwrap_or(Value::Null));
+            new.insert(column.to_string(), value);
+        }
+        RowChange {
+            table: Server::TABLE.to_string(),
+            key_column: Server::KEY.to_string(),
+            id: survivor.server_id,
+            old: Value::Object(old),
+            new: Value::Object(new),
+        }
+    }
+}
+
+/// Identifiers and descriptions the surviving server lacks, and the merged server's placement
+/// and cluster when it has none of its own. Shared with the SQLite repository.
+pub(crate) fn inherited_columns(survivor: &Server, merged: &Server) -> InheritedColumns {
+    let text = [
+        ("system_uuid", &survivor.system_uuid, &merged.system_uuid),
+        ("serial_number", &survivor.serial_number, &merged.serial_number),
+        ("chassis_serial_number", &survivor.chassis_serial_number, &merged.chassis_serial_number),
+        ("manufacturer", &survivor.manufacturer, &merged.manufacturer),
+        ("product_name", &survivor.product_name, &merged.product_name),
+        ("chassis_manufacturer", &survivor.chassis_manufacturer, &merged.chassis_manufacturer),
+    ]
+    .into_iter()
+    .filter(|(_, own, _)| own.is_none())
+    .filter_map(|(column, _, theirs)| theirs.clone().map(|value| (column, value)))
+    .collect();
+
+    let placement = |server: &Server| [
+        ("data_center_id", server.data_center_id),
+        ("rack_id", server.rack_id),
+        ("rack_position_id", server.rack_position_id),
+    ];
+    let cluster = |server: &Server| [
+        ("cluster_id", server.cluster_id),
+        ("sub_cluster_id", server.sub_cluster_id),
+    ];
+    // Unset location IDs are stored as 0
+    let set = |id: &Option<i32>| id.is_some_and(|id| id != 0);
+    let mut integer = Vec::new();
+    if !placement(survivor).iter().any(|(_, id)| set(id)) {
+        integer.extend(placement(merged).into_iter().filter_map(|(column, id)| id.filter(|&id| id != 0).map(|id| (column, id))));
+    }
+    if !cluster(survivor).iter().any(|(_, id)| set(id)) {
+        integer.extend(cluster(merged).into_iter().filter_map(|(column, id)| id.filter(|&id| id != 0).map(|id| (column, id))));
+    }
+    InheritedColumns { text, integer }
+}
+
+/// Audit entry for a merged server's deleted row
+pub(crate) fn deleted_change(merged: &Server) -> RowChange {
+    RowChange {
+        table: Server::TABLE.to_string(),
+        key_column: Server::KEY.to_string(),
+        id: merged.server_id,
+        old: serde_json::to_value(merged).unwrap_or_default(),
+        new: Value::Null,
+    }
+}
+
+/// IDs of open conflicts that list one of `server_ids` among their candidates
+pub(crate) fn conflicts_naming(open: Vec<(i32, Value)>, server_ids: &[i32]) -> Vec<i32> {
+    open.into_iter()
+        .filter(|(_, candidates)| {
+            serde_json::from_value::<Vec<IdentityCandidate>>(candidates.clone())
+                .is_ok_and(|candidates| candidates.iter().any(|c| server_ids.contains(&c.server_id)))
+        })
+        .map(|(conflict_id, _)| conflict_id)
+        .collect()
+}
+
+/// Record the merge once it has been committed
+pub(crate) fn record_merge(report: &ServerMergeReport, changes: Vec<RowChange>) {
+    for change in changes {
+        AuditRecorder::record_change(change);
+    }
+    AuditRecorder::record_action(AuditAction {
+        action: "servers.merge".to_string(),
+        target: Server::TABLE.to_string(),
+        detail: json!(report),
+        success: true,
+        error: None,
+    });
+}
+
+#[derive(Clone)]
+pub struct ServerMergeRepository {
+    pool: MySqlPool,
+}
+
+impl ServerMergeRepository {
+    pub fn new(pool: MySqlPool) -> Self {
+        Self { pool }
+    }
+
+    /// Pairs of server records that probably describe the same machine, most likely first
+    pub async fn find_duplicate_servers(&self, min_confidence: i32) -> Result<Vec<DuplicateServers>, sqlx::Error> {
+        let servers: Vec<FingerprintRow> = sqlx::query_as(FINGERPRINT_QUERY)
+            .fetch_all(&self.pool)
+            .await?;
+        let nics: Vec<(i32, Option<String>)> = sqlx::query_as("SELECT server_id, mac_address FROM server_network_interfaces")
+            .fetch_all(&self.pool)
+            .await?;
+        let bmcs: Vec<(i32, Option<String>, Option<String>)> = sqlx::query_as(
+            "SELECT server_id, mac_address, ip_address FROM server_bmc_interfaces WHERE server_id IS NOT NULL ORDER BY bmc_interface_id"
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(find_duplicate_servers(&build_fingerprints(servers, nics, bmcs), min_confidence))
+    }
+
+    /// Fold `merged_ids` into `server_id` in a single transaction and delete them. Rolled
+    /// back on a dry run, so the report shows what a merge would do.
+    pub async fn merge_servers(&self, server_id: i32, merged_ids: Vec<i32>, dry_run: bool) -> Result<ServerMergeReport, sqlx::Error> {
+        let merged_ids = merge_order(server_id, merged_ids)?;
+        let mut report = ServerMergeReport {
+            server_id,
+            merged_server_ids: merged_ids.clone(),
+            dry_run,
+            ..Default::default()
+        };
+        let mut changes = Vec::new();
+
+        let mut tx = self.pool.begin().await?;
+        let mut survivor = Self::lock_server(&mut tx, server_id).await?;
+        for &merged_id in &merged_ids {
+            let merged = Self::lock_server(&mut tx, merged_id).await?;
+            check_mergeable(&survivor, &merged)?;
+            Self::merge_server(&mut tx, &survivor, &merged, &mut report, &mut changes).await?;
+            survivor = Self::lock_server(&mut tx, server_id).await?;
+        }
+
+        let open: Vec<(i32, Value)> = sqlx::query_as(
+            "SELECT conflict_id, candidates FROM server_identity_conflicts WHERE resolved_at IS NULL"
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+        for conflict_id in conflicts_naming(open, &merged_ids) {
+            sqlx::query("UPDATE server_identity_conflicts SET resolved_at = NOW() WHERE conflict_id = ?")
+                .bind(conflict_id)
+                .execute(&mut *tx)
+                .await?;
+            report.resolved_conflicts += 1;
+        }
+
+        if dry_run {
+            tx.rollback().await?;
+        } else {
+            tx.commit().await?;
+            record_merge(&report, changes);
+        }
+        Ok(report)
+    }
+
+    async fn lock_server(conn: &mut MySqlConnection, server_id: i32) -> Result<Server, sqlx::Error> {
+        sqlx::query_as("SELECT * FROM servers WHERE server_id = ? FOR UPDATE")
+            .bind(server_id)
+            .fetch_optional(conn)
+            .await?
+            .ok_or_else(|| sqlx::Error::Protocol(format!("Server {} not found", server_id)))
+    }
+
+    async fn merge_server(
+        conn: &mut MySqlConnection,
+        survivor: &Server,
+        merged: &Server,
+        report: &mut ServerMergeReport,
+        changes: &mut Vec<RowChange>
+    ) -> Result<(), sqlx::Error> {
+        let (server_id, merged_id) = (survivor.server_id, merged.server_id);
+
+        // VM names are unique per host; two VMs of the same name are not the same VM
+        let clashing: Vec<(String,)> = sqlx::query_as(r#"
+            SELECT m.vm_name FROM virtual_machines m
+            JOIN virtual_machines s ON s.server_id = ? AND s.vm_name = m.vm_name
+            WHERE m.server_id = ?
+        "#)
+        .bind(server_id)
+        .bind(merged_id)
+        .fetch_all(&mut *conn)
+        .await?;
+        if !clashing.is_empty() {
+            let names: Vec<String> = clashing.into_iter().map(|(name,)| name).collect();
+            return Err(sqlx::Error::Protocol(format!(
+                "Server {} cannot be merged into server {}: virtual machines {} exist on both",
+                merged_id, server_id, names.join(", ")
+            )));
+        }
+
+        let (survivor_snapshot,): (Option<i64>,) = sqlx::query_as(LATEST_SNAPSHOT_QUERY)
+            .bind(server_id)
+            .fetch_one(&mut *conn)
+            .await?;
+        let (merged_snapshot,): (Option<i64>,) = sqlx::query_as(LATEST_SNAPSHOT_QUERY)
+            .bind(merged_id)
+            .fetch_one(&mut *conn)
+            .await?;
+        for &table in COMPONENT_TABLES {
+            let (own,): (i64,) = sqlx::query_as(&format!("SELECT COUNT(*) FROM {} WHERE server_id = ?", table))
+                .bind(server_id)
+                .fetch_one(&mut *conn)
+                .await?;
+            let discarded_id = if merged_components_win(survivor_snapshot, merged_snapshot, own) { server_id } else { merged_id };
+            let result = sqlx::query(&format!("DELETE FROM {} WHERE server_id = ?", table))
+                .bind(discarded_id)
+                .execute(&mut *conn)
+                .await?;
+            report.count_discarded(table, result.rows_affected());
+            let moved = Self::reassign(&mut *conn, table, "server_id", server_id, merged_id).await?;
+            report.count_moved(table, moved);
+        }
+
+        // Credentials are entered by hand: keep both sides, the surviving server's on a clash
+        let result = sqlx::query(r#"
+            DELETE m FROM server_credentials m
+            JOIN server_credentials s ON s.server_id = ? AND s.credential_type = m.credential_type AND s.username = m.username
+            WHERE m.server_id = ?
+        "#)
+        .bind(server_id)
+        .bind(merged_id)
+        .execute(&mut *conn)
+        .await?;
+        report.count_discarded("server_credentials", result.rows_affected());
+        let moved = Self::reassign(&mut *conn, "server_credentials", "server_id", server_id, merged_id).await?;
+        report.count_moved("server_credentials", moved);
+
+        for (table, column) in [
+            ("virtual_machines", "server_id"),
+            ("vm_migrations", "source_server_id"),
+            ("vm_migrations", "target_server_id"),
+            ("kubernetes_nodes", "server_id"),
+        ]
+        .into_iter()
+        .chain(HISTORY_TABLES.iter().map(|&table| (table, "server_id")))
+        {
+            let moved = Self::reassign(&mut *conn, table, column, server_id, merged_id).await?;
+            report.count_moved(table, moved);
+        }
+
+        // A server only occupies one set of rack units; free the merged server's if it has its own
+        let (own_positions,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM datacenter_rack_positions WHERE server_id = ?")
+            .bind(server_id)
+            .fetch_one(&mut *conn)
+            .await?;
+        if own_positions > 0 {
+            let result = sqlx::query(
+                "UPDATE datacenter_rack_positions SET server_id = NULL, device_type = NULL, status = 'AVAILABLE' WHERE server_id = ?"
+            )
+            .bind(merged_id)
+            .execute(&mut *conn)
+            .await?;
+            report.count_discarded("datacenter_rack_positions", result.rows_affected());
+        } else {
+            let moved = Self::reassign(&mut *conn, "datacenter_rack_positions", "server_id", server_id, merged_id).await?;
+            report.count_moved("datacenter_rack_positions", moved);
+        }
+
+        let inherited = inherited_columns(survivor, merged);
+        if !inherited.is_empty() {
+            let sql = inherited.update_sql();
+            let mut query = sqlx::query(&sql);
+            for (_, value) in &inherited.text {
+                query = query.bind(value);
+            }
+            for (_, value) in &inherited.integer {
+                query = query.bind(value);
+            }
+            query.bind(server_id).execute(&mut *conn).await?;
+            changes.push(inherited.change(survivor));
+        }
+
+        sqlx::query("DELETE FROM servers WHERE server_id = ?")
+            .bind(merged_id)
+            .execute(&mut *conn)
+            .await?;
+        changes.push(deleted_change(merged));
+        Ok(())
+    }
+
+    async fn reassign(conn: &mut MySqlConnection, table: &str, column: &str, server_id: i32, merged_id: i32) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query(&format!("UPDATE {} SET {} = ? WHERE {} = ?", table, column, column))
+            .bind(server_id)
+            .bind(merged_id)
+            .execute(conn)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
+
+#[async_trait]
+impl ServerMergeRepo for ServerMergeRepository {
+    async fn find_duplicate_servers(&self, min_confidence: i32) -> Result<Vec<DuplicateServers>, sqlx::Error> {
+        self.find_duplicate_servers(min_confidence).await
+    }
+
+    async fn merge_servers(&self, server_id: i32, merged_ids: Vec<i32>, dry_run: bool) -> Result<ServerMergeReport, sqlx::Error> {
+        self.merge_servers(server_id, merged_ids, dry_run).await
+    }
+}
@@ -1,165 +1,191 @@
-use sqlx::MySqlPool;
+use sqlx::database::HasArguments;
+use sqlx::{ColumnIndex, Decode, Encode, Executor, IntoArguments, MySql, Pool, Type};
 use async_trait::async_trait;
-use std::collections::HashMap;
-use crate::database::{QueryBuilderHelper, DatabaseHelper};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use std::collections::{HashMap, HashSet};
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use crate::database::{QueryBuilderHelper, DatabaseHelper, RowStream, SqlDialect};
 use crate::models::{
-    Server, ServerWithAllComponents, QueryOptions,
-    ServerBmcDetail
+    Server, ServerWithAllComponents,
+    ServerBmcDetail, InventorySnapshot, InventorySnapshotSummary, InventoryChangeType
+};
+use crate::api::query_parser::{CommonPaginationQuery, ParsedQuery, QueryParser};
+use crate::domain::{
+    part_keys, reported_part_keys, reported_parts, resolve_identity, IdentityCandidate, IdentityDecision, IdentityKey,
+    IdentityResolution, ServerIdentity, MISSING_COMPONENT_TYPES,
 };
-use crate::api::query_parser::{CommonPaginationQuery, QueryParser};
-
-// Inventory data structures matching the JSON format
-#[derive(Debug, serde::Deserialize)]
-pub struct ServerInventory {
-    pub agent_version: String,
-    pub node: NodeInfo,
-    pub cpu: CpuInfo,
-    pub memory: MemoryInfo,
-    pub disks: Vec<DiskInfo>,
-    pub network: NetworkInfo,
-    pub gpus: Vec<GpuInfo>,
-    pub power_supplies: Vec<serde_json::Value>,
-}
 
-#[derive(Debug, serde::Deserialize)]
-pub struct NodeInfo {
-    pub hostname: Option<String>,
-    pub architecture: Option<String>,
-    pub product_name: Option<String>,
-    pub manufacturer: Option<String>,
-    pub serial_number: Option<String>,
-    pub chassis_manufacturer: Option<String>,
-    pub chassis_serial_number: Option<String>,
-    pub motherboard: Option<MotherboardInfo>,
-    pub bios: Option<BiosInfo>,
-    pub bmc: BmcInfo,
+// Inventory payloads are defined in farm-proto, shared with the agent
+pub use farm_proto::hardware::*;
+
+/// The payload as kept in a snapshot, and its SHA-256 checksum (hex). The checksum is taken
+/// over the re-serialized inventory, so key order and whitespace in what the agent sent do
+/// not matter, and leaves out PSU sensor readings, which differ in every report.
+pub fn inventory_snapshot(inventory: &ServerInventory) -> Result<(serde_json::Value, String), sqlx::Error> {
+    let data = serde_json::to_value(inventory)
+        .map_err(|e| sqlx::Error::Protocol(format!("Failed to serialize inventory: {}", e)))?;
+    let mut hardware = inventory.clone();
+    for psu in &mut hardware.power_supplies {
+        psu.input_voltage = None;
+        psu.input_current = None;
+        psu.output_voltage = None;
+        psu.output_current = None;
+        psu.temperature_c = None;
+        psu.fan_speed_rpm = None;
+    }
+    let bytes = serde_json::to_vec(&hardware)
+        .map_err(|e| sqlx::Error::Protocol(format!("Failed to serialize inventory: {}", e)))?;
+    let checksum = Sha256::digest(&bytes).iter().map(|b| format!("{:02x}", b)).collect();
+    Ok((data, checksum))
 }
 
-#[derive(Debug, serde::Deserialize)]
-pub struct MotherboardInfo {
-    pub manufacturer: Option<String>,
-    pub product_name: Option<String>,
-    pub version: Option<String>,
-    pub serial_number: Option<String>,
+/// What the sync stores as a DIMM's slot: its locator, else its serial number when the
+/// firmware leaves the locator empty. DIMMs with neither are not stored.
+pub(crate) fn dimm_slot(dimm: &DimmInfo) -> Option<&str> {
+    [&dimm.slot, &dimm.serial_number].into_iter()
+        .flatten()
+        .map(String::as_str)
+        .find(|key| !key.is_empty())
 }
 
-#[derive(Debug, serde::Deserialize)]
-pub struct BiosInfo {
-    pub vendor: Option<String>,
-    pub version: Option<String>,
-    pub release_date: Option<String>,
+/// One query for the IDs of every server sharing an identifier with `identity`, and the
+/// values to bind to it. Plain SQL, shared with the SQLite repository; MACs are stored as
+/// reported, so they are compared lowercased.
+pub(crate) fn identity_candidates_query(identity: &ServerIdentity) -> (String, Vec<&str>) {
+    let (selects, values): (Vec<&str>, Vec<&str>) = identity.claims()
+        .map(|(key, value)| {
+            let select = match key {
+                IdentityKey::SystemUuid => "SELECT server_id FROM servers WHERE system_uuid = ?",
+                IdentityKey::SystemSerial => "SELECT server_id FROM servers WHERE serial_number = ?",
+                IdentityKey::ChassisSerial => "SELECT server_id FROM servers WHERE chassis_serial_number = ?",
+                IdentityKey::BoardSerial => "SELECT server_id FROM server_motherboards WHERE serial_number = ?",
+                IdentityKey::BmcMac => "SELECT server_id FROM server_bmc_interfaces WHERE server_id IS NOT NULL AND LOWER(mac_address) = ?",
+                IdentityKey::NicMac => "SELECT server_id FROM server_network_interfaces WHERE LOWER(mac_address) = ?",
+            };
+            (select, value)
+        })
+        .unzip();
+    (selects.join(" UNION "), values)
 }
 
-#[derive(Debug, serde::Deserialize)]
-pub struct BmcInfo {
-    pub ip_address: Option<String>,
-    pub mac_address: Option<String>,
-    pub firmware_version: Option<String>,
-    pub release_date: Option<String>,
-}
+/// system_uuid, serial_number, chassis_serial_number, board serial and BMC MAC of a server
+pub(crate) type StoredIdentityRow = (Option<String>, Option<String>, Option<String>, Option<String>, Option<String>);
 
-#[derive(Debug, serde::Deserialize)]
-pub struct CpuInfo {
-    pub sockets: Option<i32>,
-    pub cores: Option<i32>,
-    pub threads: Option<i32>,
-    pub cpus: Vec<CpuDetail>,
+/// What the sync matches a route on
+pub(crate) fn route_key(destination: &str, interface_name: &str) -> String {
+    format!("{} dev {}", destination, interface_name)
 }
 
-#[derive(Debug, serde::Deserialize)]
-pub struct CpuDetail {
-    pub socket: i32,
-    pub manufacturer: Option<String>,
-    pub model_name: Option<String>,
-    pub num_cores: Option<i32>,
-    pub num_threads: Option<i32>,
-    pub capacity_mhz: Option<i32>,
-    pub slot: Option<String>,
-    pub l1_cache_kb: Option<i32>,
-    pub l2_cache_kb: Option<i32>,
-    pub l3_cache_kb: Option<i32>,
+/// What the sync matches a PSU on: serial number, else name. PSUs with neither are not stored.
+pub(crate) fn power_supply_key(serial_number: &Option<String>, name: &Option<String>) -> Option<String> {
+    [serial_number, name].into_iter()
+        .flatten()
+        .find(|key| !key.is_empty())
+        .cloned()
 }
 
-#[derive(Debug, serde::Deserialize)]
-pub struct MemoryInfo {
-    pub total_bytes: Option<i64>,
-    pub dimms: Vec<DimmDetail>,
+/// Model recorded in component_bmc_types for BMCs discovered through agent inventory
+pub(crate) const GENERIC_BMC_MODEL: &str = "Generic BMC";
+
+/// A component the inventory sync deleted because the agent no longer reports it
+#[derive(Debug, Clone, serde::Serialize, schemars::JsonSchema)]
+pub struct RemovedComponent {
+    /// motherboard, cpu, memory, disk, network_interface, gpu, power_supply or bmc
+    pub component_type: String,
+    /// What the sync matched it on: socket, slot, serial (or name), MAC, GPU UUID (or PCI address),
+    /// PSU serial (or name)
+    pub identifier: String,
 }
 
-#[derive(Debug, serde::Deserialize)]
-pub struct DimmDetail {
-    pub slot: String,
-    pub size_bytes: i64,
-    pub mem_type: Option<String>,
-    pub speed_mt_s: Option<i32>,
-    pub manufacturer: Option<String>,
-    pub serial_number: Option<String>,
-    pub part_number: Option<String>,
+impl RemovedComponent {
+    pub fn new(component_type: &str, identifier: impl Into<String>) -> Self {
+        Self { component_type: component_type.to_string(), identifier: identifier.into() }
+    }
 }
 
-#[derive(Debug, serde::Deserialize)]
-pub struct DiskInfo {
-    pub name: String,
-    pub dev_path: Option<String>,
-    pub model: Option<String>,
-    pub serial: Option<String>,
-    pub size_bytes: i64,
-    pub rotational: bool,
-    pub bus_type: Option<String>,
-    pub firmware_version: Option<String>,
-    pub smart: Option<SmartInfo>,
+/// A component the inventory sync added, removed or modified on an existing server,
+/// as recorded in server_inventory_changes
+#[derive(Debug, Clone)]
+pub struct ComponentChange {
+    /// cpu, memory, disk, network, route, gpu, power, bios, bmc or chassis (the motherboard)
+    pub component_type: &'static str,
+    pub identifier: String,
+    pub change_type: InventoryChangeType,
+    pub old_data: Option<serde_json::Value>,
+    pub new_data: Option<serde_json::Value>,
 }
 
-#[derive(Debug, serde::Deserialize)]
-pub struct SmartInfo {
-    pub health: Option<String>,
-}
+impl ComponentChange {
+    pub fn added(component_type: &'static str, identifier: impl Into<String>, new_data: serde_json::Value) -> Self {
+        Self { component_type, identifier: identifier.into(), change_type: InventoryChangeType::Added, old_data: None, new_data: Some(new_data) }
+    }
 
-#[derive(Debug, serde::Deserialize)]
-pub struct NetworkInfo {
-    pub interfaces: Vec<NetworkInterface>,
-    pub routes: Vec<serde_json::Value>,
-}
+    pub fn removed(component_type: &'static str, identifier: impl Into<String>, old_data: serde_json::Value) -> Self {
+        Self { component_type, identifier: identifier.into(), change_type: InventoryChangeType::Removed, old_data: Some(old_data), new_data: None }
+    }
 
-#[derive(Debug, serde::Deserialize)]
-pub struct NetworkInterface {
-    pub name: String,
-    pub mac_address: Option<String>,
-    pub mtu: Option<i32>,
-    pub speed_mbps: Option<i32>,
-    pub driver: Option<String>,
-    pub firmware_version: Option<String>,
-    pub vendor_name: Option<String>,
-    pub device_name: Option<String>,
-    pub pci_address: Option<String>,
-    pub addresses: Vec<NetworkAddress>,
-    pub is_primary: bool,
-    pub bond_group: Option<String>,
-    pub bond_master: Option<String>,
-}
+    /// None when the compared fields are the same on both sides
+    pub fn modified(
+        component_type: &'static str,
+        identifier: impl Into<String>,
+        old_data: serde_json::Value,
+        new_data: serde_json::Value,
+    ) -> Option<Self> {
+        (old_data != new_data).then(|| Self {
+            component_type,
+            identifier: identifier.into(),
+            change_type: InventoryChangeType::Modified,
+            old_data: Some(old_data),
+            new_data: Some(new_data),
+        })
+    }
 
-#[derive(Debug, serde::Deserialize)]
-pub struct NetworkAddress {
-    pub family: String,
-    pub address: String,
-    pub prefix: i32,
+    /// The removal as reported in inventory responses and events, which predate the change
+    /// history and name the motherboard (by serial), NICs and PSUs differently. Routes are
+    /// not hardware and are left out.
+    pub fn as_removed(&self) -> Option<RemovedComponent> {
+        if self.change_type != InventoryChangeType::Removed || self.component_type == "route" {
+            return None;
+        }
+        let removed = match self.component_type {
+            "chassis" => {
+                let serial = self.old_data.as_ref()
+                    .and_then(|data| data.get("serial_number"))
+                    .and_then(|serial| serial.as_str())
+                    .unwrap_or("motherboard");
+                RemovedComponent::new("motherboard", serial)
+            }
+            "network" => RemovedComponent::new("network_interface", self.identifier.clone()),
+            "power" => RemovedComponent::new("power_supply", self.identifier.clone()),
+            component_type => RemovedComponent::new(component_type, self.identifier.clone()),
+        };
+        Some(removed)
+    }
 }
 
-#[derive(Debug, serde::Deserialize)]
-pub struct GpuInfo {
-    pub vendor: Option<String>,
-    pub model: Option<String>,
-    pub pci_address: Option<String>,
-    pub vram_mb: Option<i32>,
-    pub driver_version: Option<String>,
-    pub uuid: Option<String>,
+/// Outcome of `upsert_server_from_inventory`
+#[derive(Debug, Clone)]
+pub struct InventoryUpsert {
+    pub server_id: i32,
+    pub created: bool,
+    /// The payload matched the server's latest snapshot, so only last_inventory_at was bumped
+    pub unchanged: bool,
+    /// Always empty for a newly created server
+    pub removed_components: Vec<RemovedComponent>,
+    /// How the report was matched to the server
+    pub identity: IdentityDecision,
+    /// Set when the match was not clear-cut and was recorded in server_identity_conflicts
+    pub conflict_id: Option<i32>,
 }
 
 #[async_trait]
 pub trait ServerRepo: Send + Sync {
-    async fn get_all_servers(&self, query: CommonPaginationQuery) -> Result<(Vec<Server>, i64), sqlx::Error>;
+    async fn get_all_servers(&self, query: CommonPaginationQuery) -> Result<(Vec<Server>, Option<i64>), sqlx::Error>;
+    fn stream_servers(&self, query: CommonPaginationQuery) -> Result<RowStream<Server>, sqlx::Error>;
     async fn get_by_id(&self, id: i64) -> Result<Option<Server>, sqlx::Error>;
     async fn get_server_with_all_components(&self, server_id: i32) -> Result<Option<ServerWithAllComponents>, sqlx::Error>;
+    async fn get_server_bmc_interfaces(&self, server_id: i32) -> Result<Vec<ServerBmcDetail>, sqlx::Error>;
     async fn get_overview_stats(&self) -> Result<serde_json::Value, sqlx::Error>;
     async fn get_server_counts_by_type(&self) -> Result<Vec<(String, i64)>, sqlx::Error>;
     async fn get_server_counts_by_status(&self) -> Result<Vec<(String, i64)>, sqlx::Error>;
@@ -167,41 +193,89 @@ pub trait ServerRepo: Send + Sync {
     async fn update_server(&self, server_id: i32, updates: HashMap<String, serde_json::Value>) -> Result<bool, sqlx::Error>;
     async fn check_server_exists_by_mac(&self, mac_address: &str) -> Result<Option<i32>, sqlx::Error>;
     async fn create_server_from_inventory(&self, inventory: ServerInventory) -> Result<i32, sqlx::Error>;
-    async fn update_server_from_inventory(&self, server_id: i32, inventory: ServerInventory) -> Result<bool, sqlx::Error>;
-    async fn upsert_server_from_inventory(&self, inventory: ServerInventory) -> Result<(i32, bool), sqlx::Error>; // Returns (server_id, was_created)
+    async fn update_server_from_inventory(&self, server_id: i32, inventory: ServerInventory) -> Result<Vec<RemovedComponent>, sqlx::Error>;
+    async fn upsert_server_from_inventory(&self, inventory: ServerInventory) -> Result<InventoryUpsert, sqlx::Error>;
+
+    // Inventory snapshots
+    async fn get_inventory_snapshots(&self, server_id: i32, limit: i64) -> Result<Vec<InventorySnapshotSummary>, sqlx::Error>;
+    async fn get_inventory_snapshot(&self, server_id: i32, snapshot_id: i32) -> Result<Option<InventorySnapshot>, sqlx::Error>;
 }
 
-#[derive(Clone)]
-pub struct ServerRepository {
-    pool: MySqlPool,
+/// `ServerRepo` on any storage backend. The queries are plain SQL; what differs between
+/// backends comes from `SqlDialect`. `SqliteServerRepository` is this on SQLite.
+pub struct ServerRepository<DB: sqlx::Database = MySql> {
+    pool: Pool<DB>,
+}
+
+impl<DB: sqlx::Database> Clone for ServerRepository<DB> {
+    fn clone(&self) -> Self {
+        Self { pool: self.pool.clone() }
+    }
 }
 
-impl ServerRepository {
-    pub fn new(pool: MySqlPool) -> Self {
+impl<DB> ServerRepository<DB>
+where
+    DB: SqlDialect,
+    for<'c> &'c mut DB::Connection: Executor<'c, Database = DB>,
+    for<'q> <DB as HasArguments<'q>>::Arguments: IntoArguments<'q, DB>,
+    for<'r> &'r str: ColumnIndex<DB::Row>,
+    usize: ColumnIndex<DB::Row>,
+    for<'q> &'q str: Encode<'q, DB> + Type<DB>,
+    for<'q> Option<&'q str>: Encode<'q, DB>,
+    for<'q> Option<&'q String>: Encode<'q, DB>,
+    for<'q, 'r> String: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+    for<'q> Option<String>: Encode<'q, DB>,
+    for<'q, 'r> i32: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+    for<'q> Option<i32>: Encode<'q, DB>,
+    for<'q, 'r> i64: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+    for<'q> Option<i64>: Encode<'q, DB>,
+    for<'q, 'r> u32: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+    for<'q> Option<u32>: Encode<'q, DB>,
+    for<'q, 'r> f32: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+    for<'q> Option<f32>: Encode<'q, DB>,
+    for<'q, 'r> f64: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+    for<'q, 'r> bool: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+    for<'q> i8: Encode<'q, DB> + Type<DB>,
+    for<'q, 'r> NaiveDate: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+    for<'q> Option<NaiveDate>: Encode<'q, DB>,
+    for<'q, 'r> NaiveDateTime: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+    for<'q, 'r> DateTime<Utc>: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+    for<'q, 'r> serde_json::Value: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+    for<'q> Option<serde_json::Value>: Encode<'q, DB>,
+{
+    pub fn new(pool: Pool<DB>) -> Self {
         Self { pool }
     }
 
     /// Get all servers with pagination
-    pub async fn get_all_servers(&self, query: CommonPaginationQuery) -> Result<(Vec<Server>, i64), sqlx::Error> {
-        let (_, per_page, offset, columns, where_conditions, _) = QueryParser::parse_all(
+    pub async fn get_all_servers(&self, query: CommonPaginationQuery) -> Result<(Vec<Server>, Option<i64>), sqlx::Error> {
+        let ParsedQuery { where_conditions, options, .. } = QueryParser::parse_all(
             &query,
+            &Server::SCHEMA,
             Some("server_id DESC".to_string())
         ).map_err(|e| sqlx::Error::Protocol(format!("Query parsing error: {}", e)))?;
 
-        let total_count = QueryBuilderHelper::count(&self.pool, Server::TABLE, &where_conditions).await?;
-
-        let options = QueryOptions {
-            columns,
-            where_conditions,
-            limit: Some(per_page),
-            offset: Some(offset),
-            order_by: Some("server_id DESC".to_string()),
+        let total_count = if query.include_total() {
+            Some(QueryBuilderHelper::count(&self.pool, &Server::SCHEMA, &where_conditions).await?)
+        } else {
+            None
         };
 
-        let servers = QueryBuilderHelper::select(&self.pool, Server::TABLE, options).await?;
+        let servers = QueryBuilderHelper::select(&self.pool, &Server::SCHEMA, options).await?;
         Ok((servers, total_count))
     }
 
+    /// Stream all servers matching the query, for exports
+    pub fn stream_servers(&self, query: CommonPaginationQuery) -> Result<RowStream<Server>, sqlx::Error> {
+        let ParsedQuery { options, .. } = QueryParser::parse_all(
+            &query,
+            &Server::SCHEMA,
+            Some("server_id DESC".to_string())
+        ).map_err(|e| sqlx::Error::Protocol(format!("Query parsing error: {}", e)))?;
+
+        QueryBuilderHelper::stream(self.pool.clone(), &Server::SCHEMA, QueryParser::export_options(options))
+    }
+
     /// Find server by ID
     pub async fn get_by_id(&self, id: i64) -> Result<Option<Server>, sqlx::Error> {
         DatabaseHelper::get_by_id(&self.pool, Server::TABLE, Server::KEY, id).await
@@ -216,12 +290,14 @@ impl ServerRepository {
         };
 
         // Get all components in parallel
-        let (cpus, memory, disks, network_interfaces, gpus, bmc_interfaces, credentials, motherboard_detail) = tokio::try_join!(
+        let (cpus, memory, disks, network_interfaces, network_routes, gpus, power_supplies, bmc_interfaces, credentials, motherboard_detail) = tokio::try_join!(
             self.get_server_cpus(server_id),
             self.get_server_memory(server_id),
             self.get_server_disks(server_id),
             self.get_server_network_interfaces(server_id),
+            self.get_server_network_routes(server_id),
             self.get_server_gpus(server_id),
+            self.get_server_power_supplies(server_id),
             self.get_server_bmc_interfaces(server_id),
             self.get_server_credentials(server_id),
             self.get_server_motherboard_detail(server_id)
@@ -233,7 +309,9 @@ impl ServerRepository {
             memory,
             disks,
             network_interfaces,
+            network_routes,
             gpus,
+            power_supplies,
             bmc_interfaces,
             credentials,
             motherboard_detail,
@@ -425,6 +503,49 @@ impl ServerRepository {
             .await
     }
 
+    async fn get_server_power_supplies(&self, server_id: i32) -> Result<Vec<crate::models::ServerPowerSupplyDetail>, sqlx::Error> {
+        let query = format!(r#"
+            SELECT 
+                psu_id,
+                name,
+                manufacturer,
+                model,
+                serial_number,
+                part_number,
+                max_power_watts,
+                efficiency_rating,
+                status,
+                CAST(input_voltage AS {double}) as input_voltage,
+                CAST(input_current AS {double}) as input_current,
+                CAST(output_voltage AS {double}) as output_voltage,
+                CAST(output_current AS {double}) as output_current,
+                temperature_c,
+                fan_speed_rpm
+            FROM server_power_supplies
+            WHERE server_id = ?
+            ORDER BY name, psu_id
+        "#, double = DB::DOUBLE);
+
+        sqlx::query_as(&query)
+            .bind(server_id)
+            .fetch_all(&self.pool)
+            .await
+    }
+
+    async fn get_server_network_routes(&self, server_id: i32) -> Result<Vec<crate::models::ServerNetworkRoute>, sqlx::Error> {
+        let query = r#"
+            SELECT route_id, destination, gateway, interface_name
+            FROM server_network_routes
+            WHERE server_id = ?
+            ORDER BY interface_name, destination
+        "#;
+
+        sqlx::query_as(query)
+            .bind(server_id)
+            .fetch_all(&self.pool)
+            .await
+    }
+
     async fn get_server_credentials(&self, server_id: i32) -> Result<Vec<crate::models::ServerCredential>, sqlx::Error> {
         let query = r#"
             SELECT 
@@ -506,14 +627,6 @@ impl ServerRepository {
             .collect()
     }
 
-    /// Helper function to find count by name (case-insensitive)
-    fn find_count_by_name(stats: &[(String, i64)], name: &str) -> i64 {
-        stats.iter()
-            .find(|(status, _)| status.to_lowercase() == name.to_lowercase())
-            .map(|(_, count)| *count)
-            .unwrap_or(0)
-    }
-
     /// Get server overview statistics
     pub async fn get_overview_stats(&self) -> Result<serde_json::Value, sqlx::Error> {
         let total_servers: i64 = DatabaseHelper::get_total_count(&self.pool, Server::TABLE).await?;
@@ -577,60 +690,53 @@ impl ServerRepository {
         ).await
     }
 
-    /// Create a new server from inventory data
-    /// Validates components exist in database and creates server with all components
-    pub async fn create_server_from_inventory(
+    /// Create a new server from inventory data, given what resolve_identity made of its
+    /// identity. Fails if the inventory resolves to an existing server, or to several without
+    /// a clear best match.
+    async fn create_server_for_decision(
         &self,
-        inventory: ServerInventory
+        inventory: ServerInventory,
+        identity: &ServerIdentity,
+        decision: &IdentityDecision
     ) -> Result<i32, sqlx::Error> {
-        // Find primary NIC MAC address
-        let primary_nic = inventory.network.interfaces.iter()
-            .find(|iface| iface.is_primary)
-            .ok_or_else(|| sqlx::Error::Protocol("No primary network interface found".to_string()))?;
-
-        let primary_mac = primary_nic.mac_address.as_ref()
-            .ok_or_else(|| sqlx::Error::Protocol("Primary network interface has no MAC address".to_string()))?;
-
-        // Check if server already exists
-        if let Some(existing_id) = self.check_server_exists_by_mac(primary_mac).await? {
-            return Err(sqlx::Error::Protocol(
-                format!("Server already exists with MAC address {} (server_id: {})", primary_mac, existing_id)
-            ));
+        // Refuse to duplicate a server that is already known
+        match (decision.resolution, decision.server_id) {
+            (IdentityResolution::Matched, Some(existing_id)) => {
+                let keys: Vec<&str> = decision.matched_by.iter().map(|key| key.as_str()).collect();
+                return Err(sqlx::Error::Protocol(format!(
+                    "Server already exists with matching {} (server_id: {})", keys.join(", "), existing_id
+                )));
+            }
+            (IdentityResolution::Rejected, _) => {
+                let server_ids: Vec<String> = decision.conflicting.iter().map(|c| c.server_id.to_string()).collect();
+                return Err(sqlx::Error::Protocol(format!(
+                    "Server identity conflict: inventory matches servers {} without a clear best match", server_ids.join(", ")
+                )));
+            }
+            _ => {}
         }
 
         // Start transaction
         let mut tx = self.pool.begin().await?;
 
-        // 1. Find or create motherboard component type
-        let motherboard_id = if let Some(ref mb) = inventory.node.motherboard {
-            self.find_or_create_motherboard_type(&mut tx, mb).await?
-        } else {
-            None
-        };
-
-        // Parse BIOS release date
-        let bios_release_date = inventory.node.bios.as_ref()
-            .and_then(|bios| bios.release_date.as_ref())
-            .and_then(|date_str| chrono::NaiveDate::parse_from_str(date_str, "%m/%d/%Y").ok());
-
-        // 2. Create server record
-        let server_insert = r#"
+        // 1. Create server record
+        let server_insert = format!(r#"
             INSERT INTO servers (
                 agent_version, architecture,
                 chassis_manufacturer, chassis_serial_number,
-                manufacturer, product_name, serial_number,
+                manufacturer, product_name, serial_number, system_uuid,
                 server_name, server_type, stage, state, status, environment_type,
                 last_inventory_at, created_at, updated_at
             ) VALUES (
                 ?, ?,
                 ?, ?,
-                ?, ?, ?,
+                ?, ?, ?, ?,
                 ?, 'BAREMETAL', 'DISCOVERY', 'NEW', 'ACTIVE', 'PRODUCTION',
-                NOW(), NOW(), NOW()
+                {now}, {now}, {now}
             )
-        "#;
+        "#, now = DB::CURRENT_TIMESTAMP);
 
-        let server_result = sqlx::query(server_insert)
+        let server_result = sqlx::query(&server_insert)
             .bind(&inventory.agent_version)
             .bind(&inventory.node.architecture)
             .bind(&inventory.node.chassis_manufacturer)
@@ -638,14 +744,16 @@ impl ServerRepository {
             .bind(&inventory.node.manufacturer)
             .bind(&inventory.node.product_name)
             .bind(&inventory.node.serial_number)
+            .bind(&identity.system_uuid)
             .bind(&inventory.node.hostname)
             .execute(&mut *tx)
             .await?;
 
-        let server_id = server_result.last_insert_id() as i32;
+        let server_id = DB::last_insert_id(&server_result) as i32;
 
-        // 3. Add all components
+        // 2. Add all components
         self.insert_server_components(&mut tx, server_id, &inventory).await?;
+        self.insert_inventory_snapshot(&mut tx, server_id, &inventory).await?;
 
         // Commit transaction
         tx.commit().await?;
@@ -654,29 +762,23 @@ impl ServerRepository {
     }
 
     /// Update an existing server from inventory data
-    /// Only updates components that have changed (intelligent diff)
+    /// Only updates components that have changed (intelligent diff) and returns the ones it removed
     pub async fn update_server_from_inventory(
         &self,
         server_id: i32,
         inventory: ServerInventory
-    ) -> Result<bool, sqlx::Error> {
+    ) -> Result<Vec<RemovedComponent>, sqlx::Error> {
+        let mut changes = Vec::new();
+
         // Start transaction
         let mut tx = self.pool.begin().await?;
 
-        // 1. Find or create motherboard component type
-        let motherboard_id = if let Some(ref mb) = inventory.node.motherboard {
-            self.find_or_create_motherboard_type(&mut tx, mb).await?
-        } else {
-            None
-        };
-
-        // Parse BIOS release date
-        let bios_release_date = inventory.node.bios.as_ref()
-            .and_then(|bios| bios.release_date.as_ref())
-            .and_then(|date_str| chrono::NaiveDate::parse_from_str(date_str, "%m/%d/%Y").ok());
+        // Stored normalised, so identity lookups compare it as is. Kept when a report has none:
+        // an agent that cannot read SMBIOS does not make the machine another one.
+        let system_uuid = ServerIdentity::from_inventory(&inventory).system_uuid;
 
-        // 2. Update server record
-        sqlx::query(r#"
+        // 1. Update server record
+        sqlx::query(&format!(r#"
             UPDATE servers SET
                 agent_version = ?,
                 architecture = ?,
@@ -685,11 +787,12 @@ impl ServerRepository {
                 manufacturer = ?,
                 product_name = ?,
                 serial_number = ?,
+                system_uuid = COALESCE(?, system_uuid),
                 server_name = ?,
-                last_inventory_at = NOW(),
-                updated_at = NOW()
+                last_inventory_at = {now},
+                updated_at = {now}
             WHERE server_id = ?
-        "#)
+        "#, now = DB::CURRENT_TIMESTAMP))
         .bind(&inventory.agent_version)
         .bind(&inventory.node.architecture)
         .bind(&inventory.node.chassis_manufacturer)
@@ -697,37 +800,46 @@ impl ServerRepository {
         .bind(&inventory.node.manufacturer)
         .bind(&inventory.node.product_name)
         .bind(&inventory.node.serial_number)
+        .bind(&system_uuid)
         .bind(&inventory.node.hostname)
         .bind(server_id)
         .execute(&mut *tx)
         .await?;
 
-        // 3. Update components intelligently (only what changed)
-        self.sync_server_motherboard(&mut tx, server_id, &inventory.node.motherboard, &inventory.node.bios).await?;
-        self.sync_server_cpus(&mut tx, server_id, &inventory.cpu.cpus).await?;
-        self.sync_server_memory(&mut tx, server_id, &inventory.memory.dimms).await?;
-        self.sync_server_disks(&mut tx, server_id, &inventory.disks).await?;
-        self.sync_server_network_interfaces(&mut tx, server_id, &inventory.network.interfaces).await?;
-        self.sync_server_gpus(&mut tx, server_id, &inventory.gpus).await?;
-        self.sync_server_bmc(&mut tx, server_id, &inventory.node.bmc).await?;
+        // 2. Update components intelligently (only what changed)
+        self.sync_server_motherboard(&mut tx, server_id, &inventory.node.motherboard, &inventory.node.bios, &mut changes).await?;
+        self.sync_server_cpus(&mut tx, server_id, &inventory.cpu.cpus, &mut changes).await?;
+        self.sync_server_memory(&mut tx, server_id, &inventory.memory.dimms, &mut changes).await?;
+        self.sync_server_disks(&mut tx, server_id, &inventory.disks, &mut changes).await?;
+        self.sync_server_network_interfaces(&mut tx, server_id, &inventory.network.interfaces, &mut changes).await?;
+        self.sync_server_network_routes(&mut tx, server_id, &inventory.network.routes, &mut changes).await?;
+        self.sync_server_gpus(&mut tx, server_id, &inventory.gpus, &mut changes).await?;
+        self.sync_server_power_supplies(&mut tx, server_id, &inventory.power_supplies, &mut changes).await?;
+        self.sync_server_bmc(&mut tx, server_id, &inventory.node, &mut changes).await?;
+        self.insert_inventory_changes(&mut tx, server_id, &changes).await?;
+        self.track_missing_components(&mut tx, server_id, &inventory, &changes).await?;
+        self.insert_inventory_snapshot(&mut tx, server_id, &inventory).await?;
 
         // Commit transaction
         tx.commit().await?;
 
-        Ok(true)
+        Ok(changes.iter().filter_map(ComponentChange::as_removed).collect())
     }
 
     // Component sync methods - intelligently add/update/delete based on diff
     
     async fn sync_server_motherboard(
         &self,
-        tx: &mut sqlx::Transaction<'_, sqlx::MySql>,
+        tx: &mut sqlx::Transaction<'_, DB>,
         server_id: i32,
         motherboard_info: &Option<MotherboardInfo>,
-        bios_info: &Option<BiosInfo>
+        bios_info: &Option<BiosInfo>,
+        changes: &mut Vec<ComponentChange>
     ) -> Result<(), sqlx::Error> {
+        type ExistingMotherboard = (i32, Option<i32>, Option<String>, Option<String>, Option<String>);
+
         // Get existing motherboard
-        let existing: Option<(i32, Option<i32>, Option<String>, Option<String>, Option<String>)> = sqlx::query_as(
+        let existing: Option<ExistingMotherboard> = sqlx::query_as(
             "SELECT motherboard_id, component_motherboard_id, serial_number, bios_vendor, bios_version FROM server_motherboards WHERE server_id = ?"
         )
         .bind(server_id)
@@ -748,6 +860,17 @@ impl ServerRepository {
                     || existing_vendor != bios_info.as_ref().and_then(|b| b.vendor.clone())
                     || existing_version != bios_info.as_ref().and_then(|b| b.version.clone());
 
+                changes.extend(ComponentChange::modified(
+                    "chassis", "motherboard",
+                    json!({ "serial_number": existing_serial }),
+                    json!({ "serial_number": mb_info.serial_number }),
+                ));
+                changes.extend(ComponentChange::modified(
+                    "bios", "bios",
+                    json!({ "vendor": existing_vendor, "version": existing_version }),
+                    json!({ "vendor": bios_info.as_ref().and_then(|b| b.vendor.as_ref()), "version": bios_info.as_ref().and_then(|b| b.version.as_ref()) }),
+                ));
+
                 if needs_update {
                     sqlx::query(r#"
                         UPDATE server_motherboards SET
@@ -769,6 +892,7 @@ impl ServerRepository {
                 }
             } else {
                 // Insert new motherboard
+                changes.push(ComponentChange::added("chassis", "motherboard", json!(mb_info)));
                 sqlx::query(r#"
                     INSERT INTO server_motherboards (
                         server_id, component_motherboard_id, serial_number,
@@ -785,8 +909,12 @@ impl ServerRepository {
                 .execute(&mut **tx)
                 .await?;
             }
-        } else if existing.is_some() {
+        } else if let Some((_, _, serial, vendor, version)) = existing {
             // No motherboard in inventory, remove if exists
+            changes.push(ComponentChange::removed(
+                "chassis", "motherboard",
+                json!({ "serial_number": serial, "bios": { "vendor": vendor, "version": version } }),
+            ));
             sqlx::query("DELETE FROM server_motherboards WHERE server_id = ?")
                 .bind(server_id)
                 .execute(&mut **tx)
@@ -798,9 +926,10 @@ impl ServerRepository {
     
     async fn sync_server_cpus(
         &self,
-        tx: &mut sqlx::Transaction<'_, sqlx::MySql>,
+        tx: &mut sqlx::Transaction<'_, DB>,
         server_id: i32,
-        inventory_cpus: &[CpuDetail]
+        inventory_cpus: &[CpuSocket],
+        changes: &mut Vec<ComponentChange>
     ) -> Result<(), sqlx::Error> {
         // Get existing CPUs
         let existing: Vec<(i32, i32, Option<String>)> = sqlx::query_as(
@@ -818,7 +947,13 @@ impl ServerRepository {
         for cpu in inventory_cpus {
             let cpu_type_id = self.find_or_create_cpu_type(tx, cpu).await?;
 
-            if let Some((cpu_id, existing_slot)) = existing_sockets.remove(&cpu.socket) {
+            if let Some((cpu_id, existing_slot)) = existing_sockets.remove(&(cpu.socket as i32)) {
+                changes.extend(ComponentChange::modified(
+                    "cpu", format!("socket {}", cpu.socket),
+                    json!({ "slot": existing_slot }),
+                    json!({ "slot": cpu.slot }),
+                ));
+
                 // Update if slot or type changed
                 if existing_slot.as_ref() != cpu.slot.as_ref() {
                     sqlx::query("UPDATE server_cpus SET component_cpu_id = ?, slot = ? WHERE cpu_id = ?")
@@ -830,6 +965,7 @@ impl ServerRepository {
                 }
             } else {
                 // Insert new CPU
+                changes.push(ComponentChange::added("cpu", format!("socket {}", cpu.socket), json!(cpu)));
                 sqlx::query("INSERT INTO server_cpus (server_id, component_cpu_id, socket_number, slot) VALUES (?, ?, ?, ?)")
                     .bind(server_id)
                     .bind(cpu_type_id)
@@ -841,7 +977,8 @@ impl ServerRepository {
         }
 
         // Delete CPUs no longer present
-        for (cpu_id, _) in existing_sockets.values() {
+        for (socket, (cpu_id, slot)) in &existing_sockets {
+            changes.push(ComponentChange::removed("cpu", format!("socket {}", socket), json!({ "socket": socket, "slot": slot })));
             sqlx::query("DELETE FROM server_cpus WHERE cpu_id = ?")
                 .bind(cpu_id)
                 .execute(&mut **tx)
@@ -853,9 +990,10 @@ impl ServerRepository {
 
     async fn sync_server_memory(
         &self,
-        tx: &mut sqlx::Transaction<'_, sqlx::MySql>,
+        tx: &mut sqlx::Transaction<'_, DB>,
         server_id: i32,
-        inventory_dimms: &[DimmDetail]
+        inventory_dimms: &[DimmInfo],
+        changes: &mut Vec<ComponentChange>
     ) -> Result<(), sqlx::Error> {
         // Get existing DIMMs
         let existing: Vec<(i32, String, Option<String>)> = sqlx::query_as(
@@ -871,9 +1009,18 @@ impl ServerRepository {
 
         // Process inventory DIMMs
         for dimm in inventory_dimms {
+            let Some(slot) = dimm_slot(dimm) else {
+                continue;
+            };
             let memory_type_id = self.find_or_create_memory_type(tx, dimm).await?;
 
-            if let Some((dimm_id, existing_serial)) = existing_slots.remove(&dimm.slot) {
+            if let Some((dimm_id, existing_serial)) = existing_slots.remove(slot) {
+                changes.extend(ComponentChange::modified(
+                    "memory", slot,
+                    json!({ "serial_number": existing_serial }),
+                    json!({ "serial_number": dimm.serial_number }),
+                ));
+
                 // Update if serial or type changed
                 if existing_serial.as_ref() != dimm.serial_number.as_ref() {
                     sqlx::query("UPDATE server_memory_dimms SET component_memory_id = ?, serial_number = ? WHERE dimm_id = ?")
@@ -885,10 +1032,11 @@ impl ServerRepository {
                 }
             } else {
                 // Insert new DIMM
+                changes.push(ComponentChange::added("memory", slot, json!(dimm)));
                 sqlx::query("INSERT INTO server_memory_dimms (server_id, component_memory_id, slot, serial_number) VALUES (?, ?, ?, ?)")
                     .bind(server_id)
                     .bind(memory_type_id)
-                    .bind(&dimm.slot)
+                    .bind(slot)
                     .bind(&dimm.serial_number)
                     .execute(&mut **tx)
                     .await?;
@@ -896,7 +1044,8 @@ impl ServerRepository {
         }
 
         // Delete DIMMs no longer present
-        for (dimm_id, _) in existing_slots.values() {
+        for (slot, (dimm_id, serial)) in &existing_slots {
+            changes.push(ComponentChange::removed("memory", slot.clone(), json!({ "slot": slot, "serial_number": serial })));
             sqlx::query("DELETE FROM server_memory_dimms WHERE dimm_id = ?")
                 .bind(dimm_id)
                 .execute(&mut **tx)
@@ -908,23 +1057,26 @@ impl ServerRepository {
 
     async fn sync_server_disks(
         &self,
-        tx: &mut sqlx::Transaction<'_, sqlx::MySql>,
+        tx: &mut sqlx::Transaction<'_, DB>,
         server_id: i32,
-        inventory_disks: &[DiskInfo]
+        inventory_disks: &[DiskInfo],
+        changes: &mut Vec<ComponentChange>
     ) -> Result<(), sqlx::Error> {
+        type ExistingDisk = (i32, String, Option<String>, Option<String>, Option<String>, Option<String>);
+
         // Get existing disks
-        let existing: Vec<(i32, String, Option<String>, Option<String>, Option<String>)> = sqlx::query_as(
-            "SELECT disk_id, name, dev_path, serial, firmware_version FROM server_disks WHERE server_id = ?"
+        let existing: Vec<ExistingDisk> = sqlx::query_as(
+            "SELECT disk_id, name, dev_path, serial, firmware_version, smart_health FROM server_disks WHERE server_id = ?"
         )
         .bind(server_id)
         .fetch_all(&mut **tx)
         .await?;
 
         // Match by serial number (most reliable), fallback to name
-        let mut existing_disks: HashMap<String, (i32, String, Option<String>, Option<String>, Option<String>)> = HashMap::new();
-        for (disk_id, name, dev_path, serial, firmware) in existing {
+        let mut existing_disks: HashMap<String, ExistingDisk> = HashMap::new();
+        for (disk_id, name, dev_path, serial, firmware, health) in existing {
             let key = serial.clone().unwrap_or_else(|| name.clone());
-            existing_disks.insert(key, (disk_id, name, dev_path, serial, firmware));
+            existing_disks.insert(key, (disk_id, name, dev_path, serial, firmware, health));
         }
 
         // Process inventory disks
@@ -933,12 +1085,25 @@ impl ServerRepository {
             let key = disk.serial.clone().unwrap_or_else(|| disk.name.clone());
             let smart_health = disk.smart.as_ref().and_then(|s| s.health.as_ref()).map(|h| h.as_str());
 
-            if let Some((disk_id, existing_name, existing_dev_path, existing_serial, existing_firmware)) = existing_disks.remove(&key) {
+            if let Some((disk_id, existing_name, existing_dev_path, existing_serial, existing_firmware, existing_health)) = existing_disks.remove(&key) {
                 // Update if any field changed
                 let needs_update = existing_name != disk.name
-                    || existing_dev_path != disk.dev_path
+                    || existing_dev_path.as_deref() != Some(disk.dev_path.as_str())
                     || existing_serial != disk.serial
-                    || existing_firmware != disk.firmware_version;
+                    || existing_firmware != disk.firmware_version
+                    || existing_health.as_deref() != smart_health;
+
+                changes.extend(ComponentChange::modified(
+                    "disk", key.clone(),
+                    json!({
+                        "name": existing_name, "dev_path": existing_dev_path, "serial": existing_serial,
+                        "firmware_version": existing_firmware, "smart_health": existing_health,
+                    }),
+                    json!({
+                        "name": disk.name, "dev_path": disk.dev_path, "serial": disk.serial,
+                        "firmware_version": disk.firmware_version, "smart_health": smart_health,
+                    }),
+                ));
 
                 if needs_update {
                     sqlx::query(r#"
@@ -963,6 +1128,7 @@ impl ServerRepository {
                 }
             } else {
                 // Insert new disk
+                changes.push(ComponentChange::added("disk", key.clone(), json!(disk)));
                 sqlx::query(r#"
                     INSERT INTO server_disks (
                         server_id, component_disk_id, name, dev_path, serial, 
@@ -983,7 +1149,11 @@ impl ServerRepository {
         }
 
         // Delete disks no longer present
-        for (disk_id, _, _, _, _) in existing_disks.values() {
+        for (key, (disk_id, name, dev_path, serial, firmware, health)) in &existing_disks {
+            changes.push(ComponentChange::removed("disk", key.clone(), json!({
+                "name": name, "dev_path": dev_path, "serial": serial,
+                "firmware_version": firmware, "smart_health": health,
+            })));
             sqlx::query("DELETE FROM server_disks WHERE disk_id = ?")
                 .bind(disk_id)
                 .execute(&mut **tx)
@@ -995,12 +1165,17 @@ impl ServerRepository {
 
     async fn sync_server_network_interfaces(
         &self,
-        tx: &mut sqlx::Transaction<'_, sqlx::MySql>,
+        tx: &mut sqlx::Transaction<'_, DB>,
         server_id: i32,
-        inventory_interfaces: &[NetworkInterface]
+        inventory_interfaces: &[NetInterface],
+        changes: &mut Vec<ComponentChange>
     ) -> Result<(), sqlx::Error> {
+        type InterfaceRow = (i32, String, Option<String>, Option<String>, Option<i32>, Option<i32>, Option<String>, Option<String>, Option<bool>);
+        // The row without its MAC address, which keys the map
+        type ExistingInterface = (i32, String, Option<String>, Option<i32>, Option<i32>, Option<String>, Option<String>, Option<bool>);
+
         // Get existing interfaces
-        let existing: Vec<(i32, String, Option<String>, Option<String>, Option<i32>, Option<i32>, Option<String>, Option<String>, Option<bool>)> = sqlx::query_as(
+        let existing: Vec<InterfaceRow> = sqlx::query_as(
             "SELECT interface_id, name, mac_address, ip_address, mtu, speed_mbps, firmware_version, pci_address, is_primary FROM server_network_interfaces WHERE server_id = ?"
         )
         .bind(server_id)
@@ -1008,13 +1183,37 @@ impl ServerRepository {
         .await?;
 
         // Match by MAC address (most reliable)
-        let mut existing_interfaces: HashMap<String, (i32, String, Option<String>, Option<i32>, Option<i32>, Option<String>, Option<String>, Option<bool>)> = HashMap::new();
+        let mut existing_interfaces: HashMap<String, ExistingInterface> = HashMap::new();
         for (iface_id, name, mac, ip, mtu, speed, firmware, pci, is_primary) in existing {
             if let Some(mac_addr) = mac {
                 existing_interfaces.insert(mac_addr.to_lowercase(), (iface_id, name, ip, mtu, speed, firmware, pci, is_primary));
             }
         }
 
+        // Delete interfaces no longer present first: a replaced card comes back under the name
+        // of the old one, which has to be free by the time it is inserted
+        let reported_macs: HashSet<String> = inventory_interfaces.iter()
+            .filter_map(|iface| iface.mac_address.as_ref())
+            .map(|mac| mac.to_lowercase())
+            .collect();
+        let gone: Vec<String> = existing_interfaces.keys()
+            .filter(|mac| !reported_macs.contains(*mac))
+            .cloned()
+            .collect();
+        for mac in gone {
+            let Some((iface_id, name, ip, mtu, speed, firmware, pci, is_primary)) = existing_interfaces.remove(&mac) else {
+                continue;
+            };
+            changes.push(ComponentChange::removed("network", mac, json!({
+                "name": name, "ip_address": ip, "mtu": mtu, "speed_mbps": speed,
+                "firmware_version": firmware, "pci_address": pci, "is_primary": is_primary,
+            })));
+            sqlx::query("DELETE FROM server_network_interfaces WHERE interface_id = ?")
+                .bind(iface_id)
+                .execute(&mut **tx)
+                .await?;
+        }
+
         // Process inventory interfaces
         for iface in inventory_interfaces {
             if let Some(mac) = &iface.mac_address {
@@ -1026,12 +1225,24 @@ impl ServerRepository {
                     // Update if any field changed
                     let needs_update = existing_name != iface.name
                         || existing_ip != ip_address
-                        || existing_mtu != iface.mtu
-                        || existing_speed != iface.speed_mbps
+                        || existing_mtu != iface.mtu.map(|mtu| mtu as i32)
+                        || existing_speed != iface.speed_mbps.map(|speed| speed as i32)
                         || existing_firmware != iface.firmware_version
                         || existing_pci != iface.pci_address
                         || existing_is_primary != Some(iface.is_primary);
 
+                    changes.extend(ComponentChange::modified(
+                        "network", mac_lower.clone(),
+                        json!({
+                            "name": existing_name, "ip_address": existing_ip, "mtu": existing_mtu, "speed_mbps": existing_speed,
+                            "firmware_version": existing_firmware, "pci_address": existing_pci, "is_primary": existing_is_primary,
+                        }),
+                        json!({
+                            "name": iface.name, "ip_address": ip_address, "mtu": iface.mtu, "speed_mbps": iface.speed_mbps,
+                            "firmware_version": iface.firmware_version, "pci_address": iface.pci_address, "is_primary": iface.is_primary,
+                        }),
+                    ));
+
                     if needs_update {
                         sqlx::query(r#"
                             UPDATE server_network_interfaces SET
@@ -1063,6 +1274,7 @@ impl ServerRepository {
                     }
                 } else {
                     // Insert new interface
+                    changes.push(ComponentChange::added("network", mac_lower.clone(), json!(iface)));
                     sqlx::query(r#"
                         INSERT INTO server_network_interfaces (
                             server_id, component_network_id, name, mac_address, ip_address,
@@ -1089,25 +1301,20 @@ impl ServerRepository {
             }
         }
 
-        // Delete interfaces no longer present
-        for (iface_id, _, _, _, _, _, _, _) in existing_interfaces.values() {
-            sqlx::query("DELETE FROM server_network_interfaces WHERE interface_id = ?")
-                .bind(iface_id)
-                .execute(&mut **tx)
-                .await?;
-        }
-
         Ok(())
     }
 
     async fn sync_server_gpus(
         &self,
-        tx: &mut sqlx::Transaction<'_, sqlx::MySql>,
+        tx: &mut sqlx::Transaction<'_, DB>,
         server_id: i32,
-        inventory_gpus: &[GpuInfo]
+        inventory_gpus: &[GpuInfo],
+        changes: &mut Vec<ComponentChange>
     ) -> Result<(), sqlx::Error> {
+        type ExistingGpu = (i32, Option<String>, Option<String>, Option<String>);
+
         // Get existing GPUs
-        let existing: Vec<(i32, Option<String>, Option<String>, Option<String>)> = sqlx::query_as(
+        let existing: Vec<ExistingGpu> = sqlx::query_as(
             "SELECT gpu_id, uuid, pci_address, driver_version FROM server_gpus WHERE server_id = ?"
         )
         .bind(server_id)
@@ -1131,6 +1338,12 @@ impl ServerRepository {
                 let needs_update = existing_pci != gpu.pci_address
                     || existing_driver != gpu.driver_version;
 
+                changes.extend(ComponentChange::modified(
+                    "gpu", key.clone(),
+                    json!({ "pci_address": existing_pci, "driver_version": existing_driver }),
+                    json!({ "pci_address": gpu.pci_address, "driver_version": gpu.driver_version }),
+                ));
+
                 if needs_update {
                     sqlx::query(r#"
                         UPDATE server_gpus SET
@@ -1150,6 +1363,7 @@ impl ServerRepository {
                 }
             } else {
                 // Insert new GPU
+                changes.push(ComponentChange::added("gpu", key.clone(), json!(gpu)));
                 sqlx::query(r#"
                     INSERT INTO server_gpus (
                         server_id, component_gpu_id, pci_address, driver_version, uuid
@@ -1167,7 +1381,8 @@ impl ServerRepository {
         }
 
         // Delete GPUs no longer present
-        for (gpu_id, _, _) in existing_gpus.values() {
+        for (key, (gpu_id, pci_address, driver_version)) in &existing_gpus {
+            changes.push(ComponentChange::removed("gpu", key.clone(), json!({ "pci_address": pci_address, "driver_version": driver_version })));
             sqlx::query("DELETE FROM server_gpus WHERE gpu_id = ?")
                 .bind(gpu_id)
                 .execute(&mut **tx)
@@ -1177,16 +1392,230 @@ impl ServerRepository {
         Ok(())
     }
 
+    async fn sync_server_power_supplies(
+        &self,
+        tx: &mut sqlx::Transaction<'_, DB>,
+        server_id: i32,
+        inventory_psus: &[PowerSupplyInfo],
+        changes: &mut Vec<ComponentChange>
+    ) -> Result<(), sqlx::Error> {
+        type ExistingPsu = (i32, Option<String>, Option<String>, Option<String>, Option<String>);
+
+        // Get existing PSUs
+        let existing: Vec<ExistingPsu> = sqlx::query_as(
+            "SELECT psu_id, name, model, serial_number, status FROM server_power_supplies WHERE server_id = ?"
+        )
+        .bind(server_id)
+        .fetch_all(&mut **tx)
+        .await?;
+
+        // Match by serial number, fallback to name
+        let mut existing_psus: HashMap<String, ExistingPsu> = HashMap::new();
+        for psu in existing {
+            let key = power_supply_key(&psu.3, &psu.1).unwrap_or_else(|| format!("psu_{}", psu.0));
+            existing_psus.insert(key, psu);
+        }
+
+        // Process inventory PSUs. The agent merges several sources, so the same PSU can be listed twice.
+        let mut seen = HashSet::new();
+        for psu in inventory_psus {
+            let Some(key) = power_supply_key(&psu.serial_number, &psu.name).filter(|key| seen.insert(key.clone())) else {
+                continue;
+            };
+
+            if let Some((psu_id, name, model, serial_number, status)) = existing_psus.remove(&key) {
+                changes.extend(ComponentChange::modified(
+                    "power", key.clone(),
+                    json!({ "name": name, "model": model, "serial_number": serial_number, "status": status }),
+                    json!({ "name": psu.name, "model": psu.model, "serial_number": psu.serial_number, "status": psu.status }),
+                ));
+
+                // Readings change with every report, so the row is always refreshed
+                sqlx::query(r#"
+                    UPDATE server_power_supplies SET
+                        name = ?,
+                        manufacturer = ?,
+                        model = ?,
+                        serial_number = ?,
+                        part_number = ?,
+                        max_power_watts = ?,
+                        efficiency_rating = ?,
+                        status = ?,
+                        input_voltage = ?,
+                        input_current = ?,
+                        output_voltage = ?,
+                        output_current = ?,
+                        temperature_c = ?,
+                        fan_speed_rpm = ?
+                    WHERE psu_id = ?
+                "#)
+                .bind(&psu.name)
+                .bind(&psu.manufacturer)
+                .bind(&psu.model)
+                .bind(&psu.serial_number)
+                .bind(&psu.part_number)
+                .bind(psu.max_power_watts)
+                .bind(&psu.efficiency_rating)
+                .bind(&psu.status)
+                .bind(psu.input_voltage)
+                .bind(psu.input_current)
+                .bind(psu.output_voltage)
+                .bind(psu.output_current)
+                .bind(psu.temperature_c)
+                .bind(psu.fan_speed_rpm)
+                .bind(psu_id)
+                .execute(&mut **tx)
+                .await?;
+            } else {
+                // Insert new PSU
+                changes.push(ComponentChange::added("power", key.clone(), json!(psu)));
+                self.insert_power_supply(tx, server_id, psu).await?;
+            }
+        }
+
+        // Delete PSUs no longer present
+        for (key, (psu_id, name, model, serial_number, status)) in &existing_psus {
+            changes.push(ComponentChange::removed("power", key.clone(), json!({
+                "name": name, "model": model, "serial_number": serial_number, "status": status,
+            })));
+            sqlx::query("DELETE FROM server_power_supplies WHERE psu_id = ?")
+                .bind(psu_id)
+                .execute(&mut **tx)
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn sync_server_network_routes(
+        &self,
+        tx: &mut sqlx::Transaction<'_, DB>,
+        server_id: i32,
+        inventory_routes: &[RouteInfo],
+        changes: &mut Vec<ComponentChange>
+    ) -> Result<(), sqlx::Error> {
+        // Get existing routes
+        let existing: Vec<(i32, String, Option<String>, String)> = sqlx::query_as(
+            "SELECT route_id, destination, gateway, interface_name FROM server_network_routes WHERE server_id = ?"
+        )
+        .bind(server_id)
+        .fetch_all(&mut **tx)
+        .await?;
+
+        // Match by destination and interface
+        let mut existing_routes: HashMap<String, (i32, Option<String>)> = HashMap::new();
+        for (route_id, destination, gateway, interface_name) in existing {
+            existing_routes.insert(route_key(&destination, &interface_name), (route_id, gateway));
+        }
+
+        // Process inventory routes
+        let mut seen = HashSet::new();
+        for route in inventory_routes {
+            let key = route_key(&route.destination, &route.interface);
+            if !seen.insert(key.clone()) {
+                continue;
+            }
+
+            if let Some((route_id, existing_gateway)) = existing_routes.remove(&key) {
+                if existing_gateway.as_deref() != route.gateway.as_deref() {
+                    changes.extend(ComponentChange::modified(
+                        "route", key.clone(),
+                        json!({ "gateway": existing_gateway }),
+                        json!({ "gateway": route.gateway.as_deref() }),
+                    ));
+                    sqlx::query("UPDATE server_network_routes SET gateway = ? WHERE route_id = ?")
+                        .bind(route.gateway.as_deref())
+                        .bind(route_id)
+                        .execute(&mut **tx)
+                        .await?;
+                }
+            } else {
+                // Insert new route
+                changes.push(ComponentChange::added("route", key.clone(), json!(route)));
+                self.insert_network_route(tx, server_id, route).await?;
+            }
+        }
+
+        // Delete routes no longer present
+        for (key, (route_id, gateway)) in &existing_routes {
+            changes.push(ComponentChange::removed("route", key.clone(), json!({ "gateway": gateway })));
+            sqlx::query("DELETE FROM server_network_routes WHERE route_id = ?")
+                .bind(route_id)
+                .execute(&mut **tx)
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn insert_power_supply(
+        &self,
+        tx: &mut sqlx::Transaction<'_, DB>,
+        server_id: i32,
+        psu: &PowerSupplyInfo
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(r#"
+            INSERT INTO server_power_supplies (
+                server_id, name, manufacturer, model, serial_number, part_number,
+                max_power_watts, efficiency_rating, status,
+                input_voltage, input_current, output_voltage, output_current,
+                temperature_c, fan_speed_rpm
+            )
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+        "#)
+        .bind(server_id)
+        .bind(&psu.name)
+        .bind(&psu.manufacturer)
+        .bind(&psu.model)
+        .bind(&psu.serial_number)
+        .bind(&psu.part_number)
+        .bind(psu.max_power_watts)
+        .bind(&psu.efficiency_rating)
+        .bind(&psu.status)
+        .bind(psu.input_voltage)
+        .bind(psu.input_current)
+        .bind(psu.output_voltage)
+        .bind(psu.output_current)
+        .bind(psu.temperature_c)
+        .bind(psu.fan_speed_rpm)
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn insert_network_route(
+        &self,
+        tx: &mut sqlx::Transaction<'_, DB>,
+        server_id: i32,
+        route: &RouteInfo
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(r#"
+            INSERT INTO server_network_routes (server_id, destination, gateway, interface_name)
+            VALUES (?, ?, ?, ?)
+        "#)
+        .bind(server_id)
+        .bind(&route.destination)
+        .bind(route.gateway.as_deref())
+        .bind(&route.interface)
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
+
     async fn sync_server_bmc(
         &self,
-        tx: &mut sqlx::Transaction<'_, sqlx::MySql>,
+        tx: &mut sqlx::Transaction<'_, DB>,
         server_id: i32,
-        bmc_info: &BmcInfo
+        node: &NodeInfo,
+        changes: &mut Vec<ComponentChange>
     ) -> Result<(), sqlx::Error> {
-        if let Some(mac) = &bmc_info.mac_address {
-            // Check if BMC already exists
-            let existing: Option<(i32,)> = sqlx::query_as(
-                "SELECT bmc_interface_id FROM server_bmc_interfaces WHERE server_id = ? LIMIT 1"
+        if let Some(bmc_info @ BmcInfo { mac_address: Some(mac), .. }) = &node.bmc {
+            // Check if BMC already exists: id, MAC, IP, firmware
+            type ExistingBmc = (i32, Option<String>, Option<String>, Option<String>);
+            let existing: Option<ExistingBmc> = sqlx::query_as(
+                "SELECT bmc_interface_id, mac_address, ip_address, firmware_version FROM server_bmc_interfaces WHERE server_id = ? LIMIT 1"
             )
             .bind(server_id)
             .fetch_optional(&mut **tx)
@@ -1195,7 +1624,13 @@ impl ServerRepository {
             let bmc_release_date = bmc_info.release_date.as_ref()
                 .and_then(|date_str| chrono::NaiveDate::parse_from_str(date_str, "%m/%d/%Y").ok());
 
-            if let Some((bmc_id,)) = existing {
+            if let Some((bmc_id, existing_mac, existing_ip, existing_firmware)) = existing {
+                changes.extend(ComponentChange::modified(
+                    "bmc", "bmc0",
+                    json!({ "mac_address": existing_mac, "ip_address": existing_ip, "firmware_version": existing_firmware }),
+                    json!({ "mac_address": mac, "ip_address": bmc_info.ip_address, "firmware_version": bmc_info.firmware_version }),
+                ));
+
                 // Update existing BMC
                 sqlx::query(r#"
                     UPDATE server_bmc_interfaces SET
@@ -1214,15 +1649,17 @@ impl ServerRepository {
                 .await?;
             } else {
                 // Insert new BMC
+                changes.push(ComponentChange::added("bmc", "bmc0", json!(bmc_info)));
+                let bmc_type_id = self.find_or_create_bmc_type(tx, node).await?;
                 sqlx::query(r#"
                     INSERT INTO server_bmc_interfaces (
-                        server_id, name, mac_address, ip_address, firmware_version,
-                        release_date, supports_ipmi, supports_redfish, supports_web_interface,
-                        is_accessible
+                        server_id, component_bmc_id, name, mac_address, ip_address,
+                        firmware_version, release_date, is_accessible
                     )
-                    VALUES (?, 'bmc0', ?, ?, ?, ?, 1, 1, 1, 0)
+                    VALUES (?, ?, 'bmc0', ?, ?, ?, ?, 0)
                 "#)
                 .bind(server_id)
+                .bind(bmc_type_id)
                 .bind(mac)
                 .bind(&bmc_info.ip_address)
                 .bind(&bmc_info.firmware_version)
@@ -1232,39 +1669,443 @@ impl ServerRepository {
             }
         } else {
             // No BMC in inventory, remove if exists
-            sqlx::query("DELETE FROM server_bmc_interfaces WHERE server_id = ?")
-                .bind(server_id)
-                .execute(&mut **tx)
-                .await?;
+            let existing: Option<(Option<String>, Option<String>, Option<String>)> = sqlx::query_as(
+                "SELECT mac_address, ip_address, firmware_version FROM server_bmc_interfaces WHERE server_id = ? LIMIT 1"
+            )
+            .bind(server_id)
+            .fetch_optional(&mut **tx)
+            .await?;
+
+            if let Some((mac, ip, firmware)) = existing {
+                changes.push(ComponentChange::removed(
+                    "bmc", "bmc0",
+                    json!({ "mac_address": mac, "ip_address": ip, "firmware_version": firmware }),
+                ));
+                sqlx::query("DELETE FROM server_bmc_interfaces WHERE server_id = ?")
+                    .bind(server_id)
+                    .execute(&mut **tx)
+                    .await?;
+            }
         }
 
         Ok(())
     }
 
-    /// Create or update server from inventory data
-    /// Returns (server_id, was_created) where was_created is true if server was newly created
+    /// Create or update the server an inventory report belongs to. The server is recognised by
+    /// its identifiers (see `domain::identity`); reports that match more than one server, or
+    /// match one against contradicting identifiers, are recorded as identity conflicts and
+    /// rejected unless one server is a clear winner.
     pub async fn upsert_server_from_inventory(
         &self,
         inventory: ServerInventory
-    ) -> Result<(i32, bool), sqlx::Error> {
-        // Find primary NIC MAC address
-        let primary_nic = inventory.network.interfaces.iter()
-            .find(|iface| iface.is_primary)
-            .ok_or_else(|| sqlx::Error::Protocol("No primary network interface found".to_string()))?;
-
-        let primary_mac = primary_nic.mac_address.as_ref()
-            .ok_or_else(|| sqlx::Error::Protocol("Primary network interface has no MAC address".to_string()))?;
-
-        // Check if server already exists
-        if let Some(existing_id) = self.check_server_exists_by_mac(primary_mac).await? {
-            // Update existing server
-            self.update_server_from_inventory(existing_id, inventory).await?;
-            Ok((existing_id, false))
+    ) -> Result<InventoryUpsert, sqlx::Error> {
+        let identity = ServerIdentity::from_inventory(&inventory);
+        if identity.is_empty() {
+            return Err(sqlx::Error::Protocol(
+                "No system UUID, serial number or MAC address to identify the server by".to_string()
+            ));
+        }
+
+        let decision = resolve_identity(self.find_identity_candidates(&identity).await?);
+        let hostname = inventory.node.hostname.clone();
+
+        let (server_id, created, unchanged, removed_components) = match (decision.resolution, decision.server_id) {
+            (IdentityResolution::Matched, Some(existing_id)) => {
+                // The report renames the server to a hostname another server holds
+                if let Some(holder_id) = self.find_server_id_by_name(&hostname).await?.filter(|&id| id != existing_id) {
+                    return self.reject_hostname_reuse(decision, &identity, &hostname, holder_id).await;
+                }
+
+                // Same hardware as last time: nothing to sync but the PSU readings
+                let (_, checksum) = inventory_snapshot(&inventory)?;
+                if self.get_latest_snapshot_checksum(existing_id).await?.as_deref() == Some(checksum.as_str()) {
+                    let mut tx = self.pool.begin().await?;
+                    sqlx::query(&format!("UPDATE servers SET last_inventory_at = {} WHERE server_id = ?", DB::CURRENT_TIMESTAMP))
+                        .bind(existing_id)
+                        .execute(&mut *tx)
+                        .await?;
+                    self.refresh_power_supply_readings(&mut tx, existing_id, &inventory.power_supplies).await?;
+                    tx.commit().await?;
+                    (existing_id, false, true, Vec::new())
+                } else {
+                    let removed_components = self.update_server_from_inventory(existing_id, inventory).await?;
+                    (existing_id, false, false, removed_components)
+                }
+            }
+            (IdentityResolution::Created, _) => {
+                // A new machine cannot take the hostname of one it was told apart from, e.g. by
+                // a differing system UUID after a board swap
+                if let Some(holder_id) = self.find_server_id_by_name(&hostname).await? {
+                    return self.reject_hostname_reuse(decision, &identity, &hostname, holder_id).await;
+                }
+                let server_id = self.create_server_for_decision(inventory, &identity, &decision).await?;
+                (server_id, true, false, Vec::new())
+            }
+            _ => {
+                let conflict_id = self.record_identity_conflict(&decision, None, &hostname).await?;
+                let server_ids: Vec<String> = decision.conflicting.iter().map(|c| c.server_id.to_string()).collect();
+                return Err(sqlx::Error::Protocol(format!(
+                    "Server identity conflict: inventory from {} matches servers {} without a clear best match (conflict_id: {})",
+                    hostname, server_ids.join(", "), conflict_id
+                )));
+            }
+        };
+
+        let conflict_id = if decision.conflicting.is_empty() {
+            None
         } else {
-            // Create new server
-            let server_id = self.create_server_from_inventory(inventory).await?;
-            Ok((server_id, true))
+            Some(self.record_identity_conflict(&decision, Some(server_id), &hostname).await?)
+        };
+
+        Ok(InventoryUpsert { server_id, created, unchanged, removed_components, identity: decision, conflict_id })
+    }
+
+    /// Server names are unique, so a report naming a server it was not matched to cannot be
+    /// applied. Records the conflict, with the holder of the name among the candidates.
+    async fn reject_hostname_reuse(
+        &self,
+        decision: IdentityDecision,
+        identity: &ServerIdentity,
+        hostname: &str,
+        holder_id: i32
+    ) -> Result<InventoryUpsert, sqlx::Error> {
+        let mut rejected = IdentityDecision {
+            resolution: IdentityResolution::Rejected,
+            server_id: None,
+            matched_by: Vec::new(),
+            ..decision
+        };
+        if !rejected.conflicting.iter().any(|c| c.server_id == holder_id) {
+            let stored = self.get_server_identity(holder_id).await?;
+            rejected.conflicting.push(IdentityCandidate::score(holder_id, identity, &stored));
+        }
+        let conflict_id = self.record_identity_conflict(&rejected, None, hostname).await?;
+        Err(sqlx::Error::Protocol(format!(
+            "Server identity conflict: inventory from {} does not match server {}, which has that hostname (conflict_id: {})",
+            hostname, holder_id, conflict_id
+        )))
+    }
+
+    async fn find_server_id_by_name(&self, server_name: &str) -> Result<Option<i32>, sqlx::Error> {
+        let row: Option<(i32,)> = sqlx::query_as("SELECT server_id FROM servers WHERE server_name = ?")
+            .bind(server_name)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(row.map(|(server_id,)| server_id))
+    }
+
+    /// Score every server that shares an identifier with a report
+    async fn find_identity_candidates(&self, identity: &ServerIdentity) -> Result<Vec<IdentityCandidate>, sqlx::Error> {
+        if identity.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let (query, values) = identity_candidates_query(identity);
+        let mut candidate_query = sqlx::query_as::<_, (i32,)>(&query);
+        for value in values {
+            candidate_query = candidate_query.bind(value);
+        }
+        let server_ids = candidate_query.fetch_all(&self.pool).await?;
+
+        let mut candidates = Vec::with_capacity(server_ids.len());
+        for (server_id,) in server_ids {
+            let stored = self.get_server_identity(server_id).await?;
+            candidates.push(IdentityCandidate::score(server_id, identity, &stored));
+        }
+        Ok(candidates)
+    }
+
+    /// The identifiers stored for a server by its last accepted inventory
+    async fn get_server_identity(&self, server_id: i32) -> Result<ServerIdentity, sqlx::Error> {
+        let server: Option<StoredIdentityRow> = sqlx::query_as(r#"
+            SELECT s.system_uuid, s.serial_number, s.chassis_serial_number,
+                (SELECT mb.serial_number FROM server_motherboards mb WHERE mb.server_id = s.server_id LIMIT 1),
+                (SELECT bmc.mac_address FROM server_bmc_interfaces bmc WHERE bmc.server_id = s.server_id LIMIT 1)
+            FROM servers s
+            WHERE s.server_id = ?
+        "#)
+        .bind(server_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        let Some((system_uuid, serial_number, chassis_serial_number, board_serial_number, bmc_mac)) = server else {
+            return Ok(ServerIdentity::default());
+        };
+
+        let nic_macs: Vec<(Option<String>,)> = sqlx::query_as("SELECT mac_address FROM server_network_interfaces WHERE server_id = ?")
+            .bind(server_id)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(ServerIdentity::new(
+            system_uuid.as_deref(),
+            serial_number.as_deref(),
+            chassis_serial_number.as_deref(),
+            board_serial_number.as_deref(),
+            bmc_mac.as_deref(),
+            nic_macs.iter().filter_map(|(mac,)| mac.as_deref()),
+        ))
+    }
+
+    /// Record a decision that was not clear-cut and return its conflict_id. An agent reports
+    /// every few minutes, so an open conflict with the same outcome and candidates is reused.
+    async fn record_identity_conflict(
+        &self,
+        decision: &IdentityDecision,
+        server_id: Option<i32>,
+        hostname: &str
+    ) -> Result<i32, sqlx::Error> {
+        let open: Vec<(i32, serde_json::Value)> = sqlx::query_as(r#"
+            SELECT conflict_id, candidates
+            FROM server_identity_conflicts
+            WHERE resolution = ? AND resolved_at IS NULL
+              AND (server_id = ? OR (server_id IS NULL AND hostname = ?))
+            ORDER BY conflict_id DESC
+        "#)
+        .bind(decision.resolution.as_str())
+        .bind(server_id)
+        .bind(hostname)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let candidate_ids = |candidates: &[IdentityCandidate]| {
+            candidates.iter().map(|c| c.server_id).collect::<HashSet<i32>>()
+        };
+        let reported = candidate_ids(&decision.conflicting);
+        let existing = open.into_iter().find(|(_, candidates)| {
+            serde_json::from_value::<Vec<IdentityCandidate>>(candidates.clone())
+                .is_ok_and(|candidates| candidate_ids(&candidates) == reported)
+        });
+        if let Some((conflict_id, _)) = existing {
+            return Ok(conflict_id);
+        }
+
+        let candidates = serde_json::to_value(&decision.conflicting)
+            .map_err(|e| sqlx::Error::Protocol(format!("Failed to serialize identity candidates: {}", e)))?;
+        let result = sqlx::query(r#"
+            INSERT INTO server_identity_conflicts (server_id, resolution, hostname, confidence, candidates)
+            VALUES (?, ?, ?, ?, ?)
+        "#)
+        .bind(server_id)
+        .bind(decision.resolution.as_str())
+        .bind(hostname)
+        .bind(decision.confidence)
+        .bind(&candidates)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(DB::last_insert_id(&result) as i32)
+    }
+
+    async fn get_latest_snapshot_checksum(&self, server_id: i32) -> Result<Option<String>, sqlx::Error> {
+        let row: Option<(String,)> = sqlx::query_as(
+            "SELECT checksum FROM server_inventory_snapshots WHERE server_id = ? ORDER BY snapshot_id DESC LIMIT 1"
+        )
+        .bind(server_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.map(|(checksum,)| checksum))
+    }
+
+    /// Store the latest PSU readings of a server whose hardware has not changed, matching
+    /// PSUs the way the sync does
+    async fn refresh_power_supply_readings(
+        &self,
+        tx: &mut sqlx::Transaction<'_, DB>,
+        server_id: i32,
+        inventory_psus: &[PowerSupplyInfo],
+    ) -> Result<(), sqlx::Error> {
+        let existing: Vec<(i32, Option<String>, Option<String>)> = sqlx::query_as(
+            "SELECT psu_id, name, serial_number FROM server_power_supplies WHERE server_id = ?"
+        )
+        .bind(server_id)
+        .fetch_all(&mut **tx)
+        .await?;
+        let psu_ids: HashMap<String, i32> = existing.into_iter()
+            .filter_map(|(psu_id, name, serial_number)| Some((power_supply_key(&serial_number, &name)?, psu_id)))
+            .collect();
+
+        for psu in inventory_psus {
+            let Some(psu_id) = power_supply_key(&psu.serial_number, &psu.name).and_then(|key| psu_ids.get(&key)) else {
+                continue;
+            };
+            sqlx::query(r#"
+                UPDATE server_power_supplies SET
+                    input_voltage = ?,
+                    input_current = ?,
+                    output_voltage = ?,
+                    output_current = ?,
+                    temperature_c = ?,
+                    fan_speed_rpm = ?
+                WHERE psu_id = ?
+            "#)
+            .bind(psu.input_voltage)
+            .bind(psu.input_current)
+            .bind(psu.output_voltage)
+            .bind(psu.output_current)
+            .bind(psu.temperature_c)
+            .bind(psu.fan_speed_rpm)
+            .bind(psu_id)
+            .execute(&mut **tx)
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Record what the sync changed; runs in the update transaction
+    async fn insert_inventory_changes(
+        &self,
+        tx: &mut sqlx::Transaction<'_, DB>,
+        server_id: i32,
+        changes: &[ComponentChange]
+    ) -> Result<(), sqlx::Error> {
+        for change in changes {
+            sqlx::query(r#"
+                INSERT INTO server_inventory_changes (
+                    server_id, component_type, component_identifier, change_type, old_data, new_data
+                )
+                VALUES (?, ?, ?, ?, ?, ?)
+            "#)
+            .bind(server_id)
+            .bind(change.component_type)
+            .bind(&change.identifier)
+            .bind(change.change_type.as_str())
+            .bind(&change.old_data)
+            .bind(&change.new_data)
+            .execute(&mut **tx)
+            .await?;
         }
+
+        Ok(())
+    }
+
+    /// Record removed parts as missing and resolve open entries whose part is reported again;
+    /// runs in the update transaction, before the new snapshot is stored
+    async fn track_missing_components(
+        &self,
+        tx: &mut sqlx::Transaction<'_, DB>,
+        server_id: i32,
+        inventory: &ServerInventory,
+        changes: &[ComponentChange]
+    ) -> Result<(), sqlx::Error> {
+        let open: Vec<(i32, String, String, serde_json::Value)> = sqlx::query_as(r#"
+            SELECT missing_id, component_type, component_identifier, last_seen_data
+            FROM server_missing_components
+            WHERE server_id = ? AND resolved_at IS NULL
+        "#)
+        .bind(server_id)
+        .fetch_all(&mut **tx)
+        .await?;
+
+        let removed: Vec<&ComponentChange> = changes.iter()
+            .filter(|change| change.change_type == InventoryChangeType::Removed)
+            .filter(|change| MISSING_COMPONENT_TYPES.contains(&change.component_type))
+            .collect();
+        if open.is_empty() && removed.is_empty() {
+            return Ok(());
+        }
+
+        let (data, _) = inventory_snapshot(inventory)?;
+        let reported = reported_part_keys(&data);
+        let is_reported = |component_type: &str, keys: &HashSet<String>| {
+            reported.get(component_type).is_some_and(|reported| !reported.is_disjoint(keys))
+        };
+
+        for (missing_id, component_type, identifier, last_seen) in open {
+            if is_reported(&component_type, &part_keys(&identifier, &last_seen)) {
+                sqlx::query(&format!("UPDATE server_missing_components SET resolved_at = {} WHERE missing_id = ?", DB::CURRENT_TIMESTAMP))
+                    .bind(missing_id)
+                    .execute(&mut **tx)
+                    .await?;
+            }
+        }
+
+        if removed.is_empty() {
+            return Ok(());
+        }
+
+        // The previous report has the part's full entry; the change only holds the compared fields
+        let previous: Option<(serde_json::Value,)> = sqlx::query_as(
+            "SELECT inventory_data FROM server_inventory_snapshots WHERE server_id = ? ORDER BY snapshot_id DESC LIMIT 1"
+        )
+        .bind(server_id)
+        .fetch_optional(&mut **tx)
+        .await?;
+        let previous_parts = previous.as_ref().map(|(data,)| reported_parts(data)).unwrap_or_default();
+
+        for change in removed {
+            let last_seen = previous_parts.iter()
+                .find(|(component_type, identifier, _)| *component_type == change.component_type && *identifier == change.identifier)
+                .map(|(.., entry)| (*entry).clone())
+                .or_else(|| change.old_data.clone())
+                .unwrap_or_default();
+
+            // Moved rather than gone, e.g. a DIMM reseated in another slot
+            if is_reported(change.component_type, &part_keys(&change.identifier, &last_seen)) {
+                continue;
+            }
+
+            sqlx::query(r#"
+                INSERT INTO server_missing_components (server_id, component_type, component_identifier, last_seen_data)
+                VALUES (?, ?, ?, ?)
+            "#)
+            .bind(server_id)
+            .bind(change.component_type)
+            .bind(&change.identifier)
+            .bind(&last_seen)
+            .execute(&mut **tx)
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Record the accepted payload; runs in the create or update transaction
+    async fn insert_inventory_snapshot(
+        &self,
+        tx: &mut sqlx::Transaction<'_, DB>,
+        server_id: i32,
+        inventory: &ServerInventory
+    ) -> Result<(), sqlx::Error> {
+        let (data, checksum) = inventory_snapshot(inventory)?;
+        sqlx::query(r#"
+            INSERT INTO server_inventory_snapshots (server_id, agent_version, inventory_data, checksum)
+            VALUES (?, ?, ?, ?)
+        "#)
+        .bind(server_id)
+        .bind(&inventory.agent_version)
+        .bind(&data)
+        .bind(&checksum)
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Snapshots of a server, newest first, without their payloads
+    pub async fn get_inventory_snapshots(&self, server_id: i32, limit: i64) -> Result<Vec<InventorySnapshotSummary>, sqlx::Error> {
+        sqlx::query_as(r#"
+            SELECT snapshot_id, server_id, agent_version, checksum, created_at
+            FROM server_inventory_snapshots
+            WHERE server_id = ?
+            ORDER BY snapshot_id DESC
+            LIMIT ?
+        "#)
+        .bind(server_id)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    pub async fn get_inventory_snapshot(&self, server_id: i32, snapshot_id: i32) -> Result<Option<InventorySnapshot>, sqlx::Error> {
+        sqlx::query_as("SELECT * FROM server_inventory_snapshots WHERE server_id = ? AND snapshot_id = ?")
+            .bind(server_id)
+            .bind(snapshot_id)
+            .fetch_optional(&self.pool)
+            .await
     }
 
     // Helper methods for finding or creating component types
@@ -1273,7 +2114,7 @@ impl ServerRepository {
     /// This is used by both create and update operations
     async fn insert_server_components(
         &self,
-        tx: &mut sqlx::Transaction<'_, sqlx::MySql>,
+        tx: &mut sqlx::Transaction<'_, DB>,
         server_id: i32,
         inventory: &ServerInventory
     ) -> Result<(), sqlx::Error> {
@@ -1320,6 +2161,9 @@ impl ServerRepository {
 
         // 3. Add Memory DIMMs
         for dimm in &inventory.memory.dimms {
+            let Some(slot) = dimm_slot(dimm) else {
+                continue;
+            };
             let memory_type_id = self.find_or_create_memory_type(tx, dimm).await?;
             
             sqlx::query(r#"
@@ -1328,7 +2172,7 @@ impl ServerRepository {
             "#)
             .bind(server_id)
             .bind(memory_type_id)
-            .bind(&dimm.slot)
+            .bind(slot)
             .bind(&dimm.serial_number)
             .execute(&mut **tx)
             .await?;
@@ -1410,23 +2254,41 @@ impl ServerRepository {
             .await?;
         }
 
-        // 6. Add BMC interface if present
-        if inventory.node.bmc.mac_address.is_some() {
-            let bmc_release_date = inventory.node.bmc.release_date.as_ref()
+        // 6. Add power supplies; the agent merges several sources, so the same PSU can be listed twice
+        let mut seen = HashSet::new();
+        for psu in &inventory.power_supplies {
+            if power_supply_key(&psu.serial_number, &psu.name).is_some_and(|key| seen.insert(key)) {
+                self.insert_power_supply(tx, server_id, psu).await?;
+            }
+        }
+
+        // 7. Add routes
+        let mut seen = HashSet::new();
+        for route in &inventory.network.routes {
+            if seen.insert(route_key(&route.destination, &route.interface)) {
+                self.insert_network_route(tx, server_id, route).await?;
+            }
+        }
+
+        // 8. Add BMC interface if present
+        if let Some(bmc @ BmcInfo { mac_address: Some(mac), .. }) = &inventory.node.bmc {
+            let bmc_release_date = bmc.release_date.as_ref()
                 .and_then(|date_str| chrono::NaiveDate::parse_from_str(date_str, "%m/%d/%Y").ok());
 
+            let bmc_type_id = self.find_or_create_bmc_type(tx, &inventory.node).await?;
+
             sqlx::query(r#"
                 INSERT INTO server_bmc_interfaces (
-                    server_id, name, mac_address, ip_address, firmware_version,
-                    release_date, supports_ipmi, supports_redfish, supports_web_interface,
-                    is_accessible
+                    server_id, component_bmc_id, name, mac_address, ip_address,
+                    firmware_version, release_date, is_accessible
                 )
-                VALUES (?, 'bmc0', ?, ?, ?, ?, 1, 1, 1, 0)
+                VALUES (?, ?, 'bmc0', ?, ?, ?, ?, 0)
             "#)
             .bind(server_id)
-            .bind(&inventory.node.bmc.mac_address)
-            .bind(&inventory.node.bmc.ip_address)
-            .bind(&inventory.node.bmc.firmware_version)
+            .bind(bmc_type_id)
+            .bind(mac)
+            .bind(&bmc.ip_address)
+            .bind(&bmc.firmware_version)
             .bind(bmc_release_date)
             .execute(&mut **tx)
             .await?;
@@ -1437,7 +2299,7 @@ impl ServerRepository {
 
     async fn find_or_create_motherboard_type(
         &self,
-        tx: &mut sqlx::Transaction<'_, sqlx::MySql>,
+        tx: &mut sqlx::Transaction<'_, DB>,
         motherboard: &MotherboardInfo
     ) -> Result<Option<i32>, sqlx::Error> {
         if motherboard.manufacturer.is_none() && motherboard.product_name.is_none() {
@@ -1471,13 +2333,13 @@ impl ServerRepository {
         .execute(&mut **tx)
         .await?;
 
-        Ok(Some(result.last_insert_id() as i32))
+        Ok(Some(DB::last_insert_id(&result) as i32))
     }
 
     async fn find_or_create_cpu_type(
         &self,
-        tx: &mut sqlx::Transaction<'_, sqlx::MySql>,
-        cpu: &CpuDetail
+        tx: &mut sqlx::Transaction<'_, DB>,
+        cpu: &CpuSocket
     ) -> Result<i32, sqlx::Error> {
         // Try to find existing
         let existing: Option<(i32,)> = sqlx::query_as(r#"
@@ -1514,13 +2376,13 @@ impl ServerRepository {
         .execute(&mut **tx)
         .await?;
 
-        Ok(result.last_insert_id() as i32)
+        Ok(DB::last_insert_id(&result) as i32)
     }
 
     async fn find_or_create_memory_type(
         &self,
-        tx: &mut sqlx::Transaction<'_, sqlx::MySql>,
-        dimm: &DimmDetail
+        tx: &mut sqlx::Transaction<'_, DB>,
+        dimm: &DimmInfo
     ) -> Result<i32, sqlx::Error> {
         // Try to find existing by part number (most specific)
         if let Some(part_number) = &dimm.part_number {
@@ -1548,18 +2410,18 @@ impl ServerRepository {
         "#)
         .bind(&dimm.manufacturer)
         .bind(dimm.part_number.as_ref().map(|s| s.trim()))
-        .bind(dimm.size_bytes)
+        .bind(dimm.size_bytes.unwrap_or(0) as i64) // NOT NULL; 0 when SMBIOS gives no size
         .bind(&dimm.mem_type)
         .bind(dimm.speed_mt_s)
         .execute(&mut **tx)
         .await?;
 
-        Ok(result.last_insert_id() as i32)
+        Ok(DB::last_insert_id(&result) as i32)
     }
 
     async fn find_or_create_disk_type(
         &self,
-        tx: &mut sqlx::Transaction<'_, sqlx::MySql>,
+        tx: &mut sqlx::Transaction<'_, DB>,
         disk: &DiskInfo
     ) -> Result<i32, sqlx::Error> {
         // Try to find existing by model
@@ -1593,18 +2455,18 @@ impl ServerRepository {
         "#)
         .bind(manufacturer)
         .bind(&disk.model)
-        .bind(disk.size_bytes)
+        .bind(disk.size_bytes.map(|size| size as i64))
         .bind(&disk.bus_type)
         .execute(&mut **tx)
         .await?;
 
-        Ok(result.last_insert_id() as i32)
+        Ok(DB::last_insert_id(&result) as i32)
     }
 
     async fn find_or_create_network_type(
         &self,
-        tx: &mut sqlx::Transaction<'_, sqlx::MySql>,
-        iface: &NetworkInterface
+        tx: &mut sqlx::Transaction<'_, DB>,
+        iface: &NetInterface
     ) -> Result<i32, sqlx::Error> {
         // Try to find existing by vendor and device name
         if let (Some(vendor), Some(device)) = (&iface.vendor_name, &iface.device_name) {
@@ -1637,12 +2499,49 @@ impl ServerRepository {
         .execute(&mut **tx)
         .await?;
 
-        Ok(result.last_insert_id() as i32)
+        Ok(DB::last_insert_id(&result) as i32)
+    }
+
+    async fn find_or_create_bmc_type(
+        &self,
+        tx: &mut sqlx::Transaction<'_, DB>,
+        node: &NodeInfo
+    ) -> Result<i32, sqlx::Error> {
+        // The agent does not report a BMC model, so BMCs are typed by the server manufacturer
+        let vendor = node.manufacturer.as_deref().unwrap_or("Unknown");
+
+        // Try to find existing
+        let existing: Option<(i32,)> = sqlx::query_as(r#"
+            SELECT component_bmc_id
+            FROM component_bmc_types
+            WHERE vendor = ? AND model = ?
+            LIMIT 1
+        "#)
+        .bind(vendor)
+        .bind(GENERIC_BMC_MODEL)
+        .fetch_optional(&mut **tx)
+        .await?;
+
+        if let Some((id,)) = existing {
+            return Ok(id);
+        }
+
+        // Create new
+        let result = sqlx::query(r#"
+            INSERT INTO component_bmc_types (vendor, model)
+            VALUES (?, ?)
+        "#)
+        .bind(vendor)
+        .bind(GENERIC_BMC_MODEL)
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(DB::last_insert_id(&result) as i32)
     }
 
     async fn find_or_create_gpu_type(
         &self,
-        tx: &mut sqlx::Transaction<'_, sqlx::MySql>,
+        tx: &mut sqlx::Transaction<'_, DB>,
         gpu: &GpuInfo
     ) -> Result<i32, sqlx::Error> {
         // Try to find existing by vendor and model
@@ -1676,57 +2575,104 @@ impl ServerRepository {
         .execute(&mut **tx)
         .await?;
 
-        Ok(result.last_insert_id() as i32)
+        Ok(DB::last_insert_id(&result) as i32)
     }
 }
 
 #[async_trait]
-impl ServerRepo for ServerRepository {
-    async fn get_all_servers(&self, query: CommonPaginationQuery) -> Result<(Vec<Server>, i64), sqlx::Error> {
-        ServerRepository::get_all_servers(self, query).await
+impl<DB> ServerRepo for ServerRepository<DB>
+where
+    DB: SqlDialect,
+    for<'c> &'c mut DB::Connection: Executor<'c, Database = DB>,
+    for<'q> <DB as HasArguments<'q>>::Arguments: IntoArguments<'q, DB>,
+    for<'r> &'r str: ColumnIndex<DB::Row>,
+    usize: ColumnIndex<DB::Row>,
+    for<'q> &'q str: Encode<'q, DB> + Type<DB>,
+    for<'q> Option<&'q str>: Encode<'q, DB>,
+    for<'q> Option<&'q String>: Encode<'q, DB>,
+    for<'q, 'r> String: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+    for<'q> Option<String>: Encode<'q, DB>,
+    for<'q, 'r> i32: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+    for<'q> Option<i32>: Encode<'q, DB>,
+    for<'q, 'r> i64: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+    for<'q> Option<i64>: Encode<'q, DB>,
+    for<'q, 'r> u32: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+    for<'q> Option<u32>: Encode<'q, DB>,
+    for<'q, 'r> f32: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+    for<'q> Option<f32>: Encode<'q, DB>,
+    for<'q, 'r> f64: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+    for<'q, 'r> bool: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+    for<'q> i8: Encode<'q, DB> + Type<DB>,
+    for<'q, 'r> NaiveDate: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+    for<'q> Option<NaiveDate>: Encode<'q, DB>,
+    for<'q, 'r> NaiveDateTime: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+    for<'q, 'r> DateTime<Utc>: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+    for<'q, 'r> serde_json::Value: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+    for<'q> Option<serde_json::Value>: Encode<'q, DB>,
+{
+    async fn get_all_servers(&self, query: CommonPaginationQuery) -> Result<(Vec<Server>, Option<i64>), sqlx::Error> {
+        Self::get_all_servers(self, query).await
+    }
+
+    fn stream_servers(&self, query: CommonPaginationQuery) -> Result<RowStream<Server>, sqlx::Error> {
+        Self::stream_servers(self, query)
     }
 
     async fn get_by_id(&self, id: i64) -> Result<Option<Server>, sqlx::Error> {
-        ServerRepository::get_by_id(self, id).await
+        Self::get_by_id(self, id).await
     }
 
     async fn get_server_with_all_components(&self, server_id: i32) -> Result<Option<ServerWithAllComponents>, sqlx::Error> {
-        ServerRepository::get_server_with_all_components(self, server_id).await
+        Self::get_server_with_all_components(self, server_id).await
+    }
+
+    async fn get_server_bmc_interfaces(&self, server_id: i32) -> Result<Vec<ServerBmcDetail>, sqlx::Error> {
+        Self::get_server_bmc_interfaces(self, server_id).await
     }
 
     async fn get_overview_stats(&self) -> Result<serde_json::Value, sqlx::Error> {
-        ServerRepository::get_overview_stats(self).await
+        Self::get_overview_stats(self).await
     }
 
     async fn get_server_counts_by_type(&self) -> Result<Vec<(String, i64)>, sqlx::Error> {
-        ServerRepository::get_server_counts_by_type(self).await
+        Self::get_server_counts_by_type(self).await
     }
 
     async fn get_server_counts_by_status(&self) -> Result<Vec<(String, i64)>, sqlx::Error> {
-        ServerRepository::get_server_counts_by_status(self).await
+        Self::get_server_counts_by_status(self).await
     }
 
     async fn get_server_counts_by_environment(&self) -> Result<Vec<(String, i64)>, sqlx::Error> {
-        ServerRepository::get_server_counts_by_environment(self).await
+        Self::get_server_counts_by_environment(self).await
     }
 
     async fn update_server(&self, server_id: i32, updates: HashMap<String, serde_json::Value>) -> Result<bool, sqlx::Error> {
-        ServerRepository::update_server(self, server_id, updates).await
+        Self::update_server(self, server_id, updates).await
     }
 
     async fn check_server_exists_by_mac(&self, mac_address: &str) -> Result<Option<i32>, sqlx::Error> {
-        ServerRepository::check_server_exists_by_mac(self, mac_address).await
+        Self::check_server_exists_by_mac(self, mac_address).await
     }
 
     async fn create_server_from_inventory(&self, inventory: ServerInventory) -> Result<i32, sqlx::Error> {
-        ServerRepository::create_server_from_inventory(self, inventory).await
+        let identity = ServerIdentity::from_inventory(&inventory);
+        let decision = resolve_identity(self.find_identity_candidates(&identity).await?);
+        self.create_server_for_decision(inventory, &identity, &decision).await
+    }
+
+    async fn update_server_from_inventory(&self, server_id: i32, inventory: ServerInventory) -> Result<Vec<RemovedComponent>, sqlx::Error> {
+        Self::update_server_from_inventory(self, server_id, inventory).await
+    }
+
+    async fn upsert_server_from_inventory(&self, inventory: ServerInventory) -> Result<InventoryUpsert, sqlx::Error> {
+        Self::upsert_server_from_inventory(self, inventory).await
     }
 
-    async fn update_server_from_inventory(&self, server_id: i32, inventory: ServerInventory) -> Result<bool, sqlx::Error> {
-        ServerRepository::update_server_from_inventory(self, server_id, inventory).await
+    async fn get_inventory_snapshots(&self, server_id: i32, limit: i64) -> Result<Vec<InventorySnapshotSummary>, sqlx::Error> {
+        Self::get_inventory_snapshots(self, server_id, limit).await
     }
 
-    async fn upsert_server_from_inventory(&self, inventory: ServerInventory) -> Result<(i32, bool), sqlx::Error> {
-        ServerRepository::upsert_server_from_inventory(self, inventory).await
+    async fn get_inventory_snapshot(&self, server_id: i32, snapshot_id: i32) -> Result<Option<InventorySnapshot>, sqlx::Error> {
+        Self::get_inventory_snapshot(self, server_id, snapshot_id).await
     }
 }
\ No newline at end of file
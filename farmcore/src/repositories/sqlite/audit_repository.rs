@@ -0,0 +1,99 @@
+use sqlx::SqlitePool;
+use async_trait::async_trait;
+use crate::database::{QueryBuilderHelper, DatabaseHelper, RowStream};
+use crate::models::{AuditEvent, NewAuditEvent};
+use crate::api::query_parser::{CommonPaginationQuery, ParsedQuery, QueryParser};
+use crate::repositories::audit_repository::AuditRepo;
+
+/// SQLite implementation of `AuditRepo`
+#[derive(Clone)]
+pub struct SqliteAuditRepository {
+    pool: SqlitePool,
+}
+
+impl SqliteAuditRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    /// Get audit events with pagination and filtering, newest first
+    pub async fn get_all_events(&self, query: CommonPaginationQuery) -> Result<(Vec<AuditEvent>, Option<i64>), sqlx::Error> {
+        let ParsedQuery { where_conditions, options, .. } = QueryParser::parse_all(
+            &query,
+            &AuditEvent::SCHEMA,
+            Some("audit_id DESC".to_string())
+        ).map_err(|e| sqlx::Error::Protocol(format!("Query parsing error: {}", e)))?;
+
+        let total_count = if query.include_total() {
+            Some(QueryBuilderHelper::count(&self.pool, &AuditEvent::SCHEMA, &where_conditions).await?)
+        } else {
+            None
+        };
+
+        let events = QueryBuilderHelper::select(&self.pool, &AuditEvent::SCHEMA, options).await?;
+        Ok((events, total_count))
+    }
+
+    /// Stream all audit events matching the query, for exports
+    pub fn stream_events(&self, query: CommonPaginationQuery) -> Result<RowStream<AuditEvent>, sqlx::Error> {
+        let ParsedQuery { options, .. } = QueryParser::parse_all(
+            &query,
+            &AuditEvent::SCHEMA,
+            Some("audit_id DESC".to_string())
+        ).map_err(|e| sqlx::Error::Protocol(format!("Query parsing error: {}", e)))?;
+
+        QueryBuilderHelper::stream(self.pool.clone(), &AuditEvent::SCHEMA, QueryParser::export_options(options))
+    }
+
+    pub async fn get_event_by_id(&self, audit_id: i64) -> Result<Option<AuditEvent>, sqlx::Error> {
+        DatabaseHelper::get_by_id(&self.pool, AuditEvent::TABLE, AuditEvent::KEY, audit_id).await
+    }
+
+    pub async fn record_event(&self, event: NewAuditEvent) -> Result<i64, sqlx::Error> {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO audit_events (
+                token_id, principal_name, principal_scope, principal_role, client_ip,
+                http_method, route, request_path, resource, entity_id,
+                status_code, result, duration_ms, changes, actions
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(event.token_id)
+        .bind(&event.principal_name)
+        .bind(&event.principal_scope)
+        .bind(&event.principal_role)
+        .bind(&event.client_ip)
+        .bind(&event.http_method)
+        .bind(&event.route)
+        .bind(&event.request_path)
+        .bind(&event.resource)
+        .bind(&event.entity_id)
+        .bind(event.status_code)
+        .bind(if event.success { "SUCCESS" } else { "FAILURE" })
+        .bind(event.duration_ms)
+        .bind(&event.changes)
+        .bind(&event.actions)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.last_insert_rowid() as i64)
+    }
+}
+
+#[async_trait]
+impl AuditRepo for SqliteAuditRepository {
+    async fn get_all_events(&self, query: CommonPaginationQuery) -> Result<(Vec<AuditEvent>, Option<i64>), sqlx::Error> {
+        self.get_all_events(query).await
+    }
+
+    fn stream_events(&self, query: CommonPaginationQuery) -> Result<RowStream<AuditEvent>, sqlx::Error> {
+        Self::stream_events(self, query)
+    }
+    async fn get_event_by_id(&self, audit_id: i64) -> Result<Option<AuditEvent>, sqlx::Error> {
+        self.get_event_by_id(audit_id).await
+    }
+    async fn record_event(&self, event: NewAuditEvent) -> Result<i64, sqlx::Error> {
+        self.record_event(event).await
+    }
+}
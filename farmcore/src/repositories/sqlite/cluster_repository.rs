@@ -0,0 +1,7 @@
+use sqlx::Sqlite;
+
+use crate::repositories::cluster_repository::ClusterRepository;
+
+/// SQLite implementation of `ClusterRepo`: the same queries as on MySQL, with the
+/// backend differences taken from `SqlDialect`
+pub type SqliteClusterRepository = ClusterRepository<Sqlite>;
@@ -0,0 +1,7 @@
+use sqlx::Sqlite;
+
+use crate::repositories::component_repository::ComponentRepository;
+
+/// SQLite implementation of `ComponentRepo`: the same queries as on MySQL, with the
+/// backend differences taken from `SqlDialect`
+pub type SqliteComponentRepository = ComponentRepository<Sqlite>;
@@ -0,0 +1,7 @@
+use sqlx::Sqlite;
+
+use crate::repositories::datacenter_repository::DatacenterRepository;
+
+/// SQLite implementation of `DatacenterRepo`: the same queries as on MySQL, with the
+/// backend differences taken from `SqlDialect`
+pub type SqliteDatacenterRepository = DatacenterRepository<Sqlite>;
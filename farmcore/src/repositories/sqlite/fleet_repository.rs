@@ -0,0 +1,107 @@
+use sqlx::SqlitePool;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use crate::models::FleetStats;
+use crate::repositories::fleet_repository::FleetRepo;
+
+/// SQLite implementation of `FleetRepo`. There are no cluster, datacenter or rack tables
+/// on this backend, so location labels are empty and no rack capacity is reported.
+#[derive(Clone)]
+pub struct SqliteFleetRepository {
+    pool: SqlitePool,
+}
+
+impl SqliteFleetRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    /// Grouped fleet counts for the inventory gauges. Agents of ACTIVE servers that have
+    /// not reported since `stale_before` count as stale.
+    pub async fn get_fleet_stats(&self, stale_before: DateTime<Utc>) -> Result<FleetStats, sqlx::Error> {
+        let servers = sqlx::query_as(
+            r#"
+            SELECT status, COALESCE(server_type, '') AS server_type, '' AS cluster, '' AS datacenter,
+                   COUNT(*) AS count
+            FROM servers
+            GROUP BY status, server_type
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let stale_agents = sqlx::query_as(
+            r#"
+            SELECT '' AS cluster, '' AS datacenter, COUNT(*) AS count
+            FROM servers
+            WHERE status = 'ACTIVE' AND (last_inventory_at IS NULL OR datetime(last_inventory_at) < datetime(?))
+            HAVING COUNT(*) > 0
+            "#,
+        )
+        .bind(stale_before)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let gpus = sqlx::query_as(
+            r#"
+            SELECT t.vendor, t.model, COUNT(*) AS count
+            FROM server_gpus g
+            JOIN component_gpu_types t ON t.component_gpu_id = g.component_gpu_id
+            GROUP BY t.vendor, t.model
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let disks = sqlx::query_as(
+            r#"
+            SELECT COALESCE(smart_health, 'UNKNOWN') AS smart_health, COUNT(*) AS count
+            FROM server_disks
+            GROUP BY COALESCE(smart_health, 'UNKNOWN')
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let vms = sqlx::query_as(
+            r#"
+            SELECT hypervisor_type, vm_state, COUNT(*) AS count,
+                   COALESCE(SUM(vcpu_count), 0) AS vcpus,
+                   COALESCE(SUM(memory_mb), 0) AS memory_mb
+            FROM virtual_machines
+            GROUP BY hypervisor_type, vm_state
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let switches = sqlx::query_as(
+            r#"
+            SELECT COALESCE(status, '') AS status, COALESCE(switch_role, '') AS switch_role, COUNT(*) AS count
+            FROM switches
+            GROUP BY status, switch_role
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let switch_ports = sqlx::query_as(
+            r#"
+            SELECT COALESCE(oper_status, 'UNKNOWN') AS oper_status, COUNT(*) AS count
+            FROM switch_ports
+            GROUP BY COALESCE(oper_status, 'UNKNOWN')
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(FleetStats { servers, stale_agents, gpus, disks, racks: Vec::new(), vms, switches, switch_ports })
+    }
+}
+
+#[async_trait]
+impl FleetRepo for SqliteFleetRepository {
+    async fn get_fleet_stats(&self, stale_before: DateTime<Utc>) -> Result<FleetStats, sqlx::Error> {
+        self.get_fleet_stats(stale_before).await
+    }
+}
@@ -0,0 +1,49 @@
+use sqlx::SqlitePool;
+use async_trait::async_trait;
+use crate::database::{QueryBuilderHelper, RowStream};
+use crate::models::ServerIdentityConflict;
+use crate::api::query_parser::{CommonPaginationQuery, QueryParser};
+use crate::repositories::identity_conflict_repository::{parse_conflict_query, IdentityConflictRepo};
+
+/// SQLite implementation of `IdentityConflictRepo`
+#[derive(Clone)]
+pub struct SqliteIdentityConflictRepository {
+    pool: SqlitePool,
+}
+
+impl SqliteIdentityConflictRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    /// Get identity conflicts with pagination and filtering, newest first
+    pub async fn get_identity_conflicts(&self, query: CommonPaginationQuery, include_resolved: bool) -> Result<(Vec<ServerIdentityConflict>, Option<i64>), sqlx::Error> {
+        let (where_conditions, options) = parse_conflict_query(&query, include_resolved)?;
+
+        let total_count = if query.include_total() {
+            Some(QueryBuilderHelper::count(&self.pool, &ServerIdentityConflict::SCHEMA, &where_conditions).await?)
+        } else {
+            None
+        };
+
+        let conflicts = QueryBuilderHelper::select(&self.pool, &ServerIdentityConflict::SCHEMA, options).await?;
+        Ok((conflicts, total_count))
+    }
+
+    /// Stream all identity conflicts matching the query, for exports
+    pub fn stream_identity_conflicts(&self, query: CommonPaginationQuery, include_resolved: bool) -> Result<RowStream<ServerIdentityConflict>, sqlx::Error> {
+        let (_, options) = parse_conflict_query(&query, include_resolved)?;
+        QueryBuilderHelper::stream(self.pool.clone(), &ServerIdentityConflict::SCHEMA, QueryParser::export_options(options))
+    }
+}
+
+#[async_trait]
+impl IdentityConflictRepo for SqliteIdentityConflictRepository {
+    async fn get_identity_conflicts(&self, query: CommonPaginationQuery, include_resolved: bool) -> Result<(Vec<ServerIdentityConflict>, Option<i64>), sqlx::Error> {
+        self.get_identity_conflicts(query, include_resolved).await
+    }
+
+    fn stream_identity_conflicts(&self, query: CommonPaginationQuery, include_resolved: bool) -> Result<RowStream<ServerIdentityConflict>, sqlx::Error> {
+        Self::stream_identity_conflicts(self, query, include_resolved)
+    }
+}
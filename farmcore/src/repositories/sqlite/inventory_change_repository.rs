@@ -0,0 +1,58 @@
+use sqlx::SqlitePool;
+use async_trait::async_trait;
+use crate::database::{QueryBuilderHelper, RowStream};
+use crate::models::ServerInventoryChange;
+use crate::api::query_parser::{CommonPaginationQuery, ParsedQuery, QueryParser};
+use crate::repositories::inventory_change_repository::InventoryChangeRepo;
+
+/// SQLite implementation of `InventoryChangeRepo`
+#[derive(Clone)]
+pub struct SqliteInventoryChangeRepository {
+    pool: SqlitePool,
+}
+
+impl SqliteInventoryChangeRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    /// Get component changes with pagination and filtering, newest first
+    pub async fn get_all_changes(&self, query: CommonPaginationQuery) -> Result<(Vec<ServerInventoryChange>, Option<i64>), sqlx::Error> {
+        let ParsedQuery { where_conditions, options, .. } = QueryParser::parse_all(
+            &query,
+            &ServerInventoryChange::SCHEMA,
+            Some("change_id DESC".to_string())
+        ).map_err(|e| sqlx::Error::Protocol(format!("Query parsing error: {}", e)))?;
+
+        let total_count = if query.include_total() {
+            Some(QueryBuilderHelper::count(&self.pool, &ServerInventoryChange::SCHEMA, &where_conditions).await?)
+        } else {
+            None
+        };
+
+        let changes = QueryBuilderHelper::select(&self.pool, &ServerInventoryChange::SCHEMA, options).await?;
+        Ok((changes, total_count))
+    }
+
+    /// Stream all component changes matching the query, for exports
+    pub fn stream_changes(&self, query: CommonPaginationQuery) -> Result<RowStream<ServerInventoryChange>, sqlx::Error> {
+        let ParsedQuery { options, .. } = QueryParser::parse_all(
+            &query,
+            &ServerInventoryChange::SCHEMA,
+            Some("change_id DESC".to_string())
+        ).map_err(|e| sqlx::Error::Protocol(format!("Query parsing error: {}", e)))?;
+
+        QueryBuilderHelper::stream(self.pool.clone(), &ServerInventoryChange::SCHEMA, QueryParser::export_options(options))
+    }
+}
+
+#[async_trait]
+impl InventoryChangeRepo for SqliteInventoryChangeRepository {
+    async fn get_all_changes(&self, query: CommonPaginationQuery) -> Result<(Vec<ServerInventoryChange>, Option<i64>), sqlx::Error> {
+        self.get_all_changes(query).await
+    }
+
+    fn stream_changes(&self, query: CommonPaginationQuery) -> Result<RowStream<ServerInventoryChange>, sqlx::Error> {
+        Self::stream_changes(self, query)
+    }
+}
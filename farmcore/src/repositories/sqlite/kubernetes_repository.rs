@@ -0,0 +1,7 @@
+use sqlx::Sqlite;
+
+use crate::repositories::kubernetes_repository::KubernetesRepository;
+
+/// SQLite implementation of `K8sRepo`: the same queries as on MySQL, with the
+/// backend differences taken from `SqlDialect`
+pub type SqliteKubernetesRepository = KubernetesRepository<Sqlite>;
@@ -0,0 +1,49 @@
+use sqlx::SqlitePool;
+use async_trait::async_trait;
+use crate::database::{QueryBuilderHelper, RowStream};
+use crate::models::ServerMissingComponent;
+use crate::api::query_parser::{CommonPaginationQuery, QueryParser};
+use crate::repositories::missing_component_repository::{parse_missing_query, MissingComponentRepo};
+
+/// SQLite implementation of `MissingComponentRepo`
+#[derive(Clone)]
+pub struct SqliteMissingComponentRepository {
+    pool: SqlitePool,
+}
+
+impl SqliteMissingComponentRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    /// Get missing components with pagination and filtering, newest first
+    pub async fn get_missing_components(&self, query: CommonPaginationQuery, include_resolved: bool) -> Result<(Vec<ServerMissingComponent>, Option<i64>), sqlx::Error> {
+        let (where_conditions, options) = parse_missing_query(&query, include_resolved)?;
+
+        let total_count = if query.include_total() {
+            Some(QueryBuilderHelper::count(&self.pool, &ServerMissingComponent::SCHEMA, &where_conditions).await?)
+        } else {
+            None
+        };
+
+        let missing = QueryBuilderHelper::select(&self.pool, &ServerMissingComponent::SCHEMA, options).await?;
+        Ok((missing, total_count))
+    }
+
+    /// Stream all missing components matching the query, for exports
+    pub fn stream_missing_components(&self, query: CommonPaginationQuery, include_resolved: bool) -> Result<RowStream<ServerMissingComponent>, sqlx::Error> {
+        let (_, options) = parse_missing_query(&query, include_resolved)?;
+        QueryBuilderHelper::stream(self.pool.clone(), &ServerMissingComponent::SCHEMA, QueryParser::export_options(options))
+    }
+}
+
+#[async_trait]
+impl MissingComponentRepo for SqliteMissingComponentRepository {
+    async fn get_missing_components(&self, query: CommonPaginationQuery, include_resolved: bool) -> Result<(Vec<ServerMissingComponent>, Option<i64>), sqlx::Error> {
+        self.get_missing_components(query, include_resolved).await
+    }
+
+    fn stream_missing_components(&self, query: CommonPaginationQuery, include_resolved: bool) -> Result<RowStream<ServerMissingComponent>, sqlx::Error> {
+        Self::stream_missing_components(self, query, include_resolved)
+    }
+}
@@ -0,0 +1,34 @@
+//! SQLite implementations of the repository traits, used when `FARM_DATABASE_URL`
+//! points at an SQLite database. Every store is available on this backend; each alias is the
+//! generic repository instantiated for `Sqlite`.
+pub mod server_repository;
+pub mod component_repository;
+pub mod vm_repository;
+pub mod kubernetes_repository;
+pub mod datacenter_repository;
+pub mod cluster_repository;
+pub mod switch_repository;
+pub mod token_repository;
+pub mod audit_repository;
+pub mod webhook_repository;
+pub mod fleet_repository;
+pub mod inventory_change_repository;
+pub mod missing_component_repository;
+pub mod identity_conflict_repository;
+pub mod server_merge_repository;
+
+pub use server_repository::SqliteServerRepository;
+pub use component_repository::SqliteComponentRepository;
+pub use vm_repository::SqliteVmRepository;
+pub use kubernetes_repository::SqliteKubernetesRepository;
+pub use datacenter_repository::SqliteDatacenterRepository;
+pub use cluster_repository::SqliteClusterRepository;
+pub use switch_repository::SqliteSwitchRepository;
+pub use token_repository::SqliteTokenRepository;
+pub use audit_repository::SqliteAuditRepository;
+pub use webhook_repository::SqliteWebhookRepository;
+pub use fleet_repository::SqliteFleetRepository;
+pub use inventory_change_repository::SqliteInventoryChangeRepository;
+pub use missing_component_repository::SqliteMissingComponentRepository;
+pub use identity_conflict_repository::SqliteIdentityConflictRepository;
+pub use server_merge_repository::SqliteServerMergeRepository;
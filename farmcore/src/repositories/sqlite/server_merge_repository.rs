@@ -0,0 +1,231 @@
+use sqlx::{SqliteConnection, SqlitePool};
+use async_trait::async_trait;
+use serde_json::Value;
+
+use crate::domain::{find_duplicate_servers, DuplicateServers, RowChange};
+use crate::models::{Server, ServerMergeReport};
+use crate::repositories::server_merge_repository::{
+    build_fingerprints, check_mergeable, conflicts_naming, deleted_change, inherited_columns, merge_order,
+    merged_components_win, record_merge, FingerprintRow, ServerMergeRepo, COMPONENT_TABLES, FINGERPRINT_QUERY,
+    HISTORY_TABLES, LATEST_SNAPSHOT_QUERY,
+};
+
+/// SQLite implementation of `ServerMergeRepo`: the MySQL merge without row locks, which
+/// SQLite does not have; its write transaction already serializes merges.
+#[derive(Clone)]
+pub struct SqliteServerMergeRepository {
+    pool: SqlitePool,
+}
+
+impl SqliteServerMergeRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    /// Pairs of server records that probably describe the same machine, most likely first
+    pub async fn find_duplicate_servers(&self, min_confidence: i32) -> Result<Vec<DuplicateServers>, sqlx::Error> {
+        let servers: Vec<FingerprintRow> = sqlx::query_as(FINGERPRINT_QUERY)
+            .fetch_all(&self.pool)
+            .await?;
+        let nics: Vec<(i32, Option<String>)> = sqlx::query_as("SELECT server_id, mac_address FROM server_network_interfaces")
+            .fetch_all(&self.pool)
+            .await?;
+        let bmcs: Vec<(i32, Option<String>, Option<String>)> = sqlx::query_as(
+            "SELECT server_id, mac_address, ip_address FROM server_bmc_interfaces WHERE server_id IS NOT NULL ORDER BY bmc_interface_id"
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(find_duplicate_servers(&build_fingerprints(servers, nics, bmcs), min_confidence))
+    }
+
+    /// Fold `merged_ids` into `server_id` in a single transaction and delete them. Rolled
+    /// back on a dry run, so the report shows what a merge would do.
+    pub async fn merge_servers(&self, server_id: i32, merged_ids: Vec<i32>, dry_run: bool) -> Result<ServerMergeReport, sqlx::Error> {
+        let merged_ids = merge_order(server_id, merged_ids)?;
+        let mut report = ServerMergeReport {
+            server_id,
+            merged_server_ids: merged_ids.clone(),
+            dry_run,
+            ..Default::default()
+        };
+        let mut changes = Vec::new();
+
+        let mut tx = self.pool.begin().await?;
+        let mut survivor = Self::fetch_server(&mut tx, server_id).await?;
+        for &merged_id in &merged_ids {
+            let merged = Self::fetch_server(&mut tx, merged_id).await?;
+            check_mergeable(&survivor, &merged)?;
+            Self::merge_server(&mut tx, &survivor, &merged, &mut report, &mut changes).await?;
+            survivor = Self::fetch_server(&mut tx, server_id).await?;
+        }
+
+        let open: Vec<(i32, Value)> = sqlx::query_as(
+            "SELECT conflict_id, candidates FROM server_identity_conflicts WHERE resolved_at IS NULL"
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+        for conflict_id in conflicts_naming(open, &merged_ids) {
+            sqlx::query("UPDATE server_identity_conflicts SET resolved_at = CURRENT_TIMESTAMP WHERE conflict_id = ?")
+                .bind(conflict_id)
+                .execute(&mut *tx)
+                .await?;
+            report.resolved_conflicts += 1;
+        }
+
+        if dry_run {
+            tx.rollback().await?;
+        } else {
+            tx.commit().await?;
+            record_merge(&report, changes);
+        }
+        Ok(report)
+    }
+
+    async fn fetch_server(conn: &mut SqliteConnection, server_id: i32) -> Result<Server, sqlx::Error> {
+        sqlx::query_as("SELECT * FROM servers WHERE server_id = ?")
+            .bind(server_id)
+            .fetch_optional(conn)
+            .await?
+            .ok_or_else(|| sqlx::Error::Protocol(format!("Server {} not found", server_id)))
+    }
+
+    async fn merge_server(
+        conn: &mut SqliteConnection,
+        survivor: &Server,
+        merged: &Server,
+        report: &mut ServerMergeReport,
+        changes: &mut Vec<RowChange>
+    ) -> Result<(), sqlx::Error> {
+        let (server_id, merged_id) = (survivor.server_id, merged.server_id);
+
+        // VM names are unique per host; two VMs of the same name are not the same VM
+        let clashing: Vec<(String,)> = sqlx::query_as(r#"
+            SELECT m.vm_name FROM virtual_machines m
+            JOIN virtual_machines s ON s.server_id = ? AND s.vm_name = m.vm_name
+            WHERE m.server_id = ?
+        "#)
+        .bind(server_id)
+        .bind(merged_id)
+        .fetch_all(&mut *conn)
+        .await?;
+        if !clashing.is_empty() {
+            let names: Vec<String> = clashing.into_iter().map(|(name,)| name).collect();
+            return Err(sqlx::Error::Protocol(format!(
+                "Server {} cannot be merged into server {}: virtual machines {} exist on both",
+                merged_id, server_id, names.join(", ")
+            )));
+        }
+
+        let (survivor_snapshot,): (Option<i64>,) = sqlx::query_as(LATEST_SNAPSHOT_QUERY)
+            .bind(server_id)
+            .fetch_one(&mut *conn)
+            .await?;
+        let (merged_snapshot,): (Option<i64>,) = sqlx::query_as(LATEST_SNAPSHOT_QUERY)
+            .bind(merged_id)
+            .fetch_one(&mut *conn)
+            .await?;
+        for &table in COMPONENT_TABLES {
+            let (own,): (i64,) = sqlx::query_as(&format!("SELECT COUNT(*) FROM {} WHERE server_id = ?", table))
+                .bind(server_id)
+                .fetch_one(&mut *conn)
+                .await?;
+            let discarded_id = if merged_components_win(survivor_snapshot, merged_snapshot, own) { server_id } else { merged_id };
+            let result = sqlx::query(&format!("DELETE FROM {} WHERE server_id = ?", table))
+                .bind(discarded_id)
+                .execute(&mut *conn)
+                .await?;
+            report.count_discarded(table, result.rows_affected());
+            let moved = Self::reassign(&mut *conn, table, "server_id", server_id, merged_id).await?;
+            report.count_moved(table, moved);
+        }
+
+        // Credentials are entered by hand: keep both sides, the surviving server's on a clash
+        let result = sqlx::query(r#"
+            DELETE FROM server_credentials
+            WHERE server_id = ? AND EXISTS (
+                SELECT 1 FROM server_credentials s
+                WHERE s.server_id = ? AND s.credential_type = server_credentials.credential_type AND s.username = server_credentials.username
+            )
+        "#)
+        .bind(merged_id)
+        .bind(server_id)
+        .execute(&mut *conn)
+        .await?;
+        report.count_discarded("server_credentials", result.rows_affected());
+        let moved = Self::reassign(&mut *conn, "server_credentials", "server_id", server_id, merged_id).await?;
+        report.count_moved("server_credentials", moved);
+
+        for (table, column) in [
+            ("virtual_machines", "server_id"),
+            ("vm_migrations", "source_server_id"),
+            ("vm_migrations", "target_server_id"),
+            ("kubernetes_nodes", "server_id"),
+        ]
+        .into_iter()
+        .chain(HISTORY_TABLES.iter().map(|&table| (table, "server_id")))
+        {
+            let moved = Self::reassign(&mut *conn, table, column, server_id, merged_id).await?;
+            report.count_moved(table, moved);
+        }
+
+        // A server only occupies one set of rack units; free the merged server's if it has its own
+        let (own_positions,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM datacenter_rack_positions WHERE server_id = ?")
+            .bind(server_id)
+            .fetch_one(&mut *conn)
+            .await?;
+        if own_positions > 0 {
+            let result = sqlx::query(
+                "UPDATE datacenter_rack_positions SET server_id = NULL, device_type = NULL, status = 'AVAILABLE' WHERE server_id = ?"
+            )
+            .bind(merged_id)
+            .execute(&mut *conn)
+            .await?;
+            report.count_discarded("datacenter_rack_positions", result.rows_affected());
+        } else {
+            let moved = Self::reassign(&mut *conn, "datacenter_rack_positions", "server_id", server_id, merged_id).await?;
+            report.count_moved("datacenter_rack_positions", moved);
+        }
+
+        let inherited = inherited_columns(survivor, merged);
+        if !inherited.is_empty() {
+            let sql = inherited.update_sql();
+            let mut query = sqlx::query(&sql);
+            for (_, value) in &inherited.text {
+                query = query.bind(value);
+            }
+            for (_, value) in &inherited.integer {
+                query = query.bind(value);
+            }
+            query.bind(server_id).execute(&mut *conn).await?;
+            changes.push(inherited.change(survivor));
+        }
+
+        sqlx::query("DELETE FROM servers WHERE server_id = ?")
+            .bind(merged_id)
+            .execute(&mut *conn)
+            .await?;
+        changes.push(deleted_change(merged));
+        Ok(())
+    }
+
+    async fn reassign(conn: &mut SqliteConnection, table: &str, column: &str, server_id: i32, merged_id: i32) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query(&format!("UPDATE {} SET {} = ? WHERE {} = ?", table, column, column))
+            .bind(server_id)
+            .bind(merged_id)
+            .execute(conn)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
+
+#[async_trait]
+impl ServerMergeRepo for SqliteServerMergeRepository {
+    async fn find_duplicate_servers(&self, min_confidence: i32) -> Result<Vec<DuplicateServers>, sqlx::Error> {
+        self.find_duplicate_servers(min_confidence).await
+    }
+
+    async fn merge_servers(&self, server_id: i32, merged_ids: Vec<i32>, dry_run: bool) -> Result<ServerMergeReport, sqlx::Error> {
+        self.merge_servers(server_id, merged_ids, dry_run).await
+    }
+}
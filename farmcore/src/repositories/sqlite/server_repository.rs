@@ -0,0 +1,7 @@
+use sqlx::Sqlite;
+
+use crate::repositories::server_repository::ServerRepository;
+
+/// SQLite implementation of `ServerRepo`: the same queries as on MySQL, with the
+/// backend differences taken from `SqlDialect`
+pub type SqliteServerRepository = ServerRepository<Sqlite>;
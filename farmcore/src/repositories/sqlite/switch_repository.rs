@@ -0,0 +1,7 @@
+use sqlx::Sqlite;
+
+use crate::repositories::switch_repository::SwitchRepository;
+
+/// SQLite implementation of `SwitchRepo`: the same queries as on MySQL, with the
+/// backend differences taken from `SqlDialect`
+pub type SqliteSwitchRepository = SwitchRepository<Sqlite>;
@@ -1,14 +1,17 @@
-use sqlx::MySqlPool;
+use sqlx::database::HasArguments;
+use sqlx::{ColumnIndex, Decode, Encode, Executor, IntoArguments, MySql, Pool, Type};
 use async_trait::async_trait;
 use std::collections::HashMap;
-use crate::database::{QueryBuilderHelper, DatabaseHelper};
-use crate::models::{Switch, SwitchPort, SwitchVlan, SwitchWithPorts, QueryOptions};
-use crate::api::query_parser::{CommonPaginationQuery, QueryParser};
+use chrono::{DateTime, NaiveDateTime, Utc};
+use crate::database::{QueryBuilderHelper, DatabaseHelper, DecimalColumn, RowStream, SqlDialect};
+use crate::models::{Switch, SwitchPort, SwitchVlan, SwitchWithPorts};
+use crate::api::query_parser::{CommonPaginationQuery, ParsedQuery, QueryParser};
 
 #[async_trait]
 pub trait SwitchRepo: Send + Sync {
     // Switch CRUD
-    async fn get_all_switches(&self, query: CommonPaginationQuery) -> Result<Vec<Switch>, sqlx::Error>;
+    async fn get_all_switches(&self, query: CommonPaginationQuery) -> Result<(Vec<Switch>, Option<i64>), sqlx::Error>;
+    fn stream_switches(&self, query: CommonPaginationQuery) -> Result<RowStream<Switch>, sqlx::Error>;
     async fn get_switch_by_id(&self, switch_id: i32) -> Result<Option<Switch>, sqlx::Error>;
     async fn get_switch_with_ports(&self, switch_id: i32) -> Result<Option<SwitchWithPorts>, sqlx::Error>;
     async fn create_switch(&self, switch: Switch) -> Result<i32, sqlx::Error>;
@@ -32,13 +35,47 @@ pub trait SwitchRepo: Send + Sync {
     async fn get_all_switches_stats(&self) -> Result<serde_json::Value, sqlx::Error>;
 }
 
-#[derive(Clone)]
-pub struct SwitchRepository {
-    pool: MySqlPool,
+/// `SwitchRepo` on any storage backend; `SqliteSwitchRepository` is this on SQLite
+pub struct SwitchRepository<DB: sqlx::Database = MySql> {
+    pool: Pool<DB>,
 }
 
-impl SwitchRepository {
-    pub fn new(pool: MySqlPool) -> Self {
+impl<DB: sqlx::Database> Clone for SwitchRepository<DB> {
+    fn clone(&self) -> Self {
+        Self { pool: self.pool.clone() }
+    }
+}
+
+impl<DB> SwitchRepository<DB>
+where
+    DB: SqlDialect,
+    for<'c> &'c mut DB::Connection: Executor<'c, Database = DB>,
+    for<'q> <DB as HasArguments<'q>>::Arguments: IntoArguments<'q, DB>,
+    for<'r> &'r str: ColumnIndex<DB::Row>,
+    usize: ColumnIndex<DB::Row>,
+    for<'q> &'q str: Encode<'q, DB> + Type<DB>,
+    for<'q> Option<&'q str>: Encode<'q, DB>,
+    for<'q> Option<&'q String>: Encode<'q, DB>,
+    for<'q, 'r> String: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+    for<'q> Option<String>: Encode<'q, DB>,
+    for<'q, 'r> i32: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+    for<'q> Option<i32>: Encode<'q, DB>,
+    for<'q, 'r> i64: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+    for<'q> Option<i64>: Encode<'q, DB>,
+    for<'q, 'r> f64: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+    for<'q> Option<f64>: Encode<'q, DB>,
+    for<'q, 'r> bool: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+    for<'q> Option<bool>: Encode<'q, DB>,
+    for<'q> i8: Encode<'q, DB> + Type<DB>,
+    for<'q, 'r> NaiveDateTime: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+    for<'q> Option<NaiveDateTime>: Encode<'q, DB>,
+    for<'q, 'r> DateTime<Utc>: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+    for<'q> Option<DateTime<Utc>>: Encode<'q, DB>,
+    for<'q, 'r> serde_json::Value: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+    for<'q> Option<serde_json::Value>: Encode<'q, DB>,
+    for<'q, 'r> DecimalColumn: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+{
+    pub fn new(pool: Pool<DB>) -> Self {
         Self { pool }
     }
 
@@ -46,22 +83,33 @@ impl SwitchRepository {
     // SWITCH OPERATIONS
     // ===================================================================
 
-    pub async fn get_all_switches(&self, query: CommonPaginationQuery) -> Result<Vec<Switch>, sqlx::Error> {
-        let (_, per_page, offset, columns, where_conditions, _) = QueryParser::parse_all(
+    pub async fn get_all_switches(&self, query: CommonPaginationQuery) -> Result<(Vec<Switch>, Option<i64>), sqlx::Error> {
+        let ParsedQuery { where_conditions, options, .. } = QueryParser::parse_all(
             &query,
+            &Switch::SCHEMA,
             Some("switch_id ASC".to_string()),
         )
         .map_err(|e| sqlx::Error::Protocol(format!("Query parsing error: {}", e)))?;
 
-        let options = QueryOptions {
-            columns,
-            where_conditions,
-            order_by: Some("switch_id ASC".to_string()),
-            limit: Some(per_page),
-            offset: Some(offset),
+        let total_count = if query.include_total() {
+            Some(QueryBuilderHelper::count(&self.pool, &Switch::SCHEMA, &where_conditions).await?)
+        } else {
+            None
         };
 
-        QueryBuilderHelper::select(&self.pool, Switch::TABLE, options).await
+        let switches = QueryBuilderHelper::select(&self.pool, &Switch::SCHEMA, options).await?;
+        Ok((switches, total_count))
+    }
+
+    /// Stream all switches matching the query, for exports
+    pub fn stream_switches(&self, query: CommonPaginationQuery) -> Result<RowStream<Switch>, sqlx::Error> {
+        let ParsedQuery { options, .. } = QueryParser::parse_all(
+            &query,
+            &Switch::SCHEMA,
+            Some("switch_id ASC".to_string())
+        ).map_err(|e| sqlx::Error::Protocol(format!("Query parsing error: {}", e)))?;
+
+        QueryBuilderHelper::stream(self.pool.clone(), &Switch::SCHEMA, QueryParser::export_options(options))
     }
 
     pub async fn get_switch_by_id(&self, switch_id: i32) -> Result<Option<Switch>, sqlx::Error> {
@@ -83,6 +131,14 @@ impl SwitchRepository {
     }
 
     pub async fn create_switch(&self, switch: Switch) -> Result<i32, sqlx::Error> {
+        Self::insert_switch(&self.pool, &switch).await
+    }
+
+    /// Insert a switch through any executor, so bulk imports can share the transaction
+    pub(crate) async fn insert_switch<'e, E>(executor: E, switch: &Switch) -> Result<i32, sqlx::Error>
+    where
+        E: Executor<'e, Database = DB>,
+    {
         let query = r#"
             INSERT INTO switches (
                 switch_name, component_switch_id, serial_number, asset_tag,
@@ -116,10 +172,10 @@ impl SwitchRepository {
             .bind(switch.poll_interval_seconds)
             .bind(&switch.auth_method)
             .bind(&switch.snmp_version)
-            .execute(&self.pool)
+            .execute(executor)
             .await?;
 
-        Ok(result.last_insert_id() as i32)
+        Ok(DB::last_insert_id(&result) as i32)
     }
 
     pub async fn update_switch(
@@ -153,7 +209,7 @@ impl SwitchRepository {
             .bind(switch_id)
             .execute(&self.pool)
             .await?;
-        Ok(result.rows_affected() > 0)
+        Ok(DB::rows_affected(&result) > 0)
     }
 
     // ===================================================================
@@ -200,7 +256,7 @@ impl SwitchRepository {
             .execute(&self.pool)
             .await?;
 
-        Ok(result.last_insert_id() as i32)
+        Ok(DB::last_insert_id(&result) as i32)
     }
 
     pub async fn update_port(
@@ -225,7 +281,7 @@ impl SwitchRepository {
             .bind(port_id)
             .execute(&self.pool)
             .await?;
-        Ok(result.rows_affected() > 0)
+        Ok(DB::rows_affected(&result) > 0)
     }
 
     // ===================================================================
@@ -242,6 +298,13 @@ impl SwitchRepository {
     }
 
     pub async fn create_vlan(&self, vlan: SwitchVlan) -> Result<i32, sqlx::Error> {
+        Self::insert_vlan(&self.pool, &vlan).await
+    }
+
+    pub(crate) async fn insert_vlan<'e, E>(executor: E, vlan: &SwitchVlan) -> Result<i32, sqlx::Error>
+    where
+        E: Executor<'e, Database = DB>,
+    {
         let result = sqlx::query(
             "INSERT INTO switch_vlans (switch_id, vlan_id, vlan_name, vlan_status) VALUES (?, ?, ?, ?)",
         )
@@ -249,10 +312,10 @@ impl SwitchRepository {
         .bind(vlan.vlan_id)
         .bind(&vlan.vlan_name)
         .bind(&vlan.vlan_status)
-        .execute(&self.pool)
+        .execute(executor)
         .await?;
 
-        Ok(result.last_insert_id() as i32)
+        Ok(DB::last_insert_id(&result) as i32)
     }
 
     pub async fn delete_vlan(&self, vlan_db_id: i32) -> Result<bool, sqlx::Error> {
@@ -260,7 +323,7 @@ impl SwitchRepository {
             .bind(vlan_db_id)
             .execute(&self.pool)
             .await?;
-        Ok(result.rows_affected() > 0)
+        Ok(DB::rows_affected(&result) > 0)
     }
 
     // ===================================================================
@@ -349,10 +412,42 @@ impl SwitchRepository {
 }
 
 #[async_trait]
-impl SwitchRepo for SwitchRepository {
-    async fn get_all_switches(&self, query: CommonPaginationQuery) -> Result<Vec<Switch>, sqlx::Error> {
+impl<DB> SwitchRepo for SwitchRepository<DB>
+where
+    DB: SqlDialect,
+    for<'c> &'c mut DB::Connection: Executor<'c, Database = DB>,
+    for<'q> <DB as HasArguments<'q>>::Arguments: IntoArguments<'q, DB>,
+    for<'r> &'r str: ColumnIndex<DB::Row>,
+    usize: ColumnIndex<DB::Row>,
+    for<'q> &'q str: Encode<'q, DB> + Type<DB>,
+    for<'q> Option<&'q str>: Encode<'q, DB>,
+    for<'q> Option<&'q String>: Encode<'q, DB>,
+    for<'q, 'r> String: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+    for<'q> Option<String>: Encode<'q, DB>,
+    for<'q, 'r> i32: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+    for<'q> Option<i32>: Encode<'q, DB>,
+    for<'q, 'r> i64: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+    for<'q> Option<i64>: Encode<'q, DB>,
+    for<'q, 'r> f64: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+    for<'q> Option<f64>: Encode<'q, DB>,
+    for<'q, 'r> bool: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+    for<'q> Option<bool>: Encode<'q, DB>,
+    for<'q> i8: Encode<'q, DB> + Type<DB>,
+    for<'q, 'r> NaiveDateTime: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+    for<'q> Option<NaiveDateTime>: Encode<'q, DB>,
+    for<'q, 'r> DateTime<Utc>: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+    for<'q> Option<DateTime<Utc>>: Encode<'q, DB>,
+    for<'q, 'r> serde_json::Value: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+    for<'q> Option<serde_json::Value>: Encode<'q, DB>,
+    for<'q, 'r> DecimalColumn: Encode<'q, DB> + Decode<'r, DB> + Type<DB>,
+{
+    async fn get_all_switches(&self, query: CommonPaginationQuery) -> Result<(Vec<Switch>, Option<i64>), sqlx::Error> {
         self.get_all_switches(query).await
     }
+
+    fn stream_switches(&self, query: CommonPaginationQuery) -> Result<RowStream<Switch>, sqlx::Error> {
+        Self::stream_switches(self, query)
+    }
     async fn get_switch_by_id(&self, switch_id: i32) -> Result<Option<Switch>, sqlx::Error> {
         self.get_switch_by_id(switch_id).await
     }
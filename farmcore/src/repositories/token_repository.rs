@@ -0,0 +1,158 @@
+use sqlx::MySqlPool;
+use async_trait::async_trait;
+use sha2::{Digest, Sha256};
+use crate::database::DatabaseHelper;
+use crate::models::{ApiToken, CreateApiToken, IssuedApiToken, Role, TokenScope};
+
+/// Prefix on every issued token so they are easy to recognise in configs and logs
+const TOKEN_PREFIX: &str = "farm_";
+
+#[async_trait]
+pub trait TokenRepo: Send + Sync {
+    async fn get_all_tokens(&self) -> Result<Vec<ApiToken>, sqlx::Error>;
+    async fn get_token_by_id(&self, token_id: i32) -> Result<Option<ApiToken>, sqlx::Error>;
+    async fn find_active_token(&self, token: &str) -> Result<Option<ApiToken>, sqlx::Error>;
+    async fn create_token(&self, request: CreateApiToken) -> Result<IssuedApiToken, sqlx::Error>;
+    async fn revoke_token(&self, token_id: i32) -> Result<bool, sqlx::Error>;
+    async fn touch_token(&self, token_id: i32) -> Result<(), sqlx::Error>;
+}
+
+#[derive(Clone)]
+pub struct TokenRepository {
+    pool: MySqlPool,
+}
+
+impl TokenRepository {
+    pub fn new(pool: MySqlPool) -> Self {
+        Self { pool }
+    }
+
+    /// SHA-256 hex digest of a plaintext token; this is what gets stored and compared
+    pub fn hash_token(token: &str) -> String {
+        let digest = Sha256::digest(token.as_bytes());
+        digest.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    /// Generate a new random plaintext token (two v4 UUIDs = 244 random bits)
+    pub(crate) fn generate_token() -> String {
+        format!(
+            "{}{}{}",
+            TOKEN_PREFIX,
+            uuid::Uuid::new_v4().simple(),
+            uuid::Uuid::new_v4().simple()
+        )
+    }
+
+    pub async fn get_all_tokens(&self) -> Result<Vec<ApiToken>, sqlx::Error> {
+        sqlx::query_as(
+            r#"
+            SELECT token_id, token_name, token_prefix, description, scope, role,
+                   expires_at, last_used_at, revoked_at, created_at, updated_at
+            FROM api_tokens
+            ORDER BY token_id
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    pub async fn get_token_by_id(&self, token_id: i32) -> Result<Option<ApiToken>, sqlx::Error> {
+        DatabaseHelper::get_by_id(&self.pool, ApiToken::TABLE, ApiToken::KEY, token_id as i64).await
+    }
+
+    /// Look up a presented plaintext token; only returns tokens that are neither revoked nor expired
+    pub async fn find_active_token(&self, token: &str) -> Result<Option<ApiToken>, sqlx::Error> {
+        sqlx::query_as(
+            r#"
+            SELECT token_id, token_name, token_prefix, description, scope, role,
+                   expires_at, last_used_at, revoked_at, created_at, updated_at
+            FROM api_tokens
+            WHERE token_hash = ?
+              AND revoked_at IS NULL
+              AND (expires_at IS NULL OR expires_at > NOW())
+            LIMIT 1
+            "#,
+        )
+        .bind(Self::hash_token(token))
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    /// Issue a new token. The plaintext is returned to the caller and never persisted.
+    pub async fn create_token(&self, request: CreateApiToken) -> Result<IssuedApiToken, sqlx::Error> {
+        let token = Self::generate_token();
+        let token_prefix: String = token.chars().take(13).collect();
+        // Roles only mean something for API tokens; AGENT tokens are stored without one
+        let role = match request.scope {
+            TokenScope::Api => Some(request.role.unwrap_or(Role::Viewer)),
+            TokenScope::Agent => None,
+        };
+
+        let result = sqlx::query(
+            r#"
+            INSERT INTO api_tokens (
+                token_name, token_prefix, token_hash, description, scope, role, expires_at
+            ) VALUES (?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(&request.token_name)
+        .bind(&token_prefix)
+        .bind(Self::hash_token(&token))
+        .bind(&request.description)
+        .bind(request.scope.as_str())
+        .bind(role.map(|role| role.as_str()))
+        .bind(request.expires_at)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(IssuedApiToken {
+            token_id: result.last_insert_id() as i32,
+            token_name: request.token_name,
+            scope: request.scope,
+            role,
+            token,
+        })
+    }
+
+    /// Soft-revoke a token so it stays visible in listings
+    pub async fn revoke_token(&self, token_id: i32) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            "UPDATE api_tokens SET revoked_at = NOW() WHERE token_id = ? AND revoked_at IS NULL",
+        )
+        .bind(token_id)
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Record that a token was just used
+    pub async fn touch_token(&self, token_id: i32) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE api_tokens SET last_used_at = NOW() WHERE token_id = ?")
+            .bind(token_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl TokenRepo for TokenRepository {
+    async fn get_all_tokens(&self) -> Result<Vec<ApiToken>, sqlx::Error> {
+        self.get_all_tokens().await
+    }
+    async fn get_token_by_id(&self, token_id: i32) -> Result<Option<ApiToken>, sqlx::Error> {
+        self.get_token_by_id(token_id).await
+    }
+    async fn find_active_token(&self, token: &str) -> Result<Option<ApiToken>, sqlx::Error> {
+        self.find_active_token(token).await
+    }
+    async fn create_token(&self, request: CreateApiToken) -> Result<IssuedApiToken, sqlx::Error> {
+        self.create_token(request).await
+    }
+    async fn revoke_token(&self, token_id: i32) -> Result<bool, sqlx::Error> {
+        self.revoke_token(token_id).await
+    }
+    async fn touch_token(&self, token_id: i32) -> Result<(), sqlx::Error> {
+        self.touch_token(token_id).await
+    }
+}
@@ -1,56 +1,19 @@
 use sqlx::MySqlPool;
 use async_trait::async_trait;
 use std::collections::HashMap;
-use crate::database::{QueryBuilderHelper, DatabaseHelper};
+use crate::database::{QueryBuilderHelper, DatabaseHelper, RowStream};
 use crate::models::{
-    VirtualMachine, VmWithAllComponents, VmDisk, VmNetworkInterface, VmSnapshot, QueryOptions
+    VirtualMachine, VmWithAllComponents, VmDisk, VmNetworkInterface, VmSnapshot
 };
-use crate::api::query_parser::{CommonPaginationQuery, QueryParser};
-
-// Inventory data structures matching the JSON format from farmmanager
-#[derive(Debug, serde::Deserialize)]
-pub struct VmInventory {
-    pub host_mac_address: String,
-    pub hypervisor_type: String,
-    pub vms: Vec<VmInventoryDetail>,
-}
-
-#[derive(Debug, serde::Deserialize)]
-pub struct VmInventoryDetail {
-    pub vm_name: String,
-    pub vm_uuid: Option<String>,
-    pub vm_state: Option<String>,
-    pub hypervisor_type: String,
-    pub vcpu_count: Option<i32>,
-    pub memory_mb: Option<i32>,
-    pub guest_os_family: Option<String>,
-    pub disks: Vec<VmDiskInventory>,
-    pub network_interfaces: Vec<VmNetworkInventory>,
-}
-
-#[derive(Debug, serde::Deserialize)]
-pub struct VmDiskInventory {
-    pub disk_name: String,
-    pub disk_type: Option<String>,
-    pub disk_format: Option<String>,
-    pub disk_size_gb: Option<i32>,
-    pub disk_path: String,
-    pub is_bootable: Option<bool>,
-    pub storage_type: Option<String>,
-}
-
-#[derive(Debug, serde::Deserialize)]
-pub struct VmNetworkInventory {
-    pub interface_name: String,
-    pub mac_address: Option<String>,
-    pub interface_type: Option<String>,
-    pub network_bridge: Option<String>,
-}
+use crate::api::query_parser::{CommonPaginationQuery, ParsedQuery, QueryParser};
 
+// Inventory payloads are defined in farm-proto, shared with the agent
+pub use farm_proto::vm::*;
 
 #[async_trait]
 pub trait VmRepo: Send + Sync {
-    async fn get_all_vms(&self, query: CommonPaginationQuery) -> Result<Vec<VirtualMachine>, sqlx::Error>;
+    async fn get_all_vms(&self, query: CommonPaginationQuery) -> Result<(Vec<VirtualMachine>, Option<i64>), sqlx::Error>;
+    fn stream_vms(&self, query: CommonPaginationQuery) -> Result<RowStream<VirtualMachine>, sqlx::Error>;
     async fn get_by_id(&self, id: i64) -> Result<Option<VirtualMachine>, sqlx::Error>;
     async fn get_vms_by_server_id(&self, server_id: i32) -> Result<Vec<VirtualMachine>, sqlx::Error>;
     async fn get_vm_with_all_components(&self, vm_id: i32) -> Result<Option<VmWithAllComponents>, sqlx::Error>;
@@ -78,21 +41,32 @@ impl VmRepository {
     }
 
     /// Get all VMs with pagination
-    pub async fn get_all_vms(&self, query: CommonPaginationQuery) -> Result<Vec<VirtualMachine>, sqlx::Error> {
-        let (_, per_page, offset, columns, where_conditions, _) = QueryParser::parse_all(
+    pub async fn get_all_vms(&self, query: CommonPaginationQuery) -> Result<(Vec<VirtualMachine>, Option<i64>), sqlx::Error> {
+        let ParsedQuery { where_conditions, options, .. } = QueryParser::parse_all(
             &query,
+            &VirtualMachine::SCHEMA,
             Some("vm_id DESC".to_string())
         ).map_err(|e| sqlx::Error::Protocol(format!("Query parsing error: {}", e)))?;
 
-        let options = QueryOptions {
-            columns,
-            where_conditions,
-            limit: Some(per_page),
-            offset: Some(offset),
-            order_by: Some("vm_id DESC".to_string()),
+        let total_count = if query.include_total() {
+            Some(QueryBuilderHelper::count(&self.pool, &VirtualMachine::SCHEMA, &where_conditions).await?)
+        } else {
+            None
         };
 
-        QueryBuilderHelper::select(&self.pool, VirtualMachine::TABLE, options).await
+        let vms = QueryBuilderHelper::select(&self.pool, &VirtualMachine::SCHEMA, options).await?;
+        Ok((vms, total_count))
+    }
+
+    /// Stream all VMs matching the query, for exports
+    pub fn stream_vms(&self, query: CommonPaginationQuery) -> Result<RowStream<VirtualMachine>, sqlx::Error> {
+        let ParsedQuery { options, .. } = QueryParser::parse_all(
+            &query,
+            &VirtualMachine::SCHEMA,
+            Some("vm_id DESC".to_string())
+        ).map_err(|e| sqlx::Error::Protocol(format!("Query parsing error: {}", e)))?;
+
+        QueryBuilderHelper::stream(self.pool.clone(), &VirtualMachine::SCHEMA, QueryParser::export_options(options))
     }
 
     /// Get single VM by ID
@@ -545,10 +519,14 @@ impl VmRepository {
 
 #[async_trait]
 impl VmRepo for VmRepository {
-    async fn get_all_vms(&self, query: CommonPaginationQuery) -> Result<Vec<VirtualMachine>, sqlx::Error> {
+    async fn get_all_vms(&self, query: CommonPaginationQuery) -> Result<(Vec<VirtualMachine>, Option<i64>), sqlx::Error> {
         self.get_all_vms(query).await
     }
 
+    fn stream_vms(&self, query: CommonPaginationQuery) -> Result<RowStream<VirtualMachine>, sqlx::Error> {
+        Self::stream_vms(self, query)
+    }
+
     async fn get_by_id(&self, id: i64) -> Result<Option<VirtualMachine>, sqlx::Error> {
         self.get_by_id(id).await
     }
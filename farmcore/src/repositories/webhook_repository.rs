@@ -0,0 +1,279 @@
+use sqlx::MySqlPool;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use crate::database::{QueryBuilderHelper, DatabaseHelper};
+use crate::models::{
+    CreateWebhook, CreatedWebhook, DeliveryAttempt, DueDelivery, UpdateWebhook, WebhookDelivery,
+    WebhookSubscription, WebhookTarget,
+};
+use crate::api::query_parser::{CommonPaginationQuery, ParsedQuery, QueryParser};
+
+/// Prefix on generated signing secrets
+const SECRET_PREFIX: &str = "whsec_";
+
+#[async_trait]
+pub trait WebhookRepo: Send + Sync {
+    async fn get_all_webhooks(&self) -> Result<Vec<WebhookSubscription>, sqlx::Error>;
+    async fn get_webhook_by_id(&self, webhook_id: i32) -> Result<Option<WebhookSubscription>, sqlx::Error>;
+    async fn create_webhook(&self, request: CreateWebhook) -> Result<CreatedWebhook, sqlx::Error>;
+    async fn update_webhook(&self, webhook_id: i32, request: UpdateWebhook) -> Result<bool, sqlx::Error>;
+    async fn delete_webhook(&self, webhook_id: i32) -> Result<bool, sqlx::Error>;
+    async fn get_active_targets(&self) -> Result<Vec<WebhookTarget>, sqlx::Error>;
+    async fn enqueue_delivery(&self, webhook_id: i32, event_id: Option<i64>, event_type: &str, payload: &serde_json::Value) -> Result<i64, sqlx::Error>;
+    async fn get_due_deliveries(&self, limit: i64) -> Result<Vec<DueDelivery>, sqlx::Error>;
+    async fn claim_delivery(&self, delivery_id: i64, until: DateTime<Utc>) -> Result<bool, sqlx::Error>;
+    async fn record_attempt(&self, delivery_id: i64, attempt: DeliveryAttempt) -> Result<(), sqlx::Error>;
+    async fn get_all_deliveries(&self, query: CommonPaginationQuery) -> Result<(Vec<WebhookDelivery>, Option<i64>), sqlx::Error>;
+    async fn get_delivery_by_id(&self, delivery_id: i64) -> Result<Option<WebhookDelivery>, sqlx::Error>;
+}
+
+#[derive(Clone)]
+pub struct WebhookRepository {
+    pool: MySqlPool,
+}
+
+impl WebhookRepository {
+    pub fn new(pool: MySqlPool) -> Self {
+        Self { pool }
+    }
+
+    /// Generate a random signing secret (two v4 UUIDs = 244 random bits)
+    pub(crate) fn generate_secret() -> String {
+        format!(
+            "{}{}{}",
+            SECRET_PREFIX,
+            uuid::Uuid::new_v4().simple(),
+            uuid::Uuid::new_v4().simple()
+        )
+    }
+
+    pub async fn get_all_webhooks(&self) -> Result<Vec<WebhookSubscription>, sqlx::Error> {
+        sqlx::query_as(
+            r#"
+            SELECT webhook_id, webhook_name, description, url, event_types, is_active, created_at, updated_at
+            FROM webhook_subscriptions
+            ORDER BY webhook_id
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    pub async fn get_webhook_by_id(&self, webhook_id: i32) -> Result<Option<WebhookSubscription>, sqlx::Error> {
+        DatabaseHelper::get_by_id(&self.pool, WebhookSubscription::TABLE, WebhookSubscription::KEY, webhook_id as i64).await
+    }
+
+    /// Create a webhook. The secret is returned to the caller here and never again.
+    pub async fn create_webhook(&self, request: CreateWebhook) -> Result<CreatedWebhook, sqlx::Error> {
+        let secret = request.secret.unwrap_or_else(Self::generate_secret);
+
+        let result = sqlx::query(
+            r#"
+            INSERT INTO webhook_subscriptions (
+                webhook_name, description, url, secret, event_types, is_active
+            ) VALUES (?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(&request.webhook_name)
+        .bind(&request.description)
+        .bind(&request.url)
+        .bind(&secret)
+        .bind(serde_json::json!(request.event_types))
+        .bind(request.is_active.unwrap_or(true))
+        .execute(&self.pool)
+        .await?;
+
+        let webhook = self.get_webhook_by_id(result.last_insert_id() as i32).await?
+            .ok_or(sqlx::Error::RowNotFound)?;
+        Ok(CreatedWebhook { webhook, secret })
+    }
+
+    /// Change the fields that are set. Not routed through DatabaseHelper::update, whose audit
+    /// diff would record the secret.
+    pub async fn update_webhook(&self, webhook_id: i32, request: UpdateWebhook) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            r#"
+            UPDATE webhook_subscriptions SET
+                webhook_name = COALESCE(?, webhook_name),
+                description = COALESCE(?, description),
+                url = COALESCE(?, url),
+                secret = COALESCE(?, secret),
+                event_types = COALESCE(?, event_types),
+                is_active = COALESCE(?, is_active)
+            WHERE webhook_id = ?
+            "#,
+        )
+        .bind(&request.webhook_name)
+        .bind(&request.description)
+        .bind(&request.url)
+        .bind(&request.secret)
+        .bind(request.event_types.map(|types| serde_json::json!(types)))
+        .bind(request.is_active)
+        .bind(webhook_id)
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Delete a webhook together with its delivery log
+    pub async fn delete_webhook(&self, webhook_id: i32) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM webhook_subscriptions WHERE webhook_id = ?")
+            .bind(webhook_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn get_active_targets(&self) -> Result<Vec<WebhookTarget>, sqlx::Error> {
+        sqlx::query_as("SELECT webhook_id, event_types FROM webhook_subscriptions WHERE is_active = TRUE")
+            .fetch_all(&self.pool)
+            .await
+    }
+
+    /// Queue a delivery, due immediately
+    pub async fn enqueue_delivery(
+        &self,
+        webhook_id: i32,
+        event_id: Option<i64>,
+        event_type: &str,
+        payload: &serde_json::Value,
+    ) -> Result<i64, sqlx::Error> {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO webhook_deliveries (webhook_id, event_id, event_type, payload, status, next_attempt_at)
+            VALUES (?, ?, ?, ?, 'PENDING', ?)
+            "#,
+        )
+        .bind(webhook_id)
+        .bind(event_id)
+        .bind(event_type)
+        .bind(payload)
+        .bind(Utc::now())
+        .execute(&self.pool)
+        .await?;
+        Ok(result.last_insert_id() as i64)
+    }
+
+    /// Pending deliveries whose next attempt is due, oldest first. Deliveries of inactive
+    /// webhooks are held until the webhook is reactivated.
+    pub async fn get_due_deliveries(&self, limit: i64) -> Result<Vec<DueDelivery>, sqlx::Error> {
+        sqlx::query_as(
+            r#"
+            SELECT d.delivery_id, d.event_type, d.payload, d.attempts, w.url, w.secret
+            FROM webhook_deliveries d
+            JOIN webhook_subscriptions w ON w.webhook_id = d.webhook_id
+            WHERE d.status = 'PENDING' AND w.is_active = TRUE AND d.next_attempt_at <= ?
+            ORDER BY d.next_attempt_at, d.delivery_id
+            LIMIT ?
+            "#,
+        )
+        .bind(Utc::now())
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// Take a due delivery by pushing its next attempt out to `until`. Returns false when
+    /// another dispatcher got there first.
+    pub async fn claim_delivery(&self, delivery_id: i64, until: DateTime<Utc>) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            r#"
+            UPDATE webhook_deliveries SET next_attempt_at = ?
+            WHERE delivery_id = ? AND status = 'PENDING' AND next_attempt_at <= ?
+            "#,
+        )
+        .bind(until)
+        .bind(delivery_id)
+        .bind(Utc::now())
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn record_attempt(&self, delivery_id: i64, attempt: DeliveryAttempt) -> Result<(), sqlx::Error> {
+        let delivered_at = (attempt.status == "SUCCEEDED").then(Utc::now);
+        sqlx::query(
+            r#"
+            UPDATE webhook_deliveries SET
+                status = ?,
+                attempts = attempts + 1,
+                last_status_code = ?,
+                last_error = ?,
+                next_attempt_at = ?,
+                delivered_at = COALESCE(?, delivered_at)
+            WHERE delivery_id = ?
+            "#,
+        )
+        .bind(attempt.status)
+        .bind(attempt.status_code)
+        .bind(&attempt.error)
+        .bind(attempt.next_attempt_at)
+        .bind(delivered_at)
+        .bind(delivery_id)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Delivery log with pagination and filtering, newest first
+    pub async fn get_all_deliveries(&self, query: CommonPaginationQuery) -> Result<(Vec<WebhookDelivery>, Option<i64>), sqlx::Error> {
+        let ParsedQuery { where_conditions, options, .. } = QueryParser::parse_all(
+            &query,
+            &WebhookDelivery::SCHEMA,
+            Some("delivery_id DESC".to_string())
+        ).map_err(|e| sqlx::Error::Protocol(format!("Query parsing error: {}", e)))?;
+
+        let total_count = if query.include_total() {
+            Some(QueryBuilderHelper::count(&self.pool, &WebhookDelivery::SCHEMA, &where_conditions).await?)
+        } else {
+            None
+        };
+
+        let deliveries = QueryBuilderHelper::select(&self.pool, &WebhookDelivery::SCHEMA, options).await?;
+        Ok((deliveries, total_count))
+    }
+
+    pub async fn get_delivery_by_id(&self, delivery_id: i64) -> Result<Option<WebhookDelivery>, sqlx::Error> {
+        DatabaseHelper::get_by_id(&self.pool, WebhookDelivery::TABLE, WebhookDelivery::KEY, delivery_id).await
+    }
+}
+
+#[async_trait]
+impl WebhookRepo for WebhookRepository {
+    async fn get_all_webhooks(&self) -> Result<Vec<WebhookSubscription>, sqlx::Error> {
+        self.get_all_webhooks().await
+    }
+    async fn get_webhook_by_id(&self, webhook_id: i32) -> Result<Option<WebhookSubscription>, sqlx::Error> {
+        self.get_webhook_by_id(webhook_id).await
+    }
+    async fn create_webhook(&self, request: CreateWebhook) -> Result<CreatedWebhook, sqlx::Error> {
+        self.create_webhook(request).await
+    }
+    async fn update_webhook(&self, webhook_id: i32, request: UpdateWebhook) -> Result<bool, sqlx::Error> {
+        self.update_webhook(webhook_id, request).await
+    }
+    async fn delete_webhook(&self, webhook_id: i32) -> Result<bool, sqlx::Error> {
+        self.delete_webhook(webhook_id).await
+    }
+    async fn get_active_targets(&self) -> Result<Vec<WebhookTarget>, sqlx::Error> {
+        self.get_active_targets().await
+    }
+    async fn enqueue_delivery(&self, webhook_id: i32, event_id: Option<i64>, event_type: &str, payload: &serde_json::Value) -> Result<i64, sqlx::Error> {
+        self.enqueue_delivery(webhook_id, event_id, event_type, payload).await
+    }
+    async fn get_due_deliveries(&self, limit: i64) -> Result<Vec<DueDelivery>, sqlx::Error> {
+        self.get_due_deliveries(limit).await
+    }
+    async fn claim_delivery(&self, delivery_id: i64, until: DateTime<Utc>) -> Result<bool, sqlx::Error> {
+        self.claim_delivery(delivery_id, until).await
+    }
+    async fn record_attempt(&self, delivery_id: i64, attempt: DeliveryAttempt) -> Result<(), sqlx::Error> {
+        self.record_attempt(delivery_id, attempt).await
+    }
+    async fn get_all_deliveries(&self, query: CommonPaginationQuery) -> Result<(Vec<WebhookDelivery>, Option<i64>), sqlx::Error> {
+        self.get_all_deliveries(query).await
+    }
+    async fn get_delivery_by_id(&self, delivery_id: i64) -> Result<Option<WebhookDelivery>, sqlx::Error> {
+        self.get_delivery_by_id(delivery_id).await
+    }
+}
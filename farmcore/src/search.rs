@@ -1,15 +1,50 @@
 use serde::Deserialize;
-use crate::models::{QueryValue, WhereCondition};
+use crate::api::query_parser::QueryError;
+use crate::models::{
+    ColumnType, Predicate, QueryValue, TableSchema, WhereCondition, COMPARISON_OPERATORS, LIST_OPERATORS,
+    NULL_OPERATORS,
+};
+
+/// How deep search groups may nest
+const MAX_GROUP_DEPTH: usize = 5;
+/// Most values accepted by a single IN / NOT IN
+const MAX_LIST_VALUES: usize = 500;
 
 #[derive(Deserialize, Debug, Clone)]
 pub struct SearchCriterion {
     pub column: String,
+    #[serde(default)]
     pub term: String,
+    /// Values for IN, NOT IN and BETWEEN; a comma-separated `term` works too
+    pub terms: Option<Vec<String>>,
     pub operator: Option<LogicalOperator>,
     #[serde(rename = "comparisonOperator")]
     pub comparison_operator: Option<String>,
 }
 
+/// A parenthesized group of criteria; `operator` joins the group to the next node
+#[derive(Deserialize, Debug, Clone)]
+pub struct SearchGroup {
+    pub group: Vec<SearchNode>,
+    pub operator: Option<LogicalOperator>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(untagged)]
+pub enum SearchNode {
+    Group(SearchGroup),
+    Criterion(SearchCriterion),
+}
+
+impl SearchNode {
+    fn logical_operator(&self) -> Option<&LogicalOperator> {
+        match self {
+            SearchNode::Group(group) => group.operator.as_ref(),
+            SearchNode::Criterion(criterion) => criterion.operator.as_ref(),
+        }
+    }
+}
+
 #[derive(Deserialize, Debug, Clone)]
 #[serde(rename_all = "UPPERCASE")]
 pub enum LogicalOperator {
@@ -20,65 +55,239 @@ pub enum LogicalOperator {
 pub struct SearchQueryBuilder;
 
 impl SearchQueryBuilder {
-    /// Parse a JSON array of SearchCriterion into WHERE conditions
-    pub fn build_conditions(search_json: &str) -> Result<Vec<WhereCondition>, String> {
-        let criteria = serde_json::from_str::<Vec<SearchCriterion>>(search_json)
-            .map_err(|e| format!("Invalid search format: {}", e))?;
+    /// Parse a JSON array of search nodes (criteria and nested groups) into WHERE conditions on the given table
+    pub fn build_conditions(search_json: &str, schema: &TableSchema) -> Result<Vec<WhereCondition>, QueryError> {
+        let nodes = serde_json::from_str::<Vec<SearchNode>>(search_json)
+            .map_err(|e| QueryError::new("search", format!("Invalid search format: {}", e)))?;
 
-        Self::validate(&criteria)?;
-        Ok(Self::build_sequential_conditions(&criteria))
+        Self::build_sequential_conditions(&nodes, schema, "search", 0)
     }
 
-    fn build_sequential_conditions(criteria: &[SearchCriterion]) -> Vec<WhereCondition> {
-        criteria.iter().enumerate().map(|(index, criterion)| {
-            let op = criterion.comparison_operator.as_deref().unwrap_or("=");
+    /// Each node is joined to the one before it by the previous node's `operator`
+    fn build_sequential_conditions(
+        nodes: &[SearchNode],
+        schema: &TableSchema,
+        path: &str,
+        depth: usize,
+    ) -> Result<Vec<WhereCondition>, QueryError> {
+        if depth > MAX_GROUP_DEPTH {
+            return Err(QueryError::new(path, format!("Search groups may nest at most {} levels deep", MAX_GROUP_DEPTH)));
+        }
+
+        nodes.iter().enumerate().map(|(index, node)| {
+            let node_path = format!("{}[{}]", path, index);
             let logical_operator = if index == 0 {
                 None
             } else {
-                criteria[index - 1].operator.as_ref().map(|lo| match lo {
+                nodes[index - 1].logical_operator().map(|lo| match lo {
                     LogicalOperator::And => "AND".to_string(),
                     LogicalOperator::Or => "OR".to_string(),
                 })
             };
-            WhereCondition {
-                column: criterion.column.clone(),
-                operator: op.to_string(),
-                value: Self::parse_value(&criterion.term, op),
-                logical_operator,
-            }
+
+            let predicate = match node {
+                SearchNode::Group(group) => {
+                    let group_path = format!("{}.group", node_path);
+                    if group.group.is_empty() {
+                        return Err(QueryError::new(group_path, "Search group is empty"));
+                    }
+                    Predicate::Group(Self::build_sequential_conditions(&group.group, schema, &group_path, depth + 1)?)
+                }
+                SearchNode::Criterion(criterion) => Self::build_predicate(criterion, schema, &node_path)?,
+            };
+
+            Ok(WhereCondition { predicate, logical_operator })
         }).collect()
     }
 
-    fn parse_value(term: &str, operator: &str) -> QueryValue {
-        if let Ok(i) = term.parse::<i64>() {
-            QueryValue::Integer(i)
-        } else if let Ok(f) = term.parse::<f64>() {
-            QueryValue::Float(f)
-        } else if let Ok(b) = term.parse::<bool>() {
-            QueryValue::Boolean(b)
-        } else if operator == "LIKE" {
-            QueryValue::String(format!("%{}%", term))
-        } else {
-            QueryValue::String(term.to_string())
+    fn build_predicate(criterion: &SearchCriterion, schema: &TableSchema, path: &str) -> Result<Predicate, QueryError> {
+        if criterion.column.is_empty() {
+            return Err(QueryError::new(format!("{}.column", path), "Criterion has an empty column name"));
         }
-    }
+        let target = schema.filter(&criterion.column)
+            .ok_or_else(|| QueryError::unknown_filter(format!("{}.column", path), &criterion.column, schema))?;
+        let column = target.column();
 
-    pub fn validate(criteria: &[SearchCriterion]) -> Result<(), String> {
-        let valid_ops = ["=", "!=", ">", "<", ">=", "<=", "LIKE", "NOT LIKE"];
-        for (i, c) in criteria.iter().enumerate() {
-            if c.column.is_empty() {
-                return Err(format!("Criterion {} has an empty column name", i));
+        let op = criterion.comparison_operator.as_deref().unwrap_or("=");
+        let op_path = format!("{}.comparisonOperator", path);
+        if !COMPARISON_OPERATORS.contains(&op) {
+            return Err(QueryError::new(op_path, format!("Invalid operator '{}'", op)).with_allowed(COMPARISON_OPERATORS));
+        }
+        if !column.allows(op) {
+            return Err(QueryError::new(op_path, format!("Operator '{}' is not supported for column '{}'", op, criterion.column))
+                .with_allowed(column.operators));
+        }
+
+        let term_path = format!("{}.term", path);
+        let parse = |term: &str| column.parse_value(term.trim(), op).map_err(|e| QueryError::new(&term_path, e));
+
+        let value = if NULL_OPERATORS.contains(&op) {
+            QueryValue::Null
+        } else if LIST_OPERATORS.contains(&op) || op == "BETWEEN" {
+            let terms = Self::terms(criterion);
+            if op == "BETWEEN" && terms.len() != 2 {
+                return Err(QueryError::new(term_path, "BETWEEN takes exactly two values"));
             }
-            if c.term.is_empty() {
-                return Err(format!("Criterion {} has an empty search term", i));
+            if terms.is_empty() {
+                return Err(QueryError::new(term_path, format!("{} needs at least one value", op)));
             }
-            if let Some(ref op) = c.comparison_operator {
-                if !valid_ops.contains(&op.as_str()) {
-                    return Err(format!("Criterion {} has invalid operator '{}'", i, op));
-                }
+            if terms.len() > MAX_LIST_VALUES {
+                return Err(QueryError::new(term_path, format!("{} accepts at most {} values", op, MAX_LIST_VALUES)));
             }
+            QueryValue::List(terms.iter().map(|term| parse(term)).collect::<Result<_, _>>()?)
+        } else {
+            if criterion.term.is_empty() {
+                return Err(QueryError::new(term_path, "Criterion has an empty search term"));
+            }
+            parse(&criterion.term)?
+        };
+
+        Ok(Predicate::Compare { column: criterion.column.clone(), operator: op.to_string(), value })
+    }
+
+    /// Values for a list operator: `terms` if given, else the comma-separated `term`
+    fn terms(criterion: &SearchCriterion) -> Vec<String> {
+        match &criterion.terms {
+            Some(terms) => terms.clone(),
+            None => Self::split_list(&criterion.term),
         }
-        Ok(())
+    }
+
+    pub fn split_list(term: &str) -> Vec<String> {
+        term.split(',').map(str::trim).filter(|t| !t.is_empty()).map(str::to_string).collect()
+    }
+
+    /// Whether a comma in a plain `column=value` filter should mean IN. Free-text columns
+    /// keep commas as part of the value.
+    pub fn filter_is_list(column_type: ColumnType, value: &str) -> bool {
+        value.contains(',') && !matches!(column_type, ColumnType::String)
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Server;
+
+    fn build(search: serde_json::Value) -> Result<Vec<WhereCondition>, QueryError> {
+        SearchQueryBuilder::build_conditions(&search.to_string(), &Server::SCHEMA)
+    }
+
+    fn compare(condition: &WhereCondition) -> (&str, &str, &QueryValue) {
+        match &condition.predicate {
+            Predicate::Compare { column, operator, value } => (column.as_str(), operator.as_str(), value),
+            Predicate::Group(_) => panic!("expected a comparison, got a group"),
+        }
+    }
+
+    fn group(condition: &WhereCondition) -> &[WhereCondition] {
+        match &condition.predicate {
+            Predicate::Group(conditions) => conditions,
+            Predicate::Compare { .. } => panic!("expected a group, got a comparison"),
+        }
+    }
+
+    /// A search nested `depth` groups deep
+    fn nested(depth: usize) -> serde_json::Value {
+        (0..depth).fold(serde_json::json!({ "column": "status", "term": "ACTIVE" }), |node, _| {
+            serde_json::json!({ "group": [node] })
+        })
+    }
+
+    #[test]
+    fn the_documented_example_builds() {
+        // (status=ACTIVE OR status=MAINTENANCE) AND cluster_id IN (3,4) AND last_inventory_at < now-1d
+        let conditions = build(serde_json::json!([
+            { "group": [
+                { "column": "status", "term": "ACTIVE", "operator": "OR" },
+                { "column": "status", "term": "maintenance" },
+            ], "operator": "AND" },
+            { "column": "cluster_id", "comparisonOperator": "IN", "term": "3,4", "operator": "AND" },
+            { "column": "last_inventory_at", "comparisonOperator": "<", "term": "now-1d" },
+        ])).unwrap();
+
+        assert_eq!(conditions.len(), 3);
+        assert_eq!(conditions[0].logical_operator, None);
+        let alternatives = group(&conditions[0]);
+        assert_eq!(compare(&alternatives[0]), ("status", "=", &QueryValue::String("ACTIVE".into())));
+        // ENUM terms are matched case-insensitively and bound in their canonical case
+        assert_eq!(compare(&alternatives[1]), ("status", "=", &QueryValue::String("MAINTENANCE".into())));
+        assert_eq!(alternatives[1].logical_operator.as_deref(), Some("OR"));
+
+        assert_eq!(conditions[1].logical_operator.as_deref(), Some("AND"));
+        let cluster_ids = QueryValue::List(vec![QueryValue::Integer(3), QueryValue::Integer(4)]);
+        assert_eq!(compare(&conditions[1]), ("cluster_id", "IN", &cluster_ids));
+
+        assert_eq!(conditions[2].logical_operator.as_deref(), Some("AND"));
+        let (column, operator, value) = compare(&conditions[2]);
+        assert_eq!((column, operator), ("last_inventory_at", "<"));
+        assert!(matches!(value, QueryValue::Timestamp(_)), "{:?}", value);
+    }
+
+    #[test]
+    fn groups_nest_up_to_the_limit() {
+        let conditions = build(serde_json::json!([nested(MAX_GROUP_DEPTH)])).unwrap();
+        let mut node = &conditions[0];
+        for _ in 0..MAX_GROUP_DEPTH {
+            node = &group(node)[0];
+        }
+        assert_eq!(compare(node).0, "status");
+
+        let error = build(serde_json::json!([nested(MAX_GROUP_DEPTH + 1)])).unwrap_err();
+        assert!(error.message.contains("at most 5 levels"), "{}", error.message);
+
+        let error = build(serde_json::json!([{ "group": [] }])).unwrap_err();
+        assert_eq!(error.parameter, "search[0].group");
+    }
+
+    #[test]
+    fn list_range_and_null_operators_take_their_own_values() {
+        let conditions = build(serde_json::json!([
+            { "column": "server_name", "comparisonOperator": "NOT IN", "terms": ["a,b", "c"], "operator": "AND" },
+            { "column": "u_height", "comparisonOperator": "BETWEEN", "term": "1, 4", "operator": "AND" },
+            { "column": "rack_id", "comparisonOperator": "IS NULL" },
+        ])).unwrap();
+
+        // `terms` keeps commas inside values; a comma-separated `term` is split
+        let names = QueryValue::List(vec![QueryValue::String("a,b".into()), QueryValue::String("c".into())]);
+        assert_eq!(compare(&conditions[0]), ("server_name", "NOT IN", &names));
+        let bounds = QueryValue::List(vec![QueryValue::Integer(1), QueryValue::Integer(4)]);
+        assert_eq!(compare(&conditions[1]), ("u_height", "BETWEEN", &bounds));
+        assert_eq!(compare(&conditions[2]), ("rack_id", "IS NULL", &QueryValue::Null));
+
+        let error = build(serde_json::json!([{ "column": "u_height", "comparisonOperator": "BETWEEN", "term": "1,2,3" }])).unwrap_err();
+        assert_eq!(error.parameter, "search[0].term");
+        let error = build(serde_json::json!([{ "column": "cluster_id", "comparisonOperator": "IN", "term": " , " }])).unwrap_err();
+        assert!(error.message.contains("at least one value"), "{}", error.message);
+        let error = build(serde_json::json!([{ "column": "cluster_id", "comparisonOperator": "IN", "term": "3,x" }])).unwrap_err();
+        assert!(error.message.contains("not an integer"), "{}", error.message);
+    }
+
+    #[test]
+    fn lists_are_capped() {
+        let terms = |count: usize| (0..count).map(|i| i.to_string()).collect::<Vec<_>>();
+
+        let conditions = build(serde_json::json!([
+            { "column": "cluster_id", "comparisonOperator": "IN", "terms": terms(MAX_LIST_VALUES) },
+        ])).unwrap();
+        assert!(matches!(compare(&conditions[0]).2, QueryValue::List(values) if values.len() == MAX_LIST_VALUES));
+
+        let error = build(serde_json::json!([
+            { "column": "cluster_id", "comparisonOperator": "IN", "terms": terms(MAX_LIST_VALUES + 1) },
+        ])).unwrap_err();
+        assert!(error.message.contains("at most 500 values"), "{}", error.message);
+    }
+
+    #[test]
+    fn unknown_columns_and_disallowed_operators_are_rejected() {
+        let error = build(serde_json::json!([{ "column": "password", "term": "x" }])).unwrap_err();
+        assert_eq!(error.parameter, "search[0].column");
+        assert!(error.allowed.as_ref().is_some_and(|allowed| allowed.iter().any(|name| name == "gpu.model")));
+
+        let error = build(serde_json::json!([{ "column": "status", "comparisonOperator": ">", "term": "ACTIVE" }])).unwrap_err();
+        assert_eq!(error.parameter, "search[0].comparisonOperator");
+
+        let error = build(serde_json::json!([{ "column": "status", "comparisonOperator": "; DROP", "term": "ACTIVE" }])).unwrap_err();
+        assert!(error.message.contains("Invalid operator"), "{}", error.message);
+    }
+}
@@ -1,46 +1,161 @@
 use sqlx::MySqlPool;
-use crate::repositories::{ServerRepository, ComponentRepository, VmRepository, KubernetesRepository, DatacenterRepository, ClusterRepository, SwitchRepository};
+use crate::database::{Storage, UnsupportedOnBackend};
+use crate::domain::{EventBus, NewEvent, TopologyPlanner, TopologyService, WebhookOutbox};
+use crate::repositories::{ServerRepository, ComponentRepository, VmRepository, KubernetesRepository, DatacenterRepository, ClusterRepository, SwitchRepository, TokenRepository, AuditRepository, WebhookRepository, FleetRepository, InventoryChangeRepository, MissingComponentRepository, IdentityConflictRepository, ServerMergeRepository};
+use crate::repositories::{ServerRepo, ComponentRepo, VmRepo, K8sRepo, DatacenterRepo, ClusterRepo, SwitchRepo, TokenRepo, AuditRepo, WebhookRepo, FleetRepo, InventoryChangeRepo, MissingComponentRepo, IdentityConflictRepo, ServerMergeRepo};
+use crate::repositories::sqlite::{SqliteServerRepository, SqliteComponentRepository, SqliteVmRepository, SqliteKubernetesRepository, SqliteDatacenterRepository, SqliteClusterRepository, SqliteSwitchRepository, SqliteTokenRepository, SqliteAuditRepository, SqliteWebhookRepository, SqliteFleetRepository, SqliteInventoryChangeRepository, SqliteMissingComponentRepository, SqliteIdentityConflictRepository, SqliteServerMergeRepository};
 
 #[derive(Clone)]
 pub struct AppState {
-    pool: MySqlPool,
+    storage: Storage,
+    events: EventBus,
+    webhooks: WebhookOutbox,
 }
 
 impl AppState {
-    pub fn new(pool: MySqlPool) -> Self {
-        Self { pool }
+    pub fn new(storage: Storage) -> Self {
+        Self { storage, events: EventBus::new(), webhooks: WebhookOutbox::new() }
     }
 
-    pub fn server_repo(&self) -> ServerRepository {
-        ServerRepository::new(self.pool.clone())
+    pub fn storage(&self) -> &Storage {
+        &self.storage
     }
 
-    pub fn component_repo(&self) -> ComponentRepository {
-        ComponentRepository::new(self.pool.clone())
+    /// Domain event bus shared by every worker
+    pub fn events(&self) -> &EventBus {
+        &self.events
     }
 
-    pub fn vm_repo(&self) -> VmRepository {
-        VmRepository::new(self.pool.clone())
+    /// Outbox the webhook dispatcher sends from
+    pub fn webhook_outbox(&self) -> &WebhookOutbox {
+        &self.webhooks
     }
 
-    pub fn k8s_repo(&self) -> KubernetesRepository {
-        KubernetesRepository::new(self.pool.clone())
+    /// Publish `event` on the bus and queue its webhook deliveries before returning, so a
+    /// delivery is not lost if the process stops right after the response
+    pub async fn publish(&self, event: NewEvent) {
+        let event = self.events.publish(event);
+        self.webhooks.queue(self.webhook_repo().as_ref(), &event).await;
     }
 
-    pub fn datacenter_repo(&self) -> DatacenterRepository {
-        DatacenterRepository::new(self.pool.clone())
+    pub fn server_repo(&self) -> Box<dyn ServerRepo> {
+        match &self.storage {
+            Storage::MySql(pool) => Box::new(ServerRepository::new(pool.clone())),
+            Storage::Sqlite(pool) => Box::new(SqliteServerRepository::new(pool.clone())),
+        }
     }
 
-    pub fn cluster_repo(&self) -> ClusterRepository {
-        ClusterRepository::new(self.pool.clone())
+    pub fn component_repo(&self) -> Box<dyn ComponentRepo> {
+        match &self.storage {
+            Storage::MySql(pool) => Box::new(ComponentRepository::new(pool.clone())),
+            Storage::Sqlite(pool) => Box::new(SqliteComponentRepository::new(pool.clone())),
+        }
     }
 
-    pub fn switch_repo(&self) -> SwitchRepository {
-        SwitchRepository::new(self.pool.clone())
+    pub fn vm_repo(&self) -> Box<dyn VmRepo> {
+        match &self.storage {
+            Storage::MySql(pool) => Box::new(VmRepository::new(pool.clone())),
+            Storage::Sqlite(pool) => Box::new(SqliteVmRepository::new(pool.clone())),
+        }
     }
 
-    // Method to get the pool directly for cases where we need it
-    pub fn pool(&self) -> &MySqlPool {
-        &self.pool
+    pub fn k8s_repo(&self) -> Box<dyn K8sRepo> {
+        match &self.storage {
+            Storage::MySql(pool) => Box::new(KubernetesRepository::new(pool.clone())),
+            Storage::Sqlite(pool) => Box::new(SqliteKubernetesRepository::new(pool.clone())),
+        }
     }
-}
\ No newline at end of file
+
+    pub fn datacenter_repo(&self) -> Box<dyn DatacenterRepo> {
+        match &self.storage {
+            Storage::MySql(pool) => Box::new(DatacenterRepository::new(pool.clone())),
+            Storage::Sqlite(pool) => Box::new(SqliteDatacenterRepository::new(pool.clone())),
+        }
+    }
+
+    pub fn cluster_repo(&self) -> Box<dyn ClusterRepo> {
+        match &self.storage {
+            Storage::MySql(pool) => Box::new(ClusterRepository::new(pool.clone())),
+            Storage::Sqlite(pool) => Box::new(SqliteClusterRepository::new(pool.clone())),
+        }
+    }
+
+    pub fn switch_repo(&self) -> Box<dyn SwitchRepo> {
+        match &self.storage {
+            Storage::MySql(pool) => Box::new(SwitchRepository::new(pool.clone())),
+            Storage::Sqlite(pool) => Box::new(SqliteSwitchRepository::new(pool.clone())),
+        }
+    }
+
+    pub fn topology_service(&self) -> Box<dyn TopologyPlanner> {
+        match &self.storage {
+            Storage::MySql(pool) => Box::new(TopologyService::new(pool.clone())),
+            Storage::Sqlite(pool) => Box::new(TopologyService::new(pool.clone())),
+        }
+    }
+
+    pub fn token_repo(&self) -> Box<dyn TokenRepo> {
+        match &self.storage {
+            Storage::MySql(pool) => Box::new(TokenRepository::new(pool.clone())),
+            Storage::Sqlite(pool) => Box::new(SqliteTokenRepository::new(pool.clone())),
+        }
+    }
+
+    pub fn audit_repo(&self) -> Box<dyn AuditRepo> {
+        match &self.storage {
+            Storage::MySql(pool) => Box::new(AuditRepository::new(pool.clone())),
+            Storage::Sqlite(pool) => Box::new(SqliteAuditRepository::new(pool.clone())),
+        }
+    }
+
+    pub fn webhook_repo(&self) -> Box<dyn WebhookRepo> {
+        match &self.storage {
+            Storage::MySql(pool) => Box::new(WebhookRepository::new(pool.clone())),
+            Storage::Sqlite(pool) => Box::new(SqliteWebhookRepository::new(pool.clone())),
+        }
+    }
+
+    pub fn fleet_repo(&self) -> Box<dyn FleetRepo> {
+        match &self.storage {
+            Storage::MySql(pool) => Box::new(FleetRepository::new(pool.clone())),
+            Storage::Sqlite(pool) => Box::new(SqliteFleetRepository::new(pool.clone())),
+        }
+    }
+
+    pub fn inventory_change_repo(&self) -> Box<dyn InventoryChangeRepo> {
+        match &self.storage {
+            Storage::MySql(pool) => Box::new(InventoryChangeRepository::new(pool.clone())),
+            Storage::Sqlite(pool) => Box::new(SqliteInventoryChangeRepository::new(pool.clone())),
+        }
+    }
+
+    pub fn missing_component_repo(&self) -> Box<dyn MissingComponentRepo> {
+        match &self.storage {
+            Storage::MySql(pool) => Box::new(MissingComponentRepository::new(pool.clone())),
+            Storage::Sqlite(pool) => Box::new(SqliteMissingComponentRepository::new(pool.clone())),
+        }
+    }
+
+    pub fn identity_conflict_repo(&self) -> Box<dyn IdentityConflictRepo> {
+        match &self.storage {
+            Storage::MySql(pool) => Box::new(IdentityConflictRepository::new(pool.clone())),
+            Storage::Sqlite(pool) => Box::new(SqliteIdentityConflictRepository::new(pool.clone())),
+        }
+    }
+
+    pub fn server_merge_repo(&self) -> Box<dyn ServerMergeRepo> {
+        match &self.storage {
+            Storage::MySql(pool) => Box::new(ServerMergeRepository::new(pool.clone())),
+            Storage::Sqlite(pool) => Box::new(SqliteServerMergeRepository::new(pool.clone())),
+        }
+    }
+
+    /// The MySQL pool, for the development seeds, which are written in MySQL's SQL dialect.
+    /// On SQLite this is an error the handler answers with 501.
+    pub fn pool(&self) -> Result<&MySqlPool, UnsupportedOnBackend> {
+        match &self.storage {
+            Storage::MySql(pool) => Ok(pool),
+            Storage::Sqlite(_) => Err(UnsupportedOnBackend { backend: self.storage.backend_name() }),
+        }
+    }
+}
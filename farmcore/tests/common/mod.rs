@@ -0,0 +1,81 @@
+//! Helpers shared by the integration suites: an API on an in-memory SQLite database and a
+//! way to call it with a bearer token.
+#![allow(dead_code)] // Each suite uses its own subset
+
+use actix_web::body::{to_bytes, MessageBody};
+use actix_web::dev::{Service, ServiceResponse};
+use actix_web::http::StatusCode;
+use actix_web::{test, web, App};
+use farm_core::api::configure_api_routes;
+use farm_core::api::metrics::configure_metrics_routes;
+use farm_core::database::{init_sqlite_pool, Storage};
+use farm_core::state::AppState;
+use serde_json::{json, Value};
+
+pub const BOOTSTRAP_TOKEN: &str = "sqlite-api-bootstrap";
+
+/// Agent payloads shared with the farm-proto contract tests
+pub fn fixture(name: &str) -> Value {
+    let path = format!("{}/../farmproto/tests/fixtures/{}", env!("CARGO_MANIFEST_DIR"), name);
+    let text = std::fs::read_to_string(&path).unwrap_or_else(|e| panic!("{}: {}", path, e));
+    serde_json::from_str(&text).unwrap_or_else(|e| panic!("{}: {}", path, e))
+}
+
+/// Application state on a fresh in-memory database
+pub async fn state() -> AppState {
+    std::env::set_var("FARM_BOOTSTRAP_TOKEN", BOOTSTRAP_TOKEN);
+    let storage = Storage::Sqlite(init_sqlite_pool("sqlite::memory:").await.expect("in-memory database"));
+    AppState::new(storage)
+}
+
+/// The API and metrics routes as main registers them, on `state`
+pub async fn service(state: &AppState) -> impl Service<actix_http::Request, Response = ServiceResponse<impl MessageBody>, Error = actix_web::Error> {
+    test::init_service(
+        App::new()
+            .app_data(web::Data::new(state.clone()))
+            .configure(configure_api_routes)
+            .configure(configure_metrics_routes),
+    )
+    .await
+}
+
+pub async fn app() -> impl Service<actix_http::Request, Response = ServiceResponse<impl MessageBody>, Error = actix_web::Error> {
+    service(&state().await).await
+}
+
+/// Send a request and return its status and JSON body. Authentication failures come back
+/// from the middleware as errors, so those are rendered the way the server would.
+pub async fn call<S, B>(app: &S, method: &str, path: &str, token: &str, body: Option<Value>) -> (StatusCode, Value)
+where
+    S: Service<actix_http::Request, Response = ServiceResponse<B>, Error = actix_web::Error>,
+    B: MessageBody,
+{
+    let mut request = test::TestRequest::default()
+        .method(method.parse().unwrap())
+        .uri(path)
+        .insert_header(("Authorization", format!("Bearer {}", token)));
+    if let Some(body) = body {
+        request = request.set_json(body);
+    }
+    let (status, bytes) = match test::try_call_service(app, request.to_request()).await {
+        Ok(response) => (response.status(), test::read_body(response).await),
+        Err(e) => {
+            let response = e.error_response();
+            let status = response.status();
+            (status, to_bytes(response.into_body()).await.unwrap_or_default())
+        }
+    };
+    let body = serde_json::from_slice(&bytes).unwrap_or(Value::Null);
+    (status, body)
+}
+
+pub async fn issue_token<S, B>(app: &S, name: &str, scope: &str, role: Option<&str>) -> String
+where
+    S: Service<actix_http::Request, Response = ServiceResponse<B>, Error = actix_web::Error>,
+    B: MessageBody,
+{
+    let body = json!({ "token_name": name, "scope": scope, "role": role });
+    let (status, response) = call(app, "POST", "/api/v1/tokens", BOOTSTRAP_TOKEN, Some(body)).await;
+    assert_eq!(status, StatusCode::CREATED, "{}", response);
+    response["data"]["token"].as_str().expect("plaintext token").to_string()
+}
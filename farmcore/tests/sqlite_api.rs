@@ -0,0 +1,464 @@
+//! The v1 API on the SQLite backend, booted on an in-memory database: agent ingest, the server,
+//! VM, token, audit and metrics routes, and the 501 answered for resources that only exist on
+//! MySQL.
+mod common;
+
+use actix_web::body::MessageBody;
+use actix_web::dev::{Service, ServiceResponse};
+use actix_web::http::StatusCode;
+use common::{app, call, fixture, issue_token, BOOTSTRAP_TOKEN};
+use serde_json::{json, Value};
+
+#[actix_web::test]
+async fn tokens_are_issued_listed_and_enforced() {
+    let app = app().await;
+
+    let (status, _) = call(&app, "GET", "/api/v1/tokens/list", "not-a-token", None).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    let viewer = issue_token(&app, "viewer", "API", Some("VIEWER")).await;
+    let agent = issue_token(&app, "agent", "AGENT", None).await;
+
+    let (status, response) = call(&app, "GET", "/api/v1/tokens/list", BOOTSTRAP_TOKEN, None).await;
+    assert_eq!(status, StatusCode::OK, "{}", response);
+    let tokens = response["data"].as_array().expect("token list");
+    let role_of = |name: &str| tokens.iter().find(|t| t["token_name"] == name).map(|t| t["role"].clone());
+    assert_eq!(role_of("viewer"), Some(json!("VIEWER")));
+    assert_eq!(role_of("agent"), Some(Value::Null));
+
+    // Tokens are managed by ADMINs only, and an AGENT token is refused on API routes
+    let body = json!({ "token_name": "escalated", "scope": "API", "role": "ADMIN" });
+    let (status, _) = call(&app, "POST", "/api/v1/tokens", &viewer, Some(body)).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    let (status, _) = call(&app, "GET", "/api/v1/tokens/list", &viewer, None).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    let (status, _) = call(&app, "GET", "/api/v1/tokens/list", &agent, None).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    let body = json!({ "token_name": "agent-with-role", "scope": "AGENT", "role": "ADMIN" });
+    let (status, _) = call(&app, "POST", "/api/v1/tokens", BOOTSTRAP_TOKEN, Some(body)).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[actix_web::test]
+async fn agent_inventory_populates_servers_and_vms() {
+    let app = app().await;
+    let agent = issue_token(&app, "agent", "AGENT", None).await;
+    let viewer = issue_token(&app, "viewer", "API", Some("VIEWER")).await;
+    let inventory = fixture("inventory_v2.json");
+
+    let (status, response) = call(&app, "POST", "/api/v1/servers/inventory", &agent, Some(inventory.clone())).await;
+    assert_eq!(status, StatusCode::OK, "{}", response);
+    let server_id = response["data"]["server_id"].as_i64().expect("server id");
+
+    // Posting the same hardware again only refreshes last_inventory_at and the PSU readings
+    let mut inventory = inventory;
+    inventory["power_supplies"][0]["temperature_c"] = json!(44);
+    inventory["power_supplies"][0]["input_current"] = json!(2.5);
+    let (status, response) = call(&app, "POST", "/api/v1/servers/inventory", &agent, Some(inventory)).await;
+    assert_eq!(status, StatusCode::OK, "{}", response);
+    assert_eq!(response["data"]["server_id"].as_i64(), Some(server_id));
+    assert!(response["data"]["message"].as_str().unwrap().contains("unchanged"), "{}", response);
+
+    let (status, response) = call(&app, "GET", "/api/v1/servers/get_servers", &viewer, None).await;
+    assert_eq!(status, StatusCode::OK, "{}", response);
+    assert_eq!(response["data"].as_array().map(Vec::len), Some(1), "{}", response);
+    assert_eq!(response["meta"]["pagination"]["total_count"], 1, "{}", response);
+
+    let (status, response) = call(&app, "GET", &format!("/api/v1/servers/{}", server_id), &viewer, None).await;
+    assert_eq!(status, StatusCode::OK, "{}", response);
+    assert_eq!(response["data"]["server_name"], "gpu-node-01", "{}", response);
+    assert_eq!(response["data"]["power_supplies"][0]["temperature_c"], 44, "{}", response);
+
+    let vm_inventory = fixture("vm_v1.json");
+    let (status, response) = call(&app, "POST", "/api/v1/vms/inventory", &agent, Some(vm_inventory)).await;
+    assert_eq!(status, StatusCode::OK, "{}", response);
+
+    let (status, response) = call(&app, "GET", "/api/v1/vms/get_vms", &viewer, None).await;
+    assert_eq!(status, StatusCode::OK, "{}", response);
+    let vms = response["data"].as_array().expect("vm list");
+    assert_eq!(vms.len(), 1);
+    assert_eq!(vms[0]["vm_name"], "build-runner-1");
+
+    let path = format!("/api/v1/vms/server/{}", server_id);
+    let (status, response) = call(&app, "GET", &path, &viewer, None).await;
+    assert_eq!(status, StatusCode::OK, "{}", response);
+    assert_eq!(response["data"].as_array().map(Vec::len), Some(1), "{}", response);
+
+    // The agent may only post inventory
+    let (status, _) = call(&app, "GET", "/api/v1/servers/get_servers", &agent, None).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+}
+
+#[actix_web::test]
+async fn a_new_machine_cannot_take_a_known_hostname() {
+    let app = app().await;
+    let agent = issue_token(&app, "agent", "AGENT", None).await;
+    let mut inventory = fixture("inventory_v2.json");
+    inventory["node"]["system_uuid"] = json!("4c4c4544-0042-3510-8056-b7c04f4e3332");
+
+    let (status, response) = call(&app, "POST", "/api/v1/servers/inventory", &agent, Some(inventory.clone())).await;
+    assert_eq!(status, StatusCode::OK, "{}", response);
+    let server_id = response["data"]["server_id"].as_i64().expect("server id");
+
+    // Another system UUID rules the server out, so the report would create a second gpu-node-01
+    let mut other = inventory;
+    other["node"]["system_uuid"] = json!("5b1e2c3d-4f5a-4b6c-8d7e-9f0a1b2c3d4e");
+    let (status, response) = call(&app, "POST", "/api/v1/servers/inventory", &agent, Some(other)).await;
+    assert_eq!(status, StatusCode::CONFLICT, "{}", response);
+    assert_eq!(response["error"]["code"], "IDENTITY_CONFLICT", "{}", response);
+
+    let path = "/api/v1/inventory/identity-conflicts?resolution=REJECTED";
+    let (status, response) = call(&app, "GET", path, BOOTSTRAP_TOKEN, None).await;
+    assert_eq!(status, StatusCode::OK, "{}", response);
+    let conflicts = response["data"].as_array().expect("conflict list");
+    assert_eq!(conflicts.len(), 1, "{}", response);
+    assert_eq!(conflicts[0]["hostname"], "gpu-node-01");
+    assert_eq!(conflicts[0]["candidates"][0]["server_id"].as_i64(), Some(server_id), "{}", response);
+    assert_eq!(conflicts[0]["candidates"][0]["excluded"], true, "{}", response);
+}
+
+#[actix_web::test]
+async fn a_known_machine_cannot_take_another_servers_hostname() {
+    let app = app().await;
+    let agent = issue_token(&app, "agent", "AGENT", None).await;
+    let first = fixture("inventory_v2.json");
+
+    // A second machine sharing no identifier with the first
+    let mut second = first.clone();
+    second["node"]["hostname"] = json!("gpu-node-02");
+    second["node"]["system_uuid"] = json!("5b1e2c3d-4f5a-4b6c-8d7e-9f0a1b2c3d4e");
+    second["node"]["serial_number"] = json!("8YL3QM4");
+    second["node"]["chassis_serial_number"] = json!("8YL3QM4");
+    second["node"]["motherboard"]["serial_number"] = json!(".8YL3QM4.CNFCP0021B0043.");
+    second["network"]["interfaces"][0]["mac_address"] = json!("b4:96:91:aa:20:01");
+
+    for inventory in [&first, &second] {
+        let (status, response) = call(&app, "POST", "/api/v1/servers/inventory", &agent, Some(inventory.clone())).await;
+        assert_eq!(status, StatusCode::OK, "{}", response);
+    }
+
+    // The second machine is recognised, but now reports the first one's hostname
+    let mut renamed = second;
+    renamed["node"]["hostname"] = json!("gpu-node-01");
+    let (status, response) = call(&app, "POST", "/api/v1/servers/inventory", &agent, Some(renamed)).await;
+    assert_eq!(status, StatusCode::CONFLICT, "{}", response);
+    assert_eq!(response["error"]["code"], "IDENTITY_CONFLICT", "{}", response);
+
+    let path = "/api/v1/inventory/identity-conflicts?resolution=REJECTED";
+    let (status, response) = call(&app, "GET", path, BOOTSTRAP_TOKEN, None).await;
+    assert_eq!(status, StatusCode::OK, "{}", response);
+    assert_eq!(response["data"].as_array().map(Vec::len), Some(1), "{}", response);
+    assert_eq!(response["data"][0]["hostname"], "gpu-node-01", "{}", response);
+}
+
+#[actix_web::test]
+async fn a_merge_keeps_the_components_of_the_newer_inventory() {
+    let app = app().await;
+    let agent = issue_token(&app, "agent", "AGENT", None).await;
+    let older = fixture("inventory_v2.json");
+
+    // The same machine inventoried again as a new record, since without its disk
+    let mut newer = second_record(&older);
+    newer["disks"] = json!([]);
+    let server_ids = post_inventories(&app, &agent, &[older, newer]).await;
+
+    let path = format!("/api/v1/servers/{}/merge", server_ids[0]);
+    let body = json!({ "server_ids": [server_ids[1]] });
+    let (status, response) = call(&app, "POST", &path, BOOTSTRAP_TOKEN, Some(body)).await;
+    assert_eq!(status, StatusCode::OK, "{}", response);
+    assert_eq!(response["data"]["discarded"]["server_disks"], 1, "{}", response);
+
+    // The newer snapshot is now the server's latest, and its components are the ones kept
+    let (status, response) = call(&app, "GET", &format!("/api/v1/servers/{}", server_ids[0]), BOOTSTRAP_TOKEN, None).await;
+    assert_eq!(status, StatusCode::OK, "{}", response);
+    assert_eq!(response["data"]["disks"].as_array().map(Vec::len), Some(0), "{}", response);
+    let interfaces = response["data"]["network_interfaces"].as_array().expect("network interfaces");
+    assert_eq!(interfaces.len(), 1, "{}", response);
+    assert_eq!(interfaces[0]["mac_address"], "b4:96:91:aa:20:01", "{}", response);
+}
+
+#[actix_web::test]
+async fn a_dimm_left_out_of_a_report_is_missing_until_reported_again() {
+    let app = app().await;
+    let agent = issue_token(&app, "agent", "AGENT", None).await;
+    let inventory = fixture("inventory_v2.json");
+    let mut without_dimm = inventory.clone();
+    without_dimm["memory"]["dimms"].as_array_mut().expect("dimms").remove(0);
+    let mut reseated = inventory.clone();
+    reseated["memory"]["dimms"][0]["slot"] = json!("B1");
+
+    let server_id = post_inventories(&app, &agent, &[inventory.clone(), without_dimm]).await[0];
+    let missing_path = format!("/api/v1/servers/{}/missing", server_id);
+    let (status, response) = call(&app, "GET", &missing_path, BOOTSTRAP_TOKEN, None).await;
+    assert_eq!(status, StatusCode::OK, "{}", response);
+    let missing = response["data"].as_array().expect("missing components");
+    assert_eq!(missing.len(), 1, "{}", response);
+    assert_eq!(missing[0]["component_type"], "memory", "{}", response);
+    assert_eq!(missing[0]["component_identifier"], "A1", "{}", response);
+    assert_eq!(missing[0]["last_seen_data"]["serial_number"], "4A1B2C3D", "{}", response);
+
+    // Back in another slot: the entry is resolved and nothing new goes missing
+    post_inventories(&app, &agent, &[reseated]).await;
+    let (status, response) = call(&app, "GET", &missing_path, BOOTSTRAP_TOKEN, None).await;
+    assert_eq!(status, StatusCode::OK, "{}", response);
+    assert_eq!(response["data"].as_array().map(Vec::len), Some(0), "{}", response);
+
+    let (status, response) = call(&app, "GET", &format!("{}?include_resolved=true", missing_path), BOOTSTRAP_TOKEN, None).await;
+    assert_eq!(status, StatusCode::OK, "{}", response);
+    let missing = response["data"].as_array().expect("missing components");
+    assert_eq!(missing.len(), 1, "{}", response);
+    assert!(missing[0]["resolved_at"].is_string(), "{}", response);
+}
+
+/// The fixture machine's inventory under another hostname, serial and NIC MAC, so it is
+/// stored as a second record
+fn second_record(inventory: &Value) -> Value {
+    let mut second = inventory.clone();
+    second["node"]["hostname"] = json!("gpu-node-01-reinstalled");
+    second["node"]["serial_number"] = json!("8YL3QM4");
+    second["node"]["chassis_serial_number"] = json!("8YL3QM4");
+    second["node"]["motherboard"]["serial_number"] = json!(".8YL3QM4.CNFCP0021B0043.");
+    second["network"]["interfaces"][0]["mac_address"] = json!("b4:96:91:aa:20:01");
+    second
+}
+
+async fn post_inventories<S, B>(app: &S, agent: &str, inventories: &[Value]) -> Vec<i64>
+where
+    S: Service<actix_http::Request, Response = ServiceResponse<B>, Error = actix_web::Error>,
+    B: MessageBody,
+{
+    let mut server_ids = Vec::new();
+    for inventory in inventories {
+        let (status, response) = call(app, "POST", "/api/v1/servers/inventory", agent, Some(inventory.clone())).await;
+        assert_eq!(status, StatusCode::OK, "{}", response);
+        server_ids.push(response["data"]["server_id"].as_i64().expect("server id"));
+    }
+    server_ids
+}
+
+#[actix_web::test]
+async fn a_merge_is_refused_when_both_servers_run_a_vm_of_the_same_name() {
+    let app = app().await;
+    let agent = issue_token(&app, "agent", "AGENT", None).await;
+    let inventory = fixture("inventory_v2.json");
+    let server_ids = post_inventories(&app, &agent, &[inventory.clone(), second_record(&inventory)]).await;
+
+    let first = fixture("vm_v1.json");
+    let mut second = first.clone();
+    second["host_mac_address"] = json!("b4:96:91:aa:20:01");
+    second["vms"][0]["vm_uuid"] = json!("7a1d3b5c-9e2f-4a6b-8c0d-1e2f3a4b5c6d");
+    second["vms"][0]["network_interfaces"][0]["mac_address"] = json!("52:54:00:12:34:57");
+    for vms in [first, second] {
+        let (status, response) = call(&app, "POST", "/api/v1/vms/inventory", &agent, Some(vms)).await;
+        assert_eq!(status, StatusCode::OK, "{}", response);
+    }
+
+    let path = format!("/api/v1/servers/{}/merge", server_ids[0]);
+    let (status, response) = call(&app, "POST", &path, BOOTSTRAP_TOKEN, Some(json!({ "server_ids": [server_ids[1]] }))).await;
+    assert_eq!(status, StatusCode::CONFLICT, "{}", response);
+    assert_eq!(response["error"]["code"], "MERGE_CONFLICT", "{}", response);
+
+    // Nothing was moved
+    let (status, response) = call(&app, "GET", &format!("/api/v1/vms/server/{}", server_ids[1]), BOOTSTRAP_TOKEN, None).await;
+    assert_eq!(status, StatusCode::OK, "{}", response);
+    assert_eq!(response["data"].as_array().map(Vec::len), Some(1), "{}", response);
+}
+
+#[actix_web::test]
+async fn servers_with_different_system_uuids_are_not_merged() {
+    let app = app().await;
+    let agent = issue_token(&app, "agent", "AGENT", None).await;
+    let mut inventory = fixture("inventory_v2.json");
+    inventory["node"]["system_uuid"] = json!("4c4c4544-0042-3510-8056-b7c04f4e3332");
+    let mut second = second_record(&inventory);
+    second["node"]["system_uuid"] = json!("5b1e2c3d-4f5a-4b6c-8d7e-9f0a1b2c3d4e");
+    let server_ids = post_inventories(&app, &agent, &[inventory, second]).await;
+
+    let path = format!("/api/v1/servers/{}/merge", server_ids[0]);
+    let (status, response) = call(&app, "POST", &path, BOOTSTRAP_TOKEN, Some(json!({ "server_ids": [server_ids[1]] }))).await;
+    assert_eq!(status, StatusCode::CONFLICT, "{}", response);
+    assert_eq!(response["error"]["code"], "MERGE_CONFLICT", "{}", response);
+}
+
+#[actix_web::test]
+async fn a_dry_run_merge_reports_without_changing_anything() {
+    let app = app().await;
+    let agent = issue_token(&app, "agent", "AGENT", None).await;
+    let inventory = fixture("inventory_v2.json");
+    let server_ids = post_inventories(&app, &agent, &[inventory.clone(), second_record(&inventory)]).await;
+
+    let path = format!("/api/v1/servers/{}/merge?dry_run=true", server_ids[0]);
+    let (status, response) = call(&app, "POST", &path, BOOTSTRAP_TOKEN, Some(json!({ "server_ids": [server_ids[1]] }))).await;
+    assert_eq!(status, StatusCode::OK, "{}", response);
+    assert_eq!(response["data"]["dry_run"], true, "{}", response);
+    assert_eq!(response["data"]["discarded"]["server_network_interfaces"], 1, "{}", response);
+
+    // Both records are still there, each with its own components
+    for (server_id, mac_address) in server_ids.iter().zip(["b4:96:91:aa:10:01", "b4:96:91:aa:20:01"]) {
+        let (status, response) = call(&app, "GET", &format!("/api/v1/servers/{}", server_id), BOOTSTRAP_TOKEN, None).await;
+        assert_eq!(status, StatusCode::OK, "{}", response);
+        assert_eq!(response["data"]["network_interfaces"][0]["mac_address"], mac_address, "{}", response);
+        assert_eq!(response["data"]["disks"].as_array().map(Vec::len), Some(1), "{}", response);
+    }
+}
+
+#[actix_web::test]
+async fn mutating_requests_are_audited() {
+    let app = app().await;
+    let viewer = issue_token(&app, "viewer", "API", Some("VIEWER")).await;
+
+    let (status, _) = call(&app, "GET", "/api/v1/audit", &viewer, None).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    let (status, response) = call(&app, "GET", "/api/v1/audit", BOOTSTRAP_TOKEN, None).await;
+    assert_eq!(status, StatusCode::OK, "{}", response);
+    let events = response["data"].as_array().expect("audit events");
+    let issued = events
+        .iter()
+        .find(|e| e["http_method"] == "POST" && e["request_path"] == "/api/v1/tokens")
+        .unwrap_or_else(|| panic!("token creation not audited: {}", response));
+    assert_eq!(issued["principal_role"], "ADMIN");
+    assert_eq!(issued["result"], "SUCCESS");
+
+    let audit_id = issued["audit_id"].as_i64().unwrap();
+    let (status, response) = call(&app, "GET", &format!("/api/v1/audit/{}", audit_id), BOOTSTRAP_TOKEN, None).await;
+    assert_eq!(status, StatusCode::OK, "{}", response);
+    assert_eq!(response["data"]["audit_id"].as_i64(), Some(audit_id));
+}
+
+#[actix_web::test]
+async fn metrics_require_a_token() {
+    let app = app().await;
+    let viewer = issue_token(&app, "prometheus", "API", Some("VIEWER")).await;
+    let agent = issue_token(&app, "agent", "AGENT", None).await;
+
+    for path in ["/metrics", "/metrics/fleet"] {
+        let (status, _) = call(&app, "GET", path, "not-a-token", None).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED, "{}", path);
+        let (status, _) = call(&app, "GET", path, &agent, None).await;
+        assert_eq!(status, StatusCode::FORBIDDEN, "{}", path);
+        let (status, _) = call(&app, "GET", path, &viewer, None).await;
+        assert_eq!(status, StatusCode::OK, "{}", path);
+    }
+}
+
+#[actix_web::test]
+async fn topology_is_planned_and_applied_on_sqlite() {
+    let app = app().await;
+    let document = json!({
+        "datacenters": [{
+            "data_center_code": "AMS1",
+            "data_center_name": "Amsterdam 1",
+            "country": "NL",
+            "latitude": 52.3676,
+            "longitude": 4.9041,
+            "racks": [{ "rack_code": "R01", "rack_name": "Rack 01", "rack_height_u": 42 }],
+        }],
+        "clusters": [{
+            "cluster_code": "TRAIN",
+            "cluster_name": "Training",
+            "sub_clusters": [{ "sub_cluster_code": "A", "sub_cluster_name": "Pod A" }],
+        }],
+        "switches": [{ "switch_name": "leaf-01", "vlans": [{ "vlan_id": 100, "vlan_name": "mgmt" }] }],
+    });
+
+    let (status, response) = call(&app, "POST", "/api/v1/topology/plan", BOOTSTRAP_TOKEN, Some(document.clone())).await;
+    assert_eq!(status, StatusCode::OK, "{}", response);
+    assert_eq!(response["data"]["summary"]["create"], 6, "{}", response);
+    let fingerprint = response["data"]["fingerprint"].as_str().expect("fingerprint").to_string();
+
+    let path = format!("/api/v1/topology/apply?plan={}", fingerprint);
+    let (status, response) = call(&app, "POST", &path, BOOTSTRAP_TOKEN, Some(document.clone())).await;
+    assert_eq!(status, StatusCode::OK, "{}", response);
+    assert_eq!(response["data"]["applied"], true, "{}", response);
+
+    // Applied as planned, so the DECIMAL coordinates read back unchanged and nothing drifts
+    let (status, response) = call(&app, "POST", "/api/v1/topology/plan", BOOTSTRAP_TOKEN, Some(document)).await;
+    assert_eq!(status, StatusCode::OK, "{}", response);
+    assert_eq!(response["data"]["summary"]["create"], 0, "{}", response);
+    assert_eq!(response["data"]["summary"]["update"], 0, "{}", response);
+    assert_eq!(response["data"]["summary"]["unchanged"], 6, "{}", response);
+
+    let (status, response) = call(&app, "GET", "/api/v1/datacenters/list", BOOTSTRAP_TOKEN, None).await;
+    assert_eq!(status, StatusCode::OK, "{}", response);
+    let datacenter = &response["data"][0];
+    assert_eq!(datacenter["data_center_code"], "AMS1", "{}", response);
+    assert_eq!(datacenter["latitude"], "52.3676", "{}", response);
+    let datacenter_id = datacenter["data_center_id"].as_i64().expect("datacenter id");
+
+    let (status, response) = call(&app, "GET", &format!("/api/v1/datacenters/{}/racks", datacenter_id), BOOTSTRAP_TOKEN, None).await;
+    assert_eq!(status, StatusCode::OK, "{}", response);
+    let rack_id = response["data"][0]["rack_id"].as_i64().expect("rack id");
+
+    let position = json!({
+        "rack_position_id": 0, "rack_id": rack_id, "u_position": 10, "status": "RESERVED",
+        "reserved_for": "storage", "reservation_notes": null, "server_id": null,
+        "device_type": "STORAGE", "notes": null, "created_at": null, "updated_at": null,
+    });
+    let path = format!("/api/v1/datacenters/racks/{}/positions", rack_id);
+    let (status, response) = call(&app, "POST", &path, BOOTSTRAP_TOKEN, Some(position.clone())).await;
+    assert_eq!(status, StatusCode::CREATED, "{}", response);
+    let (status, response) = call(&app, "POST", &path, BOOTSTRAP_TOKEN, Some(position)).await;
+    assert_eq!(status, StatusCode::CONFLICT, "{}", response);
+
+    let (status, response) = call(&app, "GET", &path, BOOTSTRAP_TOKEN, None).await;
+    assert_eq!(status, StatusCode::OK, "{}", response);
+    assert_eq!(response["data"].as_array().map(Vec::len), Some(1), "{}", response);
+
+    // The rack's one position as a single NDJSON line
+    let export = format!("{}?format=ndjson&columns=u_position,status", path);
+    let (status, response) = call(&app, "GET", &export, BOOTSTRAP_TOKEN, None).await;
+    assert_eq!(status, StatusCode::OK, "{}", response);
+    assert_eq!(response, json!({ "u_position": 10, "status": "RESERVED" }));
+    let (status, response) = call(&app, "GET", &format!("{}?format=xml", path), BOOTSTRAP_TOKEN, None).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST, "{}", response);
+
+    let (status, response) = call(&app, "GET", "/api/v1/clusters/list", BOOTSTRAP_TOKEN, None).await;
+    assert_eq!(status, StatusCode::OK, "{}", response);
+    assert_eq!(response["data"][0]["cluster_code"], "TRAIN", "{}", response);
+
+    let (status, response) = call(&app, "GET", "/api/v1/switches/get_switches", BOOTSTRAP_TOKEN, None).await;
+    assert_eq!(status, StatusCode::OK, "{}", response);
+    assert_eq!(response["data"][0]["switch_name"], "leaf-01", "{}", response);
+}
+
+#[actix_web::test]
+async fn components_and_kubernetes_are_served_on_sqlite() {
+    let app = app().await;
+    let agent = issue_token(&app, "agent", "AGENT", None).await;
+
+    let (status, response) = call(&app, "POST", "/api/v1/servers/inventory", &agent, Some(fixture("inventory_v2.json"))).await;
+    assert_eq!(status, StatusCode::OK, "{}", response);
+
+    let (status, response) = call(&app, "GET", "/api/v1/components/cpus", BOOTSTRAP_TOKEN, None).await;
+    assert_eq!(status, StatusCode::OK, "{}", response);
+    assert!(!response["data"].as_array().expect("cpu types").is_empty(), "{}", response);
+
+    let (status, response) = call(&app, "POST", "/api/v1/k8s/inventory", &agent, Some(fixture("k8s_v1.json"))).await;
+    assert_eq!(status, StatusCode::OK, "{}", response);
+    let cluster_id = response["data"]["cluster_id"].as_i64().expect("cluster id");
+
+    let path = format!("/api/v1/k8s/clusters/{}/nodes", cluster_id);
+    let (status, response) = call(&app, "GET", &path, BOOTSTRAP_TOKEN, None).await;
+    assert_eq!(status, StatusCode::OK, "{}", response);
+    assert_eq!(response["data"][0]["node_name"], "gpu-node-01", "{}", response);
+}
+
+#[actix_web::test]
+async fn migrations_run_on_sqlite_and_seeds_are_mysql_only() {
+    let app = app().await;
+
+    let (status, response) = call(&app, "POST", "/api/v1/migrations/run", BOOTSTRAP_TOKEN, None).await;
+    assert_eq!(status, StatusCode::OK, "{}", response);
+
+    // A reset drops every table; running the migrations again rebuilds them
+    let (status, response) = call(&app, "POST", "/api/v1/migrations/reset", BOOTSTRAP_TOKEN, None).await;
+    assert_eq!(status, StatusCode::OK, "{}", response);
+    let (status, response) = call(&app, "POST", "/api/v1/migrations/run", BOOTSTRAP_TOKEN, None).await;
+    assert_eq!(status, StatusCode::OK, "{}", response);
+
+    let (status, response) = call(&app, "POST", "/api/v1/migrations/seed", BOOTSTRAP_TOKEN, None).await;
+    assert_eq!(status, StatusCode::NOT_IMPLEMENTED, "{}", response);
+    assert_eq!(response["error"]["code"], "NOT_SUPPORTED", "{}", response);
+}
@@ -0,0 +1,174 @@
+//! Webhook deliveries against a local receiver: the outbox row exists as soon as the change is
+//! answered, requests carry a valid X-Farm-Signature, and a failed attempt is scheduled for a
+//! retry while a successful one is marked delivered.
+mod common;
+
+use actix_web::body::MessageBody;
+use actix_web::dev::{Service, ServiceResponse};
+use actix_web::http::StatusCode;
+use actix_web::{web, App, HttpRequest, HttpResponse, HttpServer};
+use chrono::{DateTime, Utc};
+use common::{call, fixture, issue_token, service, state, BOOTSTRAP_TOKEN};
+use farm_core::domain::WebhookDispatcher;
+use hmac::{Hmac, Mac};
+use serde_json::{json, Value};
+use sha2::Sha256;
+use std::collections::VecDeque;
+use std::net::TcpListener;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+const SECRET: &str = "receiver-secret";
+
+/// A request as the receiver got it
+#[derive(Debug, Clone)]
+struct Received {
+    event: String,
+    timestamp: String,
+    signature: String,
+    body: Vec<u8>,
+}
+
+/// A local webhook receiver answering with the queued statuses, then 204
+#[derive(Clone, Default)]
+struct Receiver {
+    requests: Arc<Mutex<Vec<Received>>>,
+    statuses: Arc<Mutex<VecDeque<u16>>>,
+}
+
+impl Receiver {
+    fn start(statuses: &[u16]) -> (Self, String) {
+        let receiver = Receiver::default();
+        receiver.statuses.lock().unwrap().extend(statuses);
+
+        let listener = TcpListener::bind("127.0.0.1:0").expect("receiver port");
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        let shared = receiver.clone();
+        let server = HttpServer::new(move || {
+            App::new()
+                .app_data(web::Data::new(shared.clone()))
+                .route("/hook", web::post().to(receive))
+        })
+        .workers(1)
+        .listen(listener)
+        .expect("receiver listens")
+        .run();
+        actix_web::rt::spawn(server);
+        (receiver, url)
+    }
+
+    /// Wait until at least `count` requests have arrived
+    async fn wait_for(&self, count: usize) -> Vec<Received> {
+        for _ in 0..100 {
+            let requests = self.requests.lock().unwrap().clone();
+            if requests.len() >= count {
+                return requests;
+            }
+            actix_web::rt::time::sleep(Duration::from_millis(50)).await;
+        }
+        panic!("receiver got fewer than {} requests", count);
+    }
+}
+
+async fn receive(receiver: web::Data<Receiver>, req: HttpRequest, body: web::Bytes) -> HttpResponse {
+    let header = |name: &str| {
+        req.headers().get(name).and_then(|value| value.to_str().ok()).unwrap_or_default().to_string()
+    };
+    receiver.requests.lock().unwrap().push(Received {
+        event: header("X-Farm-Event"),
+        timestamp: header("X-Farm-Timestamp"),
+        signature: header("X-Farm-Signature"),
+        body: body.to_vec(),
+    });
+    let status = receiver.statuses.lock().unwrap().pop_front().unwrap_or(204);
+    HttpResponse::build(StatusCode::from_u16(status).unwrap()).finish()
+}
+
+/// What a receiver holding the secret computes to check a request
+fn assert_signed(request: &Received) {
+    let mut mac = Hmac::<Sha256>::new_from_slice(SECRET.as_bytes()).unwrap();
+    mac.update(request.timestamp.as_bytes());
+    mac.update(b".");
+    mac.update(&request.body);
+    let expected: String = mac.finalize().into_bytes().iter().map(|b| format!("{:02x}", b)).collect();
+    assert_eq!(request.signature, format!("sha256={}", expected), "{:?}", request);
+}
+
+/// Poll the delivery log until the dispatcher has recorded `attempts` attempts
+async fn wait_for_attempts<S, B>(app: &S, delivery_id: i64, attempts: i64) -> Value
+where
+    S: Service<actix_http::Request, Response = ServiceResponse<B>, Error = actix_web::Error>,
+    B: MessageBody,
+{
+    let path = format!("/api/v1/webhooks/deliveries/{}", delivery_id);
+    for _ in 0..100 {
+        let (status, response) = call(app, "GET", &path, BOOTSTRAP_TOKEN, None).await;
+        assert_eq!(status, StatusCode::OK, "{}", response);
+        if response["data"]["attempts"].as_i64() >= Some(attempts) {
+            return response["data"].clone();
+        }
+        actix_web::rt::time::sleep(Duration::from_millis(50)).await;
+    }
+    panic!("delivery {} never reached {} attempts", delivery_id, attempts);
+}
+
+fn timestamp(value: &Value) -> DateTime<Utc> {
+    value.as_str().and_then(|text| text.parse().ok()).unwrap_or_else(|| panic!("not a timestamp: {}", value))
+}
+
+#[actix_web::test]
+async fn deliveries_are_queued_signed_and_retried() {
+    let (receiver, url) = Receiver::start(&[500]);
+    let state = state().await;
+    let app = service(&state).await;
+    let agent = issue_token(&app, "agent", "AGENT", None).await;
+
+    let body = json!({ "webhook_name": "receiver", "url": url, "event_types": ["server.*"], "secret": SECRET });
+    let (status, response) = call(&app, "POST", "/api/v1/webhooks", BOOTSTRAP_TOKEN, Some(body)).await;
+    assert_eq!(status, StatusCode::CREATED, "{}", response);
+    let webhook_id = response["data"]["webhook_id"].as_i64().expect("webhook id");
+
+    // The delivery is in the outbox once the agent has its answer, with no dispatcher running
+    let inventory = fixture("inventory_v2.json");
+    let (status, response) = call(&app, "POST", "/api/v1/servers/inventory", &agent, Some(inventory)).await;
+    assert_eq!(status, StatusCode::OK, "{}", response);
+    let (status, response) = call(&app, "GET", "/api/v1/webhooks/deliveries", BOOTSTRAP_TOKEN, None).await;
+    assert_eq!(status, StatusCode::OK, "{}", response);
+    let deliveries = response["data"].as_array().expect("delivery list");
+    assert_eq!(deliveries.len(), 1, "{}", response);
+    assert_eq!(deliveries[0]["event_type"], "server.created");
+    assert_eq!(deliveries[0]["status"], "PENDING");
+    assert_eq!(deliveries[0]["attempts"], 0);
+    let delivery_id = deliveries[0]["delivery_id"].as_i64().expect("delivery id");
+
+    WebhookDispatcher::new(state.webhook_repo(), state.webhook_outbox().clone()).spawn();
+
+    // The receiver fails the first attempt, so the delivery is rescheduled after the base backoff
+    let requests = receiver.wait_for(1).await;
+    assert_eq!(requests[0].event, "server.created");
+    assert_signed(&requests[0]);
+    let payload: Value = serde_json::from_slice(&requests[0].body).unwrap();
+    assert_eq!(payload["type"], "server.created");
+
+    let delivery = wait_for_attempts(&app, delivery_id, 1).await;
+    assert_eq!(delivery["status"], "PENDING", "{}", delivery);
+    assert_eq!(delivery["last_status_code"], 500);
+    let retry_in = timestamp(&delivery["next_attempt_at"]) - Utc::now();
+    assert!(retry_in > chrono::Duration::seconds(5) && retry_in <= chrono::Duration::seconds(10), "{}", delivery);
+
+    // A ping is sent straight away and succeeds
+    let path = format!("/api/v1/webhooks/{}/ping", webhook_id);
+    let (status, response) = call(&app, "POST", &path, BOOTSTRAP_TOKEN, None).await;
+    assert_eq!(status, StatusCode::ACCEPTED, "{}", response);
+    let ping_id = response["data"]["delivery_id"].as_i64().expect("delivery id");
+
+    let requests = receiver.wait_for(2).await;
+    assert_eq!(requests[1].event, "webhook.ping");
+    assert_signed(&requests[1]);
+
+    let delivery = wait_for_attempts(&app, ping_id, 1).await;
+    assert_eq!(delivery["status"], "SUCCEEDED", "{}", delivery);
+    assert_eq!(delivery["last_status_code"], 204);
+    assert_eq!(delivery["next_attempt_at"], Value::Null);
+    assert!(delivery["delivered_at"].is_string(), "{}", delivery);
+}
@@ -77,6 +77,10 @@ pub enum HardwareCommands {
         /// FarmCore API base URL
         #[arg(short, long, default_value = "http://localhost:6183")]
         url: String,
+
+        /// AGENT-scoped API token for FarmCore
+        #[arg(short, long, env = "FARM_AGENT_TOKEN", hide_env_values = true)]
+        token: Option<String>,
     },
     /// Get primary disk (smallest, slowest, or lowest numbered)
     PrimaryDisk {
@@ -372,6 +376,10 @@ pub enum VmCommands {
         /// FarmCore API base URL
         #[arg(short, long, default_value = "http://localhost:6183")]
         url: String,
+
+        /// AGENT-scoped API token for FarmCore
+        #[arg(short, long, env = "FARM_AGENT_TOKEN", hide_env_values = true)]
+        token: Option<String>,
         
         /// Hypervisor type (kvm, qemu, vmware, virtualbox)
         #[arg(short = 'H', long, default_value = "kvm")]
@@ -561,5 +569,9 @@ pub enum K8sCommands {
         /// FarmCore API base URL
         #[arg(short, long, default_value = "http://localhost:6183")]
         url: String,
+
+        /// AGENT-scoped API token for FarmCore
+        #[arg(short, long, env = "FARM_AGENT_TOKEN", hide_env_values = true)]
+        token: Option<String>,
     },
 }
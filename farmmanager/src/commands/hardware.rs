@@ -41,7 +41,7 @@ pub fn handle_hardware_command(cmd: &HardwareCommands) -> Result<(), Box<dyn std
             let power_info = collect_power_supplies();
             output_data(&power_info, format)?;
         }
-        HardwareCommands::PostInventory { url } => {
+        HardwareCommands::PostInventory { url, token } => {
             println!("Collecting hardware inventory...");
             let inventory = collect_full_inventory();
             
@@ -49,10 +49,13 @@ pub fn handle_hardware_command(cmd: &HardwareCommands) -> Result<(), Box<dyn std
             println!("Posting inventory to: {}", api_url);
             
             let client = reqwest::blocking::Client::new();
-            let response = client
+            let mut request = client
                 .post(&api_url)
-                .json(&inventory)
-                .send()?;
+                .json(&inventory);
+            if let Some(token) = token {
+                request = request.bearer_auth(token);
+            }
+            let response = request.send()?;
             
             if response.status().is_success() {
                 let result: serde_json::Value = response.json()?;
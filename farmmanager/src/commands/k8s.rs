@@ -2,8 +2,11 @@ use crate::cli::K8sCommands;
 use crate::output::output_data;
 use std::io::{self, Write};
 use std::process::Command;
-use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use farm_proto::k8s::{
+    K8sInventory, K8sNamespaceInventory, K8sNodeInventory, K8sPodInventory, K8sServiceInventory,
+    K8sWorkloadInventory,
+};
 
 pub fn handle_k8s_command(cmd: &K8sCommands) -> Result<(), Box<dyn std::error::Error>> {
     match cmd {
@@ -60,7 +63,7 @@ pub fn handle_k8s_command(cmd: &K8sCommands) -> Result<(), Box<dyn std::error::E
             output_data(&inventory, format)?;
         }
         
-        K8sCommands::PostInventory { url } => {
+        K8sCommands::PostInventory { url, token } => {
             println!("Collecting Kubernetes cluster inventory...");
             let inventory = collect_k8s_inventory()?;
             
@@ -68,10 +71,13 @@ pub fn handle_k8s_command(cmd: &K8sCommands) -> Result<(), Box<dyn std::error::E
             println!("Posting K8s inventory to: {}", api_url);
             
             let client = reqwest::blocking::Client::new();
-            let response = client
+            let mut request = client
                 .post(&api_url)
-                .json(&inventory)
-                .send()?;
+                .json(&inventory);
+            if let Some(token) = token {
+                request = request.bearer_auth(token);
+            }
+            let response = request.send()?;
             
             if response.status().is_success() {
                 let result: serde_json::Value = response.json()?;
@@ -402,95 +408,6 @@ fn execute_kubectl(args: &[&str], format: &str) -> Result<(), Box<dyn std::error
     Ok(())
 }
 
-// ===================================================================
-// INVENTORY STRUCTURES (Matching Backend Schema)
-// ===================================================================
-
-#[derive(Debug, Serialize)]
-struct K8sInventory {
-    cluster_name: String,
-    cluster_version: String,
-    api_server: String,
-    nodes: Vec<K8sNodeInventory>,
-    namespaces: Vec<K8sNamespaceInventory>,
-    pods: Option<Vec<K8sPodInventory>>,
-    services: Option<Vec<K8sServiceInventory>>,
-    workloads: Option<Vec<K8sWorkloadInventory>>,
-}
-
-#[derive(Debug, Serialize)]
-struct K8sNodeInventory {
-    node_name: String,
-    node_uid: Option<String>,
-    node_type: Option<String>,
-    internal_ip: Option<String>,
-    external_ip: Option<String>,
-    hostname: Option<String>,
-    cpu_capacity: Option<i32>,
-    memory_capacity_mb: Option<i64>,
-    pod_capacity: Option<i32>,
-    node_state: Option<String>,
-    kubelet_version: Option<String>,
-    os_image: Option<String>,
-    kernel_version: Option<String>,
-    container_runtime_version: Option<String>,
-    roles: Option<String>,
-    labels: Option<serde_json::Value>,
-    taints: Option<serde_json::Value>,
-    gpu_count: Option<i32>,
-    gpu_product: Option<String>,
-    gpu_memory_mb: Option<i32>,
-}
-
-#[derive(Debug, Serialize)]
-struct K8sNamespaceInventory {
-    namespace_name: String,
-    namespace_uid: Option<String>,
-    namespace_state: Option<String>,
-    labels: Option<serde_json::Value>,
-    annotations: Option<serde_json::Value>,
-}
-
-#[derive(Debug, Serialize)]
-struct K8sPodInventory {
-    pod_name: String,
-    pod_uid: Option<String>,
-    namespace: String,
-    pod_phase: Option<String>,
-    node_name: Option<String>,
-    pod_ip: Option<String>,
-    host_ip: Option<String>,
-    start_time: Option<String>,
-    labels: Option<serde_json::Value>,
-    annotations: Option<serde_json::Value>,
-}
-
-#[derive(Debug, Serialize)]
-struct K8sServiceInventory {
-    service_name: String,
-    service_uid: Option<String>,
-    namespace: String,
-    service_type: Option<String>,
-    cluster_ip: Option<String>,
-    external_ip: Option<String>,
-    ports: Option<serde_json::Value>,
-    selector: Option<serde_json::Value>,
-    labels: Option<serde_json::Value>,
-}
-
-#[derive(Debug, Serialize)]
-struct K8sWorkloadInventory {
-    workload_name: String,
-    workload_uid: Option<String>,
-    namespace: String,
-    workload_type: String,
-    replicas_desired: Option<i32>,
-    replicas_ready: Option<i32>,
-    replicas_available: Option<i32>,
-    labels: Option<serde_json::Value>,
-    selector: Option<serde_json::Value>,
-}
-
 // ===================================================================
 // INVENTORY COLLECTION
 // ===================================================================
@@ -549,6 +466,7 @@ fn collect_k8s_inventory() -> Result<K8sInventory, Box<dyn std::error::Error>> {
     println!("  Workloads: {}", workloads.as_ref().map(|w| w.len()).unwrap_or(0));
     
     Ok(K8sInventory {
+        schema_version: farm_proto::SCHEMA_VERSION,
         cluster_name,
         cluster_version,
         api_server,
@@ -5,6 +5,7 @@ use std::io::{self, Write};
 use std::process::Command;
 use std::fs;
 use std::path::Path;
+use farm_proto::vm::{VmDiskInventory, VmInventory, VmInventoryDetail, VmNetworkInventory};
 
 #[derive(Debug, Serialize, Deserialize)]
 struct VmInfo {
@@ -53,7 +54,7 @@ pub fn handle_vm_command(cmd: &VmCommands) -> Result<(), Box<dyn std::error::Err
             reboot_vm(name, hypervisor, *force)?;
         }
         
-        VmCommands::PostInventory { url, hypervisor } => {
+        VmCommands::PostInventory { url, hypervisor, token } => {
             println!("Collecting VM inventory...");
             let inventory = collect_vm_inventory(hypervisor)?;
             
@@ -63,10 +64,13 @@ pub fn handle_vm_command(cmd: &VmCommands) -> Result<(), Box<dyn std::error::Err
             println!("Posting VM inventory to: {}", api_url);
             
             let client = reqwest::blocking::Client::new();
-            let response = client
+            let mut request = client
                 .post(&api_url)
-                .json(&inventory)
-                .send()?;
+                .json(&inventory);
+            if let Some(token) = token {
+                request = request.bearer_auth(token);
+            }
+            let response = request.send()?;
             
             if response.status().is_success() {
                 let result: serde_json::Value = response.json()?;
@@ -516,46 +520,6 @@ fn parse_virsh_dominfo(output: &str) -> Result<serde_json::Value, Box<dyn std::e
     
     Ok(serde_json::Value::Object(info))
 }
-// Structures for VM inventory
-#[derive(Debug, Serialize, Deserialize)]
-struct VmInventory {
-    host_mac_address: String,
-    hypervisor_type: String,
-    vms: Vec<VmDetail>,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-struct VmDetail {
-    vm_name: String,
-    vm_uuid: Option<String>,
-    vm_state: Option<String>,
-    hypervisor_type: String,
-    vcpu_count: Option<i32>,
-    memory_mb: Option<i32>,
-    guest_os_family: Option<String>,
-    disks: Vec<VmDiskDetail>,
-    network_interfaces: Vec<VmNetworkDetail>,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-struct VmDiskDetail {
-    disk_name: String,
-    disk_type: Option<String>,
-    disk_format: Option<String>,
-    disk_size_gb: Option<i32>,
-    disk_path: String,
-    is_bootable: Option<bool>,
-    storage_type: Option<String>,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-struct VmNetworkDetail {
-    interface_name: String,
-    mac_address: Option<String>,
-    interface_type: Option<String>,
-    network_bridge: Option<String>,
-}
-
 // Collect VM inventory from the system
 /// Get the primary network interface MAC address of the host
 fn get_host_primary_mac() -> Result<String, Box<dyn std::error::Error>> {
@@ -633,6 +597,7 @@ fn collect_kvm_inventory(host_mac_address: String) -> Result<VmInventory, Box<dy
     }
     
     Ok(VmInventory {
+        schema_version: farm_proto::SCHEMA_VERSION,
         host_mac_address,
         hypervisor_type: "KVM".to_string(),
         vms,
@@ -640,7 +605,7 @@ fn collect_kvm_inventory(host_mac_address: String) -> Result<VmInventory, Box<dy
 }
 
 // Collect detailed information for a single KVM VM
-fn collect_kvm_vm_detail(vm_name: &str) -> Result<VmDetail, Box<dyn std::error::Error>> {
+fn collect_kvm_vm_detail(vm_name: &str) -> Result<VmInventoryDetail, Box<dyn std::error::Error>> {
     // Get VM info
     let dominfo_output = Command::new("virsh")
         .args(&["dominfo", vm_name])
@@ -683,7 +648,7 @@ fn collect_kvm_vm_detail(vm_name: &str) -> Result<VmDetail, Box<dyn std::error::
     // Get guest OS info if possible
     let guest_os_family = detect_guest_os(vm_name);
     
-    Ok(VmDetail {
+    Ok(VmInventoryDetail {
         vm_name: vm_name.to_string(),
         vm_uuid,
         vm_state,
@@ -697,7 +662,7 @@ fn collect_kvm_vm_detail(vm_name: &str) -> Result<VmDetail, Box<dyn std::error::
 }
 
 // Collect disk information for a KVM VM
-fn collect_kvm_vm_disks(vm_name: &str) -> Result<Vec<VmDiskDetail>, Box<dyn std::error::Error>> {
+fn collect_kvm_vm_disks(vm_name: &str) -> Result<Vec<VmDiskInventory>, Box<dyn std::error::Error>> {
     let output = Command::new("virsh")
         .args(&["domblklist", vm_name, "--details"])
         .output()?;
@@ -723,7 +688,7 @@ fn collect_kvm_vm_disks(vm_name: &str) -> Result<Vec<VmDiskDetail>, Box<dyn std:
             // Determine disk format from file extension or qemu-img
             let disk_format = detect_disk_format(&source);
             
-            disks.push(VmDiskDetail {
+            disks.push(VmDiskInventory {
                 disk_name: target.clone(),
                 disk_type: Some(detect_disk_type(&target)),
                 disk_format: Some(disk_format),
@@ -739,7 +704,7 @@ fn collect_kvm_vm_disks(vm_name: &str) -> Result<Vec<VmDiskDetail>, Box<dyn std:
 }
 
 // Collect network interface information for a KVM VM
-fn collect_kvm_vm_networks(vm_name: &str) -> Result<Vec<VmNetworkDetail>, Box<dyn std::error::Error>> {
+fn collect_kvm_vm_networks(vm_name: &str) -> Result<Vec<VmNetworkInventory>, Box<dyn std::error::Error>> {
     let output = Command::new("virsh")
         .args(&["domiflist", vm_name])
         .output()?;
@@ -759,7 +724,7 @@ fn collect_kvm_vm_networks(vm_name: &str) -> Result<Vec<VmNetworkDetail>, Box<dy
             let source = parts[2].to_string();
             let mac_address = parts[3].to_string();
             
-            interfaces.push(VmNetworkDetail {
+            interfaces.push(VmNetworkInventory {
                 interface_name: interface_name.clone(),
                 mac_address: Some(mac_address),
                 interface_type: Some(normalize_interface_type(&interface_type)),
@@ -905,7 +870,7 @@ fn collect_virtualbox_inventory(host_mac_address: String) -> Result<VmInventory,
                 
                 // For VirtualBox, we'd need more detailed parsing
                 // This is a simplified version
-                vms.push(VmDetail {
+                vms.push(VmInventoryDetail {
                     vm_name: vm_name.to_string(),
                     vm_uuid: None,
                     vm_state: None,
@@ -921,6 +886,7 @@ fn collect_virtualbox_inventory(host_mac_address: String) -> Result<VmInventory,
     }
     
     Ok(VmInventory {
+        schema_version: farm_proto::SCHEMA_VERSION,
         host_mac_address,
         hypervisor_type: "VirtualBox".to_string(),
         vms,
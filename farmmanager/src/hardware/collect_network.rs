@@ -367,7 +367,7 @@ fn collect_routes() -> Vec<RouteInfo> {
     };
 
     for r in arr {
-        let dst = r
+        let destination = r
             .get("dst")
             .and_then(|v| v.as_str())
             .unwrap_or("default")
@@ -375,15 +375,15 @@ fn collect_routes() -> Vec<RouteInfo> {
         let gateway = r
             .get("gateway")
             .and_then(|v| v.as_str())
-            .unwrap_or("")
-            .to_string();
-        let iface = r
+            .filter(|gateway| !gateway.is_empty())
+            .map(str::to_string);
+        let interface = r
             .get("dev")
             .and_then(|v| v.as_str())
             .unwrap_or("")
             .to_string();
 
-        routes.push(RouteInfo { dst, gateway, iface });
+        routes.push(RouteInfo { destination, gateway, interface });
     }
 
     routes
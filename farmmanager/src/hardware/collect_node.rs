@@ -8,25 +8,38 @@ pub fn collect_node_info() -> NodeInfo {
     let architecture = std::env::consts::ARCH.to_string();
     
     // Collect all DMI information using smbios-lib
-    let (product_name, manufacturer, serial_number, chassis_manufacturer, chassis_serial_number, motherboard, bios) = 
-        collect_dmi_info();
+    let dmi = collect_dmi_info();
     
     let bmc = Some(collect_bmc_from_dmi());
 
     NodeInfo {
         hostname,
         architecture,
-        product_name,
-        manufacturer,
-        serial_number,
-        chassis_manufacturer,
-        chassis_serial_number,
-        motherboard,
-        bios,
+        product_name: dmi.product_name,
+        manufacturer: dmi.manufacturer,
+        serial_number: dmi.serial_number,
+        system_uuid: dmi.system_uuid,
+        chassis_manufacturer: dmi.chassis_manufacturer,
+        chassis_serial_number: dmi.chassis_serial_number,
+        motherboard: dmi.motherboard,
+        bios: dmi.bios,
         bmc,
     }
 }
 
+/// What the SMBIOS tables say about the machine itself
+#[derive(Default)]
+struct DmiInfo {
+    product_name: Option<String>,
+    manufacturer: Option<String>,
+    serial_number: Option<String>,
+    system_uuid: Option<String>,
+    chassis_manufacturer: Option<String>,
+    chassis_serial_number: Option<String>,
+    motherboard: Option<MotherboardInfo>,
+    bios: Option<BiosInfo>,
+}
+
 fn get_hostname() -> String {
     fs::read_to_string("/proc/sys/kernel/hostname")
         .unwrap_or_else(|_| "unknown".to_string())
@@ -34,7 +47,7 @@ fn get_hostname() -> String {
         .to_string()
 }
 
-fn collect_dmi_info() -> (Option<String>, Option<String>, Option<String>, Option<String>, Option<String>, Option<MotherboardInfo>, Option<BiosInfo>) {
+fn collect_dmi_info() -> DmiInfo {
     // Try to load SMBIOS data from the system
     let smbios_data = match SMBiosData::try_load_from_file("/sys/firmware/dmi/tables/DMI", None) {
         Ok(data) => data,
@@ -44,16 +57,12 @@ fn collect_dmi_info() -> (Option<String>, Option<String>, Option<String>, Option
                 Ok(table_data) => {
                     SMBiosData::from_vec_and_version(table_data, None)
                 },
-                Err(_) => return (None, None, None, None, None, None, None),
+                Err(_) => return DmiInfo::default(),
             }
         }
     };
 
-    let mut system_info = (None, None, None, None);
-    let mut chassis_manufacturer = None;
-    let mut chassis_serial_number = None;
-    let mut motherboard_info = None;
-    let mut bios_info = None;
+    let mut dmi = DmiInfo::default();
 
     // Iterate through SMBIOS structures
     for structure in smbios_data.iter() {
@@ -75,12 +84,16 @@ fn collect_dmi_info() -> (Option<String>, Option<String>, Option<String>, Option
                     .filter(|s| !s.is_empty() && s != "Not Specified" && s != "Not Available")
                     .map(|s| s.to_string());
                 
-                let sku_number = system_struct.sku_number()
-                    .to_utf8_lossy()
-                    .filter(|s| !s.is_empty() && s != "Not Specified")
-                    .map(|s| s.to_string());
+                // Firmware that never set the UUID reports it as not present rather than zeros
+                let system_uuid = match system_struct.uuid() {
+                    Some(SystemUuidData::Uuid(uuid)) => Some(uuid.to_string()),
+                    _ => None,
+                };
                 
-                system_info = (product_name, manufacturer, serial_number, sku_number);
+                dmi.product_name = product_name;
+                dmi.manufacturer = manufacturer;
+                dmi.serial_number = serial_number;
+                dmi.system_uuid = system_uuid;
             },
             
             DefinedStruct::SystemChassisInformation(chassis_struct) => {
@@ -89,14 +102,14 @@ fn collect_dmi_info() -> (Option<String>, Option<String>, Option<String>, Option
                     .to_utf8_lossy()
                     .filter(|s| !s.is_empty() && s != "Not Specified" && s != "To Be Filled By O.E.M." && s != "Default string")
                     .map(|s| s.to_string());
-                chassis_manufacturer = manufacturer;
+                dmi.chassis_manufacturer = manufacturer;
                 
                 // Extract chassis serial number
                 let serial_number = chassis_struct.serial_number()
                     .to_utf8_lossy()
                     .filter(|s| !s.is_empty() && s != "Not Specified" && s != "To Be Filled By O.E.M." && s != "Default string")
                     .map(|s| s.to_string());
-                chassis_serial_number = serial_number;
+                dmi.chassis_serial_number = serial_number;
             },
             
             DefinedStruct::BaseBoardInformation(baseboard_struct) => {
@@ -122,7 +135,7 @@ fn collect_dmi_info() -> (Option<String>, Option<String>, Option<String>, Option
                     .map(|s| s.to_string());
                 
                 if manufacturer.is_some() || product_name.is_some() || version.is_some() || serial_number.is_some() {
-                    motherboard_info = Some(MotherboardInfo {
+                    dmi.motherboard = Some(MotherboardInfo {
                         manufacturer,
                         product_name,
                         version,
@@ -149,7 +162,7 @@ fn collect_dmi_info() -> (Option<String>, Option<String>, Option<String>, Option
                     .map(|s| s.to_string());
                 
                 if vendor.is_some() || version.is_some() || release_date.is_some() {
-                    bios_info = Some(BiosInfo {
+                    dmi.bios = Some(BiosInfo {
                         vendor,
                         version,
                         release_date,
@@ -161,8 +174,7 @@ fn collect_dmi_info() -> (Option<String>, Option<String>, Option<String>, Option
         }
     }
 
-    // Return system info with chassis manufacturer and serial number
-    (system_info.0, system_info.1, system_info.2, chassis_manufacturer, chassis_serial_number, motherboard_info, bios_info)
+    dmi
 }
 
 fn collect_bmc_from_dmi() -> BmcInfo {
@@ -1,9 +1,9 @@
-use crate::hardware::types::Inventory;
+use crate::hardware::types::ServerInventory;
 use crate::hardware;
 
 const AGENT_VERSION: &str = "1.0.0";
 
-pub fn collect_full_inventory() -> Inventory {
+pub fn collect_full_inventory() -> ServerInventory {
     let node = hardware::collect_node_info();
     let cpu = hardware::collect_cpu_info();
     let memory = hardware::collect_memory_info();
@@ -12,7 +12,8 @@ pub fn collect_full_inventory() -> Inventory {
     let gpus = hardware::collect_gpus();
     let power_supplies = hardware::collect_power_supplies();
 
-    Inventory {
+    ServerInventory {
+        schema_version: farm_proto::SCHEMA_VERSION,
         agent_version: AGENT_VERSION.to_string(),
         node,
         cpu,
@@ -0,0 +1,235 @@
+//! Hardware inventory, posted by `farm-manager hardware post-inventory` to
+//! `/api/v1/servers/inventory`
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::version::Payload;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct ServerInventory {
+    pub schema_version: u32,
+    pub agent_version: String,
+    pub node: NodeInfo,
+    pub cpu: CpuInfo,
+    pub memory: MemoryInfo,
+    pub disks: Vec<DiskInfo>,
+    pub network: NetworkInfo,
+    pub gpus: Vec<GpuInfo>,
+    pub power_supplies: Vec<PowerSupplyInfo>,
+}
+
+impl Payload for ServerInventory {
+    fn upgrade(payload: &mut Map<String, Value>, from: u32) {
+        if from == 1 {
+            upgrade_v1_routes(payload);
+        }
+    }
+}
+
+/// Version 2 names route fields after the columns they are stored in and sends no gateway
+/// instead of an empty one
+fn upgrade_v1_routes(payload: &mut Map<String, Value>) {
+    let routes = payload
+        .get_mut("network")
+        .and_then(|network| network.get_mut("routes"))
+        .and_then(Value::as_array_mut);
+    for route in routes.into_iter().flatten() {
+        let Value::Object(route) = route else { continue };
+        if let Some(destination) = route.remove("dst") {
+            route.insert("destination".to_string(), destination);
+        }
+        if let Some(interface) = route.remove("iface") {
+            route.insert("interface".to_string(), interface);
+        }
+        if route.get("gateway").and_then(Value::as_str) == Some("") {
+            route.insert("gateway".to_string(), Value::Null);
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct NodeInfo {
+    pub hostname: String,
+    pub architecture: String,
+    pub product_name: Option<String>,
+    pub manufacturer: Option<String>,
+    pub serial_number: Option<String>,
+    /// SMBIOS system UUID in lowercase RFC 4122 form; None when firmware leaves it unset
+    pub system_uuid: Option<String>,
+    pub chassis_manufacturer: Option<String>,
+    pub chassis_serial_number: Option<String>,
+    pub motherboard: Option<MotherboardInfo>,
+    pub bios: Option<BiosInfo>,
+    /// None on machines without a BMC (or where it could not be read)
+    pub bmc: Option<BmcInfo>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct MotherboardInfo {
+    pub manufacturer: Option<String>,
+    pub product_name: Option<String>,
+    pub version: Option<String>,
+    pub serial_number: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct BiosInfo {
+    pub vendor: Option<String>,
+    pub version: Option<String>,
+    /// As SMBIOS reports it, MM/DD/YYYY
+    pub release_date: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct BmcInfo {
+    pub ip_address: Option<String>,
+    pub mac_address: Option<String>,
+    pub firmware_version: Option<String>,
+    pub release_date: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct CpuInfo {
+    pub sockets: Option<u32>,
+    pub cores: Option<u32>,
+    pub threads: Option<u32>,
+    pub cpus: Vec<CpuSocket>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct CpuSocket {
+    pub socket: u32,
+    pub manufacturer: Option<String>,
+    pub model_name: Option<String>,
+    pub num_cores: Option<u32>,
+    pub num_threads: Option<u32>,
+    pub capacity_mhz: Option<u32>,
+    pub slot: Option<String>,
+    pub l1_cache_kb: Option<u32>,
+    pub l2_cache_kb: Option<u32>,
+    pub l3_cache_kb: Option<u32>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct MemoryInfo {
+    pub total_bytes: Option<u64>,
+    pub dimms: Vec<DimmInfo>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct DimmInfo {
+    /// SMBIOS device locator; None when the firmware leaves it empty
+    pub slot: Option<String>,
+    pub size_bytes: Option<u64>,
+    pub mem_type: Option<String>,
+    pub speed_mt_s: Option<u32>,
+    pub manufacturer: Option<String>,
+    pub serial_number: Option<String>,
+    pub part_number: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct DiskInfo {
+    pub name: String,
+    pub dev_path: String,
+    pub model: Option<String>,
+    pub serial: Option<String>,
+    pub size_bytes: Option<u64>,
+    pub rotational: Option<bool>,
+    pub bus_type: Option<String>, // "nvme", "scsi", "virtio", etc.
+    pub firmware_version: Option<String>,
+    pub smart: Option<SmartInfo>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct SmartInfo {
+    pub health: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct NetworkInfo {
+    pub interfaces: Vec<NetInterface>,
+    pub routes: Vec<RouteInfo>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct NetInterface {
+    pub name: String,
+    pub mac_address: Option<String>,
+    pub mtu: Option<u32>,
+    pub speed_mbps: Option<u32>,
+    pub driver: Option<String>,
+    pub firmware_version: Option<String>,
+    pub vendor_name: Option<String>,
+    pub device_name: Option<String>,
+    pub pci_address: Option<String>,
+    pub addresses: Vec<IpAddress>,
+
+    // Bond/Team configuration
+    pub is_primary: bool,
+    pub bond_group: Option<String>,
+    pub bond_master: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct IpAddress {
+    pub family: String, // "IPv4" or "IPv6"
+    pub address: String,
+    pub prefix: u8,
+}
+
+/// Routing table entry, as `ip route` reports it. Version 1 sent `dst`, `iface` and an empty
+/// gateway for directly connected routes.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct RouteInfo {
+    /// Destination CIDR, or "default"
+    pub destination: String,
+    /// None for directly connected routes
+    pub gateway: Option<String>,
+    pub interface: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct GpuInfo {
+    pub vendor: Option<String>,
+    pub model: Option<String>,
+    pub pci_address: Option<String>,
+    pub vram_mb: Option<u32>,
+    pub driver_version: Option<String>,
+    pub uuid: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct PowerSupplyInfo {
+    pub name: Option<String>,
+    pub manufacturer: Option<String>,
+    pub model: Option<String>,
+    pub serial_number: Option<String>,
+    pub part_number: Option<String>,
+    pub max_power_watts: Option<u32>,
+    pub efficiency_rating: Option<String>, // "80 Plus Gold", "80 Plus Platinum", etc.
+    pub status: Option<String>, // "OK", "Critical", "Non-critical", etc.
+    pub input_voltage: Option<f32>,
+    pub input_current: Option<f32>,
+    pub output_voltage: Option<f32>,
+    pub output_current: Option<f32>,
+    pub temperature_c: Option<i32>,
+    pub fan_speed_rpm: Option<u32>,
+}
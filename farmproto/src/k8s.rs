@@ -0,0 +1,103 @@
+//! Kubernetes cluster inventory, posted by `farm-manager k8s post-inventory` to
+//! `/api/v1/k8s/inventory`
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::version::Payload;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct K8sInventory {
+    pub schema_version: u32,
+    pub cluster_name: String,
+    pub cluster_version: String,
+    pub api_server: String,
+    pub nodes: Vec<K8sNodeInventory>,
+    pub namespaces: Vec<K8sNamespaceInventory>,
+    pub pods: Option<Vec<K8sPodInventory>>,
+    pub services: Option<Vec<K8sServiceInventory>>,
+    pub workloads: Option<Vec<K8sWorkloadInventory>>,
+}
+
+impl Payload for K8sInventory {
+    // Unchanged since version 1
+    fn upgrade(_payload: &mut Map<String, Value>, _from: u32) {}
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct K8sNodeInventory {
+    pub node_name: String,
+    pub node_uid: Option<String>,
+    pub node_type: Option<String>,
+    pub internal_ip: Option<String>,
+    pub external_ip: Option<String>,
+    pub hostname: Option<String>,
+    pub cpu_capacity: Option<i32>,
+    pub memory_capacity_mb: Option<i64>,
+    pub pod_capacity: Option<i32>,
+    pub node_state: Option<String>,
+    pub kubelet_version: Option<String>,
+    pub os_image: Option<String>,
+    pub kernel_version: Option<String>,
+    pub container_runtime_version: Option<String>,
+    pub roles: Option<String>,
+    pub labels: Option<Value>,
+    pub taints: Option<Value>,
+    pub gpu_count: Option<i32>,
+    pub gpu_product: Option<String>,
+    pub gpu_memory_mb: Option<i32>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct K8sNamespaceInventory {
+    pub namespace_name: String,
+    pub namespace_uid: Option<String>,
+    pub namespace_state: Option<String>,
+    pub labels: Option<Value>,
+    pub annotations: Option<Value>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct K8sPodInventory {
+    pub pod_name: String,
+    pub pod_uid: Option<String>,
+    pub namespace: String,
+    pub pod_phase: Option<String>,
+    pub node_name: Option<String>,
+    pub pod_ip: Option<String>,
+    pub host_ip: Option<String>,
+    pub start_time: Option<String>,
+    pub labels: Option<Value>,
+    pub annotations: Option<Value>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct K8sServiceInventory {
+    pub service_name: String,
+    pub service_uid: Option<String>,
+    pub namespace: String,
+    pub service_type: Option<String>,
+    pub cluster_ip: Option<String>,
+    pub external_ip: Option<String>,
+    pub ports: Option<Value>,
+    pub selector: Option<Value>,
+    pub labels: Option<Value>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct K8sWorkloadInventory {
+    pub workload_name: String,
+    pub workload_uid: Option<String>,
+    pub namespace: String,
+    pub workload_type: String,
+    pub replicas_desired: Option<i32>,
+    pub replicas_ready: Option<i32>,
+    pub replicas_available: Option<i32>,
+    pub labels: Option<Value>,
+    pub selector: Option<Value>,
+}
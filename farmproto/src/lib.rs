@@ -0,0 +1,18 @@
+//! Inventory payloads exchanged between farm-manager (the agent) and farm-core.
+//!
+//! Both binaries build against these types, so a field added or retyped here changes what the
+//! agent sends and what the core accepts at the same time. Every top-level payload carries a
+//! `schema_version`; farm-core decodes through [`decode`], which upgrades payloads from the
+//! previous [`SUPPORTED_PREVIOUS_VERSIONS`] versions so agents can be rolled out gradually.
+pub mod hardware;
+pub mod k8s;
+pub mod version;
+pub mod vm;
+
+pub use hardware::ServerInventory;
+pub use k8s::K8sInventory;
+pub use version::{
+    decode, supported_versions, DecodeError, Payload, MIN_SCHEMA_VERSION, SCHEMA_VERSION,
+    SUPPORTED_PREVIOUS_VERSIONS,
+};
+pub use vm::VmInventory;
@@ -0,0 +1,88 @@
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value};
+use std::fmt;
+use std::ops::RangeInclusive;
+
+/// Version of the payloads defined in this crate, written by the agent. Only changes an older
+/// reader cannot ignore need a new version; adding an optional field does not.
+pub const SCHEMA_VERSION: u32 = 2;
+
+/// How many versions before [`SCHEMA_VERSION`] farm-core still accepts. A core release has to
+/// keep taking what agents from the previous release send until they are upgraded.
+pub const SUPPORTED_PREVIOUS_VERSIONS: u32 = 1;
+
+pub const MIN_SCHEMA_VERSION: u32 = SCHEMA_VERSION - SUPPORTED_PREVIOUS_VERSIONS;
+
+/// Agents released before payloads were versioned send no `schema_version`
+const UNVERSIONED: u32 = 1;
+
+pub fn supported_versions() -> RangeInclusive<u32> {
+    MIN_SCHEMA_VERSION..=SCHEMA_VERSION
+}
+
+/// A top-level payload the agent posts to farm-core
+pub trait Payload: DeserializeOwned {
+    /// Rewrite a payload in the layout of version `from` into the layout of `from + 1`.
+    /// [`decode`] calls this once per version step, oldest first.
+    fn upgrade(payload: &mut Map<String, Value>, from: u32);
+}
+
+#[derive(Debug)]
+pub enum DecodeError {
+    /// `schema_version` is outside [`supported_versions`]
+    UnsupportedVersion(u32),
+    /// `schema_version` is not a version number
+    InvalidVersion(Value),
+    /// The payload does not match the schema of its version
+    Invalid(serde_json::Error),
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodeError::UnsupportedVersion(version) => write!(
+                f,
+                "Unsupported schema_version {}; this server accepts {} to {}",
+                version, MIN_SCHEMA_VERSION, SCHEMA_VERSION
+            ),
+            DecodeError::InvalidVersion(value) => {
+                write!(f, "schema_version must be a positive integer, got {}", value)
+            }
+            DecodeError::Invalid(e) => write!(f, "Invalid payload: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            DecodeError::Invalid(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+/// Decode a payload of any supported version into the current types
+pub fn decode<T: Payload>(value: Value) -> Result<T, DecodeError> {
+    let Value::Object(mut payload) = value else {
+        return serde_json::from_value(value).map_err(DecodeError::Invalid);
+    };
+
+    let version = match payload.get("schema_version") {
+        None | Some(Value::Null) => UNVERSIONED,
+        Some(value) => value
+            .as_u64()
+            .and_then(|version| u32::try_from(version).ok())
+            .ok_or_else(|| DecodeError::InvalidVersion(value.clone()))?,
+    };
+    if !supported_versions().contains(&version) {
+        return Err(DecodeError::UnsupportedVersion(version));
+    }
+
+    for from in version..SCHEMA_VERSION {
+        T::upgrade(&mut payload, from);
+    }
+    payload.insert("schema_version".to_string(), SCHEMA_VERSION.into());
+
+    serde_json::from_value(Value::Object(payload)).map_err(DecodeError::Invalid)
+}
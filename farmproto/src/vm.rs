@@ -0,0 +1,55 @@
+//! VM inventory, posted by `farm-manager vm post-inventory` to `/api/v1/vms/inventory`
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::version::Payload;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct VmInventory {
+    pub schema_version: u32,
+    /// Primary MAC of the hypervisor host, which ties the VMs to its server
+    pub host_mac_address: String,
+    pub hypervisor_type: String,
+    pub vms: Vec<VmInventoryDetail>,
+}
+
+impl Payload for VmInventory {
+    // Unchanged since version 1
+    fn upgrade(_payload: &mut Map<String, Value>, _from: u32) {}
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct VmInventoryDetail {
+    pub vm_name: String,
+    pub vm_uuid: Option<String>,
+    pub vm_state: Option<String>,
+    pub hypervisor_type: String,
+    pub vcpu_count: Option<i32>,
+    pub memory_mb: Option<i32>,
+    pub guest_os_family: Option<String>,
+    pub disks: Vec<VmDiskInventory>,
+    pub network_interfaces: Vec<VmNetworkInventory>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct VmDiskInventory {
+    pub disk_name: String,
+    pub disk_type: Option<String>,
+    pub disk_format: Option<String>,
+    pub disk_size_gb: Option<i32>,
+    pub disk_path: String,
+    pub is_bootable: Option<bool>,
+    pub storage_type: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct VmNetworkInventory {
+    pub interface_name: String,
+    pub mac_address: Option<String>,
+    pub interface_type: Option<String>,
+    pub network_bridge: Option<String>,
+}
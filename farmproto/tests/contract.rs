@@ -0,0 +1,148 @@
+//! Contract between farm-manager and farm-core: what the agent serializes has to decode on the
+//! core unchanged, and payloads from agents on a previous schema version have to decode into
+//! the same values once upgraded.
+use farm_proto::hardware::{RouteInfo, ServerInventory};
+use farm_proto::k8s::K8sInventory;
+use farm_proto::vm::VmInventory;
+use farm_proto::{decode, DecodeError, Payload, MIN_SCHEMA_VERSION, SCHEMA_VERSION};
+use serde::Serialize;
+use serde_json::{json, Value};
+
+fn fixture(name: &str) -> Value {
+    let path = format!("{}/tests/fixtures/{}", env!("CARGO_MANIFEST_DIR"), name);
+    let text = std::fs::read_to_string(&path).unwrap_or_else(|e| panic!("{}: {}", path, e));
+    serde_json::from_str(&text).unwrap_or_else(|e| panic!("{}: {}", path, e))
+}
+
+/// Decode a payload, serialize it again the way the agent would and check nothing was lost
+fn assert_round_trip<T: Payload + Serialize + PartialEq + std::fmt::Debug>(payload: Value) -> T {
+    let decoded: T = decode(payload.clone()).expect("payload should decode");
+    let encoded = serde_json::to_value(&decoded).unwrap();
+    assert_eq!(encoded, payload);
+    assert_eq!(decode::<T>(encoded).unwrap(), decoded);
+    decoded
+}
+
+fn with_version(mut payload: Value, version: Value) -> Value {
+    payload["schema_version"] = version;
+    payload
+}
+
+#[test]
+fn hardware_inventory_round_trips() {
+    let inventory: ServerInventory = assert_round_trip(fixture("inventory_v2.json"));
+    assert_eq!(inventory.schema_version, SCHEMA_VERSION);
+}
+
+#[test]
+fn vm_inventory_round_trips() {
+    let payload = with_version(fixture("vm_v1.json"), SCHEMA_VERSION.into());
+    let inventory: VmInventory = assert_round_trip(payload);
+    assert_eq!(inventory.vms[0].disks[0].disk_format.as_deref(), Some("qcow2"));
+}
+
+#[test]
+fn k8s_inventory_round_trips() {
+    let payload = with_version(fixture("k8s_v1.json"), SCHEMA_VERSION.into());
+    let inventory: K8sInventory = assert_round_trip(payload);
+    assert_eq!(inventory.nodes[0].gpu_count, Some(1));
+    assert!(inventory.services.is_none());
+}
+
+#[test]
+fn unversioned_hardware_inventory_upgrades_to_current() {
+    let upgraded: ServerInventory = decode(fixture("inventory_v1.json")).unwrap();
+    let current: ServerInventory = decode(fixture("inventory_v2.json")).unwrap();
+    assert_eq!(upgraded, current);
+    assert_eq!(
+        upgraded.network.routes,
+        vec![
+            RouteInfo {
+                destination: "default".to_string(),
+                gateway: Some("10.20.0.1".to_string()),
+                interface: "eno1".to_string(),
+            },
+            RouteInfo {
+                destination: "10.20.0.0/24".to_string(),
+                gateway: None,
+                interface: "eno1".to_string(),
+            },
+        ]
+    );
+}
+
+#[test]
+fn explicit_previous_version_upgrades_to_current() {
+    let payload = with_version(fixture("inventory_v1.json"), MIN_SCHEMA_VERSION.into());
+    let upgraded: ServerInventory = decode(payload).unwrap();
+    assert_eq!(upgraded, decode(fixture("inventory_v2.json")).unwrap());
+}
+
+#[test]
+fn unversioned_vm_and_k8s_inventories_decode() {
+    let vms: VmInventory = decode(fixture("vm_v1.json")).unwrap();
+    assert_eq!(vms.schema_version, SCHEMA_VERSION);
+    let cluster: K8sInventory = decode(fixture("k8s_v1.json")).unwrap();
+    assert_eq!(cluster.schema_version, SCHEMA_VERSION);
+}
+
+#[test]
+fn server_without_bmc_decodes() {
+    let inventory: ServerInventory = decode(fixture("inventory_v2.json")).unwrap();
+    assert!(inventory.node.bmc.is_none());
+    assert!(inventory.memory.dimms[1].slot.is_none());
+}
+
+#[test]
+fn omitted_optional_fields_decode_as_none() {
+    let mut payload = fixture("inventory_v2.json");
+    let node = payload["node"].as_object_mut().unwrap();
+    node.remove("bmc");
+    node.remove("chassis_serial_number");
+    let inventory: ServerInventory = decode(payload).unwrap();
+    assert!(inventory.node.bmc.is_none());
+    assert!(inventory.node.chassis_serial_number.is_none());
+}
+
+#[test]
+fn system_uuid_round_trips() {
+    let mut payload = fixture("inventory_v2.json");
+    payload["node"]["system_uuid"] = json!("4c4c4544-0058-4b10-8032-b7c04f504c33");
+    let inventory: ServerInventory = assert_round_trip(payload);
+    assert_eq!(
+        inventory.node.system_uuid.as_deref(),
+        Some("4c4c4544-0058-4b10-8032-b7c04f504c33")
+    );
+}
+
+#[test]
+fn unsupported_versions_are_rejected() {
+    for version in [MIN_SCHEMA_VERSION - 1, SCHEMA_VERSION + 1] {
+        let payload = with_version(fixture("inventory_v2.json"), version.into());
+        match decode::<ServerInventory>(payload) {
+            Err(DecodeError::UnsupportedVersion(v)) => assert_eq!(v, version),
+            other => panic!("version {} should be rejected, got {:?}", version, other),
+        }
+    }
+}
+
+#[test]
+fn invalid_versions_are_rejected() {
+    for version in [json!("2"), json!(-1), json!(2.5), json!(u64::MAX)] {
+        let payload = with_version(fixture("vm_v1.json"), version.clone());
+        match decode::<VmInventory>(payload) {
+            Err(DecodeError::InvalidVersion(v)) => assert_eq!(v, version),
+            other => panic!("version {} should be rejected, got {:?}", version, other),
+        }
+    }
+}
+
+#[test]
+fn mistyped_fields_are_rejected() {
+    let mut payload = fixture("inventory_v2.json");
+    payload["network"]["interfaces"][0]["addresses"][0]["prefix"] = json!(300);
+    assert!(matches!(
+        decode::<ServerInventory>(payload),
+        Err(DecodeError::Invalid(_))
+    ));
+}
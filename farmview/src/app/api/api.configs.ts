@@ -4,6 +4,7 @@ export const API_CONFIG = {
   // Backend API URLs
   FARMCORE: {
     URL: process.env.FARM_CORE_API_URL,
+    TOKEN: process.env.FARM_CORE_API_TOKEN,
   },
   
   // Grafana Configuration
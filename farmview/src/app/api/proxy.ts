@@ -62,6 +62,10 @@ export async function proxyToFarmCore(
       headers: {
         'Content-Type': 'application/json',
         'User-Agent': 'Farm-Dashboard/1.0',
+        // Authenticate as the dashboard unless the caller supplied its own token
+        ...(API_CONFIG.FARMCORE.TOKEN && {
+          'Authorization': `Bearer ${API_CONFIG.FARMCORE.TOKEN}`
+        }),
         // Forward important headers
         ...(request.headers.get('authorization') && {
           'Authorization': request.headers.get('authorization')!
@@ -5,6 +5,7 @@ export interface Server {
   product_name?: string;
   manufacturer?: string;
   serial_number?: string;
+  system_uuid?: string;
   chassis_manufacturer?: string;
   chassis_serial_number?: string;
   bmc_ip_address?: string;
@@ -206,6 +207,31 @@ export interface ServerGpuDetail {
   vram_mb?: number;
 }
 
+export interface ServerPowerSupplyDetail {
+  psu_id: number;
+  name?: string | null;
+  manufacturer?: string | null;
+  model?: string | null;
+  serial_number?: string | null;
+  part_number?: string | null;
+  max_power_watts?: number | null;
+  efficiency_rating?: string | null;
+  status?: string | null;
+  input_voltage?: number | null;
+  input_current?: number | null;
+  output_voltage?: number | null;
+  output_current?: number | null;
+  temperature_c?: number | null;
+  fan_speed_rpm?: number | null;
+}
+
+export interface ServerNetworkRoute {
+  route_id: number;
+  destination: string;
+  gateway?: string | null;
+  interface_name: string;
+}
+
 export interface ServerInventory {
   server_id: string;
   server_name: string;
@@ -238,7 +264,9 @@ export interface ServerWithAllComponents extends Server {
   memory: ServerMemoryDetail[];
   disks: ServerDiskDetail[];
   network_interfaces: ServerNetworkDetail[];
+  network_routes: ServerNetworkRoute[];
   gpus: ServerGpuDetail[];
+  power_supplies: ServerPowerSupplyDetail[];
   bmc_interfaces: ServerBMCDetail[];
   credentials: ServerCredential[];
   motherboard_detail?: ServerMotherboardDetail;